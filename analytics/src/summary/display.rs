@@ -15,6 +15,9 @@ where
         self.title_table().printstd();
         self.instrument_table().printstd();
         self.asset_table().printstd();
+        if !self.risk_refusals.is_empty() {
+            self.risk_refusal_table().printstd();
+        }
     }
     fn title_table(&self) -> Table {
         let mut title_table = Table::new();
@@ -272,6 +275,41 @@ where
         }
         table.add_row(row);
     }
+
+    pub fn risk_refusal_table(&self) -> Table {
+        let mut table = Table::new();
+
+        // Styling
+        table.set_format(*prettytable::format::consts::FORMAT_BOX_CHARS);
+
+        // Title row spanning both columns
+        let mut title_cell = Cell::new("Risk Refusals").style_spec("bcB");
+        title_cell.set_hspan(2);
+        table.add_row(Row::new(vec![title_cell]));
+
+        table.add_row(Row::new(vec![
+            Cell::new("Total").style_spec("bcB"),
+            Cell::new(&self.risk_refusals.total.to_string()),
+        ]));
+
+        // Refusals per check (eg/ max_order_quantity | 3)
+        for (check, count) in &self.risk_refusals.checks {
+            table.add_row(Row::new(vec![
+                Cell::new(check).style_spec("bcB"),
+                Cell::new(&count.to_string()),
+            ]));
+        }
+
+        // Refusals per instrument (eg/ PETR4 | 2)
+        for (instrument, count) in &self.risk_refusals.instruments {
+            table.add_row(Row::new(vec![
+                Cell::new(instrument.name()).style_spec("bcB"),
+                Cell::new(&count.to_string()),
+            ]));
+        }
+
+        table
+    }
 }
 
 fn format_ratio(value: Decimal) -> String {
@@ -155,6 +155,44 @@ impl TearSheetGenerator {
         }
     }
 
+    /// Generate the latest [`TearSheet`] measured over the provided returns `period` (eg/
+    /// [`TradingDays`](super::super::time::TradingDays)), scaled to the target [`TimeInterval`].
+    pub fn generate_over<Period, Interval>(
+        &mut self,
+        period: Period,
+        interval: Interval,
+    ) -> TearSheet<Interval>
+    where
+        Period: TimeInterval,
+        Interval: TimeInterval,
+    {
+        let TearSheet {
+            pnl,
+            pnl_return,
+            sharpe_ratio,
+            sortino_ratio,
+            calmar_ratio,
+            profit_factor,
+            win_rate,
+            drawdown,
+            drawdown_mean,
+            drawdown_max,
+        } = self.generate(period);
+
+        TearSheet {
+            pnl,
+            pnl_return: pnl_return.scale(interval),
+            sharpe_ratio: sharpe_ratio.scale(interval),
+            sortino_ratio: sortino_ratio.scale(interval),
+            calmar_ratio: calmar_ratio.scale(interval),
+            profit_factor,
+            win_rate,
+            drawdown,
+            drawdown_mean,
+            drawdown_max,
+        }
+    }
+
     /// Reset the internal state, using a new starting `DateTime<Utc>` as seed.
     pub fn reset(&mut self, time_engine_start: DateTime<Utc>) {
         *self = Self::init(time_engine_start);
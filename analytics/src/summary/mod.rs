@@ -3,11 +3,13 @@ use crate::{
     summary::{
         asset::{TearSheetAsset, TearSheetAssetGenerator},
         instrument::{TearSheet, TearSheetGenerator},
+        risk::RiskRefusalSummary,
     },
-    time::TimeInterval,
+    time::{TimeInterval, TradingDays},
 };
 use toucan_execution::{balance::AssetBalance, AssetIndex, InstrumentIndex};
 use toucan_integration::collection::FnvIndexMap;
+use toucan_markets::calendar::ExchangeCalendar;
 
 // Placeholder name types for integration - these will be properly defined during full integration
 #[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
@@ -119,6 +121,7 @@ pub mod dataset;
 pub mod display;
 pub mod instrument;
 pub mod pnl;
+pub mod risk;
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize, Constructor)]
 pub struct TradingSummary<Interval> {
@@ -136,6 +139,10 @@ pub struct TradingSummary<Interval> {
 
     /// [`ExchangeAsset`] [`TearSheet`]s.
     pub assets: FnvIndexMap<ExchangeAsset<AssetNameInternal>, TearSheetAsset>,
+
+    /// Statistics of order requests refused by the `RiskManager`.
+    #[serde(default)]
+    pub risk_refusals: RiskRefusalSummary,
 }
 
 impl<Interval> TradingSummary<Interval> {
@@ -169,6 +176,15 @@ pub struct TradingSummaryGenerator {
 
     /// [`ExchangeAsset`] [`TearSheetAssetGenerator`]s.
     pub assets: FnvIndexMap<ExchangeAsset<AssetNameInternal>, TearSheetAssetGenerator>,
+
+    /// Statistics of order requests refused by the `RiskManager`.
+    #[serde(default)]
+    pub risk_refusals: RiskRefusalSummary,
+
+    /// Optional venue [`ExchangeCalendar`] used to measure the trading period in
+    /// [`TradingDays`].
+    #[serde(default)]
+    pub calendar: Option<ExchangeCalendar>,
 }
 
 impl TradingSummaryGenerator {
@@ -187,9 +203,36 @@ impl TradingSummaryGenerator {
             time_engine_now,
             instruments: FnvIndexMap::default(), // Simplified placeholder
             assets: FnvIndexMap::default(),      // Simplified placeholder
+            risk_refusals: RiskRefusalSummary::default(),
+            calendar: None,
+        }
+    }
+
+    /// Measure the trading period in [`TradingDays`] of the provided venue [`ExchangeCalendar`].
+    ///
+    /// Metrics are then scaled from the trading period to the [`TimeInterval`] passed to
+    /// [`Self::generate`], so [`Annual252`](super::time::Annual252) excludes weekends and venue
+    /// holidays.
+    pub fn with_calendar(self, calendar: ExchangeCalendar) -> Self {
+        Self {
+            calendar: Some(calendar),
+            ..self
         }
     }
 
+    /// [`TradingDays`] from the start to the most recent update (inclusive), or `None` if no
+    /// [`ExchangeCalendar`] is configured.
+    ///
+    /// A trading period shorter than one trading day is measured as one trading day.
+    pub fn trading_days(&self) -> Option<TradingDays> {
+        let calendar = self.calendar.as_ref()?;
+        let start = calendar.local_date(self.time_engine_start);
+        let end = calendar.local_date(self.time_engine_now).succ_opt()?;
+
+        let TradingDays(days) = TradingDays::between(calendar, start, end);
+        Some(TradingDays(days.max(1)))
+    }
+
     /// Update the [`TradingSummaryGenerator`] `time_now`.
     pub fn update_time_now(&mut self, time_now: DateTime<Utc>) {
         self.time_engine_now = time_now;
@@ -221,18 +264,34 @@ impl TradingSummaryGenerator {
         // This is a placeholder implementation
     }
 
+    /// Update the [`TradingSummaryGenerator`] from an order request for the provided instrument
+    /// that was refused by the named `RiskManager` check.
+    pub fn update_from_risk_refusal(&mut self, check: &str, instrument: &str) {
+        self.risk_refusals.record(check, instrument)
+    }
+
     /// Generate the latest [`TradingSummary`] at the specific [`TimeInterval`].
     ///
     /// For example, pass [`Annual365`](super::time::Annual365) to generate a crypto-centric
     /// (24/7 trading) annualised [`TradingSummary`].
+    ///
+    /// If an [`ExchangeCalendar`] is configured, metrics are measured over the
+    /// [`Self::trading_days`] period and scaled to the provided interval.
     pub fn generate<Interval>(&mut self, interval: Interval) -> TradingSummary<Interval>
     where
         Interval: TimeInterval + Copy,
     {
+        let trading_days = self.trading_days();
         let instruments = self
             .instruments
             .iter_mut()
-            .map(|(instrument, tear_sheet)| (instrument.clone(), tear_sheet.generate(interval)))
+            .map(|(instrument, tear_sheet)| {
+                let tear_sheet = match trading_days {
+                    Some(period) => tear_sheet.generate_over(period, interval),
+                    None => tear_sheet.generate(interval),
+                };
+                (instrument.clone(), tear_sheet)
+            })
             .collect();
 
         let assets = self
@@ -246,6 +305,7 @@ impl TradingSummaryGenerator {
             time_engine_end: self.time_engine_now,
             instruments,
             assets,
+            risk_refusals: self.risk_refusals.clone(),
         }
     }
 }
@@ -334,3 +394,104 @@ impl AssetTearSheetManager<ExchangeAsset<AssetNameInternal>> for TradingSummaryG
             .unwrap_or_else(|| panic!("TradingSummaryGenerator does not contain: {key:?}"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::Annual252;
+    use rust_decimal_macros::dec;
+
+    fn time(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    fn generator(time_start: &str, time_now: &str) -> TradingSummaryGenerator {
+        let mut generator = TradingSummaryGenerator::init::<(), AssetIndex>(
+            Decimal::ZERO,
+            time(time_start),
+            time(time_now),
+            &FnvIndexMap::default(),
+            &FnvIndexMap::default(),
+        );
+
+        let mut tear_sheet = TearSheetGenerator::init(time(time_start));
+        tear_sheet.update_from_position::<AssetIndex, InstrumentIndex>(&PositionExited {
+            timestamp: time(time_now),
+            pnl_realised: dec!(30),
+            time_exit: time(time_now),
+            instrument: "petr4".to_string(),
+            price_entry_average: dec!(10),
+            quantity_abs_max: dec!(100),
+        });
+        generator
+            .instruments
+            .insert(InstrumentNameInternal("petr4".to_string()), tear_sheet);
+
+        generator
+    }
+
+    #[test]
+    fn test_trading_summary_generator_trading_days() {
+        struct TestCase {
+            time_start: &'static str,
+            time_now: &'static str,
+            expected: TradingDays,
+        }
+
+        let cases = vec![
+            // TC0: Carnival week 2024, Monday & Tuesday are B3 holidays
+            TestCase {
+                time_start: "2024-02-12T13:00:00Z",
+                time_now: "2024-02-16T20:00:00Z",
+                expected: TradingDays(3),
+            },
+            // TC1: intraday period is measured as one trading day
+            TestCase {
+                time_start: "2024-03-15T13:00:00Z",
+                time_now: "2024-03-15T15:00:00Z",
+                expected: TradingDays(1),
+            },
+            // TC2: weekend only period is measured as one trading day
+            TestCase {
+                time_start: "2024-03-16T13:00:00Z",
+                time_now: "2024-03-17T13:00:00Z",
+                expected: TradingDays(1),
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let generator = generator(test.time_start, test.time_now)
+                .with_calendar(ExchangeCalendar::b3_equities());
+            assert_eq!(
+                generator.trading_days(),
+                Some(test.expected),
+                "TC{index} failed"
+            );
+        }
+
+        assert_eq!(
+            generator("2024-02-12T13:00:00Z", "2024-02-16T20:00:00Z").trading_days(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_trading_summary_generator_generate_with_calendar() {
+        let mut generator = generator("2024-02-12T13:00:00Z", "2024-02-16T20:00:00Z");
+        let over_period = generator.generate(TradingDays(3));
+
+        let mut generator = generator.with_calendar(ExchangeCalendar::b3_equities());
+        let annualised = generator.generate(Annual252);
+
+        // 3 trading days scaled to 252 trading days
+        let instrument = InstrumentNameInternal("petr4".to_string());
+        assert_eq!(
+            annualised.instruments[&instrument].pnl_return.value,
+            over_period.instruments[&instrument].pnl_return.value * dec!(84)
+        );
+        assert_eq!(
+            annualised.instruments[&instrument].pnl,
+            over_period.instruments[&instrument].pnl
+        );
+    }
+}
@@ -0,0 +1,65 @@
+use super::InstrumentNameInternal;
+use serde::{Deserialize, Serialize};
+use toucan_integration::collection::FnvIndexMap;
+
+/// Statistics of order requests refused by the `RiskManager` during a trading session,
+/// aggregated by the name of the refusing check and by instrument.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub struct RiskRefusalSummary {
+    /// Total number of refused order requests.
+    pub total: u64,
+
+    /// Number of refused order requests per refusing check name (eg/ "max_order_quantity").
+    pub checks: FnvIndexMap<String, u64>,
+
+    /// Number of refused order requests per instrument.
+    pub instruments: FnvIndexMap<InstrumentNameInternal, u64>,
+}
+
+impl RiskRefusalSummary {
+    /// Record an order request for the provided instrument refused by the named check.
+    pub fn record(&mut self, check: &str, instrument: &str) {
+        self.total += 1;
+
+        match self.checks.get_mut(check) {
+            Some(count) => *count += 1,
+            None => {
+                self.checks.insert(check.to_string(), 1);
+            }
+        }
+
+        match self.instruments.get_mut(instrument) {
+            Some(count) => *count += 1,
+            None => {
+                self.instruments
+                    .insert(InstrumentNameInternal::new(instrument.to_string()), 1);
+            }
+        }
+    }
+
+    /// Returns `true` if no refused order requests have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.total == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_risk_refusal_summary_record() {
+        let mut summary = RiskRefusalSummary::default();
+        assert!(summary.is_empty());
+
+        summary.record("max_order_quantity", "PETR4");
+        summary.record("max_order_quantity", "VALE3");
+        summary.record("exchange_paused", "PETR4");
+
+        assert_eq!(summary.total, 3);
+        assert_eq!(summary.checks.get("max_order_quantity"), Some(&2));
+        assert_eq!(summary.checks.get("exchange_paused"), Some(&1));
+        assert_eq!(summary.instruments.get("PETR4"), Some(&2));
+        assert_eq!(summary.instruments.get("VALE3"), Some(&1));
+    }
+}
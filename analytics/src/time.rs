@@ -1,8 +1,9 @@
 
-use chrono::TimeDelta;
+use chrono::{NaiveDate, TimeDelta};
 use serde::{Deserialize, Serialize};
 use smol_str::{format_smolstr, SmolStr};
 use std::fmt::Debug;
+use toucan_markets::calendar::TradingCalendar;
 
 /// A trait for types that represent time intervals used in financial calculations.
 ///
@@ -81,3 +82,47 @@ impl TimeInterval for TimeDelta {
         *self
     }
 }
+
+/// Number of venue trading days, counted with a [`TradingCalendar`].
+///
+/// Measuring a returns period in trading days means scaling to [`Annual252`] (252 trading days
+/// per year) excludes weekends and venue holidays, rather than assuming every calendar day trades.
+///
+/// # Examples
+/// ```rust
+/// use chrono::NaiveDate;
+/// use toucan_analytics::time::{TimeInterval, TradingDays};
+/// use toucan_markets::calendar::ExchangeCalendar;
+///
+/// let b3 = ExchangeCalendar::b3_equities();
+///
+/// // Carnival week 2024: Monday and Tuesday are B3 holidays
+/// let start = NaiveDate::from_ymd_opt(2024, 2, 12).unwrap();
+/// let end = NaiveDate::from_ymd_opt(2024, 2, 17).unwrap();
+///
+/// let period = TradingDays::between(&b3, start, end);
+/// assert_eq!(period, TradingDays(3));
+/// assert_eq!(period.interval().num_days(), 3);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Default, Deserialize, Serialize)]
+pub struct TradingDays(pub u32);
+
+impl TradingDays {
+    /// Count the trading days in the half-open date range `[start, end)`.
+    pub fn between<Calendar>(calendar: &Calendar, start: NaiveDate, end: NaiveDate) -> Self
+    where
+        Calendar: TradingCalendar,
+    {
+        Self(calendar.trading_days_between(start, end))
+    }
+}
+
+impl TimeInterval for TradingDays {
+    fn name(&self) -> SmolStr {
+        format_smolstr!("TradingDays({})", self.0)
+    }
+
+    fn interval(&self) -> TimeDelta {
+        TimeDelta::days(i64::from(self.0))
+    }
+}
@@ -85,7 +85,10 @@ use crate::{
     },
     error::ToucanError,
     risk::RiskManager,
-    system::{builder::EngineFeedMode, config::ExecutionConfig},
+    system::{
+        builder::EngineFeedMode,
+        config::{ConfigError, ExecutionConfig},
+    },
 };
 use crate::{
     engine::Engine,
@@ -281,10 +284,15 @@ where
         .executions
         .clone()
         .into_iter()
+        .enumerate()
         .try_fold(
             ExecutionBuilder::new(&args_constant.instruments),
-            |builder, config| match config {
+            |builder, (index, config)| match config {
                 ExecutionConfig::Mock(mock_config) => builder.add_mock(mock_config, clock.clone()),
+                ExecutionConfig::B3(_) => Err(ToucanError::from(ConfigError::invalid(
+                    format!("executions[{index}]"),
+                    "backtests only support mock executions",
+                ))),
             },
         )?
         .build();
@@ -1,6 +1,7 @@
 
 use crate::engine::{
     action::send_requests::{SendRequests, SendRequestsOutput},
+    clock::EngineClock,
     execution_tx::ExecutionTxMap,
     state::{
         instrument::filter::InstrumentFilter,
@@ -38,6 +39,7 @@ pub trait CancelOrders<
 impl<Clock, GlobalData, InstrumentData, ExecutionTxs, Strategy, Risk> CancelOrders
     for Engine<Clock, EngineState<GlobalData, InstrumentData>, ExecutionTxs, Strategy, Risk>
 where
+    Clock: EngineClock,
     InstrumentData: InFlightRequestRecorder,
     ExecutionTxs: ExecutionTxMap<ExchangeIndex, InstrumentIndex>,
 {
@@ -1,6 +1,7 @@
 
 use crate::engine::{
     action::send_requests::{SendCancelsAndOpensOutput, SendRequests},
+    clock::EngineClock,
     execution_tx::ExecutionTxMap,
     state::{
         instrument::filter::InstrumentFilter, order::in_flight_recorder::InFlightRequestRecorder,
@@ -37,6 +38,7 @@ impl<Clock, State, ExecutionTxs, Strategy, Risk, ExchangeKey, AssetKey, Instrume
     ClosePositions<ExchangeKey, AssetKey, InstrumentKey>
     for Engine<Clock, State, ExecutionTxs, Strategy, Risk>
 where
+    Clock: EngineClock,
     State: InFlightRequestRecorder<ExchangeKey, InstrumentKey>,
     ExecutionTxs: ExecutionTxMap<ExchangeKey, InstrumentKey>,
     Strategy: ClosePositionsStrategy<ExchangeKey, AssetKey, InstrumentKey, State = State>,
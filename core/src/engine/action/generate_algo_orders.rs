@@ -2,12 +2,15 @@
 use crate::{
     engine::{
         action::send_requests::{SendCancelsAndOpensOutput, SendRequests, SendRequestsOutput},
+        clock::EngineClock,
         error::UnrecoverableEngineError,
         execution_tx::ExecutionTxMap,
+        reaction::PausedExchanges,
+        risk::RiskRefusalRecorder,
         state::order::in_flight_recorder::InFlightRequestRecorder,
         Engine,
     },
-    risk::{RiskApproved, RiskManager, RiskRefused},
+    risk::{RiskApproved, RiskManager, RiskRefusalReason, RiskRefused},
 };
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
@@ -29,7 +32,8 @@ pub trait GenerateAlgoOrders<ExchangeKey = ExchangeIndex, InstrumentKey = Instru
     /// Returns a [`GenerateAlgoOrdersOutput`] containing work done:
     /// - Generated orders that were approved by the [`RiskManager`] and sent for execution.
     /// - Generated cancel requests that were refused by the [`RiskManager`].
-    /// - Generated open requests that were refused by the [`RiskManager`].
+    /// - Generated open requests that were refused by the [`RiskManager`], or refused because
+    ///   the exchange is paused by a [`Reaction`](crate::engine::reaction::Reaction).
     fn generate_algo_orders(&mut self) -> GenerateAlgoOrdersOutput<ExchangeKey, InstrumentKey>;
 }
 
@@ -37,7 +41,10 @@ impl<Clock, State, ExecutionTxs, Strategy, Risk, ExchangeKey, InstrumentKey>
     GenerateAlgoOrders<ExchangeKey, InstrumentKey>
     for Engine<Clock, State, ExecutionTxs, Strategy, Risk>
 where
-    State: InFlightRequestRecorder<ExchangeKey, InstrumentKey>,
+    Clock: EngineClock,
+    State: InFlightRequestRecorder<ExchangeKey, InstrumentKey>
+        + PausedExchanges<ExchangeKey>
+        + RiskRefusalRecorder<InstrumentKey>,
     ExecutionTxs: ExecutionTxMap<ExchangeKey, InstrumentKey>,
     Strategy: AlgoStrategy<ExchangeKey, InstrumentKey, State = State>,
     Risk: RiskManager<ExchangeKey, InstrumentKey, State = State>,
@@ -48,17 +55,45 @@ where
         // Generate orders
         let (cancels, opens) = self.strategy.generate_algo_orders(&self.state);
 
+        // Refuse open requests to exchanges paused by a Reaction
+        let (opens, opens_paused): (Vec<_>, Vec<_>) = opens
+            .into_iter()
+            .partition(|open| !self.state.is_paused(&open.key.exchange));
+
         // RiskApprove & RiskRefuse order requests
         let (cancels, opens, refused_cancels, refused_opens) =
             self.risk.check(&self.state, cancels, opens);
+        self.meta.trace.time_risk = Some(self.clock.time());
 
         // Send risk approved order requests
         let cancels = self.send_requests(cancels.into_iter().map(|RiskApproved(cancel)| cancel));
         let opens = self.send_requests(opens.into_iter().map(|RiskApproved(open)| open));
 
         // Collect remaining Iterators (so we can access &mut self)
-        let cancels_refused = refused_cancels.into_iter().collect();
-        let opens_refused = refused_opens.into_iter().collect();
+        let cancels_refused: NoneOneOrMany<_> = refused_cancels.into_iter().collect();
+        let opens_refused: NoneOneOrMany<_> = opens_paused
+            .into_iter()
+            .map(|open| {
+                RiskRefused::new(
+                    open,
+                    RiskRefusalReason::refused(
+                        "exchange_paused",
+                        "orders paused until exchange health is restored",
+                    ),
+                )
+            })
+            .chain(refused_opens)
+            .collect();
+
+        // Record risk refusal statistics
+        for refused in cancels_refused.iter() {
+            self.state
+                .record_risk_refusal(&refused.item.key.instrument, &refused.reason);
+        }
+        for refused in opens_refused.iter() {
+            self.state
+                .record_risk_refusal(&refused.item.key.instrument, &refused.reason);
+        }
 
         // Record in flight order requests
         self.state.record_in_flight_cancels(cancels.sent.iter());
@@ -78,7 +113,8 @@ pub struct GenerateAlgoOrdersOutput<ExchangeKey = ExchangeIndex, InstrumentKey =
     pub cancels_and_opens: SendCancelsAndOpensOutput<ExchangeKey, InstrumentKey>,
     /// Generated cancel requests that were refused by the [`RiskManager`].
     pub cancels_refused: NoneOneOrMany<RiskRefused<OrderRequestCancel<ExchangeKey, InstrumentKey>>>,
-    /// Generated open requests that were refused by the [`RiskManager`].
+    /// Generated open requests that were refused by the [`RiskManager`], or refused because the
+    /// exchange is paused by a [`Reaction`](crate::engine::reaction::Reaction).
     pub opens_refused: NoneOneOrMany<RiskRefused<OrderRequestOpen<ExchangeKey, InstrumentKey>>>,
 }
 
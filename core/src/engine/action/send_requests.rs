@@ -1,6 +1,7 @@
 
 use crate::{
     engine::{
+        clock::EngineClock,
         error::{EngineError, RecoverableEngineError, UnrecoverableEngineError},
         execution_tx::ExecutionTxMap,
         Engine,
@@ -17,6 +18,7 @@ use toucan_execution::{
         request::{RequestCancel, RequestOpen},
         OrderEvent,
     },
+    trace::OrderTrace,
     ExchangeIndex, InstrumentIndex,
 };
 use toucan_integration::{channel::Tx, collection::none_one_or_many::NoneOneOrMany, Unrecoverable};
@@ -49,6 +51,7 @@ pub trait SendRequests<ExchangeKey = ExchangeIndex, InstrumentKey = InstrumentIn
 impl<Clock, State, ExecutionTxs, Strategy, Risk, ExchangeKey, InstrumentKey>
     SendRequests<ExchangeKey, InstrumentKey> for Engine<Clock, State, ExecutionTxs, Strategy, Risk>
 where
+    Clock: EngineClock,
     ExecutionTxs: ExecutionTxMap<ExchangeKey, InstrumentKey>,
     ExchangeKey: Debug + Clone,
     InstrumentKey: Debug + Clone,
@@ -84,10 +87,15 @@ where
         ExecutionRequest<ExchangeKey, InstrumentKey>:
             From<OrderEvent<Kind, ExchangeKey, InstrumentKey>>,
     {
+        let trace = OrderTrace {
+            time_sent: Some(self.clock.time()),
+            ..self.meta.trace
+        };
+
         match self
             .execution_txs
             .find(&request.key.exchange)?
-            .send(ExecutionRequest::from(request.clone()))
+            .send(ExecutionRequest::from(request.clone()).with_trace(Some(trace)))
         {
             Ok(()) => Ok(()),
             Err(error) if error.is_unrecoverable() => {
@@ -1,7 +1,8 @@
 
 use crate::engine::{
-    audit::context::EngineContext, clock::EngineClock, error::UnrecoverableEngineError, Engine,
-    EngineOutput, UpdateFromAccountOutput, UpdateFromMarketOutput,
+    audit::context::EngineContext, clock::EngineClock, error::UnrecoverableEngineError,
+    state::trading::TradingStateUpdateAudit, Engine, EngineOutput, UpdateFromAccountOutput,
+    UpdateFromMarketOutput,
 };
 use derive_more::Constructor;
 use serde::{Deserialize, Serialize};
@@ -96,6 +97,7 @@ pub enum EngineAudit<Event, Output> {
 impl<Event, Output> Terminal for EngineAudit<Event, Output>
 where
     Event: Terminal,
+    Output: Terminal,
 {
     fn is_terminal(&self) -> bool {
         match self {
@@ -169,9 +171,14 @@ pub struct ProcessAudit<Event, Output> {
 impl<Event, Output> Terminal for ProcessAudit<Event, Output>
 where
     Event: Terminal,
+    Output: Terminal,
 {
+    /// A `ProcessAudit` is terminal if the input event is terminal (eg/ `Shutdown`), an
+    /// unrecoverable error occurred, or an output completed a graceful shutdown sequence.
     fn is_terminal(&self) -> bool {
-        self.event.is_terminal() || !self.errors.is_empty()
+        self.event.is_terminal()
+            || !self.errors.is_empty()
+            || self.outputs.iter().any(Terminal::is_terminal)
     }
 }
 
@@ -218,6 +225,16 @@ impl<Event, OnTradingDisabled, OnDisconnect>
         }
     }
 
+    /// Add an [`EngineOutput::TradingState`] if the `Engine` transitioned the `TradingState`
+    /// from `update.prev` (ie/ the state replayed from the input event) whilst processing it.
+    pub fn add_trading_state_update(self, update: TradingStateUpdateAudit) -> Self {
+        if update.prev != update.current {
+            self.add_output(EngineOutput::TradingState(update))
+        } else {
+            self
+        }
+    }
+
     pub fn with_account_update<E>(event: E, account: UpdateFromAccountOutput<OnDisconnect>) -> Self
     where
         E: Into<Event>,
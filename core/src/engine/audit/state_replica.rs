@@ -35,6 +35,7 @@ impl<State, Updates> StateReplicaManager<State, Updates> {
             meta_start: EngineMeta {
                 time_start: snapshot.context.time,
                 sequence: snapshot.context.sequence,
+                trace: Default::default(),
             },
             state_replica: snapshot,
             updates,
@@ -88,6 +89,9 @@ where
             let shutdown = audit.is_terminal();
 
             self.update_from_event(audit.event);
+            for output in &audit.outputs {
+                self.update_from_output(output);
+            }
 
             if shutdown {
                 break "EngineEvent::Shutdown";
@@ -117,7 +121,10 @@ where
     /// Updates the internal `EngineState` using the provided `EngineEvent`.
     pub fn update_from_event(&mut self, event: EngineEvent<InstrumentData::MarketEventKind>) {
         match event {
-            EngineEvent::Shutdown(_) | EngineEvent::Command(_) => {
+            EngineEvent::Shutdown(_)
+            | EngineEvent::GracefulShutdown(_)
+            | EngineEvent::Command(_)
+            | EngineEvent::Timer(_) => {
                 // No action required
             }
             EngineEvent::TradingStateUpdate(trading_state) => {
@@ -149,6 +156,23 @@ where
         }
     }
 
+    /// Updates the internal `EngineState` using the provided `EngineOutput`.
+    ///
+    /// Only outputs that record `Engine` initiated state changes which cannot be replayed from the
+    /// input `EngineEvent` (eg/ a [`TradingState`](crate::engine::state::trading::TradingState)
+    /// transition by the session gate or a tripped `KillSwitch`) are applied.
+    pub fn update_from_output<OnDisable, OnDisconnect>(
+        &mut self,
+        output: &EngineOutput<OnDisable, OnDisconnect>,
+    ) {
+        if let EngineOutput::TradingState(audit) = output {
+            let _audit = self
+                .replica_engine_state_mut()
+                .trading
+                .update(audit.current);
+        }
+    }
+
     /// Returns a reference to the `EngineState` replica.
     pub fn replica_engine_state(&self) -> &EngineState<GlobalData, InstrumentData> {
         &self.state_replica.event
@@ -0,0 +1,807 @@
+use crate::engine::{command::Command, state::position::PositionExited};
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use toucan_execution::{
+    order::{id::StrategyId, OrderKey},
+    InstrumentIndex, QuoteAsset,
+};
+use toucan_integration::collection::FnvIndexMap;
+use tracing::warn;
+
+/// Automatic circuit breaker that moves the `Engine` to
+/// [`TradingState::Disabled`](super::state::trading::TradingState::Disabled) when a configured
+/// [`KillSwitchLimits`] is breached.
+///
+/// Limits can be configured globally, per instrument, and per strategy. The `EngineState` records
+/// order opens, order rejections and exited positions as they happen, and the `Engine` evaluates
+/// the limits after every event using the current open [`Position`](super::state::position::Position)
+/// PnL.
+///
+/// Note that per-strategy PnL is attributed from the strategy of the trade that exited a
+/// position, and therefore only includes realised PnL.
+///
+/// The tallies are intraday: they are reset when the `Engine` clock rolls over to a new trading
+/// day, where the day boundary is local midnight at `utc_offset_secs` (UTC by default).
+///
+/// Once tripped, the `KillSwitch` is latched (ie/ no further limits are evaluated) until it is
+/// re-armed by a manual `TradingState::Enabled` update.
+#[derive(Debug, Clone, PartialEq, Default, Deserialize, Serialize)]
+pub struct KillSwitch {
+    /// Limits applied to the aggregate of all instruments & strategies.
+    pub limits: KillSwitchLimits,
+
+    /// Limits applied to individual instruments.
+    pub instruments: FnvIndexMap<InstrumentIndex, KillSwitchLimits>,
+
+    /// Limits applied to individual strategies.
+    pub strategies: FnvIndexMap<StrategyId, KillSwitchLimits>,
+
+    /// [`Command`]s the `Engine` actions when the `KillSwitch` trips (eg/ `CancelOrders`,
+    /// `ClosePositions`).
+    pub on_trip: Vec<Command>,
+
+    /// Most recent [`KillSwitchTripped`], if the `KillSwitch` is currently tripped.
+    pub tripped: Option<KillSwitchTripped>,
+
+    /// Intraday tallies the limits are evaluated against.
+    pub tally: KillSwitchTallies,
+
+    /// Fixed UTC offset of the local timezone whose midnight starts a new trading day.
+    ///
+    /// eg/ B3 trading days start at midnight BRT, which is a `utc_offset_secs` of `-3 * 3600`.
+    #[serde(default)]
+    pub utc_offset_secs: i32,
+
+    /// Local trading day covered by the current `tally`, if any time has been observed.
+    #[serde(default)]
+    pub day: Option<NaiveDate>,
+}
+
+/// Configurable limits evaluated by a [`KillSwitch`].
+///
+/// A limit of `None` is not evaluated. A limit is breached when the observed value exceeds it.
+#[derive(
+    Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default, Deserialize, Serialize,
+)]
+pub struct KillSwitchLimits {
+    /// Maximum loss (realised plus unrealised PnL), expressed as a positive quote amount.
+    pub max_loss: Option<Decimal>,
+
+    /// Maximum intraday drawdown from the peak PnL, expressed as a positive quote amount.
+    pub max_drawdown: Option<Decimal>,
+
+    /// Maximum number of consecutive losing positions.
+    pub max_consecutive_losses: Option<u32>,
+
+    /// Maximum ratio of rejected to opened orders.
+    pub max_rejection_rate: Option<RejectionRateLimit>,
+}
+
+/// Maximum ratio of rejected to opened orders, only evaluated once at least `min_orders` orders
+/// have been opened.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub struct RejectionRateLimit {
+    pub max_rate: Decimal,
+    pub min_orders: u64,
+}
+
+/// Intraday [`KillSwitchTally`]s for every [`KillSwitchScope`].
+#[derive(Debug, Clone, PartialEq, Default, Deserialize, Serialize)]
+pub struct KillSwitchTallies {
+    pub global: KillSwitchTally,
+    pub instruments: FnvIndexMap<InstrumentIndex, KillSwitchTally>,
+    pub strategies: FnvIndexMap<StrategyId, KillSwitchTally>,
+}
+
+/// Intraday PnL, losing streak and order rejection tally for a [`KillSwitchScope`].
+#[derive(
+    Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default, Deserialize, Serialize,
+)]
+pub struct KillSwitchTally {
+    /// Realised PnL of exited positions (including fees).
+    pub pnl_realised: Decimal,
+
+    /// Realised plus unrealised PnL of open positions, net of `pnl_open_carried`.
+    pub pnl_open: Decimal,
+
+    /// Realised plus unrealised PnL of open positions carried over from previous trading days.
+    #[serde(default)]
+    pub pnl_open_carried: Decimal,
+
+    /// Peak total PnL observed.
+    pub pnl_peak: Decimal,
+
+    pub consecutive_losses: u32,
+    pub orders_opened: u64,
+    pub orders_rejected: u64,
+}
+
+impl KillSwitchTally {
+    /// Total PnL (realised plus open).
+    pub fn pnl(&self) -> Decimal {
+        self.pnl_realised + self.pnl_open
+    }
+
+    /// Drawdown of the total PnL from the peak total PnL.
+    pub fn drawdown(&self) -> Decimal {
+        self.pnl_peak - self.pnl()
+    }
+
+    /// Ratio of rejected to opened orders, or `None` if no orders have been opened.
+    pub fn rejection_rate(&self) -> Option<Decimal> {
+        (self.orders_opened > 0)
+            .then(|| Decimal::from(self.orders_rejected) / Decimal::from(self.orders_opened))
+    }
+
+    fn record_exit(&mut self, pnl_realised: Decimal) {
+        self.pnl_realised += pnl_realised;
+        if pnl_realised < Decimal::ZERO {
+            self.consecutive_losses += 1;
+        } else {
+            self.consecutive_losses = 0;
+        }
+    }
+
+    fn update_pnl_open(&mut self, pnl_open: Decimal) {
+        self.pnl_open = pnl_open - self.pnl_open_carried;
+        self.pnl_peak = self.pnl_peak.max(self.pnl());
+    }
+
+    /// Start a new trading day, carrying over the open PnL so that only PnL accrued from the
+    /// start of the day is tallied.
+    fn roll_day(&mut self) {
+        *self = Self {
+            pnl_open_carried: self.pnl_open_carried + self.pnl_open,
+            ..Self::default()
+        };
+    }
+
+    fn rearm(&mut self) {
+        self.consecutive_losses = 0;
+        self.orders_opened = 0;
+        self.orders_rejected = 0;
+    }
+}
+
+impl KillSwitchLimits {
+    /// Returns the first [`KillSwitchRule`] breached by the provided [`KillSwitchTally`],
+    /// alongside the limit and observed value.
+    pub fn breach(&self, tally: &KillSwitchTally) -> Option<(KillSwitchRule, Decimal, Decimal)> {
+        let max_loss = self.max_loss.and_then(|limit| {
+            let loss = -tally.pnl();
+            (loss > limit).then_some((KillSwitchRule::MaxLoss, limit, loss))
+        });
+
+        let max_drawdown = || {
+            self.max_drawdown.and_then(|limit| {
+                let drawdown = tally.drawdown();
+                (drawdown > limit).then_some((KillSwitchRule::MaxDrawdown, limit, drawdown))
+            })
+        };
+
+        let max_consecutive_losses = || {
+            self.max_consecutive_losses.and_then(|limit| {
+                (tally.consecutive_losses > limit).then_some((
+                    KillSwitchRule::MaxConsecutiveLosses,
+                    Decimal::from(limit),
+                    Decimal::from(tally.consecutive_losses),
+                ))
+            })
+        };
+
+        let max_rejection_rate = || {
+            let limit = self.max_rejection_rate?;
+            if tally.orders_opened < limit.min_orders {
+                return None;
+            }
+            let rate = tally.rejection_rate()?;
+            (rate > limit.max_rate).then_some((
+                KillSwitchRule::MaxRejectionRate,
+                limit.max_rate,
+                rate,
+            ))
+        };
+
+        max_loss
+            .or_else(max_drawdown)
+            .or_else(max_consecutive_losses)
+            .or_else(max_rejection_rate)
+    }
+}
+
+/// [`KillSwitchLimits`] rule that can trip a [`KillSwitch`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub enum KillSwitchRule {
+    MaxLoss,
+    MaxDrawdown,
+    MaxConsecutiveLosses,
+    MaxRejectionRate,
+}
+
+/// Scope of the [`KillSwitchLimits`] that tripped a [`KillSwitch`].
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub enum KillSwitchScope {
+    Global,
+    Instrument(InstrumentIndex),
+    Strategy(StrategyId),
+}
+
+/// Audit record of a tripped [`KillSwitch`], explaining which rule fired.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub struct KillSwitchTripped {
+    pub scope: KillSwitchScope,
+    pub rule: KillSwitchRule,
+    pub limit: Decimal,
+    pub observed: Decimal,
+    pub time: DateTime<Utc>,
+}
+
+impl KillSwitch {
+    /// Construct a new [`KillSwitch`] with the provided global [`KillSwitchLimits`].
+    pub fn new(limits: KillSwitchLimits) -> Self {
+        Self {
+            limits,
+            ..Self::default()
+        }
+    }
+
+    /// Set the [`KillSwitchLimits`] for an instrument.
+    pub fn with_instrument_limits(
+        mut self,
+        instrument: InstrumentIndex,
+        limits: KillSwitchLimits,
+    ) -> Self {
+        self.instruments.insert(instrument, limits);
+        self
+    }
+
+    /// Set the [`KillSwitchLimits`] for a strategy.
+    pub fn with_strategy_limits(mut self, strategy: StrategyId, limits: KillSwitchLimits) -> Self {
+        self.strategies.insert(strategy, limits);
+        self
+    }
+
+    /// Set the [`Command`]s the `Engine` actions when the `KillSwitch` trips.
+    pub fn with_on_trip<CommandIter>(self, commands: CommandIter) -> Self
+    where
+        CommandIter: IntoIterator<Item = Command>,
+    {
+        Self {
+            on_trip: commands.into_iter().collect(),
+            ..self
+        }
+    }
+
+    /// Set the fixed UTC offset of the local timezone whose midnight starts a new trading day.
+    pub fn with_day_offset(self, offset: FixedOffset) -> Self {
+        Self {
+            utc_offset_secs: offset.local_minus_utc(),
+            ..self
+        }
+    }
+
+    /// Returns `true` if the `KillSwitch` is currently tripped.
+    pub fn is_tripped(&self) -> bool {
+        self.tripped.is_some()
+    }
+
+    /// Re-arm a tripped `KillSwitch`, resetting losing streaks and order rejection tallies.
+    ///
+    /// Intraday PnL is retained, so a loss or drawdown limit breached today will trip again until
+    /// the next trading day.
+    pub fn rearm(&mut self) {
+        self.tripped = None;
+        self.tally.global.rearm();
+        self.tally
+            .instruments
+            .values_mut()
+            .for_each(KillSwitchTally::rearm);
+        self.tally
+            .strategies
+            .values_mut()
+            .for_each(KillSwitchTally::rearm);
+    }
+
+    /// Update the `KillSwitch` with the current `Engine` time, resetting every intraday tally if
+    /// `time` falls on a later local trading day than the tallies cover.
+    ///
+    /// A tripped `KillSwitch` remains latched across the day rollover.
+    pub fn update_time(&mut self, time: DateTime<Utc>) {
+        let Some(offset) = FixedOffset::east_opt(self.utc_offset_secs) else {
+            return;
+        };
+        let day = time.with_timezone(&offset).date_naive();
+
+        match self.day {
+            Some(current) if current >= day => {}
+            Some(_) => {
+                self.day = Some(day);
+                self.tally.global.roll_day();
+                self.tally
+                    .instruments
+                    .values_mut()
+                    .for_each(KillSwitchTally::roll_day);
+                self.tally
+                    .strategies
+                    .values_mut()
+                    .for_each(KillSwitchTally::roll_day);
+            }
+            None => self.day = Some(day),
+        }
+    }
+
+    /// Record an order open request sent to an exchange.
+    pub fn record_open<ExchangeKey>(&mut self, key: &OrderKey<ExchangeKey, InstrumentIndex>) {
+        self.tallies_mut(&key.instrument, &key.strategy)
+            .for_each(|tally| tally.orders_opened += 1);
+    }
+
+    /// Record an order open request rejected by an exchange.
+    pub fn record_rejection<ExchangeKey>(&mut self, key: &OrderKey<ExchangeKey, InstrumentIndex>) {
+        self.tallies_mut(&key.instrument, &key.strategy)
+            .for_each(|tally| tally.orders_rejected += 1);
+    }
+
+    /// Record a [`PositionExited`] by a trade generated by the provided strategy.
+    pub fn record_position_exit(
+        &mut self,
+        strategy: &StrategyId,
+        exited: &PositionExited<QuoteAsset, InstrumentIndex>,
+    ) {
+        self.tallies_mut(&exited.instrument, strategy)
+            .for_each(|tally| tally.record_exit(exited.pnl_realised));
+    }
+
+    /// Evaluate all [`KillSwitchLimits`], using the provided realised plus unrealised PnL of each
+    /// instrument's open position.
+    ///
+    /// The intraday tallies are first rolled over if `time` is on a new trading day (see
+    /// [`Self::update_time`]).
+    ///
+    /// Returns the [`KillSwitchTripped`] if this evaluation tripped the `KillSwitch`.
+    pub fn check<'a, PnlIter>(
+        &mut self,
+        time: DateTime<Utc>,
+        pnl_open: PnlIter,
+    ) -> Option<KillSwitchTripped>
+    where
+        PnlIter: IntoIterator<Item = (&'a InstrumentIndex, Decimal)>,
+    {
+        self.update_time(time);
+
+        let mut pnl_open_total = Decimal::ZERO;
+        for (instrument, pnl) in pnl_open {
+            pnl_open_total += pnl;
+            if let Some(tally) = self.tally.instruments.get_mut(instrument) {
+                tally.update_pnl_open(pnl);
+            } else if pnl != Decimal::ZERO {
+                let mut tally = KillSwitchTally::default();
+                tally.update_pnl_open(pnl);
+                self.tally.instruments.insert(instrument.clone(), tally);
+            }
+        }
+        self.tally.global.update_pnl_open(pnl_open_total);
+        self.tally
+            .strategies
+            .values_mut()
+            .for_each(|tally| tally.update_pnl_open(Decimal::ZERO));
+
+        if self.is_tripped() {
+            return None;
+        }
+
+        let default_tally = KillSwitchTally::default();
+
+        let global = self
+            .limits
+            .breach(&self.tally.global)
+            .map(|breach| (KillSwitchScope::Global, breach));
+
+        let instrument = || {
+            self.instruments.iter().find_map(|(instrument, limits)| {
+                let tally = self
+                    .tally
+                    .instruments
+                    .get(instrument)
+                    .unwrap_or(&default_tally);
+                limits
+                    .breach(tally)
+                    .map(|breach| (KillSwitchScope::Instrument(instrument.clone()), breach))
+            })
+        };
+
+        let strategy = || {
+            self.strategies.iter().find_map(|(strategy, limits)| {
+                let tally = self
+                    .tally
+                    .strategies
+                    .get(strategy)
+                    .unwrap_or(&default_tally);
+                limits
+                    .breach(tally)
+                    .map(|breach| (KillSwitchScope::Strategy(strategy.clone()), breach))
+            })
+        };
+
+        let (scope, (rule, limit, observed)) = global.or_else(instrument).or_else(strategy)?;
+
+        warn!(
+            ?scope,
+            ?rule,
+            %limit,
+            %observed,
+            %time,
+            "Engine KillSwitch tripped"
+        );
+
+        let tripped = KillSwitchTripped {
+            scope,
+            rule,
+            limit,
+            observed,
+            time,
+        };
+        self.tripped = Some(tripped.clone());
+
+        Some(tripped)
+    }
+
+    fn tallies_mut<'a>(
+        &'a mut self,
+        instrument: &InstrumentIndex,
+        strategy: &StrategyId,
+    ) -> impl Iterator<Item = &'a mut KillSwitchTally> {
+        let KillSwitchTallies {
+            global,
+            instruments,
+            strategies,
+        } = &mut self.tally;
+
+        [
+            global,
+            instruments.entry(instrument.clone()).or_default(),
+            strategies.entry(strategy.clone()).or_default(),
+        ]
+        .into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::state::instrument::filter::InstrumentFilter;
+    use rust_decimal_macros::dec;
+    use toucan_execution::{order::id::ClientOrderId, trade::AssetFees};
+    use toucan_instrument::{exchange::ExchangeId, Side};
+
+    fn time() -> DateTime<Utc> {
+        "2024-03-15T14:00:00Z".parse().unwrap()
+    }
+
+    fn brt(date: &str, time: &str) -> DateTime<Utc> {
+        format!("{date}T{time}-03:00").parse().unwrap()
+    }
+
+    fn strategy(name: &str) -> StrategyId {
+        StrategyId::new(name)
+    }
+
+    fn key(instrument: &str, strategy_id: &str) -> OrderKey<ExchangeId, InstrumentIndex> {
+        OrderKey {
+            exchange: ExchangeId::Simulated,
+            instrument: instrument.to_string(),
+            strategy: strategy(strategy_id),
+            cid: ClientOrderId::new("cid"),
+        }
+    }
+
+    fn exited(instrument: &str, pnl_realised: Decimal) -> PositionExited<QuoteAsset> {
+        PositionExited {
+            instrument: instrument.to_string(),
+            side: Side::Buy,
+            price_entry_average: dec!(10),
+            quantity_abs_max: dec!(1),
+            pnl_realised,
+            fees_enter: AssetFees::quote_fees(dec!(0)),
+            fees_exit: AssetFees::quote_fees(dec!(0)),
+            time_enter: time(),
+            time_exit: time(),
+            trades: vec![],
+        }
+    }
+
+    #[test]
+    fn test_kill_switch_limits_breach() {
+        struct TestCase {
+            limits: KillSwitchLimits,
+            tally: KillSwitchTally,
+            expected: Option<(KillSwitchRule, Decimal, Decimal)>,
+        }
+
+        let cases = vec![
+            // TC0: no limits configured
+            TestCase {
+                limits: KillSwitchLimits::default(),
+                tally: KillSwitchTally {
+                    pnl_realised: dec!(-1000),
+                    consecutive_losses: 10,
+                    ..Default::default()
+                },
+                expected: None,
+            },
+            // TC1: loss within limit
+            TestCase {
+                limits: KillSwitchLimits {
+                    max_loss: Some(dec!(100)),
+                    ..Default::default()
+                },
+                tally: KillSwitchTally {
+                    pnl_realised: dec!(-60),
+                    pnl_open: dec!(-40),
+                    ..Default::default()
+                },
+                expected: None,
+            },
+            // TC2: realised plus unrealised loss exceeds limit
+            TestCase {
+                limits: KillSwitchLimits {
+                    max_loss: Some(dec!(100)),
+                    ..Default::default()
+                },
+                tally: KillSwitchTally {
+                    pnl_realised: dec!(-60),
+                    pnl_open: dec!(-41),
+                    ..Default::default()
+                },
+                expected: Some((KillSwitchRule::MaxLoss, dec!(100), dec!(101))),
+            },
+            // TC3: drawdown from peak exceeds limit whilst PnL is positive
+            TestCase {
+                limits: KillSwitchLimits {
+                    max_loss: Some(dec!(100)),
+                    max_drawdown: Some(dec!(50)),
+                    ..Default::default()
+                },
+                tally: KillSwitchTally {
+                    pnl_realised: dec!(20),
+                    pnl_peak: dec!(80),
+                    ..Default::default()
+                },
+                expected: Some((KillSwitchRule::MaxDrawdown, dec!(50), dec!(60))),
+            },
+            // TC4: consecutive losses exceed limit
+            TestCase {
+                limits: KillSwitchLimits {
+                    max_consecutive_losses: Some(3),
+                    ..Default::default()
+                },
+                tally: KillSwitchTally {
+                    consecutive_losses: 4,
+                    ..Default::default()
+                },
+                expected: Some((KillSwitchRule::MaxConsecutiveLosses, dec!(3), dec!(4))),
+            },
+            // TC5: rejection rate exceeds limit, but too few orders opened
+            TestCase {
+                limits: KillSwitchLimits {
+                    max_rejection_rate: Some(RejectionRateLimit {
+                        max_rate: dec!(0.5),
+                        min_orders: 10,
+                    }),
+                    ..Default::default()
+                },
+                tally: KillSwitchTally {
+                    orders_opened: 4,
+                    orders_rejected: 4,
+                    ..Default::default()
+                },
+                expected: None,
+            },
+            // TC6: rejection rate exceeds limit
+            TestCase {
+                limits: KillSwitchLimits {
+                    max_rejection_rate: Some(RejectionRateLimit {
+                        max_rate: dec!(0.5),
+                        min_orders: 10,
+                    }),
+                    ..Default::default()
+                },
+                tally: KillSwitchTally {
+                    orders_opened: 10,
+                    orders_rejected: 6,
+                    ..Default::default()
+                },
+                expected: Some((KillSwitchRule::MaxRejectionRate, dec!(0.5), dec!(0.6))),
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let actual = test.limits.breach(&test.tally);
+            assert_eq!(actual, test.expected, "TC{index} failed");
+        }
+    }
+
+    #[test]
+    fn test_kill_switch_check() {
+        let btc = "btc_usdt".to_string();
+        let eth = "eth_usdt".to_string();
+
+        let mut kill_switch = KillSwitch::new(KillSwitchLimits {
+            max_loss: Some(dec!(100)),
+            ..Default::default()
+        })
+        .with_instrument_limits(
+            eth.clone(),
+            KillSwitchLimits {
+                max_drawdown: Some(dec!(30)),
+                ..Default::default()
+            },
+        )
+        .with_strategy_limits(
+            strategy("momentum"),
+            KillSwitchLimits {
+                max_consecutive_losses: Some(1),
+                ..Default::default()
+            },
+        )
+        .with_on_trip([Command::CancelOrders(InstrumentFilter::None)]);
+
+        // Open unrealised PnL within all limits
+        let actual = kill_switch.check(time(), [(&btc, dec!(-50)), (&eth, dec!(25))]);
+        assert_eq!(actual, None);
+
+        // Instrument drawdown from the eth peak PnL of 25 to -10 exceeds limit
+        let actual = kill_switch.check(time(), [(&btc, dec!(-50)), (&eth, dec!(-10))]);
+        assert_eq!(
+            actual,
+            Some(KillSwitchTripped {
+                scope: KillSwitchScope::Instrument(eth.clone()),
+                rule: KillSwitchRule::MaxDrawdown,
+                limit: dec!(30),
+                observed: dec!(35),
+                time: time(),
+            })
+        );
+        assert!(kill_switch.is_tripped());
+
+        // Latched: global loss breach is not reported whilst tripped
+        let actual = kill_switch.check(time(), [(&btc, dec!(-500)), (&eth, dec!(-10))]);
+        assert_eq!(actual, None);
+
+        // Re-armed: PnL is retained, so the global loss limit trips
+        kill_switch.rearm();
+        let actual = kill_switch.check(time(), [(&btc, dec!(-500)), (&eth, dec!(-10))]);
+        assert_eq!(
+            actual.map(|tripped| (tripped.scope, tripped.rule, tripped.observed)),
+            Some((KillSwitchScope::Global, KillSwitchRule::MaxLoss, dec!(510)))
+        );
+    }
+
+    #[test]
+    fn test_kill_switch_record() {
+        let btc = "btc_usdt".to_string();
+
+        let mut kill_switch = KillSwitch::new(KillSwitchLimits {
+            max_rejection_rate: Some(RejectionRateLimit {
+                max_rate: dec!(0.5),
+                min_orders: 2,
+            }),
+            ..Default::default()
+        })
+        .with_strategy_limits(
+            strategy("momentum"),
+            KillSwitchLimits {
+                max_consecutive_losses: Some(1),
+                ..Default::default()
+            },
+        );
+
+        // Losing exits by different strategies only breach the global & instrument streaks
+        kill_switch.record_position_exit(&strategy("momentum"), &exited(&btc, dec!(-5)));
+        kill_switch.record_position_exit(&strategy("mean_rev"), &exited(&btc, dec!(-5)));
+        assert_eq!(kill_switch.tally.global.consecutive_losses, 2);
+        assert_eq!(kill_switch.tally.global.pnl_realised, dec!(-10));
+        assert_eq!(kill_switch.check(time(), [(&btc, dec!(0))]), None);
+
+        // Second consecutive momentum loss exceeds the strategy limit
+        kill_switch.record_position_exit(&strategy("momentum"), &exited(&btc, dec!(-1)));
+        assert_eq!(
+            kill_switch
+                .check(time(), [(&btc, dec!(0))])
+                .map(|tripped| (tripped.scope, tripped.rule)),
+            Some((
+                KillSwitchScope::Strategy(strategy("momentum")),
+                KillSwitchRule::MaxConsecutiveLosses
+            ))
+        );
+
+        // Re-arm resets the losing streaks, then rejections trip the global limit
+        kill_switch.rearm();
+        kill_switch.record_open(&key(&btc, "momentum"));
+        kill_switch.record_open(&key(&btc, "momentum"));
+        kill_switch.record_rejection(&key(&btc, "momentum"));
+        assert_eq!(kill_switch.check(time(), [(&btc, dec!(0))]), None);
+
+        kill_switch.record_open(&key(&btc, "momentum"));
+        kill_switch.record_rejection(&key(&btc, "momentum"));
+        assert_eq!(
+            kill_switch
+                .check(time(), [(&btc, dec!(0))])
+                .map(|tripped| (tripped.scope, tripped.rule)),
+            Some((KillSwitchScope::Global, KillSwitchRule::MaxRejectionRate))
+        );
+    }
+
+    #[test]
+    fn test_kill_switch_day_rollover() {
+        let btc = "btc_usdt".to_string();
+        let offset = FixedOffset::west_opt(3 * 3600).unwrap();
+        let day_one = |time: &str| brt("2024-03-15", time);
+        let day_two = |time: &str| brt("2024-03-16", time);
+
+        let mut kill_switch = KillSwitch::new(KillSwitchLimits {
+            max_loss: Some(dec!(100)),
+            max_consecutive_losses: Some(1),
+            ..Default::default()
+        })
+        .with_day_offset(offset);
+
+        // Day one: losing exit plus a losing open position carried overnight breach the loss limit
+        kill_switch.record_position_exit(&strategy("momentum"), &exited(&btc, dec!(-80)));
+        assert_eq!(
+            kill_switch.check(day_one("10:00:00"), [(&btc, dec!(-10))]),
+            None
+        );
+        assert_eq!(
+            kill_switch
+                .check(day_one("17:00:00"), [(&btc, dec!(-30))])
+                .map(|tripped| (tripped.scope, tripped.rule, tripped.observed)),
+            Some((KillSwitchScope::Global, KillSwitchRule::MaxLoss, dec!(110)))
+        );
+
+        // Day one re-arm: the intraday loss is retained, so the limit trips again
+        kill_switch.rearm();
+        assert!(kill_switch
+            .check(day_one("23:59:59"), [(&btc, dec!(-30))])
+            .is_some());
+
+        // Day two: tallies reset at BRT midnight, but the KillSwitch remains latched
+        kill_switch.update_time(day_two("00:00:00"));
+        assert!(kill_switch.is_tripped());
+        assert_eq!(kill_switch.day, NaiveDate::from_ymd_opt(2024, 3, 16));
+        assert_eq!(
+            kill_switch.tally.global,
+            KillSwitchTally {
+                pnl_open_carried: dec!(-30),
+                ..Default::default()
+            }
+        );
+
+        // Day two re-arm: only PnL accrued since the day start is tallied against the limit
+        kill_switch.rearm();
+        assert_eq!(
+            kill_switch.check(day_two("10:00:00"), [(&btc, dec!(-120))]),
+            None
+        );
+        assert_eq!(kill_switch.tally.global.pnl(), dec!(-90));
+
+        // Carried over position exits at a further loss, starting a new losing streak
+        kill_switch.record_position_exit(&strategy("momentum"), &exited(&btc, dec!(-50)));
+        assert_eq!(
+            kill_switch.check(day_two("11:00:00"), [(&btc, dec!(0))]),
+            None
+        );
+        assert_eq!(kill_switch.tally.global.pnl(), dec!(-20));
+        assert_eq!(kill_switch.tally.global.consecutive_losses, 1);
+
+        // A second day two loss breaches the loss limit again
+        kill_switch.record_position_exit(&strategy("momentum"), &exited(&btc, dec!(-81)));
+        assert_eq!(
+            kill_switch
+                .check(day_two("12:00:00"), [(&btc, dec!(0))])
+                .map(|tripped| (tripped.scope, tripped.rule, tripped.observed)),
+            Some((KillSwitchScope::Global, KillSwitchRule::MaxLoss, dec!(101)))
+        );
+    }
+}
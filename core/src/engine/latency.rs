@@ -0,0 +1,583 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use prettytable::{Cell, Row, Table};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter},
+    time::Duration,
+};
+use toucan_execution::{
+    order::{
+        id::{ClientOrderId, OrderId, StrategyId},
+        state::{ActiveOrderState, InactiveOrderState, OrderState},
+    },
+    trace::{LatencyStage, OrderTrace},
+    AccountEvent, AccountEventKind, ExchangeIndex,
+};
+use toucan_integration::collection::FnvIndexMap;
+
+/// Default maximum number of acknowledged orders awaiting a first fill tracked by a
+/// [`LatencyTracker`].
+pub const DEFAULT_MAX_AWAITING_FILL: usize = 10_000;
+
+/// Number of values below which a [`LatencyHistogram`] records exactly.
+///
+/// Above this, each power of two range is split into `LINEAR_SUB_BUCKETS / 2` buckets, bounding
+/// the relative error of recorded values to less than 1/64 (~1.6%).
+const LINEAR_SUB_BUCKETS: u64 = 128;
+const HALF_SUB_BUCKETS: u64 = LINEAR_SUB_BUCKETS / 2;
+const SUB_BUCKET_BITS: u32 = HALF_SUB_BUCKETS.trailing_zeros();
+
+/// HDR-style log-linear histogram of latencies, recorded in microseconds.
+///
+/// Memory usage is proportional to the number of distinct buckets observed, and percentiles are
+/// reported as the highest value equivalent to the bucket they fall in (clamped to the maximum
+/// recorded value).
+#[derive(Debug, Clone, Eq, PartialEq, Default, Deserialize, Serialize)]
+pub struct LatencyHistogram {
+    buckets: BTreeMap<u32, u64>,
+    count: u64,
+    sum: u64,
+    min: u64,
+    max: u64,
+}
+
+impl LatencyHistogram {
+    /// Record a latency in microseconds.
+    pub fn record(&mut self, micros: u64) {
+        *self.buckets.entry(bucket_index(micros)).or_default() += 1;
+        self.min = if self.count == 0 {
+            micros
+        } else {
+            self.min.min(micros)
+        };
+        self.max = self.max.max(micros);
+        self.count += 1;
+        self.sum = self.sum.saturating_add(micros);
+    }
+
+    /// Record a latency [`TimeDelta`], clamping negative deltas to zero.
+    pub fn record_delta(&mut self, delta: TimeDelta) {
+        let micros = delta.num_microseconds().unwrap_or(i64::MAX).max(0);
+        self.record(micros as u64);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn min(&self) -> Option<u64> {
+        (self.count > 0).then_some(self.min)
+    }
+
+    pub fn max(&self) -> Option<u64> {
+        (self.count > 0).then_some(self.max)
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.sum as f64 / self.count as f64)
+    }
+
+    /// Value (in microseconds) at the provided percentile (eg/ 99.9).
+    pub fn percentile(&self, percentile: f64) -> Option<u64> {
+        if self.count == 0 {
+            return None;
+        }
+
+        let rank = ((percentile.clamp(0.0, 100.0) / 100.0) * self.count as f64).ceil() as u64;
+        let rank = rank.max(1);
+
+        let mut cumulative = 0;
+        self.buckets.iter().find_map(|(index, count)| {
+            cumulative += count;
+            (cumulative >= rank).then(|| highest_equivalent(*index).min(self.max))
+        })
+    }
+
+    /// Summarise the histogram as [`LatencyPercentiles`].
+    pub fn percentiles(&self) -> Option<LatencyPercentiles> {
+        Some(LatencyPercentiles {
+            count: self.count,
+            min: Duration::from_micros(self.min()?),
+            p50: Duration::from_micros(self.percentile(50.0)?),
+            p90: Duration::from_micros(self.percentile(90.0)?),
+            p99: Duration::from_micros(self.percentile(99.0)?),
+            p999: Duration::from_micros(self.percentile(99.9)?),
+            max: Duration::from_micros(self.max()?),
+            mean: Duration::from_secs_f64(self.mean()? / 1_000_000.0),
+        })
+    }
+}
+
+fn bucket_index(value: u64) -> u32 {
+    if value < LINEAR_SUB_BUCKETS {
+        return value as u32;
+    }
+
+    let shift = (63 - value.leading_zeros()) - SUB_BUCKET_BITS;
+    let mantissa = value >> shift;
+    (LINEAR_SUB_BUCKETS + (shift as u64 - 1) * HALF_SUB_BUCKETS + (mantissa - HALF_SUB_BUCKETS))
+        as u32
+}
+
+fn highest_equivalent(index: u32) -> u64 {
+    let index = index as u64;
+    if index < LINEAR_SUB_BUCKETS {
+        return index;
+    }
+
+    let shift = (index - LINEAR_SUB_BUCKETS) / HALF_SUB_BUCKETS + 1;
+    let mantissa = (index - LINEAR_SUB_BUCKETS) % HALF_SUB_BUCKETS + HALF_SUB_BUCKETS;
+    ((mantissa + 1) << shift).saturating_sub(1)
+}
+
+/// [`LatencyHistogram`] for each [`LatencyStage`].
+pub type StageHistograms = BTreeMap<LatencyStage, LatencyHistogram>;
+
+/// Latency of an order produced by the [`LatencyTracker`], emitted via the `Engine` audit stream
+/// when the order is acknowledged by the exchange, and again when it is first filled.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub struct OrderLatency {
+    pub exchange: ExchangeIndex,
+    pub strategy: StrategyId,
+    pub cid: ClientOrderId,
+    pub trace: OrderTrace,
+}
+
+/// Tracks the tick-to-trade latency of every order opened by the `Engine`, maintaining a
+/// [`LatencyHistogram`] per [`LatencyStage`] for each exchange and each strategy.
+///
+/// Stages up to the exchange acknowledgement are recorded when the open order response is
+/// processed, using the [`OrderTrace`] returned on the `AccountEvent`. Acknowledged orders are
+/// then tracked (up to `max_awaiting_fill`, oldest evicted first) until their first fill.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct LatencyTracker {
+    pub exchanges: BTreeMap<ExchangeIndex, StageHistograms>,
+    pub strategies: BTreeMap<StrategyId, StageHistograms>,
+    pub awaiting_fill: FnvIndexMap<OrderId, OrderLatency>,
+    pub max_awaiting_fill: usize,
+}
+
+impl Default for LatencyTracker {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_AWAITING_FILL)
+    }
+}
+
+impl LatencyTracker {
+    pub fn new(max_awaiting_fill: usize) -> Self {
+        Self {
+            exchanges: BTreeMap::new(),
+            strategies: BTreeMap::new(),
+            awaiting_fill: FnvIndexMap::default(),
+            max_awaiting_fill,
+        }
+    }
+
+    /// Update the `LatencyTracker` from an [`AccountEvent`] processed by the `Engine` at `time`.
+    ///
+    /// Returns the [`OrderLatency`] of an order that was acknowledged or first filled.
+    pub fn update_from_account(
+        &mut self,
+        event: &AccountEvent,
+        time: DateTime<Utc>,
+    ) -> Option<OrderLatency> {
+        match &event.kind {
+            AccountEventKind::OrderSnapshot(snapshot) => {
+                let trace = event.trace.as_deref().copied()?;
+                let order = snapshot.value();
+
+                let mut latency = OrderLatency {
+                    exchange: order.key.exchange.clone(),
+                    strategy: order.key.strategy.clone(),
+                    cid: order.key.cid.clone(),
+                    trace,
+                };
+
+                match &order.state {
+                    // Acknowledged without fills, so await the first Trade
+                    OrderState::Active(ActiveOrderState::Open(open))
+                        if open.filled_quantity.is_zero() =>
+                    {
+                        self.record(&latency, &ACK_STAGES);
+                        self.await_fill(open.id.clone(), latency.clone());
+                    }
+                    // Filled (at least partially) on acknowledgement
+                    OrderState::Active(ActiveOrderState::Open(_))
+                    | OrderState::Inactive(InactiveOrderState::FullyFilled) => {
+                        latency.trace.time_fill = Some(time);
+                        self.record(&latency, &LatencyStage::ALL);
+                    }
+                    _ => self.record(&latency, &ACK_STAGES),
+                }
+
+                Some(latency)
+            }
+            AccountEventKind::Trade(trade) => {
+                let mut latency = self.awaiting_fill.shift_remove(&trade.order_id)?;
+                latency.trace.time_fill = Some(time);
+                self.record(&latency, &FILL_STAGES);
+                Some(latency)
+            }
+            _ => None,
+        }
+    }
+
+    fn await_fill(&mut self, id: OrderId, latency: OrderLatency) {
+        if self.max_awaiting_fill == 0 {
+            return;
+        }
+        while self.awaiting_fill.len() >= self.max_awaiting_fill {
+            self.awaiting_fill.shift_remove_index(0);
+        }
+        self.awaiting_fill.insert(id, latency);
+    }
+
+    fn record(&mut self, latency: &OrderLatency, stages: &[LatencyStage]) {
+        let exchange = self.exchanges.entry(latency.exchange.clone()).or_default();
+        record_stages(exchange, &latency.trace, stages);
+
+        let strategy = self.strategies.entry(latency.strategy.clone()).or_default();
+        record_stages(strategy, &latency.trace, stages);
+    }
+
+    /// Generate a [`LatencySummary`] of every recorded [`LatencyStage`].
+    pub fn summary(&self) -> LatencySummary {
+        LatencySummary {
+            exchanges: summarise(&self.exchanges),
+            strategies: summarise(&self.strategies),
+        }
+    }
+}
+
+/// Stages recorded when an order is acknowledged by the exchange.
+const ACK_STAGES: [LatencyStage; 7] = [
+    LatencyStage::ExchangeToReceived,
+    LatencyStage::ReceivedToEngine,
+    LatencyStage::EngineToRisk,
+    LatencyStage::RiskToSent,
+    LatencyStage::SentToAck,
+    LatencyStage::TickToTrade,
+    LatencyStage::TickToAck,
+];
+
+/// Stages recorded when an acknowledged order is first filled.
+const FILL_STAGES: [LatencyStage; 2] = [LatencyStage::AckToFill, LatencyStage::TickToFill];
+
+fn record_stages(histograms: &mut StageHistograms, trace: &OrderTrace, stages: &[LatencyStage]) {
+    for stage in stages {
+        if let Some(latency) = trace.latency(*stage) {
+            histograms.entry(*stage).or_default().record_delta(latency);
+        }
+    }
+}
+
+fn summarise<Key>(
+    histograms: &BTreeMap<Key, StageHistograms>,
+) -> BTreeMap<Key, BTreeMap<LatencyStage, LatencyPercentiles>>
+where
+    Key: Ord + Clone,
+{
+    histograms
+        .iter()
+        .map(|(key, stages)| {
+            let stages = stages
+                .iter()
+                .filter_map(|(stage, histogram)| Some((*stage, histogram.percentiles()?)))
+                .collect();
+            (key.clone(), stages)
+        })
+        .collect()
+}
+
+/// Percentile summary of a [`LatencyHistogram`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub struct LatencyPercentiles {
+    pub count: u64,
+    pub min: Duration,
+    pub p50: Duration,
+    pub p90: Duration,
+    pub p99: Duration,
+    pub p999: Duration,
+    pub max: Duration,
+    pub mean: Duration,
+}
+
+/// Summary of the tick-to-trade latencies recorded by a [`LatencyTracker`], per exchange and per
+/// strategy.
+///
+/// Generated at shutdown via `Engine::latency_summary`.
+#[derive(Debug, Clone, Eq, PartialEq, Default, Deserialize, Serialize)]
+pub struct LatencySummary {
+    pub exchanges: BTreeMap<ExchangeIndex, BTreeMap<LatencyStage, LatencyPercentiles>>,
+    pub strategies: BTreeMap<StrategyId, BTreeMap<LatencyStage, LatencyPercentiles>>,
+}
+
+impl LatencySummary {
+    pub fn print_summary(&self) {
+        println!();
+        self.table().printstd();
+    }
+
+    /// Generate a table with a row per exchange & strategy [`LatencyStage`], in microseconds.
+    pub fn table(&self) -> Table {
+        let mut table = Table::new();
+        table.set_format(*prettytable::format::consts::FORMAT_BOX_CHARS);
+
+        let mut title_cell = Cell::new("Tick-To-Trade Latency (µs)").style_spec("bcB");
+        title_cell.set_hspan(10);
+        table.add_row(Row::new(vec![title_cell]));
+
+        table.add_row(Row::new(
+            [
+                "Source", "Stage", "Count", "Min", "p50", "p90", "p99", "p99.9", "Max", "Mean",
+            ]
+            .into_iter()
+            .map(|header| Cell::new(header).style_spec("bcB"))
+            .collect(),
+        ));
+
+        let exchanges = self
+            .exchanges
+            .iter()
+            .map(|(exchange, stages)| (format!("exchange {exchange}"), stages));
+        let strategies = self
+            .strategies
+            .iter()
+            .map(|(strategy, stages)| (format!("strategy {strategy}"), stages));
+
+        for (source, stages) in exchanges.chain(strategies) {
+            for (stage, percentiles) in stages {
+                let micros = |duration: Duration| Cell::new(&duration.as_micros().to_string());
+                table.add_row(Row::new(vec![
+                    Cell::new(&source),
+                    Cell::new(stage.as_str()),
+                    Cell::new(&percentiles.count.to_string()),
+                    micros(percentiles.min),
+                    micros(percentiles.p50),
+                    micros(percentiles.p90),
+                    micros(percentiles.p99),
+                    micros(percentiles.p999),
+                    micros(percentiles.max),
+                    micros(percentiles.mean),
+                ]));
+            }
+        }
+
+        table
+    }
+}
+
+impl Display for LatencySummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.table())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use toucan_execution::{
+        order::{state::Open, Order, OrderKey, OrderKind, TimeInForce},
+        trade::{AssetFees, Trade, TradeId},
+    };
+    use toucan_instrument::Side;
+    use toucan_integration::snapshot::Snapshot;
+
+    fn time(micros: i64) -> DateTime<Utc> {
+        DateTime::<Utc>::MIN_UTC + TimeDelta::microseconds(micros)
+    }
+
+    #[test]
+    fn test_latency_histogram_percentile() {
+        struct TestCase {
+            values: Vec<u64>,
+            percentile: f64,
+            expected: Option<u64>,
+        }
+
+        let cases = vec![
+            // TC0: empty histogram
+            TestCase {
+                values: vec![],
+                percentile: 50.0,
+                expected: None,
+            },
+            // TC1: values below the linear sub-buckets are exact
+            TestCase {
+                values: (1..=100).collect(),
+                percentile: 50.0,
+                expected: Some(50),
+            },
+            // TC2: p99 of 1..=100
+            TestCase {
+                values: (1..=100).collect(),
+                percentile: 99.0,
+                expected: Some(99),
+            },
+            // TC3: larger values report the highest equivalent value of their bucket
+            TestCase {
+                values: vec![1000, 1000, 5000],
+                percentile: 50.0,
+                expected: Some(1007),
+            },
+            // TC4: percentile clamped to the maximum recorded value
+            TestCase {
+                values: vec![1000, 1000, 5000],
+                percentile: 100.0,
+                expected: Some(5000),
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let mut histogram = LatencyHistogram::default();
+            test.values
+                .into_iter()
+                .for_each(|value| histogram.record(value));
+
+            let actual = histogram.percentile(test.percentile);
+            assert_eq!(actual, test.expected, "TC{index} failed");
+        }
+    }
+
+    #[test]
+    fn test_bucket_index_relative_error() {
+        for value in [
+            0,
+            127,
+            128,
+            255,
+            256,
+            1_000,
+            65_535,
+            1_000_000,
+            u64::MAX / 2,
+        ] {
+            let high = highest_equivalent(bucket_index(value));
+            assert!(high >= value, "value {value} above bucket high {high}");
+            assert!(
+                (high - value) as f64 <= value as f64 / 64.0,
+                "value {value} relative error too large: {high}"
+            );
+        }
+    }
+
+    fn open_snapshot(filled: rust_decimal::Decimal, trace: OrderTrace) -> AccountEvent {
+        AccountEvent::new(
+            "mock".to_string(),
+            Snapshot(Order {
+                key: OrderKey {
+                    exchange: "mock".to_string(),
+                    instrument: "petr4".to_string(),
+                    strategy: StrategyId::new("momentum"),
+                    cid: ClientOrderId::new("cid"),
+                },
+                side: Side::Buy,
+                price: dec!(10),
+                quantity: dec!(1),
+                kind: OrderKind::Limit,
+                time_in_force: TimeInForce::ImmediateOrCancel,
+                state: OrderState::active(Open::new(OrderId::new("order"), time(0), filled)),
+            }),
+        )
+        .with_trace(Some(trace))
+    }
+
+    #[test]
+    fn test_latency_tracker_update_from_account() {
+        let trace = OrderTrace {
+            sequence: 0,
+            time_exchange: Some(time(0)),
+            time_received: Some(time(100)),
+            time_engine: time(110),
+            time_risk: Some(time(120)),
+            time_sent: Some(time(130)),
+            time_ack: Some(time(630)),
+            time_fill: None,
+        };
+
+        let mut tracker = LatencyTracker::default();
+
+        // Acknowledgement records stages up to the ack, and awaits the first fill
+        let ack = tracker
+            .update_from_account(&open_snapshot(dec!(0), trace), time(700))
+            .unwrap();
+        assert_eq!(ack.trace, trace);
+        assert_eq!(tracker.awaiting_fill.len(), 1);
+
+        let exchange = &tracker.exchanges["mock"];
+        assert_eq!(
+            exchange[&LatencyStage::TickToTrade].percentile(50.0),
+            Some(30)
+        );
+        assert_eq!(
+            exchange[&LatencyStage::SentToAck].percentile(50.0),
+            Some(500)
+        );
+        assert!(!exchange.contains_key(&LatencyStage::TickToFill));
+
+        // Trade for an unknown order is ignored
+        let trade = |order_id: &str| {
+            AccountEvent::new(
+                "mock".to_string(),
+                Trade {
+                    id: TradeId::new("trade"),
+                    order_id: OrderId::new(order_id),
+                    instrument: "petr4".to_string(),
+                    strategy: StrategyId::new("momentum"),
+                    time_exchange: time(0),
+                    side: Side::Buy,
+                    price: dec!(10),
+                    quantity: dec!(1),
+                    fees: AssetFees::default(),
+                },
+            )
+        };
+        assert_eq!(
+            tracker.update_from_account(&trade("other"), time(900)),
+            None
+        );
+
+        // First fill records the fill stages
+        let fill = tracker
+            .update_from_account(&trade("order"), time(1100))
+            .unwrap();
+        assert_eq!(fill.trace.time_fill, Some(time(1100)));
+        assert!(tracker.awaiting_fill.is_empty());
+
+        let strategy = &tracker.strategies[&StrategyId::new("momentum")];
+        assert_eq!(
+            strategy[&LatencyStage::TickToFill].percentile(50.0),
+            Some(1000)
+        );
+        assert_eq!(
+            strategy[&LatencyStage::AckToFill].percentile(50.0),
+            Some(470)
+        );
+
+        let summary = tracker.summary();
+        assert_eq!(
+            summary.exchanges["mock"][&LatencyStage::TickToFill].count,
+            1
+        );
+    }
+
+    #[test]
+    fn test_latency_tracker_evicts_oldest_awaiting_fill() {
+        let mut tracker = LatencyTracker::new(1);
+        let trace = OrderTrace::new(0, time(0));
+
+        let mut first = open_snapshot(dec!(0), trace);
+        if let AccountEventKind::OrderSnapshot(Snapshot(order)) = &mut first.kind {
+            order.state = OrderState::active(Open::new(OrderId::new("first"), time(0), dec!(0)));
+        }
+        tracker.update_from_account(&first, time(1));
+        tracker.update_from_account(&open_snapshot(dec!(0), trace), time(2));
+
+        assert_eq!(
+            tracker.awaiting_fill.keys().collect::<Vec<_>>(),
+            vec![&OrderId::new("order")]
+        );
+    }
+}
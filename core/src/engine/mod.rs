@@ -127,15 +127,23 @@ use crate::{
         clock::EngineClock,
         command::Command,
         execution_tx::ExecutionTxMap,
+        kill_switch::{KillSwitch, KillSwitchTripped},
+        latency::{LatencySummary, OrderLatency},
+        reaction::{ReactionOutput, ReactionTrigger},
+        session::SessionTransition,
         state::{
-            instrument::data::InstrumentDataState,
-            order::in_flight_recorder::InFlightRequestRecorder, position::PositionExited,
-            trading::TradingState, EngineState,
+            connectivity::stale::FeedStale,
+            instrument::{data::InstrumentDataState, filter::InstrumentFilter},
+            order::in_flight_recorder::InFlightRequestRecorder,
+            position::PositionExited,
+            trading::{TradingState, TradingStateUpdateAudit},
+            EngineState,
         },
+        timer::TimerFired,
     },
     execution::{request::ExecutionRequest, AccountStreamEvent},
     risk::RiskManager,
-    shutdown::SyncShutdown,
+    shutdown::{GracefulShutdown, ShutdownProgress, ShutdownSequence, SyncShutdown},
     EngineEvent, Sequence,
 };
 use chrono::{DateTime, Utc};
@@ -145,8 +153,10 @@ use std::fmt::Debug;
 use tracing::info;
 use toucan_analytics::summary::TradingSummaryGenerator;
 use toucan_data::{event::MarketEvent, streams::consumer::MarketStreamEvent};
-use toucan_execution::{AccountEvent, ExchangeIndex, InstrumentIndex, QuoteAsset};
-use toucan_integration::channel::Tx;
+use toucan_execution::{
+    trace::OrderTrace, AccountEvent, ExchangeIndex, InstrumentIndex, QuoteAsset,
+};
+use toucan_integration::{channel::Tx, Terminal};
 use toucan_trader::{
     AlgoStrategy, ClosePositionsStrategy, OnDisconnectStrategy, OnTradingDisabled,
 };
@@ -220,6 +230,35 @@ pub mod execution_tx;
 /// eg/ `ConnectivityStates`, `AssetStates`, `InstrumentStates`, `Position`, etc.
 pub mod state;
 
+/// Defines the automatic [`KillSwitch`](kill_switch::KillSwitch) circuit breaker that disables
+/// trading when loss, drawdown, losing streak or order rejection limits are breached.
+///
+/// Limites configuráveis globalmente, por instrumento e por estratégia.
+pub mod kill_switch;
+
+/// Defines the tick-to-trade [`LatencyTracker`](latency::LatencyTracker), recording HDR-style
+/// latency histograms per exchange and per strategy from the
+/// [`OrderTrace`](toucan_execution::trace::OrderTrace) propagated with every order.
+///
+/// Estágios: evento de mercado → engine → risco → envio → ack da bolsa → execução.
+pub mod latency;
+
+/// Defines ready-made [`Reaction`](reaction::Reaction)s (cancel orders, close positions with IOC
+/// market orders, pause new orders, no-op) run on the `OnDisconnectStrategy` and
+/// `OnTradingDisabled` hooks.
+///
+/// Cada reação executada gera um [`ReactionOutput`](reaction::ReactionOutput) na auditoria.
+pub mod reaction;
+
+/// Defines the configurable pre-trade [`LimitsRiskManager`](risk::LimitsRiskManager) enforcing
+/// order quantity, notional, position, price collar, allowed instrument, open order,
+/// message-rate and order-to-trade ratio limits, plus self-match prevention.
+///
+/// Cada recusa carrega um motivo tipado [`RiskLimitBreach`](risk::RiskLimitBreach), convertido
+/// num [`RiskRefusalReason`](toucan_risk::RiskRefusalReason) estruturado e agregado nas
+/// estatísticas de recusa via [`RiskRefusalRecorder`](risk::RiskRefusalRecorder).
+pub mod risk;
+
 /// `Engine` runners for processing input `Events`.
 ///
 /// Diferentes modos de execução:
@@ -230,6 +269,20 @@ pub mod state;
 /// eg/ `fn sync_run`, `fn sync_run_with_audit`, `fn async_run`, `fn async_run_with_audit`,
 pub mod run;
 
+/// Defines the trading [`SessionControl`](session::SessionControl) that gates the `Engine`
+/// [`TradingState`] using a venue trading calendar (eg/ B3 pre-opening, continuous trading,
+/// closing call).
+pub mod session;
+
+/// Defines one-shot and recurring `Engine` [`Timer`](timer::Timer)s, fired from the
+/// [`EngineClock`] time so back-tests remain deterministic.
+///
+/// Casos de uso:
+/// - **Stale orders**: Cancelar ordens antigas periodicamente (eg/ "every 1s")
+/// - **End-of-day**: Fechar posições em horário fixo (eg/ "at 17:50 BRT")
+/// - **Rebalancing**: Rebalanceamento periódico da estratégia
+pub mod timer;
+
 /// Defines how a component processing an input Event and generates an appropriate Audit.
 ///
 /// Este trait é fundamental para o sistema de auditoria, permitindo que qualquer
@@ -316,6 +369,7 @@ pub struct Engine<Clock, State, ExecutionTxs, Strategy, Risk> {
 /// # Fields
 /// - `time_start`: Timestamp de início da sessão atual
 /// - `sequence`: Contador monotônico de eventos processados
+/// - `trace`: [`OrderTrace`] do evento em processamento, propagado com as ordens geradas
 ///
 /// # Usage
 /// Os metadados são automaticamente mantidos pelo engine e utilizados para:
@@ -328,6 +382,10 @@ pub struct EngineMeta {
     pub time_start: DateTime<Utc>,
     /// Monotonically increasing [`Sequence`] associated with the number of events processed.
     pub sequence: Sequence,
+    /// [`OrderTrace`] of the event currently being processed, attached to any order requests
+    /// it generates.
+    #[serde(default)]
+    pub trace: OrderTrace,
 }
 
 impl<Clock, GlobalData, InstrumentData, ExecutionTxs, Strategy, Risk>
@@ -352,9 +410,25 @@ where
 
     fn process(&mut self, event: EngineEvent<InstrumentData::MarketEventKind>) -> Self::Audit {
         self.clock.process(&event);
+        self.meta.trace = self.trace(&event);
+        self.state.order_flow.update_time(self.clock.time());
+        if let Some(kill_switch) = &mut self.state.kill_switch {
+            kill_switch.update_time(self.clock.time());
+        }
+
+        let trading_replayed = replayed_trading_state(&event, self.state.trading);
 
+        let mut actioned = Vec::new();
         let process_audit = match &event {
             EngineEvent::Shutdown(_) => return EngineAudit::process(event),
+            EngineEvent::GracefulShutdown(shutdown) => match self.begin_shutdown(*shutdown) {
+                Some(output) => {
+                    actioned.extend(output.actions);
+                    ProcessAudit::with_trading_state_update(event, output.trading_disabled)
+                        .add_output(output.started)
+                }
+                None => ProcessAudit::with_event(event),
+            },
             EngineEvent::Command(command) => {
                 let output = self.action(command);
 
@@ -368,9 +442,16 @@ where
                 let trading_disabled = self.update_from_trading_state_update(*trading_state);
                 ProcessAudit::with_trading_state_update(event, trading_disabled)
             }
+            EngineEvent::Timer(_) => ProcessAudit::with_event(event),
             EngineEvent::Account(account) => {
                 let output = self.update_from_account_stream(account);
-                ProcessAudit::with_account_update(event, output)
+                let latency = self.update_latency_from_account_stream(account);
+
+                let audit = ProcessAudit::with_account_update(event, output);
+                match latency {
+                    Some(latency) => audit.add_output(latency),
+                    None => audit,
+                }
             }
             EngineEvent::Market(market) => {
                 let output = self.update_from_market_stream(market);
@@ -378,6 +459,80 @@ where
             }
         };
 
+        let mut process_audit = process_audit;
+
+        if let Some(stale_feeds) = self.update_from_stale_feeds() {
+            for stale in stale_feeds.stale {
+                process_audit = process_audit.add_output(stale);
+            }
+            for on_disconnect in stale_feeds.market_disconnect {
+                process_audit =
+                    process_audit.add_output(EngineOutput::MarketDisconnect(on_disconnect));
+            }
+            for on_disconnect in stale_feeds.account_disconnect {
+                process_audit =
+                    process_audit.add_output(EngineOutput::AccountDisconnect(on_disconnect));
+            }
+            if let Some(trading_disabled) = stale_feeds.trading_disabled {
+                process_audit =
+                    process_audit.add_output(EngineOutput::OnTradingDisabled(trading_disabled));
+            }
+        }
+
+        if let Some(kill_switch) = self.update_from_kill_switch() {
+            process_audit = process_audit.add_output(kill_switch.tripped);
+            if let Some(trading_disabled) = kill_switch.trading_disabled {
+                process_audit =
+                    process_audit.add_output(EngineOutput::OnTradingDisabled(trading_disabled));
+            }
+            actioned.extend(kill_switch.on_trip);
+        }
+
+        if let Some(session) = self.update_from_session() {
+            if let Some(transition) = session.transition {
+                process_audit = process_audit.add_output(transition);
+            }
+            if let Some(trading_disabled) = session.trading_disabled {
+                process_audit =
+                    process_audit.add_output(EngineOutput::OnTradingDisabled(trading_disabled));
+            }
+            actioned.extend(session.end_of_session);
+        }
+
+        for (fired, output) in self.fire_timers() {
+            process_audit = process_audit.add_output(fired);
+            actioned.extend(output);
+        }
+
+        let reactions = self.update_from_reactions();
+
+        let mut unrecoverable = Vec::new();
+        for output in actioned {
+            if let Some(errors) = output.unrecoverable_errors() {
+                unrecoverable.extend(errors);
+            }
+            process_audit = process_audit.add_output(output);
+        }
+        for reaction in reactions {
+            if let Some(errors) = reaction.unrecoverable_errors() {
+                unrecoverable.extend(errors);
+            }
+            process_audit = process_audit.add_output(reaction);
+        }
+
+        if let Some(progress) = self.update_from_shutdown() {
+            process_audit = process_audit.add_output(progress);
+        }
+
+        process_audit = process_audit.add_trading_state_update(TradingStateUpdateAudit {
+            prev: trading_replayed,
+            current: self.state.trading,
+        });
+
+        if !unrecoverable.is_empty() {
+            return EngineAudit::with_process_and_err(process_audit, unrecoverable);
+        }
+
         if let TradingState::Enabled = self.state.trading {
             let output = self.generate_algo_orders();
 
@@ -430,6 +585,7 @@ impl<Clock, GlobalData, InstrumentData, ExecutionTxs, Strategy, Risk>
     /// Todas as ações são logadas em nível INFO com detalhes dos parâmetros
     pub fn action(&mut self, command: &Command) -> ActionOutput
     where
+        Clock: EngineClock,
         InstrumentData: InFlightRequestRecorder,
         ExecutionTxs: ExecutionTxMap<ExchangeIndex, InstrumentIndex>,
         Strategy: ClosePositionsStrategy<State = EngineState<GlobalData, InstrumentData>>,
@@ -462,6 +618,325 @@ impl<Clock, GlobalData, InstrumentData, ExecutionTxs, Strategy, Risk>
         }
     }
 
+    /// Update the `Engine` [`TradingState`] from the trading [`SessionControl`] (if configured),
+    /// actioning any end-of-session [`Command`]s.
+    ///
+    /// Avalia a fase do pregão no horário atual do [`EngineClock`]:
+    /// - Fora das fases permitidas: `TradingState::Disabled` (aciona `OnTradingDisabled`)
+    /// - Ao sair das fases permitidas: executa os comandos de fim de sessão
+    /// - Ao retornar a uma fase permitida: reabilita o trading desabilitado pela sessão
+    ///
+    /// Returns `None` if no `SessionControl` is configured, or no session action was required.
+    pub fn update_from_session(
+        &mut self,
+    ) -> Option<UpdateFromSessionOutput<Strategy::OnTradingDisabled>>
+    where
+        Clock: EngineClock,
+        InstrumentData: InFlightRequestRecorder,
+        ExecutionTxs: ExecutionTxMap<ExchangeIndex, InstrumentIndex>,
+        Strategy: OnTradingDisabled<Clock, EngineState<GlobalData, InstrumentData>, ExecutionTxs, Risk>
+            + ClosePositionsStrategy<State = EngineState<GlobalData, InstrumentData>>,
+        Risk: RiskManager,
+    {
+        let now = self.clock.time();
+        let trading = self.state.trading;
+        let trading_halted = self
+            .state
+            .kill_switch
+            .as_ref()
+            .is_some_and(KillSwitch::is_tripped)
+            || self
+                .state
+                .stale_feeds
+                .as_ref()
+                .is_some_and(|monitor| monitor.halted)
+            || self.state.shutdown.is_some();
+        let session = self.state.session.as_mut()?;
+
+        let update = session.update(now, trading);
+        let end_of_session = if update.end_of_session {
+            session.end_of_session.clone()
+        } else {
+            vec![]
+        };
+
+        // Trading halted by a tripped KillSwitch, halted StaleFeedMonitor or graceful shutdown is
+        // not re-enabled by the session
+        let trading_disabled = update
+            .trading_state
+            .filter(|state| !(trading_halted && *state == TradingState::Enabled))
+            .and_then(|trading_state| self.transition_trading_state(trading_state));
+
+        let end_of_session = end_of_session
+            .iter()
+            .map(|command| self.action(command))
+            .collect::<Vec<_>>();
+
+        let output = UpdateFromSessionOutput {
+            transition: update.transition,
+            trading_disabled,
+            end_of_session,
+        };
+
+        (!output.is_empty()).then_some(output)
+    }
+
+    /// Evaluate the [`KillSwitch`] limits (if configured), disabling trading and actioning the
+    /// configured `on_trip` [`Command`]s if a limit is breached.
+    ///
+    /// Avalia PnL realizado + não realizado, drawdown intradiário, perdas consecutivas e taxa
+    /// de rejeição de ordens contra os limites globais, por instrumento e por estratégia.
+    ///
+    /// Returns `None` if no `KillSwitch` is configured, or it did not trip.
+    pub fn update_from_kill_switch(
+        &mut self,
+    ) -> Option<UpdateFromKillSwitchOutput<Strategy::OnTradingDisabled>>
+    where
+        Clock: EngineClock,
+        InstrumentData: InFlightRequestRecorder,
+        ExecutionTxs: ExecutionTxMap<ExchangeIndex, InstrumentIndex>,
+        Strategy: OnTradingDisabled<Clock, EngineState<GlobalData, InstrumentData>, ExecutionTxs, Risk>
+            + ClosePositionsStrategy<State = EngineState<GlobalData, InstrumentData>>,
+        Risk: RiskManager,
+    {
+        let now = self.clock.time();
+        let kill_switch = self.state.kill_switch.as_mut()?;
+
+        let pnl_open = self.state.instruments.0.values().map(|state| {
+            let pnl = state
+                .position
+                .current
+                .as_ref()
+                .map_or(Decimal::ZERO, |position| {
+                    position.pnl_realised + position.pnl_unrealised
+                });
+            (&state.key, pnl)
+        });
+
+        let tripped = kill_switch.check(now, pnl_open)?;
+        let on_trip = kill_switch.on_trip.clone();
+
+        let trading_disabled = self.transition_trading_state(TradingState::Disabled);
+
+        let on_trip = on_trip.iter().map(|command| self.action(command)).collect();
+
+        Some(UpdateFromKillSwitchOutput {
+            tripped,
+            trading_disabled,
+            on_trip,
+        })
+    }
+
+    /// Begin a [`GracefulShutdown`] sequence: disable trading, cancel all open orders and, if
+    /// configured, close all open positions with market orders.
+    ///
+    /// A sequência é então acompanhada a cada evento por [`Self::update_from_shutdown`] até que
+    /// não restem ordens (e posições) abertas, ou o timeout seja atingido.
+    ///
+    /// Returns `None` if a shutdown sequence has already been started.
+    pub fn begin_shutdown(
+        &mut self,
+        config: GracefulShutdown,
+    ) -> Option<UpdateFromShutdownOutput<Strategy::OnTradingDisabled>>
+    where
+        Clock: EngineClock,
+        InstrumentData: InFlightRequestRecorder,
+        ExecutionTxs: ExecutionTxMap<ExchangeIndex, InstrumentIndex>,
+        Strategy: OnTradingDisabled<Clock, EngineState<GlobalData, InstrumentData>, ExecutionTxs, Risk>
+            + ClosePositionsStrategy<State = EngineState<GlobalData, InstrumentData>>,
+        Risk: RiskManager,
+    {
+        if self.state.shutdown.is_some() {
+            return None;
+        }
+
+        let trading_disabled = self.transition_trading_state(TradingState::Disabled);
+
+        let mut actions = vec![self.action(&Command::CancelOrders(InstrumentFilter::None))];
+        if config.close_positions {
+            actions.push(self.action(&Command::ClosePositions(InstrumentFilter::None)));
+        }
+
+        let sequence = ShutdownSequence::start(config, self.clock.time());
+        let started = ShutdownProgress::Started {
+            deadline: sequence.deadline,
+        };
+        self.state.shutdown = Some(sequence);
+
+        Some(UpdateFromShutdownOutput {
+            started,
+            trading_disabled,
+            actions,
+        })
+    }
+
+    /// Check the progress of an in-flight [`GracefulShutdown`] sequence against the remaining
+    /// open orders and positions.
+    ///
+    /// Returns `None` if no shutdown sequence is in progress, or it is still draining.
+    pub fn update_from_shutdown(&mut self) -> Option<ShutdownProgress>
+    where
+        Clock: EngineClock,
+    {
+        let now = self.clock.time();
+        let sequence = self.state.shutdown.as_mut()?;
+
+        let (orders, positions) =
+            self.state
+                .instruments
+                .0
+                .values()
+                .fold((0, 0), |(orders, positions), state| {
+                    (
+                        orders + state.orders.0.len(),
+                        positions + usize::from(state.position.current.is_some()),
+                    )
+                });
+
+        sequence.update(now, orders, positions)
+    }
+
+    /// Evaluate the [`StaleFeedMonitor`](state::connectivity::stale::StaleFeedMonitor) (if configured) against the current [`EngineClock`]
+    /// time, marking feeds that stopped producing events as
+    /// [`Health::Stale`](state::connectivity::Health::Stale) and calling the configured
+    /// [`OnDisconnectStrategy`] for each affected exchange.
+    ///
+    /// Se o limite `halt_after` de falhas for atingido, o trading é desabilitado.
+    ///
+    /// Returns `None` if no `StaleFeedMonitor` is configured, or no feed became stale.
+    pub fn update_from_stale_feeds(
+        &mut self,
+    ) -> Option<UpdateFromStaleFeedsOutput<Strategy::OnTradingDisabled, Strategy::OnDisconnect>>
+    where
+        Clock: EngineClock,
+        Strategy: OnTradingDisabled<Clock, EngineState<GlobalData, InstrumentData>, ExecutionTxs, Risk>
+            + OnDisconnectStrategy<Clock, EngineState<GlobalData, InstrumentData>, ExecutionTxs, Risk>,
+    {
+        let now = self.clock.time();
+        let instruments = &self.state.instruments;
+        let check = self.state.stale_feeds.as_mut()?.check(
+            now,
+            self.state.connectivity.exchange_ids(),
+            |instrument| instruments.instrument_index(instrument).instrument.exchange,
+        );
+
+        if check.stale.is_empty() && !check.halted {
+            return None;
+        }
+
+        let mut market_disconnected = Vec::new();
+        let mut account_disconnected = Vec::new();
+        for stale in &check.stale {
+            let exchange = stale.feed.exchange();
+            self.queue_reaction(ReactionTrigger::Disconnect(exchange));
+
+            if stale.feed.is_market_data() {
+                self.state.connectivity.update_from_market_stale(&exchange);
+                if !market_disconnected.contains(&exchange) {
+                    market_disconnected.push(exchange);
+                }
+            } else {
+                self.state.connectivity.update_from_account_stale(&exchange);
+                if !account_disconnected.contains(&exchange) {
+                    account_disconnected.push(exchange);
+                }
+            }
+        }
+
+        let trading_disabled = if check.halted {
+            self.transition_trading_state(TradingState::Disabled)
+        } else {
+            None
+        };
+
+        Some(UpdateFromStaleFeedsOutput {
+            stale: check.stale,
+            market_disconnect: market_disconnected
+                .into_iter()
+                .map(Strategy::on_disconnect)
+                .collect(),
+            account_disconnect: account_disconnected
+                .into_iter()
+                .map(Strategy::on_disconnect)
+                .collect(),
+            trading_disabled,
+        })
+    }
+
+    /// Fire all `Engine` [`Timer`](timer::Timer)s that are due at the current [`EngineClock`]
+    /// time, actioning any associated [`Command`]s.
+    ///
+    /// Cada timer disparado fica visível para a estratégia via `EngineState::timers` durante
+    /// `generate_algo_orders`.
+    ///
+    /// Returns each [`TimerFired`] alongside the [`ActionOutput`] of its `Command` (if any).
+    pub fn fire_timers(&mut self) -> Vec<(TimerFired, Option<ActionOutput>)>
+    where
+        Clock: EngineClock,
+        InstrumentData: InFlightRequestRecorder,
+        ExecutionTxs: ExecutionTxMap<ExchangeIndex, InstrumentIndex>,
+        Strategy: ClosePositionsStrategy<State = EngineState<GlobalData, InstrumentData>>,
+        Risk: RiskManager,
+    {
+        let now = self.clock.time();
+
+        self.state
+            .timers
+            .fire_due(now)
+            .into_iter()
+            .map(|(fired, command)| {
+                let output = command.map(|command| self.action(&command));
+                (fired, output)
+            })
+            .collect()
+    }
+
+    /// Construct the [`OrderTrace`] of an input event, attaching the market event exchange &
+    /// received times if the event is a `MarketEvent`.
+    ///
+    /// O trace é anexado a todas as ordens geradas durante o processamento do evento.
+    fn trace<MarketKind>(&self, event: &EngineEvent<MarketKind>) -> OrderTrace
+    where
+        Clock: EngineClock,
+    {
+        let trace = OrderTrace::new(self.meta.sequence.value(), self.clock.time());
+
+        match event {
+            EngineEvent::Market(MarketStreamEvent::Item(market)) => {
+                trace.with_market_times(market.time_exchange, market.time_received)
+            }
+            _ => trace,
+        }
+    }
+
+    /// Update the [`LatencyTracker`](latency::LatencyTracker) (if configured) from an
+    /// [`AccountStreamEvent`], returning the [`OrderLatency`] of any acknowledged or filled order.
+    pub fn update_latency_from_account_stream(
+        &mut self,
+        event: &AccountStreamEvent,
+    ) -> Option<OrderLatency>
+    where
+        Clock: EngineClock,
+    {
+        let AccountStreamEvent::Item(event) = event else {
+            return None;
+        };
+
+        let time = self.clock.time();
+        self.state
+            .latency
+            .as_mut()?
+            .update_from_account(event, time)
+    }
+
+    /// Generate a [`LatencySummary`] of the tick-to-trade latencies recorded by the
+    /// [`LatencyTracker`](latency::LatencyTracker), if configured.
+    ///
+    /// Útil para imprimir um resumo de latência no shutdown do sistema.
+    pub fn latency_summary(&self) -> Option<LatencySummary> {
+        self.state.latency.as_ref().map(|latency| latency.summary())
+    }
+
     /// Update the `Engine` [`TradingState`].
     ///
     /// Atualiza o estado de trading do engine. Quando há transição para
@@ -479,6 +954,13 @@ impl<Clock, GlobalData, InstrumentData, ExecutionTxs, Strategy, Risk>
     ///
     /// If the `TradingState` transitions to `TradingState::Disabled`, the `Engine` will call
     /// the configured [`OnTradingDisabled`] strategy logic.
+    ///
+    /// A `TradingState::Enabled` update re-arms a tripped [`KillSwitch`].
+    ///
+    /// The update is an external (eg/ manual) override of the [`SessionControl`] gate, so trading
+    /// disabled by this update is not re-enabled at the next allowed session phase.
+    ///
+    /// [`SessionControl`]: session::SessionControl
     pub fn update_from_trading_state_update(
         &mut self,
         update: TradingState,
@@ -487,11 +969,45 @@ impl<Clock, GlobalData, InstrumentData, ExecutionTxs, Strategy, Risk>
         Strategy:
             OnTradingDisabled<Clock, EngineState<GlobalData, InstrumentData>, ExecutionTxs, Risk>,
     {
-        self.state
-            .trading
-            .update(update)
-            .transitioned_to_disabled()
-            .then(|| Strategy::on_trading_disabled())
+        if let Some(session) = &mut self.state.session {
+            session.disabled_by_session = false;
+        }
+        if let (TradingState::Enabled, Some(kill_switch)) = (update, &mut self.state.kill_switch) {
+            kill_switch.rearm();
+        }
+        if let (TradingState::Enabled, Some(monitor)) = (update, &mut self.state.stale_feeds) {
+            monitor.rearm();
+        }
+
+        self.transition_trading_state(update)
+    }
+
+    /// Transition the `Engine` [`TradingState`] on behalf of an `Engine` component (eg/ the
+    /// session gate, a tripped [`KillSwitch`]), calling the configured [`OnTradingDisabled`]
+    /// strategy logic if trading transitions to `TradingState::Disabled`.
+    fn transition_trading_state(
+        &mut self,
+        update: TradingState,
+    ) -> Option<Strategy::OnTradingDisabled>
+    where
+        Strategy:
+            OnTradingDisabled<Clock, EngineState<GlobalData, InstrumentData>, ExecutionTxs, Risk>,
+    {
+        let transitioned_to_disabled = self.state.trading.update(update).transitioned_to_disabled();
+        if transitioned_to_disabled {
+            self.queue_reaction(ReactionTrigger::TradingDisabled);
+        }
+
+        transitioned_to_disabled.then(|| Strategy::on_trading_disabled())
+    }
+
+    /// Queue the configured [`Reactions`](reaction::Reactions) (if any) of an
+    /// `OnDisconnectStrategy` or `OnTradingDisabled` hook call, run once the current event has
+    /// been processed.
+    fn queue_reaction(&mut self, trigger: ReactionTrigger) {
+        if let Some(reactions) = &mut self.state.reactions {
+            reactions.queue(trigger);
+        }
     }
 
     /// Update the [`Engine`] from an [`AccountStreamEvent`].
@@ -519,6 +1035,7 @@ impl<Clock, GlobalData, InstrumentData, ExecutionTxs, Strategy, Risk>
         event: &AccountStreamEvent,
     ) -> UpdateFromAccountOutput<Strategy::OnDisconnect>
     where
+        Clock: EngineClock,
         InstrumentData: for<'a> Processor<&'a AccountEvent>,
         GlobalData: for<'a> Processor<&'a AccountEvent>,
         Strategy: OnDisconnectStrategy<
@@ -533,14 +1050,24 @@ impl<Clock, GlobalData, InstrumentData, ExecutionTxs, Strategy, Risk>
                 self.state
                     .connectivity
                     .update_from_account_reconnecting(exchange);
+                self.queue_reaction(ReactionTrigger::Disconnect(*exchange));
 
                 UpdateFromAccountOutput::OnDisconnect(Strategy::on_disconnect(*exchange))
             }
-            AccountStreamEvent::Item(event) => self
-                .state
-                .update_from_account(event)
-                .map(UpdateFromAccountOutput::PositionExit)
-                .unwrap_or(UpdateFromAccountOutput::None),
+            AccountStreamEvent::Item(event) => {
+                if let Some(monitor) = &mut self.state.stale_feeds {
+                    monitor.record_account(
+                        &event.exchange,
+                        self.state.connectivity.exchange_ids(),
+                        self.clock.time(),
+                    );
+                }
+
+                self.state
+                    .update_from_account(event)
+                    .map(UpdateFromAccountOutput::PositionExit)
+                    .unwrap_or(UpdateFromAccountOutput::None)
+            }
         }
     }
 
@@ -573,6 +1100,7 @@ impl<Clock, GlobalData, InstrumentData, ExecutionTxs, Strategy, Risk>
         event: &MarketStreamEvent<InstrumentIndex, InstrumentData::MarketEventKind>,
     ) -> UpdateFromMarketOutput<Strategy::OnDisconnect>
     where
+        Clock: EngineClock,
         InstrumentData: InstrumentDataState,
         GlobalData:
             for<'a> Processor<&'a MarketEvent<InstrumentIndex, InstrumentData::MarketEventKind>>,
@@ -588,10 +1116,15 @@ impl<Clock, GlobalData, InstrumentData, ExecutionTxs, Strategy, Risk>
                 self.state
                     .connectivity
                     .update_from_market_reconnecting(exchange);
+                self.queue_reaction(ReactionTrigger::Disconnect(*exchange));
 
                 UpdateFromMarketOutput::OnDisconnect(Strategy::on_disconnect(*exchange))
             }
             MarketStreamEvent::Item(event) => {
+                if let Some(monitor) = &mut self.state.stale_feeds {
+                    monitor.record_market(event.exchange, &event.instrument, self.clock.time());
+                }
+
                 self.state.update_from_market(event);
                 UpdateFromMarketOutput::None
             }
@@ -609,6 +1142,8 @@ impl<Clock, GlobalData, InstrumentData, ExecutionTxs, Strategy, Risk>
     /// - **Time Window**: Período da sessão atual (start → current time)
     /// - **Instruments**: Instrumentos ativos na sessão
     /// - **Assets**: Balanços de ativos disponíveis
+    /// - **Calendar**: Calendário do [`SessionControl`](session::SessionControl), se configurado,
+    ///   usado para medir o período em dias úteis
     ///
     /// # Usage
     /// ```rust,ignore
@@ -672,7 +1207,14 @@ impl<Clock, GlobalData, InstrumentData, ExecutionTxs, Strategy, Risk>
             );
         }
 
-        gen
+        // Inject risk refusal statistics
+        gen.risk_refusals = self.state.risk_refusals.clone();
+
+        // Measure the trading period with the calendar of the session gate (if any)
+        match &self.state.session {
+            Some(session) => gen.with_calendar(session.calendar.clone()),
+            None => gen,
+        }
     }
 }
 
@@ -723,6 +1265,7 @@ where
             meta: EngineMeta {
                 time_start: clock.time(),
                 sequence: Sequence(0),
+                trace: OrderTrace::default(),
             },
             clock,
             state,
@@ -741,6 +1284,19 @@ where
     pub fn reset_metadata(&mut self) {
         self.meta.time_start = self.clock.time();
         self.meta.sequence = Sequence(0);
+        self.meta.trace = OrderTrace::default();
+    }
+}
+
+/// [`TradingState`] a [`StateReplicaManager`](audit::state_replica::StateReplicaManager) holds
+/// after replaying the provided input event, given the `TradingState` prior to the event.
+fn replayed_trading_state<MarketEventKind>(
+    event: &EngineEvent<MarketEventKind>,
+    trading: TradingState,
+) -> TradingState {
+    match event {
+        EngineEvent::TradingStateUpdate(update) => *update,
+        _ => trading,
     }
 }
 
@@ -757,6 +1313,16 @@ where
 /// - `PositionExit`: Informações sobre posições fechadas
 /// - `MarketDisconnect`: Output da estratégia de desconexão de mercado
 /// - `AlgoOrders`: Output da geração de ordens algorítmicas
+/// - `TimerFired`: Registro de um [`Timer`](timer::Timer) disparado
+/// - `Session`: Transição de fase do pregão (ver [`SessionControl`](session::SessionControl))
+/// - `KillSwitch`: Registro do [`KillSwitch`] acionado, com a regra violada
+/// - `TradingState`: Transição do [`TradingState`] iniciada pelo engine (eg/ session gate,
+///   [`KillSwitch`], feed parado, [`GracefulShutdown`]), não reproduzível a partir do evento
+/// - `Latency`: Latência tick-to-trade de uma ordem confirmada ou executada
+/// - `FeedStale`: Feed de market data ou conta que parou de produzir eventos
+/// - `Reaction`: [`Reaction`](reaction::Reaction) pronta executada num hook de desconexão ou
+///   trading desabilitado
+/// - `Shutdown`: Progresso da sequência de [`GracefulShutdown`]
 ///
 /// # Type Parameters
 /// - `OnTradingDisabled`: Tipo de output da estratégia de trading disabled
@@ -776,6 +1342,23 @@ pub enum EngineOutput<
     PositionExit(PositionExited<QuoteAsset, InstrumentKey>),
     MarketDisconnect(OnDisconnect),
     AlgoOrders(GenerateAlgoOrdersOutput<ExchangeKey, InstrumentKey>),
+    TimerFired(TimerFired),
+    Session(SessionTransition),
+    KillSwitch(KillSwitchTripped),
+    TradingState(TradingStateUpdateAudit),
+    Latency(OrderLatency),
+    FeedStale(FeedStale),
+    Reaction(ReactionOutput<ExchangeKey, InstrumentKey>),
+    Shutdown(ShutdownProgress),
+}
+
+impl<OnTradingDisabled, OnDisconnect, ExchangeKey, InstrumentKey> Terminal
+    for EngineOutput<OnTradingDisabled, OnDisconnect, ExchangeKey, InstrumentKey>
+{
+    /// An `EngineOutput` is terminal once a graceful shutdown sequence has completed.
+    fn is_terminal(&self) -> bool {
+        matches!(self, Self::Shutdown(progress) if progress.is_complete())
+    }
 }
 
 /// Output produced by the [`Engine`] updating from an [`TradingState`], used to construct
@@ -793,6 +1376,87 @@ pub enum UpdateTradingStateOutput<OnTradingDisabled> {
     OnTradingDisabled(OnTradingDisabled),
 }
 
+/// Output produced by the [`Engine`] updating from its trading
+/// [`SessionControl`](session::SessionControl), used to construct an `Engine` [`EngineAudit`].
+///
+/// # Fields
+/// - `transition`: Transição de fase do pregão, se houve
+/// - `trading_disabled`: Output da estratégia de trading desabilitado, se acionada
+/// - `end_of_session`: Outputs dos comandos de fim de sessão executados
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub struct UpdateFromSessionOutput<
+    OnTradingDisabled,
+    ExchangeKey = ExchangeIndex,
+    InstrumentKey = InstrumentIndex,
+> {
+    pub transition: Option<SessionTransition>,
+    pub trading_disabled: Option<OnTradingDisabled>,
+    pub end_of_session: Vec<ActionOutput<ExchangeKey, InstrumentKey>>,
+}
+
+impl<OnTradingDisabled, ExchangeKey, InstrumentKey>
+    UpdateFromSessionOutput<OnTradingDisabled, ExchangeKey, InstrumentKey>
+{
+    /// Returns `true` if no session action was required.
+    pub fn is_empty(&self) -> bool {
+        self.transition.is_none()
+            && self.trading_disabled.is_none()
+            && self.end_of_session.is_empty()
+    }
+}
+
+/// Output produced by the [`Engine`] evaluating its [`KillSwitch`], used to construct an `Engine`
+/// [`EngineAudit`].
+///
+/// # Fields
+/// - `tripped`: Regra violada, limite e valor observado
+/// - `trading_disabled`: Output da estratégia de trading desabilitado, se acionada
+/// - `on_trip`: Outputs dos comandos executados ao acionar o kill switch
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub struct UpdateFromKillSwitchOutput<
+    OnTradingDisabled,
+    ExchangeKey = ExchangeIndex,
+    InstrumentKey = InstrumentIndex,
+> {
+    pub tripped: KillSwitchTripped,
+    pub trading_disabled: Option<OnTradingDisabled>,
+    pub on_trip: Vec<ActionOutput<ExchangeKey, InstrumentKey>>,
+}
+
+/// Output produced by the [`Engine`] beginning a [`GracefulShutdown`] sequence, used to construct
+/// an `Engine` [`EngineAudit`].
+///
+/// # Fields
+/// - `started`: Início da sequência, com o deadline para drenar ordens e posições
+/// - `trading_disabled`: Output da estratégia de trading desabilitado, se estava habilitado
+/// - `actions`: Outputs do cancelamento de ordens e fechamento de posições
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub struct UpdateFromShutdownOutput<
+    OnTradingDisabled,
+    ExchangeKey = ExchangeIndex,
+    InstrumentKey = InstrumentIndex,
+> {
+    pub started: ShutdownProgress,
+    pub trading_disabled: Option<OnTradingDisabled>,
+    pub actions: Vec<ActionOutput<ExchangeKey, InstrumentKey>>,
+}
+
+/// Output produced by the [`Engine`] evaluating its [`StaleFeedMonitor`](state::connectivity::stale::StaleFeedMonitor), used to construct an
+/// `Engine` [`EngineAudit`].
+///
+/// # Fields
+/// - `stale`: Feeds que ficaram obsoletos, com limite e último evento recebido
+/// - `market_disconnect`: Outputs da estratégia de desconexão por exchange de market data
+/// - `account_disconnect`: Outputs da estratégia de desconexão por exchange de conta
+/// - `trading_disabled`: Output da estratégia de trading desabilitado, se o limite foi atingido
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub struct UpdateFromStaleFeedsOutput<OnTradingDisabled, OnDisconnect> {
+    pub stale: Vec<FeedStale>,
+    pub market_disconnect: Vec<OnDisconnect>,
+    pub account_disconnect: Vec<OnDisconnect>,
+    pub trading_disabled: Option<OnTradingDisabled>,
+}
+
 /// Output produced by the [`Engine`] updating from an [`AccountStreamEvent`], used to construct
 /// an `Engine` [`EngineAudit`].
 ///
@@ -858,3 +1522,448 @@ impl<OnTradingDisabled, OnDisconnect, ExchangeKey, InstrumentKey>
         Self::AlgoOrders(value)
     }
 }
+
+impl<OnTradingDisabled, OnDisconnect, ExchangeKey, InstrumentKey> From<TimerFired>
+    for EngineOutput<OnTradingDisabled, OnDisconnect, ExchangeKey, InstrumentKey>
+{
+    fn from(value: TimerFired) -> Self {
+        Self::TimerFired(value)
+    }
+}
+
+impl<OnTradingDisabled, OnDisconnect, ExchangeKey, InstrumentKey> From<SessionTransition>
+    for EngineOutput<OnTradingDisabled, OnDisconnect, ExchangeKey, InstrumentKey>
+{
+    fn from(value: SessionTransition) -> Self {
+        Self::Session(value)
+    }
+}
+
+impl<OnTradingDisabled, OnDisconnect, ExchangeKey, InstrumentKey> From<KillSwitchTripped>
+    for EngineOutput<OnTradingDisabled, OnDisconnect, ExchangeKey, InstrumentKey>
+{
+    fn from(value: KillSwitchTripped) -> Self {
+        Self::KillSwitch(value)
+    }
+}
+
+impl<OnTradingDisabled, OnDisconnect, ExchangeKey, InstrumentKey> From<TradingStateUpdateAudit>
+    for EngineOutput<OnTradingDisabled, OnDisconnect, ExchangeKey, InstrumentKey>
+{
+    fn from(value: TradingStateUpdateAudit) -> Self {
+        Self::TradingState(value)
+    }
+}
+
+impl<OnTradingDisabled, OnDisconnect, ExchangeKey, InstrumentKey> From<OrderLatency>
+    for EngineOutput<OnTradingDisabled, OnDisconnect, ExchangeKey, InstrumentKey>
+{
+    fn from(value: OrderLatency) -> Self {
+        Self::Latency(value)
+    }
+}
+
+impl<OnTradingDisabled, OnDisconnect, ExchangeKey, InstrumentKey> From<FeedStale>
+    for EngineOutput<OnTradingDisabled, OnDisconnect, ExchangeKey, InstrumentKey>
+{
+    fn from(value: FeedStale) -> Self {
+        Self::FeedStale(value)
+    }
+}
+
+impl<OnTradingDisabled, OnDisconnect, ExchangeKey, InstrumentKey>
+    From<ReactionOutput<ExchangeKey, InstrumentKey>>
+    for EngineOutput<OnTradingDisabled, OnDisconnect, ExchangeKey, InstrumentKey>
+{
+    fn from(value: ReactionOutput<ExchangeKey, InstrumentKey>) -> Self {
+        Self::Reaction(value)
+    }
+}
+
+impl<OnTradingDisabled, OnDisconnect, ExchangeKey, InstrumentKey> From<ShutdownProgress>
+    for EngineOutput<OnTradingDisabled, OnDisconnect, ExchangeKey, InstrumentKey>
+{
+    fn from(value: ShutdownProgress) -> Self {
+        Self::Shutdown(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        engine::{
+            audit::{state_replica::StateReplicaManager, Auditor},
+            clock::HistoricalClock,
+            execution_tx::MultiExchangeTxMap,
+            session::SessionControl,
+            state::{
+                global::DefaultGlobalData,
+                instrument::data::{DefaultInstrumentMarketData, InstrumentDataState},
+                IndexedInstruments,
+            },
+            timer::{Timer, TimerId, TimerSchedule},
+        },
+        test_utils::time_plus_secs,
+    };
+    use chrono::TimeDelta;
+    use toucan_data::{event::DataKind, subscription::trade::PublicTrade};
+    use toucan_execution::order::request::{OrderRequestCancel, OrderRequestOpen};
+    use toucan_instrument::{exchange::ExchangeId, ConcreteInstrument, Keyed, Side};
+    use toucan_markets::calendar::ExchangeCalendar;
+    use toucan_risk::DefaultRiskManager;
+
+    type TestState = EngineState<DefaultGlobalData, DefaultInstrumentMarketData>;
+
+    type TestEngine = Engine<
+        HistoricalClock,
+        TestState,
+        MultiExchangeTxMap,
+        TestStrategy,
+        DefaultRiskManager<TestState>,
+    >;
+
+    type TestAudit = EngineAudit<EngineEvent<DataKind>, EngineOutput<(), ()>>;
+
+    /// Strategy that never generates orders, but keeps a "rebalance" [`Timer`] scheduled whilst
+    /// the instrument price is at most 100, and cancels it otherwise.
+    struct TestStrategy;
+
+    fn rebalance() -> TimerId {
+        TimerId::new("rebalance")
+    }
+
+    impl AlgoStrategy for TestStrategy {
+        type State = TestState;
+
+        fn generate_algo_orders(
+            &self,
+            state: &Self::State,
+        ) -> (
+            impl IntoIterator<Item = OrderRequestCancel>,
+            impl IntoIterator<Item = OrderRequestOpen>,
+        ) {
+            let price = state
+                .instruments
+                .instruments(&InstrumentFilter::None)
+                .find_map(|state| state.data.price());
+
+            match (price, state.timers.timer(&rebalance())) {
+                (Some(price), Some(_)) if price > Decimal::ONE_HUNDRED => {
+                    state.timers.request_cancel(rebalance())
+                }
+                (Some(price), None) if price <= Decimal::ONE_HUNDRED => {
+                    state.timers.request_schedule(Timer::new(
+                        rebalance(),
+                        TimerSchedule::every(TimeDelta::seconds(5)),
+                    ))
+                }
+                _ => {}
+            }
+
+            (std::iter::empty(), std::iter::empty())
+        }
+    }
+
+    impl ClosePositionsStrategy for TestStrategy {
+        type State = TestState;
+
+        fn close_positions_requests<'a>(
+            &'a self,
+            _: &'a Self::State,
+            _: &'a impl Debug,
+        ) -> (
+            impl IntoIterator<Item = OrderRequestCancel> + 'a,
+            impl IntoIterator<Item = OrderRequestOpen> + 'a,
+        )
+        where
+            String: 'a,
+        {
+            (std::iter::empty(), std::iter::empty())
+        }
+    }
+
+    impl
+        OnDisconnectStrategy<
+            HistoricalClock,
+            TestState,
+            MultiExchangeTxMap,
+            DefaultRiskManager<TestState>,
+        > for TestStrategy
+    {
+        type OnDisconnect = ();
+
+        fn on_disconnect(_: ExchangeId) -> Self::OnDisconnect {}
+    }
+
+    impl
+        OnTradingDisabled<
+            HistoricalClock,
+            TestState,
+            MultiExchangeTxMap,
+            DefaultRiskManager<TestState>,
+        > for TestStrategy
+    {
+        type OnTradingDisabled = ();
+
+        fn on_trading_disabled() -> Self::OnTradingDisabled {}
+    }
+
+    fn base() -> DateTime<Utc> {
+        DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap()
+    }
+
+    fn build_engine(time_start: DateTime<Utc>) -> TestEngine {
+        let instruments: IndexedInstruments = vec![Keyed::new(
+            "inst0".to_string(),
+            ConcreteInstrument {
+                symbol: "PETR4".into(),
+                market: "spot".into(),
+                exchange: ExchangeId::Mock,
+                underlying: None,
+                name_exchange: "PETR4".into(),
+            },
+        )];
+
+        let state = EngineState::builder(&instruments, DefaultGlobalData, |_| {
+            DefaultInstrumentMarketData::default()
+        })
+        .time_engine_start(time_start)
+        .trading_state(TradingState::Enabled)
+        .build();
+
+        Engine::new(
+            HistoricalClock::new(time_start),
+            state,
+            MultiExchangeTxMap::from_iter([(ExchangeId::Mock, None)]),
+            TestStrategy,
+            DefaultRiskManager::default(),
+        )
+    }
+
+    fn market_trade(time: DateTime<Utc>, price: f64) -> EngineEvent<DataKind> {
+        EngineEvent::Market(MarketStreamEvent::Item(MarketEvent {
+            time_exchange: time,
+            time_received: time,
+            exchange: ExchangeId::Mock,
+            instrument: "inst0".to_string(),
+            kind: DataKind::Trade(PublicTrade {
+                id: time.to_string(),
+                price,
+                amount: 1.0,
+                side: Side::Buy,
+            }),
+        }))
+    }
+
+    #[test]
+    fn test_engine_process_strategy_timer_requests() {
+        struct TestCase {
+            event: EngineEvent<DataKind>,
+            expected_fired: bool,
+            expected_scheduled: bool,
+        }
+
+        let mut engine = build_engine(base());
+
+        let cases = vec![
+            // TC0: strategy requests the Timer, which is pending until the next event
+            TestCase {
+                event: market_trade(time_plus_secs(base(), 0), 10.0),
+                expected_fired: false,
+                expected_scheduled: false,
+            },
+            // TC1: requested Timer is scheduled and anchored one interval ahead
+            TestCase {
+                event: market_trade(time_plus_secs(base(), 1), 10.0),
+                expected_fired: false,
+                expected_scheduled: true,
+            },
+            // TC2: scheduled Timer fires once due
+            TestCase {
+                event: market_trade(time_plus_secs(base(), 7), 10.0),
+                expected_fired: true,
+                expected_scheduled: true,
+            },
+            // TC3: strategy requests the Timer is cancelled
+            TestCase {
+                event: market_trade(time_plus_secs(base(), 8), 200.0),
+                expected_fired: false,
+                expected_scheduled: true,
+            },
+            // TC4: cancelled Timer no longer fires
+            TestCase {
+                event: market_trade(time_plus_secs(base(), 30), 200.0),
+                expected_fired: false,
+                expected_scheduled: false,
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let audit = engine.process(test.event);
+
+            let fired = match &audit {
+                EngineAudit::Process(process) => process
+                    .outputs
+                    .iter()
+                    .any(|output| matches!(output, EngineOutput::TimerFired(_))),
+                _ => false,
+            };
+            assert_eq!(fired, test.expected_fired, "TC{index} failed");
+            assert_eq!(
+                engine.state.timers.timer(&rebalance()).is_some(),
+                test.expected_scheduled,
+                "TC{index} failed"
+            );
+        }
+    }
+
+    #[test]
+    fn test_engine_process_manual_disable_overrides_session() {
+        struct TestCase {
+            manual_disable: bool,
+            expected: TradingState,
+        }
+
+        let time = |s: &str| s.parse::<DateTime<Utc>>().unwrap();
+
+        let cases = vec![
+            // TC0: trading disabled by the session close is re-enabled at the next open
+            TestCase {
+                manual_disable: false,
+                expected: TradingState::Enabled,
+            },
+            // TC1: manual disable after the session close is not re-enabled at the next open
+            TestCase {
+                manual_disable: true,
+                expected: TradingState::Disabled,
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let mut engine = build_engine(time("2024-03-15T15:00:00Z"));
+            engine.state.session = Some(SessionControl::new(ExchangeCalendar::b3_equities()));
+
+            // Continuous trading
+            engine.process(market_trade(time("2024-03-15T15:00:00Z"), 10.0));
+            assert_eq!(
+                engine.state.trading,
+                TradingState::Enabled,
+                "TC{index} failed"
+            );
+
+            // Closing call disables trading
+            engine.process(market_trade(time("2024-03-15T19:56:00Z"), 10.0));
+            assert_eq!(
+                engine.state.trading,
+                TradingState::Disabled,
+                "TC{index} failed"
+            );
+
+            if test.manual_disable {
+                engine.process(EngineEvent::TradingStateUpdate(TradingState::Disabled));
+            }
+
+            // Continuous trading of the next trading day (Monday)
+            engine.process(market_trade(time("2024-03-18T13:00:00Z"), 10.0));
+            assert_eq!(engine.state.trading, test.expected, "TC{index} failed");
+        }
+    }
+
+    #[test]
+    fn test_engine_process_trading_state_replica() {
+        struct TestCase {
+            event: EngineEvent<DataKind>,
+            expected_state: TradingState,
+            expected_output: Option<TradingStateUpdateAudit>,
+        }
+
+        let time = |s: &str| s.parse::<DateTime<Utc>>().unwrap();
+
+        let mut engine = build_engine(time("2024-03-15T15:00:00Z"));
+        engine.state.session = Some(SessionControl::new(ExchangeCalendar::b3_equities()));
+
+        let mut replica =
+            StateReplicaManager::new(Auditor::<TestAudit>::audit_snapshot(&mut engine), ());
+
+        let cases = vec![
+            // TC0: continuous trading leaves trading enabled
+            TestCase {
+                event: market_trade(time("2024-03-15T15:00:00Z"), 10.0),
+                expected_state: TradingState::Enabled,
+                expected_output: None,
+            },
+            // TC1: closing call disables trading
+            TestCase {
+                event: market_trade(time("2024-03-15T19:56:00Z"), 10.0),
+                expected_state: TradingState::Disabled,
+                expected_output: Some(TradingStateUpdateAudit {
+                    prev: TradingState::Enabled,
+                    current: TradingState::Disabled,
+                }),
+            },
+            // TC2: continuous trading of the next trading day re-enables trading
+            TestCase {
+                event: market_trade(time("2024-03-18T13:00:00Z"), 10.0),
+                expected_state: TradingState::Enabled,
+                expected_output: Some(TradingStateUpdateAudit {
+                    prev: TradingState::Disabled,
+                    current: TradingState::Enabled,
+                }),
+            },
+            // TC3: manual update is replayed from the input event
+            TestCase {
+                event: EngineEvent::TradingStateUpdate(TradingState::Disabled),
+                expected_state: TradingState::Disabled,
+                expected_output: None,
+            },
+            // TC4: manual re-enable is replayed from the input event
+            TestCase {
+                event: EngineEvent::TradingStateUpdate(TradingState::Enabled),
+                expected_state: TradingState::Enabled,
+                expected_output: None,
+            },
+            // TC5: graceful shutdown disables trading
+            TestCase {
+                event: EngineEvent::GracefulShutdown(GracefulShutdown::default()),
+                expected_state: TradingState::Disabled,
+                expected_output: Some(TradingStateUpdateAudit {
+                    prev: TradingState::Enabled,
+                    current: TradingState::Disabled,
+                }),
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let audit = engine.process(test.event);
+            let AuditTick {
+                event: EngineAudit::Process(audit),
+                context,
+            } = Auditor::<TestAudit>::audit(&mut engine, audit)
+            else {
+                panic!("TC{index} failed: expected EngineAudit::Process");
+            };
+
+            let output = audit.outputs.iter().find_map(|output| match output {
+                EngineOutput::TradingState(update) => Some(*update),
+                _ => None,
+            });
+            assert_eq!(output, test.expected_output, "TC{index} failed");
+            assert_eq!(
+                engine.state.trading, test.expected_state,
+                "TC{index} failed"
+            );
+
+            replica.state_replica.context = context;
+            replica.update_from_event(audit.event);
+            for output in &audit.outputs {
+                replica.update_from_output(output);
+            }
+            assert_eq!(
+                replica.replica_engine_state().trading,
+                test.expected_state,
+                "TC{index} failed"
+            );
+        }
+    }
+}
@@ -0,0 +1,516 @@
+use crate::engine::{
+    action::{
+        cancel_orders::CancelOrders,
+        send_requests::{SendRequests, SendRequestsOutput},
+    },
+    clock::EngineClock,
+    error::UnrecoverableEngineError,
+    execution_tx::ExecutionTxMap,
+    state::{
+        connectivity::{ConnectivityState, ConnectivityStates},
+        instrument::{data::InstrumentDataState, filter::InstrumentFilter},
+        order::in_flight_recorder::InFlightRequestRecorder,
+        EngineState,
+    },
+    Engine,
+};
+use serde::{Deserialize, Serialize};
+use toucan_execution::{
+    order::{
+        id::{ClientOrderId, StrategyId},
+        request::{RequestCancel, RequestOpen},
+    },
+    ExchangeIndex, InstrumentIndex,
+};
+use toucan_instrument::exchange::ExchangeId;
+use toucan_integration::collection::{
+    none_one_or_many::NoneOneOrMany, one_or_many::OneOrMany, FnvIndexMap, FnvIndexSet,
+};
+use toucan_trader::close_positions::close_open_positions_with_market_orders;
+use tracing::{info, warn};
+
+/// Ready-made reaction the `Engine` runs whenever it calls the `OnDisconnectStrategy` or
+/// `OnTradingDisabled` hooks.
+///
+/// Reações podem ser compostas (eg/ `[CancelOrders, PauseOrders]`) e são executadas na ordem
+/// configurada. Every reaction run produces a [`ReactionOutput`] in the `Engine` audit.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Reaction {
+    /// Do nothing, only recording the hook call in the audit.
+    NoOp,
+
+    /// Cancel every open order on the affected exchange (every exchange for `OnTradingDisabled`).
+    CancelOrders,
+
+    /// Close every open position on the affected exchange (every exchange for
+    /// `OnTradingDisabled`) with IOC market orders.
+    ClosePositions,
+
+    /// Pause new algorithmic open orders to the affected exchange until its connectivity is
+    /// [`Health::Healthy`](super::state::connectivity::Health::Healthy) again.
+    ///
+    /// For `OnTradingDisabled`, every exchange that is not healthy is paused.
+    PauseOrders,
+}
+
+/// Hook call that triggers the configured [`Reaction`]s.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub enum ReactionTrigger {
+    /// `OnDisconnectStrategy` called for a disconnected or stale exchange feed.
+    Disconnect(ExchangeId),
+
+    /// `OnTradingDisabled` called after trading transitioned to disabled.
+    TradingDisabled,
+}
+
+impl ReactionTrigger {
+    /// [`InstrumentFilter`] of the instruments affected by the hook call.
+    pub fn filter(&self) -> InstrumentFilter {
+        match self {
+            Self::Disconnect(exchange) => InstrumentFilter::exchanges([exchange.to_string()]),
+            Self::TradingDisabled => InstrumentFilter::None,
+        }
+    }
+}
+
+/// Configurable [`Reaction`]s the `Engine` runs on the `OnDisconnectStrategy` and
+/// `OnTradingDisabled` hooks, alongside any custom logic of the `Strategy`.
+///
+/// Hook calls are queued whilst the `Engine` processes an event, and their reactions are run
+/// once the event has been processed (see `Engine::update_from_reactions`).
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+pub struct Reactions {
+    /// Reactions run when an exchange market data or account feed disconnects or becomes stale.
+    pub on_disconnect: Vec<Reaction>,
+
+    /// `on_disconnect` reactions overridden for individual exchanges.
+    pub exchanges: FnvIndexMap<ExchangeId, Vec<Reaction>>,
+
+    /// Reactions run when trading transitions to disabled.
+    pub on_trading_disabled: Vec<Reaction>,
+
+    /// [`StrategyId`] attributed to the IOC market orders generated by
+    /// [`Reaction::ClosePositions`].
+    pub strategy: StrategyId,
+
+    /// Exchanges with new algorithmic open orders currently paused.
+    pub paused: FnvIndexSet<ExchangeId>,
+
+    /// Hook calls queued whilst processing the current event.
+    pub pending: Vec<ReactionTrigger>,
+}
+
+impl Default for Reactions {
+    fn default() -> Self {
+        Self::new([], [])
+    }
+}
+
+impl Reactions {
+    /// Construct new `Reactions` run on every exchange disconnection and when trading is
+    /// disabled.
+    pub fn new(
+        on_disconnect: impl IntoIterator<Item = Reaction>,
+        on_trading_disabled: impl IntoIterator<Item = Reaction>,
+    ) -> Self {
+        Self {
+            on_disconnect: on_disconnect.into_iter().collect(),
+            exchanges: FnvIndexMap::default(),
+            on_trading_disabled: on_trading_disabled.into_iter().collect(),
+            strategy: StrategyId::new("reactions"),
+            paused: FnvIndexSet::default(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Override the `on_disconnect` reactions of an exchange.
+    pub fn with_exchange(
+        mut self,
+        exchange: ExchangeId,
+        on_disconnect: impl IntoIterator<Item = Reaction>,
+    ) -> Self {
+        self.exchanges
+            .insert(exchange, on_disconnect.into_iter().collect());
+        self
+    }
+
+    /// Attribute the IOC market orders generated by [`Reaction::ClosePositions`] to the
+    /// provided [`StrategyId`].
+    pub fn with_strategy(self, strategy: StrategyId) -> Self {
+        Self { strategy, ..self }
+    }
+
+    /// Configured [`Reaction`]s for the provided [`ReactionTrigger`].
+    pub fn reactions(&self, trigger: &ReactionTrigger) -> &[Reaction] {
+        match trigger {
+            ReactionTrigger::Disconnect(exchange) => {
+                self.exchanges.get(exchange).unwrap_or(&self.on_disconnect)
+            }
+            ReactionTrigger::TradingDisabled => &self.on_trading_disabled,
+        }
+    }
+
+    /// Queue a hook call, ignoring duplicates of an already queued call.
+    pub fn queue(&mut self, trigger: ReactionTrigger) {
+        if !self.pending.contains(&trigger) {
+            self.pending.push(trigger);
+        }
+    }
+
+    /// Pause new algorithmic open orders to the provided exchange.
+    pub fn pause(&mut self, exchange: ExchangeId) {
+        if self.paused.insert(exchange) {
+            warn!(%exchange, "Reactions pausing new orders until exchange health is restored");
+        }
+    }
+
+    /// Returns `true` if new algorithmic open orders to the provided exchange are paused.
+    pub fn is_paused(&self, exchange: &ExchangeIndex) -> bool {
+        self.paused
+            .iter()
+            .any(|paused| paused.to_string() == *exchange)
+    }
+
+    /// Resume every paused exchange whose market data & account connectivity is healthy,
+    /// returning the resumed exchanges.
+    pub fn resume_healthy(&mut self, connectivity: &ConnectivityStates) -> Vec<ExchangeId> {
+        let resumed = self
+            .paused
+            .iter()
+            .filter(|exchange| {
+                connectivity
+                    .exchanges
+                    .get(*exchange)
+                    .is_none_or(ConnectivityState::all_healthy)
+            })
+            .copied()
+            .collect::<Vec<_>>();
+
+        for exchange in &resumed {
+            info!(%exchange, "Reactions resuming new orders after exchange health restored");
+            self.paused.shift_remove(exchange);
+        }
+
+        resumed
+    }
+}
+
+/// Defines whether new algorithmic open order requests to an exchange are paused, in which case
+/// the `Engine` refuses them before they reach the `RiskManager`.
+pub trait PausedExchanges<ExchangeKey = ExchangeIndex> {
+    /// Returns `true` if new algorithmic open orders to the provided exchange are paused.
+    fn is_paused(&self, exchange: &ExchangeKey) -> bool;
+}
+
+impl<GlobalData, InstrumentData> PausedExchanges for EngineState<GlobalData, InstrumentData> {
+    fn is_paused(&self, exchange: &ExchangeIndex) -> bool {
+        self.reactions
+            .as_ref()
+            .is_some_and(|reactions| reactions.is_paused(exchange))
+    }
+}
+
+/// Audit output of a [`Reaction`] run by the `Engine`.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub enum ReactionOutput<ExchangeKey = ExchangeIndex, InstrumentKey = InstrumentIndex> {
+    NoOp {
+        trigger: ReactionTrigger,
+    },
+    CancelOrders {
+        trigger: ReactionTrigger,
+        cancels: SendRequestsOutput<RequestCancel, ExchangeKey, InstrumentKey>,
+    },
+    ClosePositions {
+        trigger: ReactionTrigger,
+        opens: SendRequestsOutput<RequestOpen, ExchangeKey, InstrumentKey>,
+    },
+    PauseOrders {
+        trigger: ReactionTrigger,
+        exchanges: Vec<ExchangeId>,
+    },
+    /// New orders to a paused exchange resumed after its health was restored.
+    ResumeOrders {
+        exchange: ExchangeId,
+    },
+}
+
+impl<ExchangeKey, InstrumentKey> ReactionOutput<ExchangeKey, InstrumentKey> {
+    /// Returns any unrecoverable errors that occurred sending the reaction order requests.
+    pub fn unrecoverable_errors(&self) -> Option<OneOrMany<UnrecoverableEngineError>> {
+        match self {
+            Self::CancelOrders { cancels, .. } => cancels.unrecoverable_errors(),
+            Self::ClosePositions { opens, .. } => opens.unrecoverable_errors(),
+            Self::NoOp { .. } | Self::PauseOrders { .. } | Self::ResumeOrders { .. } => {
+                NoneOneOrMany::None
+            }
+        }
+        .into_option()
+    }
+}
+
+impl<Clock, GlobalData, InstrumentData, ExecutionTxs, Strategy, Risk>
+    Engine<Clock, EngineState<GlobalData, InstrumentData>, ExecutionTxs, Strategy, Risk>
+{
+    /// Run the configured [`Reactions`] (if any) of every hook call queued whilst processing the
+    /// most recent event, and resume paused exchanges whose connectivity is healthy again.
+    ///
+    /// Returns a [`ReactionOutput`] for every reaction run.
+    pub fn update_from_reactions(&mut self) -> Vec<ReactionOutput>
+    where
+        Clock: EngineClock,
+        InstrumentData: InstrumentDataState,
+        ExecutionTxs: ExecutionTxMap<ExchangeIndex, InstrumentIndex>,
+    {
+        let Some(reactions) = self.state.reactions.as_mut() else {
+            return vec![];
+        };
+
+        let pending = std::mem::take(&mut reactions.pending)
+            .into_iter()
+            .flat_map(|trigger| {
+                reactions
+                    .reactions(&trigger)
+                    .iter()
+                    .map(move |reaction| (trigger, *reaction))
+            })
+            .collect::<Vec<_>>();
+
+        let mut outputs = pending
+            .into_iter()
+            .map(|(trigger, reaction)| self.react(trigger, reaction))
+            .collect::<Vec<_>>();
+
+        if let Some(reactions) = self.state.reactions.as_mut() {
+            outputs.extend(
+                reactions
+                    .resume_healthy(&self.state.connectivity)
+                    .into_iter()
+                    .map(|exchange| ReactionOutput::ResumeOrders { exchange }),
+            );
+        }
+
+        outputs
+    }
+
+    fn react(&mut self, trigger: ReactionTrigger, reaction: Reaction) -> ReactionOutput
+    where
+        Clock: EngineClock,
+        InstrumentData: InstrumentDataState,
+        ExecutionTxs: ExecutionTxMap<ExchangeIndex, InstrumentIndex>,
+    {
+        info!(?trigger, ?reaction, "Engine running Reaction");
+
+        match reaction {
+            Reaction::NoOp => ReactionOutput::NoOp { trigger },
+            Reaction::CancelOrders => ReactionOutput::CancelOrders {
+                trigger,
+                cancels: self.cancel_orders(&trigger.filter()),
+            },
+            Reaction::ClosePositions => ReactionOutput::ClosePositions {
+                trigger,
+                opens: self.close_positions_with_market_orders(&trigger.filter()),
+            },
+            Reaction::PauseOrders => {
+                let exchanges = match trigger {
+                    ReactionTrigger::Disconnect(exchange) => vec![exchange],
+                    ReactionTrigger::TradingDisabled => self
+                        .state
+                        .connectivity
+                        .exchanges
+                        .iter()
+                        .filter(|(_, state)| !state.all_healthy())
+                        .map(|(exchange, _)| *exchange)
+                        .collect(),
+                };
+
+                if let Some(reactions) = self.state.reactions.as_mut() {
+                    exchanges
+                        .iter()
+                        .for_each(|exchange| reactions.pause(*exchange));
+                }
+
+                ReactionOutput::PauseOrders { trigger, exchanges }
+            }
+        }
+    }
+
+    /// Generate & send IOC market orders closing every open position matching the provided
+    /// [`InstrumentFilter`], bypassing the `Strategy` & `RiskManager`.
+    ///
+    /// Orders are priced with the latest [`InstrumentDataState::price`], falling back to the
+    /// position average entry price.
+    fn close_positions_with_market_orders(
+        &mut self,
+        filter: &InstrumentFilter,
+    ) -> SendRequestsOutput<RequestOpen>
+    where
+        Clock: EngineClock,
+        InstrumentData: InstrumentDataState,
+        ExecutionTxs: ExecutionTxMap<ExchangeIndex, InstrumentIndex>,
+    {
+        let strategy = self
+            .state
+            .reactions
+            .as_ref()
+            .map_or_else(StrategyId::unknown, |reactions| reactions.strategy.clone());
+
+        let requests = self
+            .state
+            .instruments
+            .instruments(filter)
+            .filter_map(|state| {
+                let position = state.position.current.as_ref()?;
+                let price = state.data.price().unwrap_or(position.price_entry_average);
+
+                Some(close_open_positions_with_market_orders(
+                    state.instrument.exchange.to_string(),
+                    state.key.clone(),
+                    strategy.clone(),
+                    position.side,
+                    position.quantity_abs,
+                    price,
+                    ClientOrderId::random,
+                ))
+            })
+            .flatten()
+            .collect::<Vec<_>>();
+
+        let opens = self.send_requests(requests);
+        self.state.record_in_flight_opens(&opens.sent);
+
+        opens
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::state::connectivity::Health;
+    use indexmap::IndexMap;
+
+    fn connectivity(states: &[(ExchangeId, Health, Health)]) -> ConnectivityStates {
+        ConnectivityStates {
+            global: Health::Healthy,
+            exchanges: states
+                .iter()
+                .map(|(exchange, market_data, account)| {
+                    (
+                        *exchange,
+                        ConnectivityState {
+                            market_data: *market_data,
+                            account: *account,
+                        },
+                    )
+                })
+                .collect::<IndexMap<_, _>>(),
+        }
+    }
+
+    #[test]
+    fn test_reactions_pause_and_resume() {
+        struct TestCase {
+            paused: Vec<ExchangeId>,
+            connectivity: ConnectivityStates,
+            expected_resumed: Vec<ExchangeId>,
+            expected_paused: Vec<ExchangeId>,
+        }
+
+        let cases = vec![
+            // TC0: paused exchange still reconnecting remains paused
+            TestCase {
+                paused: vec![ExchangeId::Profitdll],
+                connectivity: connectivity(&[(
+                    ExchangeId::Profitdll,
+                    Health::Reconnecting,
+                    Health::Healthy,
+                )]),
+                expected_resumed: vec![],
+                expected_paused: vec![ExchangeId::Profitdll],
+            },
+            // TC1: paused exchange with a stale account feed remains paused
+            TestCase {
+                paused: vec![ExchangeId::Profitdll],
+                connectivity: connectivity(&[(
+                    ExchangeId::Profitdll,
+                    Health::Healthy,
+                    Health::Stale,
+                )]),
+                expected_resumed: vec![],
+                expected_paused: vec![ExchangeId::Profitdll],
+            },
+            // TC2: only the healthy exchange is resumed
+            TestCase {
+                paused: vec![ExchangeId::Profitdll, ExchangeId::Mock],
+                connectivity: connectivity(&[
+                    (ExchangeId::Profitdll, Health::Healthy, Health::Healthy),
+                    (ExchangeId::Mock, Health::Reconnecting, Health::Reconnecting),
+                ]),
+                expected_resumed: vec![ExchangeId::Profitdll],
+                expected_paused: vec![ExchangeId::Mock],
+            },
+            // TC3: untracked exchange is resumed
+            TestCase {
+                paused: vec![ExchangeId::Simulated],
+                connectivity: connectivity(&[]),
+                expected_resumed: vec![ExchangeId::Simulated],
+                expected_paused: vec![],
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let mut reactions = Reactions::new([Reaction::PauseOrders], []);
+            test.paused
+                .iter()
+                .for_each(|exchange| reactions.pause(*exchange));
+
+            let resumed = reactions.resume_healthy(&test.connectivity);
+            assert_eq!(resumed, test.expected_resumed, "TC{index} failed");
+
+            for exchange in &test.expected_paused {
+                assert!(
+                    reactions.is_paused(&exchange.to_string()),
+                    "TC{index} failed"
+                );
+            }
+            assert_eq!(
+                reactions.paused.len(),
+                test.expected_paused.len(),
+                "TC{index} failed"
+            );
+        }
+    }
+
+    #[test]
+    fn test_reactions_configured_per_trigger() {
+        let mut reactions = Reactions::new(
+            [Reaction::CancelOrders, Reaction::PauseOrders],
+            [Reaction::ClosePositions],
+        )
+        .with_exchange(ExchangeId::Mock, [Reaction::NoOp]);
+
+        assert_eq!(
+            reactions.reactions(&ReactionTrigger::Disconnect(ExchangeId::Profitdll)),
+            &[Reaction::CancelOrders, Reaction::PauseOrders]
+        );
+        assert_eq!(
+            reactions.reactions(&ReactionTrigger::Disconnect(ExchangeId::Mock)),
+            &[Reaction::NoOp]
+        );
+        assert_eq!(
+            reactions.reactions(&ReactionTrigger::TradingDisabled),
+            &[Reaction::ClosePositions]
+        );
+
+        reactions.queue(ReactionTrigger::Disconnect(ExchangeId::Profitdll));
+        reactions.queue(ReactionTrigger::Disconnect(ExchangeId::Profitdll));
+        reactions.queue(ReactionTrigger::TradingDisabled);
+        assert_eq!(
+            reactions.pending,
+            vec![
+                ReactionTrigger::Disconnect(ExchangeId::Profitdll),
+                ReactionTrigger::TradingDisabled
+            ]
+        );
+    }
+}
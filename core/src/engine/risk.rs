@@ -0,0 +1,1913 @@
+use crate::engine::state::{
+    exposure::{Exposure, ExposureError, PortfolioExposure},
+    instrument::{data::InstrumentDataState, InstrumentState},
+    order::flow::OrderFlow,
+    EngineState,
+};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+use thiserror::Error;
+use toucan_execution::{
+    order::{
+        id::ClientOrderId,
+        request::{OrderRequestCancel, OrderRequestOpen, RequestOpen},
+        state::ActiveOrderState,
+        Order, OrderKind,
+    },
+    AssetIndex, ExchangeIndex, InstrumentIndex,
+};
+use toucan_instrument::{
+    instrument::spec::{InstrumentSpec, InstrumentSpecError, SpecRounding},
+    Side,
+};
+use toucan_integration::collection::FnvIndexMap;
+use toucan_risk::{
+    calculate_abs_percent_difference, calculate_quote_notional,
+    margin::{MarginModel, MarginPosition, MarginUtilisation},
+    validate_instrument_allowed,
+    var::{project_positions, VarLimit},
+    OrderCheck, OrderContext, RiskApproved, RiskManager, RiskRefusalReason, RiskRefused,
+};
+
+/// Configurable pre-trade [`RiskManager`] that refuses algorithmic open requests breaching
+/// [`RiskLimits`].
+///
+/// Limits can be configured globally and per instrument (per instrument limits take precedence
+/// over the global limits they define), alongside net position limits per underlying, a list of
+/// allowed instruments and a maximum number of open orders across all instruments.
+///
+/// Open requests for instruments with an [`OrderSpec`] are first validated against the
+/// [`InstrumentSpec`] tick size, quantity increment, minimum quantity and minimum notional. If a
+/// [`SpecRounding`] is configured, the price & quantity are normalised to the spec before being
+/// validated, and the approved open request carries the normalised values.
+///
+/// Cancel requests are always approved. Open requests are evaluated in order, and each approved
+/// open counts towards the position & open order limits of the opens that follow it.
+///
+/// Note that projected positions are the current net [`Position`](super::state::position::Position)
+/// plus the approved opens, excluding resting orders. Orders that reduce the absolute position
+/// are never refused by a position limit.
+///
+/// If a [`SelfMatchMode`] is configured, approved opens that would cross the open orders already
+/// resting on the opposite side of the same instrument are handled according to the mode (see
+/// [`LimitsRiskManager::prevent_self_match`]).
+///
+/// When used as the `Engine` [`RiskManager`], refusals are converted into a structured
+/// [`RiskRefusalReason`] (see [`RiskLimitBreach::check_name`]).
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct LimitsRiskManager<State> {
+    /// Limits applied to every instrument.
+    pub limits: RiskLimits,
+
+    /// Limits applied to individual instruments, overriding the global `limits`.
+    pub instruments: FnvIndexMap<InstrumentIndex, RiskLimits>,
+
+    /// Maximum absolute net position quantity per underlying, aggregated across every instrument
+    /// sharing the underlying.
+    pub underlyings: FnvIndexMap<String, Decimal>,
+
+    /// Instruments allowed to be traded, or `None` if every instrument is allowed.
+    pub allowed_instruments: Option<Vec<InstrumentIndex>>,
+
+    /// Maximum number of open orders across all instruments.
+    pub max_open_orders: Option<usize>,
+
+    /// [`OrderSpec`]s that open requests must satisfy, keyed by instrument.
+    pub specs: FnvIndexMap<InstrumentIndex, OrderSpec>,
+
+    /// [`SpecRounding`] used to normalise open requests to their [`OrderSpec`], or `None` to
+    /// refuse open requests off the price tick or quantity increment.
+    pub spec_rounding: Option<SpecRounding>,
+
+    /// [`SelfMatchMode`] applied to opens crossing resting orders, or `None` to allow them.
+    #[serde(default)]
+    pub self_match: Option<SelfMatchMode>,
+
+    #[serde(skip)]
+    phantom: PhantomData<State>,
+}
+
+impl<State> Default for LimitsRiskManager<State> {
+    fn default() -> Self {
+        Self::new(RiskLimits::default())
+    }
+}
+
+impl<State> LimitsRiskManager<State> {
+    /// Construct a new `LimitsRiskManager` with the provided global [`RiskLimits`].
+    pub fn new(limits: RiskLimits) -> Self {
+        Self {
+            limits,
+            instruments: FnvIndexMap::default(),
+            underlyings: FnvIndexMap::default(),
+            allowed_instruments: None,
+            max_open_orders: None,
+            specs: FnvIndexMap::default(),
+            spec_rounding: None,
+            self_match: None,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Configure [`RiskLimits`] for an individual instrument.
+    pub fn with_instrument_limits(
+        mut self,
+        instrument: InstrumentIndex,
+        limits: RiskLimits,
+    ) -> Self {
+        self.instruments.insert(instrument, limits);
+        self
+    }
+
+    /// Configure the maximum absolute net position quantity for an underlying.
+    pub fn with_underlying_limit(mut self, underlying: impl Into<String>, limit: Decimal) -> Self {
+        self.underlyings.insert(underlying.into(), limit);
+        self
+    }
+
+    /// Only allow the provided instruments to be traded.
+    pub fn with_allowed_instruments(
+        mut self,
+        instruments: impl IntoIterator<Item = InstrumentIndex>,
+    ) -> Self {
+        self.allowed_instruments = Some(instruments.into_iter().collect());
+        self
+    }
+
+    /// Configure the maximum number of open orders across all instruments.
+    pub fn with_max_open_orders(mut self, limit: usize) -> Self {
+        self.max_open_orders = Some(limit);
+        self
+    }
+
+    /// Configure the [`InstrumentSpec`] & contract size that open requests for an individual
+    /// instrument must satisfy.
+    pub fn with_instrument_spec(
+        mut self,
+        instrument: InstrumentIndex,
+        spec: InstrumentSpec<AssetIndex>,
+        contract_size: Decimal,
+    ) -> Self {
+        self.specs.insert(
+            instrument,
+            OrderSpec {
+                spec,
+                contract_size,
+            },
+        );
+        self
+    }
+
+    /// Normalise open requests to their [`OrderSpec`] using the provided [`SpecRounding`].
+    pub fn with_spec_rounding(mut self, rounding: SpecRounding) -> Self {
+        self.spec_rounding = Some(rounding);
+        self
+    }
+
+    /// Prevent approved opens from matching resting orders using the provided [`SelfMatchMode`].
+    pub fn with_self_match_prevention(mut self, mode: SelfMatchMode) -> Self {
+        self.self_match = Some(mode);
+        self
+    }
+
+    /// Validate, and if a [`SpecRounding`] is configured normalise, an open request against the
+    /// [`OrderSpec`] of its instrument (if any).
+    pub fn apply_spec(&self, open: &mut OrderRequestOpen) -> Result<(), RiskLimitBreach> {
+        let Some(OrderSpec {
+            spec,
+            contract_size,
+        }) = self.specs.get(&open.key.instrument)
+        else {
+            return Ok(());
+        };
+
+        let order = &mut open.state;
+        match (self.spec_rounding, order.kind) {
+            (Some(rounding), OrderKind::Limit) => {
+                let (price, quantity) = spec.normalise(
+                    order.side,
+                    order.price,
+                    order.quantity,
+                    *contract_size,
+                    rounding,
+                )?;
+                order.price = price;
+                order.quantity = quantity;
+            }
+            (Some(rounding), OrderKind::Market) => {
+                order.quantity = spec.quantity.round(order.quantity, rounding.quantity);
+                spec.validate_quantity(order.price, order.quantity, *contract_size)?;
+            }
+            (None, OrderKind::Limit) => {
+                spec.validate(order.price, order.quantity, *contract_size)?
+            }
+            (None, OrderKind::Market) => {
+                spec.validate_quantity(order.price, order.quantity, *contract_size)?
+            }
+        }
+
+        Ok(())
+    }
+
+    /// [`RiskLimits`] applied to the provided instrument.
+    pub fn instrument_limits(&self, instrument: &InstrumentIndex) -> RiskLimits {
+        self.instruments
+            .get(instrument)
+            .map_or(self.limits, |limits| limits.or(self.limits))
+    }
+
+    /// Apply the configured [`SelfMatchMode`] to approved opens, given a function returning the
+    /// open orders resting on an instrument.
+    ///
+    /// An open crosses a resting order on the opposite side if it is a market order, or if its
+    /// limit price is marketable against the resting limit price. Opens crossing an open approved
+    /// earlier in the same batch are always refused, since the unsent open cannot be cancelled.
+    /// Resting orders with a cancel already in flight are ignored.
+    pub fn prevent_self_match<'a, FnResting, RestingIter>(
+        &self,
+        resting: FnResting,
+        opens: Vec<RiskApproved<OrderRequestOpen>>,
+    ) -> SelfMatchOutput
+    where
+        FnResting: Fn(&InstrumentIndex) -> RestingIter,
+        RestingIter:
+            IntoIterator<Item = &'a Order<ExchangeIndex, InstrumentIndex, ActiveOrderState>>,
+    {
+        let Some(mode) = self.self_match else {
+            return SelfMatchOutput {
+                approved: opens,
+                ..Default::default()
+            };
+        };
+
+        let mut output = SelfMatchOutput::default();
+        for RiskApproved(open) in opens {
+            let batch = output.approved.iter().find(|RiskApproved(approved)| {
+                approved.key.instrument == open.key.instrument
+                    && crosses(&open.state, approved.state.side, approved.state.price)
+            });
+            if let Some(RiskApproved(approved)) = batch {
+                let reason = RiskLimitBreach::SelfMatch(approved.key.cid.clone());
+                output.refused.push(RiskRefused { item: open, reason });
+                continue;
+            }
+
+            let crossed = resting(&open.key.instrument)
+                .into_iter()
+                .filter(|order| {
+                    order.kind == OrderKind::Limit
+                        && !matches!(order.state, ActiveOrderState::CancelInFlight(_))
+                        && !output
+                            .cancels
+                            .iter()
+                            .any(|RiskApproved(cancel)| cancel.key.cid == order.key.cid)
+                        && crosses(&open.state, order.side, order.price)
+                })
+                .collect::<Vec<_>>();
+
+            let Some(first) = crossed.first() else {
+                output.approved.push(RiskApproved::new(open));
+                continue;
+            };
+
+            if matches!(
+                mode,
+                SelfMatchMode::CancelResting | SelfMatchMode::CancelBoth
+            ) {
+                output.cancels.extend(
+                    crossed
+                        .iter()
+                        .filter_map(|order| order.to_request_cancel())
+                        .map(RiskApproved::new),
+                );
+            }
+
+            match mode {
+                SelfMatchMode::CancelResting => output.approved.push(RiskApproved::new(open)),
+                SelfMatchMode::CancelIncoming
+                | SelfMatchMode::Reject
+                | SelfMatchMode::CancelBoth => {
+                    let reason = RiskLimitBreach::SelfMatch(first.key.cid.clone());
+                    output.refused.push(RiskRefused { item: open, reason })
+                }
+            }
+        }
+
+        output
+    }
+}
+
+impl<GlobalData, InstrumentData> LimitsRiskManager<EngineState<GlobalData, InstrumentData>>
+where
+    InstrumentData: InstrumentDataState,
+{
+    /// Check the provided open requests against the configured limits, returning the approved
+    /// opens and the opens refused with a typed [`RiskLimitBreach`].
+    #[allow(clippy::type_complexity)]
+    pub fn check_opens(
+        &self,
+        state: &EngineState<GlobalData, InstrumentData>,
+        opens: impl IntoIterator<Item = OrderRequestOpen>,
+    ) -> (
+        Vec<RiskApproved<OrderRequestOpen>>,
+        Vec<RiskRefused<OrderRequestOpen, RiskLimitBreach>>,
+    ) {
+        let mut pending = PendingOpens::default();
+        let mut approved = Vec::new();
+        let mut refused = Vec::new();
+
+        for mut open in opens {
+            let result = self
+                .apply_spec(&mut open)
+                .and_then(|()| self.check_open(state, &pending, &open));
+
+            match result {
+                Ok(()) => {
+                    pending.record(&open);
+                    approved.push(RiskApproved::new(open));
+                }
+                Err(reason) => refused.push(RiskRefused { item: open, reason }),
+            }
+        }
+
+        (approved, refused)
+    }
+
+    fn check_open(
+        &self,
+        state: &EngineState<GlobalData, InstrumentData>,
+        pending: &PendingOpens,
+        open: &OrderRequestOpen,
+    ) -> Result<(), RiskLimitBreach> {
+        let instrument = &open.key.instrument;
+
+        if let Some(allowed) = &self.allowed_instruments {
+            validate_instrument_allowed(instrument, allowed)
+                .map_err(|_| RiskLimitBreach::InstrumentNotAllowed(instrument.clone()))?;
+        }
+
+        let instrument_state = state
+            .instruments
+            .0
+            .get(instrument)
+            .ok_or_else(|| RiskLimitBreach::UnknownInstrument(instrument.clone()))?;
+
+        if let Some(limit) = self.max_open_orders {
+            let open_orders = state
+                .instruments
+                .0
+                .values()
+                .map(|state| state.orders.0.len())
+                .sum::<usize>()
+                + pending.orders();
+
+            if open_orders >= limit {
+                return Err(RiskLimitBreach::OpenOrders {
+                    limit,
+                    open: open_orders,
+                });
+            }
+        }
+
+        let mut exposure = InstrumentExposure::from_state(instrument_state)
+            .with_order_flow(state.order_flow.instrument(instrument));
+        exposure.position += pending.position(instrument);
+        exposure.open_orders += pending.instrument_orders(instrument);
+        exposure.open_rate += pending.instrument_orders(instrument);
+        let contract_size = self
+            .specs
+            .get(instrument)
+            .map_or(Decimal::ONE, |spec| spec.contract_size);
+        self.instrument_limits(instrument)
+            .check(&open.state, &exposure, contract_size)?;
+
+        let Some(underlying) = instrument_state.instrument.underlying.as_ref() else {
+            return Ok(());
+        };
+        let Some(limit) = self.underlyings.get(underlying) else {
+            return Ok(());
+        };
+
+        let current = state
+            .instruments
+            .0
+            .values()
+            .filter(|state| state.instrument.underlying.as_ref() == Some(underlying))
+            .map(|state| net_position(state) + pending.position(&state.key))
+            .sum::<Decimal>();
+
+        check_position(current, &open.state, *limit).map_err(|projected| {
+            RiskLimitBreach::UnderlyingPosition {
+                underlying: underlying.clone(),
+                limit: *limit,
+                projected,
+            }
+        })
+    }
+}
+
+#[allow(clippy::type_complexity)]
+impl<GlobalData, InstrumentData> RiskManager
+    for LimitsRiskManager<EngineState<GlobalData, InstrumentData>>
+where
+    InstrumentData: InstrumentDataState,
+{
+    type State = EngineState<GlobalData, InstrumentData>;
+
+    fn check(
+        &self,
+        state: &Self::State,
+        cancels: impl IntoIterator<Item = OrderRequestCancel>,
+        opens: impl IntoIterator<Item = OrderRequestOpen>,
+    ) -> (
+        impl IntoIterator<Item = RiskApproved<OrderRequestCancel>>,
+        impl IntoIterator<Item = RiskApproved<OrderRequestOpen>>,
+        impl IntoIterator<Item = RiskRefused<OrderRequestCancel>>,
+        impl IntoIterator<Item = RiskRefused<OrderRequestOpen>>,
+    ) {
+        let (approved_opens, refused_opens) = self.check_opens(state, opens);
+        let self_match = self.prevent_self_match(
+            |instrument| {
+                state
+                    .instruments
+                    .0
+                    .get(instrument)
+                    .into_iter()
+                    .flat_map(|state| state.orders.0.values())
+            },
+            approved_opens,
+        );
+
+        (
+            cancels
+                .into_iter()
+                .map(RiskApproved::new)
+                .chain(self_match.cancels),
+            self_match.approved,
+            std::iter::empty(),
+            refused_opens
+                .into_iter()
+                .chain(self_match.refused)
+                .map(|refused| refused.map_reason(RiskRefusalReason::from)),
+        )
+    }
+}
+
+/// Records order requests refused by the `RiskManager`, aggregating the refusal statistics
+/// surfaced in the `TradingSummary`.
+pub trait RiskRefusalRecorder<InstrumentKey = InstrumentIndex> {
+    /// Record an order request for the provided instrument refused with the provided
+    /// [`RiskRefusalReason`].
+    fn record_risk_refusal(&mut self, instrument: &InstrumentKey, reason: &RiskRefusalReason);
+}
+
+impl<GlobalData, InstrumentData> RiskRefusalRecorder for EngineState<GlobalData, InstrumentData> {
+    fn record_risk_refusal(&mut self, instrument: &InstrumentIndex, reason: &RiskRefusalReason) {
+        self.risk_refusals.record(reason.check(), instrument)
+    }
+}
+
+/// Configurable per-order and per-instrument limits evaluated by a [`LimitsRiskManager`].
+///
+/// A limit of `None` is not evaluated. A limit is breached when the observed value exceeds it.
+#[derive(
+    Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default, Deserialize, Serialize,
+)]
+pub struct RiskLimits {
+    /// Maximum quantity of a single order.
+    pub max_order_quantity: Option<Decimal>,
+
+    /// Maximum notional (quantity * price) of a single order, in quote currency.
+    pub max_order_notional: Option<Decimal>,
+
+    /// Maximum absolute net position quantity.
+    pub max_position: Option<Decimal>,
+
+    /// Maximum deviation of a limit order price from the current instrument price, expressed
+    /// as a fraction (eg/ 0.05 for 5%).
+    pub max_price_deviation: Option<Decimal>,
+
+    /// Maximum number of open orders.
+    pub max_open_orders: Option<usize>,
+
+    /// Maximum number of open order requests sent per second.
+    pub max_orders_per_second: Option<usize>,
+
+    /// Maximum ratio of cancel requests sent per trade received (see
+    /// [`OrderFlow::order_to_trade_ratio`]).
+    pub max_order_to_trade_ratio: Option<Decimal>,
+}
+
+impl RiskLimits {
+    /// Combine with a fallback `RiskLimits`, using the fallback limit wherever `self` does not
+    /// define one.
+    pub fn or(self, fallback: Self) -> Self {
+        Self {
+            max_order_quantity: self.max_order_quantity.or(fallback.max_order_quantity),
+            max_order_notional: self.max_order_notional.or(fallback.max_order_notional),
+            max_position: self.max_position.or(fallback.max_position),
+            max_price_deviation: self.max_price_deviation.or(fallback.max_price_deviation),
+            max_open_orders: self.max_open_orders.or(fallback.max_open_orders),
+            max_orders_per_second: self
+                .max_orders_per_second
+                .or(fallback.max_orders_per_second),
+            max_order_to_trade_ratio: self
+                .max_order_to_trade_ratio
+                .or(fallback.max_order_to_trade_ratio),
+        }
+    }
+
+    /// Check an order against the limits, given the current [`InstrumentExposure`].
+    ///
+    /// The order notional is scaled by the instrument `contract_size` (eg/ the futures or
+    /// options multiplier), which is `Decimal::ONE` for instruments quoted per unit.
+    pub fn check(
+        &self,
+        order: &RequestOpen,
+        exposure: &InstrumentExposure,
+        contract_size: Decimal,
+    ) -> Result<(), RiskLimitBreach> {
+        if let Some(limit) = self.max_open_orders {
+            if exposure.open_orders >= limit {
+                return Err(RiskLimitBreach::InstrumentOpenOrders {
+                    limit,
+                    open: exposure.open_orders,
+                });
+            }
+        }
+
+        if let Some(limit) = self.max_orders_per_second {
+            if exposure.open_rate >= limit {
+                return Err(RiskLimitBreach::OrderRate {
+                    limit,
+                    rate: exposure.open_rate,
+                });
+            }
+        }
+
+        if let Some(limit) = self.max_order_to_trade_ratio {
+            if exposure.order_to_trade_ratio > limit {
+                return Err(RiskLimitBreach::OrderToTradeRatio {
+                    limit,
+                    ratio: exposure.order_to_trade_ratio,
+                });
+            }
+        }
+
+        if let Some(limit) = self.max_order_quantity {
+            if order.quantity.abs() > limit {
+                return Err(RiskLimitBreach::OrderQuantity {
+                    limit,
+                    quantity: order.quantity.abs(),
+                });
+            }
+        }
+
+        if let Some(limit) = self.max_price_deviation {
+            if order.kind == OrderKind::Limit {
+                let reference = exposure
+                    .reference_price
+                    .ok_or(RiskLimitBreach::MissingReferencePrice)?;
+                let deviation = calculate_abs_percent_difference(order.price, reference)
+                    .map_err(|_| RiskLimitBreach::MissingReferencePrice)?;
+
+                if deviation > limit {
+                    return Err(RiskLimitBreach::PriceCollar {
+                        limit,
+                        reference,
+                        price: order.price,
+                        deviation,
+                    });
+                }
+            }
+        }
+
+        if let Some(limit) = self.max_order_notional {
+            let price = match order.kind {
+                OrderKind::Limit => Some(order.price),
+                OrderKind::Market => exposure.reference_price,
+            }
+            .ok_or(RiskLimitBreach::MissingReferencePrice)?;
+
+            let quantity = order.quantity.abs();
+            let notional =
+                calculate_quote_notional(quantity, price, contract_size).map_err(|_| {
+                    RiskLimitBreach::NotionalOverflow {
+                        quantity,
+                        price,
+                        contract_size,
+                    }
+                })?;
+
+            if notional > limit {
+                return Err(RiskLimitBreach::OrderNotional { limit, notional });
+            }
+        }
+
+        if let Some(limit) = self.max_position {
+            check_position(exposure.position, order, limit)
+                .map_err(|projected| RiskLimitBreach::Position { limit, projected })?;
+        }
+
+        Ok(())
+    }
+}
+
+/// [`RiskLimits`] are composable into [`OrderCheck`] pipelines (eg/ as the default or
+/// per-instrument override of a [`PerInstrument`](toucan_risk::PerInstrument)).
+///
+/// Each open request is evaluated against the current [`InstrumentExposure`] of its instrument,
+/// including the [`OrderContext`] pending opens, and notionals are scaled by the
+/// [`OrderContext`] contract size (see
+/// [`RiskCheckManager::with_contract_size`](toucan_risk::RiskCheckManager::with_contract_size)).
+impl<GlobalData, InstrumentData> OrderCheck<EngineState<GlobalData, InstrumentData>> for RiskLimits
+where
+    InstrumentData: InstrumentDataState,
+{
+    fn check_open(
+        &self,
+        context: &OrderContext<'_, EngineState<GlobalData, InstrumentData>>,
+        open: &OrderRequestOpen<ExchangeIndex, InstrumentIndex>,
+    ) -> Result<(), RiskRefusalReason> {
+        let instrument = &open.key.instrument;
+        let instrument_state = context
+            .state
+            .instruments
+            .0
+            .get(instrument)
+            .ok_or_else(|| RiskLimitBreach::UnknownInstrument(instrument.clone()))?;
+
+        let mut exposure = InstrumentExposure::from_state(instrument_state)
+            .with_order_flow(context.state.order_flow.instrument(instrument));
+        exposure.position += context.pending_quantity(instrument);
+        exposure.open_orders += context.pending_orders(instrument);
+        exposure.open_rate += context.pending_orders(instrument);
+
+        self.check(&open.state, &exposure, context.contract_size)
+            .map_err(RiskRefusalReason::from)
+    }
+}
+
+/// Current instrument exposure that [`RiskLimits`] are evaluated against.
+#[derive(
+    Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default, Deserialize, Serialize,
+)]
+pub struct InstrumentExposure {
+    /// Current instrument price (see [`InstrumentDataState::price`]).
+    pub reference_price: Option<Decimal>,
+
+    /// Signed net position quantity (positive long, negative short).
+    pub position: Decimal,
+
+    /// Number of open orders.
+    pub open_orders: usize,
+
+    /// Number of open order requests sent within the
+    /// [`ORDER_RATE_WINDOW`](crate::engine::state::order::flow::ORDER_RATE_WINDOW).
+    pub open_rate: usize,
+
+    /// Ratio of cancel requests sent per trade received.
+    pub order_to_trade_ratio: Decimal,
+}
+
+impl InstrumentExposure {
+    /// Current `InstrumentExposure` of the provided [`InstrumentState`].
+    pub fn from_state<InstrumentData>(state: &InstrumentState<InstrumentData>) -> Self
+    where
+        InstrumentData: InstrumentDataState,
+    {
+        Self {
+            reference_price: state.data.price(),
+            position: net_position(state),
+            open_orders: state.orders.0.len(),
+            open_rate: 0,
+            order_to_trade_ratio: Decimal::ZERO,
+        }
+    }
+
+    /// Include the message rate & order-to-trade ratio of the instrument [`OrderFlow`], if any.
+    pub fn with_order_flow(self, flow: Option<&OrderFlow>) -> Self {
+        match flow {
+            Some(flow) => Self {
+                open_rate: flow.open_rate(),
+                order_to_trade_ratio: flow.order_to_trade_ratio(),
+                ..self
+            },
+            None => self,
+        }
+    }
+}
+
+/// Portfolio level exposure caps, evaluated against the [`PortfolioExposure`] `GlobalData`.
+///
+/// Composable into [`OrderCheck`] pipelines of an `Engine` configured with
+/// [`PortfolioExposure`] global data. Open requests are refused only if they would breach a limit
+/// whilst increasing the portfolio exposure, so reducing orders are always approved.
+#[derive(
+    Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default, Deserialize, Serialize,
+)]
+pub struct ExposureLimits {
+    /// Maximum total gross (long + short) quote notional exposure.
+    pub max_gross_exposure: Option<Decimal>,
+
+    /// Maximum absolute total net (long - short) quote notional exposure.
+    pub max_net_exposure: Option<Decimal>,
+}
+
+impl ExposureLimits {
+    /// Check the `projected` portfolio [`Exposure`] against the limits, given the `current`
+    /// portfolio [`Exposure`].
+    pub fn check(&self, current: &Exposure, projected: &Exposure) -> Result<(), RiskRefusalReason> {
+        if let Some(limit) = self.max_gross_exposure {
+            let gross = projected.gross();
+            if gross > limit && gross > current.gross() {
+                return Err(RiskRefusalReason::breach(
+                    "max_gross_exposure",
+                    limit,
+                    gross,
+                ));
+            }
+        }
+
+        if let Some(limit) = self.max_net_exposure {
+            let net = projected.net().abs();
+            if net > limit && net > current.net().abs() {
+                return Err(RiskRefusalReason::breach("max_net_exposure", limit, net));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Open requests are projected as if the [`OrderContext`] pending opens were filled first.
+impl<InstrumentData> OrderCheck<EngineState<PortfolioExposure, InstrumentData>> for ExposureLimits {
+    fn check_open(
+        &self,
+        context: &OrderContext<'_, EngineState<PortfolioExposure, InstrumentData>>,
+        open: &OrderRequestOpen<ExchangeIndex, InstrumentIndex>,
+    ) -> Result<(), RiskRefusalReason> {
+        let global = &context.state.global;
+        let current = global
+            .projected_total(context.pending.iter().map(exposure_trade))
+            .map_err(RiskLimitBreach::from)?;
+        let projected = global
+            .projected_total(context.pending.iter().chain([open]).map(exposure_trade))
+            .map_err(RiskLimitBreach::from)?;
+
+        self.check(&current, &projected)
+    }
+}
+
+/// [`VarLimit`] pre-trade check of an `Engine` configured with [`PortfolioExposure`] global
+/// data, projecting the portfolio VaR as if the [`OrderContext`] pending opens and the open
+/// request were filled at the instrument mark price (or the limit price if there is none).
+///
+/// Market orders of instruments without a mark price are refused, since they cannot be valued.
+impl<InstrumentData> OrderCheck<EngineState<PortfolioExposure, InstrumentData>> for VarLimit {
+    fn check_open(
+        &self,
+        context: &OrderContext<'_, EngineState<PortfolioExposure, InstrumentData>>,
+        open: &OrderRequestOpen<ExchangeIndex, InstrumentIndex>,
+    ) -> Result<(), RiskRefusalReason> {
+        let global = &context.state.global;
+        let notional = |open: &OrderRequestOpen| {
+            let instrument = global
+                .instrument(&open.key.instrument)
+                .ok_or_else(|| RiskLimitBreach::UnknownInstrument(open.key.instrument.clone()))?;
+
+            let (_, quantity, price) = exposure_trade(open);
+            let price = instrument
+                .valuation_price(price)
+                .ok_or(RiskLimitBreach::MissingReferencePrice)?;
+
+            Ok::<_, RiskLimitBreach>(quantity * price * instrument.contract_size)
+        };
+
+        let current =
+            context
+                .pending
+                .iter()
+                .try_fold(global.var_positions(), |positions, pending| {
+                    notional(pending).map(|notional| {
+                        project_positions(&positions, &pending.key.instrument, notional)
+                    })
+                })?;
+        let projected = project_positions(&current, &open.key.instrument, notional(open)?);
+
+        self.check(&global.returns, &current, &projected)
+    }
+}
+
+/// Pre-trade [`MarginModel`] check of open requests against the collateral available in the
+/// `EngineState` [`AssetStates`](crate::engine::state::asset::AssetStates).
+///
+/// The collateral is the total balance of the `collateral_asset` (eg/ "BRL"), and positions are
+/// valued at the current instrument price (see [`InstrumentDataState::price`]). Open requests are
+/// projected as if fully filled, and refused if the projected margin requirement exceeds the
+/// collateral whilst increasing the current requirement.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+pub struct MarginCheck {
+    /// [`MarginModel`] used to compute the margin requirement.
+    pub model: MarginModel,
+
+    /// Asset whose total balance is the available collateral.
+    pub collateral_asset: AssetIndex,
+
+    /// Contract size of each instrument, defaulting to `Decimal::ONE`.
+    #[serde(default)]
+    pub contract_sizes: FnvIndexMap<InstrumentIndex, Decimal>,
+}
+
+impl MarginCheck {
+    /// Construct a [`MarginCheck`] using the total balance of the `collateral_asset`.
+    pub fn new(model: MarginModel, collateral_asset: impl Into<AssetIndex>) -> Self {
+        Self {
+            model,
+            collateral_asset: collateral_asset.into(),
+            contract_sizes: FnvIndexMap::default(),
+        }
+    }
+
+    /// Configure the contract size of an instrument (eg/ 0.2 for WIN).
+    pub fn with_contract_size(
+        mut self,
+        instrument: InstrumentIndex,
+        contract_size: Decimal,
+    ) -> Self {
+        self.contract_sizes.insert(instrument, contract_size);
+        self
+    }
+
+    /// Total balance of the collateral asset, or zero if it has no balance.
+    pub fn collateral<GlobalData, InstrumentData>(
+        &self,
+        state: &EngineState<GlobalData, InstrumentData>,
+    ) -> Decimal {
+        state
+            .assets
+            .0
+            .get(self.collateral_asset.as_str())
+            .and_then(|asset| asset.balance.as_ref())
+            .map_or(Decimal::ZERO, |balance| balance.value.total)
+    }
+
+    /// [`MarginPosition`] of every instrument with an open position.
+    pub fn positions<GlobalData, InstrumentData>(
+        &self,
+        state: &EngineState<GlobalData, InstrumentData>,
+    ) -> Vec<MarginPosition>
+    where
+        InstrumentData: InstrumentDataState,
+    {
+        state
+            .instruments
+            .0
+            .iter()
+            .map(|(instrument, instrument_state)| {
+                MarginPosition::new(
+                    instrument.clone(),
+                    net_position(instrument_state),
+                    instrument_state.data.price(),
+                    self.contract_size(instrument),
+                )
+            })
+            .filter(|position| !position.quantity.is_zero())
+            .collect()
+    }
+
+    /// Current [`MarginUtilisation`] of the `EngineState` positions & collateral.
+    pub fn utilisation<GlobalData, InstrumentData>(
+        &self,
+        state: &EngineState<GlobalData, InstrumentData>,
+    ) -> MarginUtilisation
+    where
+        InstrumentData: InstrumentDataState,
+    {
+        self.model
+            .utilisation(self.collateral(state), &self.positions(state))
+    }
+
+    /// Project the provided [`MarginPosition`]s as if the open request was fully filled.
+    fn project<GlobalData, InstrumentData>(
+        &self,
+        state: &EngineState<GlobalData, InstrumentData>,
+        mut positions: Vec<MarginPosition>,
+        open: &OrderRequestOpen,
+    ) -> Vec<MarginPosition>
+    where
+        InstrumentData: InstrumentDataState,
+    {
+        let instrument = &open.key.instrument;
+        let quantity = signed_quantity(&open.state);
+
+        match positions
+            .iter_mut()
+            .find(|position| &position.instrument == instrument)
+        {
+            Some(position) => position.quantity += quantity,
+            None => {
+                let price = state
+                    .instruments
+                    .0
+                    .get(instrument)
+                    .and_then(|state| state.data.price())
+                    .unwrap_or(open.state.price);
+
+                positions.push(MarginPosition::new(
+                    instrument.clone(),
+                    quantity,
+                    Some(price),
+                    self.contract_size(instrument),
+                ));
+            }
+        }
+
+        positions
+    }
+
+    fn contract_size(&self, instrument: &InstrumentIndex) -> Decimal {
+        self.contract_sizes
+            .get(instrument)
+            .copied()
+            .unwrap_or(Decimal::ONE)
+    }
+}
+
+/// Open requests are projected as if the [`OrderContext`] pending opens were filled first.
+impl<GlobalData, InstrumentData> OrderCheck<EngineState<GlobalData, InstrumentData>> for MarginCheck
+where
+    InstrumentData: InstrumentDataState,
+{
+    fn check_open(
+        &self,
+        context: &OrderContext<'_, EngineState<GlobalData, InstrumentData>>,
+        open: &OrderRequestOpen<ExchangeIndex, InstrumentIndex>,
+    ) -> Result<(), RiskRefusalReason> {
+        let state = context.state;
+        let current = context
+            .pending
+            .iter()
+            .fold(self.positions(state), |positions, pending| {
+                self.project(state, positions, pending)
+            });
+        let projected = self.project(state, current.clone(), open);
+
+        self.model
+            .check(self.collateral(state), &current, &projected)
+    }
+}
+
+/// Handling of an open request that would match an open order resting on the opposite side of
+/// the same instrument.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelfMatchMode {
+    /// Cancel the crossed resting orders, and send the incoming open.
+    CancelResting,
+
+    /// Refuse the incoming open, leaving the resting orders untouched.
+    CancelIncoming,
+
+    /// Reject the incoming open, leaving the resting orders untouched. Since opens are checked
+    /// before being sent, this is equivalent to [`Self::CancelIncoming`], under the name used by
+    /// exchanges that reject the incoming order.
+    Reject,
+
+    /// Refuse the incoming open and cancel the crossed resting orders, so neither trades.
+    CancelBoth,
+}
+
+/// Output of [`LimitsRiskManager::prevent_self_match`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SelfMatchOutput {
+    /// Opens that do not match any resting order, or were approved by
+    /// [`SelfMatchMode::CancelResting`].
+    pub approved: Vec<RiskApproved<OrderRequestOpen>>,
+
+    /// Cancels of crossed resting orders.
+    pub cancels: Vec<RiskApproved<OrderRequestCancel>>,
+
+    /// Opens refused for matching a resting order.
+    pub refused: Vec<RiskRefused<OrderRequestOpen, RiskLimitBreach>>,
+}
+
+/// [`InstrumentSpec`] and contract size that an instrument's open requests must satisfy.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub struct OrderSpec {
+    pub spec: InstrumentSpec<AssetIndex>,
+    pub contract_size: Decimal,
+}
+
+/// Typed reason a [`LimitsRiskManager`] refused an open request.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize, Error)]
+pub enum RiskLimitBreach {
+    #[error("order does not satisfy instrument spec: {0}")]
+    InstrumentSpec(#[from] InstrumentSpecError),
+
+    #[error("instrument {0} is not allowed to be traded")]
+    InstrumentNotAllowed(InstrumentIndex),
+
+    #[error("instrument {0} is not tracked by the EngineState")]
+    UnknownInstrument(InstrumentIndex),
+
+    #[error("no reference price available to evaluate the order")]
+    MissingReferencePrice,
+
+    #[error("order quantity {quantity} exceeds limit {limit}")]
+    OrderQuantity { limit: Decimal, quantity: Decimal },
+
+    #[error("order notional {notional} exceeds limit {limit}")]
+    OrderNotional { limit: Decimal, notional: Decimal },
+
+    #[error("order notional of {quantity} x {price} x {contract_size} overflowed")]
+    NotionalOverflow {
+        quantity: Decimal,
+        price: Decimal,
+        contract_size: Decimal,
+    },
+
+    #[error("order price {price} deviates {deviation} from reference {reference}, exceeding limit {limit}")]
+    PriceCollar {
+        limit: Decimal,
+        reference: Decimal,
+        price: Decimal,
+        deviation: Decimal,
+    },
+
+    #[error("projected position {projected} exceeds limit {limit}")]
+    Position { limit: Decimal, projected: Decimal },
+
+    #[error("projected {underlying} underlying position {projected} exceeds limit {limit}")]
+    UnderlyingPosition {
+        underlying: String,
+        limit: Decimal,
+        projected: Decimal,
+    },
+
+    #[error("{open} open orders on instrument reached limit {limit}")]
+    InstrumentOpenOrders { limit: usize, open: usize },
+
+    #[error("{open} open orders reached limit {limit}")]
+    OpenOrders { limit: usize, open: usize },
+
+    #[error("{rate} orders sent in the last second reached limit {limit}")]
+    OrderRate { limit: usize, rate: usize },
+
+    #[error("order-to-trade ratio {ratio} exceeds limit {limit}")]
+    OrderToTradeRatio { limit: Decimal, ratio: Decimal },
+
+    #[error("order would match own resting order {0}")]
+    SelfMatch(ClientOrderId),
+}
+
+impl RiskLimitBreach {
+    /// Name of the check that was breached, used as the [`RiskRefusalReason`] check name.
+    pub fn check_name(&self) -> &'static str {
+        match self {
+            Self::InstrumentSpec(_) => "instrument_spec",
+            Self::InstrumentNotAllowed(_) => "allowed_instruments",
+            Self::UnknownInstrument(_) => "unknown_instrument",
+            Self::MissingReferencePrice => "reference_price",
+            Self::OrderQuantity { .. } => "max_order_quantity",
+            Self::OrderNotional { .. } => "max_order_notional",
+            Self::NotionalOverflow { .. } => "order_notional",
+            Self::PriceCollar { .. } => "max_price_deviation",
+            Self::Position { .. } => "max_position",
+            Self::UnderlyingPosition { .. } => "max_underlying_position",
+            Self::InstrumentOpenOrders { .. } => "max_instrument_open_orders",
+            Self::OpenOrders { .. } => "max_open_orders",
+            Self::OrderRate { .. } => "max_orders_per_second",
+            Self::OrderToTradeRatio { .. } => "max_order_to_trade_ratio",
+            Self::SelfMatch(_) => "self_match_prevention",
+        }
+    }
+}
+
+impl From<RiskLimitBreach> for RiskRefusalReason {
+    fn from(breach: RiskLimitBreach) -> Self {
+        let check = breach.check_name();
+        match breach {
+            RiskLimitBreach::OrderQuantity { limit, quantity } => {
+                Self::breach(check, limit, quantity)
+            }
+            RiskLimitBreach::OrderNotional { limit, notional } => {
+                Self::breach(check, limit, notional)
+            }
+            RiskLimitBreach::PriceCollar {
+                limit, deviation, ..
+            } => Self::breach(check, limit, deviation),
+            RiskLimitBreach::Position { limit, projected }
+            | RiskLimitBreach::UnderlyingPosition {
+                limit, projected, ..
+            } => Self::breach(check, limit, projected.abs()),
+            RiskLimitBreach::InstrumentOpenOrders { limit, open }
+            | RiskLimitBreach::OpenOrders { limit, open }
+            | RiskLimitBreach::OrderRate { limit, rate: open } => {
+                Self::breach(check, Decimal::from(limit), Decimal::from(open))
+            }
+            RiskLimitBreach::OrderToTradeRatio { limit, ratio } => {
+                Self::breach(check, limit, ratio)
+            }
+            other => Self::refused(check, other.to_string()),
+        }
+    }
+}
+
+impl From<ExposureError> for RiskLimitBreach {
+    fn from(error: ExposureError) -> Self {
+        match error {
+            ExposureError::UnknownInstrument(instrument) => Self::UnknownInstrument(instrument),
+            ExposureError::MissingPrice(_) => Self::MissingReferencePrice,
+        }
+    }
+}
+
+/// Open requests approved earlier in the same [`LimitsRiskManager::check_opens`] batch.
+#[derive(Debug, Default)]
+struct PendingOpens {
+    instruments: FnvIndexMap<InstrumentIndex, (Decimal, usize)>,
+}
+
+impl PendingOpens {
+    fn record(&mut self, open: &OrderRequestOpen) {
+        let (position, orders) = self
+            .instruments
+            .entry(open.key.instrument.clone())
+            .or_default();
+        *position += signed_quantity(&open.state);
+        *orders += 1;
+    }
+
+    fn position(&self, instrument: &InstrumentIndex) -> Decimal {
+        self.instruments
+            .get(instrument)
+            .map_or(Decimal::ZERO, |(position, _)| *position)
+    }
+
+    fn instrument_orders(&self, instrument: &InstrumentIndex) -> usize {
+        self.instruments
+            .get(instrument)
+            .map_or(0, |(_, orders)| *orders)
+    }
+
+    fn orders(&self) -> usize {
+        self.instruments.values().map(|(_, orders)| orders).sum()
+    }
+}
+
+/// Signed net position quantity of an instrument (positive long, negative short).
+fn net_position<InstrumentData>(state: &InstrumentState<InstrumentData>) -> Decimal {
+    state
+        .position
+        .current
+        .as_ref()
+        .map_or(Decimal::ZERO, |position| match position.side {
+            Side::Buy => position.quantity_abs,
+            Side::Sell => -position.quantity_abs,
+        })
+}
+
+/// Returns `true` if the incoming order would match a resting limit order on the provided side
+/// and price.
+fn crosses(incoming: &RequestOpen, resting_side: Side, resting_price: Decimal) -> bool {
+    if incoming.side == resting_side {
+        return false;
+    }
+
+    match (incoming.kind, incoming.side) {
+        (OrderKind::Market, _) => true,
+        (OrderKind::Limit, Side::Buy) => incoming.price >= resting_price,
+        (OrderKind::Limit, Side::Sell) => incoming.price <= resting_price,
+    }
+}
+
+/// Instrument, signed quantity & order price of an open request, as traded by
+/// [`PortfolioExposure::projected_total`].
+///
+/// Market orders have no order price, since their request price is only a placeholder.
+fn exposure_trade(open: &OrderRequestOpen) -> (&InstrumentIndex, Decimal, Option<Decimal>) {
+    let price = match open.state.kind {
+        OrderKind::Limit => Some(open.state.price),
+        OrderKind::Market => None,
+    };
+
+    (&open.key.instrument, signed_quantity(&open.state), price)
+}
+
+fn signed_quantity(order: &RequestOpen) -> Decimal {
+    match order.side {
+        Side::Buy => order.quantity.abs(),
+        Side::Sell => -order.quantity.abs(),
+    }
+}
+
+/// Check the position projected by an order against an absolute limit, returning the projected
+/// position if breached. Orders reducing the absolute position are always allowed.
+fn check_position(current: Decimal, order: &RequestOpen, limit: Decimal) -> Result<(), Decimal> {
+    let projected = current + signed_quantity(order);
+    if projected.abs() > limit && projected.abs() > current.abs() {
+        Err(projected)
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Utc};
+    use rust_decimal_macros::dec;
+    use toucan_execution::order::TimeInForce;
+
+    fn order(side: Side, kind: OrderKind, price: Decimal, quantity: Decimal) -> RequestOpen {
+        RequestOpen {
+            side,
+            price,
+            quantity,
+            kind,
+            time_in_force: TimeInForce::GoodUntilCancelled { post_only: false },
+        }
+    }
+
+    fn exposure(reference_price: Option<Decimal>, position: Decimal) -> InstrumentExposure {
+        InstrumentExposure {
+            reference_price,
+            position,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_risk_limits_check() {
+        struct TestCase {
+            limits: RiskLimits,
+            order: RequestOpen,
+            exposure: InstrumentExposure,
+            contract_size: Decimal,
+            expected: Result<(), RiskLimitBreach>,
+        }
+
+        let cases = vec![
+            // TC0: no limits configured approves everything
+            TestCase {
+                limits: RiskLimits::default(),
+                order: order(Side::Buy, OrderKind::Limit, dec!(100), dec!(1000)),
+                exposure: exposure(None, dec!(0)),
+                contract_size: Decimal::ONE,
+                expected: Ok(()),
+            },
+            // TC1: order quantity above limit is refused
+            TestCase {
+                limits: RiskLimits {
+                    max_order_quantity: Some(dec!(100)),
+                    ..Default::default()
+                },
+                order: order(Side::Sell, OrderKind::Limit, dec!(10), dec!(200)),
+                exposure: exposure(Some(dec!(10)), dec!(0)),
+                contract_size: Decimal::ONE,
+                expected: Err(RiskLimitBreach::OrderQuantity {
+                    limit: dec!(100),
+                    quantity: dec!(200),
+                }),
+            },
+            // TC2: market order notional uses the reference price
+            TestCase {
+                limits: RiskLimits {
+                    max_order_notional: Some(dec!(1000)),
+                    ..Default::default()
+                },
+                order: order(Side::Buy, OrderKind::Market, dec!(0), dec!(20)),
+                exposure: exposure(Some(dec!(60)), dec!(0)),
+                contract_size: Decimal::ONE,
+                expected: Err(RiskLimitBreach::OrderNotional {
+                    limit: dec!(1000),
+                    notional: dec!(1200),
+                }),
+            },
+            // TC3: limit price outside the collar is refused
+            TestCase {
+                limits: RiskLimits {
+                    max_price_deviation: Some(dec!(0.05)),
+                    ..Default::default()
+                },
+                order: order(Side::Buy, OrderKind::Limit, dec!(110), dec!(1)),
+                exposure: exposure(Some(dec!(100)), dec!(0)),
+                contract_size: Decimal::ONE,
+                expected: Err(RiskLimitBreach::PriceCollar {
+                    limit: dec!(0.05),
+                    reference: dec!(100),
+                    price: dec!(110),
+                    deviation: dec!(0.1),
+                }),
+            },
+            // TC4: price collar without a reference price is refused
+            TestCase {
+                limits: RiskLimits {
+                    max_price_deviation: Some(dec!(0.05)),
+                    ..Default::default()
+                },
+                order: order(Side::Buy, OrderKind::Limit, dec!(100), dec!(1)),
+                exposure: exposure(None, dec!(0)),
+                contract_size: Decimal::ONE,
+                expected: Err(RiskLimitBreach::MissingReferencePrice),
+            },
+            // TC5: order increasing the position above the limit is refused
+            TestCase {
+                limits: RiskLimits {
+                    max_position: Some(dec!(100)),
+                    ..Default::default()
+                },
+                order: order(Side::Sell, OrderKind::Limit, dec!(10), dec!(30)),
+                exposure: exposure(Some(dec!(10)), dec!(-80)),
+                contract_size: Decimal::ONE,
+                expected: Err(RiskLimitBreach::Position {
+                    limit: dec!(100),
+                    projected: dec!(-110),
+                }),
+            },
+            // TC6: order reducing a position already above the limit is approved
+            TestCase {
+                limits: RiskLimits {
+                    max_position: Some(dec!(100)),
+                    ..Default::default()
+                },
+                order: order(Side::Buy, OrderKind::Limit, dec!(10), dec!(30)),
+                exposure: exposure(Some(dec!(10)), dec!(-150)),
+                contract_size: Decimal::ONE,
+                expected: Ok(()),
+            },
+            // TC7: open orders at the limit are refused
+            TestCase {
+                limits: RiskLimits {
+                    max_open_orders: Some(2),
+                    ..Default::default()
+                },
+                order: order(Side::Buy, OrderKind::Limit, dec!(10), dec!(1)),
+                exposure: InstrumentExposure {
+                    reference_price: Some(dec!(10)),
+                    position: dec!(0),
+                    open_orders: 2,
+                    ..Default::default()
+                },
+                contract_size: Decimal::ONE,
+                expected: Err(RiskLimitBreach::InstrumentOpenOrders { limit: 2, open: 2 }),
+            },
+            // TC8: orders sent in the last second at the rate limit are refused
+            TestCase {
+                limits: RiskLimits {
+                    max_orders_per_second: Some(5),
+                    ..Default::default()
+                },
+                order: order(Side::Buy, OrderKind::Limit, dec!(10), dec!(1)),
+                exposure: InstrumentExposure {
+                    reference_price: Some(dec!(10)),
+                    open_rate: 5,
+                    ..Default::default()
+                },
+                contract_size: Decimal::ONE,
+                expected: Err(RiskLimitBreach::OrderRate { limit: 5, rate: 5 }),
+            },
+            // TC9: order-to-trade ratio above the limit is refused
+            TestCase {
+                limits: RiskLimits {
+                    max_order_to_trade_ratio: Some(dec!(20)),
+                    ..Default::default()
+                },
+                order: order(Side::Sell, OrderKind::Limit, dec!(10), dec!(1)),
+                exposure: InstrumentExposure {
+                    reference_price: Some(dec!(10)),
+                    order_to_trade_ratio: dec!(25),
+                    ..Default::default()
+                },
+                contract_size: Decimal::ONE,
+                expected: Err(RiskLimitBreach::OrderToTradeRatio {
+                    limit: dec!(20),
+                    ratio: dec!(25),
+                }),
+            },
+            // TC10: order notional is scaled by the contract size
+            TestCase {
+                limits: RiskLimits {
+                    max_order_notional: Some(dec!(100000)),
+                    ..Default::default()
+                },
+                order: order(Side::Buy, OrderKind::Limit, dec!(5000), dec!(1)),
+                exposure: exposure(Some(dec!(5000)), dec!(0)),
+                contract_size: dec!(50),
+                expected: Err(RiskLimitBreach::OrderNotional {
+                    limit: dec!(100000),
+                    notional: dec!(250000),
+                }),
+            },
+            // TC11: order notional overflow is refused as such, not as a missing price
+            TestCase {
+                limits: RiskLimits {
+                    max_order_notional: Some(dec!(1000)),
+                    ..Default::default()
+                },
+                order: order(Side::Buy, OrderKind::Limit, Decimal::MAX, dec!(2)),
+                exposure: exposure(Some(dec!(10)), dec!(0)),
+                contract_size: Decimal::ONE,
+                expected: Err(RiskLimitBreach::NotionalOverflow {
+                    quantity: dec!(2),
+                    price: Decimal::MAX,
+                    contract_size: Decimal::ONE,
+                }),
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let actual = test
+                .limits
+                .check(&test.order, &test.exposure, test.contract_size);
+            assert_eq!(actual, test.expected, "TC{index} failed");
+        }
+    }
+
+    #[test]
+    fn test_limits_risk_manager_apply_spec() {
+        use toucan_execution::order::{
+            id::{ClientOrderId, StrategyId},
+            OrderKey,
+        };
+        use toucan_instrument::instrument::spec::{
+            InstrumentSpecNotional, InstrumentSpecPrice, InstrumentSpecQuantity, OrderQuantityUnits,
+        };
+
+        struct TestCase {
+            rounding: Option<SpecRounding>,
+            order: RequestOpen,
+            expected: Result<(Decimal, Decimal), RiskLimitBreach>,
+        }
+
+        let spec = InstrumentSpec::new(
+            InstrumentSpecPrice::new(dec!(0.01), dec!(0.01)),
+            InstrumentSpecQuantity::new(OrderQuantityUnits::Contract, dec!(100), dec!(100)),
+            InstrumentSpecNotional::new(dec!(0)),
+        );
+
+        let cases = vec![
+            // TC0: strict validation refuses a price off the tick
+            TestCase {
+                rounding: None,
+                order: order(Side::Buy, OrderKind::Limit, dec!(10.005), dec!(100)),
+                expected: Err(RiskLimitBreach::InstrumentSpec(
+                    InstrumentSpecError::PriceTick {
+                        price: dec!(10.005),
+                        tick_size: dec!(0.01),
+                    },
+                )),
+            },
+            // TC1: normalisation rounds a passive sell price up & quantity down
+            TestCase {
+                rounding: Some(SpecRounding::default()),
+                order: order(Side::Sell, OrderKind::Limit, dec!(10.005), dec!(250)),
+                expected: Ok((dec!(10.01), dec!(200))),
+            },
+            // TC2: market order price is not rounded
+            TestCase {
+                rounding: Some(SpecRounding::default()),
+                order: order(Side::Buy, OrderKind::Market, dec!(10.005), dec!(199)),
+                expected: Ok((dec!(10.005), dec!(100))),
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let manager = LimitsRiskManager::<()>::default().with_instrument_spec(
+                InstrumentIndex::from("PETR4"),
+                spec.clone(),
+                Decimal::ONE,
+            );
+            let manager = match test.rounding {
+                Some(rounding) => manager.with_spec_rounding(rounding),
+                None => manager,
+            };
+
+            let mut open = OrderRequestOpen {
+                key: OrderKey {
+                    exchange: "b3".to_string(),
+                    instrument: InstrumentIndex::from("PETR4"),
+                    strategy: StrategyId::new("test"),
+                    cid: ClientOrderId::new("cid"),
+                },
+                state: test.order,
+            };
+
+            let actual = manager
+                .apply_spec(&mut open)
+                .map(|()| (open.state.price, open.state.quantity));
+            assert_eq!(actual, test.expected, "TC{index} failed");
+        }
+    }
+
+    #[test]
+    fn test_limits_risk_manager_prevent_self_match() {
+        use toucan_execution::order::{
+            id::{OrderId, StrategyId},
+            state::{CancelInFlight, Open},
+            OrderKey,
+        };
+
+        fn key(cid: &str) -> OrderKey {
+            OrderKey {
+                exchange: "b3".to_string(),
+                instrument: InstrumentIndex::from("PETR4"),
+                strategy: StrategyId::new("test"),
+                cid: ClientOrderId::new(cid),
+            }
+        }
+
+        fn open(cid: &str, side: Side, price: Decimal) -> RiskApproved<OrderRequestOpen> {
+            RiskApproved::new(OrderRequestOpen {
+                key: key(cid),
+                state: order(side, OrderKind::Limit, price, dec!(100)),
+            })
+        }
+
+        fn cancel(cid: &str) -> RiskApproved<OrderRequestCancel> {
+            RiskApproved::new(OrderRequestCancel {
+                key: key(cid),
+                state: toucan_execution::order::request::RequestCancel {
+                    id: Some(OrderId::new(cid)),
+                },
+            })
+        }
+
+        fn resting(
+            cid: &str,
+            side: Side,
+            price: Decimal,
+            state: ActiveOrderState,
+        ) -> Order<ExchangeIndex, InstrumentIndex, ActiveOrderState> {
+            Order {
+                key: key(cid),
+                side,
+                price,
+                quantity: dec!(100),
+                kind: OrderKind::Limit,
+                time_in_force: TimeInForce::GoodUntilCancelled { post_only: false },
+                state,
+            }
+        }
+
+        struct TestCase {
+            mode: Option<SelfMatchMode>,
+            opens: Vec<RiskApproved<OrderRequestOpen>>,
+            expected_approved: Vec<&'static str>,
+            expected_cancels: Vec<RiskApproved<OrderRequestCancel>>,
+            expected_refused: Vec<(&'static str, &'static str)>,
+        }
+
+        let open_state = |cid: &str| {
+            ActiveOrderState::Open(Open::new(
+                OrderId::new(cid),
+                DateTime::<Utc>::MIN_UTC,
+                dec!(0),
+            ))
+        };
+        let orders = [
+            resting("ask", Side::Sell, dec!(10.05), open_state("ask")),
+            resting(
+                "ask_cancelling",
+                Side::Sell,
+                dec!(10.00),
+                ActiveOrderState::CancelInFlight(CancelInFlight { order: None }),
+            ),
+            resting("bid", Side::Buy, dec!(9.95), open_state("bid")),
+        ];
+
+        let cases = vec![
+            // TC0: no SelfMatchMode configured approves crossing opens
+            TestCase {
+                mode: None,
+                opens: vec![open("buy", Side::Buy, dec!(10.10))],
+                expected_approved: vec!["buy"],
+                expected_cancels: vec![],
+                expected_refused: vec![],
+            },
+            // TC1: opens not crossing resting orders are approved
+            TestCase {
+                mode: Some(SelfMatchMode::CancelIncoming),
+                opens: vec![open("buy", Side::Buy, dec!(10.00))],
+                expected_approved: vec!["buy"],
+                expected_cancels: vec![],
+                expected_refused: vec![],
+            },
+            // TC2: CancelResting approves the open & cancels the crossed resting order
+            TestCase {
+                mode: Some(SelfMatchMode::CancelResting),
+                opens: vec![open("buy", Side::Buy, dec!(10.05))],
+                expected_approved: vec!["buy"],
+                expected_cancels: vec![cancel("ask")],
+                expected_refused: vec![],
+            },
+            // TC3: CancelIncoming refuses the open & keeps the resting order
+            TestCase {
+                mode: Some(SelfMatchMode::CancelIncoming),
+                opens: vec![open("sell", Side::Sell, dec!(9.90))],
+                expected_approved: vec![],
+                expected_cancels: vec![],
+                expected_refused: vec![("sell", "bid")],
+            },
+            // TC4: Reject refuses the open & keeps the resting order
+            TestCase {
+                mode: Some(SelfMatchMode::Reject),
+                opens: vec![open("sell", Side::Sell, dec!(9.90))],
+                expected_approved: vec![],
+                expected_cancels: vec![],
+                expected_refused: vec![("sell", "bid")],
+            },
+            // TC5: CancelBoth refuses the open & cancels the resting order
+            TestCase {
+                mode: Some(SelfMatchMode::CancelBoth),
+                opens: vec![open("sell", Side::Sell, dec!(9.90))],
+                expected_approved: vec![],
+                expected_cancels: vec![cancel("bid")],
+                expected_refused: vec![("sell", "bid")],
+            },
+            // TC6: Reject refuses a market open crossing every resting order, cancelling none
+            TestCase {
+                mode: Some(SelfMatchMode::Reject),
+                opens: vec![RiskApproved::new(OrderRequestOpen {
+                    key: key("market"),
+                    state: order(Side::Buy, OrderKind::Market, dec!(0), dec!(100)),
+                })],
+                expected_approved: vec![],
+                expected_cancels: vec![],
+                expected_refused: vec![("market", "ask")],
+            },
+            // TC7: open crossing an open earlier in the batch is refused
+            TestCase {
+                mode: Some(SelfMatchMode::CancelResting),
+                opens: vec![
+                    open("buy", Side::Buy, dec!(10.00)),
+                    open("sell", Side::Sell, dec!(10.00)),
+                ],
+                expected_approved: vec!["buy"],
+                expected_cancels: vec![],
+                expected_refused: vec![("sell", "buy")],
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let manager = match test.mode {
+                Some(mode) => LimitsRiskManager::<()>::default().with_self_match_prevention(mode),
+                None => LimitsRiskManager::<()>::default(),
+            };
+
+            let output = manager.prevent_self_match(|_| orders.iter(), test.opens);
+
+            let approved = output
+                .approved
+                .iter()
+                .map(|RiskApproved(open)| open.key.cid.0.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(approved, test.expected_approved, "TC{index} failed");
+            assert_eq!(output.cancels, test.expected_cancels, "TC{index} failed");
+
+            let refused = output
+                .refused
+                .iter()
+                .map(|refused| match &refused.reason {
+                    RiskLimitBreach::SelfMatch(resting) => {
+                        (refused.item.key.cid.0.as_str(), resting.0.as_str())
+                    }
+                    other => panic!("TC{index} failed: unexpected {other}"),
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(refused, test.expected_refused, "TC{index} failed");
+        }
+    }
+
+    #[test]
+    fn test_risk_limit_breach_into_reason() {
+        struct TestCase {
+            breach: RiskLimitBreach,
+            expected: RiskRefusalReason,
+        }
+
+        let cases = vec![
+            // TC0: limit breach carries the limit & observed value
+            TestCase {
+                breach: RiskLimitBreach::OrderQuantity {
+                    limit: dec!(100),
+                    quantity: dec!(200),
+                },
+                expected: RiskRefusalReason::breach("max_order_quantity", dec!(100), dec!(200)),
+            },
+            // TC1: short position breach observes the absolute projected position
+            TestCase {
+                breach: RiskLimitBreach::Position {
+                    limit: dec!(100),
+                    projected: dec!(-110),
+                },
+                expected: RiskRefusalReason::breach("max_position", dec!(100), dec!(110)),
+            },
+            // TC2: open order counts are converted to decimals
+            TestCase {
+                breach: RiskLimitBreach::OpenOrders { limit: 5, open: 5 },
+                expected: RiskRefusalReason::breach("max_open_orders", dec!(5), dec!(5)),
+            },
+            // TC3: breach without a numeric limit is refused with its message
+            TestCase {
+                breach: RiskLimitBreach::MissingReferencePrice,
+                expected: RiskRefusalReason::refused(
+                    "reference_price",
+                    "no reference price available to evaluate the order",
+                ),
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let actual = RiskRefusalReason::from(test.breach);
+            assert_eq!(actual, test.expected, "TC{index} failed");
+        }
+    }
+
+    #[test]
+    fn test_risk_limits_or() {
+        let instrument = RiskLimits {
+            max_position: Some(dec!(10)),
+            ..Default::default()
+        };
+        let global = RiskLimits {
+            max_order_quantity: Some(dec!(5)),
+            max_position: Some(dec!(100)),
+            ..Default::default()
+        };
+
+        let actual = instrument.or(global);
+
+        assert_eq!(actual.max_position, Some(dec!(10)));
+        assert_eq!(actual.max_order_quantity, Some(dec!(5)));
+        assert_eq!(actual.max_open_orders, None);
+    }
+
+    #[test]
+    fn test_risk_limits_order_check_pipeline() {
+        use crate::engine::state::{
+            global::DefaultGlobalData, instrument::data::DefaultInstrumentMarketData,
+            IndexedInstruments,
+        };
+        use toucan_execution::order::{id::StrategyId, OrderKey};
+        use toucan_instrument::{exchange::ExchangeId, ConcreteInstrument, Keyed};
+        use toucan_risk::RiskCheckManager;
+
+        fn open(
+            cid: &str,
+            instrument: &str,
+            price: Decimal,
+            quantity: Decimal,
+        ) -> OrderRequestOpen {
+            OrderRequestOpen {
+                key: OrderKey {
+                    exchange: "b3".to_string(),
+                    instrument: InstrumentIndex::from(instrument),
+                    strategy: StrategyId::new("test"),
+                    cid: ClientOrderId::new(cid),
+                },
+                state: order(Side::Buy, OrderKind::Limit, price, quantity),
+            }
+        }
+
+        struct TestCase {
+            opens: Vec<OrderRequestOpen>,
+            expected_approved: Vec<&'static str>,
+            expected_refused: Vec<(&'static str, RiskRefusalReason)>,
+        }
+
+        let instruments: IndexedInstruments = ["PETR4", "WINFUT"]
+            .into_iter()
+            .map(|instrument| {
+                Keyed::new(
+                    instrument.to_string(),
+                    ConcreteInstrument {
+                        symbol: instrument.into(),
+                        market: "spot".into(),
+                        exchange: ExchangeId::Mock,
+                        underlying: None,
+                        name_exchange: instrument.into(),
+                    },
+                )
+            })
+            .collect();
+        let state = EngineState::builder(&instruments, DefaultGlobalData, |_| {
+            DefaultInstrumentMarketData::default()
+        })
+        .build();
+
+        let risk = RiskCheckManager::new(RiskLimits {
+            max_order_notional: Some(dec!(30000)),
+            max_position: Some(dec!(10)),
+            ..Default::default()
+        })
+        .with_contract_size(InstrumentIndex::from("WINFUT"), dec!(0.2));
+
+        let cases = vec![
+            // TC0: notional is scaled by the contract size of the instrument
+            TestCase {
+                opens: vec![
+                    open("win", "WINFUT", dec!(120000), dec!(1)),
+                    open("petr4", "PETR4", dec!(12000), dec!(5)),
+                ],
+                expected_approved: vec!["win"],
+                expected_refused: vec![(
+                    "petr4",
+                    RiskRefusalReason::breach("max_order_notional", dec!(30000), dec!(60000)),
+                )],
+            },
+            // TC1: opens within the position limit on their own breach it together
+            TestCase {
+                opens: vec![
+                    open("first", "PETR4", dec!(30), dec!(6)),
+                    open("second", "PETR4", dec!(30), dec!(6)),
+                ],
+                expected_approved: vec!["first"],
+                expected_refused: vec![(
+                    "second",
+                    RiskRefusalReason::breach("max_position", dec!(10), dec!(12)),
+                )],
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let (_, approved, _, refused) =
+                RiskManager::check(&risk, &state, std::iter::empty(), test.opens);
+
+            let approved = approved
+                .into_iter()
+                .map(|RiskApproved(open)| open.key.cid.0)
+                .collect::<Vec<_>>();
+            assert_eq!(approved, test.expected_approved, "TC{index} failed");
+
+            let refused = refused
+                .into_iter()
+                .map(|refused| (refused.item.key.cid.0, refused.reason))
+                .collect::<Vec<_>>();
+            let expected_refused = test
+                .expected_refused
+                .into_iter()
+                .map(|(cid, reason)| (cid.into(), reason))
+                .collect::<Vec<_>>();
+            assert_eq!(refused, expected_refused, "TC{index} failed");
+        }
+    }
+
+    #[test]
+    fn test_exposure_limits_check() {
+        struct TestCase {
+            current: Exposure,
+            projected: Exposure,
+            expected: Result<(), RiskRefusalReason>,
+        }
+
+        let limits = ExposureLimits {
+            max_gross_exposure: Some(dec!(1000)),
+            max_net_exposure: Some(dec!(500)),
+        };
+
+        let exposure = |long, short| Exposure { long, short };
+
+        let cases = vec![
+            // TC0: projected exposure within limits
+            TestCase {
+                current: exposure(dec!(0), dec!(0)),
+                projected: exposure(dec!(400), dec!(300)),
+                expected: Ok(()),
+            },
+            // TC1: projected gross exposure breaches limit
+            TestCase {
+                current: exposure(dec!(400), dec!(300)),
+                projected: exposure(dec!(800), dec!(300)),
+                expected: Err(RiskRefusalReason::breach(
+                    "max_gross_exposure",
+                    dec!(1000),
+                    dec!(1100),
+                )),
+            },
+            // TC2: projected short net exposure breaches limit
+            TestCase {
+                current: exposure(dec!(100), dec!(300)),
+                projected: exposure(dec!(100), dec!(700)),
+                expected: Err(RiskRefusalReason::breach(
+                    "max_net_exposure",
+                    dec!(500),
+                    dec!(600),
+                )),
+            },
+            // TC3: reducing exposure already above the limits is approved
+            TestCase {
+                current: exposure(dec!(1500), dec!(0)),
+                projected: exposure(dec!(1200), dec!(0)),
+                expected: Ok(()),
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let actual = limits.check(&test.current, &test.projected);
+            assert_eq!(actual, test.expected, "TC{index} failed");
+        }
+    }
+}
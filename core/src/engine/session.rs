@@ -0,0 +1,243 @@
+use crate::engine::{command::Command, state::trading::TradingState};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use toucan_markets::calendar::{ExchangeCalendar, SessionPhase, TradingCalendar};
+use tracing::info;
+
+/// Trading session gate that drives the `Engine` [`TradingState`] from a venue
+/// [`ExchangeCalendar`].
+///
+/// Whilst processing every event, the `Engine` evaluates the current [`SessionPhase`] using the
+/// [`EngineClock`](super::clock::EngineClock) time:
+/// - Outside the `allowed_phases`, `TradingState::Enabled` is moved to `TradingState::Disabled`.
+/// - On re-entering an allowed phase, `TradingState::Enabled` is restored only if it was the
+///   session gate that disabled trading (ie/ a manual disable is respected).
+/// - On leaving the allowed phases, the `end_of_session` [`Command`]s are actioned (eg/
+///   `CancelOrders`, `ClosePositions`).
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SessionControl {
+    pub calendar: ExchangeCalendar,
+    pub allowed_phases: Vec<SessionPhase>,
+    pub end_of_session: Vec<Command>,
+
+    /// Last observed [`SessionPhase`], or `None` if not yet evaluated.
+    pub phase: Option<SessionPhase>,
+
+    /// `true` if the session gate moved the `TradingState` to `TradingState::Disabled`.
+    pub disabled_by_session: bool,
+}
+
+impl SessionControl {
+    /// Construct a new [`SessionControl`] that only allows [`SessionPhase::Continuous`] trading,
+    /// with no end-of-session actions.
+    pub fn new(calendar: ExchangeCalendar) -> Self {
+        Self {
+            calendar,
+            allowed_phases: vec![SessionPhase::Continuous],
+            end_of_session: vec![],
+            phase: None,
+            disabled_by_session: false,
+        }
+    }
+
+    /// Set the [`SessionPhase`]s algorithmic trading is allowed in.
+    pub fn with_allowed_phases<PhaseIter>(self, phases: PhaseIter) -> Self
+    where
+        PhaseIter: IntoIterator<Item = SessionPhase>,
+    {
+        Self {
+            allowed_phases: phases.into_iter().collect(),
+            ..self
+        }
+    }
+
+    /// Set the [`Command`]s the `Engine` actions when the session leaves the allowed phases.
+    pub fn with_end_of_session<CommandIter>(self, commands: CommandIter) -> Self
+    where
+        CommandIter: IntoIterator<Item = Command>,
+    {
+        Self {
+            end_of_session: commands.into_iter().collect(),
+            ..self
+        }
+    }
+
+    /// Returns `true` if algorithmic trading is allowed in the provided [`SessionPhase`].
+    pub fn is_allowed(&self, phase: SessionPhase) -> bool {
+        self.allowed_phases.contains(&phase)
+    }
+
+    /// Evaluate the session at the provided time, given the current `Engine` [`TradingState`].
+    pub fn update(&mut self, time: DateTime<Utc>, trading: TradingState) -> SessionUpdate {
+        let phase = self.calendar.phase(time);
+        let prev = self.phase.replace(phase);
+        let allowed = self.is_allowed(phase);
+
+        let transition = (prev != Some(phase)).then(|| {
+            info!(
+                calendar = %self.calendar.name,
+                from = ?prev,
+                to = ?phase,
+                %time,
+                "Engine trading session phase transition"
+            );
+            SessionTransition {
+                from: prev,
+                to: phase,
+                time,
+            }
+        });
+
+        let trading_state = match (allowed, trading, self.disabled_by_session) {
+            (false, TradingState::Enabled, _) => {
+                self.disabled_by_session = true;
+                Some(TradingState::Disabled)
+            }
+            (true, TradingState::Disabled, true) => {
+                self.disabled_by_session = false;
+                Some(TradingState::Enabled)
+            }
+            (true, _, _) => {
+                self.disabled_by_session = false;
+                None
+            }
+            _ => None,
+        };
+
+        let end_of_session = !allowed && prev.is_some_and(|prev| self.is_allowed(prev));
+
+        SessionUpdate {
+            transition,
+            trading_state,
+            end_of_session,
+        }
+    }
+}
+
+/// Result of evaluating a [`SessionControl`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub struct SessionUpdate {
+    /// [`SessionTransition`], if the [`SessionPhase`] changed.
+    pub transition: Option<SessionTransition>,
+
+    /// [`TradingState`] the `Engine` should move to, if any.
+    pub trading_state: Option<TradingState>,
+
+    /// `true` if the session just left the allowed phases, and the `end_of_session`
+    /// [`Command`]s should be actioned.
+    pub end_of_session: bool,
+}
+
+/// Record of a trading session [`SessionPhase`] transition.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub struct SessionTransition {
+    pub from: Option<SessionPhase>,
+    pub to: SessionPhase,
+    pub time: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::state::instrument::filter::InstrumentFilter;
+
+    fn time(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_session_control_update() {
+        struct TestCase {
+            time: &'static str,
+            trading: TradingState,
+            expected: SessionUpdate,
+        }
+
+        let mut session = SessionControl::new(ExchangeCalendar::b3_equities())
+            .with_end_of_session([Command::CancelOrders(InstrumentFilter::None)]);
+
+        let cases = vec![
+            // TC0: first evaluation during pre-opening disables trading
+            TestCase {
+                time: "2024-03-15T12:50:00Z",
+                trading: TradingState::Enabled,
+                expected: SessionUpdate {
+                    transition: Some(SessionTransition {
+                        from: None,
+                        to: SessionPhase::PreOpening,
+                        time: time("2024-03-15T12:50:00Z"),
+                    }),
+                    trading_state: Some(TradingState::Disabled),
+                    end_of_session: false,
+                },
+            },
+            // TC1: continuous trading re-enables trading disabled by the session
+            TestCase {
+                time: "2024-03-15T13:00:00Z",
+                trading: TradingState::Disabled,
+                expected: SessionUpdate {
+                    transition: Some(SessionTransition {
+                        from: Some(SessionPhase::PreOpening),
+                        to: SessionPhase::Continuous,
+                        time: time("2024-03-15T13:00:00Z"),
+                    }),
+                    trading_state: Some(TradingState::Enabled),
+                    end_of_session: false,
+                },
+            },
+            // TC2: no phase change, no action
+            TestCase {
+                time: "2024-03-15T15:00:00Z",
+                trading: TradingState::Enabled,
+                expected: SessionUpdate {
+                    transition: None,
+                    trading_state: None,
+                    end_of_session: false,
+                },
+            },
+            // TC3: closing call ends the session
+            TestCase {
+                time: "2024-03-15T19:56:00Z",
+                trading: TradingState::Enabled,
+                expected: SessionUpdate {
+                    transition: Some(SessionTransition {
+                        from: Some(SessionPhase::Continuous),
+                        to: SessionPhase::ClosingCall,
+                        time: time("2024-03-15T19:56:00Z"),
+                    }),
+                    trading_state: Some(TradingState::Disabled),
+                    end_of_session: true,
+                },
+            },
+            // TC4: manual enable outside the allowed phases is reverted
+            TestCase {
+                time: "2024-03-15T19:57:00Z",
+                trading: TradingState::Enabled,
+                expected: SessionUpdate {
+                    transition: None,
+                    trading_state: Some(TradingState::Disabled),
+                    end_of_session: false,
+                },
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let actual = session.update(time(test.time), test.trading);
+            assert_eq!(actual, test.expected, "TC{index} failed");
+        }
+    }
+
+    #[test]
+    fn test_session_control_respects_manual_disable() {
+        let mut session = SessionControl::new(ExchangeCalendar::b3_equities());
+
+        // Manually disabled during continuous trading
+        let update = session.update(time("2024-03-15T14:00:00Z"), TradingState::Disabled);
+        assert_eq!(update.trading_state, None);
+
+        // Session closes, then re-opens on the next trading day
+        let _ = session.update(time("2024-03-15T21:00:00Z"), TradingState::Disabled);
+        let update = session.update(time("2024-03-18T14:00:00Z"), TradingState::Disabled);
+        assert_eq!(update.trading_state, None);
+    }
+}
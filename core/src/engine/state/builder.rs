@@ -2,11 +2,23 @@
 use crate::engine::state::{
     asset::generate_empty_indexed_asset_states,
     connectivity::generate_empty_indexed_connectivity_states,
-    instrument::generate_indexed_instrument_states, order::Orders, position::PositionManager,
-    trading::TradingState, EngineState,
+    instrument::generate_indexed_instrument_states,
+    order::{flow::OrderFlows, Orders},
+    position::PositionManager,
+    trading::TradingState,
+    EngineState,
+};
+use crate::engine::{
+    kill_switch::KillSwitch,
+    latency::LatencyTracker,
+    reaction::Reactions,
+    session::SessionControl,
+    state::connectivity::stale::StaleFeedMonitor,
+    timer::{Timer, Timers},
 };
 use chrono::{DateTime, Utc};
 use fnv::FnvHashMap;
+use toucan_analytics::summary::risk::RiskRefusalSummary;
 use tracing::debug;
 use toucan_execution::{
     balance::{AssetBalance, Balance},
@@ -27,6 +39,12 @@ pub struct EngineStateBuilder<'a, GlobalData, FnInstrumentData> {
     time_engine_start: Option<DateTime<Utc>>,
     global: GlobalData,
     balances: FnvHashMap<AssetNameInternal, Balance>,
+    timers: Timers,
+    session: Option<SessionControl>,
+    kill_switch: Option<KillSwitch>,
+    latency: Option<LatencyTracker>,
+    stale_feeds: Option<StaleFeedMonitor>,
+    reactions: Option<Reactions>,
     instrument_data_init: FnInstrumentData,
 }
 
@@ -49,6 +67,12 @@ impl<'a, GlobalData, FnInstrumentData> EngineStateBuilder<'a, GlobalData, FnInst
             trading_state: None,
             global,
             balances: FnvHashMap::default(),
+            timers: Timers::default(),
+            session: None,
+            kill_switch: None,
+            latency: None,
+            stale_feeds: None,
+            reactions: None,
             instrument_data_init,
         }
     }
@@ -91,6 +115,70 @@ impl<'a, GlobalData, FnInstrumentData> EngineStateBuilder<'a, GlobalData, FnInst
         self
     }
 
+    /// Optionally provide initial `Engine` [`Timer`]s.
+    ///
+    /// Note that `Timer`s with duplicate `TimerId`s replace the previously provided `Timer`.
+    pub fn timers<TimerIter>(mut self, timers: TimerIter) -> Self
+    where
+        TimerIter: IntoIterator<Item = Timer>,
+    {
+        timers.into_iter().for_each(|timer| {
+            self.timers.schedule(timer);
+        });
+        self
+    }
+
+    /// Optionally provide a trading [`SessionControl`] that gates the `TradingState` by venue
+    /// session phase.
+    ///
+    /// Defaults to `None` (no session gating).
+    pub fn session(self, value: SessionControl) -> Self {
+        Self {
+            session: Some(value),
+            ..self
+        }
+    }
+
+    /// Optionally provide an automatic [`KillSwitch`] that disables trading when its limits are
+    /// breached.
+    ///
+    /// Defaults to `None` (no automatic kill switch).
+    pub fn kill_switch(self, value: KillSwitch) -> Self {
+        Self {
+            kill_switch: Some(value),
+            ..self
+        }
+    }
+
+    /// Optionally provide a [`LatencyTracker`] that records tick-to-trade latency histograms per
+    /// exchange and per strategy.
+    ///
+    /// Defaults to `None` (no latency tracking).
+    pub fn latency(self, value: LatencyTracker) -> Self {
+        Self {
+            latency: Some(value),
+            ..self
+        }
+    }
+
+    /// Optionally provide a [`StaleFeedMonitor`] that marks market data & account feeds that stop
+    /// producing events as stale.
+    pub fn stale_feeds(self, value: StaleFeedMonitor) -> Self {
+        Self {
+            stale_feeds: Some(value),
+            ..self
+        }
+    }
+
+    /// Optionally provide ready-made [`Reactions`] (eg/ cancel orders, close positions, pause
+    /// new orders) run on the `OnDisconnectStrategy` and `OnTradingDisabled` hooks.
+    pub fn reactions(self, value: Reactions) -> Self {
+        Self {
+            reactions: Some(value),
+            ..self
+        }
+    }
+
     /// Use the builder data to generate the associated [`EngineState`].
     ///
     /// If optional data is not provided (eg/ Balances), default values are used (eg/ zero Balance).
@@ -104,6 +192,12 @@ impl<'a, GlobalData, FnInstrumentData> EngineStateBuilder<'a, GlobalData, FnInst
             trading_state,
             global,
             balances,
+            timers,
+            session,
+            kill_switch,
+            latency,
+            stale_feeds,
+            reactions,
             instrument_data_init,
         } = self;
 
@@ -154,6 +248,15 @@ impl<'a, GlobalData, FnInstrumentData> EngineStateBuilder<'a, GlobalData, FnInst
             connectivity,
             assets,
             instruments,
+            timers,
+            session,
+            kill_switch,
+            latency,
+            stale_feeds,
+            reactions,
+            shutdown: None,
+            risk_refusals: RiskRefusalSummary::default(),
+            order_flow: OrderFlows::default(),
         }
     }
 }
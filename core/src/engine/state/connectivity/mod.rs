@@ -8,6 +8,9 @@ use toucan_instrument::exchange::ExchangeId;
 /// Placeholder for IndexedInstruments - reused from parent module
 use super::{IndexedInstruments, IndexedInstrumentsExt};
 
+/// Stale feed detection that marks silent market data & account feeds as [`Health::Stale`].
+pub mod stale;
+
 /// Maintains a global connection [`Health`], as well as the connection status of market data
 /// and account connections for each exchange.
 #[derive(Debug, Clone, Eq, PartialEq, Default, Deserialize, Serialize)]
@@ -33,6 +36,15 @@ impl ConnectivityStates {
         self.connectivity_mut(exchange).account = Health::Reconnecting;
     }
 
+    /// Updates from an exchange AccountStream that stopped producing events.
+    ///
+    /// Sets the account `ConnectivityState` for the provided `ExchangeId` to [`Health::Stale`].
+    pub fn update_from_account_stale(&mut self, exchange: &ExchangeId) {
+        warn!(%exchange, "EngineState detected stale AccountStream");
+        self.global = Health::Stale;
+        self.connectivity_mut(exchange).account = Health::Stale;
+    }
+
     /// Updates from an exchange AccountStream event, setting the `ConnectivityState` account
     /// connection to [`Health::Healthy`] if it was not previously.
     ///
@@ -71,6 +83,16 @@ impl ConnectivityStates {
         self.connectivity_mut(exchange).market_data = Health::Reconnecting
     }
 
+    /// Updates from an exchange MarketStream that stopped producing events.
+    ///
+    /// Sets the market data `ConnectivityState` for the provided `ExchangeId` to
+    /// [`Health::Stale`].
+    pub fn update_from_market_stale(&mut self, exchange: &ExchangeId) {
+        warn!(%exchange, "EngineState detected stale MarketStream");
+        self.global = Health::Stale;
+        self.connectivity_mut(exchange).market_data = Health::Stale
+    }
+
     /// Updates from an exchange MarketStream event, setting the `ConnectivityState` market data
     /// connection to [`Health::Healthy`] if it was not previously.
     ///
@@ -153,6 +175,9 @@ pub enum Health {
 
     /// Connection is currently attempting to re-establish after a disconnect or failure.
     Reconnecting,
+
+    /// Connection has not produced an event within its configured staleness threshold.
+    Stale,
 }
 
 /// Represents the current connection state for both market data and account connections of an
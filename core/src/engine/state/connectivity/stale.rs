@@ -0,0 +1,379 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use toucan_execution::{ExchangeIndex, InstrumentIndex};
+use toucan_instrument::exchange::ExchangeId;
+use toucan_integration::collection::{FnvIndexMap, FnvIndexSet};
+use tracing::warn;
+
+/// Detects market data & account feeds that silently stop producing events.
+///
+/// The `Engine` records a heartbeat for every market and account event it processes, and
+/// evaluates the configured staleness thresholds against the `EngineClock` after every event.
+/// Note that a silent feed produces no events, so a periodic `TimerTick` (see
+/// `SystemBuilder::timer_interval`) should be configured for live trading.
+///
+/// A feed breaching its threshold is marked [`Health::Stale`](super::Health::Stale) until its
+/// next event is received, and the `Engine` calls the configured `OnDisconnectStrategy`. Every
+/// breach counts towards the optional `halt_after` limit, which disables trading once reached.
+///
+/// Once halted, the `StaleFeedMonitor` is latched until it is re-armed by a manual
+/// `TradingState::Enabled` update.
+#[derive(Debug, Clone, Eq, PartialEq, Default, Deserialize, Serialize)]
+pub struct StaleFeedMonitor {
+    /// Thresholds applied to every exchange without an override.
+    pub thresholds: StaleThresholds,
+
+    /// Thresholds applied to individual exchanges.
+    pub exchanges: FnvIndexMap<ExchangeId, StaleThresholds>,
+
+    /// Market data thresholds applied to individual instruments.
+    pub instruments: FnvIndexMap<InstrumentIndex, Duration>,
+
+    /// Number of stale feed breaches after which trading is disabled, if any.
+    pub halt_after: Option<u32>,
+
+    /// Engine time of the most recent event received from each feed.
+    pub heartbeats: FnvIndexMap<Feed, DateTime<Utc>>,
+
+    /// Feeds that are currently stale.
+    pub stale: FnvIndexSet<Feed>,
+
+    /// Number of stale feed breaches since the `StaleFeedMonitor` was last re-armed.
+    pub breaches: u32,
+
+    /// `true` if the `halt_after` limit has been reached.
+    pub halted: bool,
+
+    /// Engine time the monitor was first evaluated, used as the heartbeat of feeds that never
+    /// produced an event.
+    pub time_start: Option<DateTime<Utc>>,
+}
+
+/// Maximum time without an event before the market data & account feeds of an exchange are
+/// considered stale. A threshold of `None` is not evaluated.
+#[derive(
+    Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default, Deserialize, Serialize,
+)]
+pub struct StaleThresholds {
+    pub market_data: Option<Duration>,
+    pub account: Option<Duration>,
+}
+
+/// Feed monitored by a [`StaleFeedMonitor`].
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub enum Feed {
+    /// Market data feed of an exchange.
+    MarketData(ExchangeId),
+
+    /// Account feed of an exchange.
+    Account(ExchangeId),
+
+    /// Market data of an individual instrument.
+    Instrument(ExchangeId, InstrumentIndex),
+}
+
+impl Feed {
+    pub fn exchange(&self) -> ExchangeId {
+        match self {
+            Self::MarketData(exchange)
+            | Self::Account(exchange)
+            | Self::Instrument(exchange, _) => *exchange,
+        }
+    }
+
+    /// Returns `true` if the `Feed` is part of an exchange market data feed.
+    pub fn is_market_data(&self) -> bool {
+        matches!(self, Self::MarketData(_) | Self::Instrument(..))
+    }
+}
+
+/// Audit record of a [`Feed`] breaching its staleness threshold.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub struct FeedStale {
+    pub feed: Feed,
+    pub threshold: Duration,
+    pub last_seen: DateTime<Utc>,
+    pub time: DateTime<Utc>,
+}
+
+/// Output of a [`StaleFeedMonitor::check`].
+#[derive(Debug, Clone, Eq, PartialEq, Default, Deserialize, Serialize)]
+pub struct StaleFeedCheck {
+    /// Feeds that became stale.
+    pub stale: Vec<FeedStale>,
+
+    /// `true` if the `halt_after` limit was reached by this check.
+    pub halted: bool,
+}
+
+impl StaleFeedMonitor {
+    /// Construct a new [`StaleFeedMonitor`] with the provided default [`StaleThresholds`].
+    pub fn new(thresholds: StaleThresholds) -> Self {
+        Self {
+            thresholds,
+            ..Self::default()
+        }
+    }
+
+    /// Set the [`StaleThresholds`] for an exchange.
+    pub fn with_exchange_thresholds(
+        mut self,
+        exchange: ExchangeId,
+        thresholds: StaleThresholds,
+    ) -> Self {
+        self.exchanges.insert(exchange, thresholds);
+        self
+    }
+
+    /// Set the market data threshold for an instrument.
+    pub fn with_instrument_threshold(
+        mut self,
+        instrument: InstrumentIndex,
+        threshold: Duration,
+    ) -> Self {
+        self.instruments.insert(instrument, threshold);
+        self
+    }
+
+    /// Disable trading once the provided number of stale feed breaches is reached.
+    pub fn with_halt_after(self, breaches: u32) -> Self {
+        Self {
+            halt_after: Some(breaches),
+            ..self
+        }
+    }
+
+    /// Record a market event heartbeat, restoring the exchange market data & instrument feeds.
+    pub fn record_market(
+        &mut self,
+        exchange: ExchangeId,
+        instrument: &InstrumentIndex,
+        time: DateTime<Utc>,
+    ) {
+        if self.instruments.contains_key(instrument) {
+            self.record(Feed::Instrument(exchange, instrument.clone()), time);
+        }
+        self.record(Feed::MarketData(exchange), time);
+    }
+
+    /// Record an account event heartbeat, restoring the exchange account feed.
+    ///
+    /// The `ExchangeIndex` is resolved against the provided tracked exchanges.
+    pub fn record_account<'a>(
+        &mut self,
+        exchange: &ExchangeIndex,
+        exchanges: impl IntoIterator<Item = &'a ExchangeId>,
+        time: DateTime<Utc>,
+    ) {
+        if let Some(exchange) = exchanges.into_iter().find(|id| id.to_string() == *exchange) {
+            self.record(Feed::Account(*exchange), time);
+        }
+    }
+
+    fn record(&mut self, feed: Feed, time: DateTime<Utc>) {
+        self.stale.shift_remove(&feed);
+        self.heartbeats.insert(feed, time);
+    }
+
+    /// Returns `true` if any market data feed of the provided exchange is stale.
+    pub fn is_market_data_stale(&self, exchange: &ExchangeId) -> bool {
+        self.stale
+            .iter()
+            .any(|feed| feed.is_market_data() && feed.exchange() == *exchange)
+    }
+
+    /// Returns `true` if the account feed of the provided exchange is stale.
+    pub fn is_account_stale(&self, exchange: &ExchangeId) -> bool {
+        self.stale.contains(&Feed::Account(*exchange))
+    }
+
+    /// Evaluate the staleness thresholds of the provided exchanges & instruments at the
+    /// provided engine time, returning the feeds that became stale.
+    ///
+    /// `instrument_exchange` resolves the exchange of each monitored instrument.
+    pub fn check<'a, FnExchange>(
+        &mut self,
+        now: DateTime<Utc>,
+        exchanges: impl IntoIterator<Item = &'a ExchangeId>,
+        instrument_exchange: FnExchange,
+    ) -> StaleFeedCheck
+    where
+        FnExchange: Fn(&InstrumentIndex) -> ExchangeId,
+    {
+        let time_start = *self.time_start.get_or_insert(now);
+
+        let exchange_feeds = exchanges.into_iter().flat_map(|exchange| {
+            let thresholds = self
+                .exchanges
+                .get(exchange)
+                .copied()
+                .unwrap_or(self.thresholds);
+
+            [
+                thresholds
+                    .market_data
+                    .map(|threshold| (Feed::MarketData(*exchange), threshold)),
+                thresholds
+                    .account
+                    .map(|threshold| (Feed::Account(*exchange), threshold)),
+            ]
+        });
+
+        let instrument_feeds = self.instruments.iter().map(|(instrument, threshold)| {
+            let exchange = instrument_exchange(instrument);
+            Some((Feed::Instrument(exchange, instrument.clone()), *threshold))
+        });
+
+        let feeds = exchange_feeds
+            .chain(instrument_feeds)
+            .flatten()
+            .collect::<Vec<_>>();
+
+        let stale = feeds
+            .into_iter()
+            .filter(|(feed, _)| !self.stale.contains(feed))
+            .filter_map(|(feed, threshold)| {
+                let last_seen = self.heartbeats.get(&feed).copied().unwrap_or(time_start);
+                let silence = now - last_seen;
+
+                (silence > TimeDelta::from_std(threshold).unwrap_or(TimeDelta::MAX)).then_some(
+                    FeedStale {
+                        feed,
+                        threshold,
+                        last_seen,
+                        time: now,
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+
+        for stale in &stale {
+            warn!(
+                feed = ?stale.feed,
+                last_seen = %stale.last_seen,
+                threshold = ?stale.threshold,
+                "StaleFeedMonitor detected stale feed"
+            );
+            self.stale.insert(stale.feed.clone());
+        }
+
+        self.breaches = self.breaches.saturating_add(stale.len() as u32);
+
+        let halted = !self.halted
+            && self
+                .halt_after
+                .is_some_and(|halt_after| self.breaches >= halt_after);
+
+        if halted {
+            warn!(
+                breaches = self.breaches,
+                "StaleFeedMonitor halt limit reached - disabling trading"
+            );
+            self.halted = true;
+        }
+
+        StaleFeedCheck { stale, halted }
+    }
+
+    /// Re-arm a halted `StaleFeedMonitor`, resetting the breach count.
+    ///
+    /// Feeds that are still stale remain stale until their next event is received.
+    pub fn rearm(&mut self) {
+        self.breaches = 0;
+        self.halted = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(secs: i64) -> DateTime<Utc> {
+        DateTime::<Utc>::MIN_UTC + TimeDelta::seconds(secs)
+    }
+
+    fn monitor() -> StaleFeedMonitor {
+        StaleFeedMonitor::new(StaleThresholds {
+            market_data: Some(Duration::from_secs(5)),
+            account: Some(Duration::from_secs(30)),
+        })
+        .with_instrument_threshold("PETR4".to_string(), Duration::from_secs(2))
+        .with_halt_after(2)
+    }
+
+    #[test]
+    fn test_stale_feed_monitor_check() {
+        struct TestCase {
+            market: Option<(&'static str, i64)>,
+            now: i64,
+            expected_stale: Vec<Feed>,
+            expected_halted: bool,
+        }
+
+        let exchanges = [ExchangeId::Mock];
+        let mut monitor = monitor();
+
+        let cases = vec![
+            // TC0: first check initialises time_start
+            TestCase {
+                market: None,
+                now: 0,
+                expected_stale: vec![],
+                expected_halted: false,
+            },
+            // TC1: VALE3 keeps the exchange feed alive, but PETR4 goes silent
+            TestCase {
+                market: Some(("VALE3", 3)),
+                now: 3,
+                expected_stale: vec![Feed::Instrument(ExchangeId::Mock, "PETR4".to_string())],
+                expected_halted: false,
+            },
+            // TC2: stale feeds are only reported once
+            TestCase {
+                market: None,
+                now: 4,
+                expected_stale: vec![],
+                expected_halted: false,
+            },
+            // TC3: exchange market data goes silent, reaching halt_after
+            TestCase {
+                market: None,
+                now: 9,
+                expected_stale: vec![Feed::MarketData(ExchangeId::Mock)],
+                expected_halted: true,
+            },
+            // TC4: PETR4 heartbeat restores its feeds, account feed breaches without re-halting
+            TestCase {
+                market: Some(("PETR4", 40)),
+                now: 40,
+                expected_stale: vec![Feed::Account(ExchangeId::Mock)],
+                expected_halted: false,
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            if let Some((instrument, time_market)) = test.market {
+                monitor.record_market(ExchangeId::Mock, &instrument.to_string(), time(time_market));
+            }
+
+            let actual = monitor.check(time(test.now), &exchanges, |_| ExchangeId::Mock);
+            let actual_stale = actual
+                .stale
+                .into_iter()
+                .map(|stale| stale.feed)
+                .collect::<Vec<_>>();
+
+            assert_eq!(actual_stale, test.expected_stale, "TC{index} failed");
+            assert_eq!(actual.halted, test.expected_halted, "TC{index} failed");
+        }
+
+        assert!(!monitor.is_market_data_stale(&ExchangeId::Mock));
+        assert!(monitor.is_account_stale(&ExchangeId::Mock));
+        assert!(monitor.halted);
+
+        monitor.rearm();
+        assert_eq!(monitor.breaches, 0);
+        assert!(!monitor.halted);
+    }
+}
@@ -0,0 +1,799 @@
+use crate::engine::{
+    state::{
+        instrument::{data::InstrumentDataState, InstrumentStates},
+        EngineState,
+    },
+    Processor,
+};
+use rust_decimal::{prelude::FromPrimitive, Decimal};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use thiserror::Error;
+use toucan_data::event::{DataKind, MarketEvent};
+use toucan_execution::{AccountEvent, AccountEventKind, InstrumentIndex};
+use toucan_instrument::{exchange::ExchangeId, Side};
+use toucan_integration::collection::FnvIndexMap;
+use toucan_risk::var::{
+    MarketReturns, RiskReport, StressResult, StressScenario, VarConfig, VarError, VarEstimate,
+    VarPosition,
+};
+use tracing::warn;
+
+/// Portfolio exposure `GlobalData` that aggregates the long, short, gross & net exposure of
+/// every tracked instrument per asset, per underlying, per exchange and in total.
+///
+/// Exposures are quote notional amounts (ie/ `quantity * price * contract_size`), updated
+/// incrementally on every [`Trade`](toucan_execution::trade::Trade) and every market price
+/// change ([`DataKind::Trade`] last price or [`DataKind::OrderBookL1`] volume weighted
+/// mid-price).
+///
+/// Note that exposures of instruments quoted in different currencies are summed without FX
+/// conversion, so cross-currency aggregates (eg/ the total of a BRL & USDT portfolio) are only
+/// meaningful as limits on a single quote currency.
+///
+/// Market prices are also sampled into [`MarketReturns`], used to estimate the portfolio
+/// Value-at-Risk (see [`Self::var`]) and the `VarLimit` pre-trade check.
+///
+/// Instruments must be registered via [`Self::with_instrument`] (see
+/// [`SystemConfig::portfolio_exposure`](crate::system::config::SystemConfig::portfolio_exposure)),
+/// events for unregistered instruments are ignored (trades with a warning).
+///
+/// Positions held before the `PortfolioExposure` was constructed (eg/ at startup), or changed
+/// whilst it was not receiving events, are seeded from the `EngineState` positions via
+/// [`EngineState::reconcile_exposure`], which should be called after every account
+/// [`AccountSnapshot`](toucan_execution::AccountSnapshot).
+#[derive(Debug, Clone, PartialEq, Default, Deserialize, Serialize)]
+pub struct PortfolioExposure {
+    /// Position & mark price of every registered instrument.
+    pub instruments: FnvIndexMap<InstrumentIndex, ExposureInstrument>,
+
+    /// [`Exposure`] per base asset (eg/ "petr4").
+    pub assets: FnvIndexMap<String, Exposure>,
+
+    /// [`Exposure`] per underlying (eg/ "petr4_brl").
+    pub underlyings: FnvIndexMap<String, Exposure>,
+
+    /// [`Exposure`] per exchange.
+    pub exchanges: FnvIndexMap<ExchangeId, Exposure>,
+
+    /// Total portfolio [`Exposure`].
+    pub total: Exposure,
+
+    /// Sampled [`MarketReturns`] of every registered instrument.
+    #[serde(default)]
+    pub returns: MarketReturns<InstrumentIndex>,
+}
+
+impl PortfolioExposure {
+    /// Register an instrument to aggregate the exposure of.
+    pub fn with_instrument(mut self, key: InstrumentIndex, instrument: ExposureInstrument) -> Self {
+        let exposure = instrument.exposure();
+        self.apply(&instrument, exposure, Exposure::default());
+        self.instruments.insert(key, instrument);
+        self
+    }
+
+    /// Use the provided [`MarketReturns`] configuration (eg/ sampling interval) and history.
+    pub fn with_returns(self, returns: MarketReturns<InstrumentIndex>) -> Self {
+        Self { returns, ..self }
+    }
+
+    /// [`ExposureInstrument`] of the provided instrument, if registered.
+    pub fn instrument(&self, instrument: &InstrumentIndex) -> Option<&ExposureInstrument> {
+        self.instruments.get(instrument)
+    }
+
+    /// [`Exposure`] of the provided base asset.
+    pub fn asset(&self, asset: &str) -> Exposure {
+        self.assets.get(asset).copied().unwrap_or_default()
+    }
+
+    /// [`Exposure`] of the provided underlying.
+    pub fn underlying(&self, underlying: &str) -> Exposure {
+        self.underlyings
+            .get(underlying)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// [`Exposure`] of the provided exchange.
+    pub fn exchange(&self, exchange: &ExchangeId) -> Exposure {
+        self.exchanges.get(exchange).copied().unwrap_or_default()
+    }
+
+    /// Total portfolio [`Exposure`] if the provided trades of signed quantity (positive for
+    /// buys) & order price were filled, in order.
+    ///
+    /// Trades are valued at the instrument mark price, or at the order price if there is none
+    /// (see [`ExposureInstrument::valuation_price`]). Market orders should be provided without
+    /// an order price, so they are refused rather than valued at a placeholder price.
+    pub fn projected_total<'a>(
+        &self,
+        trades: impl IntoIterator<Item = (&'a InstrumentIndex, Decimal, Option<Decimal>)>,
+    ) -> Result<Exposure, ExposureError> {
+        let mut projected = FnvIndexMap::<&InstrumentIndex, ExposureInstrument>::default();
+        for (instrument, quantity_delta, price) in trades {
+            let current = self
+                .instruments
+                .get(instrument)
+                .ok_or_else(|| ExposureError::UnknownInstrument(instrument.clone()))?;
+            let state = projected
+                .entry(instrument)
+                .or_insert_with(|| current.clone());
+            let price = state
+                .valuation_price(price)
+                .ok_or_else(|| ExposureError::MissingPrice(instrument.clone()))?;
+
+            state.quantity += quantity_delta;
+            state.price = Some(price);
+        }
+
+        Ok(projected
+            .iter()
+            .fold(self.total, |total, (instrument, projected)| {
+                total - self.instruments[*instrument].exposure() + projected.exposure()
+            }))
+    }
+
+    /// Reconcile the position of every registered instrument with the provided
+    /// [`InstrumentStates`], seeding missing mark prices from the instrument market data.
+    ///
+    /// Registered instruments not tracked by the [`InstrumentStates`], and tracked instruments
+    /// with an open position that are not registered, are logged with a warning.
+    pub fn reconcile<InstrumentData>(&mut self, instruments: &InstrumentStates<InstrumentData>)
+    where
+        InstrumentData: InstrumentDataState,
+    {
+        let keys = self.instruments.keys().cloned().collect::<Vec<_>>();
+        for key in keys {
+            let Some(state) = instruments.0.get(&key) else {
+                warn!(
+                    instrument = %key,
+                    "PortfolioExposure instrument is not tracked by the EngineState"
+                );
+                continue;
+            };
+
+            let quantity = state
+                .position
+                .current
+                .as_ref()
+                .map_or(Decimal::ZERO, |position| match position.side {
+                    Side::Buy => position.quantity_abs,
+                    Side::Sell => -position.quantity_abs,
+                });
+            let price = state.data.price();
+
+            self.update(&key, |instrument| {
+                instrument.quantity = quantity;
+                instrument.price = instrument.price.or(price);
+            });
+        }
+
+        for (key, state) in &instruments.0 {
+            if state.position.current.is_some() && !self.instruments.contains_key(key) {
+                warn!(
+                    instrument = %key,
+                    "PortfolioExposure ignoring position of unregistered instrument"
+                );
+            }
+        }
+    }
+
+    /// [`VarPosition`] of every registered instrument, exposed to its base asset stress factor
+    /// (unless configured otherwise) and its configured [`ExposureInstrument::betas`].
+    pub fn var_positions(&self) -> Vec<VarPosition<InstrumentIndex>> {
+        self.instruments
+            .iter()
+            .map(|(key, instrument)| {
+                let position = VarPosition::new(key.clone(), instrument.notional());
+                let position = if instrument.betas.contains_key(&instrument.asset) {
+                    position
+                } else {
+                    position.with_beta(instrument.asset.clone(), Decimal::ONE)
+                };
+
+                instrument
+                    .betas
+                    .iter()
+                    .fold(position, |position, (factor, beta)| {
+                        position.with_beta(factor.clone(), *beta)
+                    })
+            })
+            .collect()
+    }
+
+    /// Estimate the Value-at-Risk & Expected Shortfall of the current portfolio.
+    pub fn var(&self, config: &VarConfig) -> Result<VarEstimate, VarError> {
+        config.estimate(&self.returns, &self.var_positions())
+    }
+
+    /// Apply the [`StressScenario`] to the current portfolio.
+    pub fn stress(&self, scenario: &StressScenario) -> StressResult<InstrumentIndex> {
+        scenario.apply(&self.var_positions())
+    }
+
+    /// Generate an on-demand [`RiskReport`] of the current portfolio.
+    pub fn risk_report(
+        &self,
+        configs: &[VarConfig],
+        scenarios: &[StressScenario],
+    ) -> Result<RiskReport<InstrumentIndex>, VarError> {
+        RiskReport::generate(&self.returns, &self.var_positions(), configs, scenarios)
+    }
+
+    /// Update the position of the provided instrument from a trade, using the trade price as the
+    /// new mark price.
+    pub fn update_from_trade(
+        &mut self,
+        instrument: &InstrumentIndex,
+        side: Side,
+        price: Decimal,
+        quantity: Decimal,
+    ) {
+        let quantity = match side {
+            Side::Buy => quantity.abs(),
+            Side::Sell => -quantity.abs(),
+        };
+
+        if !self.instruments.contains_key(instrument) {
+            warn!(%instrument, "PortfolioExposure ignoring trade of unregistered instrument");
+            return;
+        }
+
+        self.update(instrument, |state| {
+            state.quantity += quantity;
+            state.price = Some(price);
+        });
+    }
+
+    /// Update the mark price of the provided instrument.
+    pub fn update_price(&mut self, instrument: &InstrumentIndex, price: Decimal) {
+        self.update(instrument, |state| state.price = Some(price));
+    }
+
+    fn update<F>(&mut self, instrument: &InstrumentIndex, update: F)
+    where
+        F: FnOnce(&mut ExposureInstrument),
+    {
+        let Some(state) = self.instruments.get_mut(instrument) else {
+            return;
+        };
+
+        let previous = state.exposure();
+        update(state);
+        let state = state.clone();
+
+        self.apply(&state, state.exposure(), previous);
+    }
+
+    fn apply(&mut self, instrument: &ExposureInstrument, current: Exposure, previous: Exposure) {
+        let delta = |exposure: &mut Exposure| *exposure = *exposure - previous + current;
+
+        delta(self.assets.entry(instrument.asset.clone()).or_default());
+        delta(
+            self.underlyings
+                .entry(instrument.underlying.clone())
+                .or_default(),
+        );
+        delta(self.exchanges.entry(instrument.exchange).or_default());
+        delta(&mut self.total);
+    }
+}
+
+/// Position & mark price of an instrument tracked by the [`PortfolioExposure`].
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+pub struct ExposureInstrument {
+    /// Base asset of the instrument (eg/ "petr4").
+    pub asset: String,
+
+    /// Underlying of the instrument (eg/ "petr4_brl").
+    pub underlying: String,
+
+    /// Exchange the instrument is traded on.
+    pub exchange: ExchangeId,
+
+    /// Contract size multiplier of the instrument (`Decimal::ONE` for Spot).
+    pub contract_size: Decimal,
+
+    /// Signed position quantity (positive for long, negative for short).
+    pub quantity: Decimal,
+
+    /// Most recent mark price, if any.
+    pub price: Option<Decimal>,
+
+    /// Sensitivity to named stress factors (eg/ "IBOV"), in addition to the base asset.
+    #[serde(default)]
+    pub betas: BTreeMap<String, Decimal>,
+}
+
+impl ExposureInstrument {
+    /// Construct a flat [`ExposureInstrument`] without a mark price.
+    pub fn new(
+        asset: impl Into<String>,
+        underlying: impl Into<String>,
+        exchange: ExchangeId,
+        contract_size: Decimal,
+    ) -> Self {
+        Self {
+            asset: asset.into(),
+            underlying: underlying.into(),
+            exchange,
+            contract_size,
+            quantity: Decimal::ZERO,
+            price: None,
+            betas: BTreeMap::new(),
+        }
+    }
+
+    /// Set the sensitivity of the instrument to the named stress factor.
+    pub fn with_beta(mut self, factor: impl Into<String>, beta: Decimal) -> Self {
+        self.betas.insert(factor.into(), beta);
+        self
+    }
+
+    /// Price to value a trade of the instrument at: the mark price, or the provided order price
+    /// if there is none. Non-positive prices are never used, so `None` is returned if neither
+    /// price is usable.
+    pub fn valuation_price(&self, price: Option<Decimal>) -> Option<Decimal> {
+        [self.price, price]
+            .into_iter()
+            .flatten()
+            .find(|price| price.is_sign_positive() && !price.is_zero())
+    }
+
+    /// Signed quote notional of the position, or zero if there is no mark price.
+    pub fn notional(&self) -> Decimal {
+        self.price
+            .map(|price| self.quantity * price * self.contract_size)
+            .unwrap_or_default()
+    }
+
+    /// [`Exposure`] of the position.
+    pub fn exposure(&self) -> Exposure {
+        Exposure::from_notional(self.notional())
+    }
+}
+
+/// Reason a [`PortfolioExposure`] projection could not be computed.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize, Error)]
+pub enum ExposureError {
+    #[error("instrument {0} is not registered with the PortfolioExposure")]
+    UnknownInstrument(InstrumentIndex),
+
+    #[error("instrument {0} has no usable price to value the order at")]
+    MissingPrice(InstrumentIndex),
+}
+
+impl<InstrumentData> EngineState<PortfolioExposure, InstrumentData>
+where
+    InstrumentData: InstrumentDataState,
+{
+    /// Reconcile the [`PortfolioExposure`] `GlobalData` with the current instrument positions
+    /// (see [`PortfolioExposure::reconcile`]).
+    pub fn reconcile_exposure(&mut self) {
+        self.global.reconcile(&self.instruments)
+    }
+}
+
+/// Long & short quote notional exposure.
+#[derive(
+    Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default, Deserialize, Serialize,
+)]
+pub struct Exposure {
+    /// Quote notional of long positions (always positive).
+    pub long: Decimal,
+
+    /// Quote notional of short positions (always positive).
+    pub short: Decimal,
+}
+
+impl Exposure {
+    /// Construct an [`Exposure`] from a signed quote notional (positive for long).
+    pub fn from_notional(notional: Decimal) -> Self {
+        if notional.is_sign_negative() {
+            Self {
+                long: Decimal::ZERO,
+                short: notional.abs(),
+            }
+        } else {
+            Self {
+                long: notional,
+                short: Decimal::ZERO,
+            }
+        }
+    }
+
+    /// Sum of the long & short exposure.
+    pub fn gross(&self) -> Decimal {
+        self.long + self.short
+    }
+
+    /// Long exposure minus short exposure.
+    pub fn net(&self) -> Decimal {
+        self.long - self.short
+    }
+}
+
+impl std::ops::Add for Exposure {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            long: self.long + rhs.long,
+            short: self.short + rhs.short,
+        }
+    }
+}
+
+impl std::ops::Sub for Exposure {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            long: self.long - rhs.long,
+            short: self.short - rhs.short,
+        }
+    }
+}
+
+impl<ExchangeKey, AssetKey> Processor<&AccountEvent<ExchangeKey, AssetKey, InstrumentIndex>>
+    for PortfolioExposure
+{
+    type Audit = ();
+
+    fn process(&mut self, event: &AccountEvent<ExchangeKey, AssetKey, InstrumentIndex>) {
+        if let AccountEventKind::Trade(trade) = &event.kind {
+            self.update_from_trade(&trade.instrument, trade.side, trade.price, trade.quantity);
+        }
+    }
+}
+
+impl Processor<&MarketEvent<InstrumentIndex, DataKind>> for PortfolioExposure {
+    type Audit = ();
+
+    fn process(&mut self, event: &MarketEvent<InstrumentIndex, DataKind>) {
+        let price = match &event.kind {
+            DataKind::Trade(trade) => Decimal::from_f64(trade.price),
+            DataKind::OrderBookL1(l1) => l1.volume_weighed_mid_price(),
+            _ => None,
+        };
+
+        if let Some(price) = price {
+            if self.instruments.contains_key(&event.instrument) {
+                self.returns
+                    .update(event.instrument.clone(), event.time_exchange, price);
+            }
+            self.update_price(&event.instrument, price);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, TimeDelta, Utc};
+    use rust_decimal_macros::dec;
+    use std::{str::FromStr, time::Duration};
+    use toucan_data::subscription::trade::PublicTrade;
+    use toucan_risk::var::VarMethod;
+
+    fn exposure() -> PortfolioExposure {
+        PortfolioExposure::default()
+            .with_instrument(
+                InstrumentIndex::from("PETR4"),
+                ExposureInstrument::new("petr4", "petr4_brl", ExchangeId::Other, Decimal::ONE),
+            )
+            .with_instrument(
+                InstrumentIndex::from("WINFUT"),
+                ExposureInstrument::new("win", "win_brl", ExchangeId::Other, dec!(0.2)),
+            )
+            .with_instrument(
+                InstrumentIndex::from("VALE3"),
+                ExposureInstrument::new("vale3", "vale3_brl", ExchangeId::Mock, Decimal::ONE),
+            )
+    }
+
+    #[test]
+    fn test_portfolio_exposure_aggregation() {
+        struct TestCase {
+            instrument: &'static str,
+            side: Side,
+            price: Decimal,
+            quantity: Decimal,
+            expected_total: Exposure,
+            expected_other: Exposure,
+        }
+
+        let mut exposure = exposure();
+
+        let cases = vec![
+            // TC0: buy PETR4 opens long exposure
+            TestCase {
+                instrument: "PETR4",
+                side: Side::Buy,
+                price: dec!(30),
+                quantity: dec!(100),
+                expected_total: Exposure {
+                    long: dec!(3000),
+                    short: dec!(0),
+                },
+                expected_other: Exposure {
+                    long: dec!(3000),
+                    short: dec!(0),
+                },
+            },
+            // TC1: sell WINFUT opens short exposure scaled by contract size
+            TestCase {
+                instrument: "WINFUT",
+                side: Side::Sell,
+                price: dec!(120000),
+                quantity: dec!(1),
+                expected_total: Exposure {
+                    long: dec!(3000),
+                    short: dec!(24000),
+                },
+                expected_other: Exposure {
+                    long: dec!(3000),
+                    short: dec!(24000),
+                },
+            },
+            // TC2: buy VALE3 on another exchange only changes the total
+            TestCase {
+                instrument: "VALE3",
+                side: Side::Buy,
+                price: dec!(60),
+                quantity: dec!(50),
+                expected_total: Exposure {
+                    long: dec!(6000),
+                    short: dec!(24000),
+                },
+                expected_other: Exposure {
+                    long: dec!(3000),
+                    short: dec!(24000),
+                },
+            },
+            // TC3: sell PETR4 beyond the long position flips it short at the new price
+            TestCase {
+                instrument: "PETR4",
+                side: Side::Sell,
+                price: dec!(32),
+                quantity: dec!(150),
+                expected_total: Exposure {
+                    long: dec!(3000),
+                    short: dec!(25600),
+                },
+                expected_other: Exposure {
+                    long: dec!(0),
+                    short: dec!(25600),
+                },
+            },
+            // TC4: trade of an unregistered instrument is ignored
+            TestCase {
+                instrument: "ITUB4",
+                side: Side::Buy,
+                price: dec!(35),
+                quantity: dec!(100),
+                expected_total: Exposure {
+                    long: dec!(3000),
+                    short: dec!(25600),
+                },
+                expected_other: Exposure {
+                    long: dec!(0),
+                    short: dec!(25600),
+                },
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            exposure.update_from_trade(
+                &InstrumentIndex::from(test.instrument),
+                test.side,
+                test.price,
+                test.quantity,
+            );
+            assert_eq!(exposure.total, test.expected_total, "TC{index} failed");
+            assert_eq!(
+                exposure.exchange(&ExchangeId::Other),
+                test.expected_other,
+                "TC{index} failed"
+            );
+        }
+
+        assert_eq!(exposure.asset("petr4").net(), dec!(-1600));
+        assert_eq!(exposure.underlying("win_brl").gross(), dec!(24000));
+        assert_eq!(exposure.exchange(&ExchangeId::Mock).net(), dec!(3000));
+        assert_eq!(exposure.total.gross(), dec!(28600));
+        assert_eq!(exposure.total.net(), dec!(-22600));
+
+        // Price change re-marks the position
+        exposure.update_price(&InstrumentIndex::from("VALE3"), dec!(70));
+        assert_eq!(exposure.exchange(&ExchangeId::Mock).long, dec!(3500));
+        assert_eq!(exposure.total.long, dec!(3500));
+    }
+
+    #[test]
+    fn test_portfolio_exposure_projected_total() {
+        let mut exposure = exposure();
+        let petr4 = InstrumentIndex::from("PETR4");
+        exposure.update_from_trade(&petr4, Side::Buy, dec!(30), dec!(100));
+
+        // Reducing the position reduces the projected exposure
+        assert_eq!(
+            exposure.projected_total([(&petr4, dec!(-40), Some(dec!(30)))]),
+            Ok(Exposure {
+                long: dec!(1800),
+                short: dec!(0),
+            })
+        );
+
+        // Instrument without a mark price uses the provided price
+        assert_eq!(
+            exposure.projected_total([(
+                &InstrumentIndex::from("VALE3"),
+                dec!(-10),
+                Some(dec!(60))
+            )]),
+            Ok(Exposure {
+                long: dec!(3000),
+                short: dec!(600),
+            })
+        );
+
+        // Trades of the same instrument are netted, so buying back the projected sale is flat
+        let vale3 = InstrumentIndex::from("VALE3");
+        assert_eq!(
+            exposure.projected_total([
+                (&vale3, dec!(-10), Some(dec!(60))),
+                (&vale3, dec!(10), Some(dec!(60)))
+            ]),
+            Ok(exposure.total)
+        );
+
+        // Market order of an instrument without a mark price cannot be valued
+        assert_eq!(
+            exposure.projected_total([(&vale3, dec!(10), None)]),
+            Err(ExposureError::MissingPrice(vale3.clone()))
+        );
+        assert_eq!(
+            exposure.projected_total([(&vale3, dec!(10), Some(dec!(0)))]),
+            Err(ExposureError::MissingPrice(vale3.clone()))
+        );
+
+        // Market order of an instrument with a mark price is valued at the mark price
+        assert_eq!(
+            exposure.projected_total([(&petr4, dec!(10), None)]),
+            Ok(Exposure {
+                long: dec!(3300),
+                short: dec!(0),
+            })
+        );
+
+        assert_eq!(
+            exposure.projected_total([(&InstrumentIndex::from("ITUB4"), dec!(1), Some(dec!(35)))]),
+            Err(ExposureError::UnknownInstrument(InstrumentIndex::from(
+                "ITUB4"
+            )))
+        );
+    }
+
+    #[test]
+    fn test_engine_state_reconcile_exposure() {
+        use crate::engine::state::{
+            instrument::data::DefaultInstrumentMarketData, IndexedInstruments,
+        };
+        use toucan_execution::{
+            order::{id::OrderId, id::StrategyId},
+            trade::{AssetFees, Trade, TradeId},
+        };
+        use toucan_instrument::{ConcreteInstrument, Keyed};
+
+        let instruments: IndexedInstruments = ["PETR4", "ITUB4"]
+            .into_iter()
+            .map(|instrument| {
+                Keyed::new(
+                    instrument.to_string(),
+                    ConcreteInstrument {
+                        symbol: instrument.into(),
+                        market: "spot".into(),
+                        exchange: ExchangeId::Mock,
+                        underlying: None,
+                        name_exchange: instrument.into(),
+                    },
+                )
+            })
+            .collect();
+        let mut state = EngineState::builder(&instruments, exposure(), |_| {
+            DefaultInstrumentMarketData::default()
+        })
+        .build();
+
+        let trade = |instrument: &str, side, quantity| AccountEvent {
+            exchange: ExchangeId::Mock.to_string(),
+            broker: None,
+            account: None,
+            trace: None,
+            kind: AccountEventKind::Trade(Trade {
+                id: TradeId::new(instrument),
+                order_id: OrderId::new(instrument),
+                instrument: InstrumentIndex::from(instrument),
+                strategy: StrategyId::new("test"),
+                time_exchange: DateTime::<Utc>::MIN_UTC,
+                side,
+                price: dec!(30),
+                quantity,
+                fees: AssetFees::quote_fees(Decimal::ZERO),
+            }),
+        };
+
+        // Positions opened before the PortfolioExposure was constructed (eg/ at startup)
+        state.update_from_account(&trade("PETR4", Side::Sell, dec!(100)));
+        state.update_from_account(&trade("ITUB4", Side::Buy, dec!(50)));
+        state.global = exposure();
+        assert_eq!(state.global.total, Exposure::default());
+
+        state.reconcile_exposure();
+
+        let petr4 = InstrumentIndex::from("PETR4");
+        assert_eq!(
+            state.global.instrument(&petr4).unwrap().quantity,
+            dec!(-100)
+        );
+        assert_eq!(
+            state
+                .global
+                .instrument(&InstrumentIndex::from("WINFUT"))
+                .unwrap()
+                .quantity,
+            dec!(0)
+        );
+        assert_eq!(
+            state.global.instrument(&InstrumentIndex::from("ITUB4")),
+            None
+        );
+
+        // Reconciled position is re-marked by the next price
+        state.global.update_price(&petr4, dec!(30));
+        assert_eq!(state.global.total.short, dec!(3000));
+    }
+
+    #[test]
+    fn test_portfolio_exposure_var_and_stress() {
+        let petr4 = InstrumentIndex::from("PETR4");
+        let mut exposure = PortfolioExposure::default()
+            .with_returns(MarketReturns::new(Duration::from_secs(60), 100))
+            .with_instrument(
+                petr4.clone(),
+                ExposureInstrument::new("petr4", "petr4_brl", ExchangeId::Other, Decimal::ONE)
+                    .with_beta("IBOV", dec!(1.2)),
+            );
+
+        // Sampled PETR4 returns: +10%, -20%, +25%
+        let time = DateTime::<Utc>::MIN_UTC;
+        for (minute, price) in [(0, 100.0), (1, 110.0), (1, 200.0), (2, 88.0), (3, 110.0)] {
+            exposure.process(&MarketEvent {
+                time_exchange: time + TimeDelta::minutes(minute),
+                time_received: time + TimeDelta::minutes(minute),
+                exchange: ExchangeId::Other,
+                instrument: petr4.clone(),
+                kind: DataKind::Trade(PublicTrade {
+                    id: "trade".to_string(),
+                    price,
+                    amount: 1.0,
+                    side: Side::Buy,
+                }),
+            });
+        }
+        assert_eq!(exposure.returns.series(&petr4).unwrap().returns.len(), 3);
+
+        exposure.update_from_trade(&petr4, Side::Buy, dec!(100), dec!(10));
+
+        let var = exposure
+            .var(&VarConfig::new(VarMethod::Historical, dec!(0.9)))
+            .unwrap();
+        assert_eq!(var.var, dec!(200));
+
+        let stress = exposure.stress(&StressScenario::from_str("IBOV -10%, petr4 -5%").unwrap());
+        assert_eq!(stress.pnl, dec!(-170));
+    }
+}
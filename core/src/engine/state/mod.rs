@@ -1,28 +1,38 @@
 
 use crate::engine::state::asset::AssetState;
 use crate::engine::{
+    kill_switch::KillSwitch,
+    latency::LatencyTracker,
+    reaction::Reactions,
+    session::SessionControl,
     state::{
         asset::{filter::AssetFilter, AssetStates},
         builder::EngineStateBuilder,
-        connectivity::ConnectivityStates,
+        connectivity::{stale::StaleFeedMonitor, ConnectivityStates},
         instrument::{
             data::InstrumentDataState, filter::InstrumentFilter,
             generate_unindexed_instrument_account_snapshot, InstrumentStates,
         },
+        order::flow::OrderFlows,
         position::PositionExited,
         trading::TradingState,
     },
+    timer::Timers,
     Processor,
 };
+use crate::shutdown::ShutdownSequence;
 use derive_more::Constructor;
 use fnv::FnvHashMap;
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
-use toucan_analytics::summary::asset::TearSheetAssetGenerator;
+use toucan_analytics::summary::{asset::TearSheetAssetGenerator, risk::RiskRefusalSummary};
 use toucan_data::event::MarketEvent;
 use toucan_execution::{
-    balance::AssetBalance, AccountEvent, AccountEventKind, ExchangeIndex, InstrumentIndex,
-    QuoteAsset, UnindexedAccountSnapshot,
+    balance::AssetBalance,
+    error::OrderError,
+    order::state::{InactiveOrderState, OrderState},
+    AccountEvent, AccountEventKind, ExchangeIndex, InstrumentIndex, QuoteAsset,
+    UnindexedAccountSnapshot,
 };
 use toucan_integration::{collection::one_or_many::OneOrMany, snapshot::Snapshot};
 use toucan_instrument::{exchange::ExchangeId, Keyed, Instrument}; // ExchangeId still used in connectivity
@@ -51,6 +61,10 @@ pub mod asset;
 /// and account connections for each exchange.
 pub mod connectivity;
 
+/// Portfolio exposure `GlobalData` aggregating long, short, gross & net exposure per asset,
+/// underlying, exchange and in total.
+pub mod exposure;
+
 /// Instrument-level state and associated state management logic.
 pub mod instrument;
 
@@ -90,6 +104,46 @@ pub struct EngineState<GlobalData, InstrumentData> {
     /// State of every instrument (eg/ "b3_spot_petr4_brl", "binance_spot_btc_usdt", etc.)
     /// being tracked by the `Engine`.
     pub instruments: InstrumentStates<InstrumentData, ExchangeIndex, InstrumentIndex>,
+
+    /// Registered `Engine` [`Timers`], and the timers fired whilst processing the most recent
+    /// event.
+    pub timers: Timers,
+
+    /// Optional trading [`SessionControl`] gating the `TradingState` by venue session phase.
+    pub session: Option<SessionControl>,
+
+    /// Optional automatic [`KillSwitch`] that disables trading when loss, drawdown, losing
+    /// streak or order rejection limits are breached.
+    pub kill_switch: Option<KillSwitch>,
+
+    /// Optional [`LatencyTracker`] recording tick-to-trade latency histograms per exchange and
+    /// per strategy.
+    #[serde(default)]
+    pub latency: Option<LatencyTracker>,
+
+    /// Optional [`StaleFeedMonitor`] marking market data & account feeds that stop producing
+    /// events as [`Health::Stale`](connectivity::Health::Stale).
+    #[serde(default)]
+    pub stale_feeds: Option<StaleFeedMonitor>,
+
+    /// Optional ready-made [`Reactions`] run on the `OnDisconnectStrategy` and
+    /// `OnTradingDisabled` hooks.
+    #[serde(default)]
+    pub reactions: Option<Reactions>,
+
+    /// In progress [`GracefulShutdown`](crate::shutdown::GracefulShutdown) sequence, if any.
+    #[serde(default)]
+    pub shutdown: Option<ShutdownSequence>,
+
+    /// Statistics of algorithmic order requests refused by the `RiskManager`, aggregated by
+    /// refusing check and by instrument.
+    #[serde(default)]
+    pub risk_refusals: RiskRefusalSummary,
+
+    /// Per-instrument [`OrderFlows`] of sent order requests & received trades, used by the
+    /// message-rate and order-to-trade ratio risk limits.
+    #[serde(default)]
+    pub order_flow: OrderFlows,
 }
 
 impl<GlobalData, InstrumentData> EngineState<GlobalData, InstrumentData> {
@@ -166,6 +220,14 @@ impl<GlobalData, InstrumentData> EngineState<GlobalData, InstrumentData> {
 
                 instrument_state.update_from_order_snapshot(order.as_ref());
                 instrument_state.data.process(event);
+
+                if let (
+                    Some(kill_switch),
+                    OrderState::Inactive(InactiveOrderState::OpenFailed(OrderError::Rejected(_))),
+                ) = (&mut self.kill_switch, &order.value().state)
+                {
+                    kill_switch.record_rejection(&order.value().key);
+                }
                 None
             }
             AccountEventKind::OrderCancelled(response) => {
@@ -181,7 +243,13 @@ impl<GlobalData, InstrumentData> EngineState<GlobalData, InstrumentData> {
                 let instrument_state = self.instruments.instrument_index_mut(&trade.instrument);
 
                 instrument_state.data.process(event);
-                instrument_state.update_from_trade(trade)
+                let exited = instrument_state.update_from_trade(trade);
+                self.order_flow.record_trade(&trade.instrument);
+
+                if let (Some(kill_switch), Some(exited)) = (&mut self.kill_switch, &exited) {
+                    kill_switch.record_position_exit(&trade.strategy, exited);
+                }
+                exited
             }
         };
 
@@ -195,7 +263,8 @@ impl<GlobalData, InstrumentData> EngineState<GlobalData, InstrumentData> {
     ///
     /// This method:
     /// - Sets the market data [`ConnectivityState`](connectivity::ConnectivityState) to
-    ///   [`Health::Healthy`](connectivity::Health::Healthy) if it was not previously.
+    ///   [`Health::Healthy`](connectivity::Health::Healthy) if it was not previously, and no
+    ///   monitored market data feed of the exchange is stale.
     /// - Updates the `GlobalData` with the `MarketEvent`.
     /// - Updates the associated [`InstrumentDataState`] with the `MarketEvent`.
     pub fn update_from_market(
@@ -206,10 +275,17 @@ impl<GlobalData, InstrumentData> EngineState<GlobalData, InstrumentData> {
             for<'a> Processor<&'a MarketEvent<InstrumentIndex, InstrumentData::MarketEventKind>>,
         InstrumentData: InstrumentDataState,
     {
-        // Set exchange market data connectivity to Healthy if it was Reconnecting
+        // Set exchange market data connectivity to Healthy if it was Reconnecting, unless
+        // another instrument market data feed of the exchange is still stale
         // Convert ExchangeId to external ExchangeIndex (String) interface
-        let exchange_index = event.exchange.to_string();
-        self.connectivity.update_from_market_event(&exchange_index);
+        let market_data_stale = self
+            .stale_feeds
+            .as_ref()
+            .is_some_and(|monitor| monitor.is_market_data_stale(&event.exchange));
+        if !market_data_stale {
+            let exchange_index = event.exchange.to_string();
+            self.connectivity.update_from_market_event(&exchange_index);
+        }
 
         let instrument_state = self.instruments.instrument_index_mut(&event.instrument);
 
@@ -228,6 +304,15 @@ impl<GlobalData, InstrumentData> From<&EngineState<GlobalData, InstrumentData>>
             connectivity,
             assets,
             instruments,
+            timers: _,
+            session: _,
+            kill_switch: _,
+            latency: _,
+            stale_feeds: _,
+            reactions: _,
+            shutdown: _,
+            risk_refusals: _,
+            order_flow: _,
         } = value;
 
         // Allocate appropriately
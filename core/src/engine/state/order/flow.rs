@@ -0,0 +1,141 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use fnv::FnvHashMap;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use toucan_execution::InstrumentIndex;
+
+/// Window over which the [`OrderFlow::recent_opens`] message rate is measured.
+pub const ORDER_RATE_WINDOW: TimeDelta = TimeDelta::seconds(1);
+
+/// Per-instrument tallies of the order requests sent and trades received during the trading
+/// session, used by the `LimitsRiskManager` message-rate and order-to-trade ratio limits.
+///
+/// The `EngineState` records every sent order request & trade, and the `Engine` updates the
+/// `time_now` from its [`EngineClock`](crate::engine::clock::EngineClock) before processing each
+/// event.
+#[derive(Debug, Clone, PartialEq, Default, Deserialize, Serialize)]
+pub struct OrderFlows {
+    /// Most recent `Engine` clock time.
+    pub time_now: Option<DateTime<Utc>>,
+
+    /// [`OrderFlow`] of every instrument that sent an order request or received a trade.
+    pub instruments: FnvHashMap<InstrumentIndex, OrderFlow>,
+}
+
+impl OrderFlows {
+    /// Update the current `Engine` clock time, discarding opens outside the
+    /// [`ORDER_RATE_WINDOW`].
+    pub fn update_time(&mut self, time: DateTime<Utc>) {
+        self.time_now = Some(time);
+        self.instruments
+            .values_mut()
+            .for_each(|flow| flow.prune(time));
+    }
+
+    /// Record an open order request sent for the provided instrument.
+    pub fn record_open(&mut self, instrument: &InstrumentIndex) {
+        let time_now = self.time_now;
+        let flow = self.instrument_mut(instrument);
+        flow.opens += 1;
+        if let Some(time) = time_now {
+            flow.recent_opens.push_back(time);
+        }
+    }
+
+    /// Record a cancel order request sent for the provided instrument.
+    pub fn record_cancel(&mut self, instrument: &InstrumentIndex) {
+        self.instrument_mut(instrument).cancels += 1;
+    }
+
+    /// Record a trade (ie/ fill) received for the provided instrument.
+    pub fn record_trade(&mut self, instrument: &InstrumentIndex) {
+        self.instrument_mut(instrument).trades += 1;
+    }
+
+    /// [`OrderFlow`] of the provided instrument, if any order request or trade was recorded.
+    pub fn instrument(&self, instrument: &InstrumentIndex) -> Option<&OrderFlow> {
+        self.instruments.get(instrument)
+    }
+
+    fn instrument_mut(&mut self, instrument: &InstrumentIndex) -> &mut OrderFlow {
+        self.instruments.entry(instrument.clone()).or_default()
+    }
+}
+
+/// Order request & trade tallies of an instrument.
+#[derive(Debug, Clone, Eq, PartialEq, Default, Deserialize, Serialize)]
+pub struct OrderFlow {
+    /// Times of the open order requests sent within the [`ORDER_RATE_WINDOW`].
+    pub recent_opens: VecDeque<DateTime<Utc>>,
+
+    /// Total open order requests sent.
+    pub opens: u64,
+
+    /// Total cancel order requests sent.
+    pub cancels: u64,
+
+    /// Total trades received.
+    pub trades: u64,
+}
+
+impl OrderFlow {
+    /// Number of open order requests sent within the [`ORDER_RATE_WINDOW`].
+    pub fn open_rate(&self) -> usize {
+        self.recent_opens.len()
+    }
+
+    /// Ratio of cancel order requests sent per trade received, using at least one trade as the
+    /// denominator.
+    pub fn order_to_trade_ratio(&self) -> Decimal {
+        Decimal::from(self.cancels) / Decimal::from(self.trades.max(1))
+    }
+
+    fn prune(&mut self, time: DateTime<Utc>) {
+        while self
+            .recent_opens
+            .front()
+            .is_some_and(|open| time.signed_duration_since(*open) >= ORDER_RATE_WINDOW)
+        {
+            self.recent_opens.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_order_flows() {
+        let instrument = InstrumentIndex::from("PETR4");
+        let time = DateTime::<Utc>::MIN_UTC;
+        let mut flows = OrderFlows::default();
+
+        flows.update_time(time);
+        flows.record_open(&instrument);
+        flows.update_time(time + TimeDelta::milliseconds(500));
+        flows.record_open(&instrument);
+        flows.record_cancel(&instrument);
+        flows.record_cancel(&instrument);
+        flows.record_cancel(&instrument);
+        assert_eq!(flows.instrument(&instrument).unwrap().open_rate(), 2);
+        assert_eq!(
+            flows
+                .instrument(&instrument)
+                .unwrap()
+                .order_to_trade_ratio(),
+            dec!(3)
+        );
+
+        // Opens older than the window are discarded, totals are kept
+        flows.update_time(time + TimeDelta::milliseconds(1200));
+        flows.record_trade(&instrument);
+        flows.record_trade(&instrument);
+        let flow = flows.instrument(&instrument).unwrap();
+        assert_eq!(flow.open_rate(), 1);
+        assert_eq!(flow.opens, 2);
+        assert_eq!(flow.order_to_trade_ratio(), dec!(1.5));
+    }
+}
@@ -53,6 +53,7 @@ where
 
         instrument_state.orders.record_in_flight_cancel(request);
         instrument_state.data.record_in_flight_cancel(request);
+        self.order_flow.record_cancel(&request.key.instrument);
     }
 
     fn record_in_flight_open(
@@ -65,5 +66,10 @@ where
 
         instrument_state.orders.record_in_flight_open(request);
         instrument_state.data.record_in_flight_open(request);
+        self.order_flow.record_open(&request.key.instrument);
+
+        if let Some(kill_switch) = &mut self.kill_switch {
+            kill_switch.record_open(&request.key);
+        }
     }
 }
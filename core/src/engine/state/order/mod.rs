@@ -18,6 +18,8 @@ use toucan_execution::{
 };
 use toucan_integration::snapshot::Snapshot;
 
+/// Per-instrument [`OrderFlows`](flow::OrderFlows) tallies of sent order requests & trades.
+pub mod flow;
 pub mod in_flight_recorder;
 pub mod manager;
 
@@ -58,7 +58,9 @@ impl TradingState {
 /// Audit record of a [`TradingState`] update, containing the previous and current state.
 ///
 /// Enables upstream components to ascertain if and how the [`TradingState`] has changed.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(
+    Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize,
+)]
 pub struct TradingStateUpdateAudit {
     pub prev: TradingState,
     pub current: TradingState,
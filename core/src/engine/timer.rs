@@ -0,0 +1,599 @@
+use crate::engine::command::Command;
+use chrono::{DateTime, Days, FixedOffset, NaiveTime, TimeDelta, Utc};
+use derive_more::{Display, From};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+use tracing::debug;
+
+/// Unique identifier for an `Engine` [`Timer`].
+#[derive(
+    Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize, Display, From,
+)]
+pub struct TimerId(pub SmolStr);
+
+impl TimerId {
+    pub fn new<S: AsRef<str>>(id: S) -> Self {
+        Self(SmolStr::new(id))
+    }
+}
+
+/// [`EngineEvent`](crate::EngineEvent) prompting the `Engine` to fire any due [`Timer`]s.
+///
+/// Timers are evaluated against the [`EngineClock`](super::clock::EngineClock) whilst processing
+/// every input event, so back-tests driven by a `HistoricalClock` fire them deterministically from
+/// the historical event timestamps. When live trading, a periodic `TimerTick` ensures timers also
+/// fire during quiet markets.
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Deserialize, Serialize,
+)]
+pub struct TimerTick;
+
+/// Defines when a [`Timer`] fires.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub enum TimerSchedule {
+    /// Fire once at the provided time.
+    ///
+    /// If the time has already passed when the `Timer` is first evaluated, it fires immediately.
+    Once(DateTime<Utc>),
+
+    /// Fire repeatedly, once every interval, starting one interval after the `Timer` is first
+    /// evaluated.
+    Interval(TimeDelta),
+
+    /// Fire every day at the provided local `time`, where the local timezone is a fixed offset
+    /// (in seconds) from UTC.
+    ///
+    /// eg/ 17:50 BRT is `NaiveTime(17:50)` with a `utc_offset_secs` of `-3 * 3600`.
+    Daily {
+        time: NaiveTime,
+        utc_offset_secs: i32,
+    },
+}
+
+impl TimerSchedule {
+    /// Construct a [`TimerSchedule::Once`] that fires at the provided time.
+    pub fn once(time: DateTime<Utc>) -> Self {
+        Self::Once(time)
+    }
+
+    /// Construct a [`TimerSchedule::Interval`] that fires every `interval`.
+    pub fn every(interval: TimeDelta) -> Self {
+        Self::Interval(interval)
+    }
+
+    /// Construct a [`TimerSchedule::Daily`] that fires every day at the provided local `time`.
+    pub fn daily(time: NaiveTime, offset: FixedOffset) -> Self {
+        Self::Daily {
+            time,
+            utc_offset_secs: offset.local_minus_utc(),
+        }
+    }
+
+    /// Determine the next time this schedule is due, given the previous due time (if any) and the
+    /// current `Engine` time.
+    ///
+    /// Returns `None` if the schedule is exhausted (ie/ a `Once` schedule that has fired).
+    fn next(&self, prev: Option<DateTime<Utc>>, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match (*self, prev) {
+            (Self::Once(time), None) => Some(time),
+            (Self::Once(_), Some(_)) => None,
+            (Self::Interval(interval), None) => now.checked_add_signed(interval),
+            (Self::Interval(interval), Some(prev)) => next_interval_after(prev, interval, now),
+            (
+                Self::Daily {
+                    time,
+                    utc_offset_secs,
+                },
+                _,
+            ) => next_daily_after(time, utc_offset_secs, now),
+        }
+    }
+}
+
+/// Next time in the sequence `prev + n * interval` that is strictly after `now`.
+///
+/// Missed intervals are skipped rather than fired in a burst (eg/ when a back-test has a gap in
+/// the historical market data).
+fn next_interval_after(
+    prev: DateTime<Utc>,
+    interval: TimeDelta,
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    if interval <= TimeDelta::zero() {
+        return None;
+    }
+
+    let elapsed = now.signed_duration_since(prev);
+    let (Some(elapsed), Some(interval_nanos)) =
+        (elapsed.num_nanoseconds(), interval.num_nanoseconds())
+    else {
+        return now.checked_add_signed(interval);
+    };
+
+    // Overflowing step (eg/ an extreme gap between prev & now) re-anchors the sequence at now
+    match elapsed
+        .div_euclid(interval_nanos)
+        .max(0)
+        .checked_add(1)
+        .and_then(|intervals| interval_nanos.checked_mul(intervals))
+    {
+        Some(step) => prev.checked_add_signed(TimeDelta::nanoseconds(step)),
+        None => now.checked_add_signed(interval),
+    }
+}
+
+/// Next occurrence of the local `time` (at the provided fixed UTC offset) strictly after `now`.
+fn next_daily_after(
+    time: NaiveTime,
+    utc_offset_secs: i32,
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let offset = FixedOffset::east_opt(utc_offset_secs)?;
+    let today = now
+        .with_timezone(&offset)
+        .date_naive()
+        .and_time(time)
+        .and_local_timezone(offset)
+        .single()?
+        .with_timezone(&Utc);
+
+    if today > now {
+        Some(today)
+    } else {
+        today.checked_add_days(Days::new(1))
+    }
+}
+
+/// One-shot or recurring `Engine` timer.
+///
+/// When a `Timer` fires, the `Engine` records a [`TimerFired`] that strategies can observe via
+/// [`Timers::is_fired`], and actions the optional [`Command`] (eg/ `CancelOrders` for stale order
+/// cancels, `ClosePositions` for end-of-day flattening).
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Timer {
+    pub id: TimerId,
+    pub schedule: TimerSchedule,
+    pub command: Option<Command>,
+
+    /// Next `Engine` time the `Timer` is due, or `None` if not yet evaluated.
+    pub time_next: Option<DateTime<Utc>>,
+}
+
+impl Timer {
+    /// Construct a new `Timer` that only notifies strategies when it fires.
+    pub fn new<Id>(id: Id, schedule: TimerSchedule) -> Self
+    where
+        Id: Into<TimerId>,
+    {
+        Self {
+            id: id.into(),
+            schedule,
+            command: None,
+            time_next: None,
+        }
+    }
+
+    /// Construct a new `Timer` that the `Engine` actions the provided [`Command`] for when it
+    /// fires.
+    pub fn with_command<Id>(id: Id, schedule: TimerSchedule, command: Command) -> Self
+    where
+        Id: Into<TimerId>,
+    {
+        Self {
+            command: Some(command),
+            ..Self::new(id, schedule)
+        }
+    }
+}
+
+/// Record of a [`Timer`] firing.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub struct TimerFired {
+    pub id: TimerId,
+    /// `Engine` time the `Timer` was due.
+    pub time_due: DateTime<Utc>,
+    /// `Engine` time the `Timer` was fired.
+    pub time_fired: DateTime<Utc>,
+}
+
+/// Request to register or cancel a [`Timer`] at runtime, made via [`Timers::request_schedule`]
+/// or [`Timers::request_cancel`].
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum TimerRequest {
+    Schedule(Timer),
+    Cancel(TimerId),
+}
+
+/// Pending [`TimerRequest`]s, queued via a shared reference to the [`Timers`].
+#[derive(Debug, Default)]
+struct TimerRequests(Mutex<Vec<TimerRequest>>);
+
+impl TimerRequests {
+    fn push(&self, request: TimerRequest) {
+        self.0.lock().push(request)
+    }
+
+    fn take(&mut self) -> Vec<TimerRequest> {
+        std::mem::take(self.0.get_mut())
+    }
+}
+
+impl Clone for TimerRequests {
+    fn clone(&self) -> Self {
+        Self(Mutex::new(self.0.lock().clone()))
+    }
+}
+
+impl PartialEq for TimerRequests {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other) || *self.0.lock() == *other.0.lock()
+    }
+}
+
+/// Collection of registered `Engine` [`Timer`]s.
+///
+/// Also records the timers that fired whilst the `Engine` processed the most recent event, so an
+/// `AlgoStrategy` can react to them in `generate_algo_orders`.
+///
+/// Since an `AlgoStrategy` is only provided a shared reference to the `EngineState`, it schedules
+/// & cancels timers at runtime via [`TimerRequest`]s, which are applied the next time the `Engine`
+/// fires due timers.
+#[derive(Debug, Clone, PartialEq, Default, Deserialize, Serialize)]
+pub struct Timers {
+    timers: Vec<Timer>,
+    fired: Vec<TimerFired>,
+    #[serde(skip)]
+    requests: TimerRequests,
+}
+
+impl Timers {
+    /// Register a [`Timer`], returning any existing `Timer` with the same [`TimerId`] it replaced.
+    pub fn schedule(&mut self, timer: Timer) -> Option<Timer> {
+        let replaced = self.cancel(&timer.id);
+        self.timers.push(timer);
+        replaced
+    }
+
+    /// Cancel the [`Timer`] associated with the provided [`TimerId`], returning it if present.
+    pub fn cancel(&mut self, id: &TimerId) -> Option<Timer> {
+        let index = self.timers.iter().position(|timer| &timer.id == id)?;
+        Some(self.timers.remove(index))
+    }
+
+    /// Request a [`Timer`] is registered, replacing any existing `Timer` with the same [`TimerId`]
+    /// once applied.
+    pub fn request_schedule(&self, timer: Timer) {
+        self.requests.push(TimerRequest::Schedule(timer))
+    }
+
+    /// Request the [`Timer`] associated with the provided [`TimerId`] is cancelled once applied.
+    pub fn request_cancel(&self, id: TimerId) {
+        self.requests.push(TimerRequest::Cancel(id))
+    }
+
+    /// Apply all pending [`TimerRequest`]s in the order they were requested.
+    pub fn apply_requests(&mut self) {
+        for request in self.requests.take() {
+            match request {
+                TimerRequest::Schedule(timer) => {
+                    self.schedule(timer);
+                }
+                TimerRequest::Cancel(id) => {
+                    self.cancel(&id);
+                }
+            }
+        }
+    }
+
+    /// Return a reference to the [`Timer`] associated with the provided [`TimerId`], if present.
+    pub fn timer(&self, id: &TimerId) -> Option<&Timer> {
+        self.timers.iter().find(|timer| &timer.id == id)
+    }
+
+    /// Return an `Iterator` of all registered [`Timer`]s.
+    pub fn timers(&self) -> impl Iterator<Item = &Timer> {
+        self.timers.iter()
+    }
+
+    /// [`TimerFired`]s generated whilst the `Engine` processed the most recent event.
+    pub fn fired(&self) -> &[TimerFired] {
+        &self.fired
+    }
+
+    /// Returns `true` if the [`Timer`] associated with the provided [`TimerId`] fired whilst the
+    /// `Engine` processed the most recent event.
+    pub fn is_fired(&self, id: &TimerId) -> bool {
+        self.fired.iter().any(|fired| &fired.id == id)
+    }
+
+    /// Fire all [`Timer`]s that are due at the provided `Engine` time, after applying any pending
+    /// [`TimerRequest`]s.
+    ///
+    /// Returns each [`TimerFired`] in the order the timers were due, alongside the associated
+    /// [`Command`] that should be actioned (if any). Exhausted one-shot timers are removed.
+    pub fn fire_due(&mut self, now: DateTime<Utc>) -> Vec<(TimerFired, Option<Command>)> {
+        self.apply_requests();
+
+        let mut fired = Vec::new();
+
+        self.timers.retain_mut(|timer| {
+            let Some(time_due) = timer.time_next.or_else(|| timer.schedule.next(None, now)) else {
+                return false;
+            };
+
+            if time_due > now {
+                timer.time_next = Some(time_due);
+                return true;
+            }
+
+            debug!(id = %timer.id, %time_due, %now, "Engine Timer fired");
+            fired.push((
+                TimerFired {
+                    id: timer.id.clone(),
+                    time_due,
+                    time_fired: now,
+                },
+                timer.command.clone(),
+            ));
+
+            timer.time_next = timer.schedule.next(Some(time_due), now);
+            timer.time_next.is_some()
+        });
+
+        fired.sort_by(|(a, _), (b, _)| a.time_due.cmp(&b.time_due).then(a.id.cmp(&b.id)));
+        self.fired = fired.iter().map(|(fired, _)| fired.clone()).collect();
+
+        fired
+    }
+}
+
+impl FromIterator<Timer> for Timers {
+    fn from_iter<Iter>(iter: Iter) -> Self
+    where
+        Iter: IntoIterator<Item = Timer>,
+    {
+        let mut timers = Self::default();
+        iter.into_iter().for_each(|timer| {
+            timers.schedule(timer);
+        });
+        timers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        engine::state::instrument::filter::InstrumentFilter,
+        test_utils::{time_plus_millis, time_plus_secs},
+    };
+
+    fn base() -> DateTime<Utc> {
+        DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap()
+    }
+
+    fn fired_ids(fired: &[(TimerFired, Option<Command>)]) -> Vec<&str> {
+        fired.iter().map(|(fired, _)| fired.id.0.as_str()).collect()
+    }
+
+    #[test]
+    fn test_timers_fire_due_once() {
+        let mut timers = Timers::from_iter([Timer::new(
+            TimerId::new("once"),
+            TimerSchedule::once(time_plus_secs(base(), 10)),
+        )]);
+
+        assert!(timers.fire_due(time_plus_secs(base(), 5)).is_empty());
+        assert!(!timers.is_fired(&TimerId::new("once")));
+
+        let fired = timers.fire_due(time_plus_secs(base(), 11));
+        assert_eq!(fired_ids(&fired), vec!["once"]);
+        assert_eq!(fired[0].0.time_due, time_plus_secs(base(), 10));
+        assert_eq!(fired[0].0.time_fired, time_plus_secs(base(), 11));
+        assert!(timers.is_fired(&TimerId::new("once")));
+
+        // Exhausted one-shot Timer is removed
+        assert!(timers.timer(&TimerId::new("once")).is_none());
+        assert!(timers.fire_due(time_plus_secs(base(), 20)).is_empty());
+        assert!(timers.fired().is_empty());
+    }
+
+    #[test]
+    fn test_timers_fire_due_interval() {
+        struct TestCase {
+            now_millis: i64,
+            expected_fired: bool,
+            expected_time_next_millis: i64,
+        }
+
+        let mut timers = Timers::default();
+        timers.schedule(Timer::new(
+            TimerId::new("every_1s"),
+            TimerSchedule::every(TimeDelta::seconds(1)),
+        ));
+
+        let cases = vec![
+            // TC0: first evaluation anchors the Timer one interval ahead
+            TestCase {
+                now_millis: 0,
+                expected_fired: false,
+                expected_time_next_millis: 1000,
+            },
+            // TC1: not yet due
+            TestCase {
+                now_millis: 999,
+                expected_fired: false,
+                expected_time_next_millis: 1000,
+            },
+            // TC2: exactly due
+            TestCase {
+                now_millis: 1000,
+                expected_fired: true,
+                expected_time_next_millis: 2000,
+            },
+            // TC3: gap in events skips missed intervals without drifting
+            TestCase {
+                now_millis: 5500,
+                expected_fired: true,
+                expected_time_next_millis: 6000,
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let fired = timers.fire_due(time_plus_millis(base(), test.now_millis));
+            assert_eq!(!fired.is_empty(), test.expected_fired, "TC{index} failed");
+            assert_eq!(
+                timers.timer(&TimerId::new("every_1s")).unwrap().time_next,
+                Some(time_plus_millis(base(), test.expected_time_next_millis)),
+                "TC{index} failed"
+            );
+        }
+    }
+
+    #[test]
+    fn test_timers_fire_due_daily() {
+        // 17:50 BRT (UTC-3) is 20:50 UTC
+        let brt = FixedOffset::west_opt(3 * 3600).unwrap();
+        let schedule = TimerSchedule::daily(NaiveTime::from_hms_opt(17, 50, 0).unwrap(), brt);
+        let day = |hour, min| {
+            DateTime::parse_from_rfc3339(&format!("2024-03-15T{hour:02}:{min:02}:00Z"))
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+
+        let mut timers = Timers::from_iter([Timer::new(TimerId::new("eod"), schedule)]);
+
+        assert!(timers.fire_due(day(13, 0)).is_empty());
+        assert_eq!(
+            timers.timer(&TimerId::new("eod")).unwrap().time_next,
+            Some(day(20, 50))
+        );
+
+        let fired = timers.fire_due(day(20, 51));
+        assert_eq!(fired_ids(&fired), vec!["eod"]);
+        assert_eq!(
+            timers.timer(&TimerId::new("eod")).unwrap().time_next,
+            Some(day(20, 50) + TimeDelta::days(1))
+        );
+    }
+
+    #[test]
+    fn test_timers_schedule_replace_and_cancel() {
+        let mut timers = Timers::default();
+        let command = Command::CancelOrders(InstrumentFilter::None);
+
+        assert!(timers
+            .schedule(Timer::new(
+                TimerId::new("stale"),
+                TimerSchedule::every(TimeDelta::seconds(5))
+            ))
+            .is_none());
+
+        let replaced = timers.schedule(Timer::with_command(
+            TimerId::new("stale"),
+            TimerSchedule::once(base()),
+            command.clone(),
+        ));
+        assert_eq!(
+            replaced.map(|timer| timer.schedule),
+            Some(TimerSchedule::Interval(TimeDelta::seconds(5)))
+        );
+        assert_eq!(timers.timers().count(), 1);
+
+        let fired = timers.fire_due(base());
+        assert_eq!(fired_ids(&fired), vec!["stale"]);
+        assert_eq!(fired[0].1, Some(command));
+
+        timers.schedule(Timer::new(
+            TimerId::new("cancelled"),
+            TimerSchedule::once(base()),
+        ));
+        assert!(timers.cancel(&TimerId::new("cancelled")).is_some());
+        assert!(timers.fire_due(base()).is_empty());
+    }
+
+    #[test]
+    fn test_next_interval_after() {
+        struct TestCase {
+            interval: TimeDelta,
+            now: DateTime<Utc>,
+            expected: Option<DateTime<Utc>>,
+        }
+
+        let cases = vec![
+            // TC0: next interval strictly after now
+            TestCase {
+                interval: TimeDelta::seconds(1),
+                now: time_plus_millis(base(), 2500),
+                expected: Some(time_plus_millis(base(), 3000)),
+            },
+            // TC1: intervals missed exceeding i32::MAX do not truncate
+            TestCase {
+                interval: TimeDelta::nanoseconds(1),
+                now: time_plus_secs(base(), 10),
+                expected: Some(time_plus_secs(base(), 10) + TimeDelta::nanoseconds(1)),
+            },
+            // TC2: overflowing step falls back to one interval after now
+            TestCase {
+                interval: TimeDelta::nanoseconds(1 << 62),
+                now: time_plus_secs(base(), 1) + TimeDelta::nanoseconds(1 << 62),
+                expected: Some(time_plus_secs(base(), 1) + TimeDelta::nanoseconds(1 << 62) * 2),
+            },
+            // TC3: non-positive interval never fires
+            TestCase {
+                interval: TimeDelta::zero(),
+                now: time_plus_secs(base(), 1),
+                expected: None,
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let actual = next_interval_after(base(), test.interval, test.now);
+            assert_eq!(actual, test.expected, "TC{index} failed");
+        }
+    }
+
+    #[test]
+    fn test_timers_requests_applied_before_fire_due() {
+        let mut timers = Timers::from_iter([Timer::new(
+            TimerId::new("cancelled"),
+            TimerSchedule::once(base()),
+        )]);
+
+        timers.request_schedule(Timer::new(
+            TimerId::new("scheduled"),
+            TimerSchedule::once(base()),
+        ));
+        timers.request_cancel(TimerId::new("cancelled"));
+
+        // Requests are pending until applied
+        assert!(timers.timer(&TimerId::new("scheduled")).is_none());
+        assert!(timers.timer(&TimerId::new("cancelled")).is_some());
+
+        let fired = timers.fire_due(base());
+        assert_eq!(fired_ids(&fired), vec!["scheduled"]);
+        assert!(timers.timers().next().is_none());
+    }
+
+    #[test]
+    fn test_timers_fire_due_ordered_by_time_due() {
+        let mut timers = Timers::from_iter([
+            Timer::new(
+                TimerId::new("b"),
+                TimerSchedule::once(time_plus_secs(base(), 2)),
+            ),
+            Timer::new(
+                TimerId::new("a"),
+                TimerSchedule::once(time_plus_secs(base(), 3)),
+            ),
+            Timer::new(
+                TimerId::new("c"),
+                TimerSchedule::once(time_plus_secs(base(), 1)),
+            ),
+        ]);
+
+        let fired = timers.fire_due(time_plus_secs(base(), 10));
+        assert_eq!(fired_ids(&fired), vec!["c", "b", "a"]);
+    }
+}
@@ -13,6 +13,7 @@
 //! - **MarketData**: Errors from the data module (streaming, parsing, subscription)
 //! - **Execution**: Execution errors (orders, balances, liquidations)
 //! - **JoinError**: Failures when awaiting async tasks (join)
+//! - **Config**: Errors loading or validating a `SystemConfig`
 //!
 //! ## Usage
 //!
@@ -32,7 +33,7 @@
 //! }
 //! ```
 
-use crate::execution::error::ExecutionError;
+use crate::{execution::error::ExecutionError, system::config::ConfigError};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use toucan_data::error::DataError;
@@ -59,6 +60,9 @@ pub enum ToucanError {
     /// Indicates that the receiver side of a communication channel was dropped
     #[error("ExecutionRxDropped: {0}")]
     ExecutionRxDropped(RxDropped),
+    /// Errors loading or validating a `SystemConfig`
+    #[error("Config: {0}")]
+    Config(#[from] ConfigError),
 }
 /// Indicates that the receiver side of a communication channel was dropped.
 ///
@@ -1,8 +1,11 @@
 
-use crate::execution::{
-    error::ExecutionError,
-    request::{ExecutionRequest, RequestFuture},
-    AccountStreamEvent,
+use crate::{
+    execution::{
+        error::ExecutionError,
+        request::{ExecutionRequest, RequestFuture},
+        AccountStreamEvent,
+    },
+    metric::ExecutionMetrics,
 };
 use toucan_data::streams::{
     consumer::StreamKey,
@@ -20,11 +23,13 @@ use toucan_execution::{
         state::{Open, OrderState},
         Order,
     },
+    trace::OrderTrace,
     AccountEvent, AccountEventKind,
 };
 use toucan_execution::{AssetIndex, ExchangeIndex, IndexError, InstrumentIndex};
 use toucan_integration::{
     channel::{mpsc_unbounded, Tx, UnboundedTx},
+    metric::registry::{Histogram, MetricRegistry},
     snapshot::Snapshot,
     stream::merge::merge,
 };
@@ -35,7 +40,7 @@ pub type AssetNameExchange = String;
 pub type InstrumentNameExchange = String;
 use derive_more::Constructor;
 use futures::{future::Either, stream::FuturesUnordered, Stream, StreamExt};
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
 use tracing::{error, info, warn};
 
 /// Per-exchange execution manager that actions order requests from the Engine and forwards back
@@ -63,6 +68,9 @@ pub struct ExecutionManager<RequestStream, Client> {
     ///
     /// For example, `InstrumentNameExchange` -> `InstrumentIndex`.
     pub indexer: AccountEventIndexer,
+
+    /// Request throughput, acknowledgement latency, timeout & rejection metrics.
+    pub metrics: ExecutionMetrics,
 }
 
 impl<RequestStream, Client> ExecutionManager<RequestStream, Client>
@@ -135,9 +143,12 @@ where
             response_rx.into_stream(),
             account_stream
                 .with_reconnect_backoff::<_, ExecutionError>(reconnect_policy, stream_key)
-                .with_reconnection_events(indexer.map.exchange.value),
+                .with_reconnection_events(indexer.map.exchange.value)
+                .with_metrics(MetricRegistry::global(), "account"),
         );
 
+        let metrics = ExecutionMetrics::new(MetricRegistry::global(), indexer.map.exchange.value);
+
         Ok((
             Self::new(
                 request_stream,
@@ -145,6 +156,7 @@ where
                 response_tx,
                 client,
                 indexer,
+                metrics,
             ),
             merged_account_stream,
         ))
@@ -186,6 +198,7 @@ where
                     broker: None,
                     account: None,
                     kind: AccountEventKind::Snapshot(indexed_snapshot),
+                    trace: None,
                 })
             }
             Err(error) => Err(ExecutionError::Client(indexer.client_error(error)?)),
@@ -244,7 +257,7 @@ where
                     Some(ExecutionRequest::Shutdown) | None => {
                         break;
                     }
-                    Some(ExecutionRequest::Cancel(request)) => {
+                    Some(ExecutionRequest::Cancel(request, trace)) => {
                         // Panic since the system is set up incorrectly, so it's foolish to continue
                         let client_request = self
                             .indexer
@@ -253,13 +266,18 @@ where
                                 "ExecutionManager received cancel request for non-configured key: {error}"
                             ));
 
+                        self.metrics.cancel_requests.increment();
                         in_flight_cancels.push(RequestFuture::new(
-                            self.client.cancel_order(client_request),
+                            with_latency(
+                                self.client.cancel_order(client_request),
+                                self.metrics.cancel_latency.clone(),
+                                trace,
+                            ),
                             self.request_timeout,
-                            request,
+                            (request, trace),
                         ))
                     },
-                    Some(ExecutionRequest::Open(request)) => {
+                    Some(ExecutionRequest::Open(request, trace)) => {
                         // Panic since the system is set up incorrectly, so it's foolish to continue
                         let client_request = self
                             .indexer
@@ -268,10 +286,15 @@ where
                                 "ExecutionManager received open request for non-configured key: {error}"
                             ));
 
+                        self.metrics.open_requests.increment();
                         in_flight_opens.push(RequestFuture::new(
-                            self.client.open_order(client_request),
+                            with_latency(
+                                self.client.open_order(client_request),
+                                self.metrics.open_latency.clone(),
+                                trace,
+                            ),
                             self.request_timeout,
-                            request,
+                            (request, trace),
                         ))
                     }
                 },
@@ -279,8 +302,8 @@ where
                 // Process next ExecutionRequest::Cancel response
                 response_cancel = next_cancel_response => {
                     match response_cancel {
-                        Ok(Some(response)) => {
-                            let event = match self.process_cancel_response(response) {
+                        Ok((Some(response), trace)) => {
+                            let event = match self.process_cancel_response(response, trace) {
                                 Ok(indexed_event) => indexed_event,
                                 Err(error) => {
                                     warn!(
@@ -296,14 +319,15 @@ where
                                 break;
                             }
                         }
-                        Err(request) => {
-                            let event = Self::process_cancel_timeout(request);
+                        Err((request, trace)) => {
+                            self.metrics.cancel_timeouts.increment();
+                            let event = Self::process_cancel_timeout(request, trace);
 
                             if self.response_tx.send(event).is_err() {
                                 break;
                             }
                         }
-                        Ok(None) => {
+                        Ok((None, _)) => {
                             // Do nothing
                         }
                     };
@@ -312,8 +336,12 @@ where
                 // Process next ExecutionRequest::Open response
                 response_open = next_open_response => {
                     match response_open {
-                        Ok(Some(response)) => {
-                            let event = match self.process_open_response(response) {
+                        Ok((Some(response), trace)) => {
+                            if response.state.is_err() {
+                                self.metrics.rejects.increment();
+                            }
+
+                            let event = match self.process_open_response(response, trace) {
                                 Ok(indexed_event) => indexed_event,
                                 Err(error) => {
                                     warn!(
@@ -329,14 +357,15 @@ where
                                 break;
                             }
                         }
-                        Err(request) => {
-                            let event = Self::process_open_timeout(request);
+                        Err((request, trace)) => {
+                            self.metrics.open_timeouts.increment();
+                            let event = Self::process_open_timeout(request, trace);
 
                             if self.response_tx.send(event).is_err() {
                                 break;
                             }
                         }
-                        Ok(None) => {
+                        Ok((None, _)) => {
                             // Do nothing
                         }
                     }
@@ -353,6 +382,7 @@ where
     fn process_cancel_response(
         &self,
         order: UnindexedOrderResponseCancel,
+        trace: Option<OrderTrace>,
     ) -> Result<AccountStreamEvent, IndexError> {
         let order = self.indexer.order_response_cancel(order)?;
 
@@ -361,11 +391,13 @@ where
             broker: None,
             account: None,
             kind: AccountEventKind::OrderCancelled(order),
+            trace: trace.map(Box::new),
         }))
     }
 
     fn process_cancel_timeout(
         order: OrderRequestCancel<ExchangeIndex, InstrumentIndex>,
+        trace: Option<OrderTrace>,
     ) -> AccountStreamEvent {
         let OrderRequestCancel { key, state: _ } = order;
 
@@ -377,12 +409,14 @@ where
                 key,
                 state: Err(OrderError::Connectivity(ConnectivityError::Timeout)),
             }),
+            trace: trace.map(Box::new),
         })
     }
 
     fn process_open_response(
         &self,
         order: Order<ExchangeId, InstrumentNameExchange, Result<Open, UnindexedOrderError>>,
+        trace: Option<OrderTrace>,
     ) -> Result<AccountStreamEvent, IndexError> {
         let Order {
             key,
@@ -415,11 +449,13 @@ where
                 time_in_force,
                 state,
             })),
+            trace: trace.map(Box::new),
         }))
     }
 
     fn process_open_timeout(
         order: OrderRequestOpen<ExchangeIndex, InstrumentIndex>,
+        trace: Option<OrderTrace>,
     ) -> AccountStreamEvent {
         let OrderRequestOpen { key, state } = order;
 
@@ -436,6 +472,24 @@ where
                 time_in_force: state.time_in_force,
                 state: OrderState::inactive(OrderError::Connectivity(ConnectivityError::Timeout)),
             })),
+            trace: trace.map(Box::new),
         })
     }
 }
+
+/// Record the elapsed time until the provided request future resolves (ie/ is acknowledged),
+/// stamping the acknowledgement on the request [`OrderTrace`] if present.
+async fn with_latency<Fut>(
+    future: Fut,
+    latency: Histogram,
+    trace: Option<OrderTrace>,
+) -> (Fut::Output, Option<OrderTrace>)
+where
+    Fut: std::future::Future,
+{
+    let start = Instant::now();
+    let output = future.await;
+    let elapsed = start.elapsed();
+    latency.observe_duration(elapsed);
+    (output, trace.map(|trace| trace.acknowledged(elapsed)))
+}
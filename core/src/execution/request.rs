@@ -1,5 +1,4 @@
 
-use derive_more::From;
 use serde::{Deserialize, Serialize};
 use std::{
     future::Future,
@@ -8,20 +7,65 @@ use std::{
 };
 use toucan_execution::{
     order::request::{OrderRequestCancel, OrderRequestOpen},
+    trace::OrderTrace,
     ExchangeIndex, InstrumentIndex,
 };
 
 /// Represents an `Engine` request to the `ExecutionManager`.
-#[derive(Debug, Clone, PartialEq, PartialOrd, Deserialize, Serialize, From)]
+///
+/// Order requests carry the optional [`OrderTrace`] of the event that generated them, which the
+/// `ExecutionManager` returns on the `AccountEvent` responding to the request.
+#[derive(Debug, Clone, PartialEq, PartialOrd, Deserialize, Serialize)]
 pub enum ExecutionRequest<ExchangeKey = ExchangeIndex, InstrumentKey = InstrumentIndex> {
     /// Request `ExecutionManager` shutdown.
     Shutdown,
 
     /// Request to cancel an existing `Order`.
-    Cancel(OrderRequestCancel<ExchangeKey, InstrumentKey>),
+    Cancel(
+        OrderRequestCancel<ExchangeKey, InstrumentKey>,
+        Option<OrderTrace>,
+    ),
 
     /// Request to open an new `Order`.
-    Open(OrderRequestOpen<ExchangeKey, InstrumentKey>),
+    Open(
+        OrderRequestOpen<ExchangeKey, InstrumentKey>,
+        Option<OrderTrace>,
+    ),
+}
+
+impl<ExchangeKey, InstrumentKey> ExecutionRequest<ExchangeKey, InstrumentKey> {
+    /// Attach the [`OrderTrace`] of the event that generated this order request.
+    pub fn with_trace(self, trace: Option<OrderTrace>) -> Self {
+        match self {
+            Self::Shutdown => Self::Shutdown,
+            Self::Cancel(request, _) => Self::Cancel(request, trace),
+            Self::Open(request, _) => Self::Open(request, trace),
+        }
+    }
+
+    /// [`OrderTrace`] of the event that generated this order request, if any.
+    pub fn trace(&self) -> Option<&OrderTrace> {
+        match self {
+            Self::Shutdown => None,
+            Self::Cancel(_, trace) | Self::Open(_, trace) => trace.as_ref(),
+        }
+    }
+}
+
+impl<ExchangeKey, InstrumentKey> From<OrderRequestCancel<ExchangeKey, InstrumentKey>>
+    for ExecutionRequest<ExchangeKey, InstrumentKey>
+{
+    fn from(value: OrderRequestCancel<ExchangeKey, InstrumentKey>) -> Self {
+        Self::Cancel(value, None)
+    }
+}
+
+impl<ExchangeKey, InstrumentKey> From<OrderRequestOpen<ExchangeKey, InstrumentKey>>
+    for ExecutionRequest<ExchangeKey, InstrumentKey>
+{
+    fn from(value: OrderRequestOpen<ExchangeKey, InstrumentKey>) -> Self {
+        Self::Open(value, None)
+    }
 }
 
 #[derive(Debug)]
@@ -75,13 +75,13 @@
 /// simultaneously and offers flexibility to run most types of trading strategies.
 /// It allows enabling/disabling algorithmic order generation and can execute commands issued from external processes (e.g., CloseAllPositions, OpenOrders, CancelOrders, etc.)
 use crate::{
-    engine::{command::Command, state::trading::TradingState},
+    engine::{command::Command, state::trading::TradingState, timer::TimerTick},
     execution::AccountStreamEvent,
 };
 use chrono::{DateTime, Utc};
 use derive_more::{Constructor, From};
 use serde::{Deserialize, Serialize};
-use shutdown::Shutdown;
+use shutdown::{GracefulShutdown, Shutdown};
 use toucan_data::{
     event::{DataKind, MarketEvent},
     streams::consumer::MarketStreamEvent,
@@ -107,6 +107,9 @@ pub mod execution;
 /// Provides default Core Tracing logging initialisers.
 pub mod logging;
 
+/// Standard `Engine`, `ExecutionManager` & PnL metrics, exportable via Prometheus or InfluxDB.
+pub mod metric;
+
 /// RiskManager interface for reviewing and optionally filtering algorithmic cancel and open
 /// order requests.
 pub use toucan_risk as risk;
@@ -123,6 +126,10 @@ pub use toucan_analytics as analytics; // transitional re-export
 /// Utilities for initialising and interacting with a full trading system.
 pub mod system;
 
+/// Optional embedded HTTP & WebSocket server for controlling and monitoring a running `System`.
+#[cfg(feature = "server")]
+pub mod server;
+
 /// Backtesting utilities.
 pub mod backtest;
 
@@ -150,7 +157,7 @@ pub struct Timed<T> {
 }
 
 /// Default [`Engine`](engine::Engine) event that encompasses market events, account/execution
-/// events, and `Engine` commands.
+/// events, `Engine` commands, and timer ticks.
 ///
 /// Note that the `Engine` can be configured to process custom events.
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize, From)]
@@ -161,8 +168,10 @@ pub enum EngineEvent<
     InstrumentKey = InstrumentIndex,
 > {
     Shutdown(Shutdown),
+    GracefulShutdown(GracefulShutdown),
     Command(Command<ExchangeKey, AssetKey, InstrumentKey>),
     TradingStateUpdate(TradingState),
+    Timer(TimerTick),
     Account(AccountStreamEvent<ExchangeKey, AssetKey, InstrumentKey>),
     Market(MarketStreamEvent<InstrumentKey, MarketKind>),
 }
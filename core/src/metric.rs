@@ -0,0 +1,477 @@
+//! Standard Toucan metrics, recorded in a [`MetricRegistry`] and exported via the
+//! `toucan_integration::metric` Prometheus endpoint or InfluxDB writer.
+//!
+//! | Metric                                     | Kind      | Tags                  |
+//! |--------------------------------------------|-----------|-----------------------|
+//! | `toucan_engine_feed_events_total`          | counter   |                       |
+//! | `toucan_engine_feed_queue_depth`           | gauge     |                       |
+//! | `toucan_engine_audit_queue_depth`          | gauge     |                       |
+//! | `toucan_engine_events_processed_total`     | counter   | `kind`                |
+//! | `toucan_engine_reconnects_total`           | counter   | `stream`, `exchange`  |
+//! | `toucan_engine_unrecoverable_errors_total` | counter   |                       |
+//! | `toucan_engine_kill_switch_trips_total`    | counter   |                       |
+//! | `toucan_engine_stale_feeds_total`          | counter   | `stream`, `exchange`  |
+//! | `toucan_engine_trading_enabled`            | gauge     |                       |
+//! | `toucan_pnl_realised`                      | gauge     | `instrument`          |
+//! | `toucan_pnl_unrealised`                    | gauge     | `instrument`          |
+//! | `toucan_execution_requests_total`          | counter   | `exchange`, `request` |
+//! | `toucan_execution_ack_latency_ms`          | histogram | `exchange`, `request` |
+//! | `toucan_execution_timeouts_total`          | counter   | `exchange`, `request` |
+//! | `toucan_execution_order_rejects_total`     | counter   | `exchange`            |
+//! | `toucan_order_latency_ms`                  | histogram | `exchange`, `stage`   |
+//!
+//! Reconnecting streams can additionally be metered via
+//! [`ReconnectingStream::with_metrics`](toucan_data::streams::reconnect::stream::ReconnectingStream::with_metrics).
+
+use crate::{
+    engine::{
+        audit::{
+            context::EngineContext, state_replica::StateReplicaManager, AuditTick, Auditor,
+            EngineAudit,
+        },
+        latency::OrderLatency,
+        state::{
+            connectivity::stale::Feed,
+            instrument::{data::InstrumentDataState, InstrumentNameInternal},
+            trading::TradingState,
+            EngineState,
+        },
+        EngineOutput, Processor,
+    },
+    system::System,
+    EngineEvent,
+};
+use fnv::FnvHashMap;
+use futures::StreamExt;
+use rust_decimal::prelude::ToPrimitive;
+use std::fmt::Debug;
+use tokio::task::JoinHandle;
+use toucan_data::{event::MarketEvent, streams::reconnect};
+use toucan_execution::{trace::LatencyStage, AccountEvent, InstrumentIndex};
+use toucan_instrument::exchange::ExchangeId;
+use toucan_integration::{
+    channel::UnboundedRx,
+    metric::{
+        metered::MeteredRx,
+        registry::{Counter, Gauge, Histogram, MetricRegistry, DEFAULT_LATENCY_BUCKETS_MS},
+        Tag,
+    },
+    snapshot::SnapUpdates,
+};
+use tracing::info;
+
+pub const ENGINE_FEED_EVENTS: &str = "toucan_engine_feed_events_total";
+pub const ENGINE_FEED_QUEUE_DEPTH: &str = "toucan_engine_feed_queue_depth";
+pub const ENGINE_AUDIT_EVENTS: &str = "toucan_engine_audit_events_total";
+pub const ENGINE_AUDIT_QUEUE_DEPTH: &str = "toucan_engine_audit_queue_depth";
+pub const ENGINE_EVENTS_PROCESSED: &str = "toucan_engine_events_processed_total";
+pub const ENGINE_RECONNECTS: &str = "toucan_engine_reconnects_total";
+pub const ENGINE_UNRECOVERABLE_ERRORS: &str = "toucan_engine_unrecoverable_errors_total";
+pub const ENGINE_KILL_SWITCH_TRIPS: &str = "toucan_engine_kill_switch_trips_total";
+pub const ENGINE_STALE_FEEDS: &str = "toucan_engine_stale_feeds_total";
+pub const ENGINE_TRADING_ENABLED: &str = "toucan_engine_trading_enabled";
+pub const PNL_REALISED: &str = "toucan_pnl_realised";
+pub const PNL_UNREALISED: &str = "toucan_pnl_unrealised";
+pub const EXECUTION_REQUESTS: &str = "toucan_execution_requests_total";
+pub const EXECUTION_ACK_LATENCY_MS: &str = "toucan_execution_ack_latency_ms";
+pub const EXECUTION_TIMEOUTS: &str = "toucan_execution_timeouts_total";
+pub const EXECUTION_ORDER_REJECTS: &str = "toucan_execution_order_rejects_total";
+pub const ORDER_LATENCY_MS: &str = "toucan_order_latency_ms";
+
+/// Wrap the `Engine` feed [`UnboundedRx`] to record [`ENGINE_FEED_EVENTS`] throughput and
+/// [`ENGINE_FEED_QUEUE_DEPTH`].
+pub fn metered_feed<Event>(registry: &MetricRegistry, rx: UnboundedRx<Event>) -> MeteredRx<Event> {
+    MeteredRx::new(
+        rx,
+        registry.counter(ENGINE_FEED_EVENTS, Vec::<Tag>::new()),
+        registry.gauge(ENGINE_FEED_QUEUE_DEPTH, Vec::<Tag>::new()),
+    )
+}
+
+/// Wrap the `Engine` AuditStream [`UnboundedRx`] to record [`ENGINE_AUDIT_QUEUE_DEPTH`].
+pub fn metered_audit<Audit>(registry: &MetricRegistry, rx: UnboundedRx<Audit>) -> MeteredRx<Audit> {
+    MeteredRx::new(
+        rx,
+        registry.counter(ENGINE_AUDIT_EVENTS, Vec::<Tag>::new()),
+        registry.gauge(ENGINE_AUDIT_QUEUE_DEPTH, Vec::<Tag>::new()),
+    )
+}
+
+/// Per-exchange `ExecutionManager` metric handles.
+#[derive(Debug, Clone)]
+pub struct ExecutionMetrics {
+    pub open_requests: Counter,
+    pub cancel_requests: Counter,
+    pub open_latency: Histogram,
+    pub cancel_latency: Histogram,
+    pub open_timeouts: Counter,
+    pub cancel_timeouts: Counter,
+    pub rejects: Counter,
+}
+
+impl ExecutionMetrics {
+    pub fn new(registry: &MetricRegistry, exchange: ExchangeId) -> Self {
+        let tags = |request: &'static str| {
+            [
+                Tag::new("exchange", exchange.as_str()),
+                Tag::new("request", request),
+            ]
+        };
+
+        Self {
+            open_requests: registry.counter(EXECUTION_REQUESTS, tags("open")),
+            cancel_requests: registry.counter(EXECUTION_REQUESTS, tags("cancel")),
+            open_latency: registry.histogram(
+                EXECUTION_ACK_LATENCY_MS,
+                tags("open"),
+                DEFAULT_LATENCY_BUCKETS_MS,
+            ),
+            cancel_latency: registry.histogram(
+                EXECUTION_ACK_LATENCY_MS,
+                tags("cancel"),
+                DEFAULT_LATENCY_BUCKETS_MS,
+            ),
+            open_timeouts: registry.counter(EXECUTION_TIMEOUTS, tags("open")),
+            cancel_timeouts: registry.counter(EXECUTION_TIMEOUTS, tags("cancel")),
+            rejects: registry.counter(
+                EXECUTION_ORDER_REJECTS,
+                [Tag::new("exchange", exchange.as_str())],
+            ),
+        }
+    }
+}
+
+/// `Engine` metrics derived from the AuditStream and an `EngineState` replica, keeping all metric
+/// recording off the `Engine` hot path.
+#[derive(Debug, Clone)]
+pub struct EngineMetrics {
+    registry: MetricRegistry,
+    events_shutdown: Counter,
+    events_command: Counter,
+    events_trading_state: Counter,
+    events_timer: Counter,
+    events_account: Counter,
+    events_market: Counter,
+    unrecoverable_errors: Counter,
+    kill_switch_trips: Counter,
+    trading_enabled: Gauge,
+    pnl: FnvHashMap<InstrumentNameInternal, (Gauge, Gauge)>,
+}
+
+impl EngineMetrics {
+    pub fn new(registry: &MetricRegistry) -> Self {
+        let events = |kind: &'static str| {
+            registry.counter(ENGINE_EVENTS_PROCESSED, [Tag::new("kind", kind)])
+        };
+
+        Self {
+            registry: registry.clone(),
+            events_shutdown: events("shutdown"),
+            events_command: events("command"),
+            events_trading_state: events("trading_state"),
+            events_timer: events("timer"),
+            events_account: events("account"),
+            events_market: events("market"),
+            unrecoverable_errors: registry.counter(ENGINE_UNRECOVERABLE_ERRORS, Vec::<Tag>::new()),
+            kill_switch_trips: registry.counter(ENGINE_KILL_SWITCH_TRIPS, Vec::<Tag>::new()),
+            trading_enabled: registry.gauge(ENGINE_TRADING_ENABLED, Vec::<Tag>::new()),
+            pnl: FnvHashMap::default(),
+        }
+    }
+
+    /// Record the event throughput, reconnections, errors & kill switch trips of an
+    /// [`EngineAudit`].
+    pub fn observe_audit<MarketKind, OnDisable, OnDisconnect>(
+        &self,
+        audit: &EngineAudit<EngineEvent<MarketKind>, EngineOutput<OnDisable, OnDisconnect>>,
+    ) {
+        let EngineAudit::Process(audit) = audit else {
+            return;
+        };
+
+        match &audit.event {
+            EngineEvent::Shutdown(_) | EngineEvent::GracefulShutdown(_) => {
+                self.events_shutdown.increment()
+            }
+            EngineEvent::Command(_) => self.events_command.increment(),
+            EngineEvent::TradingStateUpdate(_) => self.events_trading_state.increment(),
+            EngineEvent::Timer(_) => self.events_timer.increment(),
+            EngineEvent::Account(event) => {
+                self.events_account.increment();
+                if let reconnect::Event::Reconnecting(exchange) = event {
+                    self.record_reconnect("account", exchange);
+                }
+            }
+            EngineEvent::Market(event) => {
+                self.events_market.increment();
+                if let reconnect::Event::Reconnecting(exchange) = event {
+                    self.record_reconnect("market", exchange);
+                }
+            }
+        }
+
+        self.unrecoverable_errors.add(audit.errors.len() as u64);
+
+        let trips = audit
+            .outputs
+            .iter()
+            .filter(|output| matches!(output, EngineOutput::KillSwitch(_)))
+            .count();
+        self.kill_switch_trips.add(trips as u64);
+
+        for output in &audit.outputs {
+            match output {
+                EngineOutput::Latency(latency) => self.record_latency(latency),
+                EngineOutput::FeedStale(stale) => self.record_stale_feed(&stale.feed),
+                _ => {}
+            }
+        }
+    }
+
+    /// Record the tick-to-trade & tick-to-ack latency of an acknowledged order, or the
+    /// tick-to-fill latency of a filled order.
+    fn record_latency(&self, latency: &OrderLatency) {
+        let stages = if latency.trace.time_fill.is_some() {
+            &[LatencyStage::TickToFill][..]
+        } else {
+            &[LatencyStage::TickToTrade, LatencyStage::TickToAck][..]
+        };
+
+        for stage in stages {
+            let Some(delta) = latency.trace.latency(*stage) else {
+                continue;
+            };
+
+            self.registry
+                .histogram(
+                    ORDER_LATENCY_MS,
+                    [
+                        Tag::new("exchange", latency.exchange.clone()),
+                        Tag::new("stage", stage.as_str()),
+                    ],
+                    DEFAULT_LATENCY_BUCKETS_MS,
+                )
+                .observe(delta.num_microseconds().unwrap_or(i64::MAX) as f64 / 1000.0);
+        }
+    }
+
+    fn record_reconnect(&self, stream: &'static str, exchange: &ExchangeId) {
+        self.registry
+            .counter(
+                ENGINE_RECONNECTS,
+                [
+                    Tag::new("stream", stream),
+                    Tag::new("exchange", exchange.as_str()),
+                ],
+            )
+            .increment();
+    }
+
+    fn record_stale_feed(&self, feed: &Feed) {
+        let stream = if feed.is_market_data() {
+            "market"
+        } else {
+            "account"
+        };
+
+        self.registry
+            .counter(
+                ENGINE_STALE_FEEDS,
+                [
+                    Tag::new("stream", stream),
+                    Tag::new("exchange", feed.exchange().as_str()),
+                ],
+            )
+            .increment();
+    }
+
+    /// Record the [`TradingState`] and per-instrument realised & unrealised PnL of an
+    /// [`EngineState`].
+    pub fn observe_state<GlobalData, InstrumentData>(
+        &mut self,
+        state: &EngineState<GlobalData, InstrumentData>,
+    ) {
+        self.trading_enabled.set(match state.trading {
+            TradingState::Enabled => 1.0,
+            TradingState::Disabled => 0.0,
+        });
+
+        for (name, instrument) in &state.instruments.0 {
+            if !self.pnl.contains_key(name) {
+                let tags = [Tag::new("instrument", name.clone())];
+                let gauges = (
+                    self.registry.gauge(PNL_REALISED, tags.clone()),
+                    self.registry.gauge(PNL_UNREALISED, tags),
+                );
+                self.pnl.insert(name.clone(), gauges);
+            }
+            let (realised, unrealised) = &self.pnl[name];
+
+            let pnl_unrealised = instrument
+                .position
+                .current
+                .as_ref()
+                .map(|position| position.pnl_unrealised)
+                .unwrap_or_default();
+
+            realised.set(
+                instrument
+                    .tear_sheet
+                    .pnl_returns
+                    .pnl_raw
+                    .to_f64()
+                    .unwrap_or_default(),
+            );
+            unrealised.set(pnl_unrealised.to_f64().unwrap_or_default());
+        }
+    }
+}
+
+/// Record [`EngineMetrics`] from the AuditStream, maintaining an `EngineState` replica to derive
+/// state metrics from. Runs until the AuditStream ends.
+pub async fn run_audit_metrics<GlobalData, InstrumentData, OnDisable, OnDisconnect>(
+    registry: MetricRegistry,
+    audit: SnapUpdates<
+        AuditTick<EngineState<GlobalData, InstrumentData>>,
+        UnboundedRx<
+            AuditTick<
+                EngineAudit<
+                    EngineEvent<InstrumentData::MarketEventKind>,
+                    EngineOutput<OnDisable, OnDisconnect>,
+                >,
+            >,
+        >,
+    >,
+) where
+    GlobalData: for<'a> Processor<&'a AccountEvent>
+        + for<'a> Processor<&'a MarketEvent<InstrumentIndex, InstrumentData::MarketEventKind>>,
+    InstrumentData: InstrumentDataState,
+    OnDisable: Debug,
+    OnDisconnect: Debug,
+{
+    let SnapUpdates { snapshot, updates } = audit;
+    let mut metrics = EngineMetrics::new(&registry);
+    let mut replica = StateReplicaManager::new(snapshot, ());
+    let mut updates = metered_audit(&registry, updates);
+
+    metrics.observe_state(&replica.state_replica.event);
+
+    while let Some(tick) = StreamExt::next(&mut updates).await {
+        metrics.observe_audit(&tick.event);
+
+        if let EngineAudit::Process(audit) = tick.event {
+            if replica.state_replica.context.sequence >= tick.context.sequence {
+                continue;
+            }
+            replica.state_replica.context = tick.context;
+            replica.update_from_event(audit.event);
+            metrics.observe_state(&replica.state_replica.event);
+        }
+    }
+
+    info!("EngineMetrics AuditStream ended");
+}
+
+impl<Engine, Event> System<Engine, Event>
+where
+    Engine: Processor<Event> + Auditor<Engine::Audit, Context = EngineContext>,
+    Event: Debug + Clone + Send,
+{
+    /// Spawn a task recording [`EngineMetrics`] in the provided [`MetricRegistry`], taking
+    /// ownership of the `System` audit snapshot with updates.
+    ///
+    /// Returns `None` if the `System` was not built in
+    /// [`AuditMode::Enabled`](crate::system::builder::AuditMode), or the audit has already been
+    /// taken.
+    pub fn spawn_metrics<GlobalData, InstrumentData, OnDisable, OnDisconnect>(
+        &mut self,
+        registry: MetricRegistry,
+    ) -> Option<JoinHandle<()>>
+    where
+        Engine: Processor<
+                Event,
+                Audit = EngineAudit<
+                    EngineEvent<InstrumentData::MarketEventKind>,
+                    EngineOutput<OnDisable, OnDisconnect>,
+                >,
+            > + Auditor<Engine::Audit, Snapshot = EngineState<GlobalData, InstrumentData>>,
+        GlobalData: for<'a> Processor<&'a AccountEvent>
+            + for<'a> Processor<&'a MarketEvent<InstrumentIndex, InstrumentData::MarketEventKind>>
+            + Send
+            + 'static,
+        InstrumentData: InstrumentDataState + Send + 'static,
+        InstrumentData::MarketEventKind: Send,
+        OnDisable: Debug + Send + 'static,
+        OnDisconnect: Debug + Send + 'static,
+    {
+        let audit = self.take_audit()?;
+        Some(tokio::spawn(run_audit_metrics(registry, audit)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{engine::timer::TimerTick, shutdown::Shutdown};
+    use toucan_data::event::DataKind;
+
+    type Audit = EngineAudit<EngineEvent<DataKind>, EngineOutput<(), ()>>;
+
+    #[test]
+    fn test_engine_metrics_observe_audit() {
+        let registry = MetricRegistry::default();
+        let metrics = EngineMetrics::new(&registry);
+
+        let audits: Vec<Audit> = vec![
+            EngineAudit::process(EngineEvent::Timer(TimerTick)),
+            EngineAudit::process(EngineEvent::Timer(TimerTick)),
+            EngineAudit::process(EngineEvent::Market(reconnect::Event::Reconnecting(
+                ExchangeId::Mock,
+            ))),
+            EngineAudit::process(EngineEvent::Shutdown(Shutdown)),
+            EngineAudit::FeedEnded,
+        ];
+        audits.iter().for_each(|audit| metrics.observe_audit(audit));
+
+        let actual = registry
+            .metrics(0)
+            .into_iter()
+            .filter(|metric| {
+                metric.fields[0].value != 0u64.into() && metric.name != ENGINE_TRADING_ENABLED
+            })
+            .map(|metric| {
+                let tags = metric
+                    .tags
+                    .into_iter()
+                    .map(|tag| tag.value)
+                    .collect::<Vec<_>>();
+                (metric.name, tags, metric.fields[0].value.clone())
+            })
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            (
+                ENGINE_EVENTS_PROCESSED,
+                vec!["market".to_string()],
+                1u64.into(),
+            ),
+            (
+                ENGINE_EVENTS_PROCESSED,
+                vec!["shutdown".to_string()],
+                1u64.into(),
+            ),
+            (
+                ENGINE_EVENTS_PROCESSED,
+                vec!["timer".to_string()],
+                2u64.into(),
+            ),
+            (
+                ENGINE_RECONNECTS,
+                vec!["mock".to_string(), "market".to_string()],
+                1u64.into(),
+            ),
+        ];
+
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,501 @@
+use crate::engine::{
+    command::Command,
+    state::{
+        asset::AssetNameExchange,
+        connectivity::ConnectivityStates,
+        instrument::{data::InstrumentDataState, filter::InstrumentFilter},
+        position::Position,
+        trading::TradingState,
+    },
+};
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    http::{header::CONTENT_TYPE, StatusCode},
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Serialize;
+use std::{fmt::Debug, sync::Arc};
+use tokio::sync::broadcast::error::RecvError;
+use toucan_execution::{
+    balance::AssetBalance,
+    order::{
+        request::{OrderRequestCancel, OrderRequestOpen},
+        state::ActiveOrderState,
+        Order,
+    },
+    ExchangeIndex, InstrumentIndex, QuoteAsset,
+};
+use toucan_integration::{
+    channel::Tx,
+    collection::one_or_many::OneOrMany,
+    metric::{prometheus, registry::MetricRegistry},
+};
+use toucan_risk::margin::MarginUtilisation;
+use tracing::{error, warn};
+
+use super::{auth, ServerState};
+
+/// Construct the server [`Router`], with every `/api/v1` route behind bearer token
+/// authentication.
+pub fn router<Event, GlobalData, InstrumentData>(
+    state: Arc<ServerState<Event, GlobalData, InstrumentData>>,
+) -> Router
+where
+    Event: From<Command> + From<TradingState> + Debug + Clone + Send + Sync + 'static,
+    GlobalData: Serialize + Send + Sync + 'static,
+    InstrumentData: InstrumentDataState + Serialize + Send + Sync + 'static,
+{
+    let api = Router::new()
+        .route(
+            "/trading_state",
+            get(trading_state::<Event, GlobalData, InstrumentData>)
+                .post(update_trading_state::<Event, GlobalData, InstrumentData>),
+        )
+        .route(
+            "/open_requests",
+            post(send_open_requests::<Event, GlobalData, InstrumentData>),
+        )
+        .route(
+            "/cancel_requests",
+            post(send_cancel_requests::<Event, GlobalData, InstrumentData>),
+        )
+        .route(
+            "/close_positions",
+            post(close_positions::<Event, GlobalData, InstrumentData>),
+        )
+        .route(
+            "/cancel_orders",
+            post(cancel_orders::<Event, GlobalData, InstrumentData>),
+        )
+        .route(
+            "/positions",
+            get(positions::<Event, GlobalData, InstrumentData>),
+        )
+        .route("/orders", get(orders::<Event, GlobalData, InstrumentData>))
+        .route(
+            "/balances",
+            get(balances::<Event, GlobalData, InstrumentData>),
+        )
+        .route(
+            "/connectivity",
+            get(connectivity::<Event, GlobalData, InstrumentData>),
+        )
+        .route("/margin", get(margin::<Event, GlobalData, InstrumentData>))
+        .route("/stream", get(stream::<Event, GlobalData, InstrumentData>))
+        .route("/metrics", get(metrics))
+        .route_layer(middleware::from_fn_with_state(
+            Arc::clone(&state),
+            auth::require_token::<Event, GlobalData, InstrumentData>,
+        ));
+
+    Router::new()
+        .route("/health", get(|| async { StatusCode::OK }))
+        .nest("/api/v1", api)
+        .with_state(state)
+}
+
+/// Forward an `Event` to the `Engine`, returning `503 Service Unavailable` if the `Engine` has
+/// stopped.
+fn send<Event, GlobalData, InstrumentData, T>(
+    state: &ServerState<Event, GlobalData, InstrumentData>,
+    event: T,
+) -> StatusCode
+where
+    Event: Debug + Clone + Send,
+    T: Into<Event>,
+{
+    match state.feed_tx.send(event) {
+        Ok(()) => StatusCode::ACCEPTED,
+        Err(error) => {
+            error!(
+                ?error,
+                "Toucan server failed to send event to stopped Engine"
+            );
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+    }
+}
+
+async fn trading_state<Event, GlobalData, InstrumentData>(
+    State(state): State<Arc<ServerState<Event, GlobalData, InstrumentData>>>,
+) -> Json<TradingState> {
+    Json(state.replica.read().state_replica.event.trading)
+}
+
+async fn update_trading_state<Event, GlobalData, InstrumentData>(
+    State(state): State<Arc<ServerState<Event, GlobalData, InstrumentData>>>,
+    Json(trading_state): Json<TradingState>,
+) -> StatusCode
+where
+    Event: From<TradingState> + Debug + Clone + Send,
+{
+    send(&state, trading_state)
+}
+
+async fn send_open_requests<Event, GlobalData, InstrumentData>(
+    State(state): State<Arc<ServerState<Event, GlobalData, InstrumentData>>>,
+    Json(requests): Json<OneOrMany<OrderRequestOpen>>,
+) -> StatusCode
+where
+    Event: From<Command> + Debug + Clone + Send,
+{
+    send(&state, Command::SendOpenRequests(requests))
+}
+
+async fn send_cancel_requests<Event, GlobalData, InstrumentData>(
+    State(state): State<Arc<ServerState<Event, GlobalData, InstrumentData>>>,
+    Json(requests): Json<OneOrMany<OrderRequestCancel>>,
+) -> StatusCode
+where
+    Event: From<Command> + Debug + Clone + Send,
+{
+    send(&state, Command::SendCancelRequests(requests))
+}
+
+async fn close_positions<Event, GlobalData, InstrumentData>(
+    State(state): State<Arc<ServerState<Event, GlobalData, InstrumentData>>>,
+    Json(filter): Json<InstrumentFilter>,
+) -> StatusCode
+where
+    Event: From<Command> + Debug + Clone + Send,
+{
+    send(&state, Command::ClosePositions(filter))
+}
+
+async fn cancel_orders<Event, GlobalData, InstrumentData>(
+    State(state): State<Arc<ServerState<Event, GlobalData, InstrumentData>>>,
+    Json(filter): Json<InstrumentFilter>,
+) -> StatusCode
+where
+    Event: From<Command> + Debug + Clone + Send,
+{
+    send(&state, Command::CancelOrders(filter))
+}
+
+async fn positions<Event, GlobalData, InstrumentData>(
+    State(state): State<Arc<ServerState<Event, GlobalData, InstrumentData>>>,
+) -> Json<Vec<Position<QuoteAsset>>> {
+    let replica = state.replica.read();
+    let positions = replica
+        .state_replica
+        .event
+        .instruments
+        .0
+        .values()
+        .filter_map(|instrument| instrument.position.current.clone())
+        .collect();
+
+    Json(positions)
+}
+
+async fn orders<Event, GlobalData, InstrumentData>(
+    State(state): State<Arc<ServerState<Event, GlobalData, InstrumentData>>>,
+) -> Json<Vec<Order<ExchangeIndex, InstrumentIndex, ActiveOrderState>>> {
+    let replica = state.replica.read();
+    let orders = replica
+        .state_replica
+        .event
+        .instruments
+        .0
+        .values()
+        .flat_map(|instrument| instrument.orders.0.values().cloned())
+        .collect();
+
+    Json(orders)
+}
+
+async fn balances<Event, GlobalData, InstrumentData>(
+    State(state): State<Arc<ServerState<Event, GlobalData, InstrumentData>>>,
+) -> Json<Vec<AssetBalance<AssetNameExchange>>> {
+    let replica = state.replica.read();
+    let balances = replica
+        .state_replica
+        .event
+        .assets
+        .0
+        .values()
+        .filter(|asset| asset.balance.is_some())
+        .map(AssetBalance::from)
+        .collect();
+
+    Json(balances)
+}
+
+async fn connectivity<Event, GlobalData, InstrumentData>(
+    State(state): State<Arc<ServerState<Event, GlobalData, InstrumentData>>>,
+) -> Json<ConnectivityStates> {
+    Json(
+        state
+            .replica
+            .read()
+            .state_replica
+            .event
+            .connectivity
+            .clone(),
+    )
+}
+
+async fn margin<Event, GlobalData, InstrumentData>(
+    State(state): State<Arc<ServerState<Event, GlobalData, InstrumentData>>>,
+) -> Result<Json<MarginUtilisation>, StatusCode>
+where
+    InstrumentData: InstrumentDataState,
+{
+    let margin = state.margin.as_ref().ok_or(StatusCode::NOT_FOUND)?;
+    let replica = state.replica.read();
+
+    Ok(Json(margin.utilisation(&replica.state_replica.event)))
+}
+
+async fn metrics() -> impl IntoResponse {
+    (
+        [(CONTENT_TYPE, prometheus::CONTENT_TYPE)],
+        prometheus::encode(MetricRegistry::global()),
+    )
+}
+
+async fn stream<Event, GlobalData, InstrumentData>(
+    State(state): State<Arc<ServerState<Event, GlobalData, InstrumentData>>>,
+    upgrade: WebSocketUpgrade,
+) -> Response
+where
+    Event: Send + Sync + 'static,
+    GlobalData: Serialize + Send + Sync + 'static,
+    InstrumentData: Serialize + Send + Sync + 'static,
+{
+    upgrade.on_upgrade(move |socket| run_stream(state, socket))
+}
+
+/// Stream a [`ServerMessage::Snapshot`](super::ServerMessage::Snapshot) followed by every
+/// [`ServerMessage::Audit`](super::ServerMessage::Audit) to a WebSocket client.
+async fn run_stream<Event, GlobalData, InstrumentData>(
+    state: Arc<ServerState<Event, GlobalData, InstrumentData>>,
+    mut socket: WebSocket,
+) where
+    GlobalData: Serialize,
+    InstrumentData: Serialize,
+{
+    // Subscribe before snapshotting so no update is missed (AuditTick sequence identifies repeats)
+    let mut updates = state.updates.subscribe();
+
+    if send_snapshot(&state, &mut socket).await.is_err() {
+        return;
+    }
+
+    loop {
+        tokio::select! {
+            update = updates.recv() => {
+                let sent = match update {
+                    Ok(message) => socket.send(Message::Text(message.as_ref().into())).await,
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!(skipped, "Toucan server WebSocket client lagged, re-sending snapshot");
+                        send_snapshot(&state, &mut socket).await
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+
+                if sent.is_err() {
+                    break;
+                }
+            }
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            }
+        }
+    }
+}
+
+async fn send_snapshot<Event, GlobalData, InstrumentData>(
+    state: &ServerState<Event, GlobalData, InstrumentData>,
+    socket: &mut WebSocket,
+) -> Result<(), axum::Error>
+where
+    GlobalData: Serialize,
+    InstrumentData: Serialize,
+{
+    match state.snapshot_message() {
+        Ok(message) => socket.send(Message::Text(message.into())).await,
+        Err(error) => {
+            error!(
+                ?error,
+                "Toucan server failed to serialise EngineState snapshot"
+            );
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        engine::{
+            audit::{context::EngineContext, state_replica::StateReplicaManager, AuditTick},
+            state::{
+                global::DefaultGlobalData, instrument::data::DefaultInstrumentMarketData,
+                trading::TradingStateUpdateAudit, EngineState, IndexedInstruments,
+            },
+            EngineOutput,
+        },
+        EngineEvent, Sequence,
+    };
+    use chrono::{DateTime, Utc};
+    use parking_lot::RwLock;
+    use tokio::sync::broadcast;
+    use toucan_data::event::DataKind;
+    use toucan_instrument::{exchange::ExchangeId, ConcreteInstrument, Keyed};
+    use toucan_integration::channel::{mpsc_unbounded, UnboundedRx};
+
+    type TestServerState =
+        ServerState<EngineEvent<DataKind>, DefaultGlobalData, DefaultInstrumentMarketData>;
+
+    fn server_state() -> (Arc<TestServerState>, UnboundedRx<EngineEvent<DataKind>>) {
+        let instruments: IndexedInstruments = vec![Keyed::new(
+            "inst0".to_string(),
+            ConcreteInstrument {
+                symbol: "PETR4".into(),
+                market: "spot".into(),
+                exchange: ExchangeId::Mock,
+                underlying: None,
+                name_exchange: "PETR4".into(),
+            },
+        )];
+
+        let engine_state = EngineState::builder(&instruments, DefaultGlobalData, |_| {
+            DefaultInstrumentMarketData::default()
+        })
+        .trading_state(TradingState::Enabled)
+        .build();
+
+        let snapshot = AuditTick {
+            event: engine_state,
+            context: EngineContext {
+                sequence: Sequence(0),
+                time: DateTime::<Utc>::MIN_UTC,
+            },
+        };
+
+        let (feed_tx, feed_rx) = mpsc_unbounded();
+        let (updates, _) = broadcast::channel(1);
+
+        let state = Arc::new(ServerState {
+            api_token: "secret".to_string(),
+            feed_tx,
+            replica: RwLock::new(StateReplicaManager::new(snapshot, ())),
+            updates,
+            margin: None,
+        });
+
+        (state, feed_rx)
+    }
+
+    #[tokio::test]
+    async fn test_trading_state() {
+        struct TestCase {
+            output: Option<EngineOutput<(), ()>>,
+            expected: TradingState,
+        }
+
+        let (state, _feed_rx) = server_state();
+
+        let cases = vec![
+            // TC0: TradingState of the seed EngineState snapshot
+            TestCase {
+                output: None,
+                expected: TradingState::Enabled,
+            },
+            // TC1: unrelated EngineOutput does not change the TradingState
+            TestCase {
+                output: Some(EngineOutput::OnTradingDisabled(())),
+                expected: TradingState::Enabled,
+            },
+            // TC2: Engine initiated disable (eg/ session gate, tripped KillSwitch)
+            TestCase {
+                output: Some(EngineOutput::TradingState(TradingStateUpdateAudit {
+                    prev: TradingState::Enabled,
+                    current: TradingState::Disabled,
+                })),
+                expected: TradingState::Disabled,
+            },
+            // TC3: Engine initiated re-enable (eg/ session open)
+            TestCase {
+                output: Some(EngineOutput::TradingState(TradingStateUpdateAudit {
+                    prev: TradingState::Disabled,
+                    current: TradingState::Enabled,
+                })),
+                expected: TradingState::Enabled,
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            if let Some(output) = &test.output {
+                state.replica.write().update_from_output(output);
+            }
+
+            let Json(actual) = trading_state(State(Arc::clone(&state))).await;
+            assert_eq!(actual, test.expected, "TC{index} failed");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_commands() {
+        let (state, mut feed_rx) = server_state();
+
+        let actual =
+            update_trading_state(State(Arc::clone(&state)), Json(TradingState::Disabled)).await;
+        assert_eq!(actual, StatusCode::ACCEPTED);
+        assert_eq!(
+            feed_rx.rx.try_recv().ok(),
+            Some(EngineEvent::TradingStateUpdate(TradingState::Disabled))
+        );
+
+        let actual = close_positions(State(Arc::clone(&state)), Json(InstrumentFilter::None)).await;
+        assert_eq!(actual, StatusCode::ACCEPTED);
+        assert_eq!(
+            feed_rx.rx.try_recv().ok(),
+            Some(EngineEvent::Command(Command::ClosePositions(
+                InstrumentFilter::None
+            )))
+        );
+
+        let actual = cancel_orders(State(Arc::clone(&state)), Json(InstrumentFilter::None)).await;
+        assert_eq!(actual, StatusCode::ACCEPTED);
+        assert_eq!(
+            feed_rx.rx.try_recv().ok(),
+            Some(EngineEvent::Command(Command::CancelOrders(
+                InstrumentFilter::None
+            )))
+        );
+
+        // Engine has stopped
+        drop(feed_rx);
+        let actual =
+            update_trading_state(State(Arc::clone(&state)), Json(TradingState::Enabled)).await;
+        assert_eq!(actual, StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn test_replica_views() {
+        let (state, _feed_rx) = server_state();
+
+        let Json(actual) = positions(State(Arc::clone(&state))).await;
+        assert!(actual.is_empty());
+
+        let Json(actual) = orders(State(Arc::clone(&state))).await;
+        assert!(actual.is_empty());
+
+        let Json(actual) = balances(State(Arc::clone(&state))).await;
+        assert!(actual.is_empty());
+
+        // MarginCheck is not configured
+        let actual = margin(State(Arc::clone(&state))).await;
+        assert_eq!(actual.err(), Some(StatusCode::NOT_FOUND));
+    }
+}
@@ -0,0 +1,160 @@
+use axum::{
+    extract::{Request, State},
+    http::{header::AUTHORIZATION, HeaderMap, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+
+use super::ServerState;
+
+/// Query parameter used by WebSocket clients that cannot set an `Authorization` header.
+pub const TOKEN_QUERY_PARAM: &str = "token";
+
+/// Middleware rejecting requests without a valid bearer token with `401 Unauthorized`.
+pub async fn require_token<Event, GlobalData, InstrumentData>(
+    State(state): State<Arc<ServerState<Event, GlobalData, InstrumentData>>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    if is_authorised(request.headers(), request.uri().query(), &state.api_token) {
+        Ok(next.run(request).await)
+    } else {
+        Err(StatusCode::UNAUTHORIZED)
+    }
+}
+
+/// Returns `true` if the `Authorization: Bearer` header, or the URL-decoded `token` query
+/// parameter, matches the expected api token.
+pub fn is_authorised(headers: &HeaderMap, query: Option<&str>, api_token: &str) -> bool {
+    let header_token = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::to_owned);
+
+    header_token
+        .or_else(|| query.and_then(query_token))
+        .is_some_and(|token| constant_time_eq(token.as_bytes(), api_token.as_bytes()))
+}
+
+/// URL-decoded value of the `token` query parameter (eg/ "%2B" decodes to "+"), if any.
+fn query_token(query: &str) -> Option<String> {
+    serde_urlencoded::from_str::<Vec<(String, String)>>(query)
+        .ok()?
+        .into_iter()
+        .find_map(|(key, value)| (key == TOKEN_QUERY_PARAM).then_some(value))
+}
+
+/// Compare two byte slices in time independent of the position of the first difference.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_is_authorised() {
+        struct TestCase {
+            header: Option<&'static str>,
+            query: Option<&'static str>,
+            expected: bool,
+        }
+
+        let cases = vec![
+            // TC0: no credentials
+            TestCase {
+                header: None,
+                query: None,
+                expected: false,
+            },
+            // TC1: valid bearer token
+            TestCase {
+                header: Some("Bearer secret"),
+                query: None,
+                expected: true,
+            },
+            // TC2: invalid bearer token
+            TestCase {
+                header: Some("Bearer secreT"),
+                query: None,
+                expected: false,
+            },
+            // TC3: token without Bearer scheme
+            TestCase {
+                header: Some("secret"),
+                query: None,
+                expected: false,
+            },
+            // TC4: valid query token alongside other parameters
+            TestCase {
+                header: None,
+                query: Some("format=json&token=secret"),
+                expected: true,
+            },
+            // TC5: token prefix of a different query parameter
+            TestCase {
+                header: None,
+                query: Some("tokens=secret"),
+                expected: false,
+            },
+            // TC6: empty token
+            TestCase {
+                header: Some("Bearer "),
+                query: None,
+                expected: false,
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let mut headers = HeaderMap::new();
+            if let Some(header) = test.header {
+                headers.insert(AUTHORIZATION, HeaderValue::from_static(header));
+            }
+
+            let actual = is_authorised(&headers, test.query, "secret");
+            assert_eq!(actual, test.expected, "TC{index} failed");
+        }
+    }
+
+    #[test]
+    fn test_is_authorised_url_decodes_query_token() {
+        struct TestCase {
+            query: &'static str,
+            expected: bool,
+        }
+
+        let api_token = "a+b/c==";
+
+        let cases = vec![
+            // TC0: percent-encoded token
+            TestCase {
+                query: "token=a%2Bb%2Fc%3D%3D",
+                expected: true,
+            },
+            // TC1: unencoded '/' & '=' are accepted as is
+            TestCase {
+                query: "format=json&token=a%2Bb/c==",
+                expected: true,
+            },
+            // TC2: unencoded '+' decodes to a space
+            TestCase {
+                query: "token=a+b/c==",
+                expected: false,
+            },
+            // TC3: percent-encoded parameter name
+            TestCase {
+                query: "%74oken=a%2Bb%2Fc%3D%3D",
+                expected: true,
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let actual = is_authorised(&HeaderMap::new(), Some(test.query), api_token);
+            assert_eq!(actual, test.expected, "TC{index} failed");
+        }
+    }
+}
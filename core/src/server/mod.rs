@@ -0,0 +1,360 @@
+//! Embedded HTTP & WebSocket server for controlling and monitoring a running
+//! [`System`](crate::system::System).
+//!
+//! Requires the `server` feature.
+//!
+//! # Endpoints
+//! All endpoints except `/health` require an `Authorization: Bearer <api_token>` header (or a
+//! `?token=<api_token>` query parameter for WebSocket clients that cannot set headers).
+//!
+//! | Method | Path                        | Description                                        |
+//! |--------|-----------------------------|----------------------------------------------------|
+//! | GET    | `/health`                   | Liveness probe                                     |
+//! | GET    | `/api/v1/trading_state`     | Current `TradingState`                             |
+//! | POST   | `/api/v1/trading_state`     | Update the `TradingState` (eg/ `"Disabled"`)       |
+//! | POST   | `/api/v1/open_requests`     | Send `OrderRequestOpen`s                           |
+//! | POST   | `/api/v1/cancel_requests`   | Send `OrderRequestCancel`s                         |
+//! | POST   | `/api/v1/close_positions`   | Close positions matching an `InstrumentFilter`     |
+//! | POST   | `/api/v1/cancel_orders`     | Cancel orders matching an `InstrumentFilter`       |
+//! | GET    | `/api/v1/positions`         | Open positions                                     |
+//! | GET    | `/api/v1/orders`            | Active orders                                      |
+//! | GET    | `/api/v1/balances`          | Asset balances                                     |
+//! | GET    | `/api/v1/connectivity`      | Market data & account connectivity health          |
+//! | GET    | `/api/v1/margin`            | Margin utilisation (`404` if not configured)       |
+//! | GET    | `/api/v1/stream`            | WebSocket stream of [`ServerMessage`]s             |
+//! | GET    | `/api/v1/metrics`           | Prometheus metrics (see [`crate::metric`])         |
+//!
+//! Commands are forwarded to the `Engine` and answered with `202 Accepted`; their outcome is
+//! observed via the audit stream.
+//!
+//! Read-only views are served from an `EngineState` replica maintained from the `Engine`
+//! AuditStream, so the server never touches the `Engine` hot path.
+
+use crate::{
+    engine::{
+        audit::{
+            context::EngineContext, state_replica::StateReplicaManager, AuditTick, Auditor,
+            EngineAudit,
+        },
+        command::Command,
+        risk::MarginCheck,
+        state::{instrument::data::InstrumentDataState, trading::TradingState, EngineState},
+        EngineOutput, Processor,
+    },
+    metric::{metered_audit, EngineMetrics},
+    system::System,
+    EngineEvent,
+};
+use futures::StreamExt;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Debug, net::SocketAddr, sync::Arc};
+use tokio::{net::TcpListener, sync::broadcast, task::JoinHandle};
+use toucan_data::event::MarketEvent;
+use toucan_execution::{AccountEvent, InstrumentIndex};
+use toucan_integration::{
+    channel::{UnboundedRx, UnboundedTx},
+    metric::registry::MetricRegistry,
+    snapshot::SnapUpdates,
+};
+use tracing::{error, info};
+
+/// HTTP endpoint handlers and [`Router`](axum::Router) construction.
+pub mod api;
+
+/// Bearer token authentication.
+pub mod auth;
+
+/// Default capacity of the broadcast channel used to stream updates to WebSocket clients.
+pub const DEFAULT_STREAM_CAPACITY: usize = 4096;
+
+/// Embedded server configuration.
+///
+/// The `Debug` output redacts the `api_token`, so the configuration can be logged safely.
+#[derive(Clone, Eq, PartialEq, Deserialize, Serialize)]
+pub struct ServerConfig {
+    /// Socket address the server binds to (eg/ "127.0.0.1:8080").
+    pub bind: SocketAddr,
+
+    /// Bearer token required to access authenticated endpoints.
+    pub api_token: String,
+
+    /// Capacity of the broadcast channel used to stream updates to WebSocket clients. Slow
+    /// clients that lag behind are re-sent a full snapshot.
+    pub stream_capacity: usize,
+
+    /// Optional [`MarginCheck`] used to estimate the current margin utilisation served by the
+    /// `/margin` endpoint.
+    #[serde(default)]
+    pub margin: Option<MarginCheck>,
+}
+
+impl ServerConfig {
+    /// Construct a new [`ServerConfig`] using the [`DEFAULT_STREAM_CAPACITY`].
+    pub fn new(bind: SocketAddr, api_token: impl Into<String>) -> Self {
+        Self {
+            bind,
+            api_token: api_token.into(),
+            stream_capacity: DEFAULT_STREAM_CAPACITY,
+            margin: None,
+        }
+    }
+
+    /// Serve the current margin utilisation estimated by the provided [`MarginCheck`].
+    pub fn with_margin(self, margin: MarginCheck) -> Self {
+        Self {
+            margin: Some(margin),
+            ..self
+        }
+    }
+}
+
+impl Debug for ServerConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServerConfig")
+            .field("bind", &self.bind)
+            .field("api_token", &"<redacted>")
+            .field("stream_capacity", &self.stream_capacity)
+            .field("margin", &self.margin)
+            .finish()
+    }
+}
+
+/// All errors generated by the embedded server.
+#[derive(Debug, thiserror::Error)]
+pub enum ServerError {
+    #[error("System audit stream is not available - build the System with AuditMode::Enabled")]
+    AuditDisabled,
+
+    #[error("server api_token must not be empty")]
+    EmptyApiToken,
+
+    #[error("server IO: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Message streamed to WebSocket clients.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum ServerMessage<Snapshot, Audit> {
+    /// Full `EngineState` snapshot, sent on connection and after a client lags behind.
+    Snapshot(Snapshot),
+
+    /// `Engine` audit update.
+    Audit(Audit),
+}
+
+/// Handles for a running embedded server.
+#[derive(Debug)]
+pub struct ServerHandle {
+    /// Address the server is listening on.
+    pub addr: SocketAddr,
+
+    /// Task serving HTTP & WebSocket connections.
+    pub server: JoinHandle<Result<(), ServerError>>,
+
+    /// Task updating the `EngineState` replica and broadcasting updates from the AuditStream.
+    pub audit: JoinHandle<()>,
+}
+
+impl ServerHandle {
+    /// Stop the server tasks.
+    pub fn abort(&self) {
+        self.server.abort();
+        self.audit.abort();
+    }
+}
+
+/// State shared between the server endpoint handlers.
+#[derive(Debug)]
+pub struct ServerState<Event, GlobalData, InstrumentData> {
+    pub api_token: String,
+    pub feed_tx: UnboundedTx<Event>,
+    pub replica: RwLock<StateReplicaManager<EngineState<GlobalData, InstrumentData>, ()>>,
+    pub updates: broadcast::Sender<Arc<str>>,
+    pub margin: Option<MarginCheck>,
+}
+
+impl<Event, GlobalData, InstrumentData> ServerState<Event, GlobalData, InstrumentData> {
+    /// Serialise the `EngineState` replica as a [`ServerMessage::Snapshot`].
+    pub fn snapshot_message(&self) -> Result<String, serde_json::Error>
+    where
+        GlobalData: Serialize,
+        InstrumentData: Serialize,
+    {
+        let replica = self.replica.read();
+        serde_json::to_string(&ServerMessage::<_, ()>::Snapshot(&replica.state_replica))
+    }
+}
+
+/// Spawn the embedded server, consuming the `System` AuditStream to maintain an `EngineState`
+/// replica and stream updates to WebSocket clients.
+pub async fn spawn<Event, GlobalData, InstrumentData, OnDisable, OnDisconnect>(
+    config: ServerConfig,
+    feed_tx: UnboundedTx<Event>,
+    audit: SnapUpdates<
+        AuditTick<EngineState<GlobalData, InstrumentData>>,
+        UnboundedRx<
+            AuditTick<
+                EngineAudit<
+                    EngineEvent<InstrumentData::MarketEventKind>,
+                    EngineOutput<OnDisable, OnDisconnect>,
+                >,
+            >,
+        >,
+    >,
+) -> Result<ServerHandle, ServerError>
+where
+    Event: From<Command> + From<TradingState> + Debug + Clone + Send + Sync + 'static,
+    GlobalData: for<'a> Processor<&'a AccountEvent>
+        + for<'a> Processor<&'a MarketEvent<InstrumentIndex, InstrumentData::MarketEventKind>>
+        + Serialize
+        + Send
+        + Sync
+        + 'static,
+    InstrumentData: InstrumentDataState + Serialize + Send + Sync + 'static,
+    InstrumentData::MarketEventKind: Serialize + Send,
+    OnDisable: Debug + Serialize + Send + 'static,
+    OnDisconnect: Debug + Serialize + Send + 'static,
+{
+    if config.api_token.is_empty() {
+        return Err(ServerError::EmptyApiToken);
+    }
+
+    let SnapUpdates { snapshot, updates } = audit;
+    let (updates_tx, _) = broadcast::channel(config.stream_capacity.max(1));
+
+    let state = Arc::new(ServerState {
+        api_token: config.api_token,
+        feed_tx,
+        replica: RwLock::new(StateReplicaManager::new(snapshot, ())),
+        updates: updates_tx,
+        margin: config.margin,
+    });
+
+    let listener = TcpListener::bind(config.bind).await?;
+    let addr = listener.local_addr()?;
+    info!(%addr, "Toucan server listening");
+
+    let audit = tokio::spawn(run_audit_updates(Arc::clone(&state), updates));
+
+    let router = api::router(state);
+    let server = tokio::spawn(async move {
+        axum::serve(listener, router)
+            .await
+            .map_err(ServerError::from)
+    });
+
+    Ok(ServerHandle {
+        addr,
+        server,
+        audit,
+    })
+}
+
+impl<Engine, Event> System<Engine, Event>
+where
+    Engine: Processor<Event> + Auditor<Engine::Audit, Context = EngineContext>,
+    Event: Debug + Clone + Send,
+{
+    /// Spawn the embedded HTTP & WebSocket server, taking ownership of the `System` audit
+    /// snapshot with updates.
+    ///
+    /// Requires the `System` to be built in [`AuditMode::Enabled`](crate::system::builder::AuditMode).
+    pub async fn serve<GlobalData, InstrumentData, OnDisable, OnDisconnect>(
+        &mut self,
+        config: ServerConfig,
+    ) -> Result<ServerHandle, ServerError>
+    where
+        Engine: Processor<
+                Event,
+                Audit = EngineAudit<
+                    EngineEvent<InstrumentData::MarketEventKind>,
+                    EngineOutput<OnDisable, OnDisconnect>,
+                >,
+            > + Auditor<Engine::Audit, Snapshot = EngineState<GlobalData, InstrumentData>>,
+        Event: From<Command> + From<TradingState> + Sync + 'static,
+        GlobalData: for<'a> Processor<&'a AccountEvent>
+            + for<'a> Processor<&'a MarketEvent<InstrumentIndex, InstrumentData::MarketEventKind>>
+            + Serialize
+            + Send
+            + Sync
+            + 'static,
+        InstrumentData: InstrumentDataState + Serialize + Send + Sync + 'static,
+        InstrumentData::MarketEventKind: Serialize + Send,
+        OnDisable: Debug + Serialize + Send + 'static,
+        OnDisconnect: Debug + Serialize + Send + 'static,
+    {
+        let audit = self.take_audit().ok_or(ServerError::AuditDisabled)?;
+        spawn(config, self.feed_tx.clone(), audit).await
+    }
+}
+
+/// Update the `EngineState` replica from the AuditStream, broadcasting every
+/// [`ServerMessage::Audit`] to WebSocket clients and recording [`EngineMetrics`] in the global
+/// [`MetricRegistry`].
+async fn run_audit_updates<Event, GlobalData, InstrumentData, OnDisable, OnDisconnect>(
+    state: Arc<ServerState<Event, GlobalData, InstrumentData>>,
+    updates: UnboundedRx<
+        AuditTick<
+            EngineAudit<
+                EngineEvent<InstrumentData::MarketEventKind>,
+                EngineOutput<OnDisable, OnDisconnect>,
+            >,
+        >,
+    >,
+) where
+    GlobalData: for<'a> Processor<&'a AccountEvent>
+        + for<'a> Processor<&'a MarketEvent<InstrumentIndex, InstrumentData::MarketEventKind>>,
+    InstrumentData: InstrumentDataState,
+    InstrumentData::MarketEventKind: Serialize,
+    OnDisable: Debug + Serialize,
+    OnDisconnect: Debug + Serialize,
+{
+    let registry = MetricRegistry::global();
+    let mut metrics = EngineMetrics::new(registry);
+    let mut updates = metered_audit(registry, updates);
+
+    metrics.observe_state(&state.replica.read().state_replica.event);
+
+    while let Some(tick) = StreamExt::next(&mut updates).await {
+        metrics.observe_audit(&tick.event);
+
+        if let EngineAudit::Process(audit) = &tick.event {
+            let mut replica = state.replica.write();
+            if replica.state_replica.context.sequence >= tick.context.sequence {
+                continue;
+            }
+            replica.state_replica.context = tick.context;
+            replica.update_from_event(audit.event.clone());
+            for output in &audit.outputs {
+                replica.update_from_output(output);
+            }
+            metrics.observe_state(&replica.state_replica.event);
+        }
+
+        match serde_json::to_string(&ServerMessage::<(), _>::Audit(&tick)) {
+            Ok(message) => {
+                // Error only indicates there are no connected WebSocket clients
+                let _ = state.updates.send(Arc::from(message));
+            }
+            Err(error) => error!(?error, "Toucan server failed to serialise AuditTick"),
+        }
+    }
+
+    info!("Toucan server AuditStream ended");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_server_config_debug_redacts_api_token() {
+        let config = ServerConfig::new(SocketAddr::from(([127, 0, 0, 1], 8080)), "secret");
+
+        let debug = format!("{config:?}");
+        assert!(!debug.contains("secret"), "{debug}");
+        assert!(debug.contains(r#"api_token: "<redacted>""#), "{debug}");
+    }
+}
@@ -47,9 +47,27 @@
 //!
 //! The `Shutdown` type serves as a signal that can be sent through the event system
 //! to trigger graceful shutdown of the entire trading system.
+//!
+//! ## Graceful Shutdown Sequence
+//!
+//! A [`GracefulShutdown`] sequence flattens the `Engine` before it stops: trading is disabled,
+//! working orders are cancelled, positions are optionally closed, and the `Engine` waits for the
+//! acknowledgements or fills up to a deadline. Use [`shutdown_signal`] to trigger it on
+//! SIGINT/SIGTERM:
+//! ```rust,ignore
+//! use toucan_core::shutdown::GracefulShutdown;
+//!
+//! let (engine, report) = system
+//!     .shutdown_on_signal(GracefulShutdown::default(), risk_free_return, Daily)
+//!     .await?;
+//!
+//! report.summary.print_summary();
+//! ```
 
+use chrono::{DateTime, TimeDelta, Utc};
 use serde::{Deserialize, Serialize};
-use std::future::Future;
+use std::{future::Future, time::Duration};
+use tracing::{info, warn};
 
 /// Trait for components that can be shut down synchronously.
 ///
@@ -94,3 +112,248 @@ pub trait AsyncShutdown {
     Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Deserialize, Serialize,
 )]
 pub struct Shutdown;
+
+/// Default time the `Engine` waits for working orders to be cancelled (and positions closed)
+/// during a [`GracefulShutdown`].
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Configurable graceful shutdown sequence actioned by the `Engine` on an
+/// [`EngineEvent::GracefulShutdown`](crate::EngineEvent::GracefulShutdown):
+/// 1. Disable algorithmic trading.
+/// 2. Cancel all working orders.
+/// 3. Optionally close all open positions.
+/// 4. Wait for the acknowledgements or fills, up to the `timeout` deadline.
+///
+/// Once drained (or timed out), the `Engine` stops and the
+/// [`System`](crate::system::System) takes a final checkpoint & `TradingSummary` before stopping
+/// the execution managers and streams.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub struct GracefulShutdown {
+    /// Close all open positions (using the configured `ClosePositionsStrategy`) after cancelling
+    /// working orders.
+    pub close_positions: bool,
+
+    /// Maximum time to wait for the cancel & close acknowledgements or fills.
+    pub timeout: Duration,
+}
+
+impl Default for GracefulShutdown {
+    fn default() -> Self {
+        Self {
+            close_positions: false,
+            timeout: DEFAULT_SHUTDOWN_TIMEOUT,
+        }
+    }
+}
+
+impl GracefulShutdown {
+    /// Construct a new `GracefulShutdown` sequence.
+    pub fn new(close_positions: bool, timeout: Duration) -> Self {
+        Self {
+            close_positions,
+            timeout,
+        }
+    }
+}
+
+/// In progress [`GracefulShutdown`] sequence tracked by the `EngineState`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub struct ShutdownSequence {
+    pub config: GracefulShutdown,
+    pub time_start: DateTime<Utc>,
+    pub deadline: DateTime<Utc>,
+
+    /// `true` once the sequence has drained or timed out.
+    pub complete: bool,
+}
+
+impl ShutdownSequence {
+    /// Start a new `ShutdownSequence` at the provided `Engine` time.
+    pub fn start(config: GracefulShutdown, time_start: DateTime<Utc>) -> Self {
+        let timeout = TimeDelta::from_std(config.timeout).unwrap_or(TimeDelta::MAX);
+
+        Self {
+            config,
+            time_start,
+            deadline: time_start
+                .checked_add_signed(timeout)
+                .unwrap_or(DateTime::<Utc>::MAX_UTC),
+            complete: false,
+        }
+    }
+
+    /// Evaluate the sequence against the number of working orders & open positions remaining.
+    ///
+    /// Open positions are only waited on if the sequence closes positions.
+    ///
+    /// Returns `None` if the sequence is already complete, or is still waiting.
+    pub fn update(
+        &mut self,
+        now: DateTime<Utc>,
+        orders: usize,
+        positions: usize,
+    ) -> Option<ShutdownProgress> {
+        if self.complete {
+            return None;
+        }
+
+        let positions = if self.config.close_positions {
+            positions
+        } else {
+            0
+        };
+
+        let progress = if orders == 0 && positions == 0 {
+            info!(%now, "ShutdownSequence drained working orders & positions");
+            ShutdownProgress::Drained { time: now }
+        } else if now >= self.deadline {
+            warn!(
+                %now,
+                orders,
+                positions,
+                "ShutdownSequence timed out waiting for working orders & positions"
+            );
+            ShutdownProgress::TimedOut {
+                time: now,
+                orders,
+                positions,
+            }
+        } else {
+            return None;
+        };
+
+        self.complete = true;
+        Some(progress)
+    }
+}
+
+/// Progress of a [`GracefulShutdown`] sequence, used to construct an `Engine` audit.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub enum ShutdownProgress {
+    /// Trading disabled and cancel (and close) requests sent.
+    Started { deadline: DateTime<Utc> },
+
+    /// All working orders (and positions) acknowledged or filled before the deadline.
+    Drained { time: DateTime<Utc> },
+
+    /// Deadline reached with working orders (or positions) remaining.
+    TimedOut {
+        time: DateTime<Utc>,
+        orders: usize,
+        positions: usize,
+    },
+}
+
+impl ShutdownProgress {
+    /// Returns `true` if the sequence has finished and the `Engine` should stop.
+    pub fn is_complete(&self) -> bool {
+        !matches!(self, Self::Started { .. })
+    }
+}
+
+/// OS signal that triggered a shutdown.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub enum ShutdownSignal {
+    /// SIGINT (eg/ Ctrl-C).
+    Interrupt,
+    /// SIGTERM (eg/ `docker stop`, `systemctl stop`).
+    Terminate,
+}
+
+/// Wait for a SIGINT or SIGTERM (only SIGINT on non-unix platforms).
+pub async fn shutdown_signal() -> std::io::Result<ShutdownSignal> {
+    #[cfg(unix)]
+    {
+        let mut terminate =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())?;
+
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result.map(|_| ShutdownSignal::Interrupt),
+            _ = terminate.recv() => Ok(ShutdownSignal::Terminate),
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        tokio::signal::ctrl_c()
+            .await
+            .map(|_| ShutdownSignal::Interrupt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_shutdown_sequence_update() {
+        struct TestCase {
+            close_positions: bool,
+            elapsed_secs: i64,
+            orders: usize,
+            positions: usize,
+            expected: Option<ShutdownProgress>,
+        }
+
+        let time_start = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        let time = |secs| time_start + TimeDelta::seconds(secs);
+
+        let cases = vec![
+            // TC0: working orders remaining before the deadline
+            TestCase {
+                close_positions: false,
+                elapsed_secs: 5,
+                orders: 2,
+                positions: 1,
+                expected: None,
+            },
+            // TC1: orders drained, positions ignored when not closing positions
+            TestCase {
+                close_positions: false,
+                elapsed_secs: 5,
+                orders: 0,
+                positions: 1,
+                expected: Some(ShutdownProgress::Drained { time: time(5) }),
+            },
+            // TC2: orders drained, waiting on open positions when closing positions
+            TestCase {
+                close_positions: true,
+                elapsed_secs: 5,
+                orders: 0,
+                positions: 1,
+                expected: None,
+            },
+            // TC3: deadline reached with positions remaining
+            TestCase {
+                close_positions: true,
+                elapsed_secs: 10,
+                orders: 0,
+                positions: 1,
+                expected: Some(ShutdownProgress::TimedOut {
+                    time: time(10),
+                    orders: 0,
+                    positions: 1,
+                }),
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let config = GracefulShutdown::new(test.close_positions, Duration::from_secs(10));
+            let mut sequence = ShutdownSequence::start(config, time_start);
+
+            let actual = sequence.update(time(test.elapsed_secs), test.orders, test.positions);
+            assert_eq!(actual, test.expected, "TC{index} failed");
+            assert_eq!(
+                sequence.complete,
+                test.expected.is_some(),
+                "TC{index} failed"
+            );
+
+            if test.expected.is_some() {
+                let again = sequence.update(time(test.elapsed_secs), 0, 0);
+                assert_eq!(again, None, "TC{index} failed");
+            }
+        }
+    }
+}
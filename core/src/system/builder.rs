@@ -5,8 +5,15 @@ use crate::{
         audit::{context::EngineContext, Auditor},
         clock::EngineClock,
         execution_tx::MultiExchangeTxMap,
+        kill_switch::KillSwitch,
+        reaction::Reactions,
         run::{async_run, async_run_with_audit, sync_run, sync_run_with_audit},
-        state::{builder::EngineStateBuilder, trading::TradingState, EngineState},
+        session::SessionControl,
+        state::{
+            builder::EngineStateBuilder, connectivity::stale::StaleFeedMonitor,
+            trading::TradingState, EngineState,
+        },
+        timer::{Timer, TimerTick},
         Engine, Processor,
     },
     error::ToucanError,
@@ -14,13 +21,19 @@ use crate::{
         builder::{ExecutionBuildFutures, ExecutionBuilder},
         AccountStreamEvent,
     },
+    metric::metered_feed,
     shutdown::SyncShutdown,
     system::{config::ExecutionConfig, System, SystemAuxillaryHandles},
 };
 use toucan_data::streams::reconnect::stream::ReconnectingStream;
-use toucan_execution::{balance::Balance, InstrumentIndex};
+use toucan_execution::{
+    balance::Balance,
+    client::b3::{B3Config, B3ExecutionClient},
+    InstrumentIndex,
+};
 use toucan_integration::{
     channel::{mpsc_unbounded, Channel, ChannelTxDroppable},
+    metric::registry::MetricRegistry,
     snapshot::SnapUpdates,
     FeedEnded, Terminal,
 };
@@ -32,7 +45,7 @@ use derive_more::Constructor;
 use fnv::FnvHashMap;
 use futures::Stream;
 use serde::{Deserialize, Serialize};
-use std::{fmt::Debug, marker::PhantomData};
+use std::{fmt::Debug, marker::PhantomData, time::Duration};
 
 /// Defines how the `Engine` processes input events.
 ///
@@ -101,6 +114,12 @@ pub struct SystemBuilder<'a, Clock, Strategy, Risk, MarketStream, GlobalData, Fn
     audit_mode: Option<AuditMode>,
     trading_state: Option<TradingState>,
     balances: FnvHashMap<AssetNameInternal, Balance>,
+    timer_interval: Option<Duration>,
+    timers: Vec<Timer>,
+    session: Option<SessionControl>,
+    kill_switch: Option<KillSwitch>,
+    stale_feeds: Option<StaleFeedMonitor>,
+    reactions: Option<Reactions>,
 }
 
 impl<'a, Clock, Strategy, Risk, MarketStream, GlobalData, FnInstrumentData>
@@ -118,6 +137,12 @@ impl<'a, Clock, Strategy, Risk, MarketStream, GlobalData, FnInstrumentData>
             audit_mode: None,
             trading_state: None,
             balances: FnvHashMap::default(),
+            timer_interval: None,
+            timers: Vec::new(),
+            session: None,
+            kill_switch: None,
+            stale_feeds: None,
+            reactions: None,
         }
     }
 
@@ -165,6 +190,68 @@ impl<'a, Clock, Strategy, Risk, MarketStream, GlobalData, FnInstrumentData>
         self
     }
 
+    /// Configura opcionalmente o intervalo de envio de [`TimerTick`]s ao engine.
+    ///
+    /// Garante que os `Timer`s do engine disparem mesmo sem eventos de mercado ou de conta
+    /// (ex: mercado parado). Útil em live/paper trading; em backtests os timers já disparam
+    /// deterministicamente a partir dos timestamps históricos, então o padrão é desabilitado.
+    pub fn timer_interval(self, value: Duration) -> Self {
+        Self {
+            timer_interval: Some(value),
+            ..self
+        }
+    }
+
+    /// Registra opcionalmente [`Timer`]s do engine (ex: cancelamento periódico de ordens
+    /// antigas, fechamento de posições no fim do dia).
+    ///
+    /// Observação: `Timer`s com o mesmo `TimerId` substituem os registrados anteriormente.
+    pub fn timers<TimerIter>(mut self, timers: TimerIter) -> Self
+    where
+        TimerIter: IntoIterator<Item = Timer>,
+    {
+        self.timers.extend(timers);
+        self
+    }
+
+    /// Configura opcionalmente um [`SessionControl`] que controla o `TradingState` pelas fases do
+    /// pregão do calendário da bolsa.
+    ///
+    /// O mesmo calendário mede o período em dias úteis usado para anualizar o resumo de trading.
+    pub fn session(self, value: SessionControl) -> Self {
+        Self {
+            session: Some(value),
+            ..self
+        }
+    }
+
+    /// Configura opcionalmente um [`KillSwitch`] automático que desabilita o trading quando
+    /// seus limites são violados.
+    pub fn kill_switch(self, value: KillSwitch) -> Self {
+        Self {
+            kill_switch: Some(value),
+            ..self
+        }
+    }
+
+    /// Configura opcionalmente um [`StaleFeedMonitor`] que marca feeds silenciosos como
+    /// `Stale` e pode interromper o trading após violações repetidas.
+    pub fn stale_feeds(self, value: StaleFeedMonitor) -> Self {
+        Self {
+            stale_feeds: Some(value),
+            ..self
+        }
+    }
+
+    /// Configura opcionalmente [`Reactions`] prontas (cancelar ordens, fechar posições, pausar
+    /// novas ordens) executadas nos hooks `OnDisconnectStrategy` e `OnTradingDisabled`.
+    pub fn reactions(self, value: Reactions) -> Self {
+        Self {
+            reactions: Some(value),
+            ..self
+        }
+    }
+
     /// Constrói o [`SystemBuild`] com as configurações aplicadas ao builder.
     ///
     /// Constrói todos os componentes do sistema mas não inicia tasks ou streams.
@@ -206,6 +293,12 @@ impl<'a, Clock, Strategy, Risk, MarketStream, GlobalData, FnInstrumentData>
             audit_mode,
             trading_state,
             balances,
+            timer_interval,
+            timers,
+            session,
+            kill_switch,
+            stale_feeds,
+            reactions,
         } = self;
 
         // Default if not provided
@@ -222,6 +315,10 @@ impl<'a, Clock, Strategy, Risk, MarketStream, GlobalData, FnInstrumentData>
                     ExecutionConfig::Mock(mock_config) => {
                         builder.add_mock(mock_config, clock.clone())
                     }
+                    ExecutionConfig::B3(b3_config) => builder.add_live::<B3ExecutionClient>(
+                        B3Config::from(&b3_config),
+                        b3_config.request_timeout(),
+                    ),
                 },
             )?
             .build();
@@ -231,7 +328,28 @@ impl<'a, Clock, Strategy, Risk, MarketStream, GlobalData, FnInstrumentData>
             .time_engine_start(clock.time())
             .trading_state(trading_state)
             .balances(balances)
-            .build();
+            .timers(timers);
+
+        let state = match session {
+            Some(session) => state.session(session),
+            None => state,
+        };
+
+        let state = match kill_switch {
+            Some(kill_switch) => state.kill_switch(kill_switch),
+            None => state,
+        };
+
+        let state = match stale_feeds {
+            Some(stale_feeds) => state.stale_feeds(stale_feeds),
+            None => state,
+        };
+
+        let state = match reactions {
+            Some(reactions) => state.reactions(reactions),
+            None => state,
+        }
+        .build();
 
         // Construct Engine
         let engine = Engine::new(clock, state, execution.execution_tx_map, strategy, risk);
@@ -243,6 +361,7 @@ impl<'a, Clock, Strategy, Risk, MarketStream, GlobalData, FnInstrumentData>
             market_stream,
             account_channel: execution.account_channel,
             execution_build_futures: execution.futures,
+            timer_interval,
             phantom_event: PhantomData,
         })
     }
@@ -271,6 +390,9 @@ pub struct SystemBuild<Engine, Event, MarketStream> {
     /// Futures for initialising `ExecutionBuild` components.
     pub execution_build_futures: ExecutionBuildFutures,
 
+    /// Optional interval at which [`TimerTick`]s are sent to the `Engine`.
+    pub timer_interval: Option<Duration>,
+
     phantom_event: PhantomData<Event>,
 }
 
@@ -282,10 +404,18 @@ where
         + Send
         + 'static,
     Engine::Audit: From<FeedEnded> + Terminal + Debug + Clone + Send + 'static,
-    Event: From<MarketStream::Item> + From<AccountStreamEvent> + Debug + Clone + Send + 'static,
+    Event: From<MarketStream::Item>
+        + From<AccountStreamEvent>
+        + From<TimerTick>
+        + Debug
+        + Clone
+        + Send
+        + 'static,
     MarketStream: Stream + Send + 'static,
 {
     /// Cria um novo `SystemBuild` a partir dos componentes fornecidos.
+    ///
+    /// O envio periódico de [`TimerTick`]s fica desabilitado; veja [`Self::timer_interval`].
     pub fn new(
         engine: Engine,
         engine_feed_mode: EngineFeedMode,
@@ -301,10 +431,19 @@ where
             market_stream,
             account_channel,
             execution_build_futures,
+            timer_interval: None,
             phantom_event: Default::default(),
         }
     }
 
+    /// Configura opcionalmente o intervalo de envio de [`TimerTick`]s ao engine.
+    pub fn timer_interval(self, value: Duration) -> Self {
+        Self {
+            timer_interval: Some(value),
+            ..self
+        }
+    }
+
     /// Inicializa o sistema usando o runtime tokio atual.
     ///
     /// Spawn de todas as tasks necessárias e retorna a instância `System` em execução.
@@ -333,6 +472,7 @@ where
             market_stream,
             account_channel,
             execution_build_futures,
+            timer_interval,
             phantom_event: _,
         } = self;
 
@@ -341,8 +481,9 @@ where
             .init_with_runtime(runtime.clone())
             .await?;
 
-        // Initialise central Engine channel
-        let (feed_tx, mut feed_rx) = mpsc_unbounded();
+        // Initialise central Engine channel, metering throughput & queue depth
+        let (feed_tx, feed_rx) = mpsc_unbounded();
+        let mut feed_rx = metered_feed(MetricRegistry::global(), feed_rx);
 
         // Forward MarketStreamEvents to Engine feed
         let market_to_engine = runtime
@@ -353,6 +494,19 @@ where
         let account_stream = account_channel.rx.into_stream();
         let account_to_engine = runtime.spawn(account_stream.forward_to(feed_tx.clone()));
 
+        // Optionally forward periodic TimerTicks to Engine feed
+        let timer_to_engine = timer_interval.map(|interval| {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+            let ticks = futures::stream::unfold(ticker, |mut ticker| async move {
+                ticker.tick().await;
+                Some((TimerTick, ticker))
+            });
+
+            runtime.spawn(ticks.forward_to(feed_tx.clone()))
+        });
+
         // Run Engine in configured mode
         let (engine, audit) = match (engine_feed_mode, audit_mode) {
             (EngineFeedMode::Iterator, AuditMode::Enabled) => {
@@ -416,6 +570,7 @@ where
                 execution,
                 market_to_engine,
                 account_to_engine,
+                timer_to_engine,
             },
             feed_tx,
             audit,
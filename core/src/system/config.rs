@@ -1,81 +1,63 @@
-
-use serde::{Deserialize, Serialize};
-use std::fmt::Debug;
-use toucan_execution::client::mock::MockExecutionConfig;
 /// Configuration module for trading system components.
 ///
 /// Provides data structures for configuring various aspects of a trading system,
-/// including instruments and execution components.
-use toucan_instrument::{exchange::ExchangeId, Underlying, ConcreteInstrument};
+/// including instruments, execution components, market data subscriptions, risk limits,
+/// strategy parameters and `Engine` run modes.
+///
+/// A [`SystemConfig`] can be loaded and validated from a TOML or YAML file with
+/// [`SystemConfig::from_path`]. Any error points at the offending key, eg/
+/// `instruments[1].spec.price.tick_size`.
+use crate::{
+    engine::{
+        clock::EngineClock,
+        execution_tx::MultiExchangeTxMap,
+        kill_switch::{KillSwitch, KillSwitchLimits},
+        risk::{LimitsRiskManager, MarginCheck, RiskLimits, SelfMatchMode},
+        state::{
+            exposure::{ExposureInstrument, PortfolioExposure},
+            trading::TradingState,
+            EngineState, IndexedInstruments,
+        },
+        Engine,
+    },
+    error::ToucanError,
+    system::builder::{AuditMode, EngineFeedMode, SystemArgs, SystemBuild, SystemBuilder},
+};
+use derive_more::{Display, From};
+use rust_decimal::Decimal;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt::Debug,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use thiserror::Error;
+use toucan_data::subscription::{SubKind, Subscription};
+use toucan_execution::{
+    client::{b3::B3Config, mock::MockExecutionConfig},
+    order::id::StrategyId,
+    InstrumentIndex,
+};
+use toucan_instrument::{
+    exchange::ExchangeId,
+    instrument::{
+        quote::InstrumentQuoteAsset,
+        spec::{InstrumentSpec, SpecRounding},
+    },
+    ConcreteInstrument, InstrumentKind, Keyed, MarketDataInstrument, Underlying,
+};
+use toucan_risk::margin::MarginModel;
 
 /// Placeholder types for configuration
 pub type AssetNameExchange = String;
 pub type InstrumentNameExchange = String;
 pub type InstrumentNameInternal = String;
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
-pub enum InstrumentKind {
-    Spot,
-    Future(FutureContract),
-    Option(OptionContract),
-    Perpetual(PerpetualContract),
-}
-
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
-pub struct FutureContract {
-    pub expiry: String,
-    pub contract_size: f64,
-    pub settlement_asset: String,
-}
-
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
-pub struct OptionContract {
-    pub strike: f64,
-    pub expiry: String,
-    pub contract_size: f64,
-    pub settlement_asset: String,
-    pub kind: String,
-    pub exercise: String,
-}
-
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
-pub struct PerpetualContract {
-    pub funding_rate: f64,
-    pub contract_size: f64,
-    pub settlement_asset: String,
-}
-
-#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
-pub struct InstrumentQuoteAsset {
-    pub asset: String,
-}
-
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
-pub struct InstrumentSpec {
-    pub quantity: InstrumentSpecQuantity,
-    pub price: Option<f64>,
-    pub notional: Option<f64>,
-}
-
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
-pub struct InstrumentSpecQuantity {
-    pub unit: OrderQuantityUnits,
-    pub min: f64,
-    pub increment: f64,
-}
-
-#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
-pub enum OrderQuantityUnits {
-    Asset(String),
-    Contract,
-    Quote,
-}
-
-use derive_more::From;
-
 /// Top-level configuration for a full trading system.
 ///
-/// Contains configuration for all instruments and execution components.
+/// Contains configuration for all instruments and execution components, and optionally the
+/// market data subscriptions, risk limits, strategy parameters and `Engine` run modes.
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct SystemConfig {
     /// Configurations for all instruments the system will track.
@@ -83,6 +65,22 @@ pub struct SystemConfig {
 
     /// Configurations for all execution components.
     pub executions: Vec<ExecutionConfig>,
+
+    /// Market data subscriptions for the configured instruments.
+    #[serde(default)]
+    pub market_data: Vec<MarketDataConfig>,
+
+    /// Risk limits enforced by the `Engine`.
+    #[serde(default)]
+    pub risk: RiskConfig,
+
+    /// Optional strategy identifier and parameters.
+    #[serde(default)]
+    pub strategy: Option<StrategyConfig>,
+
+    /// `Engine` run modes.
+    #[serde(default)]
+    pub engine: EngineConfig,
 }
 
 /// Convenient minimal instrument configuration, used to generate an [`Instrument`] on startup.
@@ -101,21 +99,996 @@ pub struct InstrumentConfig {
     pub quote: InstrumentQuoteAsset,
 
     /// Type of the instrument (spot, perpetual, future, option).
-    pub kind: InstrumentKind,
+    pub kind: InstrumentKind<AssetNameExchange>,
 
     /// Optional additional specifications for the instrument.
-    pub spec: Option<InstrumentSpec>,
+    pub spec: Option<InstrumentSpec<AssetNameExchange>>,
 }
 
 /// Configuration for an execution link.
 ///
-/// Represents different types of execution configurations,
-/// currently only supporting mock execution for backtesting.
+/// Represents different types of execution configurations, either a mock execution for
+/// backtesting & paper trading or a live execution.
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize, From)]
-#[serde(untagged)]
+#[serde(rename_all = "snake_case")]
 pub enum ExecutionConfig {
     /// Mock execution configuration for backtesting
     Mock(MockExecutionConfig),
+
+    /// Live B3 execution configuration (ProfitDLL).
+    B3(B3ExecutionConfig),
+}
+
+/// Live B3 execution configuration, used to generate a [`B3Config`].
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub struct B3ExecutionConfig {
+    /// ProfitDLL activation key.
+    pub activation_key: String,
+    pub username: String,
+    pub password: String,
+
+    /// Transport used to connect to B3.
+    #[serde(default)]
+    pub transport: B3TransportConfig,
+
+    /// Broker identifier.
+    #[serde(default = "default_b3_broker_id")]
+    pub broker_id: String,
+
+    /// Account identifier within the broker.
+    #[serde(default = "default_b3_account_id")]
+    pub account_id: String,
+
+    #[serde(default = "default_true")]
+    pub auto_reconnect: bool,
+
+    #[serde(default = "default_b3_connection_timeout_secs")]
+    pub connection_timeout_secs: u64,
+
+    /// Timeout of each `ExecutionRequest` sent to the exchange.
+    #[serde(default = "default_request_timeout_ms")]
+    pub request_timeout_ms: u64,
+}
+
+/// Transport used by a [`B3ExecutionConfig`].
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum B3TransportConfig {
+    /// ProfitDLL transport, optionally overriding the DLL path.
+    ProfitDll { dll_path: Option<String> },
+
+    /// In-process mock transport, useful for paper trading & integration tests.
+    Mock,
+}
+
+impl Default for B3TransportConfig {
+    fn default() -> Self {
+        Self::ProfitDll { dll_path: None }
+    }
+}
+
+impl B3ExecutionConfig {
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_millis(self.request_timeout_ms)
+    }
+}
+
+impl From<&B3ExecutionConfig> for B3Config {
+    fn from(value: &B3ExecutionConfig) -> Self {
+        let config = B3Config::new(
+            value.activation_key.clone(),
+            value.username.clone(),
+            value.password.clone(),
+        )
+        .with_broker_id(value.broker_id.clone())
+        .with_account_id(value.account_id.clone())
+        .with_auto_reconnect(value.auto_reconnect)
+        .with_connection_timeout(value.connection_timeout_secs);
+
+        match &value.transport {
+            B3TransportConfig::ProfitDll {
+                dll_path: Some(dll_path),
+            } => config.with_dll_path(dll_path.clone()),
+            B3TransportConfig::ProfitDll { dll_path: None } | B3TransportConfig::Mock => config,
+        }
+    }
+}
+
+/// Market data subscriptions for a configured instrument.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub struct MarketDataConfig {
+    /// Exchange identifier of the subscribed instrument.
+    pub exchange: ExchangeId,
+
+    /// Exchange-specific name of a configured instrument.
+    pub instrument: InstrumentNameExchange,
+
+    /// Kinds of market data to subscribe to (eg/ `PublicTrades`, `OrderBooksL1`).
+    pub kinds: Vec<SubKind>,
+}
+
+/// Risk limits enforced by the `Engine`.
+#[derive(Debug, Clone, Eq, PartialEq, Default, Deserialize, Serialize)]
+pub struct RiskConfig {
+    /// Optional automatic [`KillSwitch`] configuration.
+    #[serde(default)]
+    pub kill_switch: Option<KillSwitchConfig>,
+
+    /// Optional pre-trade [`LimitsRiskManager`] configuration.
+    #[serde(default)]
+    pub limits: Option<RiskLimitsConfig>,
+
+    /// Optional derivatives [`MarginCheck`] configuration.
+    #[serde(default)]
+    pub margin: Option<MarginConfig>,
+}
+
+/// Configuration used to generate a [`MarginCheck`].
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+pub struct MarginConfig {
+    /// Path to the TOML or YAML [`MarginModel`] file containing the per-contract initial margin
+    /// table and margin scenarios (eg/ "config/b3_margin.toml").
+    pub table: PathBuf,
+
+    /// Asset whose total balance is the available collateral (eg/ "BRL").
+    pub collateral_asset: String,
+}
+
+/// Configuration used to generate a [`LimitsRiskManager`].
+#[derive(Debug, Clone, Eq, PartialEq, Default, Deserialize, Serialize)]
+pub struct RiskLimitsConfig {
+    /// Global [`RiskLimits`].
+    #[serde(default)]
+    pub limits: RiskLimits,
+
+    /// [`RiskLimits`] per instrument, keyed by exchange instrument name.
+    #[serde(default)]
+    pub instruments: BTreeMap<InstrumentNameExchange, RiskLimits>,
+
+    /// Maximum absolute net position quantity per underlying (eg/ "PETR4_BRL").
+    #[serde(default)]
+    pub underlyings: BTreeMap<String, Decimal>,
+
+    /// Exchange instrument names allowed to be traded, or `None` if every instrument is allowed.
+    #[serde(default)]
+    pub allowed_instruments: Option<Vec<InstrumentNameExchange>>,
+
+    /// Maximum number of open orders across all instruments.
+    #[serde(default)]
+    pub max_open_orders: Option<usize>,
+
+    /// [`SpecRounding`] used to normalise orders to the configured instrument specs, or `None`
+    /// to refuse orders off the price tick or quantity increment.
+    #[serde(default)]
+    pub spec_rounding: Option<SpecRounding>,
+
+    /// [`SelfMatchMode`] applied to orders crossing own resting orders (eg/ "cancel_resting"),
+    /// or `None` to allow them.
+    #[serde(default)]
+    pub self_match: Option<SelfMatchMode>,
+}
+
+impl<State> From<&RiskLimitsConfig> for LimitsRiskManager<State> {
+    fn from(value: &RiskLimitsConfig) -> Self {
+        let manager = value.instruments.iter().fold(
+            LimitsRiskManager::new(value.limits),
+            |manager, (instrument, limits)| {
+                manager.with_instrument_limits(InstrumentIndex::from(instrument.as_str()), *limits)
+            },
+        );
+
+        let manager = value
+            .underlyings
+            .iter()
+            .fold(manager, |manager, (underlying, limit)| {
+                manager.with_underlying_limit(underlying, *limit)
+            });
+
+        let manager = match &value.allowed_instruments {
+            Some(allowed) => manager.with_allowed_instruments(
+                allowed
+                    .iter()
+                    .map(|instrument| InstrumentIndex::from(instrument.as_str())),
+            ),
+            None => manager,
+        };
+
+        let manager = match value.spec_rounding {
+            Some(rounding) => manager.with_spec_rounding(rounding),
+            None => manager,
+        };
+
+        let manager = match value.self_match {
+            Some(mode) => manager.with_self_match_prevention(mode),
+            None => manager,
+        };
+
+        match value.max_open_orders {
+            Some(limit) => manager.with_max_open_orders(limit),
+            None => manager,
+        }
+    }
+}
+
+/// Configuration used to generate a [`KillSwitch`].
+#[derive(Debug, Clone, Eq, PartialEq, Default, Deserialize, Serialize)]
+pub struct KillSwitchConfig {
+    /// Global [`KillSwitchLimits`].
+    #[serde(default)]
+    pub limits: KillSwitchLimits,
+
+    /// [`KillSwitchLimits`] per instrument, keyed by exchange instrument name.
+    #[serde(default)]
+    pub instruments: BTreeMap<InstrumentNameExchange, KillSwitchLimits>,
+
+    /// [`KillSwitchLimits`] per strategy, keyed by [`StrategyId`].
+    #[serde(default)]
+    pub strategies: BTreeMap<String, KillSwitchLimits>,
+
+    /// Fixed UTC offset (in seconds) of the local timezone whose midnight starts a new trading
+    /// day, resetting the intraday tallies.
+    #[serde(default)]
+    pub utc_offset_secs: i32,
+}
+
+impl From<&KillSwitchConfig> for KillSwitch {
+    fn from(value: &KillSwitchConfig) -> Self {
+        let kill_switch = value.instruments.iter().fold(
+            KillSwitch::new(value.limits),
+            |kill_switch, (instrument, limits)| {
+                kill_switch
+                    .with_instrument_limits(InstrumentIndex::from(instrument.as_str()), *limits)
+            },
+        );
+
+        let kill_switch =
+            value
+                .strategies
+                .iter()
+                .fold(kill_switch, |kill_switch, (strategy, limits)| {
+                    kill_switch.with_strategy_limits(StrategyId::new(strategy), *limits)
+                });
+
+        KillSwitch {
+            utc_offset_secs: value.utc_offset_secs,
+            ..kill_switch
+        }
+    }
+}
+
+/// Strategy identifier and parameters, used by the caller to construct the `Strategy`.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+pub struct StrategyConfig {
+    /// Strategy name, used as the [`StrategyId`].
+    pub name: String,
+
+    /// Strategy specific parameters.
+    #[serde(default)]
+    pub parameters: BTreeMap<String, StrategyParameter>,
+}
+
+/// Value of a [`StrategyConfig`] parameter.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum StrategyParameter {
+    Boolean(bool),
+    Integer(i64),
+    Decimal(Decimal),
+    String(String),
+}
+
+impl StrategyConfig {
+    pub fn id(&self) -> StrategyId {
+        StrategyId::new(&self.name)
+    }
+
+    /// Returns the [`StrategyParameter`] associated with the provided key.
+    pub fn parameter(&self, key: &str) -> Result<&StrategyParameter, ConfigError> {
+        self.parameters
+            .get(key)
+            .ok_or_else(|| ConfigError::invalid(Self::key(key), "missing strategy parameter"))
+    }
+
+    /// Returns the `Decimal` parameter associated with the provided key (integers are accepted).
+    pub fn decimal(&self, key: &str) -> Result<Decimal, ConfigError> {
+        match self.parameter(key)? {
+            StrategyParameter::Decimal(value) => Ok(*value),
+            StrategyParameter::Integer(value) => Ok(Decimal::from(*value)),
+            _ => Err(ConfigError::invalid(Self::key(key), "expected a decimal")),
+        }
+    }
+
+    /// Returns the integer parameter associated with the provided key.
+    pub fn integer(&self, key: &str) -> Result<i64, ConfigError> {
+        match self.parameter(key)? {
+            StrategyParameter::Integer(value) => Ok(*value),
+            _ => Err(ConfigError::invalid(Self::key(key), "expected an integer")),
+        }
+    }
+
+    /// Returns the boolean parameter associated with the provided key.
+    pub fn boolean(&self, key: &str) -> Result<bool, ConfigError> {
+        match self.parameter(key)? {
+            StrategyParameter::Boolean(value) => Ok(*value),
+            _ => Err(ConfigError::invalid(Self::key(key), "expected a boolean")),
+        }
+    }
+
+    /// Returns the string parameter associated with the provided key.
+    pub fn string(&self, key: &str) -> Result<&str, ConfigError> {
+        match self.parameter(key)? {
+            StrategyParameter::String(value) => Ok(value),
+            _ => Err(ConfigError::invalid(Self::key(key), "expected a string")),
+        }
+    }
+
+    fn key(key: &str) -> String {
+        format!("strategy.parameters.{key}")
+    }
+}
+
+/// `Engine` run modes.
+#[derive(Debug, Clone, Eq, PartialEq, Default, Deserialize, Serialize)]
+pub struct EngineConfig {
+    #[serde(default)]
+    pub feed_mode: EngineFeedMode,
+
+    #[serde(default)]
+    pub audit_mode: AuditMode,
+
+    /// Initial [`TradingState`].
+    #[serde(default)]
+    pub trading_state: TradingState,
+
+    /// Optional interval at which `TimerTick`s are sent to the `Engine`.
+    #[serde(default)]
+    pub timer_interval_ms: Option<u64>,
+}
+
+/// Format of a [`SystemConfig`] file.
+#[derive(
+    Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize, Display,
+)]
+pub enum ConfigFormat {
+    #[display("TOML")]
+    Toml,
+    #[display("YAML")]
+    Yaml,
+}
+
+impl ConfigFormat {
+    /// Determine the [`ConfigFormat`] from the file extension (`.toml`, `.yaml` or `.yml`).
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "toml" => Some(Self::Toml),
+            "yaml" | "yml" => Some(Self::Yaml),
+            _ => None,
+        }
+    }
+}
+
+/// Errors generated when loading a [`SystemConfig`].
+///
+/// `key` is the path to the offending key, eg/ `executions[0].b3.username`.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize, Error)]
+pub enum ConfigError {
+    #[error("failed to read config file {path}: {message}")]
+    Io { path: String, message: String },
+
+    #[error("unsupported config file {0}: expected a .toml, .yaml or .yml extension")]
+    UnsupportedFormat(String),
+
+    #[error("invalid {format} at `{key}`: {message}")]
+    Parse {
+        format: ConfigFormat,
+        key: String,
+        message: String,
+    },
+
+    #[error("invalid value at `{key}`: {reason}")]
+    Invalid { key: String, reason: String },
+}
+
+impl ConfigError {
+    pub fn invalid(key: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::Invalid {
+            key: key.into(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Path to the offending key, if known.
+    pub fn key(&self) -> Option<&str> {
+        match self {
+            Self::Parse { key, .. } | Self::Invalid { key, .. } => Some(key),
+            Self::Io { .. } | Self::UnsupportedFormat(_) => None,
+        }
+    }
+}
+
+impl SystemConfig {
+    /// Load and validate a [`SystemConfig`] from a TOML or YAML file, determining the format
+    /// from the file extension.
+    pub fn from_path<P>(path: P) -> Result<Self, ConfigError>
+    where
+        P: AsRef<Path>,
+    {
+        let (format, contents) = read_file(path.as_ref())?;
+        Self::from_str_with_format(&contents, format)
+    }
+
+    /// Parse and validate a [`SystemConfig`] from a TOML string.
+    pub fn from_toml_str(contents: &str) -> Result<Self, ConfigError> {
+        Self::from_str_with_format(contents, ConfigFormat::Toml)
+    }
+
+    /// Parse and validate a [`SystemConfig`] from a YAML string.
+    pub fn from_yaml_str(contents: &str) -> Result<Self, ConfigError> {
+        Self::from_str_with_format(contents, ConfigFormat::Yaml)
+    }
+
+    /// Parse and validate a [`SystemConfig`] from a string in the provided [`ConfigFormat`].
+    pub fn from_str_with_format(contents: &str, format: ConfigFormat) -> Result<Self, ConfigError> {
+        let config: Self = parse_str(contents, format)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Validate the [`SystemConfig`], returning a [`ConfigError::Invalid`] pointing at the first
+    /// offending key.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        for (index, instrument) in self.instruments.iter().enumerate() {
+            let key = format!("instruments[{index}]");
+
+            if instrument.name_exchange.is_empty() {
+                return Err(ConfigError::invalid(
+                    format!("{key}.name_exchange"),
+                    "must not be empty",
+                ));
+            }
+
+            if self.instruments[..index].iter().any(|other| {
+                other.exchange == instrument.exchange
+                    && other.name_exchange == instrument.name_exchange
+            }) {
+                return Err(ConfigError::invalid(
+                    format!("{key}.name_exchange"),
+                    format!(
+                        "duplicate instrument {} on exchange {}",
+                        instrument.name_exchange, instrument.exchange
+                    ),
+                ));
+            }
+
+            if let Some(spec) = &instrument.spec {
+                validate_positive(spec.price.tick_size, || {
+                    format!("{key}.spec.price.tick_size")
+                })?;
+                validate_non_negative(spec.price.min, || format!("{key}.spec.price.min"))?;
+                validate_positive(spec.quantity.increment, || {
+                    format!("{key}.spec.quantity.increment")
+                })?;
+                validate_non_negative(spec.quantity.min, || format!("{key}.spec.quantity.min"))?;
+                validate_non_negative(spec.notional.min, || format!("{key}.spec.notional.min"))?;
+            }
+        }
+
+        for (index, execution) in self.executions.iter().enumerate() {
+            let key = format!("executions[{index}]");
+            let exchange = execution.exchange();
+
+            if self.executions[..index]
+                .iter()
+                .any(|other| other.exchange() == exchange)
+            {
+                return Err(ConfigError::invalid(
+                    key,
+                    format!("duplicate execution for exchange {exchange}"),
+                ));
+            }
+
+            if let ExecutionConfig::B3(config) = execution {
+                let key = format!("{key}.b3");
+                for (field, value) in [
+                    ("activation_key", &config.activation_key),
+                    ("username", &config.username),
+                    ("password", &config.password),
+                ] {
+                    if value.is_empty() {
+                        return Err(ConfigError::invalid(
+                            format!("{key}.{field}"),
+                            "must not be empty",
+                        ));
+                    }
+                }
+
+                if config.request_timeout_ms == 0 {
+                    return Err(ConfigError::invalid(
+                        format!("{key}.request_timeout_ms"),
+                        "must be greater than zero",
+                    ));
+                }
+            }
+        }
+
+        for (index, market_data) in self.market_data.iter().enumerate() {
+            let key = format!("market_data[{index}]");
+
+            if self
+                .instrument(market_data.exchange, &market_data.instrument)
+                .is_none()
+            {
+                return Err(ConfigError::invalid(
+                    format!("{key}.instrument"),
+                    format!(
+                        "instrument {} on exchange {} is not configured",
+                        market_data.instrument, market_data.exchange
+                    ),
+                ));
+            }
+
+            if market_data.kinds.is_empty() {
+                return Err(ConfigError::invalid(
+                    format!("{key}.kinds"),
+                    "must not be empty",
+                ));
+            }
+        }
+
+        if let Some(kill_switch) = &self.risk.kill_switch {
+            let key = "risk.kill_switch";
+            validate_kill_switch_limits(&kill_switch.limits, &format!("{key}.limits"))?;
+
+            for (instrument, limits) in &kill_switch.instruments {
+                if !self
+                    .instruments
+                    .iter()
+                    .any(|config| &config.name_exchange == instrument)
+                {
+                    return Err(ConfigError::invalid(
+                        format!("{key}.instruments.{instrument}"),
+                        "instrument is not configured",
+                    ));
+                }
+                validate_kill_switch_limits(limits, &format!("{key}.instruments.{instrument}"))?;
+            }
+
+            for (strategy, limits) in &kill_switch.strategies {
+                validate_kill_switch_limits(limits, &format!("{key}.strategies.{strategy}"))?;
+            }
+        }
+
+        if let Some(limits) = &self.risk.limits {
+            let key = "risk.limits";
+            validate_risk_limits(&limits.limits, &format!("{key}.limits"))?;
+
+            let configured = |instrument: &InstrumentNameExchange| {
+                self.instruments
+                    .iter()
+                    .any(|config| &config.name_exchange == instrument)
+            };
+
+            for instrument in limits.instruments.keys() {
+                if !configured(instrument) {
+                    return Err(ConfigError::invalid(
+                        format!("{key}.instruments.{instrument}"),
+                        "instrument is not configured",
+                    ));
+                }
+            }
+
+            for (index, instrument) in limits.allowed_instruments.iter().flatten().enumerate() {
+                if !configured(instrument) {
+                    return Err(ConfigError::invalid(
+                        format!("{key}.allowed_instruments[{index}]"),
+                        "instrument is not configured",
+                    ));
+                }
+            }
+
+            for (instrument, instrument_limits) in &limits.instruments {
+                validate_risk_limits(
+                    instrument_limits,
+                    &format!("{key}.instruments.{instrument}"),
+                )?;
+            }
+
+            for (underlying, limit) in &limits.underlyings {
+                validate_positive(*limit, || format!("{key}.underlyings.{underlying}"))?;
+            }
+
+            if limits.max_open_orders == Some(0) {
+                return Err(ConfigError::invalid(
+                    format!("{key}.max_open_orders"),
+                    "must be greater than zero",
+                ));
+            }
+        }
+
+        if let Some(margin) = &self.risk.margin {
+            if margin.collateral_asset.is_empty() {
+                return Err(ConfigError::invalid(
+                    "risk.margin.collateral_asset",
+                    "must not be empty",
+                ));
+            }
+        }
+
+        if let Some(strategy) = &self.strategy {
+            if strategy.name.is_empty() {
+                return Err(ConfigError::invalid("strategy.name", "must not be empty"));
+            }
+        }
+
+        if self.engine.timer_interval_ms == Some(0) {
+            return Err(ConfigError::invalid(
+                "engine.timer_interval_ms",
+                "must be greater than zero",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Find the [`InstrumentConfig`] with the provided exchange & exchange instrument name.
+    pub fn instrument(
+        &self,
+        exchange: ExchangeId,
+        name_exchange: &str,
+    ) -> Option<&InstrumentConfig> {
+        self.instruments.iter().find(|instrument| {
+            instrument.exchange == exchange && instrument.name_exchange == name_exchange
+        })
+    }
+
+    /// Generate the [`IndexedInstruments`] of the configured instruments, keyed by their
+    /// exchange instrument name.
+    pub fn indexed_instruments(&self) -> IndexedInstruments {
+        self.instruments
+            .iter()
+            .map(|instrument| {
+                Keyed::new(
+                    InstrumentIndex::from(instrument.name_exchange.as_str()),
+                    ConcreteInstrument::from(instrument.clone()),
+                )
+            })
+            .collect()
+    }
+
+    /// Generate the market data [`Subscription`]s of the configured [`MarketDataConfig`]s.
+    ///
+    /// Subscriptions to instruments that are not configured are skipped, see [`Self::validate`].
+    pub fn subscriptions(&self) -> Vec<Subscription<ExchangeId, MarketDataInstrument, SubKind>> {
+        self.market_data
+            .iter()
+            .filter_map(|market_data| {
+                let instrument = self.instrument(market_data.exchange, &market_data.instrument)?;
+                Some((market_data, instrument))
+            })
+            .flat_map(|(market_data, instrument)| {
+                market_data.kinds.iter().map(|kind| {
+                    Subscription::from((
+                        market_data.exchange,
+                        instrument.underlying.base.as_str(),
+                        instrument.underlying.quote.as_str(),
+                        instrument.kind.clone().into(),
+                        *kind,
+                    ))
+                })
+            })
+            .collect()
+    }
+
+    /// Generate the configured [`KillSwitch`], if any.
+    pub fn kill_switch(&self) -> Option<KillSwitch> {
+        self.risk.kill_switch.as_ref().map(KillSwitch::from)
+    }
+
+    /// Generate the configured pre-trade [`LimitsRiskManager`], if any.
+    ///
+    /// Orders are validated against the [`InstrumentSpec`] of every configured instrument that
+    /// defines one.
+    pub fn risk_manager<State>(&self) -> Option<LimitsRiskManager<State>> {
+        let manager = LimitsRiskManager::from(self.risk.limits.as_ref()?);
+
+        let manager = self
+            .instruments
+            .iter()
+            .filter_map(|instrument| Some((instrument, instrument.spec.clone()?)))
+            .fold(manager, |manager, (instrument, spec)| {
+                manager.with_instrument_spec(
+                    InstrumentIndex::from(instrument.name_exchange.as_str()),
+                    spec,
+                    instrument.kind.contract_size(),
+                )
+            });
+
+        Some(manager)
+    }
+
+    /// Generate a [`PortfolioExposure`] `GlobalData` tracking every configured instrument.
+    ///
+    /// Instruments are aggregated by base asset, by "{base}_{quote}" underlying (as used by the
+    /// [`RiskLimits`] underlying limits) and by exchange.
+    pub fn portfolio_exposure(&self) -> PortfolioExposure {
+        self.instruments
+            .iter()
+            .fold(PortfolioExposure::default(), |exposure, instrument| {
+                exposure.with_instrument(
+                    InstrumentIndex::from(instrument.name_exchange.as_str()),
+                    ExposureInstrument::new(
+                        instrument.underlying.base.as_str(),
+                        format!(
+                            "{}_{}",
+                            instrument.underlying.base, instrument.underlying.quote
+                        ),
+                        instrument.exchange,
+                        instrument.kind.contract_size(),
+                    ),
+                )
+            })
+    }
+
+    /// Generate the configured [`MarginCheck`], if any, loading the [`MarginModel`] table file.
+    ///
+    /// Contract sizes are taken from the configured instrument kinds.
+    pub fn margin_check(&self) -> Result<Option<MarginCheck>, ConfigError> {
+        let Some(margin) = &self.risk.margin else {
+            return Ok(None);
+        };
+
+        let (format, contents) = read_file(&margin.table)?;
+        let model: MarginModel = parse_str(&contents, format)?;
+
+        let check = self.instruments.iter().fold(
+            MarginCheck::new(model, margin.collateral_asset.as_str()),
+            |check, instrument| {
+                check.with_contract_size(
+                    InstrumentIndex::from(instrument.name_exchange.as_str()),
+                    instrument.kind.contract_size(),
+                )
+            },
+        );
+
+        Ok(Some(check))
+    }
+
+    /// Construct a [`SystemBuilder`] with the configured executions, risk limits and `Engine`
+    /// run modes applied.
+    ///
+    /// The `instruments` are usually generated with [`Self::indexed_instruments`].
+    #[allow(clippy::too_many_arguments)]
+    pub fn system_builder<'a, Clock, Strategy, Risk, MarketStream, GlobalData, FnInstrumentData>(
+        &self,
+        instruments: &'a IndexedInstruments,
+        clock: Clock,
+        strategy: Strategy,
+        risk: Risk,
+        market_stream: MarketStream,
+        global_data: GlobalData,
+        instrument_data_init: FnInstrumentData,
+    ) -> SystemBuilder<'a, Clock, Strategy, Risk, MarketStream, GlobalData, FnInstrumentData> {
+        let builder = SystemBuilder::new(SystemArgs::new(
+            instruments,
+            self.executions.clone(),
+            clock,
+            strategy,
+            risk,
+            market_stream,
+            global_data,
+            instrument_data_init,
+        ))
+        .engine_feed_mode(self.engine.feed_mode.clone())
+        .audit_mode(self.engine.audit_mode.clone())
+        .trading_state(self.engine.trading_state);
+
+        let builder = match self.engine.timer_interval_ms {
+            Some(interval) => builder.timer_interval(Duration::from_millis(interval)),
+            None => builder,
+        };
+
+        match self.kill_switch() {
+            Some(kill_switch) => builder.kill_switch(kill_switch),
+            None => builder,
+        }
+    }
+
+    /// Build a ready [`SystemBuild`] from the [`SystemConfig`] and the provided components.
+    ///
+    /// See [`Self::system_builder`].
+    #[allow(clippy::too_many_arguments, clippy::type_complexity)]
+    pub fn build<
+        'a,
+        Event,
+        Clock,
+        Strategy,
+        Risk,
+        MarketStream,
+        GlobalData,
+        FnInstrumentData,
+        InstrumentData,
+    >(
+        &self,
+        instruments: &'a IndexedInstruments,
+        clock: Clock,
+        strategy: Strategy,
+        risk: Risk,
+        market_stream: MarketStream,
+        global_data: GlobalData,
+        instrument_data_init: FnInstrumentData,
+    ) -> Result<
+        SystemBuild<
+            Engine<
+                Clock,
+                EngineState<GlobalData, InstrumentData>,
+                MultiExchangeTxMap,
+                Strategy,
+                Risk,
+            >,
+            Event,
+            MarketStream,
+        >,
+        ToucanError,
+    >
+    where
+        Clock: EngineClock + Clone + Send + Sync + 'static,
+        FnInstrumentData: Fn(&'a Keyed<InstrumentIndex, ConcreteInstrument>) -> InstrumentData,
+    {
+        self.system_builder(
+            instruments,
+            clock,
+            strategy,
+            risk,
+            market_stream,
+            global_data,
+            instrument_data_init,
+        )
+        .build()
+    }
+}
+
+impl ExecutionConfig {
+    /// [`ExchangeId`] of the execution link.
+    pub fn exchange(&self) -> ExchangeId {
+        match self {
+            Self::Mock(config) => config.mocked_exchange,
+            Self::B3(_) => ExchangeId::Other,
+        }
+    }
+}
+
+/// Read a TOML or YAML file, determining the [`ConfigFormat`] from the file extension.
+fn read_file(path: &Path) -> Result<(ConfigFormat, String), ConfigError> {
+    let format = ConfigFormat::from_path(path)
+        .ok_or_else(|| ConfigError::UnsupportedFormat(path.display().to_string()))?;
+
+    let contents = std::fs::read_to_string(path).map_err(|error| ConfigError::Io {
+        path: path.display().to_string(),
+        message: error.to_string(),
+    })?;
+
+    Ok((format, contents))
+}
+
+fn parse_str<T>(contents: &str, format: ConfigFormat) -> Result<T, ConfigError>
+where
+    T: DeserializeOwned,
+{
+    match format {
+        ConfigFormat::Toml => parse(toml::Deserializer::new(contents), format),
+        ConfigFormat::Yaml => parse(serde_yaml::Deserializer::from_str(contents), format),
+    }
+}
+
+/// Deserialize a `T`, tracking the path to the offending key of any error.
+///
+/// Externally tagged enums (eg/ [`ExecutionConfig`]) are represented as single key maps in both
+/// formats, eg/ `b3: { .. }` in YAML rather than a `!b3` tag.
+fn parse<'de, Deserializer, T>(
+    deserializer: Deserializer,
+    format: ConfigFormat,
+) -> Result<T, ConfigError>
+where
+    Deserializer: serde::Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let mut track = serde_path_to_error::Track::new();
+    let deserializer = serde_path_to_error::Deserializer::new(deserializer, &mut track);
+
+    serde_yaml::with::singleton_map_recursive::deserialize(deserializer).map_err(|error| {
+        ConfigError::Parse {
+            format,
+            key: track.path().to_string(),
+            message: error.to_string(),
+        }
+    })
+}
+
+fn validate_positive(value: Decimal, key: impl FnOnce() -> String) -> Result<(), ConfigError> {
+    if value > Decimal::ZERO {
+        Ok(())
+    } else {
+        Err(ConfigError::invalid(key(), "must be greater than zero"))
+    }
+}
+
+fn validate_non_negative(value: Decimal, key: impl FnOnce() -> String) -> Result<(), ConfigError> {
+    if value >= Decimal::ZERO {
+        Ok(())
+    } else {
+        Err(ConfigError::invalid(key(), "must not be negative"))
+    }
+}
+
+fn validate_kill_switch_limits(limits: &KillSwitchLimits, key: &str) -> Result<(), ConfigError> {
+    if let Some(max_loss) = limits.max_loss {
+        validate_positive(max_loss, || format!("{key}.max_loss"))?;
+    }
+    if let Some(max_drawdown) = limits.max_drawdown {
+        validate_positive(max_drawdown, || format!("{key}.max_drawdown"))?;
+    }
+    if let Some(rejection) = limits.max_rejection_rate {
+        if rejection.max_rate <= Decimal::ZERO || rejection.max_rate > Decimal::ONE {
+            return Err(ConfigError::invalid(
+                format!("{key}.max_rejection_rate.max_rate"),
+                "must be within (0, 1]",
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn validate_risk_limits(limits: &RiskLimits, key: &str) -> Result<(), ConfigError> {
+    if let Some(max_order_quantity) = limits.max_order_quantity {
+        validate_positive(max_order_quantity, || format!("{key}.max_order_quantity"))?;
+    }
+    if let Some(max_order_notional) = limits.max_order_notional {
+        validate_positive(max_order_notional, || format!("{key}.max_order_notional"))?;
+    }
+    if let Some(max_position) = limits.max_position {
+        validate_positive(max_position, || format!("{key}.max_position"))?;
+    }
+    if let Some(max_price_deviation) = limits.max_price_deviation {
+        validate_positive(max_price_deviation, || format!("{key}.max_price_deviation"))?;
+    }
+    if limits.max_open_orders == Some(0) {
+        return Err(ConfigError::invalid(
+            format!("{key}.max_open_orders"),
+            "must be greater than zero",
+        ));
+    }
+    if limits.max_orders_per_second == Some(0) {
+        return Err(ConfigError::invalid(
+            format!("{key}.max_orders_per_second"),
+            "must be greater than zero",
+        ));
+    }
+    if let Some(max_order_to_trade_ratio) = limits.max_order_to_trade_ratio {
+        validate_positive(max_order_to_trade_ratio, || {
+            format!("{key}.max_order_to_trade_ratio")
+        })?;
+    }
+    Ok(())
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_b3_broker_id() -> String {
+    "ProfitDLL".to_string()
+}
+
+fn default_b3_account_id() -> String {
+    "default".to_string()
+}
+
+fn default_b3_connection_timeout_secs() -> u64 {
+    30
+}
+
+fn default_request_timeout_ms() -> u64 {
+    5_000
 }
 
 impl From<InstrumentConfig> for ConcreteInstrument {
@@ -132,3 +1105,331 @@ impl From<InstrumentConfig> for ConcreteInstrument {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    const TOML: &str = r#"
+[[instruments]]
+exchange = "other"
+name_exchange = "PETR4"
+underlying = { base = "PETR4", quote = "BRL" }
+quote = "underlying_quote"
+kind = "spot"
+spec.price = { min = 0.01, tick_size = 0.01 }
+spec.quantity = { unit = "Contract", min = 100, increment = 100 }
+spec.notional = { min = 0 }
+
+[[executions]]
+[executions.b3]
+activation_key = "key"
+username = "user"
+password = "pass"
+account_id = "12345"
+transport = "mock"
+
+[[market_data]]
+exchange = "other"
+instrument = "PETR4"
+kinds = ["PublicTrades", "OrderBooksL1"]
+
+[risk.kill_switch.limits]
+max_loss = 5000
+max_consecutive_losses = 3
+
+[risk.kill_switch.instruments.PETR4]
+max_drawdown = 1000
+
+[risk.limits]
+allowed_instruments = ["PETR4"]
+max_open_orders = 20
+limits = { max_order_quantity = 1000, max_price_deviation = 0.05 }
+underlyings = { PETR4_BRL = 5000 }
+spec_rounding = { price = "Passive", quantity = "Down" }
+self_match = "cancel_resting"
+
+[risk.limits.instruments.PETR4]
+max_position = 2000
+max_orders_per_second = 10
+
+[strategy]
+name = "momentum"
+parameters = { lookback = 20, threshold = 0.5, enabled = true, side = "long" }
+
+[engine]
+feed_mode = "Stream"
+audit_mode = "Enabled"
+trading_state = "Enabled"
+timer_interval_ms = 1000
+"#;
+
+    #[test]
+    fn test_system_config_from_toml_str() {
+        let config = SystemConfig::from_toml_str(TOML).unwrap();
+
+        let instrument = &config.instruments[0];
+        assert_eq!(instrument.quote, InstrumentQuoteAsset::UnderlyingQuote);
+        assert_eq!(instrument.kind, InstrumentKind::Spot);
+        assert_eq!(
+            instrument.spec.as_ref().unwrap().price.tick_size,
+            dec!(0.01)
+        );
+
+        let ExecutionConfig::B3(b3) = &config.executions[0] else {
+            panic!("expected B3 ExecutionConfig");
+        };
+        assert_eq!(b3.transport, B3TransportConfig::Mock);
+        assert_eq!(b3.broker_id, "ProfitDLL");
+        assert_eq!(b3.request_timeout(), Duration::from_millis(5_000));
+        assert_eq!(B3Config::from(b3).account_id, "12345");
+
+        assert_eq!(config.subscriptions().len(), 2);
+        assert_eq!(config.indexed_instruments()[0].key, "PETR4");
+
+        assert_eq!(config.engine.feed_mode, EngineFeedMode::Stream);
+        assert_eq!(config.engine.audit_mode, AuditMode::Enabled);
+        assert_eq!(config.engine.trading_state, TradingState::Enabled);
+    }
+
+    #[test]
+    fn test_system_config_risk_from_toml_str() {
+        let config = SystemConfig::from_toml_str(TOML).unwrap();
+
+        let kill_switch = config.risk.kill_switch.as_ref().unwrap();
+        assert_eq!(kill_switch.limits.max_loss, Some(dec!(5000)));
+        assert_eq!(
+            kill_switch.instruments["PETR4"].max_drawdown,
+            Some(dec!(1000))
+        );
+
+        let limits = config.risk_manager::<()>().unwrap();
+        assert_eq!(limits.max_open_orders, Some(20));
+        assert_eq!(limits.underlyings["PETR4_BRL"], dec!(5000));
+        let instrument_limits = limits.instrument_limits(&InstrumentIndex::from("PETR4"));
+        assert_eq!(instrument_limits.max_position, Some(dec!(2000)));
+        assert_eq!(instrument_limits.max_order_quantity, Some(dec!(1000)));
+        assert_eq!(instrument_limits.max_orders_per_second, Some(10));
+        assert_eq!(limits.self_match, Some(SelfMatchMode::CancelResting));
+        assert_eq!(limits.spec_rounding, Some(SpecRounding::default()));
+        assert_eq!(limits.specs["PETR4"].spec.quantity.increment, dec!(100));
+
+        let exposure = config.portfolio_exposure();
+        let petr4 = exposure
+            .instrument(&InstrumentIndex::from("PETR4"))
+            .unwrap();
+        assert_eq!(petr4.asset, "PETR4");
+        assert_eq!(petr4.underlying, "PETR4_BRL");
+        assert_eq!(petr4.contract_size, Decimal::ONE);
+    }
+
+    #[test]
+    fn test_system_config_strategy_from_toml_str() {
+        let config = SystemConfig::from_toml_str(TOML).unwrap();
+
+        let strategy = config.strategy.as_ref().unwrap();
+        assert_eq!(strategy.id(), StrategyId::new("momentum"));
+        assert_eq!(strategy.integer("lookback"), Ok(20));
+        assert_eq!(strategy.decimal("lookback"), Ok(dec!(20)));
+        assert_eq!(strategy.decimal("threshold"), Ok(dec!(0.5)));
+        assert_eq!(strategy.boolean("enabled"), Ok(true));
+        assert_eq!(strategy.string("side"), Ok("long"));
+        assert_eq!(
+            strategy.integer("missing").unwrap_err().key(),
+            Some("strategy.parameters.missing")
+        );
+    }
+
+    #[test]
+    fn test_system_config_from_yaml_str() {
+        let yaml = r#"
+instruments:
+  - exchange: other
+    name_exchange: WINZ25
+    underlying: { base: WIN, quote: BRL }
+    quote: underlying_quote
+    kind: spot
+    spec: null
+executions:
+  - b3:
+      activation_key: key
+      username: user
+      password: pass
+      transport:
+        profit_dll:
+          dll_path: C:/ProfitDLL.dll
+market_data:
+  - exchange: other
+    instrument: WINZ25
+    kinds: [OrderBooksL1]
+"#;
+        let config = SystemConfig::from_yaml_str(yaml).unwrap();
+
+        let ExecutionConfig::B3(b3) = &config.executions[0] else {
+            panic!("expected B3 ExecutionConfig");
+        };
+        assert_eq!(
+            B3Config::from(b3).dll_path.as_deref(),
+            Some("C:/ProfitDLL.dll")
+        );
+        assert_eq!(config.engine, EngineConfig::default());
+        assert_eq!(config.risk, RiskConfig::default());
+
+        let actual =
+            SystemConfig::from_yaml_str(&yaml.replace("username: user", "username: [user]"))
+                .unwrap_err();
+        assert_eq!(actual.key(), Some("executions[0].b3.username"));
+    }
+
+    #[test]
+    fn test_system_config_errors_point_at_offending_key() {
+        struct TestCase {
+            find: &'static str,
+            replace: &'static str,
+            expected_key: &'static str,
+            expected_parse: bool,
+        }
+
+        let cases = vec![
+            // TC0: invalid Decimal
+            TestCase {
+                find: "tick_size = 0.01",
+                replace: "tick_size = \"abc\"",
+                expected_key: "instruments[0].spec.price.tick_size",
+                expected_parse: true,
+            },
+            // TC1: zero tick size
+            TestCase {
+                find: "tick_size = 0.01",
+                replace: "tick_size = 0",
+                expected_key: "instruments[0].spec.price.tick_size",
+                expected_parse: false,
+            },
+            // TC2: unknown transport
+            TestCase {
+                find: "transport = \"mock\"",
+                replace: "transport = \"carrier_pigeon\"",
+                expected_key: "executions[0].b3.transport",
+                expected_parse: true,
+            },
+            // TC3: empty credential
+            TestCase {
+                find: "username = \"user\"",
+                replace: "username = \"\"",
+                expected_key: "executions[0].b3.username",
+                expected_parse: false,
+            },
+            // TC4: subscription to an instrument that is not configured
+            TestCase {
+                find: "instrument = \"PETR4\"",
+                replace: "instrument = \"VALE3\"",
+                expected_key: "market_data[0].instrument",
+                expected_parse: false,
+            },
+            // TC5: unknown SubKind
+            TestCase {
+                find: "\"OrderBooksL1\"",
+                replace: "\"OrderBooksL9\"",
+                expected_key: "market_data[0].kinds[1]",
+                expected_parse: true,
+            },
+            // TC6: negative kill switch limit
+            TestCase {
+                find: "max_loss = 5000",
+                replace: "max_loss = -5000",
+                expected_key: "risk.kill_switch.limits.max_loss",
+                expected_parse: false,
+            },
+            // TC7: unknown EngineFeedMode
+            TestCase {
+                find: "feed_mode = \"Stream\"",
+                replace: "feed_mode = \"Batch\"",
+                expected_key: "engine.feed_mode",
+                expected_parse: true,
+            },
+            // TC8: risk limits allow an instrument that is not configured
+            TestCase {
+                find: "allowed_instruments = [\"PETR4\"]",
+                replace: "allowed_instruments = [\"PETR4\", \"VALE3\"]",
+                expected_key: "risk.limits.allowed_instruments[1]",
+                expected_parse: false,
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let contents = TOML.replace(test.find, test.replace);
+            let actual = SystemConfig::from_toml_str(&contents).unwrap_err();
+
+            assert_eq!(actual.key(), Some(test.expected_key), "TC{index} failed");
+            assert_eq!(
+                matches!(actual, ConfigError::Parse { .. }),
+                test.expected_parse,
+                "TC{index} failed"
+            );
+        }
+    }
+
+    #[test]
+    fn test_config_format_from_path() {
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("system.toml")),
+            Some(ConfigFormat::Toml)
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("system.YML")),
+            Some(ConfigFormat::Yaml)
+        );
+        assert_eq!(ConfigFormat::from_path(Path::new("system.json")), None);
+        assert!(matches!(
+            SystemConfig::from_path("system.json"),
+            Err(ConfigError::UnsupportedFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_system_config_margin_check() {
+        let table = std::env::temp_dir().join(format!("toucan_margin_{}.toml", std::process::id()));
+        std::fs::write(
+            &table,
+            r#"
+[contracts.WIN]
+initial = 3500
+factor = "IBOV"
+
+[contracts.IBOV]
+initial = 1000
+short_only = true
+
+[[scenarios]]
+name = "IBOV -15%"
+shocks = { IBOV = -0.15 }
+"#,
+        )
+        .unwrap();
+
+        let toml = format!(
+            "{TOML}\n[risk.margin]\ntable = {:?}\ncollateral_asset = \"BRL\"\n",
+            table.display().to_string()
+        );
+        let config = SystemConfig::from_toml_str(&toml).unwrap();
+        let check = config.margin_check().unwrap().unwrap();
+        std::fs::remove_file(&table).unwrap();
+
+        assert_eq!(check.collateral_asset, "BRL");
+        assert_eq!(check.model.contracts["WIN"].initial, dec!(3500));
+        assert!(check.model.contracts["IBOV"].short_only);
+        assert_eq!(check.model.scenarios[0].shocks["IBOV"], dec!(-0.15));
+        assert_eq!(check.contract_sizes["PETR4"], Decimal::ONE);
+
+        let actual = SystemConfig::from_toml_str(&toml.replace("\"BRL\"", "\"\"")).unwrap_err();
+        assert_eq!(actual.key(), Some("risk.margin.collateral_asset"));
+
+        let missing = SystemConfig::from_toml_str(&toml).unwrap();
+        std::fs::remove_file(&table).ok();
+        assert!(matches!(
+            missing.margin_check(),
+            Err(ConfigError::Io { .. })
+        ));
+    }
+}
@@ -8,21 +8,26 @@
 use crate::{
     engine::{
         audit::{context::EngineContext, AuditTick, Auditor},
+        clock::EngineClock,
         command::Command,
-        state::{instrument::filter::InstrumentFilter, trading::TradingState},
+        state::{instrument::filter::InstrumentFilter, trading::TradingState, EngineState},
+        timer::TimerTick,
         Processor,
     },
     execution::builder::ExecutionHandles,
-    shutdown::{AsyncShutdown, Shutdown},
+    shutdown::{shutdown_signal, AsyncShutdown, GracefulShutdown, Shutdown},
 };
-use std::fmt::Debug;
+use rust_decimal::Decimal;
+use std::{fmt::Debug, time::Duration};
 use tokio::task::{JoinError, JoinHandle};
+use toucan_analytics::{summary::TradingSummary, time::TimeInterval};
 use toucan_execution::order::request::{OrderRequestCancel, OrderRequestOpen};
 use toucan_integration::{
     channel::{Tx, UnboundedRx, UnboundedTx},
     collection::one_or_many::OneOrMany,
     snapshot::SnapUpdates,
 };
+use tracing::{info, warn};
 
 /// Provides a `SystemBuilder` for constructing a Toucan trading system, and associated types.
 pub mod builder;
@@ -104,6 +109,7 @@ where
                     mut execution,
                     market_to_engine,
                     account_to_engine,
+                    timer_to_engine,
                 },
             feed_tx,
             audit: _,
@@ -120,6 +126,9 @@ where
         let (engine, shutdown_audit) = engine.await?;
 
         account_to_engine.abort();
+        if let Some(timer_to_engine) = timer_to_engine {
+            timer_to_engine.abort();
+        }
         execution.shutdown().await?;
 
         Ok((engine, shutdown_audit))
@@ -191,6 +200,140 @@ where
     }
 }
 
+/// Interval at which `TimerTick`s are sent to the `Engine` while a [`GracefulShutdown`] drains.
+const SHUTDOWN_TICK_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Additional time after the [`GracefulShutdown`] timeout before a hard [`Shutdown`] is sent.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+
+type TradingEngine<Clock, GlobalData, InstrumentData, ExecutionTxs, Strategy, Risk> =
+    crate::engine::Engine<
+        Clock,
+        EngineState<GlobalData, InstrumentData>,
+        ExecutionTxs,
+        Strategy,
+        Risk,
+    >;
+
+impl<Clock, GlobalData, InstrumentData, ExecutionTxs, Strategy, Risk, Audit, Event>
+    System<TradingEngine<Clock, GlobalData, InstrumentData, ExecutionTxs, Strategy, Risk>, Event>
+where
+    TradingEngine<Clock, GlobalData, InstrumentData, ExecutionTxs, Strategy, Risk>: Processor<Event, Audit = Audit>
+        + Auditor<Audit, Context = EngineContext, Snapshot = EngineState<GlobalData, InstrumentData>>,
+    Clock: EngineClock,
+    Event: Debug + Clone + Send + From<GracefulShutdown> + From<TimerTick> + From<Shutdown>,
+{
+    /// Run a [`GracefulShutdown`] sequence.
+    ///
+    /// 1. Disable trading, cancel all open orders and optionally close all open positions.
+    /// 2. Wait for the open orders (and positions) to be acknowledged or filled, up to the
+    ///    configured timeout. A hard [`Shutdown`] is sent if the `Engine` has not stopped shortly
+    ///    after the timeout.
+    /// 3. Take a final [`EngineState`] checkpoint and generate the [`TradingSummary`].
+    /// 4. Stop the execution managers and event forwarding tasks.
+    pub async fn shutdown_gracefully<Interval>(
+        self,
+        config: GracefulShutdown,
+        risk_free_return: Decimal,
+        interval: Interval,
+    ) -> Result<
+        (
+            TradingEngine<Clock, GlobalData, InstrumentData, ExecutionTxs, Strategy, Risk>,
+            GracefulShutdownReport<EngineState<GlobalData, InstrumentData>, Audit, Interval>,
+        ),
+        JoinError,
+    >
+    where
+        Interval: TimeInterval + Copy,
+    {
+        let Self {
+            engine: mut engine_task,
+            mut handles,
+            feed_tx,
+            audit: _,
+        } = self;
+
+        let _ = feed_tx.send(config);
+
+        // TimerTicks keep the Engine clock (and shutdown deadline) moving when no events arrive
+        let deadline = tokio::time::Instant::now() + config.timeout + SHUTDOWN_GRACE;
+        let mut ticks = tokio::time::interval(SHUTDOWN_TICK_INTERVAL);
+        let mut shutdown_sent = false;
+
+        let (mut engine, shutdown_audit) = loop {
+            tokio::select! {
+                joined = &mut engine_task => break joined?,
+                now = ticks.tick() => {
+                    if now < deadline {
+                        let _ = feed_tx.send(TimerTick);
+                    } else if !shutdown_sent {
+                        warn!("GracefulShutdown did not complete before deadline, sending Shutdown");
+                        shutdown_sent = true;
+                        let _ = feed_tx.send(Shutdown);
+                    }
+                }
+            }
+        };
+
+        let checkpoint = Auditor::<Audit>::audit_snapshot(&mut engine);
+        let summary = engine
+            .trading_summary_generator(risk_free_return)
+            .generate(interval);
+
+        handles.shutdown().await?;
+
+        Ok((
+            engine,
+            GracefulShutdownReport {
+                shutdown_audit,
+                checkpoint,
+                summary,
+            },
+        ))
+    }
+
+    /// Wait for a SIGINT (Ctrl+C) or SIGTERM, then run a [`GracefulShutdown`] sequence.
+    ///
+    /// See [`Self::shutdown_gracefully`].
+    pub async fn shutdown_on_signal<Interval>(
+        self,
+        config: GracefulShutdown,
+        risk_free_return: Decimal,
+        interval: Interval,
+    ) -> Result<
+        (
+            TradingEngine<Clock, GlobalData, InstrumentData, ExecutionTxs, Strategy, Risk>,
+            GracefulShutdownReport<EngineState<GlobalData, InstrumentData>, Audit, Interval>,
+        ),
+        JoinError,
+    >
+    where
+        Interval: TimeInterval + Copy,
+    {
+        match shutdown_signal().await {
+            Ok(signal) => info!(?signal, "received shutdown signal"),
+            Err(error) => warn!(
+                ?error,
+                "failed to listen for shutdown signal, shutting down"
+            ),
+        }
+
+        self.shutdown_gracefully(config, risk_free_return, interval)
+            .await
+    }
+}
+
+/// Result of a [`System::shutdown_gracefully`] sequence.
+#[derive(Debug, Clone)]
+pub struct GracefulShutdownReport<State, Audit, Interval> {
+    /// Audit that caused the `Engine` to stop.
+    pub shutdown_audit: Audit,
+    /// Final `EngineState` checkpoint.
+    pub checkpoint: AuditTick<State>,
+    /// Final trading session summary.
+    pub summary: TradingSummary<Interval>,
+}
+
 /// Collection of task handles for auxiliary system components that support the `Engine`.
 ///
 /// Used by the [`System`] to shut down auxillary components.
@@ -204,6 +347,9 @@ pub struct SystemAuxillaryHandles {
 
     /// Task that forwards account events to the engine.
     pub account_to_engine: JoinHandle<()>,
+
+    /// Optional task that periodically sends `TimerTick`s to the engine.
+    pub timer_to_engine: Option<JoinHandle<()>>,
 }
 
 impl AsyncShutdown for SystemAuxillaryHandles {
@@ -213,6 +359,9 @@ impl AsyncShutdown for SystemAuxillaryHandles {
         // Event -> Engine tasks do not need graceful shutdown, so abort
         self.market_to_engine.abort();
         self.account_to_engine.abort();
+        if let Some(timer_to_engine) = &self.timer_to_engine {
+            timer_to_engine.abort();
+        }
 
         // Await execution components shutdowns concurrently
         self.execution.shutdown().await
@@ -225,6 +374,7 @@ impl SystemAuxillaryHandles {
             .into_iter()
             .chain(std::iter::once(self.market_to_engine))
             .chain(std::iter::once(self.account_to_engine))
+            .chain(self.timer_to_engine)
             .for_each(|handle| handle.abort());
     }
 }
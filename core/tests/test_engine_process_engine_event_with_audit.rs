@@ -253,14 +253,14 @@ fn test_engine_process_engine_event_with_audit() {
     );
 
     // Ensure ExecutionRequests were sent to ExecutionManager
-    assert_eq!(
+    assert!(matches!(
         execution_rx.next().unwrap(),
-        ExecutionRequest::Open(primary_buy_order)
-    );
-    assert_eq!(
+        ExecutionRequest::Open(request, Some(_)) if request == primary_buy_order
+    ));
+    assert!(matches!(
         execution_rx.next().unwrap(),
-        ExecutionRequest::Open(secondary_buy_order)
-    );
+        ExecutionRequest::Open(request, Some(_)) if request == secondary_buy_order
+    ));
 
     // TradingState::Disabled
     let event = EngineEvent::TradingStateUpdate(TradingState::Disabled);
@@ -429,10 +429,10 @@ fn test_engine_process_engine_event_with_audit() {
     );
 
     // Ensure ClosePositions ExecutionRequest was sent to ExecutionManager
-    assert_eq!(
+    assert!(matches!(
         execution_rx.next().unwrap(),
-        ExecutionRequest::Open(primary_sell_order)
-    );
+        ExecutionRequest::Open(request, Some(_)) if request == primary_sell_order
+    ));
 
     // Simulate OpenOrder response for Sequence(15) ClosePositionsCommand primary_sell_order
     let event = account_event_order_response(0, 3, Side::Sell, 20_000.0, 1.0, 1.0);
@@ -563,10 +563,10 @@ fn test_engine_process_engine_event_with_audit() {
     );
 
     // Ensure ExecutionRequest for Sequence(21) Command::SendOpenRequests was sent to ExecutionManager
-    assert_eq!(
+    assert!(matches!(
         execution_rx.next().unwrap(),
-        ExecutionRequest::Open(secondary_sell_order)
-    );
+        ExecutionRequest::Open(request, Some(_)) if request == secondary_sell_order
+    ));
 
     // Simulate LIMIT OpenOrder response for Sequence(21) secondary_sell_order (0/1 quantity filled)
     let event = account_event_order_response(1, 4, Side::Sell, 0.05, 1.0, 0.0);
@@ -635,6 +635,7 @@ fn test_engine_process_engine_event_with_audit() {
         exchange: "mock".to_string(),
         broker: Some("mock-broker".to_string()),
         account: Some("mock-account".to_string()),
+        trace: None,
         kind: AccountEventKind::OrderSnapshot(Snapshot(Order {
             key: OrderKey {
                 exchange: "mock".to_string(),
@@ -987,6 +988,7 @@ fn account_event_snapshot(assets: &AssetStates) -> EngineEvent<DataKind> {
         exchange: "mock".to_string(),
         broker: Some("mock-broker".into()),
         account: Some("mock-account".into()),
+        trace: None,
         kind: AccountEventKind::Snapshot(AccountSnapshot {
             exchange: "mock".to_string(),
             broker: Some("mock-broker".into()),
@@ -1035,6 +1037,7 @@ fn account_event_order_response(
         exchange: "mock".to_string(),
         broker: Some("mock-broker".into()),
         account: Some("mock-account".into()),
+        trace: None,
         kind: AccountEventKind::OrderSnapshot(Snapshot(Order {
             key: OrderKey {
                 exchange: "mock".to_string(),
@@ -1075,6 +1078,7 @@ fn account_event_balance(
         exchange: "mock".to_string(),
         broker: Some("mock-broker".into()),
         account: Some("mock-account".into()),
+        trace: None,
         kind: AccountEventKind::BalanceSnapshot(Snapshot(AssetBalance {
             asset: asset_key(asset).to_string(),
             balance: Balance::new(
@@ -1098,6 +1102,7 @@ fn account_event_trade(
         exchange: "mock".to_string(),
         broker: Some("mock-broker".into()),
         account: Some("mock-account".into()),
+        trace: None,
         kind: AccountEventKind::Trade(Trade {
             id: gen_trade_id(instrument),
             order_id: gen_order_id(instrument),
@@ -4,9 +4,25 @@ use derive_more::Constructor;
 use futures::Stream;
 use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
-use std::{convert, fmt::Debug, future, future::Future};
+use std::{
+    convert,
+    fmt::{Debug, Display},
+    future,
+    future::Future,
+};
 use tracing::{error, info, warn};
-use toucan_integration::channel::Tx;
+use toucan_integration::{
+    channel::Tx,
+    metric::{registry::MetricRegistry, Tag},
+};
+
+/// Counter of [`reconnect::Event::Item`](Event)s yielded by a metered [`ReconnectingStream`],
+/// tagged by `stream`.
+pub const METRIC_STREAM_EVENTS: &str = "toucan_stream_events_total";
+
+/// Counter of [`reconnect::Event::Reconnecting`](Event)s yielded by a metered
+/// [`ReconnectingStream`], tagged by `stream` and `origin`.
+pub const METRIC_STREAM_RECONNECTS: &str = "toucan_stream_reconnects_total";
 
 /// Utilities for handling a continually reconnecting [`Stream`] initialised via the
 /// [`init_reconnecting_stream`] function.
@@ -128,6 +144,35 @@ where
         })
     }
 
+    /// Count every [`reconnect::Event`](Event) in the provided [`MetricRegistry`], using the
+    /// [`METRIC_STREAM_EVENTS`] and [`METRIC_STREAM_RECONNECTS`] counters tagged with the
+    /// `stream` name (eg/ "market", "account").
+    fn with_metrics<Origin, T>(
+        self,
+        registry: &MetricRegistry,
+        stream: &'static str,
+    ) -> impl Stream<Item = Event<Origin, T>>
+    where
+        Self: Stream<Item = Event<Origin, T>>,
+        Origin: Display,
+    {
+        let registry = registry.clone();
+        let events = registry.counter(METRIC_STREAM_EVENTS, [Tag::new("stream", stream)]);
+
+        self.inspect(move |event| match event {
+            Event::Item(_) => events.increment(),
+            Event::Reconnecting(origin) => registry
+                .counter(
+                    METRIC_STREAM_RECONNECTS,
+                    [
+                        Tag::new("stream", stream),
+                        Tag::new("origin", origin.to_string()),
+                    ],
+                )
+                .increment(),
+        })
+    }
+
     /// Future for forwarding items in [`Self`] to the provided channel [`Tx`].
     fn forward_to<Transmitter>(self, tx: Transmitter) -> impl Future<Output = ()> + Send
     where
@@ -205,3 +250,40 @@ impl ReconnectionState {
         tokio::time::sleep(sleep_duration)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_with_metrics() {
+        let registry = MetricRegistry::default();
+        let events = vec![
+            Event::Item(1),
+            Event::Item(2),
+            Event::Reconnecting("b3"),
+            Event::Item(3),
+            Event::Reconnecting("b3"),
+        ];
+
+        let actual = futures::stream::iter(events.clone())
+            .with_metrics(&registry, "market")
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(actual, events);
+
+        let counts = registry
+            .metrics(0)
+            .into_iter()
+            .map(|metric| (metric.name, metric.fields[0].value.clone()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            counts,
+            vec![
+                (METRIC_STREAM_EVENTS, 3u64.into()),
+                (METRIC_STREAM_RECONNECTS, 2u64.into()),
+            ]
+        );
+    }
+}
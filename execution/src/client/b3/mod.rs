@@ -343,6 +343,7 @@ impl B3ExecutionClient {
                                     broker: Some(broker.clone()),
                                     account: Some(account.clone()),
                                     kind: crate::AccountEventKind::OrderSnapshot(snapshot),
+                                    trace: None,
                                 };
                                 let _ = tx.send(event);
                             } else {
@@ -388,6 +389,7 @@ impl B3ExecutionClient {
                                     broker: Some(broker.clone()),
                                     account: Some(account.clone()),
                                     kind: crate::AccountEventKind::Trade(trade),
+                                    trace: None,
                                 };
                                 let _ = tx.send(trade_event);
                                 // Emit updated order snapshot with accumulated filled quantity
@@ -417,6 +419,7 @@ impl B3ExecutionClient {
                                         broker: Some(broker.clone()),
                                         account: Some(account.clone()),
                                         kind: crate::AccountEventKind::OrderSnapshot(snapshot),
+                                        trace: None,
                                     };
                                     let _ = tx.send(snapshot_event);
                                 }
@@ -460,6 +463,7 @@ impl B3ExecutionClient {
                                     broker: Some(broker.clone()),
                                     account: Some(account.clone()),
                                     kind: crate::AccountEventKind::OrderSnapshot(snapshot),
+                                    trace: None,
                                 };
                                 let _ = tx.send(event);
                             } else {
@@ -506,6 +510,7 @@ impl B3ExecutionClient {
                                     broker: Some(broker.clone()),
                                     account: Some(account.clone()),
                                     kind: crate::AccountEventKind::OrderCancelled(response),
+                                    trace: None,
                                 };
                                 let _ = tx.send(event);
                             } else {
@@ -18,8 +18,10 @@ use derive_more::Constructor;
 use futures::stream::BoxStream;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use tokio::sync::{broadcast, mpsc, oneshot};
 use tokio_stream::{wrappers::BroadcastStream, StreamExt};
+use toucan_instrument::instrument::spec::InstrumentSpec;
 use tracing::error;
 
 #[derive(
@@ -30,6 +32,11 @@ pub struct MockExecutionConfig {
     pub initial_state: UnindexedAccountSnapshot,
     pub latency_ms: u64,
     pub fees_percent: Decimal,
+
+    /// [`InstrumentSpec`]s that open orders are validated against, keyed by exchange instrument
+    /// name. Orders for instruments without a spec are not validated.
+    #[serde(default)]
+    pub instrument_specs: BTreeMap<InstrumentNameExchange, InstrumentSpec<AssetNameExchange>>,
 }
 
 #[derive(Debug, Constructor)]
@@ -9,7 +9,7 @@ use crate::{
     },
     order::{
         id::OrderId,
-        request::{OrderRequestCancel, OrderRequestOpen},
+        request::{OrderRequestCancel, OrderRequestOpen, RequestOpen},
         state::{Cancelled, Open},
         Order, OrderKind, UnindexedOrder,
     },
@@ -23,11 +23,11 @@ use futures::stream::BoxStream;
 use itertools::Itertools;
 use rust_decimal::Decimal;
 use smol_str::ToSmolStr;
-use std::fmt::Debug;
+use std::{collections::BTreeMap, fmt::Debug};
 use tokio::sync::{broadcast, mpsc, oneshot};
 use tokio_stream::{wrappers::BroadcastStream, StreamExt};
 use tracing::{error, info};
-use toucan_instrument::{ExchangeId, MarketDataInstrument, Side};
+use toucan_instrument::{instrument::spec::InstrumentSpec, ExchangeId, MarketDataInstrument, Side};
 use toucan_integration::snapshot::Snapshot;
 
 pub mod account;
@@ -41,6 +41,7 @@ pub struct MockExchange {
     pub request_rx: mpsc::UnboundedReceiver<MockExchangeRequest>,
     pub event_tx: broadcast::Sender<UnindexedAccountEvent>,
     pub instruments: FnvHashMap<InstrumentNameExchange, MarketDataInstrument>,
+    pub instrument_specs: BTreeMap<InstrumentNameExchange, InstrumentSpec<AssetNameExchange>>,
     pub account: AccountState,
     pub order_sequence: u64,
     pub time_exchange_latest: DateTime<Utc>,
@@ -60,6 +61,7 @@ impl MockExchange {
             request_rx,
             event_tx,
             instruments,
+            instrument_specs: config.instrument_specs,
             account: AccountState::from(config.initial_state),
             order_sequence: 0,
             time_exchange_latest: Default::default(),
@@ -260,6 +262,10 @@ impl MockExchange {
             return (build_open_order_err_response(request, error), None);
         }
 
+        if let Err(error) = self.validate_order_spec(&request) {
+            return (build_open_order_err_response(request, error), None);
+        }
+
         let underlying = match self.find_instrument_data(&request.key.instrument) {
             Ok(_instrument) => {
                 // TODO: Implementar corretamente para nova arquitetura
@@ -393,6 +399,38 @@ impl MockExchange {
         }
     }
 
+    /// Validate the open request against the configured [`InstrumentSpec`] (if any), rejecting
+    /// orders off the price tick or quantity increment, or below the minimum quantity or
+    /// notional.
+    ///
+    /// Note that the `MockExchange` only simulates spot instruments, so the contract size is one.
+    pub fn validate_order_spec(
+        &self,
+        request: &OrderRequestOpen<ExchangeId, InstrumentNameExchange>,
+    ) -> Result<(), UnindexedOrderError> {
+        let Some(spec) = self.instrument_specs.get(&request.key.instrument) else {
+            return Ok(());
+        };
+
+        let RequestOpen {
+            price,
+            quantity,
+            kind,
+            ..
+        } = &request.state;
+
+        match kind {
+            OrderKind::Market => spec.validate_quantity(*price, *quantity, Decimal::ONE),
+            OrderKind::Limit => spec.validate(*price, *quantity, Decimal::ONE),
+        }
+        .map_err(|error| {
+            UnindexedOrderError::Rejected(ApiError::OrderRejected(format!(
+                "MockExchange rejected order for {}: {error}",
+                request.key.instrument
+            )))
+        })
+    }
+
     pub fn find_instrument_data(
         &self,
         instrument: &InstrumentNameExchange,
@@ -420,6 +458,7 @@ impl MockExchange {
             broker: None,
             account: None,
             kind: kind.into(),
+            trace: None,
         }
     }
 }
@@ -46,6 +46,7 @@ impl AccountEventIndexer {
             kind,
             broker: _,
             account: _,
+            trace,
         } = event;
 
         let exchange = self.map.find_exchange_index(exchange)?;
@@ -71,6 +72,7 @@ impl AccountEventIndexer {
             broker: None,
             account: None,
             kind,
+            trace,
         })
     }
 
@@ -94,6 +94,7 @@ use {serde_json as _, toucan_data as _};
 use crate::{
     balance::AssetBalance,
     order::{request::OrderResponseCancel, Order, OrderSnapshot},
+    trace::OrderTrace,
     trade::Trade,
 };
 use chrono::{DateTime, Utc};
@@ -113,6 +114,7 @@ pub mod exchange;
 pub mod indexer;
 pub mod map;
 pub mod order;
+pub mod trace;
 pub mod trade;
 pub mod transport; // Phase 2: transport abstraction layer (connectivity/protocol)
 
@@ -137,6 +139,11 @@ pub struct AccountEvent<
     pub broker: Option<BrokerId>,
     pub account: Option<AccountId>,
     pub kind: AccountEventKind<ExchangeKey, AssetKey, InstrumentKey>,
+    /// [`OrderTrace`] of the `ExecutionRequest` this event responds to, if any.
+    ///
+    /// Boxed to avoid bloating every `AccountEvent` that is not an order response.
+    #[serde(default)]
+    pub trace: Option<Box<OrderTrace>>,
 }
 
 impl<ExchangeKey, AssetKey, InstrumentKey> AccountEvent<ExchangeKey, AssetKey, InstrumentKey> {
@@ -149,6 +156,15 @@ impl<ExchangeKey, AssetKey, InstrumentKey> AccountEvent<ExchangeKey, AssetKey, I
             broker: None,
             account: None,
             kind: kind.into(),
+            trace: None,
+        }
+    }
+
+    /// Attach the [`OrderTrace`] of the `ExecutionRequest` this event responds to.
+    pub fn with_trace(self, trace: Option<OrderTrace>) -> Self {
+        Self {
+            trace: trace.map(Box::new),
+            ..self
         }
     }
 }
@@ -0,0 +1,227 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// Timestamps recorded as an order travels from the market event that triggered it to the
+/// exchange acknowledgement and fill.
+///
+/// The `Engine` stamps the first stages (market, engine, risk, sent) in its own clock domain, and
+/// the [`OrderTrace`] is then propagated with the `ExecutionRequest` and returned on the
+/// [`AccountEvent`](crate::AccountEvent) responding to it, where the ack and fill stages are
+/// stamped.
+#[derive(
+    Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default, Deserialize, Serialize,
+)]
+pub struct OrderTrace {
+    /// `Engine` `Sequence` of the event that generated the order.
+    pub sequence: u64,
+    /// Exchange time of the market event that triggered the order, if any.
+    pub time_exchange: Option<DateTime<Utc>>,
+    /// Time the market event that triggered the order was received, if any.
+    pub time_received: Option<DateTime<Utc>>,
+    /// Time the `Engine` started processing the triggering event.
+    pub time_engine: DateTime<Utc>,
+    /// Time the order passed the `RiskManager` checks.
+    pub time_risk: Option<DateTime<Utc>>,
+    /// Time the `ExecutionRequest` was sent to the `ExecutionManager`.
+    pub time_sent: Option<DateTime<Utc>>,
+    /// Time the exchange acknowledged the request.
+    pub time_ack: Option<DateTime<Utc>>,
+    /// Time the first fill for the order was processed.
+    pub time_fill: Option<DateTime<Utc>>,
+}
+
+impl OrderTrace {
+    pub fn new(sequence: u64, time_engine: DateTime<Utc>) -> Self {
+        Self {
+            sequence,
+            time_engine,
+            ..Self::default()
+        }
+    }
+
+    /// Attach the exchange & received times of the market event that triggered the order.
+    pub fn with_market_times(
+        self,
+        time_exchange: DateTime<Utc>,
+        time_received: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            time_exchange: Some(time_exchange),
+            time_received: Some(time_received),
+            ..self
+        }
+    }
+
+    /// Time of the "tick" that triggered the order: the market event `time_received` if the
+    /// order was generated from a market event, otherwise the `Engine` processing time.
+    pub fn time_tick(&self) -> DateTime<Utc> {
+        self.time_received.unwrap_or(self.time_engine)
+    }
+
+    /// Stamp the acknowledgement as arriving `elapsed` after the request was sent.
+    ///
+    /// Using the measured round trip keeps the ack in the same clock domain as the stages
+    /// stamped by the `Engine`.
+    pub fn acknowledged(self, elapsed: std::time::Duration) -> Self {
+        let time_ack = self
+            .time_sent
+            .zip(TimeDelta::from_std(elapsed).ok())
+            .map(|(time_sent, elapsed)| time_sent + elapsed);
+
+        Self { time_ack, ..self }
+    }
+
+    /// Latency of the provided [`LatencyStage`], if both of its timestamps have been recorded.
+    ///
+    /// Negative latencies (eg/ due to clock skew between the exchange and local clocks) are
+    /// clamped to zero.
+    pub fn latency(&self, stage: LatencyStage) -> Option<TimeDelta> {
+        let (start, end) = match stage {
+            LatencyStage::ExchangeToReceived => (self.time_exchange?, self.time_received?),
+            LatencyStage::ReceivedToEngine => (self.time_received?, self.time_engine),
+            LatencyStage::EngineToRisk => (self.time_engine, self.time_risk?),
+            LatencyStage::RiskToSent => (self.time_risk?, self.time_sent?),
+            LatencyStage::SentToAck => (self.time_sent?, self.time_ack?),
+            LatencyStage::AckToFill => (self.time_ack?, self.time_fill?),
+            LatencyStage::TickToTrade => (self.time_tick(), self.time_sent?),
+            LatencyStage::TickToAck => (self.time_tick(), self.time_ack?),
+            LatencyStage::TickToFill => (self.time_tick(), self.time_fill?),
+        };
+
+        Some((end - start).max(TimeDelta::zero()))
+    }
+}
+
+/// Stage of an order's lifecycle measured by an [`OrderTrace`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub enum LatencyStage {
+    /// Market event `time_exchange` → `time_received` (market data feed latency).
+    ExchangeToReceived,
+    /// Market event `time_received` → `Engine` processing (feed queueing).
+    ReceivedToEngine,
+    /// `Engine` processing → `RiskManager` checks passed (strategy & risk).
+    EngineToRisk,
+    /// `RiskManager` checks passed → `ExecutionRequest` sent.
+    RiskToSent,
+    /// `ExecutionRequest` sent → exchange acknowledgement.
+    SentToAck,
+    /// Exchange acknowledgement → first fill.
+    AckToFill,
+    /// Tick → `ExecutionRequest` sent.
+    TickToTrade,
+    /// Tick → exchange acknowledgement.
+    TickToAck,
+    /// Tick → first fill.
+    TickToFill,
+}
+
+impl LatencyStage {
+    pub const ALL: [Self; 9] = [
+        Self::ExchangeToReceived,
+        Self::ReceivedToEngine,
+        Self::EngineToRisk,
+        Self::RiskToSent,
+        Self::SentToAck,
+        Self::AckToFill,
+        Self::TickToTrade,
+        Self::TickToAck,
+        Self::TickToFill,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ExchangeToReceived => "exchange_to_received",
+            Self::ReceivedToEngine => "received_to_engine",
+            Self::EngineToRisk => "engine_to_risk",
+            Self::RiskToSent => "risk_to_sent",
+            Self::SentToAck => "sent_to_ack",
+            Self::AckToFill => "ack_to_fill",
+            Self::TickToTrade => "tick_to_trade",
+            Self::TickToAck => "tick_to_ack",
+            Self::TickToFill => "tick_to_fill",
+        }
+    }
+}
+
+impl Display for LatencyStage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(micros: i64) -> DateTime<Utc> {
+        DateTime::<Utc>::MIN_UTC + TimeDelta::microseconds(micros)
+    }
+
+    #[test]
+    fn test_order_trace_latency() {
+        let trace = OrderTrace {
+            sequence: 1,
+            time_exchange: Some(time(0)),
+            time_received: Some(time(100)),
+            time_engine: time(150),
+            time_risk: Some(time(160)),
+            time_sent: Some(time(175)),
+            time_ack: None,
+            time_fill: None,
+        }
+        .acknowledged(std::time::Duration::from_micros(1000));
+
+        struct TestCase {
+            stage: LatencyStage,
+            expected: Option<TimeDelta>,
+        }
+
+        let cases = vec![
+            // TC0: market feed latency
+            TestCase {
+                stage: LatencyStage::ExchangeToReceived,
+                expected: Some(TimeDelta::microseconds(100)),
+            },
+            // TC1: strategy & risk latency
+            TestCase {
+                stage: LatencyStage::EngineToRisk,
+                expected: Some(TimeDelta::microseconds(10)),
+            },
+            // TC2: ack stamped from the measured round trip
+            TestCase {
+                stage: LatencyStage::SentToAck,
+                expected: Some(TimeDelta::microseconds(1000)),
+            },
+            // TC3: tick-to-trade measured from time_received
+            TestCase {
+                stage: LatencyStage::TickToTrade,
+                expected: Some(TimeDelta::microseconds(75)),
+            },
+            // TC4: fill not yet recorded
+            TestCase {
+                stage: LatencyStage::TickToFill,
+                expected: None,
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let actual = trace.latency(test.stage);
+            assert_eq!(actual, test.expected, "TC{index} failed");
+        }
+    }
+
+    #[test]
+    fn test_order_trace_latency_clamps_negative() {
+        let trace = OrderTrace {
+            time_exchange: Some(time(500)),
+            time_received: Some(time(100)),
+            ..OrderTrace::default()
+        };
+
+        assert_eq!(
+            trace.latency(LatencyStage::ExchangeToReceived),
+            Some(TimeDelta::zero())
+        );
+    }
+}
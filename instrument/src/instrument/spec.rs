@@ -1,8 +1,10 @@
 
 
+use crate::Side;
 use derive_more::Constructor;
-use rust_decimal::Decimal;
+use rust_decimal::{Decimal, RoundingStrategy};
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 #[derive(
     Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize, Constructor,
@@ -13,6 +15,56 @@ pub struct InstrumentSpec<AssetKey> {
     pub notional: InstrumentSpecNotional,
 }
 
+impl<AssetKey> InstrumentSpec<AssetKey> {
+    /// Validate an order price and quantity (expressed in the [`OrderQuantityUnits`] of this
+    /// spec) without modifying them.
+    ///
+    /// The `contract_size` is used to calculate the notional value of `Contract` quantities
+    /// (see [`InstrumentKind::contract_size`](super::kind::InstrumentKind::contract_size)).
+    pub fn validate(
+        &self,
+        price: Decimal,
+        quantity: Decimal,
+        contract_size: Decimal,
+    ) -> Result<(), InstrumentSpecError> {
+        self.price.validate(price)?;
+        self.validate_quantity(price, quantity, contract_size)
+    }
+
+    /// Validate an order quantity (expressed in the [`OrderQuantityUnits`] of this spec) against
+    /// the quantity increment, minimum quantity and minimum notional, without validating the
+    /// price tick (eg/ for market orders).
+    pub fn validate_quantity(
+        &self,
+        price: Decimal,
+        quantity: Decimal,
+        contract_size: Decimal,
+    ) -> Result<(), InstrumentSpecError> {
+        self.quantity.validate(quantity)?;
+        self.notional
+            .validate(self.quantity.unit.notional(price, quantity, contract_size))
+    }
+
+    /// Round the order price to the tick size & the order quantity to the quantity increment
+    /// using the provided [`SpecRounding`], then validate the minimum price, quantity and
+    /// notional.
+    ///
+    /// Returns the normalised `(price, quantity)`.
+    pub fn normalise(
+        &self,
+        side: Side,
+        price: Decimal,
+        quantity: Decimal,
+        contract_size: Decimal,
+        rounding: SpecRounding,
+    ) -> Result<(Decimal, Decimal), InstrumentSpecError> {
+        let price = self.price.round(price, side, rounding.price);
+        let quantity = self.quantity.round(quantity, rounding.quantity);
+        self.validate(price, quantity, contract_size)?;
+        Ok((price, quantity))
+    }
+}
+
 #[derive(
     Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize, Constructor,
 )]
@@ -21,6 +73,32 @@ pub struct InstrumentSpecPrice {
     pub tick_size: Decimal,
 }
 
+impl InstrumentSpecPrice {
+    /// Round the price to the tick size using the provided [`RoundingPolicy`].
+    ///
+    /// A non-positive tick size leaves the price unchanged.
+    pub fn round(&self, price: Decimal, side: Side, policy: RoundingPolicy) -> Decimal {
+        round_to_step(price, self.tick_size, policy.strategy(side))
+    }
+
+    /// Validate the price is a multiple of the tick size and not below the minimum price.
+    pub fn validate(&self, price: Decimal) -> Result<(), InstrumentSpecError> {
+        if !is_multiple_of(price, self.tick_size) {
+            return Err(InstrumentSpecError::PriceTick {
+                price,
+                tick_size: self.tick_size,
+            });
+        }
+        if price < self.min {
+            return Err(InstrumentSpecError::PriceBelowMin {
+                price,
+                min: self.min,
+            });
+        }
+        Ok(())
+    }
+}
+
 #[derive(
     Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize, Constructor,
 )]
@@ -30,6 +108,40 @@ pub struct InstrumentSpecQuantity<AssetKey> {
     pub increment: Decimal,
 }
 
+impl<AssetKey> InstrumentSpecQuantity<AssetKey> {
+    /// Round the absolute quantity to the quantity increment using the provided
+    /// [`RoundingPolicy`] (`Passive` rounds down).
+    ///
+    /// A non-positive increment leaves the quantity unchanged.
+    pub fn round(&self, quantity: Decimal, policy: RoundingPolicy) -> Decimal {
+        round_to_step(quantity.abs(), self.increment, policy.strategy(Side::Buy))
+    }
+
+    /// Validate the absolute quantity is a multiple of the quantity increment and not below the
+    /// minimum quantity.
+    pub fn validate(&self, quantity: Decimal) -> Result<(), InstrumentSpecError> {
+        let quantity = quantity.abs();
+        if !is_multiple_of(quantity, self.increment) {
+            return Err(InstrumentSpecError::QuantityIncrement {
+                quantity,
+                increment: self.increment,
+            });
+        }
+        if quantity < self.min || quantity.is_zero() {
+            return Err(InstrumentSpecError::QuantityBelowMin {
+                quantity,
+                min: self.min,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Units an order quantity is expressed in.
+///
+/// - `Asset`: Quantity of the provided asset (eg/ shares of PETR4).
+/// - `Contract`: Number of contracts, each representing `contract_size` of the underlying asset.
+/// - `Quote`: Notional value in the quote asset.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
 pub enum OrderQuantityUnits<AssetKey> {
     Asset(AssetKey),
@@ -37,9 +149,315 @@ pub enum OrderQuantityUnits<AssetKey> {
     Quote,
 }
 
+impl<AssetKey> OrderQuantityUnits<AssetKey> {
+    /// Notional value in the quote asset of an absolute quantity expressed in these units.
+    pub fn notional(&self, price: Decimal, quantity: Decimal, contract_size: Decimal) -> Decimal {
+        let quantity = quantity.abs();
+        match self {
+            Self::Asset(_) => quantity * price,
+            Self::Contract => quantity * contract_size * price,
+            Self::Quote => quantity,
+        }
+    }
+
+    /// Convert a quantity expressed in these units into the `target` units, using the provided
+    /// price and contract size.
+    ///
+    /// Conversions between two different `Asset` units are not supported.
+    pub fn convert(
+        &self,
+        quantity: Decimal,
+        target: &Self,
+        price: Decimal,
+        contract_size: Decimal,
+    ) -> Result<Decimal, InstrumentSpecError>
+    where
+        AssetKey: PartialEq,
+    {
+        // Convert into quantity of the underlying asset first
+        let asset = match self {
+            Self::Asset(_) => quantity,
+            Self::Contract => quantity * contract_size,
+            Self::Quote => quantity
+                .checked_div(price)
+                .ok_or(InstrumentSpecError::ConversionPrice(price))?,
+        };
+
+        match (self, target) {
+            (Self::Asset(from), Self::Asset(to)) if from != to => {
+                Err(InstrumentSpecError::ConversionAsset)
+            }
+            (_, Self::Asset(_)) => Ok(asset),
+            (_, Self::Contract) => asset
+                .checked_div(contract_size)
+                .ok_or(InstrumentSpecError::ConversionContractSize(contract_size)),
+            (_, Self::Quote) => Ok(asset * price),
+        }
+    }
+}
+
 #[derive(
     Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize, Constructor,
 )]
 pub struct InstrumentSpecNotional {
     pub min: Decimal,
 }
+
+impl InstrumentSpecNotional {
+    /// Validate the notional value is not below the minimum notional.
+    pub fn validate(&self, notional: Decimal) -> Result<(), InstrumentSpecError> {
+        if notional < self.min {
+            Err(InstrumentSpecError::NotionalBelowMin {
+                notional,
+                min: self.min,
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Explicit [`RoundingPolicy`]s used by [`InstrumentSpec::normalise`] for order prices and
+/// quantities.
+///
+/// Defaults to `Passive` prices and `Down` quantities, so a normalised order is never more
+/// aggressive or larger than the original.
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize, Constructor,
+)]
+pub struct SpecRounding {
+    pub price: RoundingPolicy,
+    pub quantity: RoundingPolicy,
+}
+
+impl Default for SpecRounding {
+    fn default() -> Self {
+        Self {
+            price: RoundingPolicy::Passive,
+            quantity: RoundingPolicy::Down,
+        }
+    }
+}
+
+/// Policy used to round a value to a tick size or quantity increment.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub enum RoundingPolicy {
+    /// Round to the nearest step, with midpoints rounded away from zero.
+    Nearest,
+    /// Round down to the previous step.
+    Down,
+    /// Round up to the next step.
+    Up,
+    /// Round away from the market: buy prices down and sell prices up.
+    Passive,
+}
+
+impl RoundingPolicy {
+    fn strategy(self, side: Side) -> RoundingStrategy {
+        match (self, side) {
+            (Self::Nearest, _) => RoundingStrategy::MidpointAwayFromZero,
+            (Self::Down, _) | (Self::Passive, Side::Buy) => RoundingStrategy::ToNegativeInfinity,
+            (Self::Up, _) | (Self::Passive, Side::Sell) => RoundingStrategy::ToPositiveInfinity,
+        }
+    }
+}
+
+/// Reason an order does not satisfy an [`InstrumentSpec`].
+#[derive(
+    Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize, Error,
+)]
+pub enum InstrumentSpecError {
+    #[error("price {price} is not a multiple of tick size {tick_size}")]
+    PriceTick { price: Decimal, tick_size: Decimal },
+
+    #[error("price {price} is below minimum price {min}")]
+    PriceBelowMin { price: Decimal, min: Decimal },
+
+    #[error("quantity {quantity} is not a multiple of increment {increment}")]
+    QuantityIncrement {
+        quantity: Decimal,
+        increment: Decimal,
+    },
+
+    #[error("quantity {quantity} is below minimum quantity {min}")]
+    QuantityBelowMin { quantity: Decimal, min: Decimal },
+
+    #[error("notional {notional} is below minimum notional {min}")]
+    NotionalBelowMin { notional: Decimal, min: Decimal },
+
+    #[error("cannot convert quantity units with price {0}")]
+    ConversionPrice(Decimal),
+
+    #[error("cannot convert quantity units with contract size {0}")]
+    ConversionContractSize(Decimal),
+
+    #[error("cannot convert quantity between different assets")]
+    ConversionAsset,
+}
+
+fn round_to_step(value: Decimal, step: Decimal, strategy: RoundingStrategy) -> Decimal {
+    if step <= Decimal::ZERO {
+        return value;
+    }
+    (value / step).round_dp_with_strategy(0, strategy) * step
+}
+
+fn is_multiple_of(value: Decimal, step: Decimal) -> bool {
+    step <= Decimal::ZERO || (value % step).is_zero()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn spec() -> InstrumentSpec<&'static str> {
+        InstrumentSpec::new(
+            InstrumentSpecPrice::new(dec!(0.01), dec!(0.01)),
+            InstrumentSpecQuantity::new(OrderQuantityUnits::Asset("PETR4"), dec!(100), dec!(100)),
+            InstrumentSpecNotional::new(dec!(1000)),
+        )
+    }
+
+    #[test]
+    fn test_instrument_spec_normalise() {
+        struct TestCase {
+            side: Side,
+            price: Decimal,
+            quantity: Decimal,
+            rounding: SpecRounding,
+            expected: Result<(Decimal, Decimal), InstrumentSpecError>,
+        }
+
+        let cases = vec![
+            // TC0: passive buy rounds price down & quantity down
+            TestCase {
+                side: Side::Buy,
+                price: dec!(37.456),
+                quantity: dec!(250),
+                rounding: SpecRounding::default(),
+                expected: Ok((dec!(37.45), dec!(200))),
+            },
+            // TC1: passive sell rounds price up
+            TestCase {
+                side: Side::Sell,
+                price: dec!(37.451),
+                quantity: dec!(300),
+                rounding: SpecRounding::default(),
+                expected: Ok((dec!(37.46), dec!(300))),
+            },
+            // TC2: nearest rounding of price & quantity
+            TestCase {
+                side: Side::Buy,
+                price: dec!(37.455),
+                quantity: dec!(150),
+                rounding: SpecRounding::new(RoundingPolicy::Nearest, RoundingPolicy::Nearest),
+                expected: Ok((dec!(37.46), dec!(200))),
+            },
+            // TC3: quantity rounded down below minimum is rejected
+            TestCase {
+                side: Side::Buy,
+                price: dec!(37.45),
+                quantity: dec!(99),
+                rounding: SpecRounding::default(),
+                expected: Err(InstrumentSpecError::QuantityBelowMin {
+                    quantity: dec!(0),
+                    min: dec!(100),
+                }),
+            },
+            // TC4: notional below minimum is rejected
+            TestCase {
+                side: Side::Sell,
+                price: dec!(5),
+                quantity: dec!(100),
+                rounding: SpecRounding::default(),
+                expected: Err(InstrumentSpecError::NotionalBelowMin {
+                    notional: dec!(500),
+                    min: dec!(1000),
+                }),
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let actual = spec().normalise(
+                test.side,
+                test.price,
+                test.quantity,
+                Decimal::ONE,
+                test.rounding,
+            );
+            assert_eq!(actual, test.expected, "TC{index} failed");
+        }
+    }
+
+    #[test]
+    fn test_instrument_spec_validate() {
+        assert_eq!(
+            spec().validate(dec!(37.45), dec!(200), Decimal::ONE),
+            Ok(())
+        );
+        assert_eq!(
+            spec().validate(dec!(37.455), dec!(200), Decimal::ONE),
+            Err(InstrumentSpecError::PriceTick {
+                price: dec!(37.455),
+                tick_size: dec!(0.01),
+            })
+        );
+        assert_eq!(
+            spec().validate(dec!(37.45), dec!(250), Decimal::ONE),
+            Err(InstrumentSpecError::QuantityIncrement {
+                quantity: dec!(250),
+                increment: dec!(100),
+            })
+        );
+    }
+
+    #[test]
+    fn test_order_quantity_units_convert() {
+        struct TestCase {
+            from: OrderQuantityUnits<&'static str>,
+            to: OrderQuantityUnits<&'static str>,
+            quantity: Decimal,
+            expected: Result<Decimal, InstrumentSpecError>,
+        }
+
+        // price 50, contract size 10
+        let cases = vec![
+            // TC0: contracts to asset
+            TestCase {
+                from: OrderQuantityUnits::Contract,
+                to: OrderQuantityUnits::Asset("WIN"),
+                quantity: dec!(3),
+                expected: Ok(dec!(30)),
+            },
+            // TC1: quote to contracts
+            TestCase {
+                from: OrderQuantityUnits::Quote,
+                to: OrderQuantityUnits::Contract,
+                quantity: dec!(1000),
+                expected: Ok(dec!(2)),
+            },
+            // TC2: asset to quote
+            TestCase {
+                from: OrderQuantityUnits::Asset("WIN"),
+                to: OrderQuantityUnits::Quote,
+                quantity: dec!(4),
+                expected: Ok(dec!(200)),
+            },
+            // TC3: different assets are not convertible
+            TestCase {
+                from: OrderQuantityUnits::Asset("WIN"),
+                to: OrderQuantityUnits::Asset("IND"),
+                quantity: dec!(4),
+                expected: Err(InstrumentSpecError::ConversionAsset),
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let actual = test
+                .from
+                .convert(test.quantity, &test.to, dec!(50), dec!(10));
+            assert_eq!(actual, test.expected, "TC{index} failed");
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use super::{registry::MetricRegistry, Metric, Value};
+use crate::error::SocketError;
+use reqwest::header::{AUTHORIZATION, CONTENT_TYPE};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::Write,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tracing::{info, warn};
+
+/// InfluxDB v2 write API configuration.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+pub struct InfluxConfig {
+    /// Base Url of the InfluxDB server (eg/ "http://localhost:8086").
+    pub url: String,
+    pub org: String,
+    pub bucket: String,
+    pub token: String,
+}
+
+/// Writes [`Metric`]s to InfluxDB using the line protocol with millisecond precision.
+#[derive(Debug, Clone)]
+pub struct InfluxWriter {
+    client: reqwest::Client,
+    url: url::Url,
+    token: String,
+}
+
+impl InfluxWriter {
+    pub fn new(config: InfluxConfig) -> Result<Self, SocketError> {
+        let mut url = url::Url::parse(&config.url)?.join("api/v2/write")?;
+        url.query_pairs_mut()
+            .append_pair("org", &config.org)
+            .append_pair("bucket", &config.bucket)
+            .append_pair("precision", "ms");
+
+        Ok(Self {
+            client: reqwest::Client::new(),
+            url,
+            token: config.token,
+        })
+    }
+
+    /// Write a batch of [`Metric`]s.
+    pub async fn write(&self, metrics: &[Metric]) -> Result<(), SocketError> {
+        if metrics.is_empty() {
+            return Ok(());
+        }
+
+        let body = metrics
+            .iter()
+            .map(encode_line)
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let response = self
+            .client
+            .post(self.url.clone())
+            .header(AUTHORIZATION, format!("Token {}", self.token))
+            .header(CONTENT_TYPE, "text/plain; charset=utf-8")
+            .body(body)
+            .send()
+            .await?;
+
+        let status = response.status();
+        if status.is_success() {
+            Ok(())
+        } else {
+            Err(SocketError::HttpResponse(
+                status,
+                response.text().await.unwrap_or_default(),
+            ))
+        }
+    }
+
+    /// Periodically write a snapshot of every series in the [`MetricRegistry`].
+    ///
+    /// Failed writes are logged and retried on the next interval. Runs forever.
+    pub async fn run(self, registry: MetricRegistry, interval: Duration) {
+        info!(url = %self.url, ?interval, "InfluxWriter running");
+
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            ticker.tick().await;
+
+            let time = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_millis() as u64)
+                .unwrap_or_default();
+
+            if let Err(error) = self.write(&registry.metrics(time)).await {
+                warn!(?error, "InfluxWriter failed to write metrics");
+            }
+        }
+    }
+}
+
+/// Encode a [`Metric`] using the InfluxDB line protocol, with a millisecond timestamp.
+///
+/// eg/ `measurement,tag_key=tag_value field_key=1i 1700000000000`
+pub fn encode_line(metric: &Metric) -> String {
+    let mut line = escape(metric.name, &[',', ' ']);
+
+    for tag in &metric.tags {
+        let _ = write!(
+            line,
+            ",{}={}",
+            escape(tag.key, &[',', '=', ' ']),
+            escape(&tag.value, &[',', '=', ' '])
+        );
+    }
+
+    let fields = metric
+        .fields
+        .iter()
+        .map(|field| {
+            let value = match &field.value {
+                Value::Float(value) => value.to_string(),
+                Value::Int(value) => format!("{value}i"),
+                Value::UInt(value) => format!("{value}u"),
+                Value::Bool(value) => value.to_string(),
+                Value::String(value) => format!("\"{}\"", escape(value, &['"'])),
+            };
+            format!("{}={value}", escape(field.key, &[',', '=', ' ']))
+        })
+        .collect::<Vec<_>>();
+
+    let _ = write!(line, " {} {}", fields.join(","), metric.time);
+    line
+}
+
+/// Backslash escape the provided special characters (and backslashes).
+fn escape(input: &str, special: &[char]) -> String {
+    let mut output = String::with_capacity(input.len());
+    for char in input.chars() {
+        if char == '\\' || special.contains(&char) {
+            output.push('\\');
+        }
+        output.push(char);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::{Field, Tag};
+
+    #[test]
+    fn test_encode_line() {
+        struct TestCase {
+            input: Metric,
+            expected: &'static str,
+        }
+
+        let cases = vec![
+            // TC0: fields of every value type
+            TestCase {
+                input: Metric {
+                    name: "engine",
+                    time: 1700000000000,
+                    tags: vec![],
+                    fields: vec![
+                        Field::new("pnl", -1.5),
+                        Field::new("delta", -2i64),
+                        Field::new("events", 10u64),
+                        Field::new("enabled", true),
+                        Field::new("state", "Enabled".to_string()),
+                    ],
+                },
+                expected: "engine pnl=-1.5,delta=-2i,events=10u,enabled=true,state=\"Enabled\" 1700000000000",
+            },
+            // TC1: tags and special characters are escaped
+            TestCase {
+                input: Metric {
+                    name: "stream events",
+                    time: 1,
+                    tags: vec![
+                        Tag::new("exchange", "b3"),
+                        Tag::new("stream", "market,l1=x y"),
+                    ],
+                    fields: vec![Field::new("note", "say \"hi\"".to_string())],
+                },
+                expected: r#"stream\ events,exchange=b3,stream=market\,l1\=x\ y note="say \"hi\"" 1"#,
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let actual = encode_line(&test.input);
+            assert_eq!(actual, test.expected, "TC{index} failed");
+        }
+    }
+
+    #[test]
+    fn test_influx_writer_url() {
+        let writer = InfluxWriter::new(InfluxConfig {
+            url: "http://localhost:8086".to_string(),
+            org: "toucan".to_string(),
+            bucket: "live metrics".to_string(),
+            token: "token".to_string(),
+        })
+        .unwrap();
+
+        assert_eq!(
+            writer.url.as_str(),
+            "http://localhost:8086/api/v2/write?org=toucan&bucket=live+metrics&precision=ms"
+        );
+    }
+}
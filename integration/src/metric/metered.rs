@@ -0,0 +1,86 @@
+use super::registry::{Counter, Gauge};
+use crate::channel::UnboundedRx;
+use futures::Stream;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// [`UnboundedRx`] wrapper that counts received items and records the remaining queue depth on
+/// every receive.
+///
+/// Implements both [`Iterator`] and [`Stream`], so it can feed either the synchronous or
+/// asynchronous `Engine` run loops.
+#[derive(Debug)]
+pub struct MeteredRx<T> {
+    pub rx: UnboundedRx<T>,
+    pub received: Counter,
+    pub depth: Gauge,
+}
+
+impl<T> MeteredRx<T> {
+    pub fn new(rx: UnboundedRx<T>, received: Counter, depth: Gauge) -> Self {
+        Self {
+            rx,
+            received,
+            depth,
+        }
+    }
+
+    fn record(&self, item: &Option<T>) {
+        if item.is_some() {
+            self.received.increment();
+        }
+        self.depth.set(self.rx.rx.len() as f64);
+    }
+}
+
+impl<T> Iterator for MeteredRx<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = Iterator::next(&mut self.rx);
+        self.record(&item);
+        item
+    }
+}
+
+impl<T> Stream for MeteredRx<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let poll = self.rx.rx.poll_recv(cx);
+        if let Poll::Ready(item) = &poll {
+            self.record(item);
+        }
+        poll
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{channel::mpsc_unbounded, metric::registry::MetricRegistry, metric::Tag};
+
+    #[test]
+    fn test_metered_rx() {
+        let registry = MetricRegistry::default();
+        let (tx, rx) = mpsc_unbounded();
+        let mut rx = MeteredRx::new(
+            rx,
+            registry.counter("received", Vec::<Tag>::new()),
+            registry.gauge("depth", Vec::<Tag>::new()),
+        );
+
+        tx.tx.send(1).unwrap();
+        tx.tx.send(2).unwrap();
+        tx.tx.send(3).unwrap();
+
+        assert_eq!(rx.next(), Some(1));
+        assert_eq!((rx.received.value(), rx.depth.value()), (1, 2.0));
+
+        drop(tx);
+        assert_eq!(rx.by_ref().collect::<Vec<_>>(), vec![2, 3]);
+        assert_eq!((rx.received.value(), rx.depth.value()), (3, 0.0));
+    }
+}
@@ -1,6 +1,18 @@
 
 use serde::{Deserialize, Serialize};
 
+/// Thread-safe [`MetricRegistry`](registry::MetricRegistry) of counters, gauges & histograms.
+pub mod registry;
+
+/// Prometheus text format encoding and `/metrics` scrape endpoint.
+pub mod prometheus;
+
+/// InfluxDB line protocol encoding and writer.
+pub mod influx;
+
+/// Channel receivers instrumented with throughput & queue depth metrics.
+pub mod metered;
+
 #[derive(Debug, Clone, PartialOrd, PartialEq, Serialize)]
 pub struct Metric {
     /// Metric name.
@@ -0,0 +1,290 @@
+use super::{
+    registry::{MetricRegistry, Series},
+    Tag,
+};
+use std::fmt::Write;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+use tracing::{debug, info};
+
+/// `Content-Type` of the Prometheus text exposition format.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Path the Prometheus scrape endpoint is served on.
+pub const METRICS_PATH: &str = "/metrics";
+
+/// Maximum size of a scrape request head.
+const MAX_REQUEST_HEAD_BYTES: usize = 8192;
+
+/// Encode every series in the [`MetricRegistry`] using the Prometheus text exposition format.
+pub fn encode(registry: &MetricRegistry) -> String {
+    let mut output = String::new();
+    let mut current_name = None;
+
+    for (key, series) in registry.series() {
+        let name = sanitise_name(key.name);
+
+        if current_name.as_deref() != Some(name.as_str()) {
+            let _ = writeln!(output, "# TYPE {name} {}", series.kind());
+            current_name = Some(name.clone());
+        }
+
+        match series {
+            Series::Counter(counter) => {
+                write_sample(&mut output, &name, &key.tags, None, counter.value() as f64)
+            }
+            Series::Gauge(gauge) => {
+                write_sample(&mut output, &name, &key.tags, None, gauge.value())
+            }
+            Series::Histogram(histogram) => {
+                let snapshot = histogram.snapshot();
+                let bucket_name = format!("{name}_bucket");
+
+                for (bound, count) in snapshot.buckets {
+                    write_sample(
+                        &mut output,
+                        &bucket_name,
+                        &key.tags,
+                        Some(&format_value(bound)),
+                        count as f64,
+                    );
+                }
+                write_sample(
+                    &mut output,
+                    &bucket_name,
+                    &key.tags,
+                    Some("+Inf"),
+                    snapshot.count as f64,
+                );
+                write_sample(
+                    &mut output,
+                    &format!("{name}_sum"),
+                    &key.tags,
+                    None,
+                    snapshot.sum,
+                );
+                write_sample(
+                    &mut output,
+                    &format!("{name}_count"),
+                    &key.tags,
+                    None,
+                    snapshot.count as f64,
+                );
+            }
+        }
+    }
+
+    output
+}
+
+fn write_sample(output: &mut String, name: &str, tags: &[Tag], le: Option<&str>, value: f64) {
+    output.push_str(name);
+
+    let labels = tags
+        .iter()
+        .map(|tag| (sanitise_label(tag.key), tag.value.as_str()))
+        .chain(le.map(|le| ("le".to_string(), le)))
+        .map(|(key, value)| format!("{key}=\"{}\"", escape_label_value(value)))
+        .collect::<Vec<_>>();
+
+    if !labels.is_empty() {
+        let _ = write!(output, "{{{}}}", labels.join(","));
+    }
+
+    let _ = writeln!(output, " {}", format_value(value));
+}
+
+/// Replace characters not permitted in a Prometheus metric name (`[a-zA-Z_:][a-zA-Z0-9_:]*`).
+fn sanitise_name(name: &str) -> String {
+    sanitise(name, |char| {
+        char.is_ascii_alphanumeric() || char == '_' || char == ':'
+    })
+}
+
+/// Replace characters not permitted in a Prometheus label name (`[a-zA-Z_][a-zA-Z0-9_]*`).
+fn sanitise_label(label: &str) -> String {
+    sanitise(label, |char| char.is_ascii_alphanumeric() || char == '_')
+}
+
+fn sanitise(input: &str, is_valid: impl Fn(char) -> bool) -> String {
+    let mut output = input
+        .chars()
+        .map(|char| if is_valid(char) { char } else { '_' })
+        .collect::<String>();
+
+    if output.is_empty() || output.starts_with(|char: char| char.is_ascii_digit()) {
+        output.insert(0, '_');
+    }
+
+    output
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', r#"\""#)
+        .replace('\n', r"\n")
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value == f64::INFINITY {
+        "+Inf".to_string()
+    } else if value == f64::NEG_INFINITY {
+        "-Inf".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Serve the [`MetricRegistry`] for Prometheus scraping via `GET /metrics` on the provided
+/// [`TcpListener`].
+///
+/// This is a minimal HTTP/1.1 responder that closes every connection after responding, which
+/// is sufficient for Prometheus scrapers. Runs until the [`TcpListener`] fails.
+pub async fn serve(listener: TcpListener, registry: MetricRegistry) -> std::io::Result<()> {
+    info!(addr = ?listener.local_addr(), "Prometheus metrics endpoint listening");
+
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let registry = registry.clone();
+
+        tokio::spawn(async move {
+            if let Err(error) = respond(stream, &registry).await {
+                debug!(%peer, ?error, "Prometheus metrics endpoint failed to respond");
+            }
+        });
+    }
+}
+
+async fn respond(mut stream: TcpStream, registry: &MetricRegistry) -> std::io::Result<()> {
+    let mut head = Vec::with_capacity(1024);
+    let mut buffer = [0u8; 1024];
+
+    while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+        let read = stream.read(&mut buffer).await?;
+        if read == 0 || head.len() + read > MAX_REQUEST_HEAD_BYTES {
+            break;
+        }
+        head.extend_from_slice(&buffer[..read]);
+    }
+
+    let request_line = String::from_utf8_lossy(&head);
+    let mut parts = request_line.split_whitespace();
+
+    let (status, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some(path)) if path.split('?').next() == Some(METRICS_PATH) => {
+            ("200 OK", encode(registry))
+        }
+        (Some("GET"), Some(_)) => ("404 Not Found", String::new()),
+        _ => ("405 Method Not Allowed", String::new()),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {CONTENT_TYPE}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::registry::MetricRegistry;
+
+    #[test]
+    fn test_encode() {
+        struct TestCase {
+            registry: MetricRegistry,
+            expected: &'static str,
+        }
+
+        let cases = vec![
+            // TC0: empty registry
+            TestCase {
+                registry: MetricRegistry::default(),
+                expected: "",
+            },
+            // TC1: counter series sharing a name share a TYPE line
+            TestCase {
+                registry: {
+                    let registry = MetricRegistry::default();
+                    registry
+                        .counter("events_total", [("stream", "market")])
+                        .add(3);
+                    registry
+                        .counter("events_total", [("stream", "account")])
+                        .add(1);
+                    registry
+                },
+                expected: "# TYPE events_total counter\n\
+                    events_total{stream=\"account\"} 1\n\
+                    events_total{stream=\"market\"} 3\n",
+            },
+            // TC2: gauge with invalid name characters and escaped label value
+            TestCase {
+                registry: {
+                    let registry = MetricRegistry::default();
+                    registry
+                        .gauge("pnl.unrealised", [("instrument", "WIN\"Z5\\")])
+                        .set(-12.5);
+                    registry
+                },
+                expected: "# TYPE pnl_unrealised gauge\n\
+                    pnl_unrealised{instrument=\"WIN\\\"Z5\\\\\"} -12.5\n",
+            },
+            // TC3: histogram with cumulative buckets, sum & count
+            TestCase {
+                registry: {
+                    let registry = MetricRegistry::default();
+                    let histogram =
+                        registry.histogram("latency_ms", Vec::<Tag>::new(), &[1.0, 2.5]);
+                    histogram.observe(0.5);
+                    histogram.observe(2.0);
+                    histogram.observe(7.0);
+                    registry
+                },
+                expected: "# TYPE latency_ms histogram\n\
+                    latency_ms_bucket{le=\"1\"} 1\n\
+                    latency_ms_bucket{le=\"2.5\"} 2\n\
+                    latency_ms_bucket{le=\"+Inf\"} 3\n\
+                    latency_ms_sum 9.5\n\
+                    latency_ms_count 3\n",
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let actual = encode(&test.registry);
+            assert_eq!(actual, test.expected, "TC{index} failed");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_serve() {
+        let registry = MetricRegistry::default();
+        registry
+            .counter("requests_total", Vec::<Tag>::new())
+            .increment();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve(listener, registry));
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with("# TYPE requests_total counter\nrequests_total 1\n"));
+    }
+}
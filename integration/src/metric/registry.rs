@@ -0,0 +1,419 @@
+use super::{Field, Metric, Tag};
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, OnceLock, RwLock,
+    },
+    time::Duration,
+};
+use tracing::warn;
+
+/// Default [`Histogram`] bucket upper bounds, suitable for latencies measured in milliseconds.
+pub const DEFAULT_LATENCY_BUCKETS_MS: &[f64] = &[
+    0.1, 0.5, 1.0, 2.5, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0,
+];
+
+/// Process-wide [`MetricRegistry`] used by default by Toucan components.
+static GLOBAL: OnceLock<MetricRegistry> = OnceLock::new();
+
+/// Thread-safe registry of named & tagged metric series.
+///
+/// Series are registered once and return cheap [`Counter`], [`Gauge`] and [`Histogram`] handles
+/// that are updated lock-free, so hot paths should keep hold of their handles rather than looking
+/// them up per update.
+///
+/// Cloning a [`MetricRegistry`] is cheap and yields a handle to the same series.
+#[derive(Debug, Clone, Default)]
+pub struct MetricRegistry {
+    series: Arc<RwLock<BTreeMap<SeriesKey, Series>>>,
+}
+
+/// Unique identifier of a metric series: a name and a (sorted) set of [`Tag`]s.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct SeriesKey {
+    pub name: &'static str,
+    pub tags: Vec<Tag>,
+}
+
+impl SeriesKey {
+    pub fn new<Tags>(name: &'static str, tags: Tags) -> Self
+    where
+        Tags: IntoIterator,
+        Tags::Item: Into<Tag>,
+    {
+        let mut tags = tags.into_iter().map(Into::into).collect::<Vec<_>>();
+        tags.sort();
+        Self { name, tags }
+    }
+}
+
+/// A registered metric series.
+#[derive(Debug, Clone)]
+pub enum Series {
+    Counter(Counter),
+    Gauge(Gauge),
+    Histogram(Histogram),
+}
+
+impl Series {
+    /// Prometheus metric type name of this series.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Series::Counter(_) => "counter",
+            Series::Gauge(_) => "gauge",
+            Series::Histogram(_) => "histogram",
+        }
+    }
+}
+
+impl MetricRegistry {
+    /// Process-wide [`MetricRegistry`] used by default by Toucan components.
+    pub fn global() -> &'static MetricRegistry {
+        GLOBAL.get_or_init(MetricRegistry::default)
+    }
+
+    /// Register (or fetch the existing) monotonically increasing [`Counter`] series.
+    pub fn counter<Tags>(&self, name: &'static str, tags: Tags) -> Counter
+    where
+        Tags: IntoIterator,
+        Tags::Item: Into<Tag>,
+    {
+        match self.register(SeriesKey::new(name, tags), || {
+            Series::Counter(Counter::default())
+        }) {
+            Series::Counter(counter) => counter,
+            _ => Counter::default(),
+        }
+    }
+
+    /// Register (or fetch the existing) [`Gauge`] series.
+    pub fn gauge<Tags>(&self, name: &'static str, tags: Tags) -> Gauge
+    where
+        Tags: IntoIterator,
+        Tags::Item: Into<Tag>,
+    {
+        match self.register(SeriesKey::new(name, tags), || {
+            Series::Gauge(Gauge::default())
+        }) {
+            Series::Gauge(gauge) => gauge,
+            _ => Gauge::default(),
+        }
+    }
+
+    /// Register (or fetch the existing) [`Histogram`] series with the provided bucket upper
+    /// bounds.
+    ///
+    /// If the series already exists, the existing buckets are retained.
+    pub fn histogram<Tags>(&self, name: &'static str, tags: Tags, buckets: &[f64]) -> Histogram
+    where
+        Tags: IntoIterator,
+        Tags::Item: Into<Tag>,
+    {
+        match self.register(SeriesKey::new(name, tags), || {
+            Series::Histogram(Histogram::new(buckets))
+        }) {
+            Series::Histogram(histogram) => histogram,
+            _ => Histogram::new(buckets),
+        }
+    }
+
+    /// Fetch the existing series, or insert a new one.
+    ///
+    /// Series sharing a name must share a kind. Conflicting registrations are given a detached
+    /// series that is never exported.
+    fn register<FnInit>(&self, key: SeriesKey, init: FnInit) -> Series
+    where
+        FnInit: FnOnce() -> Series,
+    {
+        if let Some(series) = self
+            .series
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(&key)
+        {
+            return series.clone();
+        }
+
+        let mut series = self
+            .series
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let new = init();
+
+        // First series with this name (empty tags sort first) determines the kind
+        let existing_kind = series
+            .range(SeriesKey::new(key.name, Vec::<Tag>::new())..)
+            .next()
+            .filter(|(existing, _)| existing.name == key.name)
+            .map(|(_, existing)| existing.kind());
+
+        match existing_kind {
+            Some(kind) if kind != new.kind() => {
+                warn!(
+                    name = key.name,
+                    registered = kind,
+                    requested = new.kind(),
+                    "MetricRegistry ignoring series registered with conflicting kind"
+                );
+                new
+            }
+            _ => series.entry(key).or_insert(new).clone(),
+        }
+    }
+
+    /// Snapshot of every registered series, ordered by name then tags.
+    pub fn series(&self) -> Vec<(SeriesKey, Series)> {
+        self.series
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter()
+            .map(|(key, series)| (key.clone(), series.clone()))
+            .collect()
+    }
+
+    /// Snapshot of every registered series as generic [`Metric`]s, timestamped with the provided
+    /// milliseconds since the Unix epoch.
+    ///
+    /// Counters and gauges produce a single `value` [`Field`], histograms produce `count` and
+    /// `sum` [`Field`]s.
+    pub fn metrics(&self, time: u64) -> Vec<Metric> {
+        self.series()
+            .into_iter()
+            .map(|(key, series)| Metric {
+                name: key.name,
+                time,
+                tags: key.tags,
+                fields: match series {
+                    Series::Counter(counter) => vec![Field::new("value", counter.value())],
+                    Series::Gauge(gauge) => vec![Field::new("value", gauge.value())],
+                    Series::Histogram(histogram) => {
+                        let snapshot = histogram.snapshot();
+                        vec![
+                            Field::new("count", snapshot.count),
+                            Field::new("sum", snapshot.sum),
+                        ]
+                    }
+                },
+            })
+            .collect()
+    }
+}
+
+/// Monotonically increasing counter handle.
+#[derive(Debug, Clone, Default)]
+pub struct Counter(Arc<AtomicU64>);
+
+impl Counter {
+    pub fn increment(&self) {
+        self.add(1);
+    }
+
+    pub fn add(&self, value: u64) {
+        self.0.fetch_add(value, Ordering::Relaxed);
+    }
+
+    pub fn value(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Gauge handle holding the last set `f64` value.
+#[derive(Debug, Clone, Default)]
+pub struct Gauge(Arc<AtomicU64>);
+
+impl Gauge {
+    pub fn set(&self, value: f64) {
+        self.0.store(value.to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn add(&self, value: f64) {
+        atomic_add_f64(&self.0, value);
+    }
+
+    pub fn value(&self) -> f64 {
+        f64::from_bits(self.0.load(Ordering::Relaxed))
+    }
+}
+
+/// Histogram handle counting observations into fixed buckets.
+#[derive(Debug, Clone)]
+pub struct Histogram(Arc<HistogramCore>);
+
+#[derive(Debug)]
+struct HistogramCore {
+    bounds: Vec<f64>,
+    buckets: Vec<AtomicU64>,
+    sum: AtomicU64,
+    count: AtomicU64,
+}
+
+/// Point-in-time view of a [`Histogram`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistogramSnapshot {
+    /// Cumulative observation count per bucket upper bound (excluding the implicit `+Inf`
+    /// bucket, which equals `count`).
+    pub buckets: Vec<(f64, u64)>,
+    pub sum: f64,
+    pub count: u64,
+}
+
+impl Histogram {
+    /// Construct a detached [`Histogram`] with the provided bucket upper bounds.
+    ///
+    /// Bounds are sorted, and non-finite or duplicate bounds are discarded.
+    pub fn new(buckets: &[f64]) -> Self {
+        let mut bounds = buckets
+            .iter()
+            .copied()
+            .filter(|bound| bound.is_finite())
+            .collect::<Vec<_>>();
+        bounds.sort_by(f64::total_cmp);
+        bounds.dedup();
+
+        Self(Arc::new(HistogramCore {
+            buckets: bounds.iter().map(|_| AtomicU64::new(0)).collect(),
+            bounds,
+            sum: AtomicU64::new(0f64.to_bits()),
+            count: AtomicU64::new(0),
+        }))
+    }
+
+    /// Record an observation. Non-finite values are ignored.
+    pub fn observe(&self, value: f64) {
+        if !value.is_finite() {
+            return;
+        }
+
+        if let Some(index) = self.0.bounds.iter().position(|bound| value <= *bound) {
+            self.0.buckets[index].fetch_add(1, Ordering::Relaxed);
+        }
+        atomic_add_f64(&self.0.sum, value);
+        self.0.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a [`Duration`] observation in milliseconds.
+    pub fn observe_duration(&self, duration: Duration) {
+        self.observe(duration.as_secs_f64() * 1000.0);
+    }
+
+    pub fn snapshot(&self) -> HistogramSnapshot {
+        let mut cumulative = 0;
+        let buckets = self
+            .0
+            .bounds
+            .iter()
+            .zip(&self.0.buckets)
+            .map(|(bound, count)| {
+                cumulative += count.load(Ordering::Relaxed);
+                (*bound, cumulative)
+            })
+            .collect();
+
+        HistogramSnapshot {
+            buckets,
+            sum: f64::from_bits(self.0.sum.load(Ordering::Relaxed)),
+            count: self.0.count.load(Ordering::Relaxed),
+        }
+    }
+}
+
+fn atomic_add_f64(atomic: &AtomicU64, value: f64) {
+    let _ = atomic.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+        Some((f64::from_bits(bits) + value).to_bits())
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_snapshot() {
+        struct TestCase {
+            observations: Vec<f64>,
+            expected: HistogramSnapshot,
+        }
+
+        let cases = vec![
+            // TC0: no observations
+            TestCase {
+                observations: vec![],
+                expected: HistogramSnapshot {
+                    buckets: vec![(1.0, 0), (5.0, 0)],
+                    sum: 0.0,
+                    count: 0,
+                },
+            },
+            // TC1: observations on bucket bounds are inclusive
+            TestCase {
+                observations: vec![1.0, 5.0],
+                expected: HistogramSnapshot {
+                    buckets: vec![(1.0, 1), (5.0, 2)],
+                    sum: 6.0,
+                    count: 2,
+                },
+            },
+            // TC2: observations above the largest bound only count towards +Inf
+            TestCase {
+                observations: vec![0.5, 3.0, 10.0],
+                expected: HistogramSnapshot {
+                    buckets: vec![(1.0, 1), (5.0, 2)],
+                    sum: 13.5,
+                    count: 3,
+                },
+            },
+            // TC3: non-finite observations are ignored
+            TestCase {
+                observations: vec![f64::NAN, f64::INFINITY, 2.0],
+                expected: HistogramSnapshot {
+                    buckets: vec![(1.0, 0), (5.0, 1)],
+                    sum: 2.0,
+                    count: 1,
+                },
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let histogram = Histogram::new(&[5.0, 1.0, f64::NAN, 1.0]);
+            test.observations
+                .into_iter()
+                .for_each(|value| histogram.observe(value));
+
+            assert_eq!(histogram.snapshot(), test.expected, "TC{index} failed");
+        }
+    }
+
+    #[test]
+    fn test_registry_series_are_shared_by_key() {
+        let registry = MetricRegistry::default();
+
+        let counter = registry.counter("events_total", [("stream", "market")]);
+        counter.add(2);
+        registry
+            .counter("events_total", [("stream", "market")])
+            .increment();
+        registry
+            .counter("events_total", [("stream", "account")])
+            .increment();
+
+        // Conflicting kind returns a detached handle without replacing the counter
+        registry
+            .gauge("events_total", [("stream", "market")])
+            .set(9.0);
+
+        let actual = registry
+            .metrics(1)
+            .into_iter()
+            .map(|metric| (metric.tags[0].value.clone(), metric.fields))
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            ("account".to_string(), vec![Field::new("value", 1u64)]),
+            ("market".to_string(), vec![Field::new("value", 3u64)]),
+        ];
+
+        assert_eq!(actual, expected);
+    }
+}
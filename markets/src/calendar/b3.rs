@@ -0,0 +1,240 @@
+//! B3 cash equities session hours are expressed in Brasília time (BRT, UTC-3), which has had no
+//! daylight saving time since 2019:
+//!
+//! | Phase         | BRT           |
+//! |---------------|---------------|
+//! | `PreOpening`  | 09:45 - 10:00 |
+//! | `Continuous`  | 10:00 - 16:55 |
+//! | `ClosingCall` | 16:55 - 17:00 |
+//! | `AfterMarket` | 17:25 - 17:45 |
+//!
+//! B3 shifts these hours when the US enters/leaves daylight saving time, so construct a custom
+//! [`SessionSchedule`] to model the seasonal hours if required.
+//!
+//! B3 holidays include national holidays, Carnival Monday & Tuesday, Good Friday, Corpus Christi,
+//! Christmas Eve and the last business day of the year. São Paulo municipal holidays (Jan 25 and
+//! Jul 9) are trading days. Exceptional closures can be added with
+//! [`HolidayCalendar::with_dates`].
+
+use super::{HolidayCalendar, HolidayRule, PhaseWindow, SessionPhase, SessionSchedule};
+use chrono::{FixedOffset, NaiveTime};
+
+/// Brasília time (BRT, UTC-3) offset from UTC, in seconds.
+pub const BRT_UTC_OFFSET_SECS: i32 = -3 * 3600;
+
+/// Brasília time (BRT, UTC-3).
+pub fn brt() -> FixedOffset {
+    FixedOffset::east_opt(BRT_UTC_OFFSET_SECS).expect("BRT offset is valid")
+}
+
+/// B3 cash equities [`SessionSchedule`] (BRT).
+pub fn equities_schedule() -> SessionSchedule {
+    let hm = |hour, min| NaiveTime::from_hms_opt(hour, min, 0).expect("valid session time");
+
+    SessionSchedule::new(
+        brt(),
+        [
+            PhaseWindow::new(SessionPhase::PreOpening, hm(9, 45), hm(10, 0)),
+            PhaseWindow::new(SessionPhase::Continuous, hm(10, 0), hm(16, 55)),
+            PhaseWindow::new(SessionPhase::ClosingCall, hm(16, 55), hm(17, 0)),
+            PhaseWindow::new(SessionPhase::AfterMarket, hm(17, 25), hm(17, 45)),
+        ],
+    )
+}
+
+/// B3 [`HolidayCalendar`] rules.
+pub fn holidays() -> HolidayCalendar {
+    let fixed = |month, day| HolidayRule::Fixed {
+        month,
+        day,
+        since: None,
+    };
+
+    HolidayCalendar::new([
+        // Confraternização Universal
+        fixed(1, 1),
+        // Carnival Monday & Tuesday
+        HolidayRule::EasterOffset { days: -48 },
+        HolidayRule::EasterOffset { days: -47 },
+        // Good Friday
+        HolidayRule::EasterOffset { days: -2 },
+        // Tiradentes
+        fixed(4, 21),
+        // Labour Day
+        fixed(5, 1),
+        // Corpus Christi
+        HolidayRule::EasterOffset { days: 60 },
+        // Independence Day
+        fixed(9, 7),
+        // Nossa Senhora Aparecida
+        fixed(10, 12),
+        // Finados
+        fixed(11, 2),
+        // Proclamação da República
+        fixed(11, 15),
+        // Dia Nacional de Zumbi e da Consciência Negra (national holiday since 2024)
+        HolidayRule::Fixed {
+            month: 11,
+            day: 20,
+            since: Some(2024),
+        },
+        // Christmas Eve & Christmas
+        fixed(12, 24),
+        fixed(12, 25),
+        // No trading on the last business day of the year
+        HolidayRule::LastWeekdayOfYear,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendar::{ExchangeCalendar, TradingCalendar};
+    use chrono::{DateTime, Datelike, NaiveDate, Utc, Weekday};
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_b3_weekday_holidays() {
+        struct TestCase {
+            year: i32,
+            expected: Vec<NaiveDate>,
+        }
+
+        let cases = vec![
+            // TC0: 2024
+            TestCase {
+                year: 2024,
+                expected: vec![
+                    date(2024, 1, 1),
+                    date(2024, 2, 12),
+                    date(2024, 2, 13),
+                    date(2024, 3, 29),
+                    date(2024, 5, 1),
+                    date(2024, 5, 30),
+                    date(2024, 11, 15),
+                    date(2024, 11, 20),
+                    date(2024, 12, 24),
+                    date(2024, 12, 25),
+                    date(2024, 12, 31),
+                ],
+            },
+            // TC1: 2025
+            TestCase {
+                year: 2025,
+                expected: vec![
+                    date(2025, 1, 1),
+                    date(2025, 3, 3),
+                    date(2025, 3, 4),
+                    date(2025, 4, 18),
+                    date(2025, 4, 21),
+                    date(2025, 5, 1),
+                    date(2025, 6, 19),
+                    date(2025, 11, 20),
+                    date(2025, 12, 24),
+                    date(2025, 12, 25),
+                    date(2025, 12, 31),
+                ],
+            },
+        ];
+
+        let holidays = holidays();
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let actual = date(test.year, 1, 1)
+                .iter_days()
+                .take_while(|day| day.year() == test.year)
+                .filter(|day| !matches!(day.weekday(), Weekday::Sat | Weekday::Sun))
+                .filter(|day| holidays.is_holiday(*day))
+                .collect::<Vec<_>>();
+
+            assert_eq!(actual, test.expected, "TC{index} failed");
+        }
+    }
+
+    #[test]
+    fn test_b3_equities_phase() {
+        struct TestCase {
+            time: &'static str,
+            expected: SessionPhase,
+        }
+
+        let cases = vec![
+            // TC0: 09:00 BRT before pre-opening
+            TestCase {
+                time: "2024-03-15T12:00:00Z",
+                expected: SessionPhase::Closed,
+            },
+            // TC1: 09:50 BRT opening auction
+            TestCase {
+                time: "2024-03-15T12:50:00Z",
+                expected: SessionPhase::PreOpening,
+            },
+            // TC2: 10:00 BRT continuous trading
+            TestCase {
+                time: "2024-03-15T13:00:00Z",
+                expected: SessionPhase::Continuous,
+            },
+            // TC3: 16:57 BRT closing auction
+            TestCase {
+                time: "2024-03-15T19:57:00Z",
+                expected: SessionPhase::ClosingCall,
+            },
+            // TC4: 17:10 BRT between closing call and after-market
+            TestCase {
+                time: "2024-03-15T20:10:00Z",
+                expected: SessionPhase::Closed,
+            },
+            // TC5: 17:30 BRT after-market
+            TestCase {
+                time: "2024-03-15T20:30:00Z",
+                expected: SessionPhase::AfterMarket,
+            },
+            // TC6: 11:00 BRT on a Saturday
+            TestCase {
+                time: "2024-03-16T14:00:00Z",
+                expected: SessionPhase::Closed,
+            },
+            // TC7: 11:00 BRT on Good Friday
+            TestCase {
+                time: "2024-03-29T14:00:00Z",
+                expected: SessionPhase::Closed,
+            },
+            // TC8: 01:00 UTC Saturday is still Friday 22:00 BRT
+            TestCase {
+                time: "2024-03-16T01:00:00Z",
+                expected: SessionPhase::Closed,
+            },
+        ];
+
+        let calendar = ExchangeCalendar::b3_equities();
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let time = test.time.parse::<DateTime<Utc>>().unwrap();
+            assert_eq!(calendar.phase(time), test.expected, "TC{index} failed");
+        }
+    }
+
+    #[test]
+    fn test_b3_trading_days_in_year() {
+        let calendar = ExchangeCalendar::b3_equities();
+
+        // 2024: 262 weekdays - 11 weekday holidays
+        assert_eq!(calendar.trading_days_in_year(2024), 251);
+        // 2025: 261 weekdays - 11 weekday holidays
+        assert_eq!(calendar.trading_days_in_year(2025), 250);
+    }
+
+    #[test]
+    fn test_b3_next_trading_day() {
+        let calendar = ExchangeCalendar::b3_equities();
+
+        // Friday before Carnival -> Ash Wednesday
+        assert_eq!(
+            calendar.next_trading_day(date(2024, 2, 9)),
+            Some(date(2024, 2, 14))
+        );
+    }
+}
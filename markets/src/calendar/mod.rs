@@ -0,0 +1,420 @@
+use chrono::{DateTime, Datelike, Days, FixedOffset, NaiveDate, NaiveTime, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+use std::collections::BTreeSet;
+
+/// B3 (Brasil, Bolsa, Balcão) session schedule and holiday rules.
+pub mod b3;
+
+/// Phase of a venue trading session.
+#[derive(
+    Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default, Deserialize, Serialize,
+)]
+pub enum SessionPhase {
+    /// Venue is closed (outside all session windows, weekend or holiday).
+    #[default]
+    Closed,
+
+    /// Opening auction (call) - orders are accepted but not matched continuously.
+    PreOpening,
+
+    /// Continuous trading.
+    Continuous,
+
+    /// Closing auction (call).
+    ClosingCall,
+
+    /// After-market trading session.
+    AfterMarket,
+}
+
+impl SessionPhase {
+    /// Returns `true` if the phase is an auction (opening or closing call).
+    pub fn is_auction(&self) -> bool {
+        matches!(self, Self::PreOpening | Self::ClosingCall)
+    }
+
+    /// Returns `true` if the venue accepts orders during the phase.
+    pub fn is_open(&self) -> bool {
+        !matches!(self, Self::Closed)
+    }
+}
+
+/// Intraday window of a [`SessionPhase`], in venue local time.
+///
+/// The window is half-open (`start <= time < end`). Windows with an `end` at or before their
+/// `start` wrap past midnight (eg/ `00:00 -> 00:00` covers the whole day).
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub struct PhaseWindow {
+    pub phase: SessionPhase,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl PhaseWindow {
+    /// Construct a new [`PhaseWindow`].
+    pub fn new(phase: SessionPhase, start: NaiveTime, end: NaiveTime) -> Self {
+        Self { phase, start, end }
+    }
+
+    /// Returns `true` if the provided local time falls within the window.
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start < self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+/// Intraday session schedule of a venue.
+///
+/// Venue local time is modelled as a fixed offset from UTC.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub struct SessionSchedule {
+    /// Venue local time offset from UTC, in seconds (eg/ BRT is `-3 * 3600`).
+    pub utc_offset_secs: i32,
+
+    /// Session phase windows. If windows overlap, the first matching window wins.
+    pub windows: Vec<PhaseWindow>,
+}
+
+impl SessionSchedule {
+    /// Construct a new [`SessionSchedule`] from a venue local time offset and phase windows.
+    pub fn new<WindowIter>(offset: FixedOffset, windows: WindowIter) -> Self
+    where
+        WindowIter: IntoIterator<Item = PhaseWindow>,
+    {
+        Self {
+            utc_offset_secs: offset.local_minus_utc(),
+            windows: windows.into_iter().collect(),
+        }
+    }
+
+    /// Construct a [`SessionSchedule`] with [`SessionPhase::Continuous`] trading all day (eg/
+    /// crypto venues).
+    pub fn continuous() -> Self {
+        Self::new(
+            FixedOffset::east_opt(0).expect("zero offset is valid"),
+            [PhaseWindow::new(
+                SessionPhase::Continuous,
+                NaiveTime::MIN,
+                NaiveTime::MIN,
+            )],
+        )
+    }
+
+    /// Venue local time offset from UTC.
+    pub fn offset(&self) -> FixedOffset {
+        FixedOffset::east_opt(self.utc_offset_secs)
+            .unwrap_or_else(|| FixedOffset::east_opt(0).expect("zero offset is valid"))
+    }
+
+    /// [`SessionPhase`] at the provided venue local time, assuming it is a trading day.
+    pub fn phase_at(&self, time: NaiveTime) -> SessionPhase {
+        self.windows
+            .iter()
+            .find(|window| window.contains(time))
+            .map(|window| window.phase)
+            .unwrap_or(SessionPhase::Closed)
+    }
+
+    /// Local time the last open phase of the session ends (ie/ end-of-session), if any.
+    pub fn session_end(&self) -> Option<NaiveTime> {
+        self.windows
+            .iter()
+            .filter(|window| window.phase.is_open())
+            .map(|window| window.end)
+            .max()
+    }
+}
+
+/// Rule that determines if a date is a venue holiday.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub enum HolidayRule {
+    /// Fixed calendar date every year, optionally only from a given year onwards.
+    Fixed {
+        month: u32,
+        day: u32,
+        since: Option<i32>,
+    },
+
+    /// Date relative to (Western) Easter Sunday, in days (eg/ Good Friday is `-2`).
+    EasterOffset { days: i64 },
+
+    /// Last weekday (Monday to Friday) of the year.
+    LastWeekdayOfYear,
+}
+
+impl HolidayRule {
+    /// Returns `true` if the provided date is a holiday according to this rule.
+    pub fn matches(&self, date: NaiveDate) -> bool {
+        match *self {
+            Self::Fixed { month, day, since } => {
+                date.month() == month
+                    && date.day() == day
+                    && since.is_none_or(|since| date.year() >= since)
+            }
+            Self::EasterOffset { days } => easter_sunday(date.year())
+                .and_then(|easter| shift_days(easter, days))
+                .is_some_and(|holiday| holiday == date),
+            Self::LastWeekdayOfYear => last_weekday_of_year(date.year()) == Some(date),
+        }
+    }
+}
+
+/// Venue holiday calendar composed of recurring [`HolidayRule`]s and ad-hoc closure dates.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default, Deserialize, Serialize)]
+pub struct HolidayCalendar {
+    pub rules: Vec<HolidayRule>,
+    pub dates: BTreeSet<NaiveDate>,
+}
+
+impl HolidayCalendar {
+    /// Construct a new [`HolidayCalendar`] from recurring [`HolidayRule`]s.
+    pub fn new<RuleIter>(rules: RuleIter) -> Self
+    where
+        RuleIter: IntoIterator<Item = HolidayRule>,
+    {
+        Self {
+            rules: rules.into_iter().collect(),
+            dates: BTreeSet::new(),
+        }
+    }
+
+    /// Add ad-hoc venue closure dates (eg/ exceptional closures announced by the venue).
+    pub fn with_dates<DateIter>(mut self, dates: DateIter) -> Self
+    where
+        DateIter: IntoIterator<Item = NaiveDate>,
+    {
+        self.dates.extend(dates);
+        self
+    }
+
+    /// Returns `true` if the provided date is a holiday.
+    pub fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.dates.contains(&date) || self.rules.iter().any(|rule| rule.matches(date))
+    }
+}
+
+/// Interface for determining when a venue is trading.
+pub trait TradingCalendar {
+    /// Returns `true` if the venue has a trading session on the provided (venue local) date.
+    fn is_trading_day(&self, date: NaiveDate) -> bool;
+
+    /// [`SessionPhase`] of the venue at the provided time.
+    fn phase(&self, time: DateTime<Utc>) -> SessionPhase;
+
+    /// Number of trading days in the half-open date range `[start, end)`.
+    fn trading_days_between(&self, start: NaiveDate, end: NaiveDate) -> u32 {
+        start
+            .iter_days()
+            .take_while(|date| *date < end)
+            .filter(|date| self.is_trading_day(*date))
+            .count() as u32
+    }
+
+    /// Number of trading days in the provided calendar year (eg/ ~252 for B3).
+    fn trading_days_in_year(&self, year: i32) -> u32 {
+        match (
+            NaiveDate::from_ymd_opt(year, 1, 1),
+            NaiveDate::from_ymd_opt(year + 1, 1, 1),
+        ) {
+            (Some(start), Some(end)) => self.trading_days_between(start, end),
+            _ => 0,
+        }
+    }
+
+    /// Next trading day strictly after the provided date, searching up to one year ahead.
+    fn next_trading_day(&self, date: NaiveDate) -> Option<NaiveDate> {
+        date.iter_days()
+            .skip(1)
+            .take(366)
+            .find(|date| self.is_trading_day(*date))
+    }
+}
+
+/// Trading calendar of a venue: intraday [`SessionSchedule`], [`HolidayCalendar`] and weekly
+/// closures.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct ExchangeCalendar {
+    pub name: SmolStr,
+    pub schedule: SessionSchedule,
+    pub holidays: HolidayCalendar,
+    pub closed_weekdays: Vec<Weekday>,
+}
+
+impl ExchangeCalendar {
+    /// Construct a new [`ExchangeCalendar`], closed on Saturdays and Sundays.
+    pub fn new<S>(name: S, schedule: SessionSchedule, holidays: HolidayCalendar) -> Self
+    where
+        S: AsRef<str>,
+    {
+        Self {
+            name: SmolStr::new(name),
+            schedule,
+            holidays,
+            closed_weekdays: vec![Weekday::Sat, Weekday::Sun],
+        }
+    }
+
+    /// Construct an [`ExchangeCalendar`] that is always in [`SessionPhase::Continuous`] (eg/ 24/7
+    /// crypto venues).
+    pub fn always_open() -> Self {
+        Self {
+            name: SmolStr::new("always_open"),
+            schedule: SessionSchedule::continuous(),
+            holidays: HolidayCalendar::default(),
+            closed_weekdays: vec![],
+        }
+    }
+
+    /// Construct the B3 cash equities [`ExchangeCalendar`].
+    ///
+    /// See [`b3`] for session hours and holiday rules.
+    pub fn b3_equities() -> Self {
+        Self::new("b3_equities", b3::equities_schedule(), b3::holidays())
+    }
+
+    /// Venue local date of the provided time.
+    pub fn local_date(&self, time: DateTime<Utc>) -> NaiveDate {
+        time.with_timezone(&self.schedule.offset()).date_naive()
+    }
+}
+
+impl TradingCalendar for ExchangeCalendar {
+    fn is_trading_day(&self, date: NaiveDate) -> bool {
+        !self.closed_weekdays.contains(&date.weekday()) && !self.holidays.is_holiday(date)
+    }
+
+    fn phase(&self, time: DateTime<Utc>) -> SessionPhase {
+        let local = time.with_timezone(&self.schedule.offset());
+
+        if self.is_trading_day(local.date_naive()) {
+            self.schedule.phase_at(local.time())
+        } else {
+            SessionPhase::Closed
+        }
+    }
+}
+
+/// Western (Gregorian) Easter Sunday of the provided year, via the anonymous Gregorian
+/// algorithm (Meeus/Jones/Butcher).
+pub fn easter_sunday(year: i32) -> Option<NaiveDate> {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+
+fn shift_days(date: NaiveDate, days: i64) -> Option<NaiveDate> {
+    if days >= 0 {
+        date.checked_add_days(Days::new(days.unsigned_abs()))
+    } else {
+        date.checked_sub_days(Days::new(days.unsigned_abs()))
+    }
+}
+
+fn last_weekday_of_year(year: i32) -> Option<NaiveDate> {
+    let mut date = NaiveDate::from_ymd_opt(year, 12, 31)?;
+    while matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+        date = date.pred_opt()?;
+    }
+    Some(date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn time(hour: u32, min: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, min, 0).unwrap()
+    }
+
+    #[test]
+    fn test_easter_sunday() {
+        struct TestCase {
+            year: i32,
+            expected: NaiveDate,
+        }
+
+        let cases = vec![
+            TestCase {
+                year: 2019,
+                expected: date(2019, 4, 21),
+            },
+            TestCase {
+                year: 2024,
+                expected: date(2024, 3, 31),
+            },
+            TestCase {
+                year: 2025,
+                expected: date(2025, 4, 20),
+            },
+            TestCase {
+                year: 2038,
+                expected: date(2038, 4, 25),
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            assert_eq!(
+                easter_sunday(test.year),
+                Some(test.expected),
+                "TC{index} failed"
+            );
+        }
+    }
+
+    #[test]
+    fn test_phase_window_contains() {
+        let day = PhaseWindow::new(SessionPhase::Continuous, time(10, 0), time(17, 0));
+        assert!(!day.contains(time(9, 59)));
+        assert!(day.contains(time(10, 0)));
+        assert!(!day.contains(time(17, 0)));
+
+        let overnight = PhaseWindow::new(SessionPhase::AfterMarket, time(22, 0), time(2, 0));
+        assert!(overnight.contains(time(23, 0)));
+        assert!(overnight.contains(time(1, 0)));
+        assert!(!overnight.contains(time(12, 0)));
+
+        let all_day = PhaseWindow::new(SessionPhase::Continuous, NaiveTime::MIN, NaiveTime::MIN);
+        assert!(all_day.contains(time(0, 0)));
+        assert!(all_day.contains(time(23, 59)));
+    }
+
+    #[test]
+    fn test_last_weekday_of_year() {
+        // 2022-12-31 is a Saturday
+        assert_eq!(last_weekday_of_year(2022), Some(date(2022, 12, 30)));
+        assert_eq!(last_weekday_of_year(2024), Some(date(2024, 12, 31)));
+    }
+
+    #[test]
+    fn test_always_open_calendar() {
+        let calendar = ExchangeCalendar::always_open();
+        let saturday = "2024-03-16T03:00:00Z".parse::<DateTime<Utc>>().unwrap();
+
+        assert_eq!(calendar.phase(saturday), SessionPhase::Continuous);
+        assert_eq!(
+            calendar.trading_days_between(date(2024, 1, 1), date(2025, 1, 1)),
+            366
+        );
+    }
+}
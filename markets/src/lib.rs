@@ -0,0 +1,41 @@
+#![forbid(unsafe_code)]
+#![warn(
+    unused,
+    clippy::cognitive_complexity,
+    unused_crate_dependencies,
+    unused_extern_crates,
+    clippy::unused_self,
+    clippy::useless_let_if_seq,
+    missing_debug_implementations,
+    rust_2018_idioms
+)]
+#![allow(clippy::type_complexity, clippy::too_many_arguments, type_alias_bounds)]
+
+//! # 🗓️ Markets - Venue Trading Calendars
+//!
+//! Defines per-venue trading sessions (pre-opening, continuous, closing call, after-market) and
+//! holiday calendars.
+//!
+//! ## 💡 Usage
+//!
+//! ```rust
+//! use chrono::{DateTime, NaiveDate, Utc};
+//! use toucan_markets::calendar::{ExchangeCalendar, SessionPhase, TradingCalendar};
+//!
+//! let b3 = ExchangeCalendar::b3_equities();
+//!
+//! // 14:00 UTC is 11:00 BRT
+//! let time = "2024-03-15T14:00:00Z".parse::<DateTime<Utc>>().unwrap();
+//! assert_eq!(b3.phase(time), SessionPhase::Continuous);
+//!
+//! // Carnival Tuesday
+//! assert!(!b3.is_trading_day(NaiveDate::from_ymd_opt(2024, 2, 13).unwrap()));
+//! ```
+
+/// Trading session phases, schedules, holiday calendars and the [`TradingCalendar`]
+/// interface.
+///
+/// eg/ `ExchangeCalendar::b3_equities()`, `ExchangeCalendar::always_open()`, etc.
+///
+/// [`TradingCalendar`]: calendar::TradingCalendar
+pub mod calendar;
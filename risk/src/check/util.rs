@@ -1,4 +1,3 @@
-
 use rust_decimal::Decimal;
 use std::collections::HashMap;
 
@@ -21,8 +20,10 @@ pub fn calculate_quote_notional(
     price: Decimal,
     contract_size: Decimal,
 ) -> Result<Decimal, &'static str> {
-    let notional = quantity * price * contract_size;
-    Ok(notional)
+    quantity
+        .checked_mul(price)
+        .and_then(|notional| notional.checked_mul(contract_size))
+        .ok_or("Notional calculation overflowed")
 }
 
 /// Utility function to calculate the maximum position size for a given instrument.
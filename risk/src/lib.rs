@@ -104,7 +104,24 @@
 /// such as position limits, exposure, market hours, etc.
 pub mod check;
 
+/// Derivatives margin (garantia) model combining per-contract initial margin tables with
+/// scenario-based portfolio margin.
+pub mod margin;
+
+/// Composable [`OrderCheck`] pipelines (all-of, any-of, per-instrument overrides) and the
+/// [`RiskCheckManager`] built from them.
+pub mod pipeline;
+
+/// Structured [`RiskRefusalReason`] attached to every [`RiskRefused`] order request.
+pub mod reason;
+
+/// Value-at-Risk & Expected Shortfall estimation (historical, parametric and Monte Carlo),
+/// stress scenarios, and the [`VarLimit`](var::VarLimit) pre-trade budget.
+pub mod var;
+
 pub use check::*;
+pub use pipeline::*;
+pub use reason::*;
 
 use derive_more::{Constructor, Display, From};
 use serde::{Deserialize, Serialize};
@@ -152,7 +169,8 @@ impl<T> RiskApproved<T> {
 /// Rejected result of a [`RiskManager`] check.
 ///
 /// Contains the rejected item and the specific reason for rejection,
-/// allowing detailed logging and corrective actions.
+/// allowing detailed logging and corrective actions. By default the reason is a structured
+/// [`RiskRefusalReason`] identifying the refusing check, its limit and the observed value.
 ///
 /// # Example
 /// ```rust,ignore
@@ -162,7 +180,7 @@ impl<T> RiskApproved<T> {
 /// println!("Order rejected: {}", refused.reason);
 /// ```
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
-pub struct RiskRefused<T, Reason = String> {
+pub struct RiskRefused<T, Reason = RiskRefusalReason> {
     /// The item that was rejected
     pub item: T,
     /// Specific reason for rejection
@@ -171,7 +189,7 @@ pub struct RiskRefused<T, Reason = String> {
 
 impl<T> RiskRefused<T> {
     /// Creates a new `RiskRefused` instance with the provided item and reason.
-    pub fn new(item: T, reason: impl Into<String>) -> Self {
+    pub fn new(item: T, reason: impl Into<RiskRefusalReason>) -> Self {
         Self {
             item,
             reason: reason.into(),
@@ -184,6 +202,17 @@ impl<T, Reason> RiskRefused<T, Reason> {
     pub fn into_item(self) -> T {
         self.item
     }
+
+    /// Maps the rejection reason, eg/ converting a typed reason into a [`RiskRefusalReason`].
+    pub fn map_reason<F, R>(self, f: F) -> RiskRefused<T, R>
+    where
+        F: FnOnce(Reason) -> R,
+    {
+        RiskRefused {
+            item: self.item,
+            reason: f(self.reason),
+        }
+    }
 }
 
 /// RiskManager interface for reviewing and optionally filtering cancel and open orders generated by an [`AlgoStrategy`](trader::AlgoStrategy).
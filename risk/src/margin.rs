@@ -0,0 +1,455 @@
+use crate::{var::StressScenario, RiskRefusalReason};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use toucan_execution::InstrumentIndex;
+
+/// Margin (garantia) model for exchange traded derivatives (eg/ B3 WIN, WDO & DI1 futures and
+/// options), combining a per-contract initial margin table with scenario-based portfolio margin.
+///
+/// The margin requirement is the larger of:
+/// - **Table margin**: `|net contracts| * initial` per contract root, so calendar spreads of the
+///   same root offset each other. Roots configured as `short_only` (eg/ options) are margined per
+///   series, and only net short positions require margin.
+/// - **Scenario margin**: worst portfolio loss over the configured [`StressScenario`]s, shocking
+///   each position's quote notional by the shock of its contract risk factor.
+///
+/// Instruments without a [`ContractMargin`] (eg/ spot equities) require no margin.
+///
+/// # Example
+/// ```rust,ignore
+/// use risk::margin::{MarginModel, MarginPosition};
+///
+/// let model: MarginModel = toml::from_str(r#"
+///     [contracts.WIN]
+///     initial = 3500
+///     factor = "IBOV"
+///
+///     [[scenarios]]
+///     name = "IBOV -15%"
+///     shocks = { IBOV = -0.15 }
+/// "#)?;
+///
+/// let requirement = model.requirement(&[MarginPosition::new("WINZ25", dec!(2), None, dec!(0.2))]);
+/// assert_eq!(requirement.table, dec!(7000));
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Default, Deserialize, Serialize)]
+pub struct MarginModel {
+    /// [`ContractMargin`] per contract root (eg/ "WIN" matches "WINZ25").
+    #[serde(default)]
+    pub contracts: BTreeMap<String, ContractMargin>,
+
+    /// Portfolio margin [`StressScenario`]s.
+    #[serde(default)]
+    pub scenarios: Vec<StressScenario>,
+}
+
+impl MarginModel {
+    /// Name of the check reported in [`RiskRefusalReason`]s.
+    pub const CHECK: &'static str = "margin";
+
+    /// Configure the [`ContractMargin`] of a contract root.
+    pub fn with_contract(mut self, root: impl Into<String>, contract: ContractMargin) -> Self {
+        self.contracts.insert(root.into(), contract);
+        self
+    }
+
+    /// Add a portfolio margin [`StressScenario`].
+    pub fn with_scenario(mut self, scenario: StressScenario) -> Self {
+        self.scenarios.push(scenario);
+        self
+    }
+
+    /// Contract root & [`ContractMargin`] of the provided symbol, matching the exact symbol
+    /// first and then the longest configured root prefix (eg/ "WINZ25" matches "WIN").
+    pub fn contract(&self, symbol: &str) -> Option<(&str, &ContractMargin)> {
+        self.contracts
+            .get_key_value(symbol)
+            .or_else(|| {
+                self.contracts
+                    .iter()
+                    .filter(|(root, _)| symbol.starts_with(root.as_str()))
+                    .max_by_key(|(root, _)| root.len())
+            })
+            .map(|(root, contract)| (root.as_str(), contract))
+    }
+
+    /// [`MarginRequirement`] of the provided positions.
+    pub fn requirement<Key>(&self, positions: &[MarginPosition<Key>]) -> MarginRequirement
+    where
+        Key: AsRef<str>,
+    {
+        let mut contracts = BTreeMap::<&str, (Decimal, &ContractMargin)>::new();
+        let mut factors = BTreeMap::<&str, Decimal>::new();
+
+        for position in positions {
+            let Some((root, contract)) = self.contract(position.instrument.as_ref()) else {
+                continue;
+            };
+
+            // Option series are margined individually, futures are netted per root
+            let series = if contract.short_only {
+                position.instrument.as_ref()
+            } else {
+                root
+            };
+            contracts
+                .entry(series)
+                .or_insert((Decimal::ZERO, contract))
+                .0 += position.quantity;
+
+            let factor = contract.factor.as_deref().unwrap_or(root);
+            *factors.entry(factor).or_default() += position.notional();
+        }
+
+        let table = contracts
+            .values()
+            .map(|(quantity, contract)| contract.requirement(*quantity))
+            .sum();
+
+        let scenario = self
+            .scenarios
+            .iter()
+            .map(|scenario| {
+                -factors
+                    .iter()
+                    .filter_map(|(factor, notional)| {
+                        Some(*notional * scenario.shocks.get(*factor)?)
+                    })
+                    .sum::<Decimal>()
+            })
+            .fold(Decimal::ZERO, Decimal::max);
+
+        MarginRequirement { table, scenario }
+    }
+
+    /// Check the [`MarginRequirement`] of the `projected` positions (ie/ after the order is
+    /// filled) against the available `collateral`, given the `current` positions.
+    ///
+    /// Orders that do not increase the margin requirement are always approved.
+    pub fn check<Key>(
+        &self,
+        collateral: Decimal,
+        current: &[MarginPosition<Key>],
+        projected: &[MarginPosition<Key>],
+    ) -> Result<(), RiskRefusalReason>
+    where
+        Key: AsRef<str>,
+    {
+        let projected = self.requirement(projected).total();
+        if projected <= collateral || projected <= self.requirement(current).total() {
+            Ok(())
+        } else {
+            Err(RiskRefusalReason::breach(
+                Self::CHECK,
+                collateral,
+                projected,
+            ))
+        }
+    }
+
+    /// Current [`MarginUtilisation`] of the provided positions given the available
+    /// `collateral`.
+    pub fn utilisation<Key>(
+        &self,
+        collateral: Decimal,
+        positions: &[MarginPosition<Key>],
+    ) -> MarginUtilisation
+    where
+        Key: AsRef<str>,
+    {
+        let requirement = self.requirement(positions);
+        MarginUtilisation {
+            collateral,
+            requirement,
+            available: collateral - requirement.total(),
+            utilisation: (!collateral.is_zero()).then(|| requirement.total() / collateral),
+        }
+    }
+}
+
+/// Initial margin of a derivative contract root.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub struct ContractMargin {
+    /// Initial margin per contract in collateral currency.
+    pub initial: Decimal,
+
+    /// Risk factor of the contract shocked by margin scenarios (eg/ "IBOV" for WIN & IND),
+    /// defaults to the contract root.
+    #[serde(default)]
+    pub factor: Option<String>,
+
+    /// Only net short positions require margin (eg/ options, where long positions pay the
+    /// premium upfront).
+    #[serde(default)]
+    pub short_only: bool,
+}
+
+impl ContractMargin {
+    /// Construct a [`ContractMargin`] exposed to the contract root risk factor.
+    pub fn new(initial: Decimal) -> Self {
+        Self {
+            initial,
+            factor: None,
+            short_only: false,
+        }
+    }
+
+    /// Set the risk factor shocked by margin scenarios.
+    pub fn with_factor(self, factor: impl Into<String>) -> Self {
+        Self {
+            factor: Some(factor.into()),
+            ..self
+        }
+    }
+
+    /// Only require margin for net short positions.
+    pub fn with_short_only(self, short_only: bool) -> Self {
+        Self { short_only, ..self }
+    }
+
+    fn requirement(&self, quantity: Decimal) -> Decimal {
+        let contracts = if self.short_only {
+            (-quantity).max(Decimal::ZERO)
+        } else {
+            quantity.abs()
+        };
+
+        contracts * self.initial
+    }
+}
+
+/// Derivative position evaluated by a [`MarginModel`].
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+pub struct MarginPosition<Key = InstrumentIndex> {
+    /// Instrument of the position, matched against [`MarginModel::contracts`] roots.
+    pub instrument: Key,
+
+    /// Signed number of contracts (positive for long, negative for short).
+    pub quantity: Decimal,
+
+    /// Current price, if any (positions without a price are excluded from scenario margin).
+    pub price: Option<Decimal>,
+
+    /// Contract size multiplier (eg/ 0.2 for WIN).
+    pub contract_size: Decimal,
+}
+
+impl<Key> MarginPosition<Key> {
+    /// Construct a [`MarginPosition`].
+    pub fn new(
+        instrument: Key,
+        quantity: Decimal,
+        price: Option<Decimal>,
+        contract_size: Decimal,
+    ) -> Self {
+        Self {
+            instrument,
+            quantity,
+            price,
+            contract_size,
+        }
+    }
+
+    /// Signed quote notional of the position, or zero if there is no price.
+    pub fn notional(&self) -> Decimal {
+        self.price
+            .map(|price| self.quantity * price * self.contract_size)
+            .unwrap_or_default()
+    }
+}
+
+/// Margin requirement in collateral currency.
+#[derive(
+    Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default, Deserialize, Serialize,
+)]
+pub struct MarginRequirement {
+    /// Per-contract table margin.
+    pub table: Decimal,
+
+    /// Worst scenario portfolio loss.
+    pub scenario: Decimal,
+}
+
+impl MarginRequirement {
+    /// Total margin requirement (ie/ the larger of the table & scenario margin).
+    pub fn total(&self) -> Decimal {
+        self.table.max(self.scenario)
+    }
+}
+
+/// Margin utilisation of the available collateral.
+#[derive(
+    Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default, Deserialize, Serialize,
+)]
+pub struct MarginUtilisation {
+    /// Available collateral (eg/ BRL balance deposited at the broker).
+    pub collateral: Decimal,
+
+    /// Current [`MarginRequirement`].
+    pub requirement: MarginRequirement,
+
+    /// Collateral remaining after the total margin requirement.
+    pub available: Decimal,
+
+    /// Total margin requirement as a fraction of the collateral, `None` without collateral.
+    pub utilisation: Option<Decimal>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model() -> MarginModel {
+        MarginModel::default()
+            .with_contract(
+                "WIN",
+                ContractMargin::new(Decimal::from(3_500)).with_factor("IBOV"),
+            )
+            .with_contract(
+                "IND",
+                ContractMargin::new(Decimal::from(17_500)).with_factor("IBOV"),
+            )
+            .with_contract(
+                "WDO",
+                ContractMargin::new(Decimal::from(2_000)).with_factor("USDBRL"),
+            )
+            .with_contract(
+                "IBOV",
+                ContractMargin::new(Decimal::from(1_000)).with_short_only(true),
+            )
+            .with_scenario(StressScenario::new("crash").with_shock("IBOV", Decimal::new(-15, 2)))
+            .with_scenario(StressScenario::new("rally").with_shock("IBOV", Decimal::new(15, 2)))
+    }
+
+    #[test]
+    fn test_margin_model_requirement() {
+        struct TestCase {
+            positions: Vec<MarginPosition<&'static str>>,
+            expected: MarginRequirement,
+        }
+
+        let win = |quantity: i64| {
+            MarginPosition::new(
+                "WINZ25",
+                quantity.into(),
+                Some(Decimal::from(120_000)),
+                Decimal::new(2, 1),
+            )
+        };
+
+        let cases = vec![
+            // TC0: single long future uses table margin, scenario loss 2 * 24000 * 15%
+            TestCase {
+                positions: vec![win(2)],
+                expected: MarginRequirement {
+                    table: Decimal::from(7_000),
+                    scenario: Decimal::from(7_200),
+                },
+            },
+            // TC1: calendar spread of the same root nets the table margin
+            TestCase {
+                positions: vec![
+                    win(2),
+                    MarginPosition::new("WING26", Decimal::from(-2), None, Decimal::new(2, 1)),
+                ],
+                expected: MarginRequirement {
+                    table: Decimal::ZERO,
+                    scenario: Decimal::from(7_200),
+                },
+            },
+            // TC2: WIN hedged with IND offsets in scenarios, but not in the table
+            TestCase {
+                positions: vec![
+                    win(5),
+                    MarginPosition::new(
+                        "INDZ25",
+                        Decimal::from(-1),
+                        Some(Decimal::from(120_000)),
+                        Decimal::ONE,
+                    ),
+                ],
+                expected: MarginRequirement {
+                    table: Decimal::from(35_000),
+                    scenario: Decimal::ZERO,
+                },
+            },
+            // TC3: long options require no margin
+            TestCase {
+                positions: vec![MarginPosition::new(
+                    "IBOVX130",
+                    Decimal::from(10),
+                    None,
+                    Decimal::ONE,
+                )],
+                expected: MarginRequirement::default(),
+            },
+            // TC4: short options require table margin
+            TestCase {
+                positions: vec![MarginPosition::new(
+                    "IBOVL125",
+                    Decimal::from(-3),
+                    None,
+                    Decimal::ONE,
+                )],
+                expected: MarginRequirement {
+                    table: Decimal::from(3_000),
+                    scenario: Decimal::ZERO,
+                },
+            },
+            // TC5: instruments without a contract margin are ignored
+            TestCase {
+                positions: vec![MarginPosition::new(
+                    "PETR4",
+                    Decimal::from(100),
+                    Some(Decimal::from(30)),
+                    Decimal::ONE,
+                )],
+                expected: MarginRequirement::default(),
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let actual = model().requirement(&test.positions);
+            assert_eq!(actual, test.expected, "TC{index} failed");
+        }
+    }
+
+    #[test]
+    fn test_margin_model_check_and_utilisation() {
+        let model = model();
+        let position = |quantity: i64| {
+            vec![MarginPosition::new(
+                "WDOF26",
+                Decimal::from(quantity),
+                None,
+                Decimal::from(10),
+            )]
+        };
+
+        // 5 contracts require 10000 of 12000 collateral
+        assert_eq!(
+            model.check(Decimal::from(12_000), &position(4), &position(5)),
+            Ok(())
+        );
+        assert_eq!(
+            model.check(Decimal::from(12_000), &position(5), &position(7)),
+            Err(RiskRefusalReason::breach(
+                MarginModel::CHECK,
+                Decimal::from(12_000),
+                Decimal::from(14_000),
+            ))
+        );
+
+        // Reducing a position already over the collateral is approved
+        assert_eq!(
+            model.check(Decimal::from(12_000), &position(8), &position(7)),
+            Ok(())
+        );
+
+        let utilisation = model.utilisation(Decimal::from(12_000), &position(3));
+        assert_eq!(utilisation.requirement.total(), Decimal::from(6_000));
+        assert_eq!(utilisation.available, Decimal::from(6_000));
+        assert_eq!(utilisation.utilisation, Some(Decimal::new(5, 1)));
+    }
+}
@@ -0,0 +1,675 @@
+use crate::{check::RiskCheck, reason::RiskRefusalReason, RiskApproved, RiskManager, RiskRefused};
+use rust_decimal::Decimal;
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Formatter},
+    hash::Hash,
+    marker::PhantomData,
+};
+use toucan_execution::{
+    order::request::{OrderRequestCancel, OrderRequestOpen},
+    ExchangeIndex, InstrumentIndex, Side,
+};
+
+/// Boxed [`OrderCheck`], used to compose heterogeneous checks into a pipeline.
+pub type BoxedOrderCheck<State, ExchangeKey = ExchangeIndex, InstrumentKey = InstrumentIndex> =
+    Box<dyn OrderCheck<State, ExchangeKey, InstrumentKey> + Send + Sync>;
+
+/// Typed check of a single open order request in an [`OrderContext`], refusing it with a
+/// structured [`RiskRefusalReason`].
+///
+/// `OrderCheck`s compose via [`AllOf`], [`AnyOf`] and [`PerInstrument`], and a pipeline is turned
+/// into a [`RiskManager`] via [`RiskCheckManager`]. Generic [`RiskCheck`]s are adapted via
+/// [`CheckOrder`], and any
+/// `Fn(&OrderContext<State>, &OrderRequestOpen) -> Result<(), RiskRefusalReason>` closure is also
+/// an `OrderCheck`.
+///
+/// # Example
+/// ```rust,ignore
+/// let checks = AllOf::new()
+///     .with(CheckOrder::new("max_order_quantity", CheckHigherThan::new(dec!(100)), |_, open| {
+///         Some(open.state.quantity.abs())
+///     }))
+///     .with(PerInstrument::new(default_collar).with_instrument(petr4, petr4_collar));
+///
+/// let risk = RiskCheckManager::new(checks).with_contract_size(winfut, dec!(0.2));
+/// ```
+pub trait OrderCheck<State, ExchangeKey = ExchangeIndex, InstrumentKey = InstrumentIndex> {
+    /// Check the provided open request, returning the [`RiskRefusalReason`] if it is refused.
+    fn check_open(
+        &self,
+        context: &OrderContext<'_, State, ExchangeKey, InstrumentKey>,
+        open: &OrderRequestOpen<ExchangeKey, InstrumentKey>,
+    ) -> Result<(), RiskRefusalReason>;
+}
+
+impl<State, ExchangeKey, InstrumentKey, F> OrderCheck<State, ExchangeKey, InstrumentKey> for F
+where
+    F: Fn(
+        &OrderContext<'_, State, ExchangeKey, InstrumentKey>,
+        &OrderRequestOpen<ExchangeKey, InstrumentKey>,
+    ) -> Result<(), RiskRefusalReason>,
+{
+    fn check_open(
+        &self,
+        context: &OrderContext<'_, State, ExchangeKey, InstrumentKey>,
+        open: &OrderRequestOpen<ExchangeKey, InstrumentKey>,
+    ) -> Result<(), RiskRefusalReason> {
+        self(context, open)
+    }
+}
+
+/// Context an [`OrderCheck`] evaluates an open request in.
+///
+/// Alongside the `State`, it carries the opens approved earlier in the same batch, so checks can
+/// project their limits as if every pending open was filled, and the contract size of the open
+/// request instrument (eg/ the futures or options multiplier).
+#[derive(Debug)]
+pub struct OrderContext<'a, State, ExchangeKey = ExchangeIndex, InstrumentKey = InstrumentIndex> {
+    pub state: &'a State,
+    pub pending: &'a [OrderRequestOpen<ExchangeKey, InstrumentKey>],
+    pub contract_size: Decimal,
+}
+
+impl<'a, State, ExchangeKey, InstrumentKey> OrderContext<'a, State, ExchangeKey, InstrumentKey> {
+    /// Construct a new `OrderContext` without pending opens, and a unit contract size.
+    pub fn new(state: &'a State) -> Self {
+        Self {
+            state,
+            pending: &[],
+            contract_size: Decimal::ONE,
+        }
+    }
+
+    /// Include the provided opens approved earlier in the same batch.
+    pub fn with_pending(self, pending: &'a [OrderRequestOpen<ExchangeKey, InstrumentKey>]) -> Self {
+        Self { pending, ..self }
+    }
+
+    /// Use the provided contract size of the open request instrument.
+    pub fn with_contract_size(self, contract_size: Decimal) -> Self {
+        Self {
+            contract_size,
+            ..self
+        }
+    }
+
+    /// Signed quantity (positive for buys) of the pending opens of the provided instrument.
+    pub fn pending_quantity(&self, instrument: &InstrumentKey) -> Decimal
+    where
+        InstrumentKey: PartialEq,
+    {
+        self.pending
+            .iter()
+            .filter(|open| &open.key.instrument == instrument)
+            .map(|open| match open.state.side {
+                Side::Buy => open.state.quantity.abs(),
+                Side::Sell => -open.state.quantity.abs(),
+            })
+            .sum()
+    }
+
+    /// Number of pending opens of the provided instrument.
+    pub fn pending_orders(&self, instrument: &InstrumentKey) -> usize
+    where
+        InstrumentKey: PartialEq,
+    {
+        self.pending
+            .iter()
+            .filter(|open| &open.key.instrument == instrument)
+            .count()
+    }
+}
+
+/// Adapts a generic [`RiskCheck`] into a named [`OrderCheck`], extracting the check `Input`
+/// from the [`OrderContext`] and open request.
+///
+/// If the input function returns `None` the check is not applicable, and the request passes.
+/// Refusals are attributed to the configured `name` rather than the generic [`RiskCheck::name`].
+#[derive(Debug, Clone)]
+pub struct CheckOrder<Check, FnInput> {
+    pub name: String,
+    pub check: Check,
+    pub input: FnInput,
+}
+
+impl<Check, FnInput> CheckOrder<Check, FnInput> {
+    /// Construct a new `CheckOrder` named `name`.
+    pub fn new(name: impl Into<String>, check: Check, input: FnInput) -> Self {
+        Self {
+            name: name.into(),
+            check,
+            input,
+        }
+    }
+}
+
+impl<State, ExchangeKey, InstrumentKey, Check, FnInput>
+    OrderCheck<State, ExchangeKey, InstrumentKey> for CheckOrder<Check, FnInput>
+where
+    Check: RiskCheck,
+    Check::Error: Into<RiskRefusalReason>,
+    FnInput: Fn(
+        &OrderContext<'_, State, ExchangeKey, InstrumentKey>,
+        &OrderRequestOpen<ExchangeKey, InstrumentKey>,
+    ) -> Option<Check::Input>,
+{
+    fn check_open(
+        &self,
+        context: &OrderContext<'_, State, ExchangeKey, InstrumentKey>,
+        open: &OrderRequestOpen<ExchangeKey, InstrumentKey>,
+    ) -> Result<(), RiskRefusalReason> {
+        let Some(input) = (self.input)(context, open) else {
+            return Ok(());
+        };
+
+        self.check
+            .check(&input)
+            .map_err(|error| error.into().with_check(self.name.as_str()))
+    }
+}
+
+/// [`OrderCheck`] approving a request only if every configured check approves it.
+///
+/// Checks are evaluated in order, and the request is refused with the [`RiskRefusalReason`] of
+/// the first check that refuses it. An empty `AllOf` approves every request.
+pub struct AllOf<State, ExchangeKey = ExchangeIndex, InstrumentKey = InstrumentIndex> {
+    pub checks: Vec<BoxedOrderCheck<State, ExchangeKey, InstrumentKey>>,
+}
+
+impl<State, ExchangeKey, InstrumentKey> AllOf<State, ExchangeKey, InstrumentKey> {
+    /// Construct a new empty `AllOf`.
+    pub fn new() -> Self {
+        Self { checks: Vec::new() }
+    }
+
+    /// Append an [`OrderCheck`] to the pipeline.
+    pub fn with<Check>(mut self, check: Check) -> Self
+    where
+        Check: OrderCheck<State, ExchangeKey, InstrumentKey> + Send + Sync + 'static,
+    {
+        self.checks.push(Box::new(check));
+        self
+    }
+}
+
+impl<State, ExchangeKey, InstrumentKey> Default for AllOf<State, ExchangeKey, InstrumentKey> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<State, ExchangeKey, InstrumentKey> Debug for AllOf<State, ExchangeKey, InstrumentKey> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AllOf")
+            .field("checks", &self.checks.len())
+            .finish()
+    }
+}
+
+impl<State, ExchangeKey, InstrumentKey> OrderCheck<State, ExchangeKey, InstrumentKey>
+    for AllOf<State, ExchangeKey, InstrumentKey>
+{
+    fn check_open(
+        &self,
+        context: &OrderContext<'_, State, ExchangeKey, InstrumentKey>,
+        open: &OrderRequestOpen<ExchangeKey, InstrumentKey>,
+    ) -> Result<(), RiskRefusalReason> {
+        self.checks
+            .iter()
+            .try_for_each(|check| check.check_open(context, open))
+    }
+}
+
+/// [`OrderCheck`] approving a request if at least one configured check approves it.
+///
+/// If every check refuses the request, it is refused with the [`RiskRefusalReason`] of the last
+/// check. An empty `AnyOf` refuses every request.
+pub struct AnyOf<State, ExchangeKey = ExchangeIndex, InstrumentKey = InstrumentIndex> {
+    pub checks: Vec<BoxedOrderCheck<State, ExchangeKey, InstrumentKey>>,
+}
+
+impl<State, ExchangeKey, InstrumentKey> AnyOf<State, ExchangeKey, InstrumentKey> {
+    /// Name of the refusal check used when an `AnyOf` has no checks configured.
+    pub const NAME: &'static str = "any_of";
+
+    /// Construct a new empty `AnyOf`.
+    pub fn new() -> Self {
+        Self { checks: Vec::new() }
+    }
+
+    /// Append an [`OrderCheck`] to the alternatives.
+    pub fn with<Check>(mut self, check: Check) -> Self
+    where
+        Check: OrderCheck<State, ExchangeKey, InstrumentKey> + Send + Sync + 'static,
+    {
+        self.checks.push(Box::new(check));
+        self
+    }
+}
+
+impl<State, ExchangeKey, InstrumentKey> Default for AnyOf<State, ExchangeKey, InstrumentKey> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<State, ExchangeKey, InstrumentKey> Debug for AnyOf<State, ExchangeKey, InstrumentKey> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AnyOf")
+            .field("checks", &self.checks.len())
+            .finish()
+    }
+}
+
+impl<State, ExchangeKey, InstrumentKey> OrderCheck<State, ExchangeKey, InstrumentKey>
+    for AnyOf<State, ExchangeKey, InstrumentKey>
+{
+    fn check_open(
+        &self,
+        context: &OrderContext<'_, State, ExchangeKey, InstrumentKey>,
+        open: &OrderRequestOpen<ExchangeKey, InstrumentKey>,
+    ) -> Result<(), RiskRefusalReason> {
+        let mut refused = RiskRefusalReason::refused(Self::NAME, "no checks configured");
+
+        for check in &self.checks {
+            match check.check_open(context, open) {
+                Ok(()) => return Ok(()),
+                Err(reason) => refused = reason,
+            }
+        }
+
+        Err(refused)
+    }
+}
+
+/// [`OrderCheck`] evaluating a per-instrument override check if one is configured for the
+/// request instrument, otherwise the default check.
+pub struct PerInstrument<State, ExchangeKey = ExchangeIndex, InstrumentKey = InstrumentIndex> {
+    pub default: BoxedOrderCheck<State, ExchangeKey, InstrumentKey>,
+    pub instruments: HashMap<InstrumentKey, BoxedOrderCheck<State, ExchangeKey, InstrumentKey>>,
+}
+
+impl<State, ExchangeKey, InstrumentKey> PerInstrument<State, ExchangeKey, InstrumentKey>
+where
+    InstrumentKey: Eq + Hash,
+{
+    /// Construct a new `PerInstrument` with the provided default [`OrderCheck`].
+    pub fn new<Check>(default: Check) -> Self
+    where
+        Check: OrderCheck<State, ExchangeKey, InstrumentKey> + Send + Sync + 'static,
+    {
+        Self {
+            default: Box::new(default),
+            instruments: HashMap::new(),
+        }
+    }
+
+    /// Override the default [`OrderCheck`] for the provided instrument.
+    pub fn with_instrument<Check>(mut self, instrument: InstrumentKey, check: Check) -> Self
+    where
+        Check: OrderCheck<State, ExchangeKey, InstrumentKey> + Send + Sync + 'static,
+    {
+        self.instruments.insert(instrument, Box::new(check));
+        self
+    }
+}
+
+impl<State, ExchangeKey, InstrumentKey> Debug for PerInstrument<State, ExchangeKey, InstrumentKey>
+where
+    InstrumentKey: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PerInstrument")
+            .field("instruments", &self.instruments.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl<State, ExchangeKey, InstrumentKey> OrderCheck<State, ExchangeKey, InstrumentKey>
+    for PerInstrument<State, ExchangeKey, InstrumentKey>
+where
+    InstrumentKey: Eq + Hash,
+{
+    fn check_open(
+        &self,
+        context: &OrderContext<'_, State, ExchangeKey, InstrumentKey>,
+        open: &OrderRequestOpen<ExchangeKey, InstrumentKey>,
+    ) -> Result<(), RiskRefusalReason> {
+        self.instruments
+            .get(&open.key.instrument)
+            .unwrap_or(&self.default)
+            .check_open(context, open)
+    }
+}
+
+/// [`RiskManager`] built from an [`OrderCheck`] pipeline.
+///
+/// Cancel requests are always approved. Open requests are evaluated in order, and each approved
+/// open is included in the [`OrderContext`] pending opens of the opens that follow it. Refused
+/// opens are refused with the structured [`RiskRefusalReason`] of the check that refused them.
+///
+/// Instruments without a configured contract size are evaluated with a unit contract size.
+pub struct RiskCheckManager<State, Check, InstrumentKey = InstrumentIndex> {
+    pub check: Check,
+    pub contract_sizes: HashMap<InstrumentKey, Decimal>,
+    phantom: PhantomData<State>,
+}
+
+impl<State, Check, InstrumentKey> RiskCheckManager<State, Check, InstrumentKey> {
+    /// Construct a new `RiskCheckManager` from the provided [`OrderCheck`] pipeline.
+    pub fn new(check: Check) -> Self {
+        Self {
+            check,
+            contract_sizes: HashMap::new(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Configure the contract size of an instrument (eg/ 0.2 for WIN).
+    pub fn with_contract_size(mut self, instrument: InstrumentKey, contract_size: Decimal) -> Self
+    where
+        InstrumentKey: Eq + Hash,
+    {
+        self.contract_sizes.insert(instrument, contract_size);
+        self
+    }
+}
+
+impl<State, Check, InstrumentKey> Debug for RiskCheckManager<State, Check, InstrumentKey>
+where
+    Check: Debug,
+    InstrumentKey: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RiskCheckManager")
+            .field("check", &self.check)
+            .field("contract_sizes", &self.contract_sizes)
+            .finish()
+    }
+}
+
+#[allow(clippy::type_complexity)]
+impl<State, Check, ExchangeKey, InstrumentKey> RiskManager<ExchangeKey, InstrumentKey>
+    for RiskCheckManager<State, Check, InstrumentKey>
+where
+    Check: OrderCheck<State, ExchangeKey, InstrumentKey>,
+    InstrumentKey: Eq + Hash,
+{
+    type State = State;
+
+    fn check(
+        &self,
+        state: &Self::State,
+        cancels: impl IntoIterator<Item = OrderRequestCancel<ExchangeKey, InstrumentKey>>,
+        opens: impl IntoIterator<Item = OrderRequestOpen<ExchangeKey, InstrumentKey>>,
+    ) -> (
+        impl IntoIterator<Item = RiskApproved<OrderRequestCancel<ExchangeKey, InstrumentKey>>>,
+        impl IntoIterator<Item = RiskApproved<OrderRequestOpen<ExchangeKey, InstrumentKey>>>,
+        impl IntoIterator<Item = RiskRefused<OrderRequestCancel<ExchangeKey, InstrumentKey>>>,
+        impl IntoIterator<Item = RiskRefused<OrderRequestOpen<ExchangeKey, InstrumentKey>>>,
+    ) {
+        let mut approved = Vec::new();
+        let mut refused = Vec::new();
+
+        for open in opens {
+            let contract_size = self
+                .contract_sizes
+                .get(&open.key.instrument)
+                .copied()
+                .unwrap_or(Decimal::ONE);
+            let context = OrderContext::new(state)
+                .with_pending(&approved)
+                .with_contract_size(contract_size);
+
+            match self.check.check_open(&context, &open) {
+                Ok(()) => approved.push(open),
+                Err(reason) => refused.push(RiskRefused::new(open, reason)),
+            }
+        }
+
+        (
+            cancels.into_iter().map(RiskApproved::new),
+            approved.into_iter().map(RiskApproved::new),
+            std::iter::empty(),
+            refused,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::check::CheckHigherThan;
+    use rust_decimal::Decimal;
+    use toucan_execution::{
+        order::{
+            id::{ClientOrderId, StrategyId},
+            request::RequestOpen,
+            OrderKey, OrderKind, TimeInForce,
+        },
+        Side,
+    };
+
+    fn open(instrument: &str, price: i64, quantity: i64) -> OrderRequestOpen<String, String> {
+        OrderRequestOpen {
+            key: OrderKey {
+                exchange: "b3".to_string(),
+                instrument: instrument.to_string(),
+                strategy: StrategyId::new("test"),
+                cid: ClientOrderId::new("cid"),
+            },
+            state: RequestOpen {
+                side: Side::Buy,
+                price: Decimal::from(price),
+                quantity: Decimal::from(quantity),
+                kind: OrderKind::Limit,
+                time_in_force: TimeInForce::GoodUntilCancelled { post_only: false },
+            },
+        }
+    }
+
+    fn max_quantity(limit: i64) -> impl OrderCheck<(), String, String> + Send + Sync + 'static {
+        CheckOrder::new(
+            "max_order_quantity",
+            CheckHigherThan::new(Decimal::from(limit)),
+            |_: &OrderContext<(), String, String>, open: &OrderRequestOpen<String, String>| {
+                Some(open.state.quantity.abs())
+            },
+        )
+    }
+
+    fn max_position(limit: i64) -> impl OrderCheck<(), String, String> + Send + Sync + 'static {
+        CheckOrder::new(
+            "max_position",
+            CheckHigherThan::new(Decimal::from(limit)),
+            |context: &OrderContext<(), String, String>,
+             open: &OrderRequestOpen<String, String>| {
+                Some(context.pending_quantity(&open.key.instrument) + open.state.quantity)
+            },
+        )
+    }
+
+    fn max_notional(limit: i64) -> impl OrderCheck<(), String, String> + Send + Sync + 'static {
+        CheckOrder::new(
+            "max_order_notional",
+            CheckHigherThan::new(Decimal::from(limit)),
+            |context: &OrderContext<(), String, String>,
+             open: &OrderRequestOpen<String, String>| {
+                Some(open.state.quantity * open.state.price * context.contract_size)
+            },
+        )
+    }
+
+    fn max_price(limit: i64) -> impl OrderCheck<(), String, String> + Send + Sync + 'static {
+        CheckOrder::new(
+            "max_price",
+            CheckHigherThan::new(Decimal::from(limit)),
+            |_: &OrderContext<(), String, String>, open: &OrderRequestOpen<String, String>| {
+                Some(open.state.price)
+            },
+        )
+    }
+
+    #[test]
+    fn test_order_check_pipelines() {
+        struct TestCase {
+            check: BoxedOrderCheck<(), String, String>,
+            open: OrderRequestOpen<String, String>,
+            expected: Result<(), RiskRefusalReason>,
+        }
+
+        let cases = vec![
+            // TC0: AllOf approves when every check approves
+            TestCase {
+                check: Box::new(AllOf::new().with(max_quantity(10)).with(max_price(100))),
+                open: open("PETR4", 50, 5),
+                expected: Ok(()),
+            },
+            // TC1: AllOf refuses with the first refusing check, attributed to its name
+            TestCase {
+                check: Box::new(AllOf::new().with(max_quantity(10)).with(max_price(100))),
+                open: open("PETR4", 150, 20),
+                expected: Err(RiskRefusalReason::breach(
+                    "max_order_quantity",
+                    Decimal::from(10),
+                    Decimal::from(20),
+                )),
+            },
+            // TC2: AnyOf approves when a single check approves
+            TestCase {
+                check: Box::new(AnyOf::new().with(max_quantity(10)).with(max_price(100))),
+                open: open("PETR4", 50, 20),
+                expected: Ok(()),
+            },
+            // TC3: AnyOf refuses with the last reason when every check refuses
+            TestCase {
+                check: Box::new(AnyOf::new().with(max_quantity(10)).with(max_price(100))),
+                open: open("PETR4", 150, 20),
+                expected: Err(RiskRefusalReason::breach(
+                    "max_price",
+                    Decimal::from(100),
+                    Decimal::from(150),
+                )),
+            },
+            // TC4: empty AnyOf refuses
+            TestCase {
+                check: Box::new(AnyOf::new()),
+                open: open("PETR4", 50, 5),
+                expected: Err(RiskRefusalReason::refused("any_of", "no checks configured")),
+            },
+            // TC5: PerInstrument override takes precedence over the default
+            TestCase {
+                check: Box::new(
+                    PerInstrument::new(max_quantity(10))
+                        .with_instrument("VALE3".to_string(), max_quantity(100)),
+                ),
+                open: open("VALE3", 50, 50),
+                expected: Ok(()),
+            },
+            // TC6: PerInstrument falls back to the default check
+            TestCase {
+                check: Box::new(
+                    PerInstrument::new(max_quantity(10))
+                        .with_instrument("VALE3".to_string(), max_quantity(100)),
+                ),
+                open: open("PETR4", 50, 50),
+                expected: Err(RiskRefusalReason::breach(
+                    "max_order_quantity",
+                    Decimal::from(10),
+                    Decimal::from(50),
+                )),
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let actual = test.check.check_open(&OrderContext::new(&()), &test.open);
+            assert_eq!(actual, test.expected, "TC{index} failed");
+        }
+    }
+
+    #[test]
+    fn test_risk_check_manager() {
+        let risk = RiskCheckManager::new(AllOf::new().with(max_quantity(10)));
+
+        let (cancels, approved, refused_cancels, refused) = RiskManager::check(
+            &risk,
+            &(),
+            std::iter::empty(),
+            vec![open("PETR4", 50, 5), open("PETR4", 50, 15)],
+        );
+
+        assert_eq!(cancels.into_iter().count(), 0);
+        assert_eq!(refused_cancels.into_iter().count(), 0);
+
+        let approved = approved.into_iter().collect::<Vec<_>>();
+        assert_eq!(approved, vec![RiskApproved::new(open("PETR4", 50, 5))]);
+
+        let refused = refused.into_iter().collect::<Vec<_>>();
+        assert_eq!(refused.len(), 1);
+        assert_eq!(refused[0].item, open("PETR4", 50, 15));
+        assert_eq!(refused[0].reason.check(), "max_order_quantity");
+        assert_eq!(refused[0].reason.observed(), Some(Decimal::from(15)));
+    }
+
+    #[test]
+    fn test_risk_check_manager_pending_opens() {
+        struct TestCase {
+            opens: Vec<OrderRequestOpen<String, String>>,
+            expected_approved: Vec<OrderRequestOpen<String, String>>,
+            expected_refused: Vec<(OrderRequestOpen<String, String>, RiskRefusalReason)>,
+        }
+
+        let risk =
+            RiskCheckManager::new(AllOf::new().with(max_position(10)).with(max_notional(500)))
+                .with_contract_size("WINFUT".to_string(), Decimal::new(2, 1));
+
+        let cases = vec![
+            // TC0: opens within the limit on their own breach it together, so the second is refused
+            TestCase {
+                opens: vec![open("PETR4", 10, 6), open("PETR4", 10, 6)],
+                expected_approved: vec![open("PETR4", 10, 6)],
+                expected_refused: vec![(
+                    open("PETR4", 10, 6),
+                    RiskRefusalReason::breach("max_position", Decimal::from(10), Decimal::from(12)),
+                )],
+            },
+            // TC1: pending opens of other instruments are not folded into the position
+            TestCase {
+                opens: vec![open("PETR4", 10, 6), open("VALE3", 10, 6)],
+                expected_approved: vec![open("PETR4", 10, 6), open("VALE3", 10, 6)],
+                expected_refused: vec![],
+            },
+            // TC2: notional is scaled by the configured contract size of the instrument
+            TestCase {
+                opens: vec![open("WINFUT", 300, 5), open("PETR4", 300, 5)],
+                expected_approved: vec![open("WINFUT", 300, 5)],
+                expected_refused: vec![(
+                    open("PETR4", 300, 5),
+                    RiskRefusalReason::breach(
+                        "max_order_notional",
+                        Decimal::from(500),
+                        Decimal::from(1500),
+                    ),
+                )],
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let (_, approved, _, refused) =
+                RiskManager::check(&risk, &(), std::iter::empty(), test.opens);
+
+            let approved = approved
+                .into_iter()
+                .map(|RiskApproved(open)| open)
+                .collect::<Vec<_>>();
+            assert_eq!(approved, test.expected_approved, "TC{index} failed");
+
+            let refused = refused
+                .into_iter()
+                .map(|refused| (refused.item, refused.reason))
+                .collect::<Vec<_>>();
+            assert_eq!(refused, test.expected_refused, "TC{index} failed");
+        }
+    }
+}
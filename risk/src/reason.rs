@@ -0,0 +1,125 @@
+use crate::check::CheckHigherThanError;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// Structured reason a [`RiskManager`](crate::RiskManager) refused an order request.
+///
+/// Carries the name of the check that refused the request, and where applicable the configured
+/// limit and the observed value that breached it. This allows refusals to be aggregated by check
+/// (see `TradingSummary` risk refusal statistics) rather than by free-form message.
+///
+/// # Example
+/// ```rust,ignore
+/// use risk::RiskRefusalReason;
+///
+/// let reason = RiskRefusalReason::breach("max_order_quantity", dec!(100), dec!(250));
+/// assert_eq!(reason.check(), "max_order_quantity");
+/// assert_eq!(reason.to_string(), "max_order_quantity: observed 250 exceeds limit 100");
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub enum RiskRefusalReason {
+    /// Observed value breached the limit of the named check.
+    Breach {
+        check: String,
+        limit: Decimal,
+        observed: Decimal,
+    },
+
+    /// Named check refused the request for a reason without a numeric limit (eg/ instrument not
+    /// allowed, missing reference price, exchange paused).
+    Refused { check: String, reason: String },
+
+    /// Free-form refusal reason not attributed to a named check.
+    Other(String),
+}
+
+impl RiskRefusalReason {
+    /// Name of [`RiskRefusalReason::Other`] refusals when aggregated by check.
+    pub const OTHER: &'static str = "other";
+
+    /// Construct a [`RiskRefusalReason::Breach`].
+    pub fn breach(check: impl Into<String>, limit: Decimal, observed: Decimal) -> Self {
+        Self::Breach {
+            check: check.into(),
+            limit,
+            observed,
+        }
+    }
+
+    /// Construct a [`RiskRefusalReason::Refused`].
+    pub fn refused(check: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::Refused {
+            check: check.into(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Name of the check that refused the request.
+    pub fn check(&self) -> &str {
+        match self {
+            Self::Breach { check, .. } | Self::Refused { check, .. } => check,
+            Self::Other(_) => Self::OTHER,
+        }
+    }
+
+    /// Configured limit that was breached, if any.
+    pub fn limit(&self) -> Option<Decimal> {
+        match self {
+            Self::Breach { limit, .. } => Some(*limit),
+            _ => None,
+        }
+    }
+
+    /// Observed value that breached the limit, if any.
+    pub fn observed(&self) -> Option<Decimal> {
+        match self {
+            Self::Breach { observed, .. } => Some(*observed),
+            _ => None,
+        }
+    }
+
+    /// Attribute the refusal to the provided check name, eg/ when a generic [`RiskCheck`](crate::RiskCheck)
+    /// is configured under a descriptive name in a pipeline.
+    pub fn with_check(self, name: impl Into<String>) -> Self {
+        match self {
+            Self::Breach {
+                limit, observed, ..
+            } => Self::breach(name, limit, observed),
+            Self::Refused { reason, .. } => Self::refused(name, reason),
+            Self::Other(reason) => Self::refused(name, reason),
+        }
+    }
+}
+
+impl Display for RiskRefusalReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Breach {
+                check,
+                limit,
+                observed,
+            } => write!(f, "{check}: observed {observed} exceeds limit {limit}"),
+            Self::Refused { check, reason } => write!(f, "{check}: {reason}"),
+            Self::Other(reason) => write!(f, "{reason}"),
+        }
+    }
+}
+
+impl From<String> for RiskRefusalReason {
+    fn from(reason: String) -> Self {
+        Self::Other(reason)
+    }
+}
+
+impl From<&str> for RiskRefusalReason {
+    fn from(reason: &str) -> Self {
+        Self::Other(reason.to_string())
+    }
+}
+
+impl From<CheckHigherThanError<Decimal>> for RiskRefusalReason {
+    fn from(error: CheckHigherThanError<Decimal>) -> Self {
+        Self::breach("CheckHigherThan", error.limit, error.input)
+    }
+}
@@ -0,0 +1,197 @@
+use super::{MarketReturns, VarConfig, VarError, VarPosition};
+use crate::RiskRefusalReason;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Display, hash::Hash};
+
+/// Pre-trade Value-at-Risk budget that refuses orders pushing the portfolio VaR over budget.
+///
+/// Orders are refused only if the projected VaR exceeds the budget whilst increasing the current
+/// VaR, so orders reducing the risk of a portfolio already over budget are approved.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub struct VarLimit {
+    /// [`VarConfig`] used to estimate the portfolio VaR.
+    pub config: VarConfig,
+
+    /// Maximum portfolio VaR in quote currency.
+    pub budget: Decimal,
+
+    /// Approve orders if VaR cannot be estimated due to missing or insufficient return history
+    /// (eg/ shortly after start-up). Defaults to refusing them.
+    #[serde(default)]
+    pub allow_insufficient_history: bool,
+}
+
+impl VarLimit {
+    /// Name of the check reported in [`RiskRefusalReason`]s.
+    pub const CHECK: &'static str = "max_var";
+
+    /// Construct a [`VarLimit`] that refuses orders if VaR cannot be estimated.
+    pub fn new(config: VarConfig, budget: Decimal) -> Self {
+        Self {
+            config,
+            budget,
+            allow_insufficient_history: false,
+        }
+    }
+
+    /// Approve orders if VaR cannot be estimated due to missing or insufficient return history.
+    pub fn with_allow_insufficient_history(self, allow: bool) -> Self {
+        Self {
+            allow_insufficient_history: allow,
+            ..self
+        }
+    }
+
+    /// Check the VaR of the `projected` positions (ie/ after the order is filled) against the
+    /// budget, given the `current` positions.
+    pub fn check<Key>(
+        &self,
+        returns: &MarketReturns<Key>,
+        current: &[VarPosition<Key>],
+        projected: &[VarPosition<Key>],
+    ) -> Result<(), RiskRefusalReason>
+    where
+        Key: Eq + Hash + Display,
+    {
+        let projected = match self.config.estimate(returns, projected) {
+            Ok(estimate) => estimate.var,
+            Err(VarError::MissingReturns(_) | VarError::InsufficientHistory { .. })
+                if self.allow_insufficient_history =>
+            {
+                return Ok(())
+            }
+            Err(error) => return Err(RiskRefusalReason::refused(Self::CHECK, error.to_string())),
+        };
+
+        if projected <= self.budget {
+            return Ok(());
+        }
+
+        match self.config.estimate(returns, current) {
+            Ok(current) if projected <= current.var => Ok(()),
+            _ => Err(RiskRefusalReason::breach(
+                Self::CHECK,
+                self.budget,
+                projected,
+            )),
+        }
+    }
+}
+
+/// Positions after trading the signed quote `notional` (positive for buys) in the provided
+/// instrument.
+pub fn project_positions<Key>(
+    positions: &[VarPosition<Key>],
+    instrument: &Key,
+    notional: Decimal,
+) -> Vec<VarPosition<Key>>
+where
+    Key: Clone + PartialEq,
+{
+    let mut projected = positions.to_vec();
+
+    match projected
+        .iter_mut()
+        .find(|position| &position.instrument == instrument)
+    {
+        Some(position) => position.notional += notional,
+        None => projected.push(VarPosition::new(instrument.clone(), notional)),
+    }
+
+    projected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::var::{ReturnSeries, VarMethod};
+
+    #[test]
+    fn test_var_limit_check() {
+        struct TestCase {
+            limit: VarLimit,
+            current: Decimal,
+            instrument: &'static str,
+            order: Decimal,
+            expected: Result<(), RiskRefusalReason>,
+        }
+
+        // PETR4 returns: +10%, -20%, +5%
+        let mut returns = MarketReturns::default();
+        returns.series.insert(
+            "PETR4".to_string(),
+            ReturnSeries {
+                returns: [
+                    Decimal::new(10, 2),
+                    Decimal::new(-20, 2),
+                    Decimal::new(5, 2),
+                ]
+                .into_iter()
+                .collect(),
+                ..Default::default()
+            },
+        );
+
+        let config = VarConfig::new(VarMethod::Historical, Decimal::new(9, 1));
+        let limit = VarLimit::new(config, Decimal::from(100));
+
+        let cases = vec![
+            // TC0: projected VaR within budget
+            TestCase {
+                limit,
+                current: Decimal::ZERO,
+                instrument: "PETR4",
+                order: Decimal::from(500),
+                expected: Ok(()),
+            },
+            // TC1: projected VaR over budget
+            TestCase {
+                limit,
+                current: Decimal::from(400),
+                instrument: "PETR4",
+                order: Decimal::from(200),
+                expected: Err(RiskRefusalReason::breach(
+                    VarLimit::CHECK,
+                    Decimal::from(100),
+                    Decimal::from(120),
+                )),
+            },
+            // TC2: reducing VaR already over budget is approved
+            TestCase {
+                limit,
+                current: Decimal::from(1000),
+                instrument: "PETR4",
+                order: Decimal::from(-200),
+                expected: Ok(()),
+            },
+            // TC3: instrument without returns is refused
+            TestCase {
+                limit,
+                current: Decimal::ZERO,
+                instrument: "VALE3",
+                order: Decimal::ONE,
+                expected: Err(RiskRefusalReason::refused(
+                    VarLimit::CHECK,
+                    "no return series for instrument: VALE3",
+                )),
+            },
+            // TC4: instrument without returns is approved if configured
+            TestCase {
+                limit: limit.with_allow_insufficient_history(true),
+                current: Decimal::ZERO,
+                instrument: "VALE3",
+                order: Decimal::ONE,
+                expected: Ok(()),
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let current = vec![VarPosition::new("PETR4".to_string(), test.current)];
+            let projected = project_positions(&current, &test.instrument.to_string(), test.order);
+
+            let actual = test.limit.check(&returns, &current, &projected);
+            assert_eq!(actual, test.expected, "TC{index} failed");
+        }
+    }
+}
@@ -0,0 +1,648 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rust_decimal::{
+    prelude::{FromPrimitive, ToPrimitive},
+    Decimal,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter},
+    hash::Hash,
+};
+use thiserror::Error;
+use toucan_execution::InstrumentIndex;
+
+/// Pre-trade Value-at-Risk budget ([`VarLimit`]).
+pub mod limit;
+
+/// Rolling instrument return series collected from market data ([`MarketReturns`]).
+pub mod returns;
+
+/// User-defined stress scenarios applied to current positions ([`StressScenario`]).
+pub mod stress;
+
+pub use limit::*;
+pub use returns::*;
+pub use stress::*;
+
+/// Minimum number of aligned returns required to estimate VaR.
+pub const MIN_VAR_RETURNS: usize = 2;
+
+/// Method used to estimate Value-at-Risk & Expected Shortfall.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VarMethod {
+    /// Empirical quantile of the portfolio PnL revalued with each historical return.
+    Historical,
+
+    /// Variance-covariance (delta-normal) estimate assuming normally distributed returns.
+    Parametric,
+
+    /// Empirical quantile of the portfolio PnL revalued with simulated correlated normal returns
+    /// (using the historical mean & covariance), seeded so estimates are reproducible.
+    MonteCarlo { simulations: usize, seed: u64 },
+}
+
+impl Display for VarMethod {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VarMethod::Historical => write!(f, "historical"),
+            VarMethod::Parametric => write!(f, "parametric"),
+            VarMethod::MonteCarlo { simulations, .. } => write!(f, "monte_carlo({simulations})"),
+        }
+    }
+}
+
+/// Configuration of a Value-at-Risk & Expected Shortfall estimate.
+///
+/// # Example
+/// ```rust,ignore
+/// use risk::var::{VarConfig, VarMethod};
+///
+/// // 99% 1-day historical VaR with daily sampled returns
+/// let config = VarConfig::new(VarMethod::Historical, dec!(0.99));
+/// let estimate = config.estimate(&returns, &positions)?;
+/// println!("VaR {} ES {}", estimate.var, estimate.expected_shortfall);
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub struct VarConfig {
+    /// Estimation [`VarMethod`].
+    pub method: VarMethod,
+
+    /// Confidence level between 0 and 1 exclusive (eg/ 0.99).
+    pub confidence: Decimal,
+
+    /// Horizon in multiples of the [`MarketReturns`] sampling interval, scaled with the
+    /// square-root-of-time rule.
+    pub horizon: u32,
+}
+
+impl VarConfig {
+    /// Construct a [`VarConfig`] with a horizon of one return interval.
+    pub fn new(method: VarMethod, confidence: Decimal) -> Self {
+        Self {
+            method,
+            confidence,
+            horizon: 1,
+        }
+    }
+
+    /// Set the horizon in multiples of the return sampling interval.
+    pub fn with_horizon(self, horizon: u32) -> Self {
+        Self { horizon, ..self }
+    }
+
+    /// Estimate the Value-at-Risk & Expected Shortfall of the provided positions.
+    ///
+    /// Positions with a zero notional are ignored, and an empty portfolio has zero VaR.
+    pub fn estimate<Key>(
+        &self,
+        returns: &MarketReturns<Key>,
+        positions: &[VarPosition<Key>],
+    ) -> Result<VarEstimate, VarError>
+    where
+        Key: Eq + Hash + Display,
+    {
+        self.validate()?;
+
+        let positions = positions
+            .iter()
+            .filter(|position| !position.notional.is_zero())
+            .collect::<Vec<_>>();
+
+        if positions.is_empty() {
+            return Ok(self.into_estimate(0.0, 0.0));
+        }
+
+        let series = returns.aligned(
+            positions.iter().map(|position| &position.instrument),
+            MIN_VAR_RETURNS,
+        )?;
+        let weights = positions
+            .iter()
+            .map(|position| position.notional.to_f64().unwrap_or_default())
+            .collect::<Vec<_>>();
+
+        let horizon = f64::from(self.horizon);
+        let confidence = self.confidence.to_f64().unwrap_or_default();
+
+        let (var, expected_shortfall) = match self.method {
+            VarMethod::Historical => {
+                let pnl = portfolio_pnl(&series, &weights)
+                    .map(|pnl| pnl * horizon.sqrt())
+                    .collect();
+                tail_risk(pnl, self.confidence)
+            }
+            VarMethod::Parametric => {
+                let pnl = portfolio_pnl(&series, &weights).collect::<Vec<_>>();
+                let (mean, std_dev) = mean_std_dev(&pnl);
+                let (mean, std_dev) = (mean * horizon, std_dev * horizon.sqrt());
+                let z = inverse_normal_cdf(confidence);
+                (
+                    z * std_dev - mean,
+                    std_dev * normal_pdf(z) / (1.0 - confidence) - mean,
+                )
+            }
+            VarMethod::MonteCarlo { simulations, seed } => {
+                let pnl = simulate_pnl(&series, &weights, horizon, simulations, seed);
+                tail_risk(pnl, self.confidence)
+            }
+        };
+
+        Ok(self.into_estimate(var, expected_shortfall))
+    }
+
+    fn validate(&self) -> Result<(), VarError> {
+        if self.confidence <= Decimal::ZERO || self.confidence >= Decimal::ONE {
+            return Err(VarError::InvalidConfidence(self.confidence));
+        }
+        if self.horizon == 0 {
+            return Err(VarError::InvalidHorizon);
+        }
+        if let VarMethod::MonteCarlo { simulations: 0, .. } = self.method {
+            return Err(VarError::InvalidSimulations);
+        }
+        Ok(())
+    }
+
+    fn into_estimate(self, var: f64, expected_shortfall: f64) -> VarEstimate {
+        let to_decimal = |value: f64| {
+            Decimal::from_f64(value)
+                .unwrap_or_default()
+                .round_dp(VAR_DECIMAL_PLACES)
+        };
+
+        VarEstimate {
+            method: self.method,
+            confidence: self.confidence,
+            horizon: self.horizon,
+            var: to_decimal(var),
+            expected_shortfall: to_decimal(expected_shortfall),
+        }
+    }
+}
+
+/// Decimal places of the quote amounts in a [`VarEstimate`].
+const VAR_DECIMAL_PLACES: u32 = 8;
+
+/// Quote notional position of an instrument evaluated by VaR & stress scenarios.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+pub struct VarPosition<Key = InstrumentIndex> {
+    /// Instrument of the position.
+    pub instrument: Key,
+
+    /// Signed quote notional of the position (positive for long, negative for short).
+    pub notional: Decimal,
+
+    /// Sensitivity of the position to each named [`StressScenario`] risk factor (eg/ "IBOV").
+    #[serde(default)]
+    pub betas: BTreeMap<String, Decimal>,
+}
+
+impl<Key> VarPosition<Key> {
+    /// Construct a [`VarPosition`] without any stress factor sensitivities.
+    pub fn new(instrument: Key, notional: Decimal) -> Self {
+        Self {
+            instrument,
+            notional,
+            betas: BTreeMap::new(),
+        }
+    }
+
+    /// Set the sensitivity of the position to the named stress factor.
+    pub fn with_beta(mut self, factor: impl Into<String>, beta: Decimal) -> Self {
+        self.betas.insert(factor.into(), beta);
+        self
+    }
+}
+
+/// Value-at-Risk & Expected Shortfall estimate, as positive quote currency loss amounts.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize)]
+pub struct VarEstimate {
+    pub method: VarMethod,
+    pub confidence: Decimal,
+    pub horizon: u32,
+
+    /// Loss that is not exceeded with the configured confidence over the horizon.
+    pub var: Decimal,
+
+    /// Mean loss over the horizon given the loss exceeds the VaR (aka CVaR).
+    pub expected_shortfall: Decimal,
+}
+
+/// All errors generated when estimating Value-at-Risk.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize, Error)]
+pub enum VarError {
+    #[error("VaR confidence {0} must be between 0 and 1 exclusive")]
+    InvalidConfidence(Decimal),
+
+    #[error("VaR horizon must be at least one return interval")]
+    InvalidHorizon,
+
+    #[error("Monte Carlo VaR requires at least one simulation")]
+    InvalidSimulations,
+
+    #[error("no return series for instrument: {0}")]
+    MissingReturns(String),
+
+    #[error("insufficient return history: {available} aligned returns, {required} required")]
+    InsufficientHistory { available: usize, required: usize },
+}
+
+/// On-demand risk report of the current portfolio, containing every configured VaR estimate
+/// and stress scenario result.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+pub struct RiskReport<Key = InstrumentIndex> {
+    pub var: Vec<VarEstimate>,
+    pub stress: Vec<StressResult<Key>>,
+}
+
+impl<Key> RiskReport<Key>
+where
+    Key: Eq + Hash + Display + Clone,
+{
+    /// Generate a [`RiskReport`] of the provided positions.
+    pub fn generate(
+        returns: &MarketReturns<Key>,
+        positions: &[VarPosition<Key>],
+        configs: &[VarConfig],
+        scenarios: &[StressScenario],
+    ) -> Result<Self, VarError> {
+        Ok(Self {
+            var: configs
+                .iter()
+                .map(|config| config.estimate(returns, positions))
+                .collect::<Result<_, _>>()?,
+            stress: scenarios
+                .iter()
+                .map(|scenario| scenario.apply(positions))
+                .collect(),
+        })
+    }
+}
+
+impl<Key> Display for RiskReport<Key> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for estimate in &self.var {
+            writeln!(
+                f,
+                "VaR {} {}% {}x: VaR {:.2} ES {:.2}",
+                estimate.method,
+                estimate.confidence * Decimal::ONE_HUNDRED,
+                estimate.horizon,
+                estimate.var,
+                estimate.expected_shortfall,
+            )?;
+        }
+        for result in &self.stress {
+            writeln!(f, "Stress {}: PnL {:.2}", result.scenario, result.pnl)?;
+        }
+        Ok(())
+    }
+}
+
+/// Portfolio PnL of every aligned return observation.
+fn portfolio_pnl<'a>(series: &'a [Vec<f64>], weights: &'a [f64]) -> impl Iterator<Item = f64> + 'a {
+    let observations = series.first().map(Vec::len).unwrap_or_default();
+    (0..observations).map(move |index| {
+        series
+            .iter()
+            .zip(weights)
+            .map(|(returns, weight)| returns[index] * weight)
+            .sum()
+    })
+}
+
+/// Empirical VaR & Expected Shortfall of a PnL distribution, using the `ceil(n * (1 - c))`
+/// worst outcomes as the tail.
+fn tail_risk(mut pnl: Vec<f64>, confidence: Decimal) -> (f64, f64) {
+    if pnl.is_empty() {
+        return (0.0, 0.0);
+    }
+
+    pnl.sort_by(f64::total_cmp);
+
+    let tail = (Decimal::from(pnl.len()) * (Decimal::ONE - confidence))
+        .ceil()
+        .to_usize()
+        .unwrap_or(1)
+        .clamp(1, pnl.len());
+
+    let var = -pnl[tail - 1];
+    let expected_shortfall = -pnl[..tail].iter().sum::<f64>() / tail as f64;
+
+    (var, expected_shortfall)
+}
+
+/// Simulate portfolio PnL with correlated normal returns sharing the historical mean &
+/// covariance of the provided return series.
+fn simulate_pnl(
+    series: &[Vec<f64>],
+    weights: &[f64],
+    horizon: f64,
+    simulations: usize,
+    seed: u64,
+) -> Vec<f64> {
+    let means = series
+        .iter()
+        .map(|returns| mean_std_dev(returns).0)
+        .collect::<Vec<_>>();
+    let mean = means
+        .iter()
+        .zip(weights)
+        .map(|(mean, weight)| mean * weight)
+        .sum::<f64>()
+        * horizon;
+
+    // Portfolio loadings on independent standard normal shocks (ie/ L^T w)
+    let cholesky = cholesky(&covariance(series, &means));
+    let loadings = (0..series.len())
+        .map(|factor| {
+            cholesky
+                .iter()
+                .zip(weights)
+                .map(|(row, weight)| row[factor] * weight)
+                .sum::<f64>()
+                * horizon.sqrt()
+        })
+        .collect::<Vec<_>>();
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..simulations)
+        .map(|_| {
+            mean + loadings
+                .iter()
+                .map(|loading| loading * standard_normal(&mut rng))
+                .sum::<f64>()
+        })
+        .collect()
+}
+
+/// Sample mean & standard deviation (Bessel corrected).
+fn mean_std_dev(values: &[f64]) -> (f64, f64) {
+    let count = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / count;
+    let variance = values
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f64>()
+        / (count - 1.0);
+
+    (mean, variance.sqrt())
+}
+
+/// Sample covariance matrix (Bessel corrected) of the provided return series.
+fn covariance(series: &[Vec<f64>], means: &[f64]) -> Vec<Vec<f64>> {
+    let denominator = series.first().map(Vec::len).unwrap_or_default() as f64 - 1.0;
+
+    series
+        .iter()
+        .zip(means)
+        .map(|(x, mean_x)| {
+            series
+                .iter()
+                .zip(means)
+                .map(|(y, mean_y)| {
+                    x.iter()
+                        .zip(y)
+                        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+                        .sum::<f64>()
+                        / denominator
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Lower triangular Cholesky decomposition of a covariance matrix.
+///
+/// Non-positive pivots (eg/ perfectly correlated or constant series) are treated as zero, so
+/// positive semi-definite matrices are also supported.
+fn cholesky(matrix: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let size = matrix.len();
+    let mut lower = vec![vec![0.0; size]; size];
+
+    for row in 0..size {
+        for column in 0..=row {
+            let sum = (0..column)
+                .map(|k| lower[row][k] * lower[column][k])
+                .sum::<f64>();
+
+            lower[row][column] = if row == column {
+                (matrix[row][row] - sum).max(0.0).sqrt()
+            } else if lower[column][column] > 0.0 {
+                (matrix[row][column] - sum) / lower[column][column]
+            } else {
+                0.0
+            };
+        }
+    }
+
+    lower
+}
+
+/// Standard normal sample generated with the Box-Muller transform.
+fn standard_normal(rng: &mut StdRng) -> f64 {
+    let uniform_open = 1.0 - rng.random::<f64>();
+    let uniform = rng.random::<f64>();
+    (-2.0 * uniform_open.ln()).sqrt() * (std::f64::consts::TAU * uniform).cos()
+}
+
+/// Standard normal probability density function.
+fn normal_pdf(z: f64) -> f64 {
+    (-0.5 * z * z).exp() / std::f64::consts::TAU.sqrt()
+}
+
+/// Inverse standard normal cumulative distribution function (Acklam's rational approximation,
+/// relative error below 1.15e-9).
+fn inverse_normal_cdf(probability: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1,
+        2.209_460_984_245_205e2,
+        -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2,
+        -3.066_479_806_614_716e1,
+        2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1,
+        1.615_858_368_580_409e2,
+        -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1,
+        -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3,
+        -3.223_964_580_411_365e-1,
+        -2.400_758_277_161_838,
+        -2.549_732_539_343_734,
+        4.374_664_141_464_968,
+        2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3,
+        3.224_671_290_700_398e-1,
+        2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+
+    if probability < P_LOW {
+        tail((-2.0 * probability.ln()).sqrt())
+    } else if probability <= 1.0 - P_LOW {
+        let q = probability - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        -tail((-2.0 * (1.0 - probability).ln()).sqrt())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, TimeDelta, Utc};
+    use std::{str::FromStr, time::Duration};
+
+    fn returns(prices: &[(&str, &[&str])]) -> MarketReturns<String> {
+        let mut returns = MarketReturns::new(Duration::from_secs(60), 100);
+        for (instrument, prices) in prices {
+            for (index, price) in prices.iter().enumerate() {
+                returns.update(
+                    instrument.to_string(),
+                    DateTime::<Utc>::MIN_UTC + TimeDelta::minutes(index as i64),
+                    Decimal::from_str(price).unwrap(),
+                );
+            }
+        }
+        returns
+    }
+
+    #[test]
+    fn test_var_config_estimate() {
+        struct TestCase {
+            config: VarConfig,
+            positions: Vec<VarPosition<String>>,
+            expected: Result<(Decimal, Decimal), VarError>,
+        }
+
+        // PETR4 returns: +10%, -10%, +5%, -20%, +25%
+        // VALE3 returns: -10%, +10%, -5%, +20%, -25%
+        let returns = returns(&[
+            ("PETR4", &["100", "110", "99", "103.95", "83.16", "103.95"]),
+            ("VALE3", &["100", "90", "99", "94.05", "112.86", "84.645"]),
+        ]);
+
+        let petr4 = |notional: i64| VarPosition::new("PETR4".to_string(), notional.into());
+        let vale3 = |notional: i64| VarPosition::new("VALE3".to_string(), notional.into());
+        let historical = VarConfig::new(VarMethod::Historical, Decimal::new(8, 1));
+
+        let cases = vec![
+            // TC0: historical long PETR4, 80% tail is the single worst return (-20%)
+            TestCase {
+                config: historical,
+                positions: vec![petr4(1000)],
+                expected: Ok((Decimal::from(200), Decimal::from(200))),
+            },
+            // TC1: historical short PETR4, tail is the +25% return
+            TestCase {
+                config: historical,
+                positions: vec![petr4(-1000)],
+                expected: Ok((Decimal::from(250), Decimal::from(250))),
+            },
+            // TC2: historical perfectly hedged portfolio has no risk
+            TestCase {
+                config: historical,
+                positions: vec![petr4(1000), vale3(1000)],
+                expected: Ok((Decimal::ZERO, Decimal::ZERO)),
+            },
+            // TC3: historical horizon scales with the square root of time
+            TestCase {
+                config: historical.with_horizon(4),
+                positions: vec![petr4(1000)],
+                expected: Ok((Decimal::from(400), Decimal::from(400))),
+            },
+            // TC4: empty portfolio has zero VaR
+            TestCase {
+                config: historical,
+                positions: vec![petr4(0)],
+                expected: Ok((Decimal::ZERO, Decimal::ZERO)),
+            },
+            // TC5: invalid confidence
+            TestCase {
+                config: VarConfig::new(VarMethod::Historical, Decimal::ONE),
+                positions: vec![petr4(1000)],
+                expected: Err(VarError::InvalidConfidence(Decimal::ONE)),
+            },
+            // TC6: instrument without returns
+            TestCase {
+                config: historical,
+                positions: vec![VarPosition::new("ITUB4".to_string(), Decimal::ONE)],
+                expected: Err(VarError::MissingReturns("ITUB4".to_string())),
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let actual = test
+                .config
+                .estimate(&returns, &test.positions)
+                .map(|estimate| (estimate.var, estimate.expected_shortfall));
+            assert_eq!(actual, test.expected, "TC{index} failed");
+        }
+    }
+
+    #[test]
+    fn test_var_config_estimate_parametric_and_monte_carlo() {
+        // Alternating +1% / -1% returns: mean 0, sample standard deviation ~1.0025%
+        let mut returns = MarketReturns::default();
+        returns.series.insert(
+            "PETR4".to_string(),
+            ReturnSeries {
+                returns: (0..200)
+                    .map(|index| Decimal::new(if index % 2 == 0 { 1 } else { -1 }, 2))
+                    .collect(),
+                ..Default::default()
+            },
+        );
+
+        let positions = vec![VarPosition::new("PETR4".to_string(), Decimal::from(1000))];
+        let confidence = Decimal::new(99, 2);
+
+        // z(0.99) = 2.3263, sigma = 10.0251 => VaR 23.32, ES = sigma * pdf(z) / 0.01 = 26.72
+        let parametric = VarConfig::new(VarMethod::Parametric, confidence)
+            .estimate(&returns, &positions)
+            .unwrap();
+        assert_eq!(parametric.var.round_dp(2), Decimal::new(2332, 2));
+        assert_eq!(
+            parametric.expected_shortfall.round_dp(2),
+            Decimal::new(2672, 2)
+        );
+
+        // Monte Carlo converges towards the parametric estimate, and is reproducible by seed
+        let monte_carlo = VarConfig::new(
+            VarMethod::MonteCarlo {
+                simulations: 20_000,
+                seed: 7,
+            },
+            confidence,
+        );
+        let first = monte_carlo.estimate(&returns, &positions).unwrap();
+        let second = monte_carlo.estimate(&returns, &positions).unwrap();
+        assert_eq!(first, second);
+        assert!((first.var - parametric.var).abs() < Decimal::ONE);
+        assert!((first.expected_shortfall - parametric.expected_shortfall).abs() < Decimal::ONE);
+    }
+
+    #[test]
+    fn test_inverse_normal_cdf() {
+        assert!((inverse_normal_cdf(0.5)).abs() < 1e-9);
+        assert!((inverse_normal_cdf(0.975) - 1.959_963_985).abs() < 1e-8);
+        assert!((inverse_normal_cdf(0.01) + 2.326_347_874).abs() < 1e-8);
+    }
+}
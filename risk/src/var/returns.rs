@@ -0,0 +1,173 @@
+use super::VarError;
+use chrono::{DateTime, TimeDelta, Utc};
+use rust_decimal::{prelude::ToPrimitive, Decimal};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+    time::Duration,
+};
+use toucan_execution::InstrumentIndex;
+
+/// Default interval at which [`MarketReturns`] sample instrument prices.
+pub const DEFAULT_RETURN_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Default maximum number of returns kept per instrument by [`MarketReturns`].
+pub const DEFAULT_RETURN_CAPACITY: usize = 1_000;
+
+/// Rolling simple return series of every instrument, collected from market data prices.
+///
+/// Prices are sampled at a fixed `interval` (ie/ the first price received at least `interval`
+/// after the previous sample), so the VaR horizon is expressed in multiples of the `interval`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(bound(deserialize = "Key: Deserialize<'de> + Eq + Hash"))]
+pub struct MarketReturns<Key = InstrumentIndex> {
+    /// Interval between sampled prices.
+    pub interval: Duration,
+
+    /// Maximum number of returns kept per instrument.
+    pub capacity: usize,
+
+    /// [`ReturnSeries`] of every instrument that received a price.
+    pub series: HashMap<Key, ReturnSeries>,
+}
+
+impl<Key> Default for MarketReturns<Key> {
+    fn default() -> Self {
+        Self::new(DEFAULT_RETURN_INTERVAL, DEFAULT_RETURN_CAPACITY)
+    }
+}
+
+impl<Key> PartialEq for MarketReturns<Key>
+where
+    Key: Eq + Hash,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.interval == other.interval
+            && self.capacity == other.capacity
+            && self.series == other.series
+    }
+}
+
+impl<Key> MarketReturns<Key> {
+    /// Construct empty [`MarketReturns`] sampling prices every `interval`, keeping at most
+    /// `capacity` returns per instrument.
+    pub fn new(interval: Duration, capacity: usize) -> Self {
+        Self {
+            interval,
+            capacity,
+            series: HashMap::new(),
+        }
+    }
+}
+
+impl<Key> MarketReturns<Key>
+where
+    Key: Eq + Hash,
+{
+    /// Update the [`ReturnSeries`] of the provided instrument with a new market price.
+    pub fn update(&mut self, instrument: Key, time: DateTime<Utc>, price: Decimal) {
+        let interval = TimeDelta::from_std(self.interval).unwrap_or(TimeDelta::MAX);
+        let capacity = self.capacity;
+
+        self.series
+            .entry(instrument)
+            .or_default()
+            .update(time, price, interval, capacity);
+    }
+
+    /// [`ReturnSeries`] of the provided instrument, if any price has been received.
+    pub fn series(&self, instrument: &Key) -> Option<&ReturnSeries> {
+        self.series.get(instrument)
+    }
+
+    /// Most recent returns of every provided instrument, truncated to the length of the shortest
+    /// series so they are aligned in time. Requires at least `required` aligned returns.
+    pub(crate) fn aligned<'a, Instruments>(
+        &self,
+        instruments: Instruments,
+        required: usize,
+    ) -> Result<Vec<Vec<f64>>, VarError>
+    where
+        Key: std::fmt::Display + 'a,
+        Instruments: IntoIterator<Item = &'a Key>,
+    {
+        let series = instruments
+            .into_iter()
+            .map(|instrument| {
+                self.series(instrument)
+                    .ok_or_else(|| VarError::MissingReturns(instrument.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let available = series
+            .iter()
+            .map(|series| series.returns.len())
+            .min()
+            .unwrap_or_default();
+
+        if available < required {
+            return Err(VarError::InsufficientHistory {
+                available,
+                required,
+            });
+        }
+
+        Ok(series
+            .into_iter()
+            .map(|series| {
+                series
+                    .returns
+                    .iter()
+                    .skip(series.returns.len() - available)
+                    .map(|value| value.to_f64().unwrap_or_default())
+                    .collect()
+            })
+            .collect())
+    }
+}
+
+/// Rolling simple return series of an instrument.
+#[derive(Debug, Clone, PartialEq, Default, Deserialize, Serialize)]
+pub struct ReturnSeries {
+    /// Time of the most recently sampled price.
+    pub last_time: Option<DateTime<Utc>>,
+
+    /// Most recently sampled price.
+    pub last_price: Option<Decimal>,
+
+    /// Simple returns between consecutive sampled prices, oldest first.
+    pub returns: VecDeque<Decimal>,
+}
+
+impl ReturnSeries {
+    fn update(
+        &mut self,
+        time: DateTime<Utc>,
+        price: Decimal,
+        interval: TimeDelta,
+        capacity: usize,
+    ) {
+        if price <= Decimal::ZERO {
+            return;
+        }
+
+        let (Some(last_time), Some(last_price)) = (self.last_time, self.last_price) else {
+            self.last_time = Some(time);
+            self.last_price = Some(price);
+            return;
+        };
+
+        if time.signed_duration_since(last_time) < interval {
+            return;
+        }
+
+        self.returns.push_back(price / last_price - Decimal::ONE);
+        while self.returns.len() > capacity {
+            self.returns.pop_front();
+        }
+
+        self.last_time = Some(time);
+        self.last_price = Some(price);
+    }
+}
@@ -0,0 +1,200 @@
+use super::VarPosition;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, str::FromStr};
+use thiserror::Error;
+use toucan_execution::InstrumentIndex;
+
+/// User-defined stress scenario of relative shocks applied to named risk factors
+/// (eg/ "IBOV -10%", "USDBRL +5%").
+///
+/// Each position is exposed to a factor through its [`VarPosition::betas`], so the stressed PnL
+/// of a position is `notional * sum(beta * shock)` over the shocked factors.
+///
+/// # Example
+/// ```rust,ignore
+/// use risk::var::StressScenario;
+///
+/// let scenario: StressScenario = "IBOV -10%, USDBRL +5%".parse()?;
+/// assert_eq!(scenario.shocks["IBOV"], dec!(-0.10));
+///
+/// let result = scenario.apply(&positions);
+/// println!("{}: {}", result.scenario, result.pnl);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+pub struct StressScenario {
+    /// Name of the scenario (eg/ "covid_crash").
+    pub name: String,
+
+    /// Relative shock of each named risk factor (eg/ -0.10 for a 10% fall).
+    pub shocks: BTreeMap<String, Decimal>,
+}
+
+impl StressScenario {
+    /// Construct a [`StressScenario`] without any shocks.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            shocks: BTreeMap::new(),
+        }
+    }
+
+    /// Set the relative shock of the named risk factor.
+    pub fn with_shock(mut self, factor: impl Into<String>, shock: Decimal) -> Self {
+        self.shocks.insert(factor.into(), shock);
+        self
+    }
+
+    /// Apply the scenario to the provided positions.
+    pub fn apply<Key>(&self, positions: &[VarPosition<Key>]) -> StressResult<Key>
+    where
+        Key: Clone,
+    {
+        let instruments = positions
+            .iter()
+            .map(|position| {
+                let shock = position
+                    .betas
+                    .iter()
+                    .filter_map(|(factor, beta)| Some(*beta * *self.shocks.get(factor)?))
+                    .sum::<Decimal>();
+
+                (position.instrument.clone(), position.notional * shock)
+            })
+            .collect::<Vec<_>>();
+
+        StressResult {
+            scenario: self.name.clone(),
+            pnl: instruments.iter().map(|(_, pnl)| *pnl).sum(),
+            instruments,
+        }
+    }
+}
+
+/// Parses comma or semicolon separated "<FACTOR> <SHOCK>%" shocks, using the input as the
+/// scenario name (eg/ "IBOV -10%, USDBRL +5%").
+impl FromStr for StressScenario {
+    type Err = StressScenarioParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let scenario = input
+            .split([',', ';'])
+            .map(str::trim)
+            .filter(|shock| !shock.is_empty())
+            .try_fold(Self::new(input.trim()), |scenario, shock| {
+                let error = || StressScenarioParseError(shock.to_string());
+
+                let (factor, percent) = shock.rsplit_once(char::is_whitespace).ok_or_else(error)?;
+                let percent = percent
+                    .strip_suffix('%')
+                    .and_then(|percent| Decimal::from_str(percent.trim_start_matches('+')).ok())
+                    .ok_or_else(error)?;
+
+                Ok(scenario.with_shock(factor.trim(), percent / Decimal::ONE_HUNDRED))
+            })?;
+
+        if scenario.shocks.is_empty() {
+            return Err(StressScenarioParseError(input.to_string()));
+        }
+
+        Ok(scenario)
+    }
+}
+
+/// Error returned when parsing a [`StressScenario`] from an invalid string.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Deserialize, Serialize, Error)]
+#[error("invalid stress shock: {0} (expected eg/ \"IBOV -10%\")")]
+pub struct StressScenarioParseError(pub String);
+
+/// Result of applying a [`StressScenario`] to the current positions.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+pub struct StressResult<Key = InstrumentIndex> {
+    /// Name of the applied scenario.
+    pub scenario: String,
+
+    /// Total stressed quote PnL of the portfolio.
+    pub pnl: Decimal,
+
+    /// Stressed quote PnL of each position.
+    pub instruments: Vec<(Key, Decimal)>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stress_scenario_from_str() {
+        struct TestCase {
+            input: &'static str,
+            expected: Result<Vec<(&'static str, Decimal)>, StressScenarioParseError>,
+        }
+
+        let cases = vec![
+            // TC0: single shock
+            TestCase {
+                input: "IBOV -10%",
+                expected: Ok(vec![("IBOV", Decimal::new(-10, 2))]),
+            },
+            // TC1: multiple shocks with explicit sign
+            TestCase {
+                input: "IBOV -10%, USDBRL +5%",
+                expected: Ok(vec![
+                    ("IBOV", Decimal::new(-10, 2)),
+                    ("USDBRL", Decimal::new(5, 2)),
+                ]),
+            },
+            // TC2: missing percent suffix
+            TestCase {
+                input: "IBOV -10",
+                expected: Err(StressScenarioParseError("IBOV -10".to_string())),
+            },
+            // TC3: missing factor
+            TestCase {
+                input: "-10%",
+                expected: Err(StressScenarioParseError("-10%".to_string())),
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let actual = StressScenario::from_str(test.input).map(|scenario| {
+                scenario
+                    .shocks
+                    .into_iter()
+                    .map(|(factor, shock)| (factor, shock.normalize()))
+                    .collect::<Vec<_>>()
+            });
+            let expected = test.expected.map(|shocks| {
+                shocks
+                    .into_iter()
+                    .map(|(factor, shock)| (factor.to_string(), shock.normalize()))
+                    .collect::<Vec<_>>()
+            });
+            assert_eq!(actual, expected, "TC{index} failed");
+        }
+    }
+
+    #[test]
+    fn test_stress_scenario_apply() {
+        let positions = vec![
+            VarPosition::new("PETR4", Decimal::from(10_000)).with_beta("IBOV", Decimal::new(12, 1)),
+            VarPosition::new("USDBRL_FUT", Decimal::from(-5_000)).with_beta("USDBRL", Decimal::ONE),
+            VarPosition::new("BTC", Decimal::from(1_000)),
+        ];
+
+        let result = StressScenario::from_str("IBOV -10%, USDBRL +5%")
+            .unwrap()
+            .apply(&positions);
+
+        assert_eq!(result.scenario, "IBOV -10%, USDBRL +5%");
+        assert_eq!(
+            result.instruments,
+            vec![
+                ("PETR4", Decimal::from(-1_200)),
+                ("USDBRL_FUT", Decimal::from(-250)),
+                ("BTC", Decimal::ZERO),
+            ]
+        );
+        assert_eq!(result.pnl, Decimal::from(-1_450));
+    }
+}
@@ -3,7 +3,7 @@
 
 pub mod shared;
 
-pub mod order_book_imbalance; // always available: simple reusable example
+pub mod order_book_imbalance; // always available: canonical end-to-end example
 
 #[cfg(feature = "momentum")]
 pub mod momentum;
@@ -16,3 +16,9 @@ pub mod microstructure;
 
 #[cfg(feature = "options")]
 pub mod options;
+
+#[cfg(feature = "pairs")]
+pub mod pairs;
+
+#[cfg(feature = "portfolio")]
+pub mod portfolio;
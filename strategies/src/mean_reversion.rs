@@ -1,4 +1,637 @@
+//! Mean reversion (Bollinger band / z-score) strategy.
+//!
+//! Enters when the price stretches beyond `entry_z` standard deviations from its rolling mean and
+//! exits once it reverts to within `exit_z` standard deviations, or once the position has been
+//! held for longer than `max_holding`. Entries are sized from a quote notional and rounded down
+//! to the instrument lot size of its [`InstrumentSpec`].
+//!
+//! Indicators are maintained by the [`MeanReversionData`] instrument data state, so the
+//! [`MeanReversionStrategy`] only reads the [`EngineState`] and runs unchanged in backtests
+//! (`run_backtests`) and live.
+//!
+//! # Example
+//! ```rust,ignore
+//! let strategy = MeanReversionStrategy::new(
+//!     StrategyId::new("mean_reversion"),
+//!     MeanReversionConfig::default(),
+//! )
+//! .with_instrument_spec("PETR4".to_string(), petr4_spec, Decimal::ONE);
+//!
+//! let state = EngineState::builder(&instruments, DefaultGlobalData, |_| {
+//!     strategy.instrument_data()
+//! })
+//! .build();
+//! ```
 
-//! Mean Reversion strategy placeholder (not yet implemented).
+use crate::shared::{
+    close_positions_with_market_orders,
+    indicators::{Indicator, RollingStdDev},
+};
+use chrono::{DateTime, Utc};
+use rust_decimal::{prelude::FromPrimitive, Decimal};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, marker::PhantomData, time::Duration};
+use toucan_core::{
+    engine::{
+        risk::OrderSpec,
+        state::{
+            global::DefaultGlobalData,
+            instrument::{
+                data::{DefaultInstrumentMarketData, InstrumentDataState},
+                InstrumentState,
+            },
+            order::in_flight_recorder::InFlightRequestRecorder,
+            EngineState,
+        },
+        Processor,
+    },
+    Timed,
+};
+use toucan_data::event::{DataKind, MarketEvent};
+use toucan_execution::{
+    order::{
+        id::{ClientOrderId, StrategyId},
+        request::{OrderRequestCancel, OrderRequestOpen, RequestOpen},
+        OrderKey, OrderKind, TimeInForce,
+    },
+    AccountEvent, AssetIndex, ExchangeIndex, InstrumentIndex,
+};
+use toucan_instrument::{
+    exchange::ExchangeId,
+    instrument::spec::{InstrumentSpec, OrderQuantityUnits, RoundingPolicy},
+    Side,
+};
+use toucan_trader::{
+    AlgoStrategy, ClosePositionsStrategy, OnDisconnectStrategy, OnTradingDisabled,
+};
 
-pub struct MeanReversionStrategy;
+/// Configuration for the [`MeanReversionStrategy`] and its [`MeanReversionData`] indicators.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct MeanReversionConfig {
+    /// Number of bars in the rolling mean & standard deviation (ie/ Bollinger band period).
+    pub period: usize,
+
+    /// Absolute z-score beyond which a position is entered against the move (ie/ Bollinger band
+    /// width, in standard deviations).
+    pub entry_z: Decimal,
+
+    /// Absolute z-score within which an open position is exited.
+    pub exit_z: Decimal,
+
+    /// Maximum time a position is held before it is exited, regardless of the z-score.
+    #[serde(default)]
+    pub max_holding: Option<Duration>,
+
+    /// Quote notional allocated to each entry, converted into the instrument quantity units and
+    /// rounded down to its lot size.
+    pub notional: Decimal,
+
+    /// Enter short positions when the price stretches above the upper band. Long-only by
+    /// default.
+    #[serde(default)]
+    pub allow_short: bool,
+}
+
+impl Default for MeanReversionConfig {
+    fn default() -> Self {
+        Self {
+            period: 20,
+            entry_z: Decimal::TWO,
+            exit_z: Decimal::new(5, 1),
+            max_holding: None,
+            notional: Decimal::ONE_THOUSAND,
+            allow_short: false,
+        }
+    }
+}
+
+/// [`InstrumentDataState`] maintaining the [`MeanReversionStrategy`] signal pipeline.
+///
+/// Every `Candle` close is a bar, as is every public trade price, so the strategy can run on
+/// either. Bars older than the previous bar are ignored.
+///
+/// A bar crossing outside the entry band sets the pending [`MeanReversionData::signal`] (on the
+/// side that fades the move), which is consumed once an open order on the same side is sent, and
+/// is cleared if the price reverts inside the entry band before it is traded. This ensures each
+/// band excursion is traded at most once (eg/ no re-entry after a `max_holding` exit).
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct MeanReversionData {
+    /// Latest `OrderBookL1` and traded price.
+    pub market: DefaultInstrumentMarketData,
+
+    /// Latest bar close.
+    pub close: Option<Timed<Decimal>>,
+
+    /// Latest rolling mean (ie/ Bollinger middle band), once warmed up.
+    pub mean: Option<Decimal>,
+
+    /// Latest rolling (population) standard deviation, once warmed up.
+    pub std_dev: Option<Decimal>,
+
+    /// Latest z-score of the close, once warmed up and if the standard deviation is non-zero.
+    pub zscore: Option<Decimal>,
+
+    /// Pending band excursion signal that has not been traded yet.
+    pub signal: Option<Side>,
+
+    entry_z: Decimal,
+    window: RollingStdDev,
+}
+
+impl MeanReversionData {
+    /// Construct an empty [`MeanReversionData`] using the period & entry band of the provided
+    /// [`MeanReversionConfig`].
+    pub fn new(config: &MeanReversionConfig) -> Self {
+        Self {
+            market: DefaultInstrumentMarketData::default(),
+            close: None,
+            mean: None,
+            std_dev: None,
+            zscore: None,
+            signal: None,
+            entry_z: config.entry_z.abs(),
+            window: RollingStdDev::new(config.period),
+        }
+    }
+
+    /// Bollinger `(lower, upper)` bands `width` standard deviations around the rolling mean,
+    /// once warmed up.
+    pub fn bands(&self, width: Decimal) -> Option<(Decimal, Decimal)> {
+        let distance = self.std_dev? * width;
+        let mean = self.mean?;
+        Some((mean - distance, mean + distance))
+    }
+
+    /// Side that fades a z-score outside the entry band (ie/ `Buy` below the lower band and
+    /// `Sell` above the upper band).
+    fn excursion(&self, zscore: Option<Decimal>) -> Option<Side> {
+        let zscore = zscore?;
+        if zscore <= -self.entry_z {
+            Some(Side::Buy)
+        } else if zscore >= self.entry_z {
+            Some(Side::Sell)
+        } else {
+            None
+        }
+    }
+
+    fn update_bar(&mut self, time: DateTime<Utc>, close: f64) {
+        if self.close.as_ref().is_some_and(|last| time < last.time) {
+            return;
+        }
+
+        let Some(close) = Decimal::from_f64(close) else {
+            return;
+        };
+
+        let previous = self.excursion(self.zscore);
+
+        self.close = Some(Timed::new(close, time));
+        self.std_dev = self.window.update(close);
+        self.mean = self.window.mean();
+        self.zscore = match (self.mean, self.std_dev) {
+            (Some(mean), Some(std_dev)) if !std_dev.is_zero() => Some((close - mean) / std_dev),
+            _ => None,
+        };
+
+        let current = self.excursion(self.zscore);
+        if current.is_some() && current != previous {
+            self.signal = current;
+        } else if current.is_none() {
+            self.signal = None;
+        }
+    }
+}
+
+impl Default for MeanReversionData {
+    fn default() -> Self {
+        Self::new(&MeanReversionConfig::default())
+    }
+}
+
+impl InstrumentDataState for MeanReversionData {
+    type MarketEventKind = DataKind;
+
+    fn price(&self) -> Option<Decimal> {
+        self.market
+            .price()
+            .or(self.close.as_ref().map(|close| close.value))
+    }
+}
+
+impl<InstrumentKey> Processor<&MarketEvent<InstrumentKey, DataKind>> for MeanReversionData {
+    type Audit = ();
+
+    fn process(&mut self, event: &MarketEvent<InstrumentKey, DataKind>) -> Self::Audit {
+        self.market.process(event);
+
+        match &event.kind {
+            DataKind::Candle(candle) => self.update_bar(event.time_exchange, candle.close),
+            DataKind::Trade(trade) => self.update_bar(event.time_exchange, trade.price),
+            _ => {}
+        }
+    }
+}
+
+impl<ExchangeKey, AssetKey, InstrumentKey>
+    Processor<&AccountEvent<ExchangeKey, AssetKey, InstrumentKey>> for MeanReversionData
+{
+    type Audit = ();
+
+    fn process(&mut self, _: &AccountEvent<ExchangeKey, AssetKey, InstrumentKey>) -> Self::Audit {}
+}
+
+impl<ExchangeKey, InstrumentKey> InFlightRequestRecorder<ExchangeKey, InstrumentKey>
+    for MeanReversionData
+{
+    fn record_in_flight_cancel(&mut self, _: &OrderRequestCancel<ExchangeKey, InstrumentKey>) {}
+
+    fn record_in_flight_open(&mut self, request: &OrderRequestOpen<ExchangeKey, InstrumentKey>) {
+        if self.signal == Some(request.state.side) {
+            self.signal = None;
+        }
+    }
+}
+
+/// Bollinger band / z-score mean reversion strategy.
+///
+/// For every instrument without in-flight orders, the strategy sends an IOC market order to:
+/// 1. Enter against a pending band excursion when flat.
+/// 2. Exit (and reverse if `allow_short`) on a band excursion against the open position.
+/// 3. Exit once the z-score reverts to within `exit_z` of the mean.
+/// 4. Exit once the position has been held for longer than `max_holding`.
+///
+/// Entry quantities are rounded down to the [`InstrumentSpec`] quantity increment of the
+/// instrument (eg/ B3 round lots of 100 shares) and skipped if below its minimum quantity or
+/// notional. Instruments without a configured spec are entered with the unrounded notional
+/// equivalent quantity.
+///
+/// Signals are read from the [`MeanReversionData`] of each instrument alone, so any `GlobalData`
+/// can back the [`EngineState`].
+#[derive(Debug, Clone)]
+pub struct MeanReversionStrategy<GlobalData = DefaultGlobalData> {
+    pub id: StrategyId,
+    pub config: MeanReversionConfig,
+
+    /// [`OrderSpec`]s used to size entries, keyed by instrument.
+    pub specs: HashMap<InstrumentIndex, OrderSpec>,
+    phantom: PhantomData<fn() -> GlobalData>,
+}
+
+impl<GlobalData> MeanReversionStrategy<GlobalData> {
+    /// Construct a new [`MeanReversionStrategy`] without any [`InstrumentSpec`]s.
+    pub fn new(id: StrategyId, config: MeanReversionConfig) -> Self {
+        Self {
+            id,
+            config,
+            specs: HashMap::new(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Configure the [`InstrumentSpec`] & contract size used to size entries for an individual
+    /// instrument.
+    pub fn with_instrument_spec(
+        mut self,
+        instrument: InstrumentIndex,
+        spec: InstrumentSpec<AssetIndex>,
+        contract_size: Decimal,
+    ) -> Self {
+        self.specs.insert(
+            instrument,
+            OrderSpec {
+                spec,
+                contract_size,
+            },
+        );
+        self
+    }
+
+    /// Construct the [`MeanReversionData`] required for each instrument of the [`EngineState`].
+    pub fn instrument_data(&self) -> MeanReversionData {
+        MeanReversionData::new(&self.config)
+    }
+
+    /// Quantity entered on the provided side at the provided price, or `None` if the entry is
+    /// not allowed or does not satisfy the instrument [`InstrumentSpec`].
+    fn entry_quantity(
+        &self,
+        instrument: &InstrumentIndex,
+        side: Side,
+        price: Decimal,
+    ) -> Option<Decimal> {
+        if side == Side::Sell && !self.config.allow_short {
+            return None;
+        }
+
+        let Some(OrderSpec {
+            spec,
+            contract_size,
+        }) = self.specs.get(instrument)
+        else {
+            return self.config.notional.checked_div(price);
+        };
+
+        let quantity = OrderQuantityUnits::Quote
+            .convert(
+                self.config.notional,
+                &spec.quantity.unit,
+                price,
+                *contract_size,
+            )
+            .ok()?;
+        let quantity = spec.quantity.round(quantity, RoundingPolicy::Down);
+
+        spec.validate_quantity(price, quantity, *contract_size)
+            .ok()
+            .map(|_| quantity)
+    }
+
+    fn open_request(
+        &self,
+        state: &InstrumentState<MeanReversionData>,
+    ) -> Option<OrderRequestOpen<ExchangeIndex, InstrumentIndex>> {
+        if !state.orders.0.is_empty() {
+            return None;
+        }
+
+        let price = state.data.price()?;
+
+        let (side, quantity) = match (state.position.current.as_ref(), state.data.signal) {
+            (None, Some(signal)) => (signal, self.entry_quantity(&state.key, signal, price)?),
+            (Some(position), Some(signal)) if signal != position.side => (
+                signal,
+                position.quantity_abs
+                    + self
+                        .entry_quantity(&state.key, signal, price)
+                        .unwrap_or_default(),
+            ),
+            (Some(position), _) => {
+                let held_too_long = self
+                    .config
+                    .max_holding
+                    .zip(state.data.close.as_ref())
+                    .is_some_and(|(max_holding, close)| {
+                        (close.time - position.time_enter)
+                            .to_std()
+                            .is_ok_and(|held| held >= max_holding)
+                    });
+
+                let reverted = state.data.zscore.is_some_and(|zscore| match position.side {
+                    Side::Buy => zscore >= -self.config.exit_z,
+                    Side::Sell => zscore <= self.config.exit_z,
+                });
+
+                if !held_too_long && !reverted {
+                    return None;
+                }
+
+                match position.side {
+                    Side::Buy => (Side::Sell, position.quantity_abs),
+                    Side::Sell => (Side::Buy, position.quantity_abs),
+                }
+            }
+            (None, None) => return None,
+        };
+
+        if quantity.is_zero() {
+            return None;
+        }
+
+        Some(OrderRequestOpen {
+            key: OrderKey {
+                exchange: state.instrument.exchange.to_string(),
+                instrument: state.key.clone(),
+                strategy: self.id.clone(),
+                cid: ClientOrderId::random(),
+            },
+            state: RequestOpen {
+                side,
+                price,
+                quantity,
+                kind: OrderKind::Market,
+                time_in_force: TimeInForce::ImmediateOrCancel,
+            },
+        })
+    }
+}
+
+impl<GlobalData> AlgoStrategy for MeanReversionStrategy<GlobalData> {
+    type State = EngineState<GlobalData, MeanReversionData>;
+
+    fn generate_algo_orders(
+        &self,
+        state: &Self::State,
+    ) -> (
+        impl IntoIterator<Item = OrderRequestCancel<ExchangeIndex, InstrumentIndex>>,
+        impl IntoIterator<Item = OrderRequestOpen<ExchangeIndex, InstrumentIndex>>,
+    ) {
+        let opens = state
+            .instruments
+            .0
+            .values()
+            .filter_map(|state| self.open_request(state))
+            .collect::<Vec<_>>();
+
+        (std::iter::empty(), opens)
+    }
+}
+
+impl<GlobalData> ClosePositionsStrategy for MeanReversionStrategy<GlobalData> {
+    type State = EngineState<GlobalData, MeanReversionData>;
+
+    fn close_positions_requests<'a>(
+        &'a self,
+        state: &'a Self::State,
+        filter: &'a impl std::fmt::Debug,
+    ) -> (
+        impl IntoIterator<Item = OrderRequestCancel<ExchangeIndex, InstrumentIndex>> + 'a,
+        impl IntoIterator<Item = OrderRequestOpen<ExchangeIndex, InstrumentIndex>> + 'a,
+    )
+    where
+        ExchangeIndex: 'a,
+        AssetIndex: 'a,
+        InstrumentIndex: 'a,
+    {
+        (
+            std::iter::empty(),
+            close_positions_with_market_orders(&self.id, &state.instruments, filter),
+        )
+    }
+}
+
+impl<Clock, State, ExecutionTxs, Risk, GlobalData>
+    OnDisconnectStrategy<Clock, State, ExecutionTxs, Risk> for MeanReversionStrategy<GlobalData>
+{
+    type OnDisconnect = ();
+
+    fn on_disconnect(_: ExchangeId) -> Self::OnDisconnect {}
+}
+
+impl<Clock, State, ExecutionTxs, Risk, GlobalData>
+    OnTradingDisabled<Clock, State, ExecutionTxs, Risk> for MeanReversionStrategy<GlobalData>
+{
+    type OnTradingDisabled = ();
+
+    fn on_trading_disabled() -> Self::OnTradingDisabled {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use toucan_data::subscription::candle::Candle;
+    use toucan_instrument::instrument::spec::{
+        InstrumentSpecNotional, InstrumentSpecPrice, InstrumentSpecQuantity,
+    };
+
+    fn candle(bar: i64, close: f64) -> MarketEvent<InstrumentIndex, DataKind> {
+        let time = DateTime::<Utc>::MIN_UTC + chrono::TimeDelta::days(bar);
+        MarketEvent {
+            time_exchange: time,
+            time_received: time,
+            exchange: ExchangeId::Mock,
+            instrument: "PETR4".to_string(),
+            kind: DataKind::Candle(Candle {
+                close_time: time,
+                open: close,
+                high: close,
+                low: close,
+                close,
+                volume: 1.0,
+                trade_count: 1,
+            }),
+        }
+    }
+
+    fn open(side: Side) -> OrderRequestOpen<ExchangeIndex, InstrumentIndex> {
+        OrderRequestOpen {
+            key: OrderKey {
+                exchange: "Mock".to_string(),
+                instrument: "PETR4".to_string(),
+                strategy: StrategyId::new("mean_reversion"),
+                cid: ClientOrderId::new("cid"),
+            },
+            state: RequestOpen {
+                side,
+                price: Decimal::ONE,
+                quantity: Decimal::ONE,
+                kind: OrderKind::Market,
+                time_in_force: TimeInForce::ImmediateOrCancel,
+            },
+        }
+    }
+
+    #[test]
+    fn test_mean_reversion_data_signal() {
+        let config = MeanReversionConfig {
+            period: 4,
+            entry_z: dec!(1.5),
+            ..MeanReversionConfig::default()
+        };
+        let mut data = MeanReversionData::new(&config);
+
+        // TC0: flat closes warm up the window with a zero standard deviation, so no z-score
+        for bar in 0..4 {
+            data.process(&candle(bar, 10.0));
+        }
+        assert_eq!(data.mean, Some(dec!(10)), "TC0 failed");
+        assert_eq!(data.std_dev, Some(Decimal::ZERO), "TC0 failed");
+        assert_eq!(data.zscore, None, "TC0 failed");
+
+        // TC1: close below the lower band signals a Buy (window [10, 10, 10, 6]: mean 9, std
+        // sqrt(3), z = -3 / sqrt(3) = -sqrt(3))
+        data.process(&candle(4, 6.0));
+        assert_eq!(data.mean, Some(dec!(9)), "TC1 failed");
+        assert!(data.zscore.unwrap() < dec!(-1.7), "TC1 failed");
+        assert_eq!(data.signal, Some(Side::Buy), "TC1 failed");
+        let (lower, upper) = data.bands(Decimal::ONE).unwrap();
+        assert_eq!(lower + upper, dec!(18), "TC1 failed");
+
+        // TC2: stale bars are ignored
+        data.process(&candle(0, 1.0));
+        assert_eq!(data.price(), Some(dec!(6)), "TC2 failed");
+
+        // TC3: opposite side open does not consume the signal, same side open does
+        data.record_in_flight_open(&open(Side::Sell));
+        assert_eq!(data.signal, Some(Side::Buy), "TC3 failed");
+        data.record_in_flight_open(&open(Side::Buy));
+        assert_eq!(data.signal, None, "TC3 failed");
+
+        // TC4: staying outside the band does not signal the same excursion twice
+        data.process(&candle(5, 2.0));
+        assert!(data.zscore.unwrap() < dec!(-1.5), "TC4 failed");
+        assert_eq!(data.signal, None, "TC4 failed");
+
+        // TC5: untraded signal is cleared once the close reverts inside the band
+        let mut data = MeanReversionData::new(&config);
+        for (bar, close) in [10.0, 10.0, 10.0, 10.0, 6.0, 9.0].into_iter().enumerate() {
+            data.process(&candle(bar as i64, close));
+        }
+        assert_eq!(data.signal, None, "TC5 failed");
+    }
+
+    #[test]
+    fn test_mean_reversion_entry_quantity() {
+        struct TestCase {
+            strategy: MeanReversionStrategy,
+            side: Side,
+            price: Decimal,
+            expected: Option<Decimal>,
+        }
+
+        let config = MeanReversionConfig {
+            notional: dec!(10_000),
+            ..MeanReversionConfig::default()
+        };
+        let round_lots = InstrumentSpec::new(
+            InstrumentSpecPrice::new(dec!(0.01), dec!(0.01)),
+            InstrumentSpecQuantity::new(
+                OrderQuantityUnits::Asset("PETR4".to_string()),
+                dec!(100),
+                dec!(100),
+            ),
+            InstrumentSpecNotional::new(dec!(0)),
+        );
+        let strategy = MeanReversionStrategy::new(StrategyId::new("mean_reversion"), config)
+            .with_instrument_spec("PETR4".to_string(), round_lots, Decimal::ONE);
+
+        let cases = vec![
+            // TC0: notional quantity is rounded down to the round lot (10_000 / 28 = 357.14)
+            TestCase {
+                strategy: strategy.clone(),
+                side: Side::Buy,
+                price: dec!(28),
+                expected: Some(dec!(300)),
+            },
+            // TC1: notional quantity below the minimum lot is not entered
+            TestCase {
+                strategy: strategy.clone(),
+                side: Side::Buy,
+                price: dec!(120),
+                expected: None,
+            },
+            // TC2: short entries are not allowed by default
+            TestCase {
+                strategy: strategy.clone(),
+                side: Side::Sell,
+                price: dec!(28),
+                expected: None,
+            },
+            // TC3: instruments without a spec are entered with the unrounded quantity
+            TestCase {
+                strategy: MeanReversionStrategy::new(StrategyId::new("mean_reversion"), config),
+                side: Side::Buy,
+                price: dec!(25),
+                expected: Some(dec!(400)),
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let actual = test
+                .strategy
+                .entry_quantity(&"PETR4".to_string(), test.side, test.price);
+            assert_eq!(actual, test.expected, "TC{index} failed");
+        }
+    }
+}
@@ -1,4 +1,620 @@
+//! Market microstructure strategies.
+//!
+//! Contains the [`MarketMakerStrategy`], an inventory-aware market maker in the style of
+//! Avellaneda & Stoikov ("High-frequency trading in a limit order book", 2008). Bid and ask
+//! limit orders are quoted around a reservation price that is skewed against the current
+//! inventory, with a spread that widens with the mid-price volatility.
+//!
+//! Quotes are maintained purely through the cancel & open outputs of [`AlgoStrategy`]: resting
+//! quotes that drift from the target quote (or outlive `max_quote_age`) are cancelled, and a new
+//! quote is opened once a side has no active order.
+//!
+//! # Example
+//! ```rust,ignore
+//! let strategy = MarketMakerStrategy::new(
+//!     StrategyId::new("market_maker"),
+//!     MarketMakerConfig::default(),
+//! )
+//! .with_instrument_spec("PETR4".to_string(), petr4_spec, Decimal::ONE);
+//!
+//! let state = EngineState::builder(&instruments, DefaultGlobalData, |_| {
+//!     strategy.instrument_data()
+//! })
+//! .build();
+//! ```
 
-//! Microstructure strategy placeholder (not yet implemented).
+use crate::shared::{
+    close_positions_with_market_orders, filtered_instruments,
+    indicators::{Indicator, RollingStdDev},
+};
+use chrono::{DateTime, Utc};
+use rust_decimal::{Decimal, MathematicalOps};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, marker::PhantomData, time::Duration};
+use toucan_core::{
+    engine::{
+        risk::OrderSpec,
+        state::{
+            global::DefaultGlobalData,
+            instrument::{
+                data::{DefaultInstrumentMarketData, InstrumentDataState},
+                InstrumentState,
+            },
+            order::in_flight_recorder::InFlightRequestRecorder,
+            EngineState,
+        },
+        Processor,
+    },
+    Timed,
+};
+use toucan_data::event::{DataKind, MarketEvent};
+use toucan_execution::{
+    order::{
+        id::{ClientOrderId, StrategyId},
+        request::{OrderRequestCancel, OrderRequestOpen, RequestOpen},
+        state::ActiveOrderState,
+        OrderKey, OrderKind, TimeInForce,
+    },
+    AccountEvent, AssetIndex, ExchangeIndex, InstrumentIndex,
+};
+use toucan_instrument::{
+    exchange::ExchangeId,
+    instrument::spec::{InstrumentSpec, RoundingPolicy},
+    Side,
+};
+use toucan_trader::{
+    AlgoStrategy, ClosePositionsStrategy, OnDisconnectStrategy, OnTradingDisabled,
+};
 
-pub struct MicrostructureStrategy;
+/// Configuration for the [`MarketMakerStrategy`] and its [`MarketMakerData`] volatility
+/// estimate.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct MarketMakerConfig {
+    /// Inventory risk aversion (γ). Higher values skew quotes more aggressively against the
+    /// inventory and widen the spread.
+    pub risk_aversion: Decimal,
+
+    /// Order book liquidity (κ), ie/ decay rate of the fill intensity with the distance from the
+    /// mid-price, per unit of price.
+    pub order_book_liquidity: Decimal,
+
+    /// Remaining trading horizon (T - t), in mid-price observations. Held constant, which
+    /// approximates the infinite-horizon model.
+    pub horizon: Decimal,
+
+    /// Number of mid-price changes in the rolling volatility (σ) estimate.
+    pub volatility_period: usize,
+
+    /// Minimum distance between the bid & ask quotes.
+    pub min_spread: Decimal,
+
+    /// Quantity of each bid & ask quote. Inventory (q) is measured in units of this quantity.
+    pub quantity: Decimal,
+
+    /// Maximum absolute inventory. A side is not quoted if a fill would breach it.
+    pub max_inventory: Decimal,
+
+    /// Price distance from the target quote at which a resting quote is cancelled & replaced.
+    pub requote_threshold: Decimal,
+
+    /// Maximum time a quote rests before it is cancelled & replaced, regardless of its price.
+    #[serde(default)]
+    pub max_quote_age: Option<Duration>,
+}
+
+impl Default for MarketMakerConfig {
+    fn default() -> Self {
+        Self {
+            risk_aversion: Decimal::new(1, 1),
+            order_book_liquidity: Decimal::ONE_HUNDRED,
+            horizon: Decimal::ONE_HUNDRED,
+            volatility_period: 100,
+            min_spread: Decimal::new(1, 2),
+            quantity: Decimal::ONE_HUNDRED,
+            max_inventory: Decimal::ONE_THOUSAND,
+            requote_threshold: Decimal::new(1, 2),
+            max_quote_age: None,
+        }
+    }
+}
+
+impl MarketMakerConfig {
+    /// Avellaneda–Stoikov reservation price, ie/ the mid-price skewed against the inventory:
+    ///
+    /// `r = mid - q·γ·σ²·(T - t)`
+    pub fn reservation_price(
+        &self,
+        mid: Decimal,
+        inventory: Decimal,
+        volatility: Decimal,
+    ) -> Decimal {
+        let lots = inventory.checked_div(self.quantity).unwrap_or_default();
+        mid - lots * self.risk_aversion * volatility * volatility * self.horizon
+    }
+
+    /// Avellaneda–Stoikov optimal spread, floored at the `min_spread`:
+    ///
+    /// `δ = γ·σ²·(T - t) + (2 / γ)·ln(1 + γ / κ)`
+    pub fn optimal_spread(&self, volatility: Decimal) -> Decimal {
+        let inventory_risk = self.risk_aversion * volatility * volatility * self.horizon;
+
+        let liquidity = (Decimal::ONE
+            + self
+                .risk_aversion
+                .checked_div(self.order_book_liquidity)
+                .unwrap_or_default())
+        .checked_ln()
+        .zip(Decimal::TWO.checked_div(self.risk_aversion))
+        .map(|(ln, scale)| ln * scale)
+        .unwrap_or_default();
+
+        (inventory_risk + liquidity).max(self.min_spread)
+    }
+}
+
+/// Target bid & ask quotes of the [`MarketMakerStrategy`] for an instrument.
+///
+/// A side is `None` if quoting it could breach the `max_inventory`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct Quotes {
+    pub reservation: Decimal,
+    pub bid: Option<Decimal>,
+    pub ask: Option<Decimal>,
+}
+
+impl Quotes {
+    /// Target quote price for the provided side.
+    pub fn price(&self, side: Side) -> Option<Decimal> {
+        match side {
+            Side::Buy => self.bid,
+            Side::Sell => self.ask,
+        }
+    }
+}
+
+/// [`InstrumentDataState`] maintaining the mid-price & volatility used by the
+/// [`MarketMakerStrategy`].
+///
+/// Every `OrderBookL1` update with both a best bid & ask is a mid-price observation. The
+/// volatility (σ) is the rolling standard deviation of the changes between consecutive
+/// mid-prices. Observations older than the previous observation are ignored.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct MarketMakerData {
+    /// Latest `OrderBookL1` and traded price.
+    pub market: DefaultInstrumentMarketData,
+
+    /// Latest mid-price.
+    pub mid: Option<Timed<Decimal>>,
+
+    /// Latest mid-price change volatility, once warmed up.
+    pub volatility: Option<Decimal>,
+
+    changes: RollingStdDev,
+}
+
+impl MarketMakerData {
+    /// Construct an empty [`MarketMakerData`] using the volatility period of the provided
+    /// [`MarketMakerConfig`].
+    pub fn new(config: &MarketMakerConfig) -> Self {
+        Self {
+            market: DefaultInstrumentMarketData::default(),
+            mid: None,
+            volatility: None,
+            changes: RollingStdDev::new(config.volatility_period),
+        }
+    }
+
+    fn update_mid(&mut self, time: DateTime<Utc>, mid: Decimal) {
+        if self.mid.as_ref().is_some_and(|last| time < last.time) {
+            return;
+        }
+
+        if let Some(previous) = self.mid.replace(Timed::new(mid, time)) {
+            self.volatility = self.changes.update(mid - previous.value);
+        }
+    }
+}
+
+impl Default for MarketMakerData {
+    fn default() -> Self {
+        Self::new(&MarketMakerConfig::default())
+    }
+}
+
+impl InstrumentDataState for MarketMakerData {
+    type MarketEventKind = DataKind;
+
+    fn price(&self) -> Option<Decimal> {
+        self.market.price()
+    }
+}
+
+impl<InstrumentKey> Processor<&MarketEvent<InstrumentKey, DataKind>> for MarketMakerData {
+    type Audit = ();
+
+    fn process(&mut self, event: &MarketEvent<InstrumentKey, DataKind>) -> Self::Audit {
+        self.market.process(event);
+
+        if let DataKind::OrderBookL1(l1) = &event.kind {
+            if let Some(mid) = l1.mid_price() {
+                self.update_mid(event.time_exchange, mid);
+            }
+        }
+    }
+}
+
+impl<ExchangeKey, AssetKey, InstrumentKey>
+    Processor<&AccountEvent<ExchangeKey, AssetKey, InstrumentKey>> for MarketMakerData
+{
+    type Audit = ();
+
+    fn process(&mut self, _: &AccountEvent<ExchangeKey, AssetKey, InstrumentKey>) -> Self::Audit {}
+}
+
+impl<ExchangeKey, InstrumentKey> InFlightRequestRecorder<ExchangeKey, InstrumentKey>
+    for MarketMakerData
+{
+    fn record_in_flight_cancel(&mut self, _: &OrderRequestCancel<ExchangeKey, InstrumentKey>) {}
+
+    fn record_in_flight_open(&mut self, _: &OrderRequestOpen<ExchangeKey, InstrumentKey>) {}
+}
+
+/// Inventory-aware (Avellaneda–Stoikov) market maker.
+///
+/// For every instrument with a warmed up [`MarketMakerData`] volatility, the strategy targets
+/// a bid & ask `optimal_spread` apart around the inventory skewed `reservation_price`, rounded
+/// passively to the [`InstrumentSpec`] tick size of the instrument (if configured). Each side:
+/// 1. Cancels resting quotes further than `requote_threshold` from the target quote, older
+///    than `max_quote_age`, or on a side that is no longer quoted due to `max_inventory`.
+/// 2. Opens a post-only `GoodUntilCancelled` limit quote once it has no active orders (ie/ the
+///    replacement is sent once the cancel is acknowledged).
+///
+/// Without a warmed up volatility (eg/ no two-sided market) every resting quote is cancelled.
+///
+/// Quotes are derived from the [`MarketMakerData`] & orders of each instrument alone, leaving the
+/// `GlobalData` of the [`EngineState`] as a free type parameter.
+#[derive(Debug, Clone)]
+pub struct MarketMakerStrategy<GlobalData = DefaultGlobalData> {
+    pub id: StrategyId,
+    pub config: MarketMakerConfig,
+
+    /// [`OrderSpec`]s used to round & validate quotes, keyed by instrument.
+    pub specs: HashMap<InstrumentIndex, OrderSpec>,
+    phantom: PhantomData<fn() -> GlobalData>,
+}
+
+impl<GlobalData> MarketMakerStrategy<GlobalData> {
+    /// Construct a new [`MarketMakerStrategy`] without any [`InstrumentSpec`]s.
+    pub fn new(id: StrategyId, config: MarketMakerConfig) -> Self {
+        Self {
+            id,
+            config,
+            specs: HashMap::new(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Configure the [`InstrumentSpec`] & contract size used to round & validate quotes for an
+    /// individual instrument.
+    pub fn with_instrument_spec(
+        mut self,
+        instrument: InstrumentIndex,
+        spec: InstrumentSpec<AssetIndex>,
+        contract_size: Decimal,
+    ) -> Self {
+        self.specs.insert(
+            instrument,
+            OrderSpec {
+                spec,
+                contract_size,
+            },
+        );
+        self
+    }
+
+    /// Construct the [`MarketMakerData`] required for each instrument of the [`EngineState`].
+    pub fn instrument_data(&self) -> MarketMakerData {
+        MarketMakerData::new(&self.config)
+    }
+
+    /// Target [`Quotes`] for an instrument, given its signed inventory (positive when long).
+    pub fn quotes(
+        &self,
+        instrument: &InstrumentIndex,
+        data: &MarketMakerData,
+        inventory: Decimal,
+    ) -> Option<Quotes> {
+        let mid = data.mid.as_ref()?.value;
+        let volatility = data.volatility?;
+
+        let reservation = self.config.reservation_price(mid, inventory, volatility);
+        let half_spread = self.config.optimal_spread(volatility) / Decimal::TWO;
+
+        let quote = |side: Side, price: Decimal, inventory_after: Decimal| {
+            if inventory_after.abs() > self.config.max_inventory {
+                return None;
+            }
+
+            let Some(OrderSpec {
+                spec,
+                contract_size,
+            }) = self.specs.get(instrument)
+            else {
+                return (price > Decimal::ZERO).then_some(price);
+            };
+
+            let price = spec.price.round(price, side, RoundingPolicy::Passive);
+            spec.validate(price, self.config.quantity, *contract_size)
+                .ok()
+                .map(|_| price)
+        };
+
+        Some(Quotes {
+            reservation,
+            bid: quote(
+                Side::Buy,
+                reservation - half_spread,
+                inventory + self.config.quantity,
+            ),
+            ask: quote(
+                Side::Sell,
+                reservation + half_spread,
+                inventory - self.config.quantity,
+            ),
+        })
+    }
+
+    fn quote_requests(
+        &self,
+        state: &InstrumentState<MarketMakerData>,
+    ) -> (Vec<OrderRequestCancel>, Vec<OrderRequestOpen>) {
+        let inventory = state
+            .position
+            .current
+            .as_ref()
+            .map(|position| match position.side {
+                Side::Buy => position.quantity_abs,
+                Side::Sell => -position.quantity_abs,
+            })
+            .unwrap_or_default();
+
+        let quotes = self.quotes(&state.key, &state.data, inventory);
+        let now = state.data.mid.as_ref().map(|mid| mid.time);
+
+        let mut cancels = Vec::new();
+        let mut opens = Vec::new();
+
+        for side in [Side::Buy, Side::Sell] {
+            let target = quotes.and_then(|quotes| quotes.price(side));
+
+            let mut active = false;
+            for order in state
+                .orders
+                .0
+                .values()
+                .filter(|order| order.key.strategy == self.id && order.side == side)
+            {
+                active = true;
+
+                let ActiveOrderState::Open(open) = &order.state else {
+                    continue;
+                };
+
+                let expired = self
+                    .config
+                    .max_quote_age
+                    .zip(now)
+                    .is_some_and(|(max_age, now)| {
+                        (now - open.time_exchange)
+                            .to_std()
+                            .is_ok_and(|age| age >= max_age)
+                    });
+
+                let stale = target.is_none_or(|target| {
+                    (order.price - target).abs() >= self.config.requote_threshold
+                });
+
+                if expired || stale {
+                    cancels.extend(order.to_request_cancel());
+                }
+            }
+
+            if let (false, Some(price)) = (active, target) {
+                opens.push(OrderRequestOpen {
+                    key: OrderKey {
+                        exchange: state.instrument.exchange.to_string(),
+                        instrument: state.key.clone(),
+                        strategy: self.id.clone(),
+                        cid: ClientOrderId::random(),
+                    },
+                    state: RequestOpen {
+                        side,
+                        price,
+                        quantity: self.config.quantity,
+                        kind: OrderKind::Limit,
+                        time_in_force: TimeInForce::GoodUntilCancelled { post_only: true },
+                    },
+                });
+            }
+        }
+
+        (cancels, opens)
+    }
+}
+
+impl<GlobalData> AlgoStrategy for MarketMakerStrategy<GlobalData> {
+    type State = EngineState<GlobalData, MarketMakerData>;
+
+    fn generate_algo_orders(
+        &self,
+        state: &Self::State,
+    ) -> (
+        impl IntoIterator<Item = OrderRequestCancel<ExchangeIndex, InstrumentIndex>>,
+        impl IntoIterator<Item = OrderRequestOpen<ExchangeIndex, InstrumentIndex>>,
+    ) {
+        state
+            .instruments
+            .0
+            .values()
+            .map(|state| self.quote_requests(state))
+            .fold(
+                (Vec::new(), Vec::new()),
+                |(mut cancels, mut opens), (instrument_cancels, instrument_opens)| {
+                    cancels.extend(instrument_cancels);
+                    opens.extend(instrument_opens);
+                    (cancels, opens)
+                },
+            )
+    }
+}
+
+impl<GlobalData> ClosePositionsStrategy for MarketMakerStrategy<GlobalData> {
+    type State = EngineState<GlobalData, MarketMakerData>;
+
+    /// Cancel every resting quote & close every open position with IOC market orders.
+    fn close_positions_requests<'a>(
+        &'a self,
+        state: &'a Self::State,
+        filter: &'a impl std::fmt::Debug,
+    ) -> (
+        impl IntoIterator<Item = OrderRequestCancel<ExchangeIndex, InstrumentIndex>> + 'a,
+        impl IntoIterator<Item = OrderRequestOpen<ExchangeIndex, InstrumentIndex>> + 'a,
+    )
+    where
+        ExchangeIndex: 'a,
+        AssetIndex: 'a,
+        InstrumentIndex: 'a,
+    {
+        let cancels = filtered_instruments(&state.instruments, filter)
+            .flat_map(|state| state.orders.0.values())
+            .filter(|order| order.key.strategy == self.id)
+            .filter_map(|order| order.to_request_cancel());
+
+        (
+            cancels,
+            close_positions_with_market_orders(&self.id, &state.instruments, filter),
+        )
+    }
+}
+
+impl<Clock, State, ExecutionTxs, Risk, GlobalData>
+    OnDisconnectStrategy<Clock, State, ExecutionTxs, Risk> for MarketMakerStrategy<GlobalData>
+{
+    type OnDisconnect = ();
+
+    fn on_disconnect(_: ExchangeId) -> Self::OnDisconnect {}
+}
+
+impl<Clock, State, ExecutionTxs, Risk, GlobalData>
+    OnTradingDisabled<Clock, State, ExecutionTxs, Risk> for MarketMakerStrategy<GlobalData>
+{
+    type OnTradingDisabled = ();
+
+    fn on_trading_disabled() -> Self::OnTradingDisabled {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use toucan_data::{books::Level, subscription::book::OrderBookL1};
+    use toucan_instrument::instrument::spec::{
+        InstrumentSpecNotional, InstrumentSpecPrice, InstrumentSpecQuantity, OrderQuantityUnits,
+    };
+
+    fn l1(second: i64, bid: Decimal, ask: Decimal) -> MarketEvent<InstrumentIndex, DataKind> {
+        let time = DateTime::<Utc>::MIN_UTC + chrono::TimeDelta::seconds(second);
+        MarketEvent {
+            time_exchange: time,
+            time_received: time,
+            exchange: ExchangeId::Mock,
+            instrument: "PETR4".to_string(),
+            kind: DataKind::OrderBookL1(OrderBookL1 {
+                last_update_time: time,
+                best_bid: Some(Level::new(bid, dec!(1_000))),
+                best_ask: Some(Level::new(ask, dec!(1_000))),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_market_maker_quotes() {
+        struct TestCase {
+            inventory: Decimal,
+            expected: (Option<Decimal>, Option<Decimal>),
+        }
+
+        let config = MarketMakerConfig {
+            volatility_period: 2,
+            max_inventory: dec!(200),
+            ..MarketMakerConfig::default()
+        };
+        let spec = InstrumentSpec::new(
+            InstrumentSpecPrice::new(dec!(0.01), dec!(0.01)),
+            InstrumentSpecQuantity::new(
+                OrderQuantityUnits::Asset("PETR4".to_string()),
+                dec!(100),
+                dec!(100),
+            ),
+            InstrumentSpecNotional::new(Decimal::ZERO),
+        );
+        let strategy =
+            MarketMakerStrategy::<DefaultGlobalData>::new(StrategyId::new("market_maker"), config)
+                .with_instrument_spec("PETR4".to_string(), spec, Decimal::ONE);
+
+        // Mid-prices 30.00, 30.02, 30.00 => changes of +/-0.02 => σ = 0.02
+        let mut data = strategy.instrument_data();
+        data.process(&l1(0, dec!(29.99), dec!(30.01)));
+        assert_eq!(strategy.quotes(&"PETR4".to_string(), &data, dec!(0)), None);
+        data.process(&l1(1, dec!(30.01), dec!(30.03)));
+        data.process(&l1(2, dec!(29.99), dec!(30.01)));
+        assert_eq!(data.volatility, Some(dec!(0.02)));
+
+        // δ = 0.1·0.0004·100 + (2 / 0.1)·ln(1.001) ≈ 0.024, r = 30 - q·0.004
+        let cases = vec![
+            // TC0: flat inventory quotes symmetrically around the mid-price
+            TestCase {
+                inventory: dec!(0),
+                expected: (Some(dec!(29.98)), Some(dec!(30.02))),
+            },
+            // TC1: long inventory skews the reservation price & quotes down
+            TestCase {
+                inventory: dec!(100),
+                expected: (Some(dec!(29.98)), Some(dec!(30.01))),
+            },
+            // TC2: short inventory skews the reservation price & quotes up
+            TestCase {
+                inventory: dec!(-100),
+                expected: (Some(dec!(29.99)), Some(dec!(30.02))),
+            },
+            // TC3: bid is not quoted if a fill would breach the max inventory
+            TestCase {
+                inventory: dec!(200),
+                expected: (None, Some(dec!(30.01))),
+            },
+            // TC4: ask is not quoted if a fill would breach the max inventory
+            TestCase {
+                inventory: dec!(-200),
+                expected: (Some(dec!(29.99)), None),
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let quotes = strategy
+                .quotes(&"PETR4".to_string(), &data, test.inventory)
+                .unwrap();
+            assert_eq!((quotes.bid, quotes.ask), test.expected, "TC{index} failed");
+            assert_eq!(
+                quotes.reservation,
+                dec!(30) - test.inventory / dec!(100) * dec!(0.004),
+                "TC{index} failed"
+            );
+        }
+
+        // TC5: spread widens with volatility
+        assert!(
+            config.optimal_spread(dec!(0.1)) > config.optimal_spread(dec!(0.02)),
+            "TC5 failed"
+        );
+    }
+}
@@ -1,5 +1,495 @@
+//! Momentum (trend-following) strategy.
+//!
+//! Enters on a fast/slow simple moving-average crossover and protects open positions with an
+//! ATR-based stop. Indicators are maintained by the [`MomentumData`] instrument data state, so
+//! the [`MomentumStrategy`] only reads the [`EngineState`] and runs unchanged in backtests
+//! (`run_backtests`) and live.
+//!
+//! # Example
+//! ```rust,ignore
+//! let strategy = MomentumStrategy::new(StrategyId::new("momentum"), MomentumConfig::default());
+//!
+//! let state = EngineState::builder(&instruments, DefaultGlobalData, |_| {
+//!     strategy.instrument_data()
+//! })
+//! .build();
+//! ```
 
-//! Momentum strategy placeholder (not yet implemented).
+use crate::shared::{
+    close_positions_with_market_orders,
+    indicators::{Atr, Bar, Indicator, Sma},
+};
+use chrono::{DateTime, Utc};
+use rust_decimal::{prelude::FromPrimitive, Decimal};
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+use toucan_core::{
+    engine::{
+        state::{
+            global::DefaultGlobalData,
+            instrument::{
+                data::{DefaultInstrumentMarketData, InstrumentDataState},
+                InstrumentState,
+            },
+            order::in_flight_recorder::InFlightRequestRecorder,
+            EngineState,
+        },
+        Processor,
+    },
+    Timed,
+};
+use toucan_data::event::{DataKind, MarketEvent};
+use toucan_execution::{
+    order::{
+        id::{ClientOrderId, StrategyId},
+        request::{OrderRequestCancel, OrderRequestOpen, RequestOpen},
+        OrderKey, OrderKind, TimeInForce,
+    },
+    AccountEvent, AssetIndex, ExchangeIndex, InstrumentIndex,
+};
+use toucan_instrument::{exchange::ExchangeId, Side};
+use toucan_trader::{
+    AlgoStrategy, ClosePositionsStrategy, OnDisconnectStrategy, OnTradingDisabled,
+};
 
-/// Placeholder struct.
-pub struct MomentumStrategy;
+/// Configuration for the [`MomentumStrategy`] and its [`MomentumData`] indicators.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct MomentumConfig {
+    /// Number of bars in the fast moving average.
+    pub fast_period: usize,
+
+    /// Number of bars in the slow moving average.
+    pub slow_period: usize,
+
+    /// Number of bars in the Average True Range.
+    pub atr_period: usize,
+
+    /// Distance of the protective stop from the average entry price, in ATRs.
+    pub atr_stop: Decimal,
+
+    /// Quantity entered on each crossover.
+    pub quantity: Decimal,
+
+    /// Enter short positions on bearish crossovers. Long-only by default, in which case bearish
+    /// crossovers only exit long positions.
+    #[serde(default)]
+    pub allow_short: bool,
+}
+
+impl Default for MomentumConfig {
+    fn default() -> Self {
+        Self {
+            fast_period: 10,
+            slow_period: 30,
+            atr_period: 14,
+            atr_stop: Decimal::TWO,
+            quantity: Decimal::ONE,
+            allow_short: false,
+        }
+    }
+}
+
+/// [`InstrumentDataState`] maintaining the [`MomentumStrategy`] signal pipeline.
+///
+/// Every `Candle` is a bar, as is every public trade (with high = low = close), so the strategy
+/// can run on either. Bars older than the previous bar are ignored.
+///
+/// A crossover sets the pending [`MomentumData::signal`], which is consumed once an open order
+/// on the same side is sent, so each crossover is traded at most once (eg/ no re-entry after a
+/// stop-out until the next crossover).
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct MomentumData {
+    /// Latest `OrderBookL1` and traded price.
+    pub market: DefaultInstrumentMarketData,
+
+    /// Latest bar close.
+    pub close: Option<Timed<Decimal>>,
+
+    /// Latest fast moving average, once warmed up.
+    pub fast: Option<Decimal>,
+
+    /// Latest slow moving average, once warmed up.
+    pub slow: Option<Decimal>,
+
+    /// Latest Average True Range, once warmed up.
+    pub atr: Option<Decimal>,
+
+    /// Pending crossover signal that has not been traded yet.
+    pub signal: Option<Side>,
+
+    last_trend: Option<Side>,
+    fast_mean: Sma,
+    slow_mean: Sma,
+    true_range: Atr,
+}
+
+impl MomentumData {
+    /// Construct an empty [`MomentumData`] using the indicator periods of the provided
+    /// [`MomentumConfig`].
+    pub fn new(config: &MomentumConfig) -> Self {
+        Self {
+            market: DefaultInstrumentMarketData::default(),
+            close: None,
+            fast: None,
+            slow: None,
+            atr: None,
+            signal: None,
+            last_trend: None,
+            fast_mean: Sma::new(config.fast_period),
+            slow_mean: Sma::new(config.slow_period),
+            true_range: Atr::new(config.atr_period),
+        }
+    }
+
+    /// Current trend direction (ie/ side of the fast average relative to the slow average), once
+    /// both averages are warmed up.
+    pub fn trend(&self) -> Option<Side> {
+        let spread = self.fast? - self.slow?;
+        if spread > Decimal::ZERO {
+            Some(Side::Buy)
+        } else if spread < Decimal::ZERO {
+            Some(Side::Sell)
+        } else {
+            None
+        }
+    }
+
+    /// Protective stop price for a position on the provided side, `atr_stop` ATRs away from the
+    /// average entry price.
+    pub fn stop_price(
+        &self,
+        side: Side,
+        price_entry: Decimal,
+        atr_stop: Decimal,
+    ) -> Option<Decimal> {
+        let distance = self.atr? * atr_stop;
+        Some(match side {
+            Side::Buy => price_entry - distance,
+            Side::Sell => price_entry + distance,
+        })
+    }
+
+    fn update_bar(&mut self, time: DateTime<Utc>, high: f64, low: f64, close: f64) {
+        if self.close.as_ref().is_some_and(|last| time < last.time) {
+            return;
+        }
+
+        let (Some(high), Some(low), Some(close)) = (
+            Decimal::from_f64(high),
+            Decimal::from_f64(low),
+            Decimal::from_f64(close),
+        ) else {
+            return;
+        };
+
+        self.close = Some(Timed::new(close, time));
+        self.fast = self.fast_mean.update(close);
+        self.slow = self.slow_mean.update(close);
+        self.atr = self.true_range.update(Bar::new(high, low, close));
+
+        let Some(trend) = self.trend() else {
+            return;
+        };
+        if self.last_trend.is_some_and(|last| last != trend) {
+            self.signal = Some(trend);
+        }
+        self.last_trend = Some(trend);
+    }
+}
+
+impl Default for MomentumData {
+    fn default() -> Self {
+        Self::new(&MomentumConfig::default())
+    }
+}
+
+impl InstrumentDataState for MomentumData {
+    type MarketEventKind = DataKind;
+
+    fn price(&self) -> Option<Decimal> {
+        self.market
+            .price()
+            .or(self.close.as_ref().map(|close| close.value))
+    }
+}
+
+impl<InstrumentKey> Processor<&MarketEvent<InstrumentKey, DataKind>> for MomentumData {
+    type Audit = ();
+
+    fn process(&mut self, event: &MarketEvent<InstrumentKey, DataKind>) -> Self::Audit {
+        self.market.process(event);
+
+        match &event.kind {
+            DataKind::Candle(candle) => {
+                self.update_bar(event.time_exchange, candle.high, candle.low, candle.close)
+            }
+            DataKind::Trade(trade) => {
+                self.update_bar(event.time_exchange, trade.price, trade.price, trade.price)
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<ExchangeKey, AssetKey, InstrumentKey>
+    Processor<&AccountEvent<ExchangeKey, AssetKey, InstrumentKey>> for MomentumData
+{
+    type Audit = ();
+
+    fn process(&mut self, _: &AccountEvent<ExchangeKey, AssetKey, InstrumentKey>) -> Self::Audit {}
+}
+
+impl<ExchangeKey, InstrumentKey> InFlightRequestRecorder<ExchangeKey, InstrumentKey>
+    for MomentumData
+{
+    fn record_in_flight_cancel(&mut self, _: &OrderRequestCancel<ExchangeKey, InstrumentKey>) {}
+
+    fn record_in_flight_open(&mut self, request: &OrderRequestOpen<ExchangeKey, InstrumentKey>) {
+        if self.signal == Some(request.state.side) {
+            self.signal = None;
+        }
+    }
+}
+
+/// Moving-average crossover strategy with ATR-based stops.
+///
+/// For every instrument without in-flight orders, the strategy sends an IOC market order to:
+/// 1. Enter `quantity` on a pending crossover when flat.
+/// 2. Exit (and reverse if `allow_short`) on a crossover against the open position.
+/// 3. Exit when the price crosses the ATR stop of the open position.
+///
+/// Crossovers & stops only depend on the [`MomentumData`] of each instrument, so the strategy is
+/// generic over the `GlobalData` of the [`EngineState`].
+#[derive(Debug, Clone)]
+pub struct MomentumStrategy<GlobalData = DefaultGlobalData> {
+    pub id: StrategyId,
+    pub config: MomentumConfig,
+    phantom: PhantomData<fn() -> GlobalData>,
+}
+
+impl<GlobalData> MomentumStrategy<GlobalData> {
+    /// Construct a new [`MomentumStrategy`].
+    pub fn new(id: StrategyId, config: MomentumConfig) -> Self {
+        Self {
+            id,
+            config,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Construct the [`MomentumData`] required for each instrument of the [`EngineState`].
+    pub fn instrument_data(&self) -> MomentumData {
+        MomentumData::new(&self.config)
+    }
+
+    fn entry_quantity(&self, side: Side) -> Decimal {
+        match side {
+            Side::Buy => self.config.quantity,
+            Side::Sell if self.config.allow_short => self.config.quantity,
+            Side::Sell => Decimal::ZERO,
+        }
+    }
+
+    fn open_request(
+        &self,
+        state: &InstrumentState<MomentumData>,
+    ) -> Option<OrderRequestOpen<ExchangeIndex, InstrumentIndex>> {
+        if !state.orders.0.is_empty() {
+            return None;
+        }
+
+        let price = state.data.price()?;
+
+        let (side, quantity) = match (state.position.current.as_ref(), state.data.signal) {
+            (None, Some(signal)) => (signal, self.entry_quantity(signal)),
+            (Some(position), Some(signal)) if signal != position.side => {
+                (signal, position.quantity_abs + self.entry_quantity(signal))
+            }
+            (Some(position), _) => {
+                let stop = state.data.stop_price(
+                    position.side,
+                    position.price_entry_average,
+                    self.config.atr_stop,
+                )?;
+
+                match position.side {
+                    Side::Buy if price <= stop => (Side::Sell, position.quantity_abs),
+                    Side::Sell if price >= stop => (Side::Buy, position.quantity_abs),
+                    _ => return None,
+                }
+            }
+            (None, None) => return None,
+        };
+
+        if quantity.is_zero() {
+            return None;
+        }
+
+        Some(OrderRequestOpen {
+            key: OrderKey {
+                exchange: state.instrument.exchange.to_string(),
+                instrument: state.key.clone(),
+                strategy: self.id.clone(),
+                cid: ClientOrderId::random(),
+            },
+            state: RequestOpen {
+                side,
+                price,
+                quantity,
+                kind: OrderKind::Market,
+                time_in_force: TimeInForce::ImmediateOrCancel,
+            },
+        })
+    }
+}
+
+impl<GlobalData> AlgoStrategy for MomentumStrategy<GlobalData> {
+    type State = EngineState<GlobalData, MomentumData>;
+
+    fn generate_algo_orders(
+        &self,
+        state: &Self::State,
+    ) -> (
+        impl IntoIterator<Item = OrderRequestCancel<ExchangeIndex, InstrumentIndex>>,
+        impl IntoIterator<Item = OrderRequestOpen<ExchangeIndex, InstrumentIndex>>,
+    ) {
+        let opens = state
+            .instruments
+            .0
+            .values()
+            .filter_map(|state| self.open_request(state))
+            .collect::<Vec<_>>();
+
+        (std::iter::empty(), opens)
+    }
+}
+
+impl<GlobalData> ClosePositionsStrategy for MomentumStrategy<GlobalData> {
+    type State = EngineState<GlobalData, MomentumData>;
+
+    fn close_positions_requests<'a>(
+        &'a self,
+        state: &'a Self::State,
+        filter: &'a impl std::fmt::Debug,
+    ) -> (
+        impl IntoIterator<Item = OrderRequestCancel<ExchangeIndex, InstrumentIndex>> + 'a,
+        impl IntoIterator<Item = OrderRequestOpen<ExchangeIndex, InstrumentIndex>> + 'a,
+    )
+    where
+        ExchangeIndex: 'a,
+        AssetIndex: 'a,
+        InstrumentIndex: 'a,
+    {
+        (
+            std::iter::empty(),
+            close_positions_with_market_orders(&self.id, &state.instruments, filter),
+        )
+    }
+}
+
+impl<Clock, State, ExecutionTxs, Risk, GlobalData>
+    OnDisconnectStrategy<Clock, State, ExecutionTxs, Risk> for MomentumStrategy<GlobalData>
+{
+    type OnDisconnect = ();
+
+    fn on_disconnect(_: ExchangeId) -> Self::OnDisconnect {}
+}
+
+impl<Clock, State, ExecutionTxs, Risk, GlobalData>
+    OnTradingDisabled<Clock, State, ExecutionTxs, Risk> for MomentumStrategy<GlobalData>
+{
+    type OnTradingDisabled = ();
+
+    fn on_trading_disabled() -> Self::OnTradingDisabled {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use toucan_data::subscription::candle::Candle;
+    use toucan_execution::order::{id::ClientOrderId, OrderKey};
+
+    fn candle(bar: i64, close: f64) -> MarketEvent<InstrumentIndex, DataKind> {
+        let time = DateTime::<Utc>::MIN_UTC + chrono::TimeDelta::days(bar);
+        MarketEvent {
+            time_exchange: time,
+            time_received: time,
+            exchange: ExchangeId::Mock,
+            instrument: "PETR4".to_string(),
+            kind: DataKind::Candle(Candle {
+                close_time: time,
+                open: close,
+                high: close + 1.0,
+                low: close - 1.0,
+                close,
+                volume: 1.0,
+                trade_count: 1,
+            }),
+        }
+    }
+
+    fn open(side: Side) -> OrderRequestOpen<ExchangeIndex, InstrumentIndex> {
+        OrderRequestOpen {
+            key: OrderKey {
+                exchange: "Mock".to_string(),
+                instrument: "PETR4".to_string(),
+                strategy: StrategyId::new("momentum"),
+                cid: ClientOrderId::new("cid"),
+            },
+            state: RequestOpen {
+                side,
+                price: Decimal::ONE,
+                quantity: Decimal::ONE,
+                kind: OrderKind::Market,
+                time_in_force: TimeInForce::ImmediateOrCancel,
+            },
+        }
+    }
+
+    #[test]
+    fn test_momentum_data_signal() {
+        let config = MomentumConfig {
+            fast_period: 2,
+            slow_period: 3,
+            atr_period: 2,
+            ..MomentumConfig::default()
+        };
+        let mut data = MomentumData::new(&config);
+
+        // TC0: falling closes warm up a bearish trend without signalling a crossover
+        for (bar, close) in [10.0, 9.0, 8.0].into_iter().enumerate() {
+            data.process(&candle(bar as i64, close));
+        }
+        assert_eq!(data.trend(), Some(Side::Sell), "TC0 failed");
+        assert_eq!(data.signal, None, "TC0 failed");
+
+        // TC1: rising close crosses the fast average above the slow average
+        data.process(&candle(3, 12.0));
+        assert_eq!(data.fast, Some(Decimal::from(10)), "TC1 failed");
+        assert_eq!(data.trend(), Some(Side::Buy), "TC1 failed");
+        assert_eq!(data.signal, Some(Side::Buy), "TC1 failed");
+
+        // TC2: stale bars are ignored
+        data.process(&candle(0, 1.0));
+        assert_eq!(data.price(), Some(Decimal::from(12)), "TC2 failed");
+
+        // TC3: opposite side open does not consume the signal, same side open does
+        data.record_in_flight_open(&open(Side::Sell));
+        assert_eq!(data.signal, Some(Side::Buy), "TC3 failed");
+        data.record_in_flight_open(&open(Side::Buy));
+        assert_eq!(data.signal, None, "TC3 failed");
+
+        // TC4: ATR stop is atr_stop ATRs from the entry price (ATR = mean(2, 5) = 3.5)
+        assert_eq!(data.atr, Some(Decimal::new(35, 1)), "TC4 failed");
+        assert_eq!(
+            data.stop_price(Side::Buy, Decimal::from(12), Decimal::TWO),
+            Some(Decimal::from(5)),
+            "TC4 failed"
+        );
+        assert_eq!(
+            data.stop_price(Side::Sell, Decimal::from(12), Decimal::TWO),
+            Some(Decimal::from(19)),
+            "TC4 failed"
+        );
+    }
+}
@@ -1,4 +1,404 @@
+//! Options delta-hedging strategy.
+//!
+//! Keeps an options book (eg/ B3 equity options on PETR4 or VALE3) delta-neutral by trading the
+//! underlying. Option deltas are calculated with the [`pricing`](crate::shared::pricing) models,
+//! using the implied volatility of the latest option price (falling back to a configured
+//! volatility), so the [`DeltaHedgeStrategy`] runs unchanged in backtests and live.
+//!
+//! Option positions are not opened by this strategy: any option position in the
+//! [`EngineState`] (eg/ opened by another strategy or manually) is hedged.
+//!
+//! # Example
+//! ```rust,ignore
+//! let strategy = DeltaHedgeStrategy::new(
+//!     StrategyId::new("delta_hedge"),
+//!     DeltaHedgeConfig::default(),
+//! )
+//! .with_option("PETRA380".to_string(), "PETR4".to_string(), petra380_contract)
+//! .with_instrument_spec("PETR4".to_string(), petr4_spec, Decimal::ONE)
+//! .with_calendar(ExchangeCalendar::b3_equities());
+//!
+//! let state = EngineState::builder(&instruments, DefaultGlobalData, |_| {
+//!     DefaultInstrumentMarketData::default()
+//! })
+//! .build();
+//! ```
 
-//! Options strategy placeholder (not yet implemented).
+use crate::shared::{
+    close_positions_with_market_orders,
+    pricing::{business_year_fraction, year_fraction, Greeks, OptionInputs, PricingModel},
+};
+use chrono::{DateTime, Utc};
+use rust_decimal::{
+    prelude::{FromPrimitive, ToPrimitive},
+    Decimal,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, marker::PhantomData};
+use toucan_core::engine::{
+    risk::OrderSpec,
+    state::{
+        global::DefaultGlobalData,
+        instrument::data::{DefaultInstrumentMarketData, InstrumentDataState},
+        position::Position,
+        EngineState,
+    },
+};
+use toucan_execution::{
+    order::{
+        id::{ClientOrderId, StrategyId},
+        request::{OrderRequestCancel, OrderRequestOpen, RequestOpen},
+        OrderKey, OrderKind, TimeInForce,
+    },
+    AssetIndex, ExchangeIndex, InstrumentIndex,
+};
+use toucan_instrument::{
+    exchange::ExchangeId,
+    instrument::{
+        kind::option::OptionContract,
+        spec::{InstrumentSpec, RoundingPolicy},
+    },
+    Side,
+};
+use toucan_markets::calendar::ExchangeCalendar;
+use toucan_trader::{
+    AlgoStrategy, ClosePositionsStrategy, OnDisconnectStrategy, OnTradingDisabled,
+};
 
-pub struct OptionsStrategy;
+/// Configuration for the [`DeltaHedgeStrategy`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct DeltaHedgeConfig {
+    /// [`PricingModel`] used to calculate option deltas.
+    pub model: PricingModel,
+
+    /// Continuously compounded risk-free rate (eg/ the Selic rate for BRL options).
+    pub rate: Decimal,
+
+    /// Continuously compounded dividend yield of the underlying.
+    #[serde(default)]
+    pub dividend_yield: Decimal,
+
+    /// Volatility used if an implied volatility cannot be solved from the latest option price.
+    pub volatility: Decimal,
+
+    /// Absolute net delta (in units of the underlying) tolerated before the book is re-hedged.
+    pub hedge_threshold: Decimal,
+}
+
+impl Default for DeltaHedgeConfig {
+    fn default() -> Self {
+        Self {
+            model: PricingModel::BlackScholes,
+            rate: Decimal::new(10, 2),
+            dividend_yield: Decimal::ZERO,
+            volatility: Decimal::new(30, 2),
+            hedge_threshold: Decimal::ONE_HUNDRED,
+        }
+    }
+}
+
+/// Option instrument hedged by the [`DeltaHedgeStrategy`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct HedgedOption {
+    /// Underlying instrument traded to hedge the option delta.
+    pub underlying: InstrumentIndex,
+    pub contract: OptionContract<AssetIndex>,
+}
+
+/// Delta-hedging strategy for an options book.
+///
+/// For every underlying without in-flight orders, the strategy calculates the net delta of the
+/// book (ie/ option positions × contract size × option delta, plus the underlying position ×
+/// its contract size). If its absolute value exceeds the `hedge_threshold`, an IOC market order
+/// on the underlying offsets it, converted to underlying contracts & rounded to the
+/// [`InstrumentSpec`] lot size of the underlying (if configured).
+///
+/// Times to expiry use the `DU/252` convention of the configured [`ExchangeCalendar`] (eg/
+/// [`ExchangeCalendar::b3_equities`]), or `ACT/365` without a calendar, measured from the
+/// latest market data time of the underlying.
+///
+/// Deltas are priced from instrument market data & positions only, so the `GlobalData` of the
+/// [`EngineState`] is unconstrained.
+#[derive(Debug, Clone)]
+pub struct DeltaHedgeStrategy<GlobalData = DefaultGlobalData> {
+    pub id: StrategyId,
+    pub config: DeltaHedgeConfig,
+
+    /// Hedged options, keyed by option instrument.
+    pub options: HashMap<InstrumentIndex, HedgedOption>,
+
+    /// [`OrderSpec`]s used to round hedge quantities, keyed by underlying instrument.
+    pub specs: HashMap<InstrumentIndex, OrderSpec>,
+
+    /// [`ExchangeCalendar`] used for `DU/252` times to expiry.
+    pub calendar: Option<ExchangeCalendar>,
+    phantom: PhantomData<fn() -> GlobalData>,
+}
+
+impl<GlobalData> DeltaHedgeStrategy<GlobalData> {
+    /// Construct a new [`DeltaHedgeStrategy`] without any hedged options.
+    pub fn new(id: StrategyId, config: DeltaHedgeConfig) -> Self {
+        Self {
+            id,
+            config,
+            options: HashMap::new(),
+            specs: HashMap::new(),
+            calendar: None,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Hedge positions in the provided option instrument by trading its underlying instrument.
+    pub fn with_option(
+        mut self,
+        instrument: InstrumentIndex,
+        underlying: InstrumentIndex,
+        contract: OptionContract<AssetIndex>,
+    ) -> Self {
+        self.options.insert(
+            instrument,
+            HedgedOption {
+                underlying,
+                contract,
+            },
+        );
+        self
+    }
+
+    /// Configure the [`InstrumentSpec`] & contract size (ie/ units of the underlying per
+    /// contract) used to size hedge quantities for an individual underlying instrument.
+    pub fn with_instrument_spec(
+        mut self,
+        instrument: InstrumentIndex,
+        spec: InstrumentSpec<AssetIndex>,
+        contract_size: Decimal,
+    ) -> Self {
+        self.specs.insert(
+            instrument,
+            OrderSpec {
+                spec,
+                contract_size,
+            },
+        );
+        self
+    }
+
+    /// Measure times to expiry in trading days of the provided [`ExchangeCalendar`] (`DU/252`).
+    pub fn with_calendar(mut self, calendar: ExchangeCalendar) -> Self {
+        self.calendar = Some(calendar);
+        self
+    }
+
+    /// [`Greeks`] of a hedged option instrument, priced at the latest underlying price & time
+    /// with the implied volatility of the latest option price (if it can be solved).
+    pub fn option_greeks(
+        &self,
+        state: &EngineState<GlobalData, DefaultInstrumentMarketData>,
+        instrument: &InstrumentIndex,
+    ) -> Option<Greeks> {
+        let option = self.options.get(instrument)?;
+        let underlying = &state.instruments.0.get(&option.underlying)?.data;
+
+        let time_to_expiry = match &self.calendar {
+            Some(calendar) => {
+                business_year_fraction(calendar, market_time(underlying)?, option.contract.expiry)
+            }
+            None => year_fraction(market_time(underlying)?, option.contract.expiry),
+        };
+
+        let inputs = OptionInputs {
+            kind: option.contract.kind,
+            underlying: underlying.price()?.to_f64()?,
+            strike: option.contract.strike.to_f64()?,
+            time_to_expiry,
+            rate: self.config.rate.to_f64()?,
+            dividend_yield: self.config.dividend_yield.to_f64()?,
+            volatility: self.config.volatility.to_f64()?,
+        };
+
+        let volatility = state
+            .instruments
+            .0
+            .get(instrument)
+            .and_then(|option| option.data.price())
+            .and_then(|price| price.to_f64())
+            .and_then(|price| self.config.model.implied_volatility(&inputs, price).ok())
+            .unwrap_or(inputs.volatility);
+
+        Some(self.config.model.greeks(&OptionInputs {
+            volatility,
+            ..inputs
+        }))
+    }
+
+    /// Net delta of the book on the provided underlying instrument, in units of the underlying.
+    ///
+    /// Returns `None` if the delta of any option position cannot be calculated (eg/ no
+    /// underlying price yet), in which case the book is not hedged.
+    pub fn net_delta(
+        &self,
+        state: &EngineState<GlobalData, DefaultInstrumentMarketData>,
+        underlying: &InstrumentIndex,
+    ) -> Option<Decimal> {
+        let hedge = state
+            .instruments
+            .0
+            .get(underlying)?
+            .position
+            .current
+            .as_ref()
+            .map(|position| signed_quantity(position) * self.contract_size(underlying))
+            .unwrap_or_default();
+
+        self.options
+            .iter()
+            .filter(|(_, option)| option.underlying == *underlying)
+            .try_fold(hedge, |net_delta, (instrument, option)| {
+                let Some(position) = state
+                    .instruments
+                    .0
+                    .get(instrument)
+                    .and_then(|state| state.position.current.as_ref())
+                else {
+                    return Some(net_delta);
+                };
+
+                let delta = Decimal::from_f64(self.option_greeks(state, instrument)?.delta)?;
+                Some(net_delta + signed_quantity(position) * option.contract.contract_size * delta)
+            })
+    }
+
+    /// Units of the underlying per contract of an underlying instrument (`1` if not configured).
+    fn contract_size(&self, underlying: &InstrumentIndex) -> Decimal {
+        self.specs
+            .get(underlying)
+            .map_or(Decimal::ONE, |spec| spec.contract_size)
+    }
+
+    fn hedge_request(
+        &self,
+        state: &EngineState<GlobalData, DefaultInstrumentMarketData>,
+        underlying: &InstrumentIndex,
+    ) -> Option<OrderRequestOpen<ExchangeIndex, InstrumentIndex>> {
+        let underlying_state = state.instruments.0.get(underlying)?;
+        if !underlying_state.orders.0.is_empty() {
+            return None;
+        }
+
+        let net_delta = self.net_delta(state, underlying)?;
+        if net_delta.abs() <= self.config.hedge_threshold {
+            return None;
+        }
+
+        let contracts = net_delta.checked_div(self.contract_size(underlying))?;
+        let quantity = match self.specs.get(underlying) {
+            Some(OrderSpec { spec, .. }) => spec.quantity.round(contracts, RoundingPolicy::Nearest),
+            None => contracts.abs(),
+        };
+        if quantity.is_zero() {
+            return None;
+        }
+
+        Some(OrderRequestOpen {
+            key: OrderKey {
+                exchange: underlying_state.instrument.exchange.to_string(),
+                instrument: underlying.clone(),
+                strategy: self.id.clone(),
+                cid: ClientOrderId::random(),
+            },
+            state: RequestOpen {
+                side: if net_delta > Decimal::ZERO {
+                    Side::Sell
+                } else {
+                    Side::Buy
+                },
+                price: underlying_state.data.price()?,
+                quantity,
+                kind: OrderKind::Market,
+                time_in_force: TimeInForce::ImmediateOrCancel,
+            },
+        })
+    }
+}
+
+impl<GlobalData> AlgoStrategy for DeltaHedgeStrategy<GlobalData> {
+    type State = EngineState<GlobalData, DefaultInstrumentMarketData>;
+
+    fn generate_algo_orders(
+        &self,
+        state: &Self::State,
+    ) -> (
+        impl IntoIterator<Item = OrderRequestCancel<ExchangeIndex, InstrumentIndex>>,
+        impl IntoIterator<Item = OrderRequestOpen<ExchangeIndex, InstrumentIndex>>,
+    ) {
+        let mut underlyings = self
+            .options
+            .values()
+            .map(|option| &option.underlying)
+            .collect::<Vec<_>>();
+        underlyings.sort();
+        underlyings.dedup();
+
+        let opens = underlyings
+            .into_iter()
+            .filter_map(|underlying| self.hedge_request(state, underlying))
+            .collect::<Vec<_>>();
+
+        (std::iter::empty(), opens)
+    }
+}
+
+impl<GlobalData> ClosePositionsStrategy for DeltaHedgeStrategy<GlobalData> {
+    type State = EngineState<GlobalData, DefaultInstrumentMarketData>;
+
+    fn close_positions_requests<'a>(
+        &'a self,
+        state: &'a Self::State,
+        filter: &'a impl std::fmt::Debug,
+    ) -> (
+        impl IntoIterator<Item = OrderRequestCancel<ExchangeIndex, InstrumentIndex>> + 'a,
+        impl IntoIterator<Item = OrderRequestOpen<ExchangeIndex, InstrumentIndex>> + 'a,
+    )
+    where
+        ExchangeIndex: 'a,
+        AssetIndex: 'a,
+        InstrumentIndex: 'a,
+    {
+        (
+            std::iter::empty(),
+            close_positions_with_market_orders(&self.id, &state.instruments, filter),
+        )
+    }
+}
+
+impl<Clock, State, ExecutionTxs, Risk, GlobalData>
+    OnDisconnectStrategy<Clock, State, ExecutionTxs, Risk> for DeltaHedgeStrategy<GlobalData>
+{
+    type OnDisconnect = ();
+
+    fn on_disconnect(_: ExchangeId) -> Self::OnDisconnect {}
+}
+
+impl<Clock, State, ExecutionTxs, Risk, GlobalData>
+    OnTradingDisabled<Clock, State, ExecutionTxs, Risk> for DeltaHedgeStrategy<GlobalData>
+{
+    type OnTradingDisabled = ();
+
+    fn on_trading_disabled() -> Self::OnTradingDisabled {}
+}
+
+/// Latest market data time of an instrument, if any market data has been received.
+fn market_time(data: &DefaultInstrumentMarketData) -> Option<DateTime<Utc>> {
+    let book = (data.l1.best_bid.is_some() || data.l1.best_ask.is_some())
+        .then_some(data.l1.last_update_time);
+    let trade = data.last_traded_price.as_ref().map(|price| price.time);
+    book.max(trade)
+}
+
+/// Signed [`Position`] quantity (ie/ positive when long, negative when short).
+fn signed_quantity(position: &Position) -> Decimal {
+    match position.side {
+        Side::Buy => position.quantity_abs,
+        Side::Sell => -position.quantity_abs,
+    }
+}
@@ -1,22 +1,66 @@
-
 //! Simple order book imbalance strategy.
 //!
 //! Reusable in both live and backtest modes.
-//! Does not depend on technical indicators; only compares aggregated bid vs ask volume.
+//! Does not depend on technical indicators; only compares the best bid vs ask volume of the
+//! `OrderBookL1` maintained by the [`OrderBookImbalanceData`] of each instrument, so it is
+//! the canonical end-to-end example of a strategy running unchanged in backtests
+//! (`run_backtests`) and live.
+//!
+//! # Example
+//! ```rust,ignore
+//! let strategy = OrderBookImbalanceStrategy::new(OrderBookImbalanceConfig::default())
+//!     .with_id(StrategyId::new("imbalance"));
+//!
+//! let state = EngineState::builder(&instruments, DefaultGlobalData, |_| {
+//!     strategy.instrument_data()
+//! })
+//! .build();
+//! ```
 
+use crate::shared::close_positions_with_market_orders;
 use rust_decimal::Decimal;
-use toucan_execution::order::request::OrderRequestOpen;
-use toucan_execution::{ExchangeIndex, InstrumentIndex};
-use toucan_instrument::Side;
-use toucan_trader::AlgoStrategy;
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+use toucan_core::engine::{
+    state::{
+        global::DefaultGlobalData,
+        instrument::{
+            data::{DefaultInstrumentMarketData, InstrumentDataState},
+            InstrumentState,
+        },
+        order::in_flight_recorder::InFlightRequestRecorder,
+        EngineState,
+    },
+    Processor,
+};
+use toucan_data::{
+    event::{DataKind, MarketEvent},
+    subscription::book::OrderBookL1,
+};
+use toucan_execution::{
+    order::{
+        id::{ClientOrderId, StrategyId},
+        request::{OrderRequestCancel, OrderRequestOpen, RequestOpen},
+        OrderKey, OrderKind, TimeInForce,
+    },
+    AccountEvent, AssetIndex, ExchangeIndex, InstrumentIndex,
+};
+use toucan_instrument::{exchange::ExchangeId, Side};
+use toucan_trader::{
+    AlgoStrategy, ClosePositionsStrategy, OnDisconnectStrategy, OnTradingDisabled,
+};
 
 /// Configuration for the imbalance strategy.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Deserialize, Serialize)]
 pub struct OrderBookImbalanceConfig {
     /// Minimum imbalance percentage (0-1) to trigger buy/sell. E.g., 0.6 => 60%.
     pub threshold: Decimal,
     /// Base quantity to send per order when signal occurs.
     pub quantity: Decimal,
+    /// Reverse an open position on an opposite signal (ie/ send the open position quantity plus
+    /// `quantity`) instead of sending `quantity` alone. Disabled by default.
+    #[serde(default)]
+    pub reverse: bool,
 }
 
 impl Default for OrderBookImbalanceConfig {
@@ -24,32 +68,128 @@ impl Default for OrderBookImbalanceConfig {
         Self {
             threshold: Decimal::new(60, 2),
             quantity: Decimal::ONE,
+            reverse: false,
         } // 0.60
     }
 }
 
-/// Optional volatile state (e.g., last triggered direction) to avoid over-trading.
-#[derive(Debug, Default, Clone)]
+impl OrderBookImbalanceConfig {
+    /// Side signalled by the provided imbalance, if it crosses the `threshold` (ie/ `Buy` at or
+    /// above the `threshold`, `Sell` at or below `1 - threshold`).
+    pub fn signal(&self, imbalance: Decimal) -> Option<Side> {
+        if imbalance >= self.threshold {
+            Some(Side::Buy)
+        } else if imbalance <= Decimal::ONE - self.threshold {
+            Some(Side::Sell)
+        } else {
+            None
+        }
+    }
+}
+
+/// Share of the best bid volume in the best bid & ask volume of an [`OrderBookL1`] (0-1), or
+/// `None` if either side of the book is empty.
+pub fn imbalance(book: &OrderBookL1) -> Option<Decimal> {
+    let bid_volume = book.best_bid.as_ref()?.amount;
+    let ask_volume = book.best_ask.as_ref()?.amount;
+
+    let total = bid_volume + ask_volume;
+    (!total.is_zero()).then(|| bid_volume / total)
+}
+
+/// Volatile state (e.g., last triggered direction) to avoid over-trading.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash, Deserialize, Serialize)]
 pub struct OrderBookImbalanceState {
-    #[allow(dead_code)]
-    last_side: Option<Side>,
+    /// Side of the last order sent, until the imbalance returns inside the `threshold`.
+    pub last_side: Option<Side>,
 }
 
-pub struct OrderBookImbalanceStrategy<C = OrderBookImbalanceConfig> {
-    pub config: C,
+/// Instrument level data of the [`OrderBookImbalanceStrategy`].
+///
+/// Tracks the [`DefaultInstrumentMarketData`] of an instrument, and the
+/// [`OrderBookImbalanceState`] `last_side` of the orders sent for it. The `last_side` is reset
+/// once an `OrderBookL1` imbalance returns inside the `threshold`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct OrderBookImbalanceData {
+    /// Latest `OrderBookL1` and traded price.
+    pub market: DefaultInstrumentMarketData,
+
+    /// Last triggered direction.
     pub state: OrderBookImbalanceState,
+
+    config: OrderBookImbalanceConfig,
 }
 
-impl<C> OrderBookImbalanceStrategy<C> {
-    pub fn new(config: C) -> Self {
+impl OrderBookImbalanceData {
+    /// Construct an empty [`OrderBookImbalanceData`] using the `threshold` of the provided
+    /// [`OrderBookImbalanceConfig`].
+    pub fn new(config: &OrderBookImbalanceConfig) -> Self {
         Self {
-            config,
+            market: DefaultInstrumentMarketData::default(),
             state: OrderBookImbalanceState::default(),
+            config: *config,
         }
     }
+
+    /// Side signalled by the latest `OrderBookL1`, if it differs from the `last_side`.
+    pub fn signal(&self) -> Option<Side> {
+        self.config
+            .signal(imbalance(&self.market.l1)?)
+            .filter(|side| self.state.last_side != Some(*side))
+    }
+}
+
+impl Default for OrderBookImbalanceData {
+    fn default() -> Self {
+        Self::new(&OrderBookImbalanceConfig::default())
+    }
 }
 
-// Estrutura simplificada de snapshot de livro que o motor poderia expor.
+impl InstrumentDataState for OrderBookImbalanceData {
+    type MarketEventKind = DataKind;
+
+    fn price(&self) -> Option<Decimal> {
+        self.market.price()
+    }
+}
+
+impl<InstrumentKey> Processor<&MarketEvent<InstrumentKey, DataKind>> for OrderBookImbalanceData {
+    type Audit = ();
+
+    fn process(&mut self, event: &MarketEvent<InstrumentKey, DataKind>) -> Self::Audit {
+        self.market.process(event);
+
+        if let DataKind::OrderBookL1(_) = &event.kind {
+            let inside_threshold = imbalance(&self.market.l1)
+                .is_some_and(|imbalance| self.config.signal(imbalance).is_none());
+
+            if inside_threshold {
+                self.state.last_side = None;
+            }
+        }
+    }
+}
+
+impl<ExchangeKey, AssetKey, InstrumentKey>
+    Processor<&AccountEvent<ExchangeKey, AssetKey, InstrumentKey>> for OrderBookImbalanceData
+{
+    type Audit = ();
+
+    fn process(&mut self, _: &AccountEvent<ExchangeKey, AssetKey, InstrumentKey>) -> Self::Audit {}
+}
+
+impl<ExchangeKey, InstrumentKey> InFlightRequestRecorder<ExchangeKey, InstrumentKey>
+    for OrderBookImbalanceData
+{
+    fn record_in_flight_cancel(&mut self, _: &OrderRequestCancel<ExchangeKey, InstrumentKey>) {}
+
+    fn record_in_flight_open(&mut self, request: &OrderRequestOpen<ExchangeKey, InstrumentKey>) {
+        self.state.last_side = Some(request.state.side);
+    }
+}
+
+/// Simplified book snapshot, superseded by the `OrderBookL1` of the [`OrderBookImbalanceData`].
+#[deprecated(note = "use the OrderBookL1 of the OrderBookImbalanceData market data instead")]
 #[derive(Debug, Clone)]
 pub struct SimpleBook {
     pub instrument: InstrumentIndex,
@@ -57,24 +197,331 @@ pub struct SimpleBook {
     pub best_ask_volume: Decimal,
 }
 
-impl<C: AsRef<OrderBookImbalanceConfig>> AlgoStrategy<ExchangeIndex, InstrumentIndex>
-    for OrderBookImbalanceStrategy<C>
-{
-    type State = crate::shared::NoOpState; // reutiliza um estado vazio existente / placeholder
+/// Order book imbalance strategy.
+///
+/// For every instrument without in-flight orders, the strategy sends an IOC market order
+/// (priced at the opposite best level) on the side signalled by the `OrderBookL1` imbalance.
+///
+/// A signal is only traded when it differs from the [`OrderBookImbalanceState`] `last_side` of
+/// the instrument, so a persisting imbalance does not re-trigger orders until it returns inside
+/// the `threshold`. Each order is `quantity`, unless `reverse` is configured (see
+/// [`OrderBookImbalanceConfig`]).
+///
+/// Only the [`OrderBookImbalanceData`] & position of each instrument are read, so the strategy
+/// runs on an [`EngineState`] with any `GlobalData`.
+#[derive(Debug, Clone)]
+pub struct OrderBookImbalanceStrategy<GlobalData = DefaultGlobalData> {
+    pub id: StrategyId,
+    pub config: OrderBookImbalanceConfig,
+    phantom: PhantomData<fn() -> GlobalData>,
+}
+
+impl<GlobalData> OrderBookImbalanceStrategy<GlobalData> {
+    /// Construct a new [`OrderBookImbalanceStrategy`] with the default "order_book_imbalance"
+    /// [`StrategyId`].
+    pub fn new(config: OrderBookImbalanceConfig) -> Self {
+        Self {
+            id: StrategyId::new("order_book_imbalance"),
+            config,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Configure the [`StrategyId`] used to key the orders of this strategy.
+    pub fn with_id(self, id: StrategyId) -> Self {
+        Self { id, ..self }
+    }
+
+    /// Construct the [`OrderBookImbalanceData`] required for each instrument of the
+    /// [`EngineState`].
+    pub fn instrument_data(&self) -> OrderBookImbalanceData {
+        OrderBookImbalanceData::new(&self.config)
+    }
+
+    fn open_request(
+        &self,
+        state: &InstrumentState<OrderBookImbalanceData>,
+    ) -> Option<OrderRequestOpen<ExchangeIndex, InstrumentIndex>> {
+        if !state.orders.0.is_empty() {
+            return None;
+        }
+
+        let side = state.data.signal()?;
+
+        let quantity = match state.position.current.as_ref() {
+            Some(position) if self.config.reverse && position.side != side => {
+                position.quantity_abs + self.config.quantity
+            }
+            _ => self.config.quantity,
+        };
+
+        let l1 = &state.data.market.l1;
+        let price = match side {
+            Side::Buy => l1.best_ask.as_ref()?.price,
+            Side::Sell => l1.best_bid.as_ref()?.price,
+        };
+
+        Some(OrderRequestOpen {
+            key: OrderKey {
+                exchange: state.instrument.exchange.to_string(),
+                instrument: state.key.clone(),
+                strategy: self.id.clone(),
+                cid: ClientOrderId::random(),
+            },
+            state: RequestOpen {
+                side,
+                price,
+                quantity,
+                kind: OrderKind::Market,
+                time_in_force: TimeInForce::ImmediateOrCancel,
+            },
+        })
+    }
+}
+
+impl<GlobalData> AlgoStrategy for OrderBookImbalanceStrategy<GlobalData> {
+    type State = EngineState<GlobalData, OrderBookImbalanceData>;
 
     fn generate_algo_orders(
         &self,
-        _state: &Self::State,
+        state: &Self::State,
     ) -> (
-        impl IntoIterator<
-            Item = toucan_execution::order::request::OrderRequestCancel<
-                ExchangeIndex,
-                InstrumentIndex,
-            >,
-        >,
+        impl IntoIterator<Item = OrderRequestCancel<ExchangeIndex, InstrumentIndex>>,
         impl IntoIterator<Item = OrderRequestOpen<ExchangeIndex, InstrumentIndex>>,
     ) {
-    // No real access to the book here — return empty until data integration.
-        (Vec::new(), Vec::new())
+        let opens = state
+            .instruments
+            .0
+            .values()
+            .filter_map(|state| self.open_request(state))
+            .collect::<Vec<_>>();
+
+        (std::iter::empty(), opens)
+    }
+}
+
+impl<GlobalData> ClosePositionsStrategy for OrderBookImbalanceStrategy<GlobalData> {
+    type State = EngineState<GlobalData, OrderBookImbalanceData>;
+
+    fn close_positions_requests<'a>(
+        &'a self,
+        state: &'a Self::State,
+        filter: &'a impl std::fmt::Debug,
+    ) -> (
+        impl IntoIterator<Item = OrderRequestCancel<ExchangeIndex, InstrumentIndex>> + 'a,
+        impl IntoIterator<Item = OrderRequestOpen<ExchangeIndex, InstrumentIndex>> + 'a,
+    )
+    where
+        ExchangeIndex: 'a,
+        AssetIndex: 'a,
+        InstrumentIndex: 'a,
+    {
+        (
+            std::iter::empty(),
+            close_positions_with_market_orders(&self.id, &state.instruments, filter),
+        )
+    }
+}
+
+impl<Clock, State, ExecutionTxs, Risk, GlobalData>
+    OnDisconnectStrategy<Clock, State, ExecutionTxs, Risk>
+    for OrderBookImbalanceStrategy<GlobalData>
+{
+    type OnDisconnect = ();
+
+    fn on_disconnect(_: ExchangeId) -> Self::OnDisconnect {}
+}
+
+impl<Clock, State, ExecutionTxs, Risk, GlobalData>
+    OnTradingDisabled<Clock, State, ExecutionTxs, Risk> for OrderBookImbalanceStrategy<GlobalData>
+{
+    type OnTradingDisabled = ();
+
+    fn on_trading_disabled() -> Self::OnTradingDisabled {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, TimeDelta, Utc};
+    use rust_decimal_macros::dec;
+    use toucan_data::books::Level;
+
+    #[test]
+    fn test_imbalance_signal() {
+        struct TestCase {
+            book: OrderBookL1,
+            expected_imbalance: Option<Decimal>,
+            expected_signal: Option<Side>,
+        }
+
+        fn book(bid_volume: Decimal, ask_volume: Decimal) -> OrderBookL1 {
+            OrderBookL1 {
+                last_update_time: Default::default(),
+                best_bid: Some(Level::new(dec!(30.00), bid_volume)),
+                best_ask: Some(Level::new(dec!(30.01), ask_volume)),
+            }
+        }
+
+        let config = OrderBookImbalanceConfig::default();
+
+        let cases = vec![
+            // TC0: balanced book does not signal
+            TestCase {
+                book: book(dec!(500), dec!(500)),
+                expected_imbalance: Some(dec!(0.5)),
+                expected_signal: None,
+            },
+            // TC1: bid volume at the threshold signals a Buy
+            TestCase {
+                book: book(dec!(600), dec!(400)),
+                expected_imbalance: Some(dec!(0.6)),
+                expected_signal: Some(Side::Buy),
+            },
+            // TC2: ask volume beyond the threshold signals a Sell
+            TestCase {
+                book: book(dec!(100), dec!(900)),
+                expected_imbalance: Some(dec!(0.1)),
+                expected_signal: Some(Side::Sell),
+            },
+            // TC3: empty book side has no imbalance
+            TestCase {
+                book: OrderBookL1 {
+                    best_ask: None,
+                    ..book(dec!(100), dec!(0))
+                },
+                expected_imbalance: None,
+                expected_signal: None,
+            },
+            // TC4: zero volume on both sides has no imbalance
+            TestCase {
+                book: book(dec!(0), dec!(0)),
+                expected_imbalance: None,
+                expected_signal: None,
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let imbalance = imbalance(&test.book);
+            assert_eq!(imbalance, test.expected_imbalance, "TC{index} failed");
+            assert_eq!(
+                imbalance.and_then(|imbalance| config.signal(imbalance)),
+                test.expected_signal,
+                "TC{index} failed"
+            );
+        }
+    }
+
+    #[test]
+    fn test_imbalance_data_last_side() {
+        enum Input {
+            Book(Decimal, Decimal),
+            Open(Side),
+        }
+
+        struct TestCase {
+            input: Input,
+            expected_last_side: Option<Side>,
+            expected_signal: Option<Side>,
+        }
+
+        fn l1(
+            second: i64,
+            bid_volume: Decimal,
+            ask_volume: Decimal,
+        ) -> MarketEvent<InstrumentIndex, DataKind> {
+            let time = DateTime::<Utc>::UNIX_EPOCH + TimeDelta::seconds(second);
+            MarketEvent {
+                time_exchange: time,
+                time_received: time,
+                exchange: ExchangeId::Mock,
+                instrument: "PETR4".to_string(),
+                kind: DataKind::OrderBookL1(OrderBookL1 {
+                    last_update_time: time,
+                    best_bid: Some(Level::new(dec!(30.00), bid_volume)),
+                    best_ask: Some(Level::new(dec!(30.01), ask_volume)),
+                }),
+            }
+        }
+
+        fn open(side: Side) -> OrderRequestOpen<ExchangeIndex, InstrumentIndex> {
+            OrderRequestOpen {
+                key: OrderKey {
+                    exchange: "mock".to_string(),
+                    instrument: "PETR4".to_string(),
+                    strategy: StrategyId::new("imbalance"),
+                    cid: ClientOrderId::new("cid"),
+                },
+                state: RequestOpen {
+                    side,
+                    price: dec!(30.01),
+                    quantity: Decimal::ONE,
+                    kind: OrderKind::Market,
+                    time_in_force: TimeInForce::ImmediateOrCancel,
+                },
+            }
+        }
+
+        let mut data = OrderBookImbalanceData::default();
+
+        let cases = vec![
+            // TC0: bid imbalance signals a Buy
+            TestCase {
+                input: Input::Book(dec!(700), dec!(300)),
+                expected_last_side: None,
+                expected_signal: Some(Side::Buy),
+            },
+            // TC1: sent Buy becomes the last_side, suppressing the signal
+            TestCase {
+                input: Input::Open(Side::Buy),
+                expected_last_side: Some(Side::Buy),
+                expected_signal: None,
+            },
+            // TC2: persisting bid imbalance does not re-trigger
+            TestCase {
+                input: Input::Book(dec!(800), dec!(200)),
+                expected_last_side: Some(Side::Buy),
+                expected_signal: None,
+            },
+            // TC3: opposite imbalance signals a Sell
+            TestCase {
+                input: Input::Book(dec!(200), dec!(800)),
+                expected_last_side: Some(Side::Buy),
+                expected_signal: Some(Side::Sell),
+            },
+            // TC4: sent Sell becomes the last_side
+            TestCase {
+                input: Input::Open(Side::Sell),
+                expected_last_side: Some(Side::Sell),
+                expected_signal: None,
+            },
+            // TC5: imbalance returning inside the threshold resets the last_side
+            TestCase {
+                input: Input::Book(dec!(500), dec!(500)),
+                expected_last_side: None,
+                expected_signal: None,
+            },
+            // TC6: renewed ask imbalance re-triggers a Sell
+            TestCase {
+                input: Input::Book(dec!(300), dec!(700)),
+                expected_last_side: None,
+                expected_signal: Some(Side::Sell),
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            match test.input {
+                Input::Book(bid_volume, ask_volume) => {
+                    data.process(&l1(index as i64 + 1, bid_volume, ask_volume))
+                }
+                Input::Open(side) => data.record_in_flight_open(&open(side)),
+            }
+
+            assert_eq!(
+                data.state.last_side, test.expected_last_side,
+                "TC{index} failed"
+            );
+            assert_eq!(data.signal(), test.expected_signal, "TC{index} failed");
+        }
     }
 }
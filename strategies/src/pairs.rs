@@ -0,0 +1,559 @@
+//! Pairs trading (statistical arbitrage) strategy.
+//!
+//! Trades the spread `y - β·x` of cointegrated instrument pairs (eg/ PETR3/PETR4 or
+//! ITUB4/BBDC4), where the hedge ratio (β) is a rolling OLS estimate. Entries & exits are driven
+//! by the z-score of the spread against its rolling mean & standard deviation.
+//!
+//! Every pair needs the prices of two instruments, so the pair statistics cannot live in a single
+//! `InstrumentDataState`. Instead, they are maintained by the [`PairsData`] `GlobalData`, which
+//! observes the `MarketEvent`s of every instrument, while the per-instrument
+//! [`DefaultInstrumentMarketData`] is left untouched.
+//!
+//! # Example
+//! ```rust,ignore
+//! let strategy = PairsStrategy::new(StrategyId::new("pairs"), PairsConfig::default())
+//!     .with_pair(Pair::new("ITUB4".to_string(), "BBDC4".to_string()));
+//!
+//! let state = EngineState::builder(&instruments, strategy.global_data(), |_| {
+//!     DefaultInstrumentMarketData::default()
+//! })
+//! .build();
+//! ```
+
+use crate::shared::{
+    close_positions_with_market_orders,
+    indicators::{Indicator, RollingRegression, RollingStdDev},
+};
+use chrono::{DateTime, Utc};
+use rust_decimal::{prelude::FromPrimitive, Decimal};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use toucan_core::{
+    engine::{
+        risk::OrderSpec,
+        state::{
+            instrument::{
+                data::{DefaultInstrumentMarketData, InstrumentDataState},
+                InstrumentState,
+            },
+            EngineState,
+        },
+        Processor,
+    },
+    Timed,
+};
+use toucan_data::event::{DataKind, MarketEvent};
+use toucan_execution::{
+    order::{
+        id::{ClientOrderId, StrategyId},
+        request::{OrderRequestCancel, OrderRequestOpen, RequestOpen},
+        OrderKey, OrderKind, TimeInForce,
+    },
+    AccountEvent, AssetIndex, ExchangeIndex, InstrumentIndex,
+};
+use toucan_instrument::{
+    exchange::ExchangeId,
+    instrument::spec::{InstrumentSpec, RoundingPolicy},
+    Side,
+};
+use toucan_trader::{
+    AlgoStrategy, ClosePositionsStrategy, OnDisconnectStrategy, OnTradingDisabled,
+};
+
+/// Configuration for the [`PairsStrategy`] and its [`PairsData`] statistics.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct PairsConfig {
+    /// Number of observations in the rolling OLS hedge ratio (β) estimate.
+    pub hedge_period: usize,
+
+    /// Number of spreads in the rolling z-score mean & standard deviation.
+    pub zscore_period: usize,
+
+    /// Absolute z-score at or beyond which the spread is entered (ie/ sold when rich, bought
+    /// when cheap).
+    pub entry_z: Decimal,
+
+    /// Absolute z-score within which an open spread has reverted and is exited.
+    pub exit_z: Decimal,
+
+    /// Quantity of the `y` leg per spread, the `x` leg quantity being `β × quantity`.
+    pub quantity: Decimal,
+}
+
+impl Default for PairsConfig {
+    fn default() -> Self {
+        Self {
+            hedge_period: 60,
+            zscore_period: 20,
+            entry_z: Decimal::TWO,
+            exit_z: Decimal::new(5, 1),
+            quantity: Decimal::ONE_HUNDRED,
+        }
+    }
+}
+
+/// Pair of instruments whose spread `y - β·x` is traded.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct Pair {
+    /// Dependent instrument of the hedge ratio regression.
+    pub y: InstrumentIndex,
+
+    /// Independent instrument of the hedge ratio regression, traded `β` times the `y` leg.
+    pub x: InstrumentIndex,
+}
+
+impl Pair {
+    pub fn new(y: InstrumentIndex, x: InstrumentIndex) -> Self {
+        Self { y, x }
+    }
+
+    fn contains(&self, instrument: &InstrumentIndex) -> bool {
+        self.y == *instrument || self.x == *instrument
+    }
+}
+
+/// Rolling statistics of a [`Pair`], updated by the [`PairsData`].
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct PairState {
+    pub pair: Pair,
+
+    /// Latest hedge ratio (β), including the latest observation, once warmed up.
+    pub hedge_ratio: Option<Decimal>,
+
+    /// Latest spread `y - β·x`, using the hedge ratio prior to the latest observation.
+    pub spread: Option<Timed<Decimal>>,
+
+    /// Latest spread z-score, once warmed up.
+    pub zscore: Option<Decimal>,
+
+    regression: RollingRegression,
+    spreads: RollingStdDev,
+}
+
+impl PairState {
+    fn new(pair: Pair, config: &PairsConfig) -> Self {
+        Self {
+            pair,
+            hedge_ratio: None,
+            spread: None,
+            zscore: None,
+            regression: RollingRegression::new(config.hedge_period),
+            spreads: RollingStdDev::new(config.zscore_period),
+        }
+    }
+
+    fn update(&mut self, time: DateTime<Utc>, y: Decimal, x: Decimal) {
+        // Spread uses the hedge ratio estimated before this observation, so that a dislocation
+        // is not absorbed by its own regression
+        if let Some(hedge_ratio) = self.hedge_ratio {
+            let spread = y - hedge_ratio * x;
+            self.spread = Some(Timed::new(spread, time));
+            self.zscore = match (self.spreads.update(spread), self.spreads.mean()) {
+                (Some(std_dev), Some(mean)) if !std_dev.is_zero() => {
+                    Some((spread - mean) / std_dev)
+                }
+                _ => None,
+            };
+        }
+
+        self.hedge_ratio = self.regression.update((x, y)).map(|fit| fit.slope);
+    }
+}
+
+/// `GlobalData` maintaining the [`PairState`] of every [`Pair`] traded by the [`PairsStrategy`].
+///
+/// Every `Candle` close or `Trade` price is a price observation of its instrument, and a pair is
+/// updated once both of its instruments have a price observation at the same exchange time (eg/
+/// the same candle close time). Observations older than the latest observation of an instrument
+/// are ignored.
+#[derive(Debug, Clone, PartialEq, Default, Deserialize, Serialize)]
+pub struct PairsData {
+    pub pairs: Vec<PairState>,
+    prices: HashMap<InstrumentIndex, Timed<Decimal>>,
+}
+
+impl PairsData {
+    /// Construct an empty [`PairsData`] for the provided pairs, using the periods of the provided
+    /// [`PairsConfig`].
+    pub fn new<'a>(config: &PairsConfig, pairs: impl IntoIterator<Item = &'a Pair>) -> Self {
+        Self {
+            pairs: pairs
+                .into_iter()
+                .map(|pair| PairState::new(pair.clone(), config))
+                .collect(),
+            prices: HashMap::new(),
+        }
+    }
+
+    /// Latest price observation of the provided instrument.
+    pub fn price(&self, instrument: &InstrumentIndex) -> Option<Decimal> {
+        self.prices.get(instrument).map(|price| price.value)
+    }
+
+    fn update_price(&mut self, instrument: &InstrumentIndex, time: DateTime<Utc>, price: f64) {
+        if self
+            .prices
+            .get(instrument)
+            .is_some_and(|last| time <= last.time)
+        {
+            return;
+        }
+        let Some(price) = Decimal::from_f64(price) else {
+            return;
+        };
+        self.prices
+            .insert(instrument.clone(), Timed::new(price, time));
+
+        for state in self
+            .pairs
+            .iter_mut()
+            .filter(|state| state.pair.contains(instrument))
+        {
+            let (Some(y), Some(x)) = (
+                self.prices.get(&state.pair.y),
+                self.prices.get(&state.pair.x),
+            ) else {
+                continue;
+            };
+
+            if y.time == time && x.time == time {
+                state.update(time, y.value, x.value);
+            }
+        }
+    }
+}
+
+impl Processor<&MarketEvent<InstrumentIndex, DataKind>> for PairsData {
+    type Audit = ();
+
+    fn process(&mut self, event: &MarketEvent<InstrumentIndex, DataKind>) -> Self::Audit {
+        match &event.kind {
+            DataKind::Candle(candle) => {
+                self.update_price(&event.instrument, event.time_exchange, candle.close)
+            }
+            DataKind::Trade(trade) => {
+                self.update_price(&event.instrument, event.time_exchange, trade.price)
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<ExchangeKey, AssetKey, InstrumentKey>
+    Processor<&AccountEvent<ExchangeKey, AssetKey, InstrumentKey>> for PairsData
+{
+    type Audit = ();
+
+    fn process(&mut self, _: &AccountEvent<ExchangeKey, AssetKey, InstrumentKey>) -> Self::Audit {}
+}
+
+/// Pairs trading strategy.
+///
+/// For every [`Pair`] without in-flight orders on either leg, the strategy sends simultaneous
+/// IOC market orders to:
+/// 1. Enter the spread when flat and the z-score is at or beyond `entry_z`: sell `y` & buy `x`
+///    when the spread is rich, buy `y` & sell `x` when it is cheap.
+/// 2. Exit both legs once the z-score reverts to within `exit_z` (or crosses to the other side).
+///
+/// Leg risk: if only one leg of an entry (or exit) fills, the pair is left with a single open
+/// leg, which is unwound with an IOC market order before the pair is traded again.
+///
+/// Leg quantities are rounded to the [`InstrumentSpec`] lot size of each leg (if configured).
+#[derive(Debug, Clone)]
+pub struct PairsStrategy {
+    pub id: StrategyId,
+    pub config: PairsConfig,
+
+    /// Traded pairs.
+    pub pairs: Vec<Pair>,
+
+    /// [`OrderSpec`]s used to round leg quantities, keyed by instrument.
+    pub specs: HashMap<InstrumentIndex, OrderSpec>,
+}
+
+impl PairsStrategy {
+    /// Construct a new [`PairsStrategy`] without any pairs.
+    pub fn new(id: StrategyId, config: PairsConfig) -> Self {
+        Self {
+            id,
+            config,
+            pairs: Vec::new(),
+            specs: HashMap::new(),
+        }
+    }
+
+    /// Trade the spread of the provided [`Pair`].
+    pub fn with_pair(mut self, pair: Pair) -> Self {
+        self.pairs.push(pair);
+        self
+    }
+
+    /// Configure the [`InstrumentSpec`] & contract size used to round the leg quantities of an
+    /// individual instrument.
+    pub fn with_instrument_spec(
+        mut self,
+        instrument: InstrumentIndex,
+        spec: InstrumentSpec<AssetIndex>,
+        contract_size: Decimal,
+    ) -> Self {
+        self.specs.insert(
+            instrument,
+            OrderSpec {
+                spec,
+                contract_size,
+            },
+        );
+        self
+    }
+
+    /// Construct the [`PairsData`] `GlobalData` required by the [`EngineState`].
+    pub fn global_data(&self) -> PairsData {
+        PairsData::new(&self.config, &self.pairs)
+    }
+
+    fn round_quantity(&self, instrument: &InstrumentIndex, quantity: Decimal) -> Decimal {
+        match self.specs.get(instrument) {
+            Some(OrderSpec { spec, .. }) => spec.quantity.round(quantity, RoundingPolicy::Nearest),
+            None => quantity.abs(),
+        }
+    }
+
+    fn pair_requests(
+        &self,
+        state: &EngineState<PairsData, DefaultInstrumentMarketData>,
+        pair: &PairState,
+    ) -> Vec<OrderRequestOpen<ExchangeIndex, InstrumentIndex>> {
+        let (Some(y), Some(x)) = (
+            state.instruments.0.get(&pair.pair.y),
+            state.instruments.0.get(&pair.pair.x),
+        ) else {
+            return Vec::new();
+        };
+        if !y.orders.0.is_empty() || !x.orders.0.is_empty() {
+            return Vec::new();
+        }
+
+        let legs = match (y.position.current.as_ref(), x.position.current.as_ref()) {
+            // Leg risk: unwind the single open leg
+            (Some(position), None) => vec![(y, opposite(position.side), position.quantity_abs)],
+            (None, Some(position)) => vec![(x, opposite(position.side), position.quantity_abs)],
+            (Some(y_position), Some(x_position)) => {
+                let Some(zscore) = pair.zscore else {
+                    return Vec::new();
+                };
+                let reverted = match y_position.side {
+                    Side::Buy => zscore >= -self.config.exit_z,
+                    Side::Sell => zscore <= self.config.exit_z,
+                };
+                if !reverted {
+                    return Vec::new();
+                }
+
+                vec![
+                    (y, opposite(y_position.side), y_position.quantity_abs),
+                    (x, opposite(x_position.side), x_position.quantity_abs),
+                ]
+            }
+            (None, None) => {
+                let (Some(zscore), Some(hedge_ratio)) = (pair.zscore, pair.hedge_ratio) else {
+                    return Vec::new();
+                };
+                // Negative hedge ratios are not a spread between co-moving instruments
+                if hedge_ratio <= Decimal::ZERO {
+                    return Vec::new();
+                }
+
+                let y_side = if zscore >= self.config.entry_z {
+                    Side::Sell
+                } else if zscore <= -self.config.entry_z {
+                    Side::Buy
+                } else {
+                    return Vec::new();
+                };
+
+                let y_quantity = self.round_quantity(&pair.pair.y, self.config.quantity);
+                let x_quantity = self.round_quantity(&pair.pair.x, hedge_ratio * y_quantity);
+                if y_quantity.is_zero() || x_quantity.is_zero() {
+                    return Vec::new();
+                }
+
+                vec![(y, y_side, y_quantity), (x, opposite(y_side), x_quantity)]
+            }
+        };
+
+        // Both legs must be priced, so that a pair is never entered one leg at a time
+        legs.into_iter()
+            .map(|(leg, side, quantity)| {
+                let price = leg.data.price().or(state.global.price(&leg.key))?;
+                Some(self.market_request(leg, side, quantity, price))
+            })
+            .collect::<Option<Vec<_>>>()
+            .unwrap_or_default()
+    }
+
+    fn market_request(
+        &self,
+        state: &InstrumentState<DefaultInstrumentMarketData>,
+        side: Side,
+        quantity: Decimal,
+        price: Decimal,
+    ) -> OrderRequestOpen<ExchangeIndex, InstrumentIndex> {
+        OrderRequestOpen {
+            key: OrderKey {
+                exchange: state.instrument.exchange.to_string(),
+                instrument: state.key.clone(),
+                strategy: self.id.clone(),
+                cid: ClientOrderId::random(),
+            },
+            state: RequestOpen {
+                side,
+                price,
+                quantity,
+                kind: OrderKind::Market,
+                time_in_force: TimeInForce::ImmediateOrCancel,
+            },
+        }
+    }
+}
+
+impl AlgoStrategy for PairsStrategy {
+    type State = EngineState<PairsData, DefaultInstrumentMarketData>;
+
+    fn generate_algo_orders(
+        &self,
+        state: &Self::State,
+    ) -> (
+        impl IntoIterator<Item = OrderRequestCancel<ExchangeIndex, InstrumentIndex>>,
+        impl IntoIterator<Item = OrderRequestOpen<ExchangeIndex, InstrumentIndex>>,
+    ) {
+        let opens = state
+            .global
+            .pairs
+            .iter()
+            .flat_map(|pair| self.pair_requests(state, pair))
+            .collect::<Vec<_>>();
+
+        (std::iter::empty(), opens)
+    }
+}
+
+impl ClosePositionsStrategy for PairsStrategy {
+    type State = EngineState<PairsData, DefaultInstrumentMarketData>;
+
+    fn close_positions_requests<'a>(
+        &'a self,
+        state: &'a Self::State,
+        filter: &'a impl std::fmt::Debug,
+    ) -> (
+        impl IntoIterator<Item = OrderRequestCancel<ExchangeIndex, InstrumentIndex>> + 'a,
+        impl IntoIterator<Item = OrderRequestOpen<ExchangeIndex, InstrumentIndex>> + 'a,
+    )
+    where
+        ExchangeIndex: 'a,
+        AssetIndex: 'a,
+        InstrumentIndex: 'a,
+    {
+        (
+            std::iter::empty(),
+            close_positions_with_market_orders(&self.id, &state.instruments, filter),
+        )
+    }
+}
+
+impl<Clock, State, ExecutionTxs, Risk> OnDisconnectStrategy<Clock, State, ExecutionTxs, Risk>
+    for PairsStrategy
+{
+    type OnDisconnect = ();
+
+    fn on_disconnect(_: ExchangeId) -> Self::OnDisconnect {}
+}
+
+impl<Clock, State, ExecutionTxs, Risk> OnTradingDisabled<Clock, State, ExecutionTxs, Risk>
+    for PairsStrategy
+{
+    type OnTradingDisabled = ();
+
+    fn on_trading_disabled() -> Self::OnTradingDisabled {}
+}
+
+fn opposite(side: Side) -> Side {
+    match side {
+        Side::Buy => Side::Sell,
+        Side::Sell => Side::Buy,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use toucan_data::subscription::candle::Candle;
+
+    fn candle(instrument: &str, bar: i64, close: f64) -> MarketEvent<InstrumentIndex, DataKind> {
+        let time = DateTime::<Utc>::MIN_UTC + chrono::TimeDelta::days(bar);
+        MarketEvent {
+            time_exchange: time,
+            time_received: time,
+            exchange: ExchangeId::Mock,
+            instrument: instrument.to_string(),
+            kind: DataKind::Candle(Candle {
+                close_time: time,
+                open: close,
+                high: close,
+                low: close,
+                close,
+                volume: 1_000.0,
+                trade_count: 100,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_pairs_data() {
+        let config = PairsConfig {
+            hedge_period: 3,
+            zscore_period: 3,
+            ..PairsConfig::default()
+        };
+        let mut data = PairsData::new(&config, &[Pair::new("Y".to_string(), "X".to_string())]);
+
+        // TC0: pair is only observed once both legs have a price at the same time, so the hedge
+        // ratio is not warmed up by the y observation without an x observation
+        data.process(&candle("Y", 0, 21.0));
+        assert_eq!(data.price(&"Y".to_string()), Some(dec!(21)), "TC0 failed");
+        for (bar, x) in [(1, 10.0), (2, 11.0)] {
+            data.process(&candle("X", bar, x));
+            data.process(&candle("Y", bar, 2.0 * x + 1.0));
+        }
+        assert_eq!(data.pairs[0].hedge_ratio, None, "TC0 failed");
+
+        // TC1: hedge ratio warms up with y = 2x + 1, so subsequent spreads are constant without a
+        // z-score
+        for (bar, x) in [(3, 12.0), (4, 11.0), (5, 10.0), (6, 12.0)] {
+            data.process(&candle("X", bar, x));
+            data.process(&candle("Y", bar, 2.0 * x + 1.0));
+        }
+        assert_eq!(data.pairs[0].hedge_ratio, Some(dec!(2)), "TC1 failed");
+        assert_eq!(
+            data.pairs[0].spread.as_ref().map(|spread| spread.value),
+            Some(dec!(1)),
+            "TC1 failed"
+        );
+        assert_eq!(data.pairs[0].zscore, None, "TC1 failed");
+
+        // TC2: stale observations are ignored
+        data.process(&candle("X", 0, 50.0));
+        assert_eq!(data.price(&"X".to_string()), Some(dec!(12)), "TC2 failed");
+
+        // TC3: y dislocation above the regression line at the mean x leaves the hedge ratio
+        // unchanged (spreads [1, 1, 4] => z = sqrt(2))
+        data.process(&candle("X", 7, 11.0));
+        data.process(&candle("Y", 7, 26.0));
+        assert_eq!(data.pairs[0].hedge_ratio, Some(dec!(2)), "TC3 failed");
+        assert_eq!(
+            data.pairs[0].zscore.map(|zscore| zscore.round_dp(4)),
+            Some(dec!(1.4142)),
+            "TC3 failed"
+        );
+    }
+}
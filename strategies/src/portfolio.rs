@@ -0,0 +1,677 @@
+//! Multi-strategy composition with per-strategy budgets.
+//!
+//! The `Engine` is generic over a single `Strategy`, so several strategies are run by the same
+//! `Engine` by composing them into a [`StrategyPortfolio`]. The portfolio implements the trader
+//! traits by delegating to its child strategies, each with its own [`StrategyId`] &
+//! [`StrategyBudget`], and post-processes the child orders before they reach the `RiskManager`:
+//! 1. Child orders are attributed to the [`StrategyId`] of their child.
+//! 2. Child orders breaching the [`StrategyBudget`] of their child are dropped.
+//! 3. Opposing orders on the same instrument are deconflicted (see [`ConflictPolicy`]).
+//!
+//! Engine positions are maintained per instrument, so the exposure of every child strategy is
+//! attributed by the [`StrategyBook`] `GlobalData`, which observes the `Trade` of every order.
+//!
+//! # Shared Instruments
+//! Child strategies only see the instrument-level `Position` & orders of the `EngineState`, not
+//! their own share of them. Two children trading the same instrument based on its position (as
+//! every strategy of this crate does) would act on each other's exposure, eg/ one child reversing
+//! the position opened by another, with the [`StrategyBook`] attributing the whole reversal to
+//! it. Such children must be scoped to disjoint instruments with
+//! [`StrategyPortfolio::with_scoped_strategy`].
+//!
+//! Only children whose orders do not depend on the instrument position should share
+//! instruments, in which case their opposing orders are deconflicted by the [`ConflictPolicy`].
+//!
+//! Children share a single `Strategy` type, so heterogeneous strategies are composed via an enum
+//! that delegates the trader traits to its variants.
+//!
+//! # Example
+//! ```rust,ignore
+//! let strategy = StrategyPortfolio::new()
+//!     .with_scoped_strategy(
+//!         StrategyId::new("imbalance_petr4"),
+//!         OrderBookImbalanceStrategy::new(petr4).with_id(StrategyId::new("imbalance_petr4")),
+//!         StrategyBudget::allocation(dec!(1_000_000), dec!(0.6)),
+//!         ["PETR4".to_string()],
+//!     )
+//!     .with_scoped_strategy(
+//!         StrategyId::new("imbalance_vale3"),
+//!         OrderBookImbalanceStrategy::new(vale3).with_id(StrategyId::new("imbalance_vale3")),
+//!         StrategyBudget::allocation(dec!(1_000_000), dec!(0.4)),
+//!         ["VALE3".to_string()],
+//!     );
+//!
+//! let state = EngineState::builder(&instruments, StrategyBook::default(), |instrument| {
+//!     match instrument.key.index() {
+//!         0 => OrderBookImbalanceData::new(&petr4),
+//!         _ => OrderBookImbalanceData::new(&vale3),
+//!     }
+//! })
+//! .build();
+//! ```
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use toucan_core::engine::{
+    state::{instrument::data::InstrumentDataState, EngineState},
+    Processor,
+};
+use toucan_data::event::{DataKind, MarketEvent};
+use toucan_execution::{
+    order::{
+        id::StrategyId,
+        request::{OrderRequestCancel, OrderRequestOpen},
+    },
+    AccountEvent, AccountEventKind, AssetIndex, ExchangeIndex, InstrumentIndex,
+};
+use toucan_instrument::{exchange::ExchangeId, Side};
+use toucan_trader::{
+    AlgoStrategy, ClosePositionsStrategy, OnDisconnectStrategy, OnTradingDisabled,
+};
+use tracing::warn;
+
+/// Capital & risk budget of a child strategy of a [`StrategyPortfolio`].
+///
+/// Exposures include the open orders of the child strategy, and are valued at the latest
+/// instrument price (falling back to the order price). Orders reducing the exposure of a child
+/// strategy are always within budget.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Deserialize, Serialize)]
+pub struct StrategyBudget {
+    /// Maximum gross notional (ie/ `Σ |quantity| × price`) of the strategy exposures.
+    pub max_notional: Option<Decimal>,
+
+    /// Maximum absolute exposure quantity of the strategy per instrument.
+    pub max_position: Option<Decimal>,
+}
+
+impl StrategyBudget {
+    /// Notional budget allocating `weight` (0-1) of the portfolio `capital`.
+    pub fn allocation(capital: Decimal, weight: Decimal) -> Self {
+        Self {
+            max_notional: Some(capital * weight),
+            max_position: None,
+        }
+    }
+
+    /// Limit the absolute exposure quantity per instrument.
+    pub fn with_max_position(self, max_position: Decimal) -> Self {
+        Self {
+            max_position: Some(max_position),
+            ..self
+        }
+    }
+}
+
+/// Resolution of opposing orders generated by different child strategies for the same
+/// instrument.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Deserialize, Serialize)]
+pub enum ConflictPolicy {
+    /// Keep the orders of the first child strategy (in insertion order), dropping the opposing
+    /// orders of the subsequent child strategies.
+    #[default]
+    Priority,
+
+    /// Drop every order opposed by an order of another child strategy.
+    Cancel,
+}
+
+/// Net position of a strategy in an instrument, as attributed by the [`StrategyBook`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Deserialize, Serialize)]
+pub struct StrategyPosition {
+    /// Signed net quantity (ie/ positive when long, negative when short).
+    pub quantity: Decimal,
+
+    /// Price of the latest `Trade`.
+    pub price: Decimal,
+}
+
+/// `GlobalData` attributing the net position of every strategy in every instrument, updated by
+/// the `Trade` of every order.
+///
+/// Required by the [`StrategyPortfolio`] (via `AsRef<StrategyBook>`) to enforce the
+/// [`StrategyBudget`] of its child strategies.
+#[derive(Debug, Clone, PartialEq, Default, Deserialize, Serialize)]
+pub struct StrategyBook {
+    pub positions: HashMap<StrategyId, HashMap<InstrumentIndex, StrategyPosition>>,
+}
+
+impl StrategyBook {
+    /// [`StrategyPosition`] of the provided strategy in the provided instrument, if traded.
+    pub fn position(
+        &self,
+        strategy: &StrategyId,
+        instrument: &InstrumentIndex,
+    ) -> Option<&StrategyPosition> {
+        self.positions.get(strategy)?.get(instrument)
+    }
+}
+
+impl AsRef<StrategyBook> for StrategyBook {
+    fn as_ref(&self) -> &StrategyBook {
+        self
+    }
+}
+
+impl<ExchangeKey, AssetKey> Processor<&AccountEvent<ExchangeKey, AssetKey, InstrumentIndex>>
+    for StrategyBook
+{
+    type Audit = ();
+
+    fn process(&mut self, event: &AccountEvent<ExchangeKey, AssetKey, InstrumentIndex>) {
+        let AccountEventKind::Trade(trade) = &event.kind else {
+            return;
+        };
+
+        let position = self
+            .positions
+            .entry(trade.strategy.clone())
+            .or_default()
+            .entry(trade.instrument.clone())
+            .or_default();
+
+        position.quantity += signed(trade.side, trade.quantity);
+        position.price = trade.price;
+    }
+}
+
+impl Processor<&MarketEvent<InstrumentIndex, DataKind>> for StrategyBook {
+    type Audit = ();
+
+    fn process(&mut self, _: &MarketEvent<InstrumentIndex, DataKind>) -> Self::Audit {}
+}
+
+/// Child strategy of a [`StrategyPortfolio`].
+#[derive(Debug, Clone)]
+pub struct PortfolioMember<Strategy> {
+    /// [`StrategyId`] every order of the child strategy is attributed to.
+    pub id: StrategyId,
+    pub strategy: Strategy,
+    pub budget: StrategyBudget,
+
+    /// Instruments the child strategy is scoped to, or `None` if it may trade every instrument.
+    pub instruments: Option<HashSet<InstrumentIndex>>,
+}
+
+impl<Strategy> PortfolioMember<Strategy> {
+    /// Returns `true` if the child strategy is scoped to the provided instrument.
+    pub fn trades(&self, instrument: &InstrumentIndex) -> bool {
+        self.instruments
+            .as_ref()
+            .is_none_or(|instruments| instruments.contains(instrument))
+    }
+}
+
+/// Strategy combinator delegating to N child strategies.
+///
+/// Every order generated by a child strategy is attributed to the [`StrategyId`] of its
+/// [`PortfolioMember`], so the [`StrategyBook`] can attribute its fills. Child opens that would
+/// breach the [`StrategyBudget`] of the child are dropped, and opposing opens generated by
+/// different children for the same instrument are deconflicted with the [`ConflictPolicy`].
+///
+/// Orders generated by a scoped child for instruments outside of its scope are dropped (see
+/// [`Self::with_scoped_strategy`]).
+///
+/// Closing positions is also delegated to every child strategy, but positions are maintained per
+/// instrument, so only the first close order per instrument (within the scope of its child) is
+/// kept.
+#[derive(Debug, Clone)]
+pub struct StrategyPortfolio<Strategy> {
+    pub members: Vec<PortfolioMember<Strategy>>,
+    pub conflict_policy: ConflictPolicy,
+}
+
+impl<Strategy> Default for StrategyPortfolio<Strategy> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Strategy> StrategyPortfolio<Strategy> {
+    /// Construct a new [`StrategyPortfolio`] without any child strategies.
+    pub fn new() -> Self {
+        Self {
+            members: Vec::new(),
+            conflict_policy: ConflictPolicy::default(),
+        }
+    }
+
+    /// Add a child strategy with its own [`StrategyId`] & [`StrategyBudget`], which may trade
+    /// every instrument.
+    pub fn with_strategy(
+        mut self,
+        id: StrategyId,
+        strategy: Strategy,
+        budget: StrategyBudget,
+    ) -> Self {
+        self.members.push(PortfolioMember {
+            id,
+            strategy,
+            budget,
+            instruments: None,
+        });
+        self
+    }
+
+    /// Add a child strategy with its own [`StrategyId`] & [`StrategyBudget`], scoped to the
+    /// provided instruments.
+    ///
+    /// Children acting on the instrument-level `Position` must be scoped to disjoint instruments.
+    pub fn with_scoped_strategy<InstrumentIter>(
+        mut self,
+        id: StrategyId,
+        strategy: Strategy,
+        budget: StrategyBudget,
+        instruments: InstrumentIter,
+    ) -> Self
+    where
+        InstrumentIter: IntoIterator<Item = InstrumentIndex>,
+    {
+        self.members.push(PortfolioMember {
+            id,
+            strategy,
+            budget,
+            instruments: Some(instruments.into_iter().collect()),
+        });
+        self
+    }
+
+    /// Use the provided [`ConflictPolicy`] for opposing child orders.
+    pub fn with_conflict_policy(self, conflict_policy: ConflictPolicy) -> Self {
+        Self {
+            conflict_policy,
+            ..self
+        }
+    }
+
+    /// Drop the opens that would breach the [`StrategyBudget`] of the child strategy, considering
+    /// the opens approved before them.
+    fn within_budget<GlobalData, InstrumentData>(
+        member: &PortfolioMember<Strategy>,
+        state: &EngineState<GlobalData, InstrumentData>,
+        opens: Vec<OrderRequestOpen<ExchangeIndex, InstrumentIndex>>,
+    ) -> Vec<OrderRequestOpen<ExchangeIndex, InstrumentIndex>>
+    where
+        GlobalData: AsRef<StrategyBook>,
+        InstrumentData: InstrumentDataState,
+    {
+        let mut exposures = exposures(&member.id, state);
+
+        opens
+            .into_iter()
+            .filter(|open| {
+                let price = state
+                    .instruments
+                    .0
+                    .get(&open.key.instrument)
+                    .and_then(|instrument| instrument.data.price())
+                    .unwrap_or(open.state.price);
+
+                let current = exposures
+                    .get(&open.key.instrument)
+                    .map_or(Decimal::ZERO, |(quantity, _)| *quantity);
+                let projected = current + signed(open.state.side, open.state.quantity);
+
+                if projected.abs() > current.abs() {
+                    let breach = match member.budget {
+                        StrategyBudget {
+                            max_position: Some(max_position),
+                            ..
+                        } if projected.abs() > max_position => Some("max_position"),
+                        StrategyBudget {
+                            max_notional: Some(max_notional),
+                            ..
+                        } => {
+                            let gross = exposures
+                                .iter()
+                                .filter(|(instrument, _)| **instrument != open.key.instrument)
+                                .map(|(_, (quantity, price))| quantity.abs() * price)
+                                .sum::<Decimal>()
+                                + projected.abs() * price;
+
+                            (gross > max_notional).then_some("max_notional")
+                        }
+                        _ => None,
+                    };
+
+                    if let Some(breach) = breach {
+                        warn!(
+                            strategy = %member.id,
+                            instrument = %open.key.instrument,
+                            breach,
+                            "StrategyPortfolio dropped child order breaching StrategyBudget"
+                        );
+                        return false;
+                    }
+                }
+
+                exposures.insert(open.key.instrument.clone(), (projected, price));
+                true
+            })
+            .collect()
+    }
+
+    /// Deconflict opposing opens generated by different child strategies for the same
+    /// instrument, using the [`ConflictPolicy`].
+    fn deconflict(
+        &self,
+        opens: Vec<(usize, OrderRequestOpen<ExchangeIndex, InstrumentIndex>)>,
+    ) -> Vec<OrderRequestOpen<ExchangeIndex, InstrumentIndex>> {
+        // Opens are ordered by child strategy, so kept opens are of higher (or equal) priority
+        let mut kept = Vec::with_capacity(opens.len());
+        for open in &opens {
+            let keep = match self.conflict_policy {
+                ConflictPolicy::Priority => !kept.iter().any(|other| opposes(open, other)),
+                ConflictPolicy::Cancel => !opens.iter().any(|other| opposes(open, other)),
+            };
+
+            if keep {
+                kept.push(open.clone());
+            } else {
+                warn!(
+                    strategy = %self.members[open.0].id,
+                    instrument = %open.1.key.instrument,
+                    policy = ?self.conflict_policy,
+                    "StrategyPortfolio dropped conflicting child order"
+                );
+            }
+        }
+
+        kept.into_iter().map(|(_, open)| open).collect()
+    }
+}
+
+impl<Strategy, GlobalData, InstrumentData> AlgoStrategy for StrategyPortfolio<Strategy>
+where
+    Strategy: AlgoStrategy<State = EngineState<GlobalData, InstrumentData>>,
+    GlobalData: AsRef<StrategyBook>,
+    InstrumentData: InstrumentDataState,
+{
+    type State = EngineState<GlobalData, InstrumentData>;
+
+    fn generate_algo_orders(
+        &self,
+        state: &Self::State,
+    ) -> (
+        impl IntoIterator<Item = OrderRequestCancel<ExchangeIndex, InstrumentIndex>>,
+        impl IntoIterator<Item = OrderRequestOpen<ExchangeIndex, InstrumentIndex>>,
+    ) {
+        let mut cancels = Vec::new();
+        let mut opens = Vec::new();
+
+        for (index, member) in self.members.iter().enumerate() {
+            let (member_cancels, member_opens) = member.strategy.generate_algo_orders(state);
+
+            cancels.extend(
+                member_cancels
+                    .into_iter()
+                    .filter(|cancel| member.trades(&cancel.key.instrument))
+                    .map(|mut cancel| {
+                        cancel.key.strategy = member.id.clone();
+                        cancel
+                    }),
+            );
+
+            let member_opens = member_opens
+                .into_iter()
+                .filter(|open| member.trades(&open.key.instrument))
+                .map(|mut open| {
+                    open.key.strategy = member.id.clone();
+                    open
+                })
+                .collect();
+
+            opens.extend(
+                Self::within_budget(member, state, member_opens)
+                    .into_iter()
+                    .map(|open| (index, open)),
+            );
+        }
+
+        (cancels, self.deconflict(opens))
+    }
+}
+
+impl<Strategy, GlobalData, InstrumentData> ClosePositionsStrategy for StrategyPortfolio<Strategy>
+where
+    Strategy: ClosePositionsStrategy<State = EngineState<GlobalData, InstrumentData>>,
+{
+    type State = EngineState<GlobalData, InstrumentData>;
+
+    fn close_positions_requests<'a>(
+        &'a self,
+        state: &'a Self::State,
+        filter: &'a impl std::fmt::Debug,
+    ) -> (
+        impl IntoIterator<Item = OrderRequestCancel<ExchangeIndex, InstrumentIndex>> + 'a,
+        impl IntoIterator<Item = OrderRequestOpen<ExchangeIndex, InstrumentIndex>> + 'a,
+    )
+    where
+        ExchangeIndex: 'a,
+        AssetIndex: 'a,
+        InstrumentIndex: 'a,
+    {
+        let mut cancelled = HashSet::new();
+        let mut closed = HashSet::new();
+        let mut cancels = Vec::new();
+        let mut opens = Vec::new();
+
+        for member in &self.members {
+            let (member_cancels, member_opens) =
+                member.strategy.close_positions_requests(state, filter);
+
+            cancels.extend(
+                member_cancels
+                    .into_iter()
+                    .filter(|cancel| member.trades(&cancel.key.instrument))
+                    .filter(|cancel| cancelled.insert(cancel.key.cid.clone()))
+                    .map(|mut cancel| {
+                        cancel.key.strategy = member.id.clone();
+                        cancel
+                    }),
+            );
+
+            opens.extend(
+                member_opens
+                    .into_iter()
+                    .filter(|open| member.trades(&open.key.instrument))
+                    .filter(|open| closed.insert(open.key.instrument.clone()))
+                    .map(|mut open| {
+                        open.key.strategy = member.id.clone();
+                        open
+                    }),
+            );
+        }
+
+        (cancels, opens)
+    }
+}
+
+impl<Clock, State, ExecutionTxs, Risk, Strategy>
+    OnDisconnectStrategy<Clock, State, ExecutionTxs, Risk> for StrategyPortfolio<Strategy>
+where
+    Strategy: OnDisconnectStrategy<Clock, State, ExecutionTxs, Risk>,
+{
+    type OnDisconnect = Strategy::OnDisconnect;
+
+    fn on_disconnect(exchange: ExchangeId) -> Self::OnDisconnect {
+        Strategy::on_disconnect(exchange)
+    }
+}
+
+impl<Clock, State, ExecutionTxs, Risk, Strategy> OnTradingDisabled<Clock, State, ExecutionTxs, Risk>
+    for StrategyPortfolio<Strategy>
+where
+    Strategy: OnTradingDisabled<Clock, State, ExecutionTxs, Risk>,
+{
+    type OnTradingDisabled = Strategy::OnTradingDisabled;
+
+    fn on_trading_disabled() -> Self::OnTradingDisabled {
+        Strategy::on_trading_disabled()
+    }
+}
+
+/// Signed exposure quantity & price of the provided strategy per instrument, including the
+/// remaining quantity of its open orders.
+fn exposures<GlobalData, InstrumentData>(
+    strategy: &StrategyId,
+    state: &EngineState<GlobalData, InstrumentData>,
+) -> HashMap<InstrumentIndex, (Decimal, Decimal)>
+where
+    GlobalData: AsRef<StrategyBook>,
+    InstrumentData: InstrumentDataState,
+{
+    let mut exposures = state
+        .global
+        .as_ref()
+        .positions
+        .get(strategy)
+        .into_iter()
+        .flatten()
+        .map(|(instrument, position)| (instrument.clone(), (position.quantity, position.price)))
+        .collect::<HashMap<_, _>>();
+
+    for instrument in state.instruments.0.values() {
+        for order in instrument
+            .orders
+            .0
+            .values()
+            .filter(|order| order.key.strategy == *strategy)
+        {
+            let remaining = order.state.open_meta().map_or(order.quantity, |open| {
+                open.quantity_remaining(order.quantity)
+            });
+
+            let (quantity, _) = exposures
+                .entry(instrument.key.clone())
+                .or_insert((Decimal::ZERO, order.price));
+            *quantity += signed(order.side, remaining);
+        }
+
+        if let (Some((_, price)), Some(latest)) =
+            (exposures.get_mut(&instrument.key), instrument.data.price())
+        {
+            *price = latest;
+        }
+    }
+
+    exposures
+}
+
+/// Determine if the provided child strategy opens are opposing orders of different child
+/// strategies for the same instrument.
+fn opposes(
+    (member, open): &(usize, OrderRequestOpen),
+    (other_member, other): &(usize, OrderRequestOpen),
+) -> bool {
+    member != other_member
+        && open.key.instrument == other.key.instrument
+        && open.state.side != other.state.side
+}
+
+fn signed(side: Side, quantity: Decimal) -> Decimal {
+    match side {
+        Side::Buy => quantity,
+        Side::Sell => -quantity,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Utc};
+    use rust_decimal_macros::dec;
+    use toucan_execution::{
+        order::id::OrderId,
+        trade::{AssetFees, Trade, TradeId},
+    };
+
+    fn trade(strategy: &str, side: Side, price: Decimal, quantity: Decimal) -> AccountEvent {
+        AccountEvent {
+            exchange: ExchangeId::Mock.to_string(),
+            broker: None,
+            account: None,
+            trace: None,
+            kind: AccountEventKind::Trade(Trade {
+                id: TradeId::new("trade"),
+                order_id: OrderId::new("order"),
+                instrument: "PETR4".to_string(),
+                strategy: StrategyId::new(strategy),
+                time_exchange: DateTime::<Utc>::MIN_UTC,
+                side,
+                price,
+                quantity,
+                fees: AssetFees::quote_fees(Decimal::ZERO),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_strategy_book() {
+        struct TestCase {
+            trade: AccountEvent,
+            expected: Vec<(&'static str, Option<StrategyPosition>)>,
+        }
+
+        let cases = vec![
+            // TC0: first trade opens the strategy position
+            TestCase {
+                trade: trade("buyer", Side::Buy, dec!(30.00), dec!(200)),
+                expected: vec![
+                    (
+                        "buyer",
+                        Some(StrategyPosition {
+                            quantity: dec!(200),
+                            price: dec!(30.00),
+                        }),
+                    ),
+                    ("seller", None),
+                ],
+            },
+            // TC1: trade of another strategy in the same instrument is attributed separately
+            TestCase {
+                trade: trade("seller", Side::Sell, dec!(30.10), dec!(100)),
+                expected: vec![
+                    (
+                        "buyer",
+                        Some(StrategyPosition {
+                            quantity: dec!(200),
+                            price: dec!(30.00),
+                        }),
+                    ),
+                    (
+                        "seller",
+                        Some(StrategyPosition {
+                            quantity: dec!(-100),
+                            price: dec!(30.10),
+                        }),
+                    ),
+                ],
+            },
+            // TC2: opposite trade reduces the strategy position
+            TestCase {
+                trade: trade("buyer", Side::Sell, dec!(30.20), dec!(50)),
+                expected: vec![(
+                    "buyer",
+                    Some(StrategyPosition {
+                        quantity: dec!(150),
+                        price: dec!(30.20),
+                    }),
+                )],
+            },
+        ];
+
+        let mut book = StrategyBook::default();
+
+        for (index, test) in cases.into_iter().enumerate() {
+            book.process(&test.trade);
+
+            for (strategy, expected) in test.expected {
+                let position = book
+                    .position(&StrategyId::new(strategy), &"PETR4".to_string())
+                    .copied();
+                assert_eq!(position, expected, "TC{index} failed: {strategy}");
+            }
+        }
+    }
+}
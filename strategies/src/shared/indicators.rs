@@ -0,0 +1,1058 @@
+//! Incremental technical indicators.
+//!
+//! Every indicator is updated one observation at a time with [`Indicator::update`] (like the
+//! `DataSetSummary` of `toucan-analytics`), using `O(1)` memory allocated up front: rolling
+//! indicators store their window in a fixed-capacity [`RollingWindow`], so updates never
+//! allocate.
+//!
+//! Indicators are not valid until they are warmed up, so [`Indicator::update`] &
+//! [`Indicator::value`] return `None` until enough observations have been seen (see
+//! [`Indicator::is_ready`]).
+//!
+//! # Example
+//! ```
+//! use rust_decimal_macros::dec;
+//! use toucan_strategies::shared::indicators::{Indicator, Sma};
+//!
+//! let mut sma = Sma::new(3);
+//!
+//! assert_eq!(sma.update(dec!(1)), None);
+//! assert_eq!(sma.update(dec!(2)), None);
+//! assert_eq!(sma.update(dec!(3)), Some(dec!(2)));
+//! assert_eq!(sma.update(dec!(7)), Some(dec!(4)));
+//! assert!(sma.is_ready());
+//! ```
+
+use rust_decimal::{Decimal, MathematicalOps};
+use serde::{Deserialize, Serialize};
+use toucan_data::books::Level;
+
+/// Incremental indicator updated one observation at a time.
+pub trait Indicator {
+    type Input;
+    type Output;
+
+    /// Update the indicator with the next observation, returning the new value once warmed up.
+    fn update(&mut self, input: Self::Input) -> Option<Self::Output>;
+
+    /// Latest value, or `None` if the indicator is not warmed up yet.
+    fn value(&self) -> Option<Self::Output>;
+
+    /// Reset the indicator to its initial (not warmed up) state, retaining its configuration.
+    fn reset(&mut self);
+
+    /// Determine if the indicator is warmed up, ie/ it has a valid value.
+    fn is_ready(&self) -> bool {
+        self.value().is_some()
+    }
+}
+
+/// Fixed-capacity ring buffer of the most recent `period` values.
+///
+/// Storage is allocated once on construction, so pushing values never allocates.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct RollingWindow {
+    values: Vec<Decimal>,
+    head: usize,
+    len: usize,
+}
+
+impl RollingWindow {
+    /// Construct an empty [`RollingWindow`] holding the most recent `period` values (at least 1).
+    pub fn new(period: usize) -> Self {
+        Self {
+            values: vec![Decimal::ZERO; period.max(1)],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Push the next value, returning the oldest value if it was evicted from a full window.
+    pub fn push(&mut self, value: Decimal) -> Option<Decimal> {
+        let evicted = self.is_full().then(|| self.values[self.head]);
+        self.values[self.head] = value;
+        self.head = (self.head + 1) % self.values.len();
+        self.len = (self.len + 1).min(self.values.len());
+        evicted
+    }
+
+    /// Capacity of the window.
+    pub fn period(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Number of values in the window.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Determine if the window holds `period` values.
+    pub fn is_full(&self) -> bool {
+        self.len == self.values.len()
+    }
+
+    /// Iterate over the values in the window, from oldest to newest.
+    pub fn iter(&self) -> impl Iterator<Item = Decimal> + '_ {
+        let start = (self.head + self.values.len() - self.len) % self.values.len();
+        (0..self.len).map(move |offset| self.values[(start + offset) % self.values.len()])
+    }
+
+    pub fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+    }
+}
+
+/// Simple moving average over the most recent `period` values.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Sma {
+    window: RollingWindow,
+    sum: Decimal,
+}
+
+impl Sma {
+    pub fn new(period: usize) -> Self {
+        Self {
+            window: RollingWindow::new(period),
+            sum: Decimal::ZERO,
+        }
+    }
+}
+
+impl Indicator for Sma {
+    type Input = Decimal;
+    type Output = Decimal;
+
+    fn update(&mut self, input: Decimal) -> Option<Decimal> {
+        self.sum += input;
+        if let Some(evicted) = self.window.push(input) {
+            self.sum -= evicted;
+        }
+        self.value()
+    }
+
+    fn value(&self) -> Option<Decimal> {
+        self.window
+            .is_full()
+            .then(|| self.sum / Decimal::from(self.window.period()))
+    }
+
+    fn reset(&mut self) {
+        self.window.clear();
+        self.sum = Decimal::ZERO;
+    }
+}
+
+/// Exponential moving average with smoothing factor `2 / (period + 1)`.
+///
+/// Seeded with the simple average of the first `period` values, so it is warmed up after
+/// `period` values.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Ema {
+    period: usize,
+    alpha: Decimal,
+    count: usize,
+    sum: Decimal,
+    value: Option<Decimal>,
+}
+
+impl Ema {
+    pub fn new(period: usize) -> Self {
+        let period = period.max(1);
+        Self::with_alpha(period, Decimal::TWO / Decimal::from(period + 1))
+    }
+
+    /// Construct an [`Ema`] with Wilder's smoothing factor `1 / period` (aka/ RMA or SMMA).
+    pub fn wilder(period: usize) -> Self {
+        let period = period.max(1);
+        Self::with_alpha(period, Decimal::ONE / Decimal::from(period))
+    }
+
+    fn with_alpha(period: usize, alpha: Decimal) -> Self {
+        Self {
+            period,
+            alpha,
+            count: 0,
+            sum: Decimal::ZERO,
+            value: None,
+        }
+    }
+}
+
+impl Indicator for Ema {
+    type Input = Decimal;
+    type Output = Decimal;
+
+    fn update(&mut self, input: Decimal) -> Option<Decimal> {
+        self.value = match self.value {
+            Some(value) => Some(value + self.alpha * (input - value)),
+            None => {
+                self.count += 1;
+                self.sum += input;
+                (self.count == self.period).then(|| self.sum / Decimal::from(self.period))
+            }
+        };
+        self.value
+    }
+
+    fn value(&self) -> Option<Decimal> {
+        self.value
+    }
+
+    fn reset(&mut self) {
+        self.count = 0;
+        self.sum = Decimal::ZERO;
+        self.value = None;
+    }
+}
+
+/// Linearly weighted moving average over the most recent `period` values, where the newest value
+/// has weight `period` and the oldest value has weight 1.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Wma {
+    window: RollingWindow,
+    sum: Decimal,
+    weighted_sum: Decimal,
+}
+
+impl Wma {
+    pub fn new(period: usize) -> Self {
+        Self {
+            window: RollingWindow::new(period),
+            sum: Decimal::ZERO,
+            weighted_sum: Decimal::ZERO,
+        }
+    }
+}
+
+impl Indicator for Wma {
+    type Input = Decimal;
+    type Output = Decimal;
+
+    fn update(&mut self, input: Decimal) -> Option<Decimal> {
+        // Every value already in a full window loses one unit of weight, including the evicted
+        // value, which drops to zero weight
+        if self.window.is_full() {
+            self.weighted_sum -= self.sum;
+        }
+        let weight = (self.window.len() + 1).min(self.window.period());
+        self.weighted_sum += input * Decimal::from(weight);
+
+        self.sum += input;
+        if let Some(evicted) = self.window.push(input) {
+            self.sum -= evicted;
+        }
+        self.value()
+    }
+
+    fn value(&self) -> Option<Decimal> {
+        let period = Decimal::from(self.window.period());
+        self.window
+            .is_full()
+            .then(|| self.weighted_sum * Decimal::TWO / (period * (period + Decimal::ONE)))
+    }
+
+    fn reset(&mut self) {
+        self.window.clear();
+        self.sum = Decimal::ZERO;
+        self.weighted_sum = Decimal::ZERO;
+    }
+}
+
+/// Relative Strength Index (0 to 100) of consecutive value changes, using Wilder's smoothing.
+///
+/// Warmed up after `period + 1` values (ie/ `period` changes).
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Rsi {
+    previous: Option<Decimal>,
+    gains: Ema,
+    losses: Ema,
+}
+
+impl Rsi {
+    pub fn new(period: usize) -> Self {
+        Self {
+            previous: None,
+            gains: Ema::wilder(period),
+            losses: Ema::wilder(period),
+        }
+    }
+}
+
+impl Indicator for Rsi {
+    type Input = Decimal;
+    type Output = Decimal;
+
+    fn update(&mut self, input: Decimal) -> Option<Decimal> {
+        if let Some(previous) = self.previous.replace(input) {
+            let change = input - previous;
+            self.gains.update(change.max(Decimal::ZERO));
+            self.losses.update((-change).max(Decimal::ZERO));
+        }
+        self.value()
+    }
+
+    fn value(&self) -> Option<Decimal> {
+        let (gains, losses) = (self.gains.value()?, self.losses.value()?);
+        Some(if gains.is_zero() && losses.is_zero() {
+            Decimal::ONE_HUNDRED / Decimal::TWO
+        } else {
+            Decimal::ONE_HUNDRED * gains / (gains + losses)
+        })
+    }
+
+    fn reset(&mut self) {
+        self.previous = None;
+        self.gains.reset();
+        self.losses.reset();
+    }
+}
+
+/// [`Macd`] output.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize, Serialize)]
+pub struct MacdValue {
+    /// Fast EMA minus slow EMA.
+    pub macd: Decimal,
+
+    /// EMA of the MACD line.
+    pub signal: Decimal,
+
+    /// MACD line minus signal line.
+    pub histogram: Decimal,
+}
+
+/// Moving Average Convergence Divergence (eg/ the classic 12, 26, 9 configuration).
+///
+/// The signal line is fed once the slow EMA is warmed up, so the indicator is warmed up after
+/// `slow + signal - 1` values.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Macd {
+    fast: Ema,
+    slow: Ema,
+    signal: Ema,
+    macd: Option<Decimal>,
+}
+
+impl Macd {
+    pub fn new(fast: usize, slow: usize, signal: usize) -> Self {
+        Self {
+            fast: Ema::new(fast),
+            slow: Ema::new(slow),
+            signal: Ema::new(signal),
+            macd: None,
+        }
+    }
+}
+
+impl Indicator for Macd {
+    type Input = Decimal;
+    type Output = MacdValue;
+
+    fn update(&mut self, input: Decimal) -> Option<MacdValue> {
+        let fast = self.fast.update(input);
+        let slow = self.slow.update(input);
+        if let (Some(fast), Some(slow)) = (fast, slow) {
+            let macd = fast - slow;
+            self.macd = Some(macd);
+            self.signal.update(macd);
+        }
+        self.value()
+    }
+
+    fn value(&self) -> Option<MacdValue> {
+        let (macd, signal) = (self.macd?, self.signal.value()?);
+        Some(MacdValue {
+            macd,
+            signal,
+            histogram: macd - signal,
+        })
+    }
+
+    fn reset(&mut self) {
+        self.fast.reset();
+        self.slow.reset();
+        self.signal.reset();
+        self.macd = None;
+    }
+}
+
+/// High, low & close prices of a bar, used as the input of range based indicators (eg/ [`Atr`]).
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize, Serialize)]
+pub struct Bar {
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+}
+
+impl Bar {
+    pub fn new(high: Decimal, low: Decimal, close: Decimal) -> Self {
+        Self { high, low, close }
+    }
+
+    /// True range of the bar relative to the previous close (or the high-low range if there is
+    /// no previous close).
+    pub fn true_range(&self, previous_close: Option<Decimal>) -> Decimal {
+        let range = self.high - self.low;
+        match previous_close {
+            Some(previous) => range
+                .max((self.high - previous).abs())
+                .max((self.low - previous).abs()),
+            None => range,
+        }
+    }
+}
+
+/// Average True Range, using Wilder's smoothing of the [`Bar::true_range`].
+///
+/// Warmed up after `period` bars.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Atr {
+    previous_close: Option<Decimal>,
+    true_range: Ema,
+}
+
+impl Atr {
+    pub fn new(period: usize) -> Self {
+        Self {
+            previous_close: None,
+            true_range: Ema::wilder(period),
+        }
+    }
+}
+
+impl Indicator for Atr {
+    type Input = Bar;
+    type Output = Decimal;
+
+    fn update(&mut self, input: Bar) -> Option<Decimal> {
+        let true_range = input.true_range(self.previous_close.replace(input.close));
+        self.true_range.update(true_range)
+    }
+
+    fn value(&self) -> Option<Decimal> {
+        self.true_range.value()
+    }
+
+    fn reset(&mut self) {
+        self.previous_close = None;
+        self.true_range.reset();
+    }
+}
+
+/// Rolling mean & population standard deviation over the most recent `period` values.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct RollingStdDev {
+    window: RollingWindow,
+    sum: Decimal,
+    sum_squares: Decimal,
+}
+
+impl RollingStdDev {
+    pub fn new(period: usize) -> Self {
+        Self {
+            window: RollingWindow::new(period),
+            sum: Decimal::ZERO,
+            sum_squares: Decimal::ZERO,
+        }
+    }
+
+    /// Rolling mean, once warmed up.
+    pub fn mean(&self) -> Option<Decimal> {
+        self.window
+            .is_full()
+            .then(|| self.sum / Decimal::from(self.window.period()))
+    }
+}
+
+impl Indicator for RollingStdDev {
+    type Input = Decimal;
+    type Output = Decimal;
+
+    fn update(&mut self, input: Decimal) -> Option<Decimal> {
+        self.sum += input;
+        self.sum_squares += input * input;
+        if let Some(evicted) = self.window.push(input) {
+            self.sum -= evicted;
+            self.sum_squares -= evicted * evicted;
+        }
+        self.value()
+    }
+
+    fn value(&self) -> Option<Decimal> {
+        let mean = self.mean()?;
+        let variance = self.sum_squares / Decimal::from(self.window.period()) - mean * mean;
+        variance.max(Decimal::ZERO).sqrt()
+    }
+
+    fn reset(&mut self) {
+        self.window.clear();
+        self.sum = Decimal::ZERO;
+        self.sum_squares = Decimal::ZERO;
+    }
+}
+
+/// [`Bollinger`] output.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize, Serialize)]
+pub struct BollingerBands {
+    pub lower: Decimal,
+    pub middle: Decimal,
+    pub upper: Decimal,
+}
+
+/// Bollinger Bands, `width` population standard deviations around the rolling mean of the most
+/// recent `period` values.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Bollinger {
+    width: Decimal,
+    stats: RollingStdDev,
+}
+
+impl Bollinger {
+    pub fn new(period: usize, width: Decimal) -> Self {
+        Self {
+            width,
+            stats: RollingStdDev::new(period),
+        }
+    }
+}
+
+impl Indicator for Bollinger {
+    type Input = Decimal;
+    type Output = BollingerBands;
+
+    fn update(&mut self, input: Decimal) -> Option<BollingerBands> {
+        self.stats.update(input);
+        self.value()
+    }
+
+    fn value(&self) -> Option<BollingerBands> {
+        let (middle, std_dev) = (self.stats.mean()?, self.stats.value()?);
+        let distance = std_dev * self.width;
+        Some(BollingerBands {
+            lower: middle - distance,
+            middle,
+            upper: middle + distance,
+        })
+    }
+
+    fn reset(&mut self) {
+        self.stats.reset();
+    }
+}
+
+/// Volume weighted average price of the most recent `period` traded [`Level`]s (price & amount).
+///
+/// Warmed up after `period` trades, as long as the window has a non-zero volume.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct RollingVwap {
+    prices: RollingWindow,
+    volumes: RollingWindow,
+    notional: Decimal,
+    volume: Decimal,
+}
+
+impl RollingVwap {
+    pub fn new(period: usize) -> Self {
+        Self {
+            prices: RollingWindow::new(period),
+            volumes: RollingWindow::new(period),
+            notional: Decimal::ZERO,
+            volume: Decimal::ZERO,
+        }
+    }
+}
+
+impl Indicator for RollingVwap {
+    type Input = Level;
+    type Output = Decimal;
+
+    fn update(&mut self, input: Level) -> Option<Decimal> {
+        self.notional += input.price * input.amount;
+        self.volume += input.amount;
+        if let (Some(price), Some(volume)) = (
+            self.prices.push(input.price),
+            self.volumes.push(input.amount),
+        ) {
+            self.notional -= price * volume;
+            self.volume -= volume;
+        }
+        self.value()
+    }
+
+    fn value(&self) -> Option<Decimal> {
+        (self.prices.is_full() && !self.volume.is_zero()).then(|| self.notional / self.volume)
+    }
+
+    fn reset(&mut self) {
+        self.prices.clear();
+        self.volumes.clear();
+        self.notional = Decimal::ZERO;
+        self.volume = Decimal::ZERO;
+    }
+}
+
+/// [`RollingMinMax`] output.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize, Serialize)]
+pub struct MinMax {
+    pub min: Decimal,
+    pub max: Decimal,
+}
+
+/// Rolling minimum & maximum of the most recent `period` values (eg/ Donchian channels).
+///
+/// The window is only rescanned when the current minimum or maximum is evicted.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct RollingMinMax {
+    window: RollingWindow,
+    min_max: Option<MinMax>,
+}
+
+impl RollingMinMax {
+    pub fn new(period: usize) -> Self {
+        Self {
+            window: RollingWindow::new(period),
+            min_max: None,
+        }
+    }
+}
+
+impl Indicator for RollingMinMax {
+    type Input = Decimal;
+    type Output = MinMax;
+
+    fn update(&mut self, input: Decimal) -> Option<MinMax> {
+        let evicted = self.window.push(input);
+
+        self.min_max = match self.min_max {
+            Some(MinMax { min, max })
+                if evicted.is_none_or(|value| value != min && value != max) =>
+            {
+                Some(MinMax {
+                    min: min.min(input),
+                    max: max.max(input),
+                })
+            }
+            _ => self.window.iter().fold(None, |min_max, value| {
+                Some(match min_max {
+                    Some(MinMax { min, max }) => MinMax {
+                        min: min.min(value),
+                        max: max.max(value),
+                    },
+                    None => MinMax {
+                        min: value,
+                        max: value,
+                    },
+                })
+            }),
+        };
+        self.value()
+    }
+
+    fn value(&self) -> Option<MinMax> {
+        self.min_max.filter(|_| self.window.is_full())
+    }
+
+    fn reset(&mut self) {
+        self.window.clear();
+        self.min_max = None;
+    }
+}
+
+/// [`RollingRegression`] output.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize, Serialize)]
+pub struct LinearFit {
+    pub slope: Decimal,
+    pub intercept: Decimal,
+}
+
+/// Rolling ordinary least squares regression of `y` on `x` over the most recent `period` `(x, y)`
+/// observations (eg/ the hedge ratio of a pair of instruments).
+///
+/// Returns `None` while the window has no `x` variance.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct RollingRegression {
+    xs: RollingWindow,
+    ys: RollingWindow,
+    sum_x: Decimal,
+    sum_y: Decimal,
+    sum_xx: Decimal,
+    sum_xy: Decimal,
+}
+
+impl RollingRegression {
+    pub fn new(period: usize) -> Self {
+        Self {
+            xs: RollingWindow::new(period),
+            ys: RollingWindow::new(period),
+            sum_x: Decimal::ZERO,
+            sum_y: Decimal::ZERO,
+            sum_xx: Decimal::ZERO,
+            sum_xy: Decimal::ZERO,
+        }
+    }
+}
+
+impl Indicator for RollingRegression {
+    type Input = (Decimal, Decimal);
+    type Output = LinearFit;
+
+    fn update(&mut self, (x, y): (Decimal, Decimal)) -> Option<LinearFit> {
+        self.sum_x += x;
+        self.sum_y += y;
+        self.sum_xx += x * x;
+        self.sum_xy += x * y;
+        if let (Some(x), Some(y)) = (self.xs.push(x), self.ys.push(y)) {
+            self.sum_x -= x;
+            self.sum_y -= y;
+            self.sum_xx -= x * x;
+            self.sum_xy -= x * y;
+        }
+        self.value()
+    }
+
+    fn value(&self) -> Option<LinearFit> {
+        if !self.xs.is_full() {
+            return None;
+        }
+
+        let count = Decimal::from(self.xs.period());
+        let variance = count * self.sum_xx - self.sum_x * self.sum_x;
+        if variance.is_zero() {
+            return None;
+        }
+
+        let slope = (count * self.sum_xy - self.sum_x * self.sum_y) / variance;
+        Some(LinearFit {
+            slope,
+            intercept: (self.sum_y - slope * self.sum_x) / count,
+        })
+    }
+
+    fn reset(&mut self) {
+        self.xs.clear();
+        self.ys.clear();
+        self.sum_x = Decimal::ZERO;
+        self.sum_y = Decimal::ZERO;
+        self.sum_xx = Decimal::ZERO;
+        self.sum_xy = Decimal::ZERO;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn rounded(value: Option<Decimal>) -> Option<Decimal> {
+        value.map(|value| value.round_dp(4))
+    }
+
+    #[test]
+    fn test_rolling_window() {
+        let mut window = RollingWindow::new(3);
+
+        // TC0: values are not evicted until the window is full
+        assert_eq!(window.push(dec!(1)), None, "TC0 failed");
+        assert_eq!(window.push(dec!(2)), None, "TC0 failed");
+        assert_eq!(window.push(dec!(3)), None, "TC0 failed");
+        assert!(window.is_full(), "TC0 failed");
+
+        // TC1: oldest value is evicted & iteration is from oldest to newest
+        assert_eq!(window.push(dec!(4)), Some(dec!(1)), "TC1 failed");
+        assert_eq!(window.push(dec!(5)), Some(dec!(2)), "TC1 failed");
+        assert_eq!(
+            window.iter().collect::<Vec<_>>(),
+            vec![dec!(3), dec!(4), dec!(5)],
+            "TC1 failed"
+        );
+
+        // TC2: cleared window is empty, with the same period
+        window.clear();
+        assert!(window.is_empty(), "TC2 failed");
+        assert_eq!(window.period(), 3, "TC2 failed");
+        assert_eq!(window.push(dec!(6)), None, "TC2 failed");
+    }
+
+    #[test]
+    fn test_moving_averages() {
+        struct TestCase {
+            input: Decimal,
+            expected_sma: Option<Decimal>,
+            expected_ema: Option<Decimal>,
+            expected_wma: Option<Decimal>,
+        }
+
+        let (mut sma, mut ema, mut wma) = (Sma::new(3), Ema::new(3), Wma::new(3));
+
+        let cases = vec![
+            // TC0: not warmed up
+            TestCase {
+                input: dec!(2),
+                expected_sma: None,
+                expected_ema: None,
+                expected_wma: None,
+            },
+            // TC1: not warmed up
+            TestCase {
+                input: dec!(4),
+                expected_sma: None,
+                expected_ema: None,
+                expected_wma: None,
+            },
+            // TC2: warmed up, EMA seeded with the SMA
+            TestCase {
+                input: dec!(6),
+                expected_sma: Some(dec!(4)),
+                expected_ema: Some(dec!(4)),
+                expected_wma: Some(dec!(4.6667)),
+            },
+            // TC3: rolling window [4, 6, 8], EMA α = 0.5
+            TestCase {
+                input: dec!(8),
+                expected_sma: Some(dec!(6)),
+                expected_ema: Some(dec!(6)),
+                expected_wma: Some(dec!(6.6667)),
+            },
+            // TC4: rolling window [6, 8, 10]
+            TestCase {
+                input: dec!(10),
+                expected_sma: Some(dec!(8)),
+                expected_ema: Some(dec!(8)),
+                expected_wma: Some(dec!(8.6667)),
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            assert_eq!(
+                sma.update(test.input),
+                test.expected_sma,
+                "TC{index} failed"
+            );
+            assert_eq!(
+                ema.update(test.input),
+                test.expected_ema,
+                "TC{index} failed"
+            );
+            assert_eq!(
+                rounded(wma.update(test.input)),
+                test.expected_wma,
+                "TC{index} failed"
+            );
+        }
+
+        // Reset indicators must warm up again
+        wma.reset();
+        assert_eq!(wma.update(dec!(1)), None);
+        assert!(!wma.is_ready());
+    }
+
+    #[test]
+    fn test_oscillators() {
+        let mut rsi = Rsi::new(2);
+        let mut macd = Macd::new(2, 3, 2);
+
+        // TC0: RSI warms up after period + 1 values (gains 0.5, losses 0.25)
+        assert_eq!(rsi.update(dec!(10)), None, "TC0 failed");
+        assert_eq!(rsi.update(dec!(11)), None, "TC0 failed");
+        assert_eq!(
+            rounded(rsi.update(dec!(10.5))),
+            Some(dec!(66.6667)),
+            "TC0 failed"
+        );
+
+        // TC1: Wilder smoothing of subsequent changes (+1 then -1)
+        assert_eq!(
+            rounded(rsi.update(dec!(11.5))),
+            Some(dec!(85.7143)),
+            "TC1 failed"
+        );
+        assert_eq!(rsi.update(dec!(10.5)), Some(dec!(40)), "TC1 failed");
+
+        // TC2: MACD is not valid until the signal line is warmed up
+        for input in [dec!(1), dec!(2), dec!(3)] {
+            assert_eq!(macd.update(input), None, "TC2 failed");
+        }
+
+        // TC3: MACD warmed up after slow + signal - 1 values
+        let value = macd.update(dec!(5)).unwrap();
+        assert_eq!(value.macd.round_dp(4), dec!(0.6667), "TC3 failed");
+        assert_eq!(value.signal.round_dp(4), dec!(0.5833), "TC3 failed");
+        assert_eq!(value.histogram.round_dp(4), dec!(0.0833), "TC3 failed");
+
+        // TC4: signal line catches up with a falling MACD line
+        let value = macd.update(dec!(4)).unwrap();
+        assert_eq!(value.macd.round_dp(4), dec!(0.3056), "TC4 failed");
+        assert_eq!(value.signal.round_dp(4), dec!(0.3981), "TC4 failed");
+        assert_eq!(value.histogram.round_dp(4), dec!(-0.0926), "TC4 failed");
+    }
+
+    #[test]
+    fn test_atr() {
+        struct TestCase {
+            input: Bar,
+            expected: Option<Decimal>,
+        }
+
+        let mut atr = Atr::new(2);
+
+        let cases = vec![
+            // TC0: first bar true range is the high-low range
+            TestCase {
+                input: Bar::new(dec!(10), dec!(8), dec!(9)),
+                expected: None,
+            },
+            // TC1: warmed up with the average of the first true ranges
+            TestCase {
+                input: Bar::new(dec!(11), dec!(9), dec!(10)),
+                expected: Some(dec!(2)),
+            },
+            // TC2: Wilder smoothing of a wider range (true range 3)
+            TestCase {
+                input: Bar::new(dec!(13), dec!(10), dec!(12)),
+                expected: Some(dec!(2.5)),
+            },
+            // TC3: gap up true range uses the previous close (true range 3)
+            TestCase {
+                input: Bar::new(dec!(15), dec!(14), dec!(14.5)),
+                expected: Some(dec!(2.75)),
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            assert_eq!(atr.update(test.input), test.expected, "TC{index} failed");
+        }
+    }
+
+    #[test]
+    fn test_rolling_statistics() {
+        struct TestCase {
+            input: Level,
+            expected_std_dev: Option<Decimal>,
+            expected_bands: Option<(Decimal, Decimal, Decimal)>,
+            expected_min_max: Option<(Decimal, Decimal)>,
+            expected_vwap: Option<Decimal>,
+        }
+
+        let mut std_dev = RollingStdDev::new(3);
+        let mut bollinger = Bollinger::new(3, dec!(2));
+        let mut min_max = RollingMinMax::new(3);
+        let mut vwap = RollingVwap::new(3);
+
+        let cases = vec![
+            // TC0: not warmed up
+            TestCase {
+                input: Level::new(dec!(1), dec!(1)),
+                expected_std_dev: None,
+                expected_bands: None,
+                expected_min_max: None,
+                expected_vwap: None,
+            },
+            // TC1: not warmed up
+            TestCase {
+                input: Level::new(dec!(3), dec!(1)),
+                expected_std_dev: None,
+                expected_bands: None,
+                expected_min_max: None,
+                expected_vwap: None,
+            },
+            // TC2: warmed up with window [1, 3, 2]
+            TestCase {
+                input: Level::new(dec!(2), dec!(2)),
+                expected_std_dev: Some(dec!(0.8165)),
+                expected_bands: Some((dec!(0.3670), dec!(2), dec!(3.6330))),
+                expected_min_max: Some((dec!(1), dec!(3))),
+                expected_vwap: Some(dec!(2)),
+            },
+            // TC3: evicted minimum is replaced by the new window minimum
+            TestCase {
+                input: Level::new(dec!(6), dec!(1)),
+                expected_std_dev: Some(dec!(1.6997)),
+                expected_bands: Some((dec!(0.2673), dec!(3.6667), dec!(7.0660))),
+                expected_min_max: Some((dec!(2), dec!(6))),
+                expected_vwap: Some(dec!(3.25)),
+            },
+            // TC4: window [2, 6, 4]
+            TestCase {
+                input: Level::new(dec!(4), dec!(2)),
+                expected_std_dev: Some(dec!(1.6330)),
+                expected_bands: Some((dec!(0.7340), dec!(4), dec!(7.2660))),
+                expected_min_max: Some((dec!(2), dec!(6))),
+                expected_vwap: Some(dec!(3.6)),
+            },
+            // TC5: zero volume trade still rolls the VWAP window
+            TestCase {
+                input: Level::new(dec!(5), dec!(0)),
+                expected_std_dev: Some(dec!(0.8165)),
+                expected_bands: Some((dec!(3.3670), dec!(5), dec!(6.6330))),
+                expected_min_max: Some((dec!(4), dec!(6))),
+                expected_vwap: Some(dec!(4.6667)),
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let price = test.input.price;
+            assert_eq!(
+                rounded(std_dev.update(price)),
+                test.expected_std_dev,
+                "TC{index} failed"
+            );
+            assert_eq!(
+                bollinger.update(price).map(|bands| (
+                    bands.lower.round_dp(4),
+                    bands.middle.round_dp(4),
+                    bands.upper.round_dp(4)
+                )),
+                test.expected_bands,
+                "TC{index} failed"
+            );
+            assert_eq!(
+                min_max.update(price).map(|range| (range.min, range.max)),
+                test.expected_min_max,
+                "TC{index} failed"
+            );
+            assert_eq!(
+                rounded(vwap.update(test.input)),
+                test.expected_vwap,
+                "TC{index} failed"
+            );
+        }
+    }
+
+    #[test]
+    fn test_rolling_regression() {
+        let mut regression = RollingRegression::new(3);
+
+        // TC0: not warmed up
+        assert_eq!(regression.update((dec!(1), dec!(3))), None, "TC0 failed");
+        assert_eq!(regression.update((dec!(2), dec!(5))), None, "TC0 failed");
+
+        // TC1: exact fit of y = 2x + 1
+        assert_eq!(
+            regression.update((dec!(3), dec!(7))),
+            Some(LinearFit {
+                slope: dec!(2),
+                intercept: dec!(1)
+            }),
+            "TC1 failed"
+        );
+
+        // TC2: rolling window [(2, 5), (3, 7), (4, 6)] => slope 0.5, intercept 4.5
+        assert_eq!(
+            regression.update((dec!(4), dec!(6))),
+            Some(LinearFit {
+                slope: dec!(0.5),
+                intercept: dec!(4.5)
+            }),
+            "TC2 failed"
+        );
+
+        // TC3: no x variance has no fit
+        let mut regression = RollingRegression::new(2);
+        regression.update((dec!(1), dec!(1)));
+        assert_eq!(regression.update((dec!(1), dec!(2))), None, "TC3 failed");
+    }
+}
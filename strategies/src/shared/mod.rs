@@ -0,0 +1,232 @@
+//! Shared modules (indicators, pricing, utilities) among strategies.
+
+/// Incremental (allocation-free) technical indicators with explicit warm-up.
+pub mod indicators;
+
+/// Black-Scholes & Black-76 option pricing, implied volatility and Greeks.
+pub mod pricing;
+
+use std::fmt::Debug;
+use toucan_core::engine::state::instrument::{
+    data::InstrumentDataState, InstrumentState, InstrumentStates,
+};
+use toucan_execution::{
+    order::{
+        id::{ClientOrderId, StrategyId},
+        request::OrderRequestOpen,
+    },
+    ExchangeIndex, InstrumentIndex,
+};
+use toucan_trader::close_positions::close_open_positions_with_market_orders;
+use tracing::warn;
+
+/// Empty state that can be reused by strategies that do not yet
+/// require specific engine data. Serves as a placeholder.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoOpState;
+
+/// Determine if an [`InstrumentState`] matches the `InstrumentFilter` received by
+/// [`ClosePositionsStrategy::close_positions_requests`](toucan_trader::ClosePositionsStrategy).
+///
+/// The trait only exposes the filter as `Debug`, so it is matched on its `Debug` representation:
+/// `None` matches every instrument, `Instruments` & `Exchanges` match by quoted key, and
+/// `Underlyings` match the "{base}_{quote}" instrument underlying (ignoring case). An
+/// unrecognised filter is logged with a warning and matches every instrument, so closing
+/// positions errs on the side of flattening too much rather than leaving positions open.
+pub fn filter_matches<InstrumentData>(
+    filter: &impl Debug,
+    state: &InstrumentState<InstrumentData>,
+) -> bool {
+    DebugFilter::parse(filter).matches(state)
+}
+
+/// Iterate over the [`InstrumentState`]s matching the `InstrumentFilter` received by
+/// [`ClosePositionsStrategy::close_positions_requests`](toucan_trader::ClosePositionsStrategy)
+/// (see [`filter_matches`]).
+pub fn filtered_instruments<'a, InstrumentData>(
+    instruments: &'a InstrumentStates<InstrumentData>,
+    filter: &impl Debug,
+) -> impl Iterator<Item = &'a InstrumentState<InstrumentData>> {
+    let filter = DebugFilter::parse(filter);
+    instruments
+        .0
+        .values()
+        .filter(move |state| filter.matches(state))
+}
+
+/// Generate IOC market orders closing every open position matching the provided filter, priced
+/// with the latest [`InstrumentDataState::price`] (falling back to the average entry price).
+pub fn close_positions_with_market_orders<InstrumentData>(
+    strategy: &StrategyId,
+    instruments: &InstrumentStates<InstrumentData>,
+    filter: &impl Debug,
+) -> Vec<OrderRequestOpen<ExchangeIndex, InstrumentIndex>>
+where
+    InstrumentData: InstrumentDataState,
+{
+    filtered_instruments(instruments, filter)
+        .filter_map(|state| {
+            let position = state.position.current.as_ref()?;
+            let price = state.data.price().unwrap_or(position.price_entry_average);
+
+            Some(close_open_positions_with_market_orders(
+                state.instrument.exchange.to_string(),
+                state.key.clone(),
+                strategy.clone(),
+                position.side,
+                position.quantity_abs,
+                price,
+                ClientOrderId::random,
+            ))
+        })
+        .flatten()
+        .collect()
+}
+
+/// `InstrumentFilter` parsed from its `Debug` representation.
+#[derive(Debug, Clone, PartialEq)]
+enum DebugFilter {
+    All,
+    Instruments(String),
+    Exchanges(String),
+    Underlyings(Vec<String>),
+}
+
+impl DebugFilter {
+    fn parse(filter: &impl Debug) -> Self {
+        let filter = format!("{filter:?}");
+
+        if filter == "None" {
+            Self::All
+        } else if filter.starts_with("Instruments(") {
+            Self::Instruments(filter)
+        } else if filter.starts_with("Exchanges(") {
+            Self::Exchanges(filter)
+        } else if filter.starts_with("Underlyings(") {
+            Self::Underlyings(
+                filter
+                    .split("Underlying {")
+                    .skip(1)
+                    .filter_map(|underlying| {
+                        let base = debug_field(underlying, "base: ")?;
+                        let quote = debug_field(underlying, "quote: ")?;
+                        Some(format!("{base}_{quote}"))
+                    })
+                    .collect(),
+            )
+        } else {
+            warn!(%filter, "unrecognised InstrumentFilter, matching every instrument");
+            Self::All
+        }
+    }
+
+    fn matches<InstrumentData>(&self, state: &InstrumentState<InstrumentData>) -> bool {
+        match self {
+            Self::All => true,
+            Self::Instruments(filter) => filter.contains(&format!("{:?}", state.key)),
+            Self::Exchanges(filter) => {
+                filter.contains(&format!("{:?}", state.instrument.exchange.to_string()))
+            }
+            Self::Underlyings(underlyings) => {
+                state
+                    .instrument
+                    .underlying
+                    .as_deref()
+                    .is_some_and(|instrument| {
+                        underlyings
+                            .iter()
+                            .any(|underlying| underlying.eq_ignore_ascii_case(instrument))
+                    })
+            }
+        }
+    }
+}
+
+/// Quoted value of a `Debug` struct field (eg/ `base: "petr4"`).
+fn debug_field<'a>(debug: &'a str, field: &str) -> Option<&'a str> {
+    let value = &debug[debug.find(field)? + field.len()..];
+    value.strip_prefix('"')?.split('"').next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use toucan_core::engine::state::{
+        global::DefaultGlobalData,
+        instrument::{data::DefaultInstrumentMarketData, filter::InstrumentFilter},
+        EngineState, IndexedInstruments,
+    };
+    use toucan_instrument::{exchange::ExchangeId, ConcreteInstrument, Keyed, Underlying};
+
+    #[test]
+    fn test_filtered_instruments() {
+        type Filter = InstrumentFilter;
+
+        struct TestCase {
+            filter: Box<dyn Debug>,
+            expected: Vec<&'static str>,
+        }
+
+        let instruments: IndexedInstruments = [
+            ("PETR4", ExchangeId::Mock, Some("PETR4_BRL")),
+            ("VALE3", ExchangeId::Mock, Some("VALE3_BRL")),
+            ("WINFUT", ExchangeId::Other, None),
+        ]
+        .into_iter()
+        .map(|(instrument, exchange, underlying)| {
+            Keyed::new(
+                instrument.to_string(),
+                ConcreteInstrument {
+                    symbol: instrument.into(),
+                    market: "spot".into(),
+                    exchange,
+                    underlying: underlying.map(str::to_string),
+                    name_exchange: instrument.into(),
+                },
+            )
+        })
+        .collect();
+        let state = EngineState::builder(&instruments, DefaultGlobalData, |_| {
+            DefaultInstrumentMarketData::default()
+        })
+        .build();
+
+        let cases = vec![
+            // TC0: None matches every instrument
+            TestCase {
+                filter: Box::new(Filter::None),
+                expected: vec!["PETR4", "VALE3", "WINFUT"],
+            },
+            // TC1: Instruments match by key
+            TestCase {
+                filter: Box::new(Filter::instruments(["VALE3".to_string()])),
+                expected: vec!["VALE3"],
+            },
+            // TC2: Exchanges match by exchange
+            TestCase {
+                filter: Box::new(Filter::exchanges([ExchangeId::Other.to_string()])),
+                expected: vec!["WINFUT"],
+            },
+            // TC3: Underlyings match the instrument underlying, ignoring case
+            TestCase {
+                filter: Box::new(Filter::underlyings([
+                    Underlying::new("petr4", "brl"),
+                    Underlying::new("itub4", "brl"),
+                ])),
+                expected: vec!["PETR4"],
+            },
+            // TC4: unrecognised filter matches every instrument
+            TestCase {
+                filter: Box::new("Sectors([\"banks\"])"),
+                expected: vec!["PETR4", "VALE3", "WINFUT"],
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let actual = filtered_instruments(&state.instruments, &test.filter)
+                .map(|state| state.key.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(actual, test.expected, "TC{index} failed");
+        }
+    }
+}
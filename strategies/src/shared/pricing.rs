@@ -0,0 +1,619 @@
+//! European option pricing with the Black-Scholes(-Merton) & Black-76 models.
+//!
+//! Both models are evaluated with the generalised Black-Scholes formula, using a cost of carry
+//! `b = r - q` (Black-Scholes on the spot price with a continuous dividend yield `q`) or `b = 0`
+//! (Black-76 on the forward / futures price).
+//!
+//! Prices, [`Greeks`] and implied volatilities are calculated in `f64` for speed, since the
+//! [`implied_volatility`](PricingModel::implied_volatility) solver evaluates the model
+//! repeatedly. Callers convert to & from `Decimal` at the boundary.
+//!
+//! American exercise (eg/ B3 equity calls) is priced as European, which is exact for calls on
+//! non-dividend paying underlyings and an approximation otherwise.
+//!
+//! # Example
+//! ```rust
+//! use toucan_instrument::instrument::kind::option::OptionKind;
+//! use toucan_strategies::shared::pricing::{OptionInputs, PricingModel};
+//!
+//! let inputs = OptionInputs {
+//!     kind: OptionKind::Call,
+//!     underlying: 100.0,
+//!     strike: 100.0,
+//!     time_to_expiry: 1.0,
+//!     rate: 0.05,
+//!     dividend_yield: 0.0,
+//!     volatility: 0.2,
+//! };
+//!
+//! let price = PricingModel::BlackScholes.price(&inputs);
+//! let volatility = PricingModel::BlackScholes.implied_volatility(&inputs, price).unwrap();
+//! assert!((volatility - 0.2).abs() < 1e-9);
+//! ```
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use toucan_instrument::instrument::kind::option::OptionKind;
+use toucan_markets::calendar::{ExchangeCalendar, TradingCalendar};
+
+/// Number of business days in a year used by the B3 `DU/252` day count convention.
+pub const B3_BUSINESS_DAYS_PER_YEAR: f64 = 252.0;
+
+/// Maximum iterations of the [`PricingModel::implied_volatility`] solver.
+const IMPLIED_VOLATILITY_MAX_ITERATIONS: usize = 100;
+
+/// Absolute price tolerance of the [`PricingModel::implied_volatility`] solver.
+const IMPLIED_VOLATILITY_TOLERANCE: f64 = 1e-10;
+
+/// Upper bound of the volatilities searched by the [`PricingModel::implied_volatility`] solver.
+const IMPLIED_VOLATILITY_MAX: f64 = 10.0;
+
+/// Numerator coefficients of the [`norm_cdf`] rational approximation, highest degree first.
+const NORM_CDF_NUMERATOR: [f64; 7] = [
+    0.035_262_496_599_891_1,
+    0.700_383_064_443_688,
+    6.373_962_203_531_65,
+    33.912_866_078_383,
+    112.079_291_497_871,
+    221.213_596_169_931,
+    220.206_867_912_376,
+];
+
+/// Denominator coefficients of the [`norm_cdf`] rational approximation, highest degree first.
+const NORM_CDF_DENOMINATOR: [f64; 8] = [
+    0.088_388_347_648_318_4,
+    1.755_667_163_182_64,
+    16.064_177_579_207,
+    86.780_732_202_946_1,
+    296.564_248_779_674,
+    637.333_633_378_831,
+    793.826_512_519_948,
+    440.413_735_824_752,
+];
+
+/// Option pricing model.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Deserialize, Serialize)]
+pub enum PricingModel {
+    /// Black-Scholes(-Merton) on the spot price of the underlying (eg/ B3 equity options on
+    /// PETR4 or VALE3).
+    #[default]
+    BlackScholes,
+
+    /// Black-76 on the forward or futures price of the underlying (eg/ options on futures).
+    Black76,
+}
+
+/// Inputs to a [`PricingModel`].
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct OptionInputs {
+    pub kind: OptionKind,
+
+    /// Spot price (Black-Scholes) or forward price (Black-76) of the underlying.
+    pub underlying: f64,
+
+    pub strike: f64,
+
+    /// Time to expiry in years (see [`year_fraction`] & [`business_year_fraction`]).
+    pub time_to_expiry: f64,
+
+    /// Continuously compounded risk-free rate.
+    pub rate: f64,
+
+    /// Continuously compounded dividend yield. Ignored by [`PricingModel::Black76`].
+    pub dividend_yield: f64,
+
+    /// Annualised volatility. Used as the initial guess by
+    /// [`PricingModel::implied_volatility`].
+    pub volatility: f64,
+}
+
+/// Option price sensitivities.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize, Serialize)]
+pub struct Greeks {
+    /// Price sensitivity to the underlying price.
+    pub delta: f64,
+
+    /// Delta sensitivity to the underlying price.
+    pub gamma: f64,
+
+    /// Price sensitivity to the volatility, per 1.00 (ie/ 100%) change in volatility.
+    pub vega: f64,
+
+    /// Price sensitivity to the passage of time, per year.
+    pub theta: f64,
+}
+
+/// Reason an implied volatility could not be solved.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize, Error)]
+pub enum PricingError {
+    #[error("underlying & strike prices must be positive")]
+    NonPositivePrice,
+
+    #[error("option has expired")]
+    Expired,
+
+    #[error("option price {price} is outside the no-arbitrage bounds [{lower}, {upper})")]
+    PriceOutOfBounds { price: f64, lower: f64, upper: f64 },
+
+    #[error("implied volatility did not converge after {0} iterations")]
+    NoConvergence(usize),
+}
+
+impl PricingModel {
+    /// Option price.
+    pub fn price(&self, inputs: &OptionInputs) -> f64 {
+        let terms = Terms::new(*self, inputs);
+
+        let Some((d1, d2)) = terms.d(inputs.volatility) else {
+            return terms.intrinsic();
+        };
+
+        match inputs.kind {
+            OptionKind::Call => {
+                terms.spot_discounted * norm_cdf(d1) - terms.strike_discounted * norm_cdf(d2)
+            }
+            OptionKind::Put => {
+                terms.strike_discounted * norm_cdf(-d2) - terms.spot_discounted * norm_cdf(-d1)
+            }
+        }
+    }
+
+    /// Option [`Greeks`].
+    ///
+    /// At expiry, or with a zero volatility, only the delta is non-zero.
+    pub fn greeks(&self, inputs: &OptionInputs) -> Greeks {
+        let terms = Terms::new(*self, inputs);
+
+        let Some((d1, d2)) = terms.d(inputs.volatility) else {
+            let in_the_money = match inputs.kind {
+                OptionKind::Call => terms.forward > inputs.strike,
+                OptionKind::Put => terms.forward < inputs.strike,
+            };
+            let delta = match (inputs.kind, in_the_money) {
+                (_, false) => 0.0,
+                (OptionKind::Call, true) => terms.carry_discount,
+                (OptionKind::Put, true) => -terms.carry_discount,
+            };
+            return Greeks {
+                delta,
+                ..Greeks::default()
+            };
+        };
+
+        let sqrt_time = inputs.time_to_expiry.sqrt();
+        let density = norm_pdf(d1);
+        let carry_less_rate = terms.carry - inputs.rate;
+
+        let delta = match inputs.kind {
+            OptionKind::Call => terms.carry_discount * norm_cdf(d1),
+            OptionKind::Put => terms.carry_discount * (norm_cdf(d1) - 1.0),
+        };
+        let gamma =
+            terms.carry_discount * density / (inputs.underlying * inputs.volatility * sqrt_time);
+        let vega = terms.spot_discounted * density * sqrt_time;
+
+        let decay = -terms.spot_discounted * density * inputs.volatility / (2.0 * sqrt_time);
+        let theta = match inputs.kind {
+            OptionKind::Call => {
+                decay
+                    - carry_less_rate * terms.spot_discounted * norm_cdf(d1)
+                    - inputs.rate * terms.strike_discounted * norm_cdf(d2)
+            }
+            OptionKind::Put => {
+                decay
+                    + carry_less_rate * terms.spot_discounted * norm_cdf(-d1)
+                    + inputs.rate * terms.strike_discounted * norm_cdf(-d2)
+            }
+        };
+
+        Greeks {
+            delta,
+            gamma,
+            vega,
+            theta,
+        }
+    }
+
+    /// Volatility that reproduces the provided option price, solved with Newton-Raphson on the
+    /// vega, falling back to bisection whenever a Newton step leaves the bracketing interval.
+    ///
+    /// The `volatility` of the [`OptionInputs`] is used as the initial guess (if positive).
+    pub fn implied_volatility(
+        &self,
+        inputs: &OptionInputs,
+        price: f64,
+    ) -> Result<f64, PricingError> {
+        if !(inputs.underlying > 0.0 && inputs.strike > 0.0) {
+            return Err(PricingError::NonPositivePrice);
+        }
+        if inputs.time_to_expiry.is_nan() || inputs.time_to_expiry <= 0.0 {
+            return Err(PricingError::Expired);
+        }
+
+        let terms = Terms::new(*self, inputs);
+        let lower = terms.intrinsic();
+        let upper = match inputs.kind {
+            OptionKind::Call => terms.spot_discounted,
+            OptionKind::Put => terms.strike_discounted,
+        };
+        if !(price >= lower && price < upper) {
+            return Err(PricingError::PriceOutOfBounds {
+                price,
+                lower,
+                upper,
+            });
+        }
+
+        let (mut low, mut high) = (0.0, IMPLIED_VOLATILITY_MAX);
+        let mut volatility = if inputs.volatility > 0.0 {
+            inputs.volatility.min(IMPLIED_VOLATILITY_MAX)
+        } else {
+            0.3
+        };
+
+        for _ in 0..IMPLIED_VOLATILITY_MAX_ITERATIONS {
+            let trial = OptionInputs {
+                volatility,
+                ..*inputs
+            };
+            let difference = self.price(&trial) - price;
+            if difference.abs() < IMPLIED_VOLATILITY_TOLERANCE {
+                return Ok(volatility);
+            }
+
+            // Price is increasing in volatility, so the root is bracketed by [low, high]
+            if difference > 0.0 {
+                high = volatility;
+            } else {
+                low = volatility;
+            }
+
+            let vega = self.greeks(&trial).vega;
+            let newton = volatility - difference / vega;
+            volatility = if vega > f64::EPSILON && newton > low && newton < high {
+                newton
+            } else {
+                (low + high) / 2.0
+            };
+        }
+
+        Err(PricingError::NoConvergence(
+            IMPLIED_VOLATILITY_MAX_ITERATIONS,
+        ))
+    }
+}
+
+/// Year fraction between two times using the `ACT/365` day count convention.
+pub fn year_fraction(from: DateTime<Utc>, to: DateTime<Utc>) -> f64 {
+    (to - from).num_seconds() as f64 / (365.0 * 24.0 * 60.0 * 60.0)
+}
+
+/// Year fraction between two times using the B3 `DU/252` day count convention, ie/ the number
+/// of trading days of the provided calendar in the venue local date range `[from, to)`, divided
+/// by [`B3_BUSINESS_DAYS_PER_YEAR`].
+pub fn business_year_fraction(
+    calendar: &ExchangeCalendar,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> f64 {
+    let days = calendar.trading_days_between(calendar.local_date(from), calendar.local_date(to));
+    f64::from(days) / B3_BUSINESS_DAYS_PER_YEAR
+}
+
+/// Model terms shared by the price, Greeks & implied volatility calculations.
+struct Terms {
+    kind: OptionKind,
+
+    /// Cost of carry (b).
+    carry: f64,
+
+    /// Forward price of the underlying at expiry.
+    forward: f64,
+
+    /// `e^((b - r)T)`
+    carry_discount: f64,
+
+    /// `S·e^((b - r)T)`
+    spot_discounted: f64,
+
+    /// `K·e^(-rT)`
+    strike_discounted: f64,
+
+    underlying: f64,
+    strike: f64,
+    time_to_expiry: f64,
+}
+
+impl Terms {
+    fn new(model: PricingModel, inputs: &OptionInputs) -> Self {
+        let time_to_expiry = inputs.time_to_expiry.max(0.0);
+        let carry = match model {
+            PricingModel::BlackScholes => inputs.rate - inputs.dividend_yield,
+            PricingModel::Black76 => 0.0,
+        };
+        let carry_discount = ((carry - inputs.rate) * time_to_expiry).exp();
+
+        Self {
+            kind: inputs.kind,
+            carry,
+            forward: inputs.underlying * (carry * time_to_expiry).exp(),
+            carry_discount,
+            spot_discounted: inputs.underlying * carry_discount,
+            strike_discounted: inputs.strike * (-inputs.rate * time_to_expiry).exp(),
+            underlying: inputs.underlying,
+            strike: inputs.strike,
+            time_to_expiry,
+        }
+    }
+
+    /// Black-Scholes `(d1, d2)`, or `None` at expiry or with a non-positive volatility.
+    fn d(&self, volatility: f64) -> Option<(f64, f64)> {
+        if !(self.time_to_expiry > 0.0 && volatility > 0.0 && self.underlying > 0.0) {
+            return None;
+        }
+
+        let deviation = volatility * self.time_to_expiry.sqrt();
+        let d1 = ((self.underlying / self.strike).ln()
+            + (self.carry + volatility * volatility / 2.0) * self.time_to_expiry)
+            / deviation;
+
+        Some((d1, d1 - deviation))
+    }
+
+    /// Discounted intrinsic value on the forward price (ie/ the zero volatility price).
+    fn intrinsic(&self) -> f64 {
+        match self.kind {
+            OptionKind::Call => (self.spot_discounted - self.strike_discounted).max(0.0),
+            OptionKind::Put => (self.strike_discounted - self.spot_discounted).max(0.0),
+        }
+    }
+}
+
+/// Standard normal cumulative distribution function, using the double precision algorithm of
+/// Hart (1968) as presented by West (2005), "Better approximations to cumulative normal
+/// functions".
+fn norm_cdf(x: f64) -> f64 {
+    let z = x.abs();
+
+    let tail = if z > 37.0 {
+        0.0
+    } else {
+        let exponential = (-z * z / 2.0).exp();
+        if z < 7.071_067_811_865_47 {
+            let horner = |coefficients: &[f64]| {
+                coefficients
+                    .iter()
+                    .fold(0.0, |polynomial, coefficient| polynomial * z + coefficient)
+            };
+            exponential * horner(&NORM_CDF_NUMERATOR) / horner(&NORM_CDF_DENOMINATOR)
+        } else {
+            let fraction = z + 1.0 / (z + 2.0 / (z + 3.0 / (z + 4.0 / (z + 0.65))));
+            exponential / fraction / 2.506_628_274_631
+        }
+    };
+
+    if x > 0.0 {
+        1.0 - tail
+    } else {
+        tail
+    }
+}
+
+/// Standard normal probability density function.
+fn norm_pdf(x: f64) -> f64 {
+    (-x * x / 2.0).exp() / std::f64::consts::TAU.sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inputs(kind: OptionKind, underlying: f64, strike: f64, time_to_expiry: f64) -> OptionInputs {
+        OptionInputs {
+            kind,
+            underlying,
+            strike,
+            time_to_expiry,
+            rate: 0.05,
+            dividend_yield: 0.0,
+            volatility: 0.2,
+        }
+    }
+
+    fn assert_close(actual: f64, expected: f64, message: &str) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{message}: {actual} != {expected}"
+        );
+    }
+
+    #[test]
+    fn test_price_and_greeks() {
+        struct TestCase {
+            model: PricingModel,
+            inputs: OptionInputs,
+            expected_price: f64,
+            expected_greeks: Greeks,
+        }
+
+        let cases = vec![
+            // TC0: Black-Scholes at-the-money call
+            TestCase {
+                model: PricingModel::BlackScholes,
+                inputs: inputs(OptionKind::Call, 100.0, 100.0, 1.0),
+                expected_price: 10.450583572185565,
+                expected_greeks: Greeks {
+                    delta: 0.6368306511756191,
+                    gamma: 0.018762017345846895,
+                    vega: 37.52403469169379,
+                    theta: -6.414027546438197,
+                },
+            },
+            // TC1: Black-Scholes at-the-money put
+            TestCase {
+                model: PricingModel::BlackScholes,
+                inputs: inputs(OptionKind::Put, 100.0, 100.0, 1.0),
+                expected_price: 5.573526022256971,
+                expected_greeks: Greeks {
+                    delta: -0.3631693488243809,
+                    gamma: 0.018762017345846895,
+                    vega: 37.52403469169379,
+                    theta: -1.657880423934626,
+                },
+            },
+            // TC2: Black-76 at-the-money call on a forward
+            TestCase {
+                model: PricingModel::Black76,
+                inputs: inputs(OptionKind::Call, 100.0, 100.0, 1.0),
+                expected_price: 7.577082146427273,
+                expected_greeks: Greeks {
+                    delta: 0.5135001229824934,
+                    gamma: 0.018879647164532515,
+                    vega: 37.75929432906503,
+                    theta: -3.3970753255851394,
+                },
+            },
+            // TC3: Black-76 in-the-money put
+            TestCase {
+                model: PricingModel::Black76,
+                inputs: OptionInputs {
+                    volatility: 0.25,
+                    ..inputs(OptionKind::Put, 100.0, 110.0, 0.5)
+                },
+                expected_price: 13.109349932852169,
+                expected_greeks: Greeks {
+                    delta: -0.6572822385221904,
+                    gamma: 0.01988406913419628,
+                    vega: 24.85508641774535,
+                    theta: -5.558304107793728,
+                },
+            },
+            // TC4: Black-Scholes PETR4 call with a dividend yield, 30 business days to expiry
+            TestCase {
+                model: PricingModel::BlackScholes,
+                inputs: OptionInputs {
+                    kind: OptionKind::Call,
+                    underlying: 36.5,
+                    strike: 38.0,
+                    time_to_expiry: 30.0 / B3_BUSINESS_DAYS_PER_YEAR,
+                    rate: 0.1475,
+                    dividend_yield: 0.05,
+                    volatility: 0.35,
+                },
+                expected_price: 1.2944096769628697,
+                expected_greeks: Greeks {
+                    delta: 0.427202733609193,
+                    gamma: 0.08857282800207136,
+                    vega: 4.916714587739983,
+                    theta: -8.556952744857465,
+                },
+            },
+            // TC5: expired in-the-money call is worth its intrinsic value with a unit delta
+            TestCase {
+                model: PricingModel::BlackScholes,
+                inputs: inputs(OptionKind::Call, 105.0, 100.0, 0.0),
+                expected_price: 5.0,
+                expected_greeks: Greeks {
+                    delta: 1.0,
+                    ..Greeks::default()
+                },
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let message = format!("TC{index} failed");
+            let greeks = test.model.greeks(&test.inputs);
+
+            assert_close(
+                test.model.price(&test.inputs),
+                test.expected_price,
+                &message,
+            );
+            assert_close(greeks.delta, test.expected_greeks.delta, &message);
+            assert_close(greeks.gamma, test.expected_greeks.gamma, &message);
+            assert_close(greeks.vega, test.expected_greeks.vega, &message);
+            assert_close(greeks.theta, test.expected_greeks.theta, &message);
+        }
+    }
+
+    #[test]
+    fn test_implied_volatility() {
+        struct TestCase {
+            model: PricingModel,
+            inputs: OptionInputs,
+            price: f64,
+            expected: Result<f64, PricingError>,
+        }
+
+        let cases = vec![
+            // TC0: recovers the volatility of a Black-Scholes call from a distant initial guess
+            TestCase {
+                model: PricingModel::BlackScholes,
+                inputs: OptionInputs {
+                    volatility: 2.0,
+                    ..inputs(OptionKind::Call, 100.0, 100.0, 1.0)
+                },
+                price: 10.450583572185565,
+                expected: Ok(0.2),
+            },
+            // TC1: recovers the volatility of a deep out-of-the-money Black-76 put
+            TestCase {
+                model: PricingModel::Black76,
+                inputs: inputs(OptionKind::Put, 100.0, 60.0, 0.25),
+                price: PricingModel::Black76.price(&OptionInputs {
+                    volatility: 0.8,
+                    ..inputs(OptionKind::Put, 100.0, 60.0, 0.25)
+                }),
+                expected: Ok(0.8),
+            },
+            // TC2: price below the intrinsic value is rejected
+            TestCase {
+                model: PricingModel::BlackScholes,
+                inputs: inputs(OptionKind::Call, 120.0, 100.0, 1.0),
+                price: 1.0,
+                expected: Err(PricingError::PriceOutOfBounds {
+                    price: 1.0,
+                    lower: 120.0 - 100.0 * (-0.05_f64).exp(),
+                    upper: 120.0,
+                }),
+            },
+            // TC3: expired option is rejected
+            TestCase {
+                model: PricingModel::BlackScholes,
+                inputs: inputs(OptionKind::Call, 100.0, 100.0, 0.0),
+                price: 1.0,
+                expected: Err(PricingError::Expired),
+            },
+        ];
+
+        for (index, test) in cases.into_iter().enumerate() {
+            let actual = test.model.implied_volatility(&test.inputs, test.price);
+            match (actual, test.expected) {
+                (Ok(actual), Ok(expected)) => {
+                    assert_close(actual, expected, &format!("TC{index} failed"))
+                }
+                (actual, expected) => assert_eq!(actual, expected, "TC{index} failed"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_business_year_fraction() {
+        let calendar = ExchangeCalendar::b3_equities();
+
+        // 2025-03-03 & 2025-03-04 are Carnival holidays, so the week has 3 business days
+        let from = DateTime::parse_from_rfc3339("2025-03-03T13:00:00Z").unwrap();
+        let to = DateTime::parse_from_rfc3339("2025-03-10T13:00:00Z").unwrap();
+
+        assert_eq!(
+            business_year_fraction(&calendar, from.into(), to.into()),
+            3.0 / B3_BUSINESS_DAYS_PER_YEAR
+        );
+        assert_close(
+            year_fraction(from.into(), to.into()),
+            7.0 / 365.0,
+            "ACT/365",
+        );
+    }
+}
@@ -0,0 +1,58 @@
+//! Fills every IOC market order sent by the `Engine` in full at its request price, so fills,
+//! positions and trading summaries can be asserted deterministically.
+
+use crate::common::{order_id, order_snapshot, requests, trade};
+use chrono::{DateTime, Utc};
+use toucan_core::{engine::Processor, execution::request::ExecutionRequest, EngineEvent};
+use toucan_data::event::DataKind;
+use toucan_execution::order::{
+    request::OrderRequestOpen,
+    state::{Open, OrderState},
+};
+use toucan_integration::channel::UnboundedRx;
+
+/// Drain every open request sent by the `Engine` since the last call, without blocking.
+pub fn open_requests(execution_rx: &mut UnboundedRx<ExecutionRequest>) -> Vec<OrderRequestOpen> {
+    requests(execution_rx)
+        .into_iter()
+        .filter_map(|request| match request {
+            ExecutionRequest::Open(request, _) => Some(request),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Fill every open request sent by the `Engine` since the last call, returning the filled
+/// requests (see [`fill`]).
+pub fn fill_open_requests<Engine>(
+    engine: &mut Engine,
+    execution_rx: &mut UnboundedRx<ExecutionRequest>,
+    time: DateTime<Utc>,
+) -> Vec<OrderRequestOpen>
+where
+    Engine: Processor<EngineEvent<DataKind>>,
+{
+    open_requests(execution_rx)
+        .into_iter()
+        .inspect(|request| fill(engine, request, time))
+        .collect()
+}
+
+/// Fill an open request in full at its request price.
+///
+/// The `Trade` is reported before the filled order snapshot, so the position is always up to date
+/// once the order is no longer in-flight.
+pub fn fill<Engine>(engine: &mut Engine, request: &OrderRequestOpen, time: DateTime<Utc>)
+where
+    Engine: Processor<EngineEvent<DataKind>>,
+{
+    engine.process(trade(request, time));
+    engine.process(order_snapshot(
+        request,
+        OrderState::active(Open {
+            id: order_id(request),
+            time_exchange: time,
+            filled_quantity: request.state.quantity,
+        }),
+    ));
+}
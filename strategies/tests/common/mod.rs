@@ -0,0 +1,164 @@
+//! Fixtures shared by the strategy integration tests.
+//!
+//! Every test drives an [`Engine`] built like a backtest by `run_backtests` (ie/
+//! [`HistoricalClock`], [`MultiExchangeTxMap`] & [`DefaultRiskManager`]), whilst the test harness
+//! plays the role of the mocked exchange.
+//!
+//! Only the fixtures used by every test binary live here. The fixtures used by some of them are
+//! declared per use with a `#[path]` module:
+//! - `fills.rs`: fills every IOC market order in full at its request price.
+//! - `spec.rs`: B3 round lot `InstrumentSpec`.
+
+use chrono::{DateTime, TimeDelta, Utc};
+use rust_decimal::Decimal;
+use toucan_core::{
+    engine::{
+        clock::HistoricalClock,
+        execution_tx::MultiExchangeTxMap,
+        state::{trading::TradingState, EngineState},
+        Engine,
+    },
+    execution::{request::ExecutionRequest, AccountStreamEvent},
+    risk::DefaultRiskManager,
+    EngineEvent,
+};
+use toucan_data::{
+    event::{DataKind, MarketEvent},
+    streams::consumer::MarketStreamEvent,
+};
+use toucan_execution::{
+    order::{id::OrderId, request::OrderRequestOpen, state::OrderState, Order},
+    trade::{AssetFees, Trade, TradeId},
+    AccountEvent, AccountEventKind, AssetIndex, ExchangeIndex, InstrumentIndex,
+};
+use toucan_instrument::{exchange::ExchangeId, ConcreteInstrument, Keyed};
+use toucan_integration::{
+    channel::{mpsc_unbounded, UnboundedRx, UnboundedTx},
+    snapshot::Snapshot,
+};
+
+pub type TestEngine<GlobalData, InstrumentData, Strategy> = Engine<
+    HistoricalClock,
+    EngineState<GlobalData, InstrumentData>,
+    MultiExchangeTxMap,
+    Strategy,
+    DefaultRiskManager<EngineState<GlobalData, InstrumentData>>,
+>;
+
+/// Build a [`TestEngine`] trading the provided mock exchange instruments with
+/// `TradingState::Enabled`, returning it alongside the receiver of its `ExecutionRequest`s.
+pub fn build_engine<GlobalData, InstrumentData, Strategy, FnInstrumentData>(
+    instruments: &[&str],
+    global: GlobalData,
+    instrument_data: FnInstrumentData,
+    strategy: Strategy,
+) -> (
+    TestEngine<GlobalData, InstrumentData, Strategy>,
+    UnboundedRx<ExecutionRequest>,
+)
+where
+    FnInstrumentData: Fn() -> InstrumentData,
+{
+    let (execution_tx, execution_rx): (UnboundedTx<ExecutionRequest>, _) = mpsc_unbounded();
+
+    let instruments = mock_instruments(instruments);
+    let state = EngineState::builder(&instruments, global, |_| instrument_data())
+        .time_engine_start(time(TimeDelta::zero()))
+        .trading_state(TradingState::Enabled)
+        .build();
+
+    let engine = Engine::new(
+        HistoricalClock::new(time(TimeDelta::zero())),
+        state,
+        MultiExchangeTxMap::from_iter([(ExchangeId::Mock, Some(execution_tx))]),
+        strategy,
+        DefaultRiskManager::default(),
+    );
+
+    (engine, execution_rx)
+}
+
+fn mock_instruments(instruments: &[&str]) -> Vec<Keyed<String, ConcreteInstrument>> {
+    instruments
+        .iter()
+        .map(|instrument| {
+            Keyed::new(
+                instrument.to_string(),
+                ConcreteInstrument {
+                    symbol: (*instrument).into(),
+                    market: "spot".into(),
+                    exchange: ExchangeId::Mock,
+                    underlying: Some(format!("{instrument}_BRL")),
+                    name_exchange: (*instrument).into(),
+                },
+            )
+        })
+        .collect()
+}
+
+pub fn market_event(
+    instrument: &str,
+    time: DateTime<Utc>,
+    kind: DataKind,
+) -> EngineEvent<DataKind> {
+    EngineEvent::Market(MarketStreamEvent::Item(MarketEvent {
+        time_exchange: time,
+        time_received: time,
+        exchange: ExchangeId::Mock,
+        instrument: instrument.to_string(),
+        kind,
+    }))
+}
+
+pub fn account_event(
+    kind: AccountEventKind<ExchangeIndex, AssetIndex, InstrumentIndex>,
+) -> EngineEvent<DataKind> {
+    EngineEvent::Account(AccountStreamEvent::Item(AccountEvent {
+        exchange: ExchangeId::Mock.to_string(),
+        broker: None,
+        account: None,
+        trace: None,
+        kind,
+    }))
+}
+
+/// Drain every request sent by the `Engine` since the last call, without blocking.
+pub fn requests(execution_rx: &mut UnboundedRx<ExecutionRequest>) -> Vec<ExecutionRequest> {
+    std::iter::from_fn(|| execution_rx.rx.try_recv().ok()).collect()
+}
+
+/// `Trade` filling the full quantity of an open request at its request price.
+pub fn trade(request: &OrderRequestOpen, time: DateTime<Utc>) -> EngineEvent<DataKind> {
+    account_event(AccountEventKind::Trade(Trade {
+        id: TradeId::new(request.key.cid.0.clone()),
+        order_id: order_id(request),
+        instrument: request.key.instrument.clone(),
+        strategy: request.key.strategy.clone(),
+        time_exchange: time,
+        side: request.state.side,
+        price: request.state.price,
+        quantity: request.state.quantity,
+        fees: AssetFees::quote_fees(Decimal::ZERO),
+    }))
+}
+
+pub fn order_snapshot(request: &OrderRequestOpen, state: OrderState) -> EngineEvent<DataKind> {
+    account_event(AccountEventKind::OrderSnapshot(Snapshot(Order {
+        key: request.key.clone(),
+        side: request.state.side,
+        price: request.state.price,
+        quantity: request.state.quantity,
+        kind: request.state.kind,
+        time_in_force: request.state.time_in_force,
+        state,
+    })))
+}
+
+pub fn order_id(request: &OrderRequestOpen) -> OrderId {
+    OrderId::new(request.key.cid.0.clone())
+}
+
+/// Offset from Thursday 2025-01-02 at 13:00 UTC (10:00 in Sao Paulo), a B3 trading session.
+pub fn time(offset: TimeDelta) -> DateTime<Utc> {
+    DateTime::<Utc>::from_timestamp(1_735_822_800, 0).unwrap() + offset
+}
@@ -0,0 +1,22 @@
+//! B3 round lot `InstrumentSpec`.
+
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use toucan_execution::AssetIndex;
+use toucan_instrument::instrument::spec::{
+    InstrumentSpec, InstrumentSpecNotional, InstrumentSpecPrice, InstrumentSpecQuantity,
+    OrderQuantityUnits,
+};
+
+/// B3 equities trade in round lots of 100 shares, priced in cents.
+pub fn round_lots(instrument: &str) -> InstrumentSpec<AssetIndex> {
+    InstrumentSpec::new(
+        InstrumentSpecPrice::new(dec!(0.01), dec!(0.01)),
+        InstrumentSpecQuantity::new(
+            OrderQuantityUnits::Asset(instrument.to_string()),
+            dec!(100),
+            dec!(100),
+        ),
+        InstrumentSpecNotional::new(Decimal::ZERO),
+    )
+}
@@ -0,0 +1,219 @@
+//! # Delta Hedge Engine Integration Test
+//!
+//! Drives an [`Engine`] running the [`DeltaHedgeStrategy`] on a B3 call option (PETRA380) and
+//! its underlying (PETR4). An option position is opened outside the strategy, then the
+//! underlying price moves, and the strategy must keep the book delta within its hedge threshold
+//! by trading round lots of the underlying.
+//!
+//! No option prices are streamed, so deltas use the configured volatility.
+
+mod common;
+#[path = "common/fills.rs"]
+mod fills;
+#[path = "common/spec.rs"]
+mod spec;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use common::{account_event, build_engine, market_event, time, TestEngine};
+use fills::fill_open_requests;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use spec::round_lots;
+use toucan_core::{
+    engine::{
+        state::{global::DefaultGlobalData, instrument::data::DefaultInstrumentMarketData},
+        Processor,
+    },
+    execution::request::ExecutionRequest,
+    EngineEvent,
+};
+use toucan_data::{books::Level, event::DataKind, subscription::book::OrderBookL1};
+use toucan_execution::{
+    order::id::{OrderId, StrategyId},
+    trade::{AssetFees, Trade, TradeId},
+    AccountEventKind,
+};
+use toucan_instrument::{
+    instrument::kind::option::{OptionContract, OptionExercise, OptionKind},
+    Side,
+};
+use toucan_integration::channel::UnboundedRx;
+use toucan_markets::calendar::ExchangeCalendar;
+use toucan_strategies::options::{DeltaHedgeConfig, DeltaHedgeStrategy};
+
+const UNDERLYING: &str = "PETR4";
+const OPTION: &str = "PETRA380";
+
+type DeltaHedgeEngine =
+    TestEngine<DefaultGlobalData, DefaultInstrumentMarketData, DeltaHedgeStrategy>;
+
+#[test]
+fn test_delta_hedge_engine() {
+    struct TestCase {
+        day: i64,
+        underlying_price: Decimal,
+        expected_hedges: Vec<(Side, Decimal)>,
+        expected_hedge_position: Option<(Side, Decimal)>,
+    }
+
+    let (mut engine, mut execution_rx) = build_delta_hedge_engine(Decimal::ONE);
+
+    // Underlying quoted before the option position exists, so there is nothing to hedge
+    underlying_l1(&mut engine, 0, dec!(36.50));
+    assert!(common::requests(&mut execution_rx).is_empty());
+
+    // Long 1,000 PETRA380 calls opened outside the hedging strategy
+    engine.process(discretionary_trade(Side::Buy, dec!(1.05), dec!(1_000)));
+
+    let cases = vec![
+        // TC0: long calls are hedged by selling the underlying (Δ ≈ 0.393)
+        TestCase {
+            day: 0,
+            underlying_price: dec!(36.50),
+            expected_hedges: vec![(Side::Sell, dec!(400))],
+            expected_hedge_position: Some((Side::Sell, dec!(400))),
+        },
+        // TC1: small underlying move keeps the net delta within the hedge threshold (Δ ≈ 0.389)
+        TestCase {
+            day: 1,
+            underlying_price: dec!(36.55),
+            expected_hedges: vec![],
+            expected_hedge_position: Some((Side::Sell, dec!(400))),
+        },
+        // TC2: underlying rally increases the call delta, so more underlying is sold (Δ ≈ 0.595)
+        TestCase {
+            day: 4,
+            underlying_price: dec!(38.50),
+            expected_hedges: vec![(Side::Sell, dec!(200))],
+            expected_hedge_position: Some((Side::Sell, dec!(600))),
+        },
+        // TC3: underlying sell-off decreases the call delta, so underlying is bought back
+        // (Δ ≈ 0.204)
+        TestCase {
+            day: 5,
+            underlying_price: dec!(35.00),
+            expected_hedges: vec![(Side::Buy, dec!(400))],
+            expected_hedge_position: Some((Side::Sell, dec!(200))),
+        },
+    ];
+
+    for (index, test) in cases.into_iter().enumerate() {
+        underlying_l1(&mut engine, test.day, test.underlying_price);
+
+        let hedges = fill_open_requests(&mut engine, &mut execution_rx, day(test.day))
+            .into_iter()
+            .map(|request| (request.state.side, request.state.quantity))
+            .collect::<Vec<_>>();
+        assert_eq!(hedges, test.expected_hedges, "TC{index} failed");
+
+        let position = engine
+            .state
+            .instruments
+            .instrument(&UNDERLYING.to_string())
+            .position
+            .current
+            .as_ref()
+            .map(|position| (position.side, position.quantity_abs));
+        assert_eq!(position, test.expected_hedge_position, "TC{index} failed");
+
+        let net_delta = engine
+            .strategy
+            .net_delta(&engine.state, &UNDERLYING.to_string())
+            .unwrap();
+        assert!(net_delta.abs() <= dec!(50), "TC{index} failed: {net_delta}");
+    }
+}
+
+#[test]
+fn test_delta_hedge_engine_contract_size() {
+    // Underlying contract of 2 PETR4 shares, so the delta is hedged with half as many contracts
+    let (mut engine, mut execution_rx) = build_delta_hedge_engine(dec!(2));
+
+    engine.process(discretionary_trade(Side::Buy, dec!(1.05), dec!(1_000)));
+    underlying_l1(&mut engine, 0, dec!(36.50));
+
+    // Δ ≈ 0.393, so 393 shares are hedged by selling 200 contracts (ie/ 400 shares)
+    let hedges = fill_open_requests(&mut engine, &mut execution_rx, day(0))
+        .into_iter()
+        .map(|request| (request.state.side, request.state.quantity))
+        .collect::<Vec<_>>();
+    assert_eq!(hedges, vec![(Side::Sell, dec!(200))]);
+
+    let net_delta = engine
+        .strategy
+        .net_delta(&engine.state, &UNDERLYING.to_string())
+        .unwrap();
+    assert!(net_delta.abs() <= dec!(50), "{net_delta}");
+}
+
+fn build_delta_hedge_engine(
+    contract_size: Decimal,
+) -> (DeltaHedgeEngine, UnboundedRx<ExecutionRequest>) {
+    let petra380 = OptionContract {
+        contract_size: Decimal::ONE,
+        settlement_asset: "BRL".to_string(),
+        kind: OptionKind::Call,
+        exercise: OptionExercise::American,
+        expiry: DateTime::parse_from_rfc3339("2025-01-17T20:00:00Z")
+            .unwrap()
+            .into(),
+        strike: dec!(38),
+    };
+
+    let config = DeltaHedgeConfig {
+        rate: dec!(0.1475),
+        volatility: dec!(0.50),
+        hedge_threshold: dec!(50),
+        ..DeltaHedgeConfig::default()
+    };
+
+    let strategy = DeltaHedgeStrategy::new(StrategyId::new("delta_hedge"), config)
+        .with_option(OPTION.to_string(), UNDERLYING.to_string(), petra380)
+        .with_instrument_spec(
+            UNDERLYING.to_string(),
+            round_lots(UNDERLYING),
+            contract_size,
+        )
+        .with_calendar(ExchangeCalendar::b3_equities());
+
+    build_engine(
+        &[UNDERLYING, OPTION],
+        DefaultGlobalData,
+        DefaultInstrumentMarketData::default,
+        strategy,
+    )
+}
+
+fn underlying_l1(engine: &mut DeltaHedgeEngine, day: i64, mid: Decimal) {
+    let time = self::day(day);
+    engine.process(market_event(
+        UNDERLYING,
+        time,
+        DataKind::OrderBookL1(OrderBookL1 {
+            last_update_time: time,
+            best_bid: Some(Level::new(mid - dec!(0.01), dec!(1_000))),
+            best_ask: Some(Level::new(mid + dec!(0.01), dec!(1_000))),
+        }),
+    ));
+}
+
+/// PETRA380 `Trade` of a strategy other than the [`DeltaHedgeStrategy`].
+fn discretionary_trade(side: Side, price: Decimal, quantity: Decimal) -> EngineEvent<DataKind> {
+    let id = format!("{OPTION}-{side}-{quantity}-{price}");
+    account_event(AccountEventKind::Trade(Trade {
+        id: TradeId::new(id.clone()),
+        order_id: OrderId::new(id),
+        instrument: OPTION.to_string(),
+        strategy: StrategyId::new("discretionary"),
+        time_exchange: day(0),
+        side,
+        price,
+        quantity,
+        fees: AssetFees::quote_fees(Decimal::ZERO),
+    }))
+}
+
+/// Calendar days from the start of the test (ie/ Thursday 2025-01-02 at 10:00 in Sao Paulo).
+fn day(days: i64) -> DateTime<Utc> {
+    time(TimeDelta::days(days))
+}
@@ -0,0 +1,237 @@
+//! # Market Maker Engine Integration Test
+//!
+//! Drives an [`Engine`] running the [`MarketMakerStrategy`] with `OrderBookL1` updates, playing
+//! the role of the exchange by acknowledging quotes & cancels and filling resting quotes, so the
+//! quote lifecycle (quote, cancel & replace, inventory skew, max inventory) can be asserted
+//! deterministically.
+
+mod common;
+#[path = "common/spec.rs"]
+mod spec;
+
+use chrono::TimeDelta;
+use common::{
+    account_event, build_engine, market_event, order_id, order_snapshot, time, trade, TestEngine,
+};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use spec::round_lots;
+use toucan_core::{
+    engine::{state::global::DefaultGlobalData, Processor},
+    execution::request::ExecutionRequest,
+};
+use toucan_data::{books::Level, event::DataKind, subscription::book::OrderBookL1};
+use toucan_execution::{
+    order::{
+        id::{OrderId, StrategyId},
+        request::{OrderRequestCancel, OrderRequestOpen, OrderResponseCancel},
+        state::{Cancelled, Open, OrderState},
+        OrderKind, TimeInForce,
+    },
+    AccountEventKind,
+};
+use toucan_instrument::Side;
+use toucan_integration::channel::UnboundedRx;
+use toucan_strategies::microstructure::{MarketMakerConfig, MarketMakerData, MarketMakerStrategy};
+
+const INSTRUMENT: &str = "PETR4";
+
+type MarketMakerEngine = TestEngine<DefaultGlobalData, MarketMakerData, MarketMakerStrategy>;
+
+#[test]
+fn test_market_maker_engine() {
+    let config = MarketMakerConfig {
+        volatility_period: 2,
+        max_inventory: dec!(100),
+        ..MarketMakerConfig::default()
+    };
+    let (mut engine, mut execution_rx) = build_market_maker_engine(config);
+
+    // TC0: quotes both sides around the mid-price once the volatility is warmed up
+    // (mid-prices 30.00, 30.02, 30.00 => σ = 0.02, δ ≈ 0.024)
+    market(&mut engine, 0, dec!(29.99), dec!(30.01));
+    market(&mut engine, 1, dec!(30.01), dec!(30.03));
+    market(&mut engine, 2, dec!(29.99), dec!(30.01));
+    let (cancels, opens) = requests(&mut execution_rx);
+    assert!(cancels.is_empty(), "TC0 failed");
+    assert_eq!(
+        quotes(&opens),
+        vec![(Side::Buy, dec!(29.98)), (Side::Sell, dec!(30.02))],
+        "TC0 failed"
+    );
+    assert!(
+        opens.iter().all(|open| open.state.kind == OrderKind::Limit
+            && open.state.time_in_force == TimeInForce::GoodUntilCancelled { post_only: true }),
+        "TC0 failed"
+    );
+    let resting = acknowledge_opens(&mut engine, &opens);
+
+    // TC1: resting quotes within the requote threshold of the target quotes are left untouched
+    market(&mut engine, 3, dec!(29.99), dec!(30.01));
+    let (cancels, opens) = requests(&mut execution_rx);
+    assert!(cancels.is_empty() && opens.is_empty(), "TC1 failed");
+
+    // TC2: mid-price jump cancels both stale quotes, and wider quotes (σ = 0.05) replace them
+    // once the cancels are acknowledged
+    market(&mut engine, 4, dec!(30.09), dec!(30.11));
+    let (cancels, opens) = requests(&mut execution_rx);
+    assert!(opens.is_empty(), "TC2 failed");
+    assert_eq!(cancelled(&cancels), cids(&resting), "TC2 failed");
+    acknowledge_cancels(&mut engine, &cancels);
+    let (cancels, opens) = requests(&mut execution_rx);
+    assert!(cancels.is_empty(), "TC2 failed");
+    assert_eq!(
+        quotes(&opens),
+        vec![(Side::Buy, dec!(30.07)), (Side::Sell, dec!(30.13))],
+        "TC2 failed"
+    );
+    let resting = acknowledge_opens(&mut engine, &opens);
+
+    // TC3: bid fill skews the reservation price down (r = 30.075), so the ask is replaced lower
+    // and the bid is no longer quoted since another fill would breach the max inventory. The
+    // filled bid is still resting when its `Trade` is processed, so it is also cancelled (which
+    // the exchange would reject), but only the ask cancel is acknowledged.
+    fill_quote(&mut engine, &resting[0]);
+    let (cancels, opens) = requests(&mut execution_rx);
+    assert!(opens.is_empty(), "TC3 failed");
+    let (ask_cancels, _): (Vec<_>, Vec<_>) = cancels
+        .into_iter()
+        .partition(|cancel| cancel.key.cid == resting[1].key.cid);
+    assert_eq!(ask_cancels.len(), 1, "TC3 failed");
+    acknowledge_cancels(&mut engine, &ask_cancels);
+    let (cancels, opens) = requests(&mut execution_rx);
+    assert!(cancels.is_empty(), "TC3 failed");
+    assert_eq!(
+        quotes(&opens),
+        vec![(Side::Sell, dec!(30.10))],
+        "TC3 failed"
+    );
+
+    let position = engine
+        .state
+        .instruments
+        .instrument(&INSTRUMENT.to_string())
+        .position
+        .current
+        .as_ref()
+        .map(|position| (position.side, position.quantity_abs));
+    assert_eq!(position, Some((Side::Buy, dec!(100))), "TC3 failed");
+}
+
+fn build_market_maker_engine(
+    config: MarketMakerConfig,
+) -> (MarketMakerEngine, UnboundedRx<ExecutionRequest>) {
+    let strategy = MarketMakerStrategy::new(StrategyId::new("market_maker"), config)
+        .with_instrument_spec(INSTRUMENT.to_string(), round_lots(INSTRUMENT), Decimal::ONE);
+    let instrument_data = strategy.instrument_data();
+
+    build_engine(
+        &[INSTRUMENT],
+        DefaultGlobalData,
+        || instrument_data.clone(),
+        strategy,
+    )
+}
+
+fn market(engine: &mut MarketMakerEngine, second: i64, bid: Decimal, ask: Decimal) {
+    let time = time(TimeDelta::seconds(second));
+    engine.process(market_event(
+        INSTRUMENT,
+        time,
+        DataKind::OrderBookL1(OrderBookL1 {
+            last_update_time: time,
+            best_bid: Some(Level::new(bid, dec!(1_000))),
+            best_ask: Some(Level::new(ask, dec!(1_000))),
+        }),
+    ));
+}
+
+/// Drain every request sent by the `Engine` since the last call, partitioned into cancels and
+/// opens.
+fn requests(
+    execution_rx: &mut UnboundedRx<ExecutionRequest>,
+) -> (Vec<OrderRequestCancel>, Vec<OrderRequestOpen>) {
+    common::requests(execution_rx).into_iter().fold(
+        (Vec::new(), Vec::new()),
+        |(mut cancels, mut opens), request| {
+            match request {
+                ExecutionRequest::Cancel(request, _) => cancels.push(request),
+                ExecutionRequest::Open(request, _) => opens.push(request),
+                _ => {}
+            }
+            (cancels, opens)
+        },
+    )
+}
+
+/// Quote (side, price) of each open request, bids first.
+fn quotes(opens: &[OrderRequestOpen]) -> Vec<(Side, Decimal)> {
+    let mut quotes = opens
+        .iter()
+        .map(|open| (open.state.side, open.state.price))
+        .collect::<Vec<_>>();
+    quotes.sort_by_key(|(side, _)| *side != Side::Buy);
+    quotes
+}
+
+fn cids(opens: &[OrderRequestOpen]) -> Vec<String> {
+    let mut cids = opens
+        .iter()
+        .map(|open| open.key.cid.0.to_string())
+        .collect::<Vec<_>>();
+    cids.sort();
+    cids
+}
+
+fn cancelled(cancels: &[OrderRequestCancel]) -> Vec<String> {
+    let mut cids = cancels
+        .iter()
+        .map(|cancel| cancel.key.cid.0.to_string())
+        .collect::<Vec<_>>();
+    cids.sort();
+    cids
+}
+
+/// Acknowledge every open request as a resting (unfilled) order, returning the requests with
+/// bids first.
+fn acknowledge_opens(
+    engine: &mut MarketMakerEngine,
+    opens: &[OrderRequestOpen],
+) -> Vec<OrderRequestOpen> {
+    let mut resting = opens.to_vec();
+    resting.sort_by_key(|open| open.state.side != Side::Buy);
+
+    for request in &resting {
+        engine.process(order_snapshot(
+            request,
+            OrderState::active(Open {
+                id: order_id(request),
+                time_exchange: time(TimeDelta::zero()),
+                filled_quantity: Decimal::ZERO,
+            }),
+        ));
+    }
+
+    resting
+}
+
+fn acknowledge_cancels(engine: &mut MarketMakerEngine, cancels: &[OrderRequestCancel]) {
+    for request in cancels {
+        engine.process(account_event(AccountEventKind::OrderCancelled(
+            OrderResponseCancel {
+                key: request.key.clone(),
+                state: Ok(Cancelled {
+                    id: OrderId::new(request.key.cid.0.clone()),
+                    time_exchange: time(TimeDelta::zero()),
+                }),
+            },
+        )));
+    }
+}
+
+/// Fill a resting quote in full at its price, reporting the `Trade` before the fully filled
+/// order snapshot.
+fn fill_quote(engine: &mut MarketMakerEngine, request: &OrderRequestOpen) {
+    engine.process(trade(request, time(TimeDelta::zero())));
+    engine.process(order_snapshot(request, OrderState::fully_filled()));
+}
@@ -0,0 +1,163 @@
+//! # Mean Reversion Backtest Integration Test
+//!
+//! Replays a synthetic daily candle series (two sell-offs away from a range) through an
+//! [`Engine`] running the [`MeanReversionStrategy`] with B3 round lot sizing, asserting its
+//! entries and exits.
+
+mod common;
+#[path = "common/fills.rs"]
+mod fills;
+#[path = "common/spec.rs"]
+mod spec;
+
+use chrono::TimeDelta;
+use common::{build_engine, time, TestEngine};
+use fills::fill_open_requests;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use spec::round_lots;
+use std::time::Duration;
+use toucan_core::{
+    engine::{state::global::DefaultGlobalData, Processor},
+    execution::request::ExecutionRequest,
+    EngineEvent,
+};
+use toucan_data::{event::DataKind, subscription::candle::Candle};
+use toucan_execution::order::id::StrategyId;
+use toucan_instrument::Side;
+use toucan_integration::channel::UnboundedRx;
+use toucan_strategies::mean_reversion::{
+    MeanReversionConfig, MeanReversionData, MeanReversionStrategy,
+};
+
+const INSTRUMENT: &str = "PETR4";
+
+type MeanReversionEngine = TestEngine<DefaultGlobalData, MeanReversionData, MeanReversionStrategy>;
+
+#[test]
+fn test_mean_reversion_backtest() {
+    struct TestCase {
+        config: MeanReversionConfig,
+        expected_fills: Vec<(usize, Side, Decimal)>,
+    }
+
+    let config = MeanReversionConfig {
+        period: 20,
+        entry_z: dec!(2),
+        exit_z: dec!(0.5),
+        max_holding: None,
+        notional: dec!(10_000),
+        allow_short: false,
+    };
+
+    let cases = vec![
+        // TC0: buys each sell-off & sells once the close reverts towards the mean
+        TestCase {
+            config,
+            expected_fills: vec![
+                (20, Side::Buy, dec!(300)),
+                (21, Side::Sell, dec!(300)),
+                (41, Side::Buy, dec!(300)),
+                (56, Side::Sell, dec!(300)),
+            ],
+        },
+        // TC1: max holding time exits the second (persistent) sell-off early
+        TestCase {
+            config: MeanReversionConfig {
+                max_holding: Some(Duration::from_secs(3 * 24 * 60 * 60)),
+                ..config
+            },
+            expected_fills: vec![
+                (20, Side::Buy, dec!(300)),
+                (21, Side::Sell, dec!(300)),
+                (41, Side::Buy, dec!(300)),
+                (44, Side::Sell, dec!(300)),
+            ],
+        },
+        // TC2: notional below one round lot never enters
+        TestCase {
+            config: MeanReversionConfig {
+                notional: dec!(2_000),
+                ..config
+            },
+            expected_fills: vec![],
+        },
+    ];
+
+    for (index, test) in cases.into_iter().enumerate() {
+        let (mut engine, mut execution_rx) = build_mean_reversion_engine(test.config);
+
+        let fills = candles()
+            .into_iter()
+            .enumerate()
+            .flat_map(|(bar, candle)| {
+                engine.process(candle);
+                fill_open_requests(
+                    &mut engine,
+                    &mut execution_rx,
+                    time(TimeDelta::days(bar as i64 + 1)),
+                )
+                .into_iter()
+                .map(move |fill| (bar, fill.state.side, fill.state.quantity))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(fills, test.expected_fills, "TC{index} failed");
+
+        let position = &engine
+            .state
+            .instruments
+            .instrument(&INSTRUMENT.to_string())
+            .position
+            .current;
+        assert!(position.is_none(), "TC{index} failed");
+    }
+}
+
+fn build_mean_reversion_engine(
+    config: MeanReversionConfig,
+) -> (MeanReversionEngine, UnboundedRx<ExecutionRequest>) {
+    let strategy = MeanReversionStrategy::new(StrategyId::new("mean_reversion"), config)
+        .with_instrument_spec(INSTRUMENT.to_string(), round_lots(INSTRUMENT), Decimal::ONE);
+    let instrument_data = strategy.instrument_data();
+
+    build_engine(
+        &[INSTRUMENT],
+        DefaultGlobalData,
+        || instrument_data.clone(),
+        strategy,
+    )
+}
+
+/// Synthetic daily closes: a 30.00 range with alternating noise, a one-bar sell-off to 28.00 that
+/// immediately reverts, then a persistent sell-off to 28.00.
+fn closes() -> Vec<f64> {
+    let range = |bars: usize| (0..bars).map(|bar| if bar % 2 == 0 { 30.1 } else { 29.9 });
+
+    range(20)
+        .chain([28.0])
+        .chain(range(20))
+        .chain(std::iter::repeat_n(28.0, 20))
+        .collect()
+}
+
+fn candles() -> Vec<EngineEvent<DataKind>> {
+    closes()
+        .into_iter()
+        .zip(1..)
+        .map(|(close, bar)| {
+            common::market_event(
+                INSTRUMENT,
+                time(TimeDelta::days(bar)),
+                DataKind::Candle(Candle {
+                    close_time: time(TimeDelta::days(bar)),
+                    open: close,
+                    high: close,
+                    low: close,
+                    close,
+                    volume: 1_000.0,
+                    trade_count: 100,
+                }),
+            )
+        })
+        .collect()
+}
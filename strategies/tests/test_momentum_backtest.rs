@@ -0,0 +1,157 @@
+//! # Momentum Backtest Integration Test
+//!
+//! Replays a synthetic daily candle series (down-trend, up-trend, down-trend) through an
+//! [`Engine`] running the [`MomentumStrategy`], asserting its entries, exits and reversals.
+
+mod common;
+#[path = "common/fills.rs"]
+mod fills;
+
+use chrono::TimeDelta;
+use common::{build_engine, time, TestEngine};
+use fills::fill_open_requests;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use toucan_core::{
+    analytics::time::Daily,
+    engine::{state::global::DefaultGlobalData, Processor},
+    execution::request::ExecutionRequest,
+    EngineEvent,
+};
+use toucan_data::{event::DataKind, subscription::candle::Candle};
+use toucan_execution::order::id::StrategyId;
+use toucan_instrument::Side;
+use toucan_integration::channel::UnboundedRx;
+use toucan_strategies::momentum::{MomentumConfig, MomentumData, MomentumStrategy};
+
+const INSTRUMENT: &str = "PETR4";
+const RISK_FREE_RETURN: Decimal = dec!(0.05);
+
+type MomentumEngine = TestEngine<DefaultGlobalData, MomentumData, MomentumStrategy>;
+
+#[test]
+fn test_momentum_backtest() {
+    struct TestCase {
+        config: MomentumConfig,
+        expected_fills: Vec<(Side, Decimal)>,
+        expected_position: Option<(Side, Decimal)>,
+    }
+
+    let config = MomentumConfig {
+        fast_period: 5,
+        slow_period: 20,
+        atr_period: 10,
+        atr_stop: dec!(3),
+        quantity: dec!(100),
+        allow_short: false,
+    };
+
+    let cases = vec![
+        // TC0: long-only enters on the bullish crossover & exits on the bearish crossover
+        TestCase {
+            config,
+            expected_fills: vec![(Side::Buy, dec!(100)), (Side::Sell, dec!(100))],
+            expected_position: None,
+        },
+        // TC1: long-short reverses into a short position on the bearish crossover
+        TestCase {
+            config: MomentumConfig {
+                allow_short: true,
+                ..config
+            },
+            expected_fills: vec![(Side::Buy, dec!(100)), (Side::Sell, dec!(200))],
+            expected_position: Some((Side::Sell, dec!(100))),
+        },
+    ];
+
+    for (index, test) in cases.into_iter().enumerate() {
+        let (mut engine, mut execution_rx) = build_momentum_engine(test.config);
+
+        let fills = candles()
+            .into_iter()
+            .enumerate()
+            .flat_map(|(bar, candle)| {
+                engine.process(candle);
+                fill_open_requests(
+                    &mut engine,
+                    &mut execution_rx,
+                    time(TimeDelta::days(bar as i64 + 1)),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let actual_fills = fills
+            .iter()
+            .map(|fill| (fill.state.side, fill.state.quantity))
+            .collect::<Vec<_>>();
+        assert_eq!(actual_fills, test.expected_fills, "TC{index} failed");
+
+        let position = engine
+            .state
+            .instruments
+            .instrument(&INSTRUMENT.to_string())
+            .position
+            .current
+            .as_ref()
+            .map(|position| (position.side, position.quantity_abs));
+        assert_eq!(position, test.expected_position, "TC{index} failed");
+
+        // Bought the up-trend & sold the down-trend, so the round trip must be profitable
+        let (entry, exit) = (&fills[0].state, &fills[1].state);
+        assert!(exit.price > entry.price, "TC{index} failed");
+
+        let summary = engine
+            .trading_summary_generator(RISK_FREE_RETURN)
+            .generate(Daily);
+        assert!(
+            summary.instruments[INSTRUMENT].pnl > Decimal::ZERO,
+            "TC{index} failed"
+        );
+    }
+}
+
+fn build_momentum_engine(
+    config: MomentumConfig,
+) -> (MomentumEngine, UnboundedRx<ExecutionRequest>) {
+    let strategy = MomentumStrategy::new(StrategyId::new("momentum"), config);
+    let instrument_data = strategy.instrument_data();
+
+    build_engine(
+        &[INSTRUMENT],
+        DefaultGlobalData,
+        || instrument_data.clone(),
+        strategy,
+    )
+}
+
+/// Synthetic daily closes: 30 bars trending down from 32.00, 40 bars trending up, then 40 bars
+/// trending down.
+fn closes() -> Vec<f64> {
+    let trending_down = (0..30).map(|bar| 32.0 - 0.1 * bar as f64);
+    let trending_up = (1..=40).map(|bar| 29.1 + 0.25 * bar as f64);
+    let reversal = (1..=40).map(|bar| 39.1 - 0.35 * bar as f64);
+
+    trending_down.chain(trending_up).chain(reversal).collect()
+}
+
+fn candles() -> Vec<EngineEvent<DataKind>> {
+    closes()
+        .into_iter()
+        .zip(1..)
+        .map(|(close, bar)| {
+            common::market_event(
+                INSTRUMENT,
+                time(TimeDelta::days(bar)),
+                DataKind::Candle(Candle {
+                    close_time: time(TimeDelta::days(bar)),
+                    open: close,
+                    high: close + 0.15,
+                    low: close - 0.15,
+                    close,
+                    volume: 1_000.0,
+                    trade_count: 100,
+                }),
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,173 @@
+//! # Order Book Imbalance Backtest Integration Test
+//!
+//! Canonical end-to-end example: replays a synthetic `OrderBookL1` series through an [`Engine`]
+//! running the [`OrderBookImbalanceStrategy`] on the [`OrderBookImbalanceData`] maintained by the
+//! `Engine`, asserting its entries, exits, reversals and trading summary.
+//!
+//! This is the backtest usage example of the README run end-to-end: the `Engine` is built by
+//! [`build_engine`] exactly as shown there, and its trading summary is generated the same way.
+
+mod common;
+#[path = "common/fills.rs"]
+mod fills;
+
+use chrono::TimeDelta;
+use common::{build_engine, market_event, time, TestEngine};
+use fills::fill_open_requests;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use toucan_core::{
+    analytics::time::Daily,
+    engine::{state::global::DefaultGlobalData, Processor},
+    execution::request::ExecutionRequest,
+    EngineEvent,
+};
+use toucan_data::{books::Level, event::DataKind, subscription::book::OrderBookL1};
+use toucan_execution::order::id::StrategyId;
+use toucan_instrument::Side;
+use toucan_integration::channel::UnboundedRx;
+use toucan_strategies::order_book_imbalance::{
+    OrderBookImbalanceConfig, OrderBookImbalanceData, OrderBookImbalanceStrategy,
+};
+
+const INSTRUMENT: &str = "PETR4";
+const RISK_FREE_RETURN: Decimal = dec!(0.05);
+
+type ImbalanceEngine =
+    TestEngine<DefaultGlobalData, OrderBookImbalanceData, OrderBookImbalanceStrategy>;
+
+#[test]
+fn test_order_book_imbalance_backtest() {
+    struct TestCase {
+        config: OrderBookImbalanceConfig,
+        expected_fills: Vec<(Side, Decimal, Decimal)>,
+        expected_position: Option<(Side, Decimal)>,
+    }
+
+    let config = OrderBookImbalanceConfig {
+        threshold: dec!(0.6),
+        quantity: dec!(100),
+        reverse: false,
+    };
+
+    let cases = vec![
+        // TC0: enters on the first bid imbalance, ignores the persisting imbalance, exits on the
+        // opposite imbalance, then re-enters on the renewed bid imbalance
+        TestCase {
+            config,
+            expected_fills: vec![
+                (Side::Buy, dec!(100), dec!(30.02)),
+                (Side::Sell, dec!(100), dec!(30.20)),
+                (Side::Buy, dec!(100), dec!(30.02)),
+            ],
+            expected_position: Some((Side::Buy, dec!(100))),
+        },
+        // TC1: higher threshold only trades the strongest imbalances
+        TestCase {
+            config: OrderBookImbalanceConfig {
+                threshold: dec!(0.75),
+                ..config
+            },
+            expected_fills: vec![
+                (Side::Buy, dec!(100), dec!(30.07)),
+                (Side::Sell, dec!(100), dec!(30.20)),
+            ],
+            expected_position: None,
+        },
+        // TC2: reverse configured, so each opposite imbalance reverses the open position
+        TestCase {
+            config: OrderBookImbalanceConfig {
+                reverse: true,
+                ..config
+            },
+            expected_fills: vec![
+                (Side::Buy, dec!(100), dec!(30.02)),
+                (Side::Sell, dec!(200), dec!(30.20)),
+                (Side::Buy, dec!(200), dec!(30.02)),
+            ],
+            expected_position: Some((Side::Buy, dec!(100))),
+        },
+    ];
+
+    for (index, test) in cases.into_iter().enumerate() {
+        let (mut engine, mut execution_rx) = build_imbalance_engine(test.config);
+
+        let fills = books()
+            .into_iter()
+            .enumerate()
+            .flat_map(|(second, book)| {
+                engine.process(book);
+                fill_open_requests(
+                    &mut engine,
+                    &mut execution_rx,
+                    time(TimeDelta::seconds(second as i64 + 1)),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let actual_fills = fills
+            .iter()
+            .map(|fill| (fill.state.side, fill.state.quantity, fill.state.price))
+            .collect::<Vec<_>>();
+        assert_eq!(actual_fills, test.expected_fills, "TC{index} failed");
+
+        let position = engine
+            .state
+            .instruments
+            .instrument(&INSTRUMENT.to_string())
+            .position
+            .current
+            .as_ref()
+            .map(|position| (position.side, position.quantity_abs));
+        assert_eq!(position, test.expected_position, "TC{index} failed");
+
+        // Imbalances led the synthetic mid-price, so the closed round trip must be profitable
+        let summary = engine
+            .trading_summary_generator(RISK_FREE_RETURN)
+            .generate(Daily);
+        assert!(
+            summary.instruments[INSTRUMENT].pnl > Decimal::ZERO,
+            "TC{index} failed"
+        );
+    }
+}
+
+fn build_imbalance_engine(
+    config: OrderBookImbalanceConfig,
+) -> (ImbalanceEngine, UnboundedRx<ExecutionRequest>) {
+    build_engine(
+        &[INSTRUMENT],
+        DefaultGlobalData,
+        || OrderBookImbalanceData::new(&config),
+        OrderBookImbalanceStrategy::new(config).with_id(StrategyId::new("imbalance")),
+    )
+}
+
+/// Synthetic `OrderBookL1` series of (best bid, best ask, bid volume, ask volume): bid pressure
+/// lifting the mid-price, ask pressure pushing it back down, then renewed bid pressure.
+fn books() -> Vec<EngineEvent<DataKind>> {
+    [
+        (dec!(29.99), dec!(30.01), dec!(500), dec!(500)),
+        (dec!(30.00), dec!(30.02), dec!(700), dec!(300)),
+        (dec!(30.05), dec!(30.07), dec!(800), dec!(200)),
+        (dec!(30.15), dec!(30.17), dec!(500), dec!(500)),
+        (dec!(30.20), dec!(30.22), dec!(200), dec!(800)),
+        (dec!(30.10), dec!(30.12), dec!(300), dec!(700)),
+        (dec!(30.00), dec!(30.02), dec!(650), dec!(350)),
+    ]
+    .into_iter()
+    .zip(1..)
+    .map(|((bid, ask, bid_volume, ask_volume), second)| {
+        let time = time(TimeDelta::seconds(second));
+        market_event(
+            INSTRUMENT,
+            time,
+            DataKind::OrderBookL1(OrderBookL1 {
+                last_update_time: time,
+                best_bid: Some(Level::new(bid, bid_volume)),
+                best_ask: Some(Level::new(ask, ask_volume)),
+            }),
+        )
+    })
+    .collect()
+}
@@ -0,0 +1,228 @@
+//! # Pairs Backtest Integration Test
+//!
+//! Replays synthetic daily candles of a cointegrated pair (ITUB4 ≈ 2 × BBDC4 + 1) through an
+//! [`Engine`] running the [`PairsStrategy`], whose spread statistics are maintained by the
+//! [`PairsData`] `GlobalData`. ITUB4 briefly dislocates above the regression line, so the spread
+//! must be sold (ITUB4 sold, β × BBDC4 bought) and then exited once it reverts.
+//!
+//! An entry leg can also be expired unfilled (ie/ leg risk), so the naked leg must be unwound.
+
+mod common;
+#[path = "common/spec.rs"]
+mod spec;
+
+use chrono::TimeDelta;
+use common::{build_engine, order_id, order_snapshot, requests, time, trade, TestEngine};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use spec::round_lots;
+use toucan_core::{
+    engine::{state::instrument::data::DefaultInstrumentMarketData, Processor},
+    execution::request::ExecutionRequest,
+    EngineEvent,
+};
+use toucan_data::{event::DataKind, subscription::candle::Candle};
+use toucan_execution::order::{
+    id::StrategyId,
+    request::OrderRequestOpen,
+    state::{Open, OrderState},
+};
+use toucan_instrument::Side;
+use toucan_integration::channel::UnboundedRx;
+use toucan_strategies::pairs::{Pair, PairsConfig, PairsData, PairsStrategy};
+
+const Y: &str = "ITUB4";
+const X: &str = "BBDC4";
+const BARS: i64 = 90;
+
+type PairsEngine = TestEngine<PairsData, DefaultInstrumentMarketData, PairsStrategy>;
+
+#[test]
+fn test_pairs_backtest() {
+    struct TestCase {
+        expire_first: Option<(&'static str, Side)>,
+        expected_fills: Vec<(&'static str, Side, Decimal)>,
+    }
+
+    let cases = vec![
+        // TC0: rich spread is sold at the ITUB4 dislocation (bar 63, β ≈ 2) and bought back
+        // once it reverts (bar 65)
+        TestCase {
+            expire_first: None,
+            expected_fills: vec![
+                (Y, Side::Sell, dec!(100)),
+                (X, Side::Buy, dec!(200)),
+                (Y, Side::Buy, dec!(100)),
+                (X, Side::Sell, dec!(200)),
+            ],
+        },
+        // TC1: BBDC4 entry leg expires unfilled, so the naked ITUB4 leg is unwound before the
+        // still rich spread is re-entered
+        TestCase {
+            expire_first: Some((X, Side::Buy)),
+            expected_fills: vec![
+                (Y, Side::Sell, dec!(100)),
+                (Y, Side::Buy, dec!(100)),
+                (Y, Side::Sell, dec!(100)),
+                (X, Side::Buy, dec!(200)),
+                (Y, Side::Buy, dec!(100)),
+                (X, Side::Sell, dec!(200)),
+            ],
+        },
+    ];
+
+    for (index, mut test) in cases.into_iter().enumerate() {
+        let (mut engine, mut execution_rx) = build_pairs_engine();
+
+        let mut fills = Vec::new();
+        for (bar, (x, y)) in (0..).zip(series()) {
+            engine.process(candle(X, bar, x));
+            engine.process(candle(Y, bar, y));
+
+            fills.extend(process_requests(
+                &mut engine,
+                &mut execution_rx,
+                bar,
+                &mut test.expire_first,
+            ));
+        }
+
+        let actual_fills = fills
+            .iter()
+            .map(|fill| {
+                let instrument = if fill.key.instrument == Y { Y } else { X };
+                (instrument, fill.state.side, fill.state.quantity)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(actual_fills, test.expected_fills, "TC{index} failed");
+
+        // Both legs are flat once the spread has been exited
+        for instrument in [Y, X] {
+            let position = &engine
+                .state
+                .instruments
+                .instrument(&instrument.to_string())
+                .position
+                .current;
+            assert!(position.is_none(), "TC{index} failed: {instrument}");
+        }
+
+        // Spread reverted after the dislocation, so selling then buying it back is profitable
+        let pnl = fills
+            .iter()
+            .map(|fill| {
+                let notional = fill.state.price * fill.state.quantity;
+                match fill.state.side {
+                    Side::Buy => -notional,
+                    Side::Sell => notional,
+                }
+            })
+            .sum::<Decimal>();
+        assert!(pnl > Decimal::ZERO, "TC{index} failed: {pnl}");
+    }
+}
+
+fn build_pairs_engine() -> (PairsEngine, UnboundedRx<ExecutionRequest>) {
+    let config = PairsConfig {
+        hedge_period: 30,
+        zscore_period: 15,
+        entry_z: dec!(3),
+        exit_z: dec!(0.5),
+        quantity: dec!(100),
+    };
+
+    let strategy = PairsStrategy::new(StrategyId::new("pairs"), config)
+        .with_pair(Pair::new(Y.to_string(), X.to_string()))
+        .with_instrument_spec(Y.to_string(), round_lots(Y), Decimal::ONE)
+        .with_instrument_spec(X.to_string(), round_lots(X), Decimal::ONE);
+
+    build_engine(
+        &[Y, X],
+        strategy.global_data(),
+        DefaultInstrumentMarketData::default,
+        strategy,
+    )
+}
+
+/// Synthetic daily closes of (BBDC4, ITUB4): BBDC4 oscillates around 30, while ITUB4 tracks
+/// 2 × BBDC4 + 1 with small noise, apart from a 0.50 dislocation over bars 63 & 64.
+fn series() -> impl Iterator<Item = (f64, f64)> {
+    (0..BARS).map(|bar| {
+        let t = bar as f64;
+        let x = round_cents(30.0 + 3.0 * (t / 4.0).sin());
+        let dislocation = if (63..=64).contains(&bar) { 0.5 } else { 0.0 };
+        let y = round_cents(2.0 * x + 1.0 + 0.05 * (1.7 * t).sin() + dislocation);
+        (x, y)
+    })
+}
+
+fn round_cents(price: f64) -> f64 {
+    (price * 100.0).round() / 100.0
+}
+
+fn candle(instrument: &str, bar: i64, close: f64) -> EngineEvent<DataKind> {
+    common::market_event(
+        instrument,
+        time(TimeDelta::days(bar)),
+        DataKind::Candle(Candle {
+            close_time: time(TimeDelta::days(bar)),
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 1_000_000.0,
+            trade_count: 10_000,
+        }),
+    )
+}
+
+/// Process every open request sent by the `Engine` until it stops sending requests, returning the
+/// filled requests.
+///
+/// The first request matching `expire_first` expires unfilled, while every other request is
+/// filled in full at its request price.
+fn process_requests(
+    engine: &mut PairsEngine,
+    execution_rx: &mut UnboundedRx<ExecutionRequest>,
+    bar: i64,
+    expire_first: &mut Option<(&'static str, Side)>,
+) -> Vec<OrderRequestOpen> {
+    let mut fills = Vec::new();
+
+    loop {
+        let requests = requests(execution_rx)
+            .into_iter()
+            .filter_map(|request| match request {
+                ExecutionRequest::Open(request, _) => Some(request),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if requests.is_empty() {
+            return fills;
+        }
+
+        for request in requests {
+            let expire = expire_first.is_some_and(|(instrument, side)| {
+                request.key.instrument == instrument && request.state.side == side
+            });
+
+            let time = time(TimeDelta::days(bar));
+            let state = if expire {
+                *expire_first = None;
+                OrderState::expired()
+            } else {
+                engine.process(trade(&request, time));
+                OrderState::active(Open {
+                    id: order_id(&request),
+                    time_exchange: time,
+                    filled_quantity: request.state.quantity,
+                })
+            };
+            engine.process(order_snapshot(&request, state));
+
+            if !expire {
+                fills.push(request);
+            }
+        }
+    }
+}
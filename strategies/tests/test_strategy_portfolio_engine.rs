@@ -0,0 +1,300 @@
+//! # Strategy Portfolio Engine Integration Test
+//!
+//! Drives an [`Engine`] running a [`StrategyPortfolio`] of child strategies that always buy (or
+//! always sell) PETR4, so the [`StrategyBudget`] of each child and the [`ConflictPolicy`] between
+//! children are the only constraints on the orders reaching the `RiskManager`.
+//!
+//! Fills are attributed to the child strategies by the [`StrategyBook`] `GlobalData`.
+
+mod common;
+#[path = "common/fills.rs"]
+mod fills;
+
+use chrono::TimeDelta;
+use common::{build_engine, market_event, time, TestEngine};
+use fills::fill_open_requests;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use toucan_core::{
+    engine::{
+        state::{
+            instrument::data::{DefaultInstrumentMarketData, InstrumentDataState},
+            EngineState,
+        },
+        Processor,
+    },
+    execution::request::ExecutionRequest,
+    EngineEvent,
+};
+use toucan_data::{books::Level, event::DataKind, subscription::book::OrderBookL1};
+use toucan_execution::{
+    order::{
+        id::{ClientOrderId, StrategyId},
+        request::{OrderRequestCancel, OrderRequestOpen, RequestOpen},
+        OrderKey, OrderKind, TimeInForce,
+    },
+    AssetIndex, ExchangeIndex, InstrumentIndex,
+};
+use toucan_instrument::{exchange::ExchangeId, Side};
+use toucan_integration::channel::UnboundedRx;
+use toucan_strategies::{
+    portfolio::{ConflictPolicy, StrategyBook, StrategyBudget, StrategyPortfolio},
+    shared::close_positions_with_market_orders,
+};
+use toucan_trader::{
+    AlgoStrategy, ClosePositionsStrategy, OnDisconnectStrategy, OnTradingDisabled,
+};
+
+const INSTRUMENT: &str = "PETR4";
+
+type State = EngineState<StrategyBook, DefaultInstrumentMarketData>;
+
+type PortfolioEngine =
+    TestEngine<StrategyBook, DefaultInstrumentMarketData, StrategyPortfolio<FixedSideStrategy>>;
+
+#[test]
+fn test_strategy_portfolio_engine() {
+    struct TestCase {
+        portfolio: StrategyPortfolio<FixedSideStrategy>,
+        expected_fills: Vec<(&'static str, Side, Decimal)>,
+        expected_book: Vec<(&'static str, Option<Decimal>)>,
+    }
+
+    let position_budget = StrategyBudget::default().with_max_position(dec!(100));
+
+    let cases = vec![
+        // TC0: buyer stops once its notional budget is used (2 × 100 PETR4 @ 30.00)
+        TestCase {
+            portfolio: StrategyPortfolio::new().with_strategy(
+                StrategyId::new("buyer"),
+                FixedSideStrategy::new(Side::Buy),
+                StrategyBudget::allocation(dec!(10_000), dec!(0.6)),
+            ),
+            expected_fills: vec![
+                ("buyer", Side::Buy, dec!(100)),
+                ("buyer", Side::Buy, dec!(100)),
+            ],
+            expected_book: vec![("buyer", Some(dec!(200)))],
+        },
+        // TC1: opposing seller is dropped while the prioritised buyer has budget, then trades
+        TestCase {
+            portfolio: StrategyPortfolio::new()
+                .with_strategy(
+                    StrategyId::new("buyer"),
+                    FixedSideStrategy::new(Side::Buy),
+                    position_budget,
+                )
+                .with_strategy(
+                    StrategyId::new("seller"),
+                    FixedSideStrategy::new(Side::Sell),
+                    position_budget,
+                ),
+            expected_fills: vec![
+                ("buyer", Side::Buy, dec!(100)),
+                ("seller", Side::Sell, dec!(100)),
+            ],
+            expected_book: vec![("buyer", Some(dec!(100))), ("seller", Some(dec!(-100)))],
+        },
+        // TC2: opposing orders are all dropped with the Cancel policy
+        TestCase {
+            portfolio: StrategyPortfolio::new()
+                .with_strategy(
+                    StrategyId::new("buyer"),
+                    FixedSideStrategy::new(Side::Buy),
+                    position_budget,
+                )
+                .with_strategy(
+                    StrategyId::new("seller"),
+                    FixedSideStrategy::new(Side::Sell),
+                    position_budget,
+                )
+                .with_conflict_policy(ConflictPolicy::Cancel),
+            expected_fills: vec![],
+            expected_book: vec![("buyer", None), ("seller", None)],
+        },
+        // TC3: seller scoped to another instrument never trades PETR4, even once the buyer
+        // has used its budget
+        TestCase {
+            portfolio: StrategyPortfolio::new()
+                .with_strategy(
+                    StrategyId::new("buyer"),
+                    FixedSideStrategy::new(Side::Buy),
+                    position_budget,
+                )
+                .with_scoped_strategy(
+                    StrategyId::new("seller"),
+                    FixedSideStrategy::new(Side::Sell),
+                    position_budget,
+                    ["VALE3".to_string()],
+                ),
+            expected_fills: vec![("buyer", Side::Buy, dec!(100))],
+            expected_book: vec![("buyer", Some(dec!(100))), ("seller", None)],
+        },
+    ];
+
+    for (index, test) in cases.into_iter().enumerate() {
+        let (mut engine, mut execution_rx) = build_portfolio_engine(test.portfolio);
+
+        let fills = (1..=3)
+            .flat_map(|second| {
+                engine.process(balanced_l1(second));
+                fill_requests(&mut engine, &mut execution_rx, second)
+            })
+            .map(|fill| {
+                let strategy = match fill.key.strategy.0.as_str() {
+                    "buyer" => "buyer",
+                    "seller" => "seller",
+                    strategy => panic!("TC{index} failed: unexpected strategy {strategy}"),
+                };
+                (strategy, fill.state.side, fill.state.quantity)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(fills, test.expected_fills, "TC{index} failed");
+
+        for (strategy, expected) in test.expected_book {
+            let quantity = engine
+                .state
+                .global
+                .position(&StrategyId::new(strategy), &INSTRUMENT.to_string())
+                .map(|position| position.quantity);
+            assert_eq!(quantity, expected, "TC{index} failed: {strategy}");
+        }
+    }
+}
+
+/// Child strategy sending an IOC market order of 100 on a fixed side for every instrument
+/// without in-flight orders, whenever it is asked to generate orders.
+#[derive(Debug, Clone)]
+struct FixedSideStrategy {
+    side: Side,
+}
+
+impl FixedSideStrategy {
+    fn new(side: Side) -> Self {
+        Self { side }
+    }
+}
+
+impl AlgoStrategy for FixedSideStrategy {
+    type State = State;
+
+    fn generate_algo_orders(
+        &self,
+        state: &Self::State,
+    ) -> (
+        impl IntoIterator<Item = OrderRequestCancel<ExchangeIndex, InstrumentIndex>>,
+        impl IntoIterator<Item = OrderRequestOpen<ExchangeIndex, InstrumentIndex>>,
+    ) {
+        let opens = state
+            .instruments
+            .0
+            .values()
+            .filter(|state| state.orders.0.is_empty())
+            .filter_map(|state| {
+                Some(OrderRequestOpen {
+                    key: OrderKey {
+                        exchange: state.instrument.exchange.to_string(),
+                        instrument: state.key.clone(),
+                        strategy: StrategyId::new("fixed_side"),
+                        cid: ClientOrderId::random(),
+                    },
+                    state: RequestOpen {
+                        side: self.side,
+                        price: state.data.price()?,
+                        quantity: dec!(100),
+                        kind: OrderKind::Market,
+                        time_in_force: TimeInForce::ImmediateOrCancel,
+                    },
+                })
+            })
+            .collect::<Vec<_>>();
+
+        (std::iter::empty(), opens)
+    }
+}
+
+impl ClosePositionsStrategy for FixedSideStrategy {
+    type State = State;
+
+    fn close_positions_requests<'a>(
+        &'a self,
+        state: &'a Self::State,
+        filter: &'a impl std::fmt::Debug,
+    ) -> (
+        impl IntoIterator<Item = OrderRequestCancel<ExchangeIndex, InstrumentIndex>> + 'a,
+        impl IntoIterator<Item = OrderRequestOpen<ExchangeIndex, InstrumentIndex>> + 'a,
+    )
+    where
+        ExchangeIndex: 'a,
+        AssetIndex: 'a,
+        InstrumentIndex: 'a,
+    {
+        (
+            std::iter::empty(),
+            close_positions_with_market_orders(
+                &StrategyId::new("fixed_side"),
+                &state.instruments,
+                filter,
+            ),
+        )
+    }
+}
+
+impl<Clock, State, ExecutionTxs, Risk> OnDisconnectStrategy<Clock, State, ExecutionTxs, Risk>
+    for FixedSideStrategy
+{
+    type OnDisconnect = ();
+
+    fn on_disconnect(_: ExchangeId) -> Self::OnDisconnect {}
+}
+
+impl<Clock, State, ExecutionTxs, Risk> OnTradingDisabled<Clock, State, ExecutionTxs, Risk>
+    for FixedSideStrategy
+{
+    type OnTradingDisabled = ();
+
+    fn on_trading_disabled() -> Self::OnTradingDisabled {}
+}
+
+fn build_portfolio_engine(
+    portfolio: StrategyPortfolio<FixedSideStrategy>,
+) -> (PortfolioEngine, UnboundedRx<ExecutionRequest>) {
+    build_engine(
+        &[INSTRUMENT],
+        StrategyBook::default(),
+        DefaultInstrumentMarketData::default,
+        portfolio,
+    )
+}
+
+/// Balanced `OrderBookL1` with a 30.00 volume weighted mid-price.
+fn balanced_l1(second: i64) -> EngineEvent<DataKind> {
+    let time = time(TimeDelta::seconds(second));
+    market_event(
+        INSTRUMENT,
+        time,
+        DataKind::OrderBookL1(OrderBookL1 {
+            last_update_time: time,
+            best_bid: Some(Level::new(dec!(29.99), dec!(1_000))),
+            best_ask: Some(Level::new(dec!(30.01), dec!(1_000))),
+        }),
+    )
+}
+
+/// Fill every open request sent by the `Engine` until it stops sending requests, returning the
+/// filled requests.
+fn fill_requests(
+    engine: &mut PortfolioEngine,
+    execution_rx: &mut UnboundedRx<ExecutionRequest>,
+    second: i64,
+) -> Vec<OrderRequestOpen> {
+    let mut fills = Vec::new();
+
+    loop {
+        let requests = fill_open_requests(engine, execution_rx, time(TimeDelta::seconds(second)));
+        if requests.is_empty() {
+            return fills;
+        }
+        fills.extend(requests);
+    }
+}